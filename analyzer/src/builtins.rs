@@ -1,3 +1,5 @@
+use fe_parser::ast as fe;
+use std::str::FromStr;
 use strum::{
     EnumString,
     IntoStaticStr,
@@ -10,12 +12,30 @@ pub enum ValueMethod {
     ToMem,
     AbiEncode,
     AbiEncodePacked,
+    Len,
+    ToBytes,
+    Push,
+    Pop,
+    #[strum(serialize = "eip712_hash")]
+    Eip712Hash,
 }
 
 #[derive(Clone, Debug, PartialEq, EnumString, IntoStaticStr)]
 #[strum(serialize_all = "snake_case")]
 pub enum GlobalMethod {
     Keccak256,
+    Sha256,
+    Selfdestruct,
+    Ecrecover,
+    Modexp,
+    Identity,
+    Blake2f,
+    Range,
+    Require,
+    #[strum(serialize = "eip712_domain_separator")]
+    Eip712DomainSeparator,
+    #[strum(serialize = "eip712_digest")]
+    Eip712Digest,
 }
 
 #[derive(Debug, PartialEq, EnumString)]
@@ -23,6 +43,15 @@ pub enum GlobalMethod {
 pub enum ContractTypeMethod {
     Create,
     Create2,
+    InterfaceId,
+}
+
+#[derive(Debug, PartialEq, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum StructTypeMethod {
+    AbiDecode,
+    #[strum(serialize = "eip712_type_hash")]
+    Eip712TypeHash,
 }
 
 #[derive(Debug, PartialEq, EnumString)]
@@ -39,6 +68,7 @@ pub enum Object {
 #[derive(Debug, PartialEq, EnumString)]
 #[strum(serialize_all = "snake_case")]
 pub enum BlockField {
+    ChainId,
     Coinbase,
     Difficulty,
     Number,
@@ -72,3 +102,64 @@ pub enum TxField {
 pub enum SelfField {
     Address,
 }
+
+#[derive(Debug, PartialEq, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum AddressField {
+    Balance,
+}
+
+#[derive(Debug, PartialEq, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum AddressMethod {
+    Send,
+    Transfer,
+    RawCall,
+    RawStaticcall,
+    RawDelegatecall,
+}
+
+#[derive(Debug, PartialEq, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum Decorator {
+    Deprecated,
+    Eip712,
+    Erc165,
+    Initializer,
+    Payable,
+    Pure,
+    Test,
+    Unsafe,
+    Upgradeable,
+    View,
+}
+
+impl Decorator {
+    /// The builtin decorator that the expression of a decorator refers to.
+    /// Only `deprecated` may be called with arguments.
+    pub fn of(decorator: &fe::Expr) -> Option<Decorator> {
+        match decorator {
+            fe::Expr::Name(name) => Decorator::from_str(name).ok(),
+            fe::Expr::Call { func, .. } => match func.node {
+                fe::Expr::Name("deprecated") => Some(Decorator::Deprecated),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// The message of a `deprecated` decorator, which is the string literal
+    /// passed to it, if any.
+    pub fn deprecation_message(decorator: &fe::Expr) -> Option<String> {
+        match decorator {
+            fe::Expr::Call { args, .. } => match args.node.as_slice() {
+                [arg] => match &arg.node {
+                    fe::CallArg::Arg(fe::Expr::Str(lines)) => Some(lines.join("")),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
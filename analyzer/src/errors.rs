@@ -1,10 +1,11 @@
 //! Semantic errors.
 
 use ansi_term::Color::Red;
+use fe_common::color::paint;
 use fe_parser::span::Span;
 
 /// Errors for things that may arise in a valid Fe AST.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum ErrorKind {
     BreakWithoutLoop,
     ContinueWithoutLoop,
@@ -22,14 +23,82 @@ pub enum ErrorKind {
     MoreThanThreeIndexedParams,
     WrongNumberOfParams,
     AlreadyDefined,
+    CannotInferTypeArgs,
+    GenericPublicFunction,
+    TraitNotImplemented,
+    InitInMixin,
+    AssignToConstant,
+    InvalidDecorator,
+    UnsafeCall,
+    MutabilityViolation,
+    NonExhaustiveMatch,
+    UnreachablePattern,
+    InvalidFreeFunction,
+    InvalidLibrary,
+    InvalidUpgradeable,
+    LibraryStringReturn,
 }
 
-#[derive(Debug, PartialEq)]
+impl ErrorKind {
+    /// A short description of the error for users.
+    pub fn message(&self) -> &'static str {
+        match self {
+            ErrorKind::BreakWithoutLoop => "`break` outside of a loop",
+            ErrorKind::ContinueWithoutLoop => "`continue` outside of a loop",
+            ErrorKind::KeyWordArgsRequired => "keyword arguments are required",
+            ErrorKind::MissingReturn => "missing return statement",
+            ErrorKind::NotSubscriptable => "value can not be indexed",
+            ErrorKind::NumericCapacityMismatch => "number does not fit the numeric type",
+            ErrorKind::StringCapacityMismatch => "string does not fit the string type",
+            ErrorKind::UndefinedValue => "undefined value",
+            ErrorKind::UnexpectedReturn => "unexpected return value",
+            ErrorKind::TypeError => "mismatched types",
+            ErrorKind::CannotMove => "value must be copied explicitly",
+            ErrorKind::NotCallable => "value is not callable",
+            ErrorKind::NumericLiteralExpected => "expected a numeric literal",
+            ErrorKind::MoreThanThreeIndexedParams => "events can have at most three indexed fields",
+            ErrorKind::WrongNumberOfParams => "wrong number of arguments",
+            ErrorKind::AlreadyDefined => "name is already defined",
+            ErrorKind::CannotInferTypeArgs => "type arguments can not be inferred",
+            ErrorKind::GenericPublicFunction => "public functions can not be generic",
+            ErrorKind::TraitNotImplemented => "trait is not implemented",
+            ErrorKind::InitInMixin => "mixins can not define `__init__`",
+            ErrorKind::AssignToConstant => "constants can not be assigned to",
+            ErrorKind::InvalidDecorator => "invalid decorator",
+            ErrorKind::UnsafeCall => "unsafe call outside of an `@unsafe` function",
+            ErrorKind::MutabilityViolation => {
+                "contract state is accessed in a way the function does not allow"
+            }
+            ErrorKind::NonExhaustiveMatch => "match is not exhaustive",
+            ErrorKind::UnreachablePattern => "unreachable pattern",
+            ErrorKind::InvalidFreeFunction => "invalid function outside of a contract",
+            ErrorKind::InvalidLibrary => "invalid library definition",
+            ErrorKind::InvalidUpgradeable => "upgradeable contracts need exactly one initializer",
+            ErrorKind::LibraryStringReturn => {
+                "library functions that return strings can not be called yet"
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct SemanticError {
     pub kind: ErrorKind,
     /// A sequence of nested spans containing the error's origin in the source
     /// code.
     pub context: Vec<Span>,
+    /// Fixes that are likely to resolve the error.
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// A proposed fix for an error, which replaces the code of a span.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Suggestion {
+    pub message: String,
+    pub span: Span,
+    pub replacement: String,
+    /// Whether the replacement can be applied without a human looking at it.
+    pub machine_applicable: bool,
 }
 
 impl SemanticError {
@@ -38,6 +107,7 @@ impl SemanticError {
         SemanticError {
             kind: ErrorKind::BreakWithoutLoop,
             context: vec![],
+            suggestions: vec![],
         }
     }
 
@@ -46,6 +116,7 @@ impl SemanticError {
         SemanticError {
             kind: ErrorKind::ContinueWithoutLoop,
             context: vec![],
+            suggestions: vec![],
         }
     }
 
@@ -54,6 +125,7 @@ impl SemanticError {
         SemanticError {
             kind: ErrorKind::KeyWordArgsRequired,
             context: vec![],
+            suggestions: vec![],
         }
     }
 
@@ -62,6 +134,7 @@ impl SemanticError {
         SemanticError {
             kind: ErrorKind::MissingReturn,
             context: vec![],
+            suggestions: vec![],
         }
     }
 
@@ -70,6 +143,7 @@ impl SemanticError {
         SemanticError {
             kind: ErrorKind::NotSubscriptable,
             context: vec![],
+            suggestions: vec![],
         }
     }
 
@@ -78,6 +152,7 @@ impl SemanticError {
         SemanticError {
             kind: ErrorKind::NumericCapacityMismatch,
             context: vec![],
+            suggestions: vec![],
         }
     }
 
@@ -86,6 +161,7 @@ impl SemanticError {
         SemanticError {
             kind: ErrorKind::StringCapacityMismatch,
             context: vec![],
+            suggestions: vec![],
         }
     }
 
@@ -94,6 +170,7 @@ impl SemanticError {
         SemanticError {
             kind: ErrorKind::UndefinedValue,
             context: vec![],
+            suggestions: vec![],
         }
     }
 
@@ -102,6 +179,7 @@ impl SemanticError {
         SemanticError {
             kind: ErrorKind::UnexpectedReturn,
             context: vec![],
+            suggestions: vec![],
         }
     }
 
@@ -110,6 +188,7 @@ impl SemanticError {
         SemanticError {
             kind: ErrorKind::TypeError,
             context: vec![],
+            suggestions: vec![],
         }
     }
 
@@ -118,6 +197,7 @@ impl SemanticError {
         SemanticError {
             kind: ErrorKind::CannotMove,
             context: vec![],
+            suggestions: vec![],
         }
     }
 
@@ -126,6 +206,7 @@ impl SemanticError {
         SemanticError {
             kind: ErrorKind::AlreadyDefined,
             context: vec![],
+            suggestions: vec![],
         }
     }
 
@@ -134,6 +215,7 @@ impl SemanticError {
         SemanticError {
             kind: ErrorKind::NotCallable,
             context: vec![],
+            suggestions: vec![],
         }
     }
 
@@ -142,6 +224,7 @@ impl SemanticError {
         SemanticError {
             kind: ErrorKind::NumericLiteralExpected,
             context: vec![],
+            suggestions: vec![],
         }
     }
 
@@ -150,6 +233,7 @@ impl SemanticError {
         SemanticError {
             kind: ErrorKind::MoreThanThreeIndexedParams,
             context: vec![],
+            suggestions: vec![],
         }
     }
 
@@ -158,6 +242,133 @@ impl SemanticError {
         SemanticError {
             kind: ErrorKind::WrongNumberOfParams,
             context: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    /// Create a new error with kind `CannotInferTypeArgs`
+    pub fn cannot_infer_type_args() -> Self {
+        SemanticError {
+            kind: ErrorKind::CannotInferTypeArgs,
+            context: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    /// Create a new error with kind `GenericPublicFunction`
+    pub fn generic_public_function() -> Self {
+        SemanticError {
+            kind: ErrorKind::GenericPublicFunction,
+            context: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    /// Create a new error with kind `TraitNotImplemented`
+    pub fn trait_not_implemented() -> Self {
+        SemanticError {
+            kind: ErrorKind::TraitNotImplemented,
+            context: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    /// Create a new error with kind `InitInMixin`
+    pub fn init_in_mixin() -> Self {
+        SemanticError {
+            kind: ErrorKind::InitInMixin,
+            context: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    /// Create a new error with kind `AssignToConstant`
+    pub fn assign_to_constant() -> Self {
+        SemanticError {
+            kind: ErrorKind::AssignToConstant,
+            context: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    /// Create a new error with kind `InvalidDecorator`
+    pub fn invalid_decorator() -> Self {
+        SemanticError {
+            kind: ErrorKind::InvalidDecorator,
+            context: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    /// Create a new error with kind `UnsafeCall`
+    pub fn unsafe_call() -> Self {
+        SemanticError {
+            kind: ErrorKind::UnsafeCall,
+            context: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    /// Create a new error with kind `MutabilityViolation`
+    pub fn mutability_violation() -> Self {
+        SemanticError {
+            kind: ErrorKind::MutabilityViolation,
+            context: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    /// Create a new error with kind `NonExhaustiveMatch`
+    pub fn non_exhaustive_match() -> Self {
+        SemanticError {
+            kind: ErrorKind::NonExhaustiveMatch,
+            context: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    /// Create a new error with kind `UnreachablePattern`
+    pub fn unreachable_pattern() -> Self {
+        SemanticError {
+            kind: ErrorKind::UnreachablePattern,
+            context: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    /// Create a new error with kind `InvalidFreeFunction`
+    pub fn invalid_free_function() -> Self {
+        SemanticError {
+            kind: ErrorKind::InvalidFreeFunction,
+            context: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    /// Create a new error with kind `InvalidLibrary`
+    pub fn invalid_library() -> Self {
+        SemanticError {
+            kind: ErrorKind::InvalidLibrary,
+            context: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    /// Create a new error with kind `InvalidUpgradeable`
+    pub fn invalid_upgradeable() -> Self {
+        SemanticError {
+            kind: ErrorKind::InvalidUpgradeable,
+            context: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    /// Create a new error with kind `LibraryStringReturn`
+    pub fn library_string_return() -> Self {
+        SemanticError {
+            kind: ErrorKind::LibraryStringReturn,
+            context: vec![],
+            suggestions: vec![],
         }
     }
 
@@ -168,6 +379,12 @@ impl SemanticError {
         self
     }
 
+    /// Attach a suggested fix to the error.
+    pub fn with_suggestion(mut self, suggestion: Suggestion) -> Self {
+        self.suggestions.push(suggestion);
+        self
+    }
+
     /// Formats the error using the source code.
     ///
     /// The string will contain the error kind, line number, and surrounding
@@ -182,7 +399,7 @@ impl SemanticError {
         let context = match (self.context.get(0), self.context.get(1)) {
             (Some(inner), Some(outer)) => {
                 let first_part = src[outer.start..inner.start].to_string();
-                let middle_part = Red.paint(&src[inner.start..inner.end]).to_string();
+                let middle_part = paint(Red, &src[inner.start..inner.end]);
                 let last_part = src[inner.end..outer.end].to_string();
 
                 format!("{}{}{}", first_part, middle_part, last_part)
@@ -7,10 +7,13 @@
 
 pub mod builtins;
 pub mod errors;
+pub mod lints;
 pub mod namespace;
 mod traversal;
 
 use crate::errors::SemanticError;
+use crate::namespace::constants::ConstValue;
+use crate::namespace::custom_errors::CustomError;
 use crate::namespace::events::Event;
 use crate::namespace::scopes::{
     ContractScope,
@@ -20,6 +23,7 @@ use crate::namespace::types::{
     Contract,
     FixedSize,
     Struct,
+    Tuple,
     Type,
 };
 use builtins::GlobalMethod;
@@ -28,6 +32,7 @@ use fe_parser::span::{
     Span,
     Spanned,
 };
+use num_bigint::BigInt;
 use std::cell::RefCell;
 use std::collections::{
     HashMap,
@@ -55,6 +60,7 @@ impl Location {
             Type::Base(_) => Ok(Location::Value),
             Type::Contract(_) => Ok(Location::Value),
             Type::Array(_) => Ok(Location::Memory),
+            Type::DynamicArray(_) => Ok(Location::Memory),
             Type::Tuple(_) => Ok(Location::Memory),
             Type::String(_) => Ok(Location::Memory),
             Type::Struct(_) => Ok(Location::Memory),
@@ -70,40 +76,79 @@ pub struct ContractAttributes {
     pub public_functions: Vec<FunctionAttributes>,
     /// An init function that has been defined by the user.
     pub init_function: Option<FunctionAttributes>,
+    /// Names of the functions, including `__init__`, that accept ether.
+    pub payable_functions: HashSet<String>,
     /// Events that have been defined by the user.
     pub events: Vec<Event>,
+    /// Custom errors that have been defined by the user.
+    pub errors: Vec<CustomError>,
     /// Static strings that the contract defines
     pub string_literals: HashSet<String>,
     /// Structs that have been defined by the user
     pub structs: Vec<Struct>,
+    /// Tuple types that are constructed or destructured in the contract
+    pub tuples: Vec<Tuple>,
     /// External contracts that may be called from within this contract.
     pub external_contracts: Vec<Contract>,
     /// Names of contracts that have been created inside of this contract.
     pub created_contracts: HashSet<String>,
+    /// Names of the functions defined outside of any contract that are called
+    /// within this contract.
+    pub free_functions: HashSet<String>,
+    /// Libraries whose functions are called within this contract. Their
+    /// addresses are linked into the contract's bytecode.
+    pub libraries: Vec<Contract>,
+    /// Types of the values that are encoded with `abi_encode`.
+    pub abi_encodings: Vec<FixedSize>,
+    /// Types of the values that are decoded from memory with `abi_decode`.
+    pub abi_decodings: Vec<FixedSize>,
+    /// Fields of the contract, including those of its mixins, ordered by the
+    /// nonce of their storage slot.
+    pub storage_layout: Vec<StorageField>,
+    /// IDs of the interfaces that the generated `supportsInterface` function
+    /// reports as supported. Empty unless the contract is marked with
+    /// `@erc165`.
+    pub supported_interfaces: Vec<u32>,
+    /// Whether the contract is marked with `@upgradeable`.
+    pub is_upgradeable: bool,
+    /// The function of an upgradeable contract that is marked with
+    /// `@initializer`.
+    pub initializer: Option<FunctionAttributes>,
+}
+
+/// A contract field and where it is stored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StorageField {
+    pub name: String,
+    pub nonce: usize,
+    pub typ: Type,
 }
 
 impl From<Shared<ContractScope>> for ContractAttributes {
     fn from(scope: Shared<ContractScope>) -> Self {
         let mut public_functions = vec![];
         let mut init_function = None;
+        let mut payable_functions = HashSet::new();
 
         for (name, def) in scope.borrow().function_defs.iter() {
-            if !def.is_public {
-                continue;
+            if def.is_payable {
+                payable_functions.insert(name.clone());
             }
 
-            if name != "__init__" {
-                public_functions.push(FunctionAttributes {
-                    name: name.clone(),
-                    param_types: def.param_types.to_owned(),
-                    return_type: def.return_type.to_owned(),
-                });
-            } else {
+            // The init function is called on deployment whether or not it is
+            // public.
+            if name == "__init__" {
                 init_function = Some(FunctionAttributes {
                     name: name.clone(),
                     param_types: def.param_types.to_owned(),
                     return_type: FixedSize::empty_tuple(),
                 })
+            } else if def.is_public {
+                public_functions.push(FunctionAttributes {
+                    name: name.clone(),
+                    param_types: def.param_types.to_owned(),
+                    return_type: def.return_type.to_owned(),
+                });
             }
         }
 
@@ -115,6 +160,13 @@ impl From<Shared<ContractScope>> for ContractAttributes {
             }
         });
 
+        let library_calls = scope.borrow().library_calls.to_owned();
+        let libraries = external_contracts
+            .iter()
+            .filter(|contract| library_calls.contains(&contract.name))
+            .cloned()
+            .collect();
+
         let structs = scope.borrow().get_module_type_defs(|typ| {
             if let Type::Struct(val) = typ {
                 Some(val.to_owned())
@@ -123,19 +175,47 @@ impl From<Shared<ContractScope>> for ContractAttributes {
             }
         });
 
+        let mut storage_layout = scope
+            .borrow()
+            .field_defs
+            .iter()
+            .map(|(name, def)| StorageField {
+                name: name.clone(),
+                nonce: def.nonce,
+                typ: def.typ.clone(),
+            })
+            .collect::<Vec<_>>();
+        storage_layout.sort_by_key(|field| field.nonce);
+
         ContractAttributes {
             public_functions,
             init_function,
+            payable_functions,
             events: scope
                 .borrow()
                 .event_defs
                 .values()
                 .map(|event| event.to_owned())
                 .collect::<Vec<Event>>(),
+            errors: scope
+                .borrow()
+                .error_defs
+                .values()
+                .map(|error| error.to_owned())
+                .collect::<Vec<CustomError>>(),
             string_literals: scope.borrow().string_defs.clone(),
             structs,
+            tuples: scope.borrow().tuple_defs.iter().cloned().collect(),
             external_contracts,
             created_contracts: scope.borrow().created_contracts.to_owned(),
+            free_functions: scope.borrow().free_function_calls.to_owned(),
+            libraries,
+            abi_encodings: scope.borrow().abi_encodings.iter().cloned().collect(),
+            abi_decodings: scope.borrow().abi_decodings.iter().cloned().collect(),
+            storage_layout,
+            supported_interfaces: vec![],
+            is_upgradeable: false,
+            initializer: None,
         }
     }
 }
@@ -237,11 +317,38 @@ impl ExpressionAttributes {
 /// The type of a function call.
 #[derive(Clone, Debug, PartialEq)]
 pub enum CallType {
-    BuiltinFunction { func: GlobalMethod },
-    TypeConstructor { typ: Type },
-    SelfAttribute { func_name: String },
+    BuiltinFunction {
+        func: GlobalMethod,
+    },
+    TypeConstructor {
+        typ: Type,
+    },
+    SelfAttribute {
+        func_name: String,
+    },
     ValueAttribute,
-    TypeAttribute { typ: Type, func_name: String },
+    TypeAttribute {
+        typ: Type,
+        func_name: String,
+    },
+    LibraryAttribute {
+        library: Contract,
+        func_name: String,
+    },
+}
+
+/// A pattern of a `match` statement arm.
+///
+/// Enum variants are matched by their index, so all patterns are values on
+/// integers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MatchPattern {
+    /// Matches any value.
+    Wildcard,
+    /// Matches exactly the given value.
+    Value(BigInt),
+    /// Matches values from `start` up to, but not including, `end`.
+    Range { start: BigInt, end: BigInt },
 }
 
 /// Contains contextual information relating to a function definition AST node.
@@ -257,11 +364,21 @@ pub struct FunctionAttributes {
 #[derive(Clone, Debug, Default)]
 pub struct Context {
     expressions: HashMap<Span, ExpressionAttributes>,
+    constants: HashMap<Span, ConstValue>,
     emits: HashMap<Span, Event>,
+    reverts: HashMap<Span, CustomError>,
     functions: HashMap<Span, FunctionAttributes>,
+    function_instances: HashMap<Span, Vec<(FunctionAttributes, Context)>>,
+    mixins: HashMap<Span, Vec<(String, Context)>>,
+    free_functions: HashMap<Span, Context>,
     declarations: HashMap<Span, FixedSize>,
     contracts: HashMap<Span, ContractAttributes>,
+    structs: HashMap<Span, Struct>,
     calls: HashMap<Span, CallType>,
+    match_arms: HashMap<Span, Vec<MatchPattern>>,
+    /// Errors in function bodies, which don't stop the analysis of the
+    /// remaining functions.
+    errors: Vec<SemanticError>,
 }
 
 impl Context {
@@ -272,11 +389,26 @@ impl Context {
     pub fn new() -> Self {
         Context {
             expressions: HashMap::new(),
+            constants: HashMap::new(),
             emits: HashMap::new(),
+            reverts: HashMap::new(),
             functions: HashMap::new(),
+            function_instances: HashMap::new(),
+            mixins: HashMap::new(),
+            free_functions: HashMap::new(),
             declarations: HashMap::new(),
             contracts: HashMap::new(),
+            structs: HashMap::new(),
             calls: HashMap::new(),
+            match_arms: HashMap::new(),
+            errors: vec![],
+        }
+    }
+
+    /// Record an error that doesn't prevent analyzing the rest of the module.
+    pub fn add_error(&mut self, error: SemanticError) {
+        if !self.errors.contains(&error) {
+            self.errors.push(error);
         }
     }
 
@@ -290,6 +422,17 @@ impl Context {
         self.expressions.get(&span.into())
     }
 
+    /// Attribute a compile-time value to an expression node.
+    pub fn add_constant<T: Into<Span>>(&mut self, span: T, value: ConstValue) {
+        self.constants.insert(span.into(), value);
+    }
+
+    /// Get the compile-time value that has been attributed to an expression
+    /// node.
+    pub fn get_constant<T: Into<Span>>(&self, span: T) -> Option<&ConstValue> {
+        self.constants.get(&span.into())
+    }
+
     /// Attribute contextual information to an emit statement node.
     pub fn add_emit(&mut self, spanned: &Spanned<fe::FuncStmt>, event: Event) {
         self.emits.insert(spanned.span, event);
@@ -300,6 +443,16 @@ impl Context {
         self.emits.get(&span.into())
     }
 
+    /// Attribute contextual information to a revert statement node.
+    pub fn add_revert(&mut self, spanned: &Spanned<fe::FuncStmt>, error: CustomError) {
+        self.reverts.insert(spanned.span, error);
+    }
+
+    /// Get information that has been attributed to a revert statement node.
+    pub fn get_revert<T: Into<Span>>(&self, span: T) -> Option<&CustomError> {
+        self.reverts.get(&span.into())
+    }
+
     /// Attribute contextual information to a function definition node.
     pub fn add_function(
         &mut self,
@@ -314,6 +467,65 @@ impl Context {
         self.functions.get(&span.into())
     }
 
+    /// Attribute an instance of a generic function to a function definition
+    /// node. The instance body is described by its own context, since the
+    /// same expressions have different types in each instance.
+    pub fn add_function_instance(
+        &mut self,
+        spanned: &Spanned<fe::ContractStmt>,
+        attributes: FunctionAttributes,
+        context: Context,
+    ) {
+        self.function_instances
+            .entry(spanned.span)
+            .or_insert_with(Vec::new)
+            .push((attributes, context));
+    }
+
+    /// Get the instances that have been attributed to a generic function
+    /// definition node.
+    pub fn get_function_instances<T: Into<Span>>(
+        &self,
+        span: T,
+    ) -> &[(FunctionAttributes, Context)] {
+        self.function_instances
+            .get(&span.into())
+            .map(|instances| instances.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Attribute a mixin used by a contract to the contract definition node.
+    /// The mixin body is described by its own context, since its fields are
+    /// stored in different slots in each contract that uses it.
+    pub fn add_mixin(&mut self, spanned: &Spanned<fe::ModuleStmt>, name: &str, context: Context) {
+        self.mixins
+            .entry(spanned.span)
+            .or_insert_with(Vec::new)
+            .push((name.to_owned(), context));
+    }
+
+    /// Get the context of a mixin that has been attributed to a contract
+    /// definition node.
+    pub fn get_mixin<T: Into<Span>>(&self, span: T, name: &str) -> Option<&Context> {
+        self.mixins
+            .get(&span.into())
+            .and_then(|mixins| mixins.iter().find(|(mixin_name, _)| mixin_name == name))
+            .map(|(_, context)| context)
+    }
+
+    /// Attribute the functions defined outside of any contract to a contract
+    /// definition node. Like mixins, they are described by their own context
+    /// for each contract.
+    pub fn add_free_functions(&mut self, spanned: &Spanned<fe::ModuleStmt>, context: Context) {
+        self.free_functions.insert(spanned.span, context);
+    }
+
+    /// Get the context of the functions defined outside of any contract that
+    /// has been attributed to a contract definition node.
+    pub fn get_free_functions<T: Into<Span>>(&self, span: T) -> Option<&Context> {
+        self.free_functions.get(&span.into())
+    }
+
     /// Attribute contextual information to a declaration node.
     pub fn add_declaration(&mut self, spanned: &Spanned<fe::FuncStmt>, typ: FixedSize) {
         self.declarations.insert(spanned.span, typ);
@@ -338,6 +550,16 @@ impl Context {
         self.contracts.get(&span.into())
     }
 
+    /// Attribute the analyzed type to a struct definition node.
+    pub fn add_struct(&mut self, spanned: &Spanned<fe::ModuleStmt>, val: Struct) {
+        self.structs.insert(spanned.span, val);
+    }
+
+    /// Get the type that has been attributed to a struct definition node.
+    pub fn get_struct<T: Into<Span>>(&self, span: T) -> Option<&Struct> {
+        self.structs.get(&span.into())
+    }
+
     /// Attribute contextual information to a call expression node.
     pub fn add_call(&mut self, spanned: &Spanned<fe::Expr>, call_type: CallType) {
         self.calls.insert(spanned.span, call_type);
@@ -347,18 +569,106 @@ impl Context {
     pub fn get_call<T: Into<Span>>(&self, span: T) -> Option<&CallType> {
         self.calls.get(&span.into())
     }
+
+    /// Attribute the alternative patterns of a `match` statement arm to the
+    /// arm node.
+    pub fn add_match_arm(&mut self, spanned: &Spanned<fe::MatchArm>, patterns: Vec<MatchPattern>) {
+        self.match_arms.insert(spanned.span, patterns);
+    }
+
+    /// Get the patterns that have been attributed to a `match` statement arm.
+    pub fn get_match_arm<T: Into<Span>>(&self, span: T) -> Option<&Vec<MatchPattern>> {
+        self.match_arms.get(&span.into())
+    }
+
+    /// All expressions that information has been attributed to, in no
+    /// particular order. Expressions of mixins, free functions and generic
+    /// function instances are in the contexts of those.
+    pub fn expressions(&self) -> impl Iterator<Item = (Span, &ExpressionAttributes)> {
+        self.expressions
+            .iter()
+            .map(|(span, attributes)| (*span, attributes))
+    }
+
+    /// All function definitions that information has been attributed to, in
+    /// no particular order.
+    pub fn functions(&self) -> impl Iterator<Item = (Span, &FunctionAttributes)> {
+        self.functions
+            .iter()
+            .map(|(span, attributes)| (*span, attributes))
+    }
+
+    /// All declarations that a type has been attributed to, in no particular
+    /// order.
+    pub fn declarations(&self) -> impl Iterator<Item = (Span, &FixedSize)> {
+        self.declarations.iter().map(|(span, typ)| (*span, typ))
+    }
+
+    /// All contract definitions that information has been attributed to, in
+    /// no particular order.
+    pub fn contracts(&self) -> impl Iterator<Item = (Span, &ContractAttributes)> {
+        self.contracts
+            .iter()
+            .map(|(span, attributes)| (*span, attributes))
+    }
+
+    /// Get the innermost expression that contains the given offset into the
+    /// source, e.g. to show its type when hovering over it in an editor.
+    ///
+    /// The contexts of mixins, free functions and generic function instances
+    /// are searched as well. A generic function has an instance for each set
+    /// of type arguments, and the expression of the first one is returned.
+    pub fn expression_at(&self, offset: usize) -> Option<(Span, &ExpressionAttributes)> {
+        let innermost = self
+            .expressions()
+            .filter(|(span, _)| span.start <= offset && offset < span.end)
+            .min_by_key(|(span, _)| span.end - span.start);
+
+        innermost.or_else(|| {
+            self.nested_contexts()
+                .find_map(|context| context.expression_at(offset))
+        })
+    }
+
+    fn nested_contexts(&self) -> impl Iterator<Item = &Context> {
+        self.mixins
+            .values()
+            .flatten()
+            .map(|(_, context)| context)
+            .chain(self.free_functions.values())
+            .chain(
+                self.function_instances
+                    .values()
+                    .flatten()
+                    .map(|(_, context)| context),
+            )
+    }
 }
 
 /// Performs semantic analysis of the source program and returns a `Context`
 /// instance.
-pub fn analyze(module: &fe::Module) -> Result<Context, SemanticError> {
+///
+/// Errors in function bodies don't stop the analysis, so all of them are
+/// returned, ordered by their position in the source code.
+pub fn analyze(module: &fe::Module) -> Result<Context, Vec<SemanticError>> {
     let context = Context::new_shared();
-    traversal::module::module(Rc::clone(&context), module)?;
-    Ok(Rc::try_unwrap(context)
+    let result = traversal::module::module(Rc::clone(&context), module);
+    let mut context = Rc::try_unwrap(context)
         .map_err(|_| "more than one strong reference pointing to context")
         // This should never panic.
         .expect("failed to unwrap reference counter")
-        .into_inner())
+        .into_inner();
+
+    if let Err(error) = result {
+        context.add_error(error);
+    }
+    if context.errors.is_empty() {
+        return Ok(context);
+    }
+
+    let mut errors = std::mem::take(&mut context.errors);
+    errors.sort_by_key(|error| error.context.first().map(|span| span.start));
+    Err(errors)
 }
 
 pub mod test_utils {
@@ -0,0 +1,292 @@
+use crate::builtins::Decorator;
+use crate::lints::{
+    for_each_expr,
+    for_each_stmt,
+    Warning,
+};
+use fe_parser::ast as fe;
+use fe_parser::span::{
+    Span,
+    Spanned,
+};
+use std::collections::HashMap;
+
+pub const NAME: &str = "deprecated";
+
+/// Warns about uses of functions, structs and constants that are decorated
+/// with `@deprecated`.
+///
+/// Uses within definitions that are deprecated themselves are not reported,
+/// since they are going away together.
+pub fn check(module: &fe::Module) -> Vec<Warning> {
+    let mut names = HashMap::new();
+    let mut bodies = HashMap::new();
+    for stmt in module.body.iter() {
+        match &stmt.node {
+            fe::ModuleStmt::StructDef {
+                decorators, name, ..
+            }
+            | fe::ModuleStmt::ConstDef {
+                decorators, name, ..
+            } => {
+                if let Some(deprecation) = deprecation(decorators) {
+                    names.insert(name.node, deprecation);
+                }
+            }
+            fe::ModuleStmt::FuncDef { def } => {
+                names.extend(functions(std::slice::from_ref(def.as_ref())))
+            }
+            fe::ModuleStmt::ContractDef { name, body, .. }
+            | fe::ModuleStmt::MixinDef { name, body, .. }
+            | fe::ModuleStmt::LibraryDef { name, body, .. } => {
+                bodies.insert(name.node, functions(body));
+            }
+            _ => {}
+        }
+    }
+
+    let mut warnings = vec![];
+    for stmt in module.body.iter() {
+        let mut checker = Checker {
+            names: &names,
+            bodies: &bodies,
+            methods: HashMap::new(),
+            warnings: &mut warnings,
+        };
+
+        match &stmt.node {
+            fe::ModuleStmt::ContractDef {
+                name, mixins, body, ..
+            } => {
+                checker.methods = bodies.get(name.node).cloned().unwrap_or_default();
+                for mixin in mixins.iter() {
+                    checker
+                        .methods
+                        .extend(bodies.get(mixin.node).cloned().unwrap_or_default());
+                }
+                checker.contract_body(body);
+            }
+            fe::ModuleStmt::MixinDef { name, body, .. }
+            | fe::ModuleStmt::LibraryDef { name, body, .. } => {
+                checker.methods = bodies.get(name.node).cloned().unwrap_or_default();
+                checker.contract_body(body);
+            }
+            fe::ModuleStmt::FuncDef { def } => {
+                checker.contract_body(std::slice::from_ref(def.as_ref()))
+            }
+            fe::ModuleStmt::StructDef {
+                decorators, body, ..
+            } if deprecation(decorators).is_none() => {
+                for field in body.iter() {
+                    let fe::StructStmt::StructField { typ, .. } = &field.node;
+                    checker.typ(typ);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    warnings
+}
+
+/// Where and why a definition was deprecated.
+#[derive(Clone)]
+struct Deprecation {
+    span: Span,
+    message: Option<String>,
+}
+
+fn deprecation(decorators: &[Spanned<fe::Expr>]) -> Option<Deprecation> {
+    decorators
+        .iter()
+        .find(|decorator| Decorator::of(&decorator.node) == Some(Decorator::Deprecated))
+        .map(|decorator| Deprecation {
+            span: decorator.span,
+            message: Decorator::deprecation_message(&decorator.node),
+        })
+}
+
+/// The deprecated functions defined in a contract, mixin or library body.
+fn functions<'a>(body: &[Spanned<fe::ContractStmt<'a>>]) -> HashMap<&'a str, Deprecation> {
+    body.iter()
+        .filter_map(|stmt| match &stmt.node {
+            fe::ContractStmt::FuncDef {
+                decorators, name, ..
+            } => deprecation(decorators).map(|deprecation| (name.node, deprecation)),
+            _ => None,
+        })
+        .collect()
+}
+
+struct Checker<'a, 'b> {
+    /// Deprecated free functions and structs.
+    names: &'b HashMap<&'a str, Deprecation>,
+    /// Deprecated functions by the name of the contract, mixin or library
+    /// that defines them.
+    bodies: &'b HashMap<&'a str, HashMap<&'a str, Deprecation>>,
+    /// Deprecated functions that can be called on `self`.
+    methods: HashMap<&'a str, Deprecation>,
+    warnings: &'b mut Vec<Warning>,
+}
+
+impl<'a, 'b> Checker<'a, 'b> {
+    fn contract_body(&mut self, body: &'a [Spanned<fe::ContractStmt<'a>>]) {
+        for stmt in body.iter() {
+            match &stmt.node {
+                fe::ContractStmt::ContractField { typ, .. } => self.typ(typ),
+                fe::ContractStmt::FuncDef {
+                    decorators,
+                    args,
+                    return_type,
+                    body,
+                    ..
+                } if deprecation(decorators).is_none() => {
+                    for arg in args.iter() {
+                        self.typ(&arg.node.typ);
+                    }
+                    if let Some(return_type) = return_type {
+                        self.typ(return_type);
+                    }
+
+                    for_each_stmt(body, &mut |stmt| {
+                        if let fe::FuncStmt::VarDecl { typ, .. } = &stmt.node {
+                            self.typ(typ);
+                        }
+                    });
+                    for_each_expr(body, &mut |exp| self.expr(exp));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn expr(&mut self, exp: &Spanned<fe::Expr>) {
+        let used = match &exp.node {
+            fe::Expr::Name(name) => self.names.get(name).map(|deprecation| (*name, deprecation)),
+            fe::Expr::Attribute { value, attr } => match value.node {
+                fe::Expr::Name("self") => self.methods.get(attr.node),
+                fe::Expr::Name(name) => self
+                    .bodies
+                    .get(name)
+                    .and_then(|functions| functions.get(attr.node)),
+                _ => None,
+            }
+            .map(|deprecation| (attr.node, deprecation)),
+            _ => None,
+        };
+
+        if let Some((name, deprecation)) = used {
+            self.warnings.push(warning(name, exp.span, deprecation));
+        }
+    }
+
+    fn typ(&mut self, typ: &Spanned<fe::TypeDesc>) {
+        match &typ.node {
+            fe::TypeDesc::Base { base } => {
+                if let Some(deprecation) = self.names.get(base) {
+                    self.warnings.push(warning(base, typ.span, deprecation));
+                }
+            }
+            fe::TypeDesc::Generic { base, args } => {
+                if let Some(deprecation) = self.names.get(base) {
+                    self.warnings.push(warning(base, typ.span, deprecation));
+                }
+                args.iter().for_each(|arg| self.typ(arg));
+            }
+            fe::TypeDesc::Array { typ, .. } => self.typ(typ),
+            fe::TypeDesc::Map { from, to } => {
+                self.typ(from);
+                self.typ(to);
+            }
+            fe::TypeDesc::Tuple { items } => items.iter().for_each(|item| self.typ(item)),
+        }
+    }
+}
+
+fn warning(name: &str, span: Span, deprecation: &Deprecation) -> Warning {
+    let message = match &deprecation.message {
+        Some(message) => format!("`{}` is deprecated: {}", name, message),
+        None => format!("`{}` is deprecated", name),
+    };
+
+    Warning {
+        lint: NAME,
+        message,
+        help: None,
+        span,
+        notes: vec![Spanned {
+            node: format!("`{}` was deprecated here", name),
+            span: deprecation.span,
+        }],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lints::{
+        deprecated,
+        with_module,
+    };
+    use rstest::rstest;
+
+    const SRC: &str = "struct Point:\
+         \n  x: u256\
+         \n@deprecated(\"use `Point` instead\")\
+         \nstruct OldPoint:\
+         \n  x: u256\
+         \n@deprecated\
+         \ndef old_helper() -> u256:\
+         \n  return 1\
+         \n@deprecated(\"use `LIMIT` instead\")\
+         \nconst OLD_LIMIT: u256 = 10\
+         \ndef double(a: u256) -> u256:\
+         \n  return a * 2\
+         \nlibrary Math:\
+         \n  @deprecated\
+         \n  pub def plus(a: u256, b: u256) -> u256:\
+         \n    return a + b\
+         \ncontract Foo:\
+         \n  @deprecated\
+         \n  def old() -> u256:\
+         \n    return old_helper()\
+         \n  pub def bar():\
+         \n    {}\n";
+
+    #[rstest(
+        stmt,
+        expected_warnings,
+        case("x: u256 = self.old()", 1),
+        case("self.old()", 1),
+        case("x: u256 = old_helper()", 1),
+        case("x: u256 = Math.plus(1, 2)", 1),
+        case("x: u256 = OLD_LIMIT", 1),
+        case("x: u256 = double(OLD_LIMIT)", 1),
+        case("p: OldPoint = OldPoint(x=1)", 2),
+        case("p: Point = Point(x=1)", 0)
+    )]
+    fn deprecated_uses(stmt: &str, expected_warnings: usize) {
+        let src = SRC.replace("{}", stmt);
+
+        assert_eq!(
+            with_module(&src, deprecated::check).len(),
+            expected_warnings
+        );
+    }
+
+    #[test]
+    fn deprecation_note() {
+        let src = SRC.replace("{}", "p: Point = OldPoint(x=1)");
+        let warnings = with_module(&src, deprecated::check);
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].message,
+            "`OldPoint` is deprecated: use `Point` instead"
+        );
+        let note = &warnings[0].notes[0];
+        assert_eq!(
+            &src[note.span.start..note.span.end],
+            "deprecated(\"use `Point` instead\")"
+        );
+    }
+}
@@ -0,0 +1,456 @@
+//! Lints for code that is semantically valid but likely to be a mistake.
+//!
+//! Lints never prevent a module from compiling. Each lint produces a list of
+//! `Warning`s that are reported alongside the compiled output.
+
+use crate::Context;
+use ansi_term::Color::{
+    Red,
+    Yellow,
+};
+use fe_common::color::paint;
+use fe_parser::ast as fe;
+use fe_parser::span::{
+    Span,
+    Spanned,
+};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use strum::EnumString;
+
+mod deprecated;
+mod shadowing;
+mod tx_origin;
+mod unbounded_loop;
+
+/// A lint that can be run on a Fe module.
+pub struct Lint {
+    /// The name used to refer to the lint, e.g. in configuration files.
+    pub name: &'static str,
+    /// A short description of what the lint detects.
+    pub description: &'static str,
+    /// The parameters that the lint can be configured with.
+    pub params: &'static [LintParam],
+    check: fn(&fe::Module, &LintParams) -> Vec<Warning>,
+}
+
+/// A parameter of a lint, e.g. a threshold above which the lint warns.
+/// Parameters are non-negative integers and unset by default.
+pub struct LintParam {
+    /// The name used to refer to the parameter in configuration files.
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// The values of the parameters of the built-in lints.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LintParams {
+    values: HashMap<(&'static str, &'static str), u64>,
+}
+
+impl LintParams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the parameter named `param` of the lint named `lint`.
+    pub fn set(&mut self, lint: &str, param: &str, value: u64) -> Result<(), String> {
+        let lint = find(lint).ok_or_else(|| format!("unknown lint `{}`", lint))?;
+        let param = lint
+            .params
+            .iter()
+            .find(|known| known.name == param)
+            .ok_or_else(|| format!("unknown parameter `{}` of lint `{}`", param, lint.name))?;
+
+        self.values.insert((lint.name, param.name), value);
+        Ok(())
+    }
+
+    /// The value of the parameter named `param` of the lint named `lint`, if
+    /// it is set.
+    pub fn get(&self, lint: &str, param: &str) -> Option<u64> {
+        let lint = find(lint)?;
+        let param = lint.params.iter().find(|known| known.name == param)?;
+        self.values.get(&(lint.name, param.name)).copied()
+    }
+}
+
+/// The name and description of a lint that isn't built into the compiler.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LintInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// A collection of lints that isn't built into the compiler, e.g. rules
+/// shipped by a third party.
+///
+/// The warnings of a pack are reported and configured like those of the
+/// built-in lints, so the names of its lints should be prefixed with the name
+/// of the pack, e.g. `vendor::reentrancy`, to avoid conflicts.
+pub trait LintPack: Debug {
+    /// The lints of the pack.
+    fn lints(&self) -> Vec<LintInfo>;
+
+    /// Checks an analyzed module and returns the warnings of the pack's
+    /// lints.
+    fn check(&self, module: &fe::Module, context: &Context) -> Vec<Warning>;
+}
+
+/// All lints known to the compiler.
+pub const LINTS: &[Lint] = &[
+    Lint {
+        name: deprecated::NAME,
+        description: "uses of deprecated functions and structs",
+        params: &[],
+        check: |module, _| deprecated::check(module),
+    },
+    Lint {
+        name: shadowing::NAME,
+        description: "local variables named like another variable, parameter or field",
+        params: &[],
+        check: |module, _| shadowing::check(module),
+    },
+    Lint {
+        name: tx_origin::NAME,
+        description: "use of `tx.origin` for authorization",
+        params: &[],
+        check: |module, _| tx_origin::check(module),
+    },
+    Lint {
+        name: unbounded_loop::NAME,
+        description: "loops bounded by a growing storage value",
+        params: unbounded_loop::PARAMS,
+        check: unbounded_loop::check,
+    },
+];
+
+/// Returns the lint with the given name.
+pub fn find(name: &str) -> Option<&'static Lint> {
+    LINTS.iter().find(|lint| lint.name == name)
+}
+
+/// Determines how warnings of a lint are handled.
+#[derive(Clone, Copy, Debug, PartialEq, EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum Level {
+    /// Warnings are not reported.
+    Allow,
+    /// Warnings are reported, but compilation continues.
+    Warn,
+    /// Warnings are reported as errors and compilation fails.
+    Deny,
+}
+
+impl Default for Level {
+    fn default() -> Self {
+        Level::Warn
+    }
+}
+
+/// A potential problem found by a lint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Warning {
+    /// The name of the lint that produced the warning.
+    pub lint: &'static str,
+    pub message: String,
+    /// A suggestion on how to resolve the warning.
+    pub help: Option<String>,
+    pub span: Span,
+    /// Related code, e.g. the definition that the warning refers to.
+    pub notes: Vec<Spanned<String>>,
+}
+
+impl Warning {
+    /// Formats the warning using the source code.
+    ///
+    /// The string will contain the message, line number, offending code and
+    /// help text. Warnings of denied lints are labeled as errors.
+    pub fn format_user(&self, src: &str, level: Level) -> String {
+        let line = src[..self.span.start].lines().count();
+        let (label, color) = match level {
+            Level::Deny => ("error", Red),
+            _ => ("warning", Yellow),
+        };
+        let code = paint(color, &src[self.span.start..self.span.end]);
+
+        let mut formatted = format!(
+            "{}[{}]: {} on line {}\n{}",
+            label, self.lint, self.message, line, code
+        );
+        for note in self.notes.iter() {
+            let line = src[..note.span.start].lines().count();
+            formatted.push_str(&format!(
+                "\nnote: {} on line {}\n{}",
+                note.node,
+                line,
+                &src[note.span.start..note.span.end]
+            ));
+        }
+        if let Some(help) = &self.help {
+            formatted.push_str(&format!("\nhelp: {}", help));
+        }
+
+        formatted
+    }
+}
+
+/// Runs all lints on the module and returns the warnings ordered by their
+/// position in the source.
+pub fn check(module: &fe::Module) -> Vec<Warning> {
+    check_with(module, &LintParams::new())
+}
+
+/// Runs all lints on the module like `check`, with the parameters set in
+/// `params`.
+pub fn check_with(module: &fe::Module, params: &LintParams) -> Vec<Warning> {
+    let mut warnings = LINTS
+        .iter()
+        .flat_map(|lint| (lint.check)(module, params))
+        .collect::<Vec<_>>();
+
+    warnings.sort_by_key(|warning| warning.span.start);
+    warnings
+}
+
+/// Calls `f` with the body of every function defined in the module.
+fn for_each_function<'a, F>(module: &'a fe::Module<'a>, mut f: F)
+where
+    F: FnMut(&'a [Spanned<fe::FuncStmt<'a>>]),
+{
+    for stmt in module.body.iter() {
+        match &stmt.node {
+            fe::ModuleStmt::ContractDef { body, .. } | fe::ModuleStmt::LibraryDef { body, .. } => {
+                for contract_stmt in body.iter() {
+                    if let fe::ContractStmt::FuncDef { body, .. } = &contract_stmt.node {
+                        f(body)
+                    }
+                }
+            }
+            fe::ModuleStmt::FuncDef { def } => {
+                if let fe::ContractStmt::FuncDef { body, .. } = &def.node {
+                    f(body)
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Calls `f` with every statement, including statements nested in the bodies
+/// of compound statements.
+fn for_each_stmt<'a, F>(stmts: &'a [Spanned<fe::FuncStmt<'a>>], f: &mut F)
+where
+    F: FnMut(&'a Spanned<fe::FuncStmt<'a>>),
+{
+    for stmt in stmts.iter() {
+        f(stmt);
+
+        match &stmt.node {
+            fe::FuncStmt::For { body, or_else, .. }
+            | fe::FuncStmt::While { body, or_else, .. }
+            | fe::FuncStmt::If { body, or_else, .. } => {
+                for_each_stmt(body, f);
+                for_each_stmt(or_else, f);
+            }
+            fe::FuncStmt::Match { arms, .. } => {
+                for arm in arms.iter() {
+                    for_each_stmt(&arm.node.body, f);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Calls `f` with every expression contained in the statements, including
+/// nested sub-expressions.
+pub fn for_each_expr<'a, F>(stmts: &[Spanned<fe::FuncStmt<'a>>], f: &mut F)
+where
+    F: FnMut(&Spanned<fe::Expr<'a>>),
+{
+    for stmt in stmts.iter() {
+        match &stmt.node {
+            fe::FuncStmt::Return { value } => value.iter().for_each(|exp| walk_expr(exp, f)),
+            fe::FuncStmt::VarDecl { target, value, .. } => {
+                walk_expr(target, f);
+                value.iter().for_each(|exp| walk_expr(exp, f));
+            }
+            fe::FuncStmt::Assign { targets, value } => {
+                targets.iter().for_each(|exp| walk_expr(exp, f));
+                walk_expr(value, f);
+            }
+            fe::FuncStmt::AugAssign { target, value, .. } => {
+                walk_expr(target, f);
+                walk_expr(value, f);
+            }
+            fe::FuncStmt::For {
+                target,
+                iter,
+                body,
+                or_else,
+            } => {
+                walk_expr(target, f);
+                walk_expr(iter, f);
+                for_each_expr(body, f);
+                for_each_expr(or_else, f);
+            }
+            fe::FuncStmt::While {
+                test,
+                body,
+                or_else,
+            }
+            | fe::FuncStmt::If {
+                test,
+                body,
+                or_else,
+            } => {
+                walk_expr(test, f);
+                for_each_expr(body, f);
+                for_each_expr(or_else, f);
+            }
+            // Patterns are compile-time values, only the matched value and
+            // the bodies are visited.
+            fe::FuncStmt::Match { value, arms } => {
+                walk_expr(value, f);
+                for arm in arms.iter() {
+                    for_each_expr(&arm.node.body, f);
+                }
+            }
+            fe::FuncStmt::Assert { test, msg } => {
+                walk_expr(test, f);
+                msg.iter().for_each(|exp| walk_expr(exp, f));
+            }
+            fe::FuncStmt::Emit { value } => walk_expr(value, f),
+            fe::FuncStmt::Revert { error } => error.iter().for_each(|exp| walk_expr(exp, f)),
+            // Expression statements are not spanned on their own, their span
+            // is the statement's span.
+            fe::FuncStmt::Expr { value } => walk_expr(
+                &Spanned {
+                    node: value.clone(),
+                    span: stmt.span,
+                },
+                f,
+            ),
+            fe::FuncStmt::Pass
+            | fe::FuncStmt::Break
+            | fe::FuncStmt::Continue
+            | fe::FuncStmt::Error(_) => {}
+        }
+    }
+}
+
+/// Calls `f` with the expression and all of its sub-expressions.
+fn walk_expr<'a, F>(exp: &Spanned<fe::Expr<'a>>, f: &mut F)
+where
+    F: FnMut(&Spanned<fe::Expr<'a>>),
+{
+    f(exp);
+    walk_sub_exprs(&exp.node, f);
+}
+
+/// Calls `f` with all sub-expressions of the expression.
+fn walk_sub_exprs<'a, F>(exp: &fe::Expr<'a>, f: &mut F)
+where
+    F: FnMut(&Spanned<fe::Expr<'a>>),
+{
+    match exp {
+        fe::Expr::Ternary {
+            if_expr,
+            test,
+            else_expr,
+        } => {
+            walk_expr(if_expr, f);
+            walk_expr(test, f);
+            walk_expr(else_expr, f);
+        }
+        fe::Expr::BoolOperation { left, right, .. }
+        | fe::Expr::BinOperation { left, right, .. }
+        | fe::Expr::CompOperation { left, right, .. } => {
+            walk_expr(left, f);
+            walk_expr(right, f);
+        }
+        fe::Expr::UnaryOperation { operand, .. } => walk_expr(operand, f),
+        fe::Expr::Attribute { value, .. } => walk_expr(value, f),
+        fe::Expr::Subscript { value, .. } => walk_expr(value, f),
+        fe::Expr::Call { func, args } => {
+            walk_expr(func, f);
+            for arg in args.node.iter() {
+                match &arg.node {
+                    // positional arguments are spanned by the argument
+                    fe::CallArg::Arg(value) => walk_expr(
+                        &Spanned {
+                            node: value.clone(),
+                            span: arg.span,
+                        },
+                        f,
+                    ),
+                    fe::CallArg::Kwarg(kwarg) => walk_expr(&kwarg.value, f),
+                }
+            }
+        }
+        fe::Expr::List { elts } | fe::Expr::Tuple { elts } => {
+            elts.iter().for_each(|exp| walk_expr(exp, f))
+        }
+        fe::Expr::ListComp { .. }
+        | fe::Expr::Bool(_)
+        | fe::Expr::Name(_)
+        | fe::Expr::Num(_)
+        | fe::Expr::Str(_)
+        | fe::Expr::Ellipsis => {}
+    }
+}
+
+/// Parses the source code and calls `f` with the module, e.g. to run a lint
+/// on it.
+#[cfg(test)]
+fn with_module<T, F>(src: &str, f: F) -> T
+where
+    F: FnOnce(&fe::Module) -> T,
+{
+    let tokens = fe_parser::get_parse_tokens(src).expect("unable to parse contract");
+    let module = fe_parser::parsers::file_input(&tokens[..])
+        .expect("unable to build module AST")
+        .1
+        .node;
+
+    f(&module)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lints::{
+        for_each_expr,
+        for_each_function,
+        with_module,
+    };
+    use rstest::rstest;
+
+    /// The source code of every expression visited in the body of `bar`.
+    fn visited(stmt: &str) -> Vec<String> {
+        let src = format!("contract Foo:\n  pub def bar():\n    {}\n", stmt);
+        let mut visited = vec![];
+        with_module(&src, |module| {
+            for_each_function(module, |body| {
+                for_each_expr(body, &mut |exp| {
+                    visited.push(src[exp.span.start..exp.span.end].to_string())
+                })
+            })
+        });
+
+        visited
+    }
+
+    #[rstest(
+        stmt,
+        expected,
+        case("foo(a)", vec!["foo(a)", "foo", "a"]),
+        case("self.foo(a + b)", vec!["self.foo(a + b)", "self.foo", "self", "a + b", "a", "b"]),
+        case("foo(bar(a), x=b)", vec!["foo(bar(a), x=b)", "foo", "bar(a)", "bar", "a", "b"]),
+        case("x: u256 = foo(-a)", vec!["x", "foo(-a)", "foo", "-a", "a"]),
+        case("emit Foo(a, x=b)", vec!["Foo(a, x=b)", "Foo", "a", "b"])
+    )]
+    fn nested_expressions(stmt: &str, expected: Vec<&str>) {
+        assert_eq!(visited(stmt), expected);
+    }
+}
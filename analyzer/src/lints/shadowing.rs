@@ -0,0 +1,203 @@
+use crate::lints::Warning;
+use fe_parser::ast as fe;
+use fe_parser::span::Spanned;
+use std::collections::HashMap;
+
+pub const NAME: &str = "shadowing";
+
+/// Warns about local variables that have the same name as a variable of an
+/// enclosing block, a parameter of the function or a storage field.
+///
+/// Storage fields are only accessed through `self`, so assigning to a local
+/// variable that is named like a field leaves the field unchanged.
+pub fn check(module: &fe::Module) -> Vec<Warning> {
+    let mut warnings = vec![];
+
+    let mut mixin_fields = HashMap::new();
+    for stmt in module.body.iter() {
+        if let fe::ModuleStmt::MixinDef { name, body, .. } = &stmt.node {
+            mixin_fields.insert(name.node, field_names(body));
+        }
+    }
+
+    for stmt in module.body.iter() {
+        let (fields, body) = match &stmt.node {
+            fe::ModuleStmt::ContractDef { mixins, body, .. } => {
+                let mut fields = field_names(body);
+                for mixin in mixins.iter() {
+                    fields.extend(mixin_fields.get(mixin.node).cloned().unwrap_or_default());
+                }
+                (fields, body.as_slice())
+            }
+            fe::ModuleStmt::MixinDef { body, .. } | fe::ModuleStmt::LibraryDef { body, .. } => {
+                (field_names(body), body.as_slice())
+            }
+            fe::ModuleStmt::FuncDef { def } => (vec![], std::slice::from_ref(def.as_ref())),
+            _ => continue,
+        };
+
+        for stmt in body.iter() {
+            if let fe::ContractStmt::FuncDef { args, body, .. } = &stmt.node {
+                let mut checker = Checker {
+                    fields: &fields,
+                    params: args.iter().map(|arg| arg.node.name.node).collect(),
+                    scopes: vec![vec![]],
+                    warnings: &mut warnings,
+                };
+                checker.block(body);
+            }
+        }
+    }
+
+    warnings
+}
+
+fn field_names<'a>(body: &'a [Spanned<fe::ContractStmt<'a>>]) -> Vec<&'a str> {
+    body.iter()
+        .filter_map(|stmt| match &stmt.node {
+            fe::ContractStmt::ContractField { name, .. } => Some(name.node),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Tracks the names that are defined while walking the body of a function.
+struct Checker<'a, 'b> {
+    fields: &'b [&'a str],
+    params: Vec<&'a str>,
+    /// The names of the local variables of each enclosing block.
+    scopes: Vec<Vec<&'a str>>,
+    warnings: &'b mut Vec<Warning>,
+}
+
+impl<'a, 'b> Checker<'a, 'b> {
+    fn block(&mut self, stmts: &'a [Spanned<fe::FuncStmt<'a>>]) {
+        for stmt in stmts.iter() {
+            match &stmt.node {
+                fe::FuncStmt::VarDecl { target, .. } => self.define(target),
+                fe::FuncStmt::For {
+                    target,
+                    body,
+                    or_else,
+                    ..
+                } => {
+                    self.scopes.push(vec![]);
+                    self.define(target);
+                    self.block(body);
+                    self.scopes.pop();
+                    self.nested_block(or_else);
+                }
+                fe::FuncStmt::While { body, or_else, .. }
+                | fe::FuncStmt::If { body, or_else, .. } => {
+                    self.nested_block(body);
+                    self.nested_block(or_else);
+                }
+                fe::FuncStmt::Match { arms, .. } => {
+                    for arm in arms.iter() {
+                        self.nested_block(&arm.node.body);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn nested_block(&mut self, stmts: &'a [Spanned<fe::FuncStmt<'a>>]) {
+        self.scopes.push(vec![]);
+        self.block(stmts);
+        self.scopes.pop();
+    }
+
+    /// Checks the names defined by the target of a declaration and adds them
+    /// to the current block.
+    fn define(&mut self, target: &'a Spanned<fe::Expr<'a>>) {
+        match &target.node {
+            fe::Expr::Name(name) => {
+                let depth = self.scopes.len() - 1;
+                let shadowed = if self.scopes[..depth]
+                    .iter()
+                    .any(|scope| scope.contains(name))
+                {
+                    Some(format!(
+                        "a variable of an enclosing block is named `{}`",
+                        name
+                    ))
+                } else if self.params.contains(name) {
+                    Some(format!("the function has a parameter named `{}`", name))
+                } else if self.fields.contains(name) {
+                    Some(format!(
+                        "the storage field `self.{}` has the same name",
+                        name
+                    ))
+                } else {
+                    None
+                };
+
+                if let Some(help) = shadowed {
+                    self.warnings.push(warning(name, help, target));
+                }
+                self.scopes[depth].push(*name);
+            }
+            fe::Expr::Tuple { elts } => elts.iter().for_each(|elt| self.define(elt)),
+            _ => {}
+        }
+    }
+}
+
+fn warning(name: &str, help: String, target: &Spanned<fe::Expr>) -> Warning {
+    Warning {
+        lint: NAME,
+        message: format!("variable `{}` shadows another definition", name),
+        help: Some(format!(
+            "{}. Assignments to this variable don't change the other definition, consider \
+             renaming it.",
+            help
+        )),
+        span: target.span,
+        notes: vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lints::{
+        shadowing,
+        with_module,
+    };
+    use rstest::rstest;
+
+    #[rstest(
+        stmt,
+        expected_warnings,
+        case("balance: u256 = 0", 1),
+        case("amount: u256 = 0", 1),
+        case("total: u256 = 0", 1),
+        case("other: u256 = 0", 0)
+    )]
+    fn shadowed_names(stmt: &str, expected_warnings: usize) {
+        let src = format!(
+            "contract Foo:\
+             \n  balance: u256\
+             \n  pub def bar(amount: u256):\
+             \n    total: u256 = amount\
+             \n    if amount > 1:\
+             \n      {}\
+             \n      self.balance = total\n",
+            stmt
+        );
+
+        assert_eq!(with_module(&src, shadowing::check).len(), expected_warnings);
+    }
+
+    #[test]
+    fn sibling_blocks() {
+        let src = "contract Foo:\
+             \n  pub def bar(x: u256):\
+             \n    if x > 1:\
+             \n      y: u256 = 1\
+             \n    else:\
+             \n      y: u256 = 2\n";
+
+        assert_eq!(with_module(src, shadowing::check).len(), 0);
+    }
+}
@@ -0,0 +1,79 @@
+use crate::lints::{
+    for_each_expr,
+    for_each_function,
+    Warning,
+};
+use fe_parser::ast as fe;
+use fe_parser::span::Spanned;
+
+pub const NAME: &str = "tx_origin";
+
+/// Warns about comparisons against `tx.origin`.
+///
+/// Authorizing callers using `tx.origin` allows any contract that the
+/// authorized account interacts with to act on its behalf.
+pub fn check(module: &fe::Module) -> Vec<Warning> {
+    let mut warnings = vec![];
+
+    for_each_function(module, |body| {
+        for_each_expr(body, &mut |exp| {
+            if let fe::Expr::CompOperation { left, op, right } = &exp.node {
+                let is_equality = matches!(op.node, fe::CompOperator::Eq | fe::CompOperator::NotEq);
+
+                if is_equality && (is_tx_origin(left) || is_tx_origin(right)) {
+                    warnings.push(Warning {
+                        lint: NAME,
+                        message: "`tx.origin` used for authorization".to_string(),
+                        help: Some(
+                            "`tx.origin` is the account that started the transaction, which may \
+                             be a different account than the caller. Any contract called by an \
+                             authorized account can pass this check. Use `msg.sender` instead."
+                                .to_string(),
+                        ),
+                        span: exp.span,
+                        notes: vec![],
+                    })
+                }
+            }
+        })
+    });
+
+    warnings
+}
+
+fn is_tx_origin(exp: &Spanned<fe::Expr>) -> bool {
+    if let fe::Expr::Attribute { value, attr } = &exp.node {
+        return value.node == fe::Expr::Name("tx") && attr.node == "origin";
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lints::{
+        tx_origin,
+        with_module,
+    };
+    use rstest::rstest;
+
+    #[rstest(
+        body,
+        expected_warnings,
+        case("assert tx.origin == self.owner", 1),
+        case("assert self.owner != tx.origin", 1),
+        case("if tx.origin == self.owner:\n      revert", 1),
+        case("require(tx.origin == self.owner)", 1),
+        case("foo(tx.origin != self.owner)", 1),
+        case("assert msg.sender == self.owner", 0),
+        case("x: address = tx.origin", 0)
+    )]
+    fn tx_origin_comparisons(body: &str, expected_warnings: usize) {
+        let src = format!(
+            "contract Foo:\n  owner: address\n  pub def bar():\n    {}\n",
+            body
+        );
+
+        assert_eq!(with_module(&src, tx_origin::check).len(), expected_warnings);
+    }
+}
@@ -0,0 +1,394 @@
+use crate::lints::{
+    for_each_expr,
+    for_each_stmt,
+    walk_expr,
+    LintParam,
+    LintParams,
+    Warning,
+};
+use fe_parser::ast as fe;
+use fe_parser::span::Spanned;
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+
+pub const NAME: &str = "unbounded_loop";
+
+pub const MAX_ITERATIONS: &str = "max_iterations";
+
+pub const PARAMS: &[LintParam] = &[LintParam {
+    name: MAX_ITERATIONS,
+    description: "the number of iterations above which loops with a constant bound are reported",
+}];
+
+/// Warns about `while` loops whose condition, or `for` loops whose range or
+/// array, depends on a storage field that grows over time.
+///
+/// The gas cost of such a loop increases with the field's value. Once it
+/// exceeds the block gas limit, the function can no longer be called.
+///
+/// With `max_iterations` set, `for` loops over a range with literal bounds or
+/// over a fixed-size storage array are reported if they run more often.
+pub fn check(module: &fe::Module, params: &LintParams) -> Vec<Warning> {
+    let mut warnings = vec![];
+    let max_iterations = params.get(NAME, MAX_ITERATIONS);
+
+    for stmt in module.body.iter() {
+        if let fe::ModuleStmt::ContractDef { body, .. } = &stmt.node {
+            let growing = growing_fields(body);
+            let fixed_arrays = fixed_arrays(body);
+
+            for_each_loop(body, |test| {
+                walk_expr(test, &mut |exp| {
+                    if let Some(field) = storage_field(exp) {
+                        if growing.contains(field) {
+                            warnings.push(warning(field, test))
+                        }
+                    }
+                });
+
+                if let (Some(max_iterations), Some(iterations)) =
+                    (max_iterations, iterations(test, &fixed_arrays))
+                {
+                    if iterations > max_iterations {
+                        warnings.push(too_many_iterations(iterations, max_iterations, test))
+                    }
+                }
+            });
+        }
+    }
+
+    warnings
+}
+
+fn warning(field: &str, test: &Spanned<fe::Expr>) -> Warning {
+    Warning {
+        lint: NAME,
+        message: format!(
+            "loop bound depends on storage field `{}`, which grows over time",
+            field
+        ),
+        help: Some(format!(
+            "the gas cost of this loop increases as `self.{}` grows, until the function exceeds the \
+             block gas limit and can no longer be called. Consider limiting the number of \
+             iterations or processing the elements in batches.",
+            field
+        )),
+        span: test.span,
+        notes: vec![],
+    }
+}
+
+fn too_many_iterations(iterations: u64, max_iterations: u64, iter: &Spanned<fe::Expr>) -> Warning {
+    Warning {
+        lint: NAME,
+        message: format!(
+            "loop runs {} times, more than the maximum of {}",
+            iterations, max_iterations
+        ),
+        help: Some(
+            "the gas cost of a loop increases with each iteration and may exceed the block gas \
+             limit. Consider processing the elements in batches, or raise `max_iterations` of \
+             this lint if the loop is known to fit."
+                .to_string(),
+        ),
+        span: iter.span,
+        notes: vec![],
+    }
+}
+
+/// The number of iterations of a loop over `range` with literal bounds or
+/// over a fixed-size storage array.
+fn iterations(iter: &Spanned<fe::Expr>, fixed_arrays: &HashMap<&str, u64>) -> Option<u64> {
+    if let Some(field) = storage_field(iter) {
+        return fixed_arrays.get(field).copied();
+    }
+
+    if let fe::Expr::Call { func, args } = &iter.node {
+        if func.node == fe::Expr::Name("range") {
+            let bounds = args
+                .node
+                .iter()
+                .map(|arg| match &arg.node {
+                    // literals that don't fit are beyond any maximum anyway
+                    fe::CallArg::Arg(fe::Expr::Num(num)) => Some(num.parse().unwrap_or(u64::MAX)),
+                    _ => None,
+                })
+                .collect::<Option<Vec<u64>>>()?;
+            return match bounds.as_slice() {
+                [end] => Some(*end),
+                [start, end] => Some(end.saturating_sub(*start)),
+                _ => None,
+            };
+        }
+    }
+
+    None
+}
+
+/// The sizes of the fixed-size arrays among the storage fields of the
+/// contract, by field name.
+fn fixed_arrays<'a>(body: &'a [Spanned<fe::ContractStmt<'a>>]) -> HashMap<&'a str, u64> {
+    body.iter()
+        .filter_map(|stmt| match &stmt.node {
+            fe::ContractStmt::ContractField {
+                name,
+                typ:
+                    Spanned {
+                        node: fe::TypeDesc::Array { dimension, .. },
+                        ..
+                    },
+                ..
+            } => Some((name.node, *dimension as u64)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Calls `f` with the condition of every `while` loop and the iterator of
+/// every `for` loop in the contract.
+fn for_each_loop<'a, F>(body: &'a [Spanned<fe::ContractStmt<'a>>], mut f: F)
+where
+    F: FnMut(&'a Spanned<fe::Expr<'a>>),
+{
+    for stmt in body.iter() {
+        if let fe::ContractStmt::FuncDef { body, .. } = &stmt.node {
+            for_each_stmt(body, &mut |stmt| match &stmt.node {
+                fe::FuncStmt::While { test, .. } => f(test),
+                fe::FuncStmt::For { iter, .. } => f(iter),
+                _ => {}
+            })
+        }
+    }
+}
+
+/// Names of the storage fields that are incremented anywhere in the contract,
+/// i.e. `self.field += x` or `self.field = self.field + x`, and of the storage
+/// arrays that are pushed to, i.e. `self.field.push(x)`.
+fn growing_fields<'a>(body: &'a [Spanned<fe::ContractStmt<'a>>]) -> HashSet<&'a str> {
+    let mut fields = HashSet::new();
+    let arrays = body
+        .iter()
+        .filter_map(|stmt| match &stmt.node {
+            fe::ContractStmt::ContractField {
+                name,
+                typ:
+                    Spanned {
+                        node: fe::TypeDesc::Generic { base: "Array", .. },
+                        ..
+                    },
+                ..
+            } => Some(name.node),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+
+    for stmt in body.iter() {
+        if let fe::ContractStmt::FuncDef { body, .. } = &stmt.node {
+            for_each_expr(body, &mut |exp| {
+                if let fe::Expr::Call { func, .. } = &exp.node {
+                    if let fe::Expr::Attribute { value, attr } = &func.node {
+                        match storage_field(value) {
+                            Some(field) if attr.node == "push" && arrays.contains(field) => {
+                                fields.insert(field);
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            });
+            for_each_stmt(body, &mut |stmt| match &stmt.node {
+                fe::FuncStmt::AugAssign { target, op, .. } if op.node == fe::BinOperator::Add => {
+                    if let Some(field) = storage_field(target) {
+                        fields.insert(field);
+                    }
+                }
+                fe::FuncStmt::Assign { targets, value } => {
+                    if let fe::Expr::BinOperation {
+                        left,
+                        op:
+                            Spanned {
+                                node: fe::BinOperator::Add,
+                                ..
+                            },
+                        right,
+                    } = &value.node
+                    {
+                        for target in targets.iter() {
+                            if let Some(field) = storage_field(target) {
+                                if [left, right]
+                                    .iter()
+                                    .any(|operand| storage_field(operand) == Some(field))
+                                {
+                                    fields.insert(field);
+                                }
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            })
+        }
+    }
+
+    fields
+}
+
+/// Returns the name of the field if the expression is of the form
+/// `self.field`.
+fn storage_field<'a>(exp: &Spanned<fe::Expr<'a>>) -> Option<&'a str> {
+    if let fe::Expr::Attribute { value, attr } = &exp.node {
+        if value.node == fe::Expr::Name("self") {
+            return Some(attr.node);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lints::{
+        unbounded_loop,
+        with_module,
+        LintParams,
+    };
+    use rstest::rstest;
+
+    fn check(src: &str) -> Vec<String> {
+        check_with(src, &LintParams::new())
+    }
+
+    fn check_with(src: &str, params: &LintParams) -> Vec<String> {
+        with_module(src, |module| unbounded_loop::check(module, params))
+            .into_iter()
+            .map(|warning| warning.message)
+            .collect()
+    }
+
+    #[rstest(
+        grow,
+        expected_warnings,
+        case("self.count += 1", 1),
+        case("self.count = self.count + 1", 1),
+        case("self.count = 10", 0),
+        case("self.count -= 1", 0)
+    )]
+    fn loop_over_storage_field(grow: &str, expected_warnings: usize) {
+        let src = format!(
+            "contract Foo:\
+             \n  count: u256\
+             \n  pub def add():\
+             \n    {}\
+             \n  pub def sum() -> u256:\
+             \n    i: u256 = 0\
+             \n    while i < self.count:\
+             \n      i = i + 1\
+             \n    return i\n",
+            grow
+        );
+
+        let warnings = check(&src);
+        assert_eq!(warnings.len(), expected_warnings);
+        if expected_warnings > 0 {
+            assert_eq!(
+                warnings[0],
+                "loop bound depends on storage field `count`, which grows over time"
+            );
+        }
+    }
+
+    #[rstest(
+        grow,
+        repeat,
+        expected_warnings,
+        case("self.items.push(1)", "for x in self.items:", 1),
+        case("self.items.push(1)", "while i < self.items.len():", 1),
+        case("self.items.pop()", "for x in self.items:", 0),
+        case("self.items[0] = 1", "while i < self.items.len():", 0)
+    )]
+    fn loop_over_storage_array(grow: &str, repeat: &str, expected_warnings: usize) {
+        let src = format!(
+            "contract Foo:\
+             \n  items: Array<u256>\
+             \n  pub def add():\
+             \n    {}\
+             \n  pub def count() -> u256:\
+             \n    i: u256 = 0\
+             \n    {}\
+             \n      i = i + 1\
+             \n    return i\n",
+            grow, repeat
+        );
+
+        let warnings = check(&src);
+        assert_eq!(warnings.len(), expected_warnings);
+        if expected_warnings > 0 {
+            assert_eq!(
+                warnings[0],
+                "loop bound depends on storage field `items`, which grows over time"
+            );
+        }
+    }
+
+    #[test]
+    fn range_over_storage_field() {
+        let warnings = check(
+            "contract Foo:\
+             \n  count: u256\
+             \n  pub def add():\
+             \n    self.count += 1\
+             \n  pub def sum() -> u256:\
+             \n    total: u256 = 0\
+             \n    for i in range(self.count):\
+             \n      total = total + i\
+             \n    return total\n",
+        );
+
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[rstest(
+        repeat,
+        max_iterations,
+        expected_warnings,
+        case("for i in range(1000):", None, 0),
+        case("for i in range(1000):", Some(1000), 0),
+        case("for i in range(1000):", Some(999), 1),
+        case("for i in range(10, 1000):", Some(990), 0),
+        case("for i in range(10, 1000):", Some(989), 1),
+        case("for i in self.values:", Some(99), 1),
+        case("for i in self.values:", Some(100), 0),
+        case("for i in range(self.count):", Some(0), 0)
+    )]
+    fn loop_exceeding_max_iterations(
+        repeat: &str,
+        max_iterations: Option<u64>,
+        expected_warnings: usize,
+    ) {
+        let src = format!(
+            "contract Foo:\
+             \n  count: u256\
+             \n  values: u256[100]\
+             \n  pub def sum() -> u256:\
+             \n    total: u256 = 0\
+             \n    {}\
+             \n      total = total + i\
+             \n    return total\n",
+            repeat
+        );
+        let mut params = LintParams::new();
+        if let Some(max_iterations) = max_iterations {
+            params
+                .set("unbounded_loop", "max_iterations", max_iterations)
+                .unwrap();
+        }
+
+        let warnings = check_with(&src, &params);
+        assert_eq!(warnings.len(), expected_warnings);
+        if expected_warnings > 0 {
+            assert!(warnings[0].starts_with("loop runs"));
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::namespace::types::Base;
+use num_bigint::BigInt;
+
+/// A value that is known at compile time.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConstValue {
+    Int(BigInt),
+    Bool(bool),
+}
+
+/// A module or contract constant.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Constant {
+    pub typ: Base,
+    pub value: ConstValue,
+}
+
+impl ConstValue {
+    /// The value as a decimal 256 bit word. Negative integers are encoded in
+    /// two's complement and booleans as `0` or `1`.
+    pub fn to_word(&self) -> String {
+        match self {
+            ConstValue::Int(value) if value < &BigInt::from(0) => {
+                (BigInt::from(2).pow(256) + value).to_string()
+            }
+            ConstValue::Int(value) => value.to_string(),
+            ConstValue::Bool(value) => (*value as u8).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::namespace::constants::ConstValue;
+    use num_bigint::BigInt;
+
+    #[test]
+    fn to_word() {
+        assert_eq!(ConstValue::Int(BigInt::from(42)).to_word(), "42");
+        assert_eq!(
+            ConstValue::Int(BigInt::from(-1)).to_word(),
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+        );
+        assert_eq!(ConstValue::Bool(true).to_word(), "1");
+    }
+}
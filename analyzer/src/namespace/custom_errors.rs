@@ -0,0 +1,54 @@
+use crate::namespace::types::{
+    AbiEncoding,
+    FixedSize,
+};
+use fe_common::utils::keccak;
+
+/// A user defined error that a contract can revert with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomError {
+    pub name: String,
+    /// The 4 byte selector that precedes the encoded error data.
+    pub selector: String,
+    fields: Vec<FixedSize>,
+}
+
+impl CustomError {
+    pub fn new(name: &str, fields: Vec<FixedSize>) -> Self {
+        let abi_fields = fields
+            .iter()
+            .map(|field| field.abi_name())
+            .collect::<Vec<String>>();
+        let signature = format!("{}({})", name, abi_fields.join(","));
+
+        Self {
+            name: name.to_owned(),
+            selector: keccak::partial(signature.as_bytes(), 4),
+            fields,
+        }
+    }
+
+    /// The error's field types.
+    pub fn field_types(&self) -> Vec<FixedSize> {
+        self.fields.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::namespace::custom_errors::CustomError;
+    use crate::namespace::types::{
+        FixedSize,
+        U256,
+    };
+
+    #[test]
+    fn selector() {
+        let error = CustomError::new(
+            "InsufficientBalance",
+            vec![FixedSize::Base(U256), FixedSize::Base(U256)],
+        );
+
+        assert_eq!(error.selector, "0xcf479181");
+    }
+}
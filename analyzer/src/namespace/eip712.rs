@@ -0,0 +1,126 @@
+//! Hashing of structs as EIP-712 typed data.
+//!
+//! The fields of a struct are all base types, so its data is encoded as one
+//! word per field, which is how structs are laid out in memory.
+
+use crate::namespace::types::{
+    AbiEncoding,
+    Base,
+    FixedSize,
+    Struct,
+};
+use fe_common::utils::keccak;
+
+/// The type of the domain that signatures are bound to.
+pub const DOMAIN_TYPE: &str =
+    "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+
+/// The fields of the domain type as `(name, type)` pairs.
+pub const DOMAIN_FIELDS: &[(&str, &str)] = &[
+    ("name", "string"),
+    ("version", "string"),
+    ("chainId", "uint256"),
+    ("verifyingContract", "address"),
+];
+
+/// The EIP-712 type of a struct field, if it has one. Fixed-point numbers
+/// can't be encoded.
+pub fn field_type(typ: &FixedSize) -> Option<String> {
+    match typ {
+        FixedSize::Base(Base::Fixed(_)) => None,
+        FixedSize::Base(Base::Byte) => Some("bytes1".to_string()),
+        FixedSize::Base(base) => Some(base.abi_name()),
+        _ => None,
+    }
+}
+
+/// The fields of a struct as `(name, type)` pairs, if all of them can be
+/// encoded.
+pub fn fields(struct_: &Struct) -> Option<Vec<(String, String)>> {
+    struct_
+        .get_field_names()
+        .into_iter()
+        .zip(struct_.get_field_types().iter())
+        .map(|(name, typ)| field_type(typ).map(|typ| (name, typ)))
+        .collect()
+}
+
+/// The encoding of a struct type, e.g. `Mail(address from,address to)`.
+pub fn encode_type(struct_: &Struct) -> Option<String> {
+    let fields = fields(struct_)?
+        .into_iter()
+        .map(|(name, typ)| format!("{} {}", typ, name))
+        .collect::<Vec<_>>();
+
+    Some(format!("{}({})", struct_.name, fields.join(",")))
+}
+
+/// The hash of the encoding of a struct type as a hex string.
+pub fn type_hash(struct_: &Struct) -> Option<String> {
+    encode_type(struct_).map(|encoded| keccak::full(encoded.as_bytes()))
+}
+
+/// The hash of the domain type as a hex string.
+pub fn domain_type_hash() -> String {
+    keccak::full(DOMAIN_TYPE.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::namespace::eip712::{
+        domain_type_hash,
+        encode_type,
+        type_hash,
+    };
+    use crate::namespace::types::{
+        Base,
+        FixedPoint,
+        FixedSize,
+        Integer,
+        Struct,
+    };
+
+    fn permit() -> Struct {
+        let mut permit = Struct::new("Permit");
+        permit.add_field("owner", &FixedSize::Base(Base::Address));
+        permit.add_field("spender", &FixedSize::Base(Base::Address));
+        permit.add_field("value", &FixedSize::Base(Base::Numeric(Integer::U256)));
+        permit.add_field("nonce", &FixedSize::Base(Base::Numeric(Integer::U256)));
+        permit.add_field("deadline", &FixedSize::Base(Base::Numeric(Integer::U256)));
+        permit
+    }
+
+    #[test]
+    fn permit_type_hash() {
+        assert_eq!(
+            encode_type(&permit()).unwrap(),
+            "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)"
+        );
+        assert_eq!(
+            type_hash(&permit()).unwrap(),
+            "0x6e71edae12b1b97f4d1f60370fef10105fa2faae0126114a169c64845d6126c9"
+        );
+    }
+
+    #[test]
+    fn domain_hash() {
+        assert_eq!(
+            domain_type_hash(),
+            "0x8b73c3c69bb8fe3d512ecc4cf759cc79239f7b179b0ffacaa9a75d522b39400f"
+        );
+    }
+
+    #[test]
+    fn fixed_point_field() {
+        let mut price = Struct::new("Price");
+        price.add_field(
+            "amount",
+            &FixedSize::Base(Base::Fixed(FixedPoint {
+                integer: Integer::I128,
+                decimals: 18,
+            })),
+        );
+
+        assert_eq!(encode_type(&price), None);
+    }
+}
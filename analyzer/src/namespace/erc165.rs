@@ -0,0 +1,85 @@
+//! ERC-165 interface detection.
+//!
+//! The ID of an interface is the XOR of the selectors of its functions.
+
+use crate::namespace::types::AbiEncoding;
+use crate::FunctionAttributes;
+use fe_common::utils::keccak;
+
+/// The name of the function that reports whether an interface is supported.
+pub const SUPPORTS_INTERFACE: &str = "supportsInterface";
+
+/// The ID of the ERC-165 interface, which consists of `supportsInterface`.
+pub const ERC165_INTERFACE_ID: u32 = 0x01ff_c9a7;
+
+/// An ID that no interface may have, which `supportsInterface` reports as
+/// unsupported.
+pub const INVALID_INTERFACE_ID: u32 = 0xffff_ffff;
+
+/// The 4 byte selector of a function.
+pub fn selector(function: &FunctionAttributes) -> u32 {
+    let params = function
+        .param_types
+        .iter()
+        .map(|typ| typ.abi_name())
+        .collect::<Vec<_>>();
+    let signature = format!("{}({})", function.name, params.join(","));
+
+    let hash = keccak::full_as_bytes(signature.as_bytes());
+    u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]])
+}
+
+/// The ID of the interface that consists of the given functions.
+pub fn interface_id(functions: &[FunctionAttributes]) -> u32 {
+    functions
+        .iter()
+        .fold(0, |id, function| id ^ selector(function))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::namespace::erc165::{
+        interface_id,
+        selector,
+    };
+    use crate::namespace::types::{
+        Base,
+        FixedSize,
+        U256,
+    };
+    use crate::FunctionAttributes;
+
+    fn function(name: &str, param_types: Vec<FixedSize>) -> FunctionAttributes {
+        FunctionAttributes {
+            name: name.to_string(),
+            param_types,
+            return_type: FixedSize::Base(Base::Bool),
+        }
+    }
+
+    #[test]
+    fn erc20_interface_id() {
+        let address = || FixedSize::Base(Base::Address);
+        let u256 = || FixedSize::Base(U256);
+        let erc20 = [
+            function("totalSupply", vec![]),
+            function("balanceOf", vec![address()]),
+            function("transfer", vec![address(), u256()]),
+            function("transferFrom", vec![address(), address(), u256()]),
+            function("approve", vec![address(), u256()]),
+            function("allowance", vec![address(), address()]),
+        ];
+
+        assert_eq!(interface_id(&erc20), 0x36372b07);
+    }
+
+    #[test]
+    fn transfer_selector() {
+        let transfer = function(
+            "transfer",
+            vec![FixedSize::Base(Base::Address), FixedSize::Base(U256)],
+        );
+
+        assert_eq!(selector(&transfer), 0xa9059cbb);
+    }
+}
@@ -0,0 +1,279 @@
+use crate::errors::SemanticError;
+use crate::namespace::scopes::ModuleScope;
+use crate::namespace::types::{
+    AbiEncoding,
+    FixedSize,
+    Type,
+};
+use fe_parser::ast as fe;
+use fe_parser::span::{
+    Span,
+    Spanned,
+};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+/// An owned copy of a type description that may refer to type parameters.
+///
+/// Generic definitions are instantiated long after their AST nodes have been
+/// visited, so the types in their signatures are kept around in this form.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypeTemplate {
+    Base {
+        base: String,
+    },
+    Array {
+        typ: Box<TypeTemplate>,
+        dimension: usize,
+    },
+    Map {
+        from: Box<TypeTemplate>,
+        to: Box<TypeTemplate>,
+    },
+    Tuple {
+        items: Vec<TypeTemplate>,
+    },
+    Generic {
+        base: String,
+        args: Vec<TypeTemplate>,
+    },
+}
+
+impl TypeTemplate {
+    /// Builds a type description node from the template. The node does not
+    /// have a meaningful span.
+    pub fn type_desc(&self) -> fe::TypeDesc {
+        fn spanned(template: &TypeTemplate) -> Spanned<fe::TypeDesc> {
+            Spanned {
+                node: template.type_desc(),
+                span: Span::new(0, 0),
+            }
+        }
+
+        match self {
+            TypeTemplate::Base { base } => fe::TypeDesc::Base { base },
+            TypeTemplate::Array { typ, dimension } => fe::TypeDesc::Array {
+                typ: Box::new(spanned(typ)),
+                dimension: *dimension,
+            },
+            TypeTemplate::Map { from, to } => fe::TypeDesc::Map {
+                from: Box::new(spanned(from)),
+                to: Box::new(spanned(to)),
+            },
+            TypeTemplate::Tuple { items } => fe::TypeDesc::Tuple {
+                items: items.iter().map(spanned).collect(),
+            },
+            TypeTemplate::Generic { base, args } => fe::TypeDesc::Generic {
+                base,
+                args: args.iter().map(spanned).collect(),
+            },
+        }
+    }
+}
+
+impl From<&fe::TypeDesc<'_>> for TypeTemplate {
+    fn from(typ: &fe::TypeDesc) -> Self {
+        match typ {
+            fe::TypeDesc::Base { base } => TypeTemplate::Base {
+                base: base.to_string(),
+            },
+            fe::TypeDesc::Array { typ, dimension } => TypeTemplate::Array {
+                typ: Box::new((&typ.node).into()),
+                dimension: *dimension,
+            },
+            fe::TypeDesc::Map { from, to } => TypeTemplate::Map {
+                from: Box::new((&from.node).into()),
+                to: Box::new((&to.node).into()),
+            },
+            fe::TypeDesc::Tuple { items } => TypeTemplate::Tuple {
+                items: items.iter().map(|item| (&item.node).into()).collect(),
+            },
+            fe::TypeDesc::Generic { base, args } => TypeTemplate::Generic {
+                base: base.to_string(),
+                args: args.iter().map(|arg| (&arg.node).into()).collect(),
+            },
+        }
+    }
+}
+
+/// A function with type parameters. Each distinct set of type arguments it is
+/// called with results in a separate instance of the function.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GenericFunctionDef {
+    pub type_params: Vec<String>,
+    pub params: Vec<(String, TypeTemplate)>,
+    pub return_type: Option<TypeTemplate>,
+}
+
+/// A struct with type parameters. Each distinct set of type arguments it is
+/// used with results in a separate struct type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GenericStructDef {
+    pub type_params: Vec<String>,
+    pub fields: Vec<(String, TypeTemplate)>,
+}
+
+/// The type arguments that a generic struct was instantiated with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StructInstance {
+    pub base: String,
+    pub type_args: Vec<FixedSize>,
+}
+
+/// The name of the instance of a generic function or struct for the given type
+/// arguments, e.g. `max$uint256`.
+pub fn instance_name(name: &str, type_args: &[FixedSize]) -> String {
+    let arg_names = type_args
+        .iter()
+        .map(|typ| typ.abi_safe_name())
+        .collect::<Vec<_>>();
+
+    format!("{}${}", name, arg_names.join("$"))
+}
+
+/// Infers the type arguments of a generic definition by matching the
+/// templates of its parameters against the types that they are given.
+pub fn infer_type_args<'a>(
+    module_scope: &ModuleScope,
+    type_params: &[String],
+    params: impl Iterator<Item = (&'a TypeTemplate, &'a Type)>,
+) -> Result<Vec<FixedSize>, SemanticError> {
+    let mut bindings = HashMap::new();
+    for (template, typ) in params {
+        bind_type_args(module_scope, type_params, template, typ, &mut bindings)?;
+    }
+
+    type_params
+        .iter()
+        .map(|param| {
+            bindings
+                .get(param)
+                .cloned()
+                .ok_or_else(SemanticError::cannot_infer_type_args)
+        })
+        .collect()
+}
+
+/// Binds the type parameters that occur in `template` by matching it against
+/// the type of an argument.
+fn bind_type_args(
+    module_scope: &ModuleScope,
+    type_params: &[String],
+    template: &TypeTemplate,
+    typ: &Type,
+    bindings: &mut HashMap<String, FixedSize>,
+) -> Result<(), SemanticError> {
+    match (template, typ) {
+        (TypeTemplate::Base { base }, _) if type_params.contains(base) => {
+            let typ = FixedSize::try_from(typ.to_owned())?;
+            match bindings.get(base) {
+                Some(bound) if *bound != typ => Err(SemanticError::type_error()),
+                Some(_) => Ok(()),
+                None => {
+                    bindings.insert(base.to_owned(), typ);
+                    Ok(())
+                }
+            }
+        }
+        (TypeTemplate::Array { typ: inner, .. }, Type::Array(array)) => bind_type_args(
+            module_scope,
+            type_params,
+            inner,
+            &Type::Base(array.inner.clone()),
+            bindings,
+        ),
+        (TypeTemplate::Generic { base, args }, Type::DynamicArray(array))
+            if base == "Array" && args.len() == 1 =>
+        {
+            bind_type_args(
+                module_scope,
+                type_params,
+                &args[0],
+                &Type::Base(array.inner.clone()),
+                bindings,
+            )
+        }
+        (TypeTemplate::Tuple { items }, Type::Tuple(tuple)) if items.len() == tuple.items.len() => {
+            items
+                .iter()
+                .zip(tuple.items.iter())
+                .try_for_each(|(item, typ)| {
+                    bind_type_args(
+                        module_scope,
+                        type_params,
+                        item,
+                        &Type::Base(typ.clone()),
+                        bindings,
+                    )
+                })
+        }
+        (TypeTemplate::Generic { base, args }, Type::Struct(val)) => {
+            if let Some(instance) = module_scope.get_struct_instance(&val.name) {
+                if instance.base == *base && instance.type_args.len() == args.len() {
+                    for (arg, typ) in args.iter().zip(instance.type_args.into_iter()) {
+                        bind_type_args(module_scope, type_params, arg, &typ.into(), bindings)?;
+                    }
+                }
+            }
+
+            Ok(())
+        }
+        // Any other mismatch is reported when the arguments are checked
+        // against the parameters of the instance.
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::namespace::generics::{
+        instance_name,
+        TypeTemplate,
+    };
+    use crate::namespace::types::{
+        Base,
+        FixedSize,
+        U256,
+    };
+    use fe_parser as parser;
+
+    #[test]
+    fn template_round_trip() {
+        let tokens = parser::get_parse_tokens("Pair<T, u8>").expect("Couldn't parse type");
+        let typ = parser::parsers::type_desc(&tokens[..])
+            .expect("Couldn't build type AST")
+            .1;
+
+        let template = TypeTemplate::from(&typ.node);
+        assert_eq!(
+            template,
+            TypeTemplate::Generic {
+                base: "Pair".to_string(),
+                args: vec![
+                    TypeTemplate::Base {
+                        base: "T".to_string()
+                    },
+                    TypeTemplate::Base {
+                        base: "u8".to_string()
+                    },
+                ],
+            }
+        );
+        assert_eq!(TypeTemplate::from(&template.type_desc()), template);
+    }
+
+    #[test]
+    fn instance_names() {
+        assert_eq!(
+            instance_name("max", &[FixedSize::Base(U256)]),
+            "max$uint256"
+        );
+        assert_eq!(
+            instance_name(
+                "Pair",
+                &[FixedSize::Base(Base::Address), FixedSize::Base(Base::Bool)]
+            ),
+            "Pair$address$bool"
+        );
+    }
+}
@@ -1,4 +1,11 @@
+pub mod constants;
+pub mod custom_errors;
+pub mod eip712;
+pub mod erc165;
 pub mod events;
+pub mod generics;
 pub mod operations;
+pub mod proxy;
 pub mod scopes;
+pub mod traits;
 pub mod types;
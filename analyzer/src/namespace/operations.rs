@@ -1,6 +1,7 @@
 use crate::errors::SemanticError;
 use crate::namespace::types::{
     Array,
+    DynamicArray,
     Map,
     Type,
     U256,
@@ -12,6 +13,7 @@ use crate::namespace::types::{
 pub fn index(value: Type, index: Type) -> Result<Type, SemanticError> {
     match value {
         Type::Array(array) => index_array(array, index),
+        Type::DynamicArray(array) => index_dynamic_array(array, index),
         Type::Map(map) => index_map(map, index),
         Type::Base(_) => Err(SemanticError::not_subscriptable()),
         Type::Tuple(_) => Err(SemanticError::not_subscriptable()),
@@ -29,6 +31,14 @@ fn index_array(array: Array, index: Type) -> Result<Type, SemanticError> {
     Ok(Type::Base(array.inner))
 }
 
+fn index_dynamic_array(array: DynamicArray, index: Type) -> Result<Type, SemanticError> {
+    if index != Type::Base(U256) {
+        return Err(SemanticError::type_error());
+    }
+
+    Ok(Type::Base(array.inner))
+}
+
 fn index_map(map: Map, index: Type) -> Result<Type, SemanticError> {
     if index != Type::Base(map.key) {
         return Err(SemanticError::type_error());
@@ -44,6 +54,7 @@ mod tests {
     use crate::namespace::types::{
         Array,
         Base,
+        DynamicArray,
         Map,
         Type,
         U256,
@@ -54,6 +65,7 @@ mod tests {
         inner: U256,
         size: 100,
     });
+    const U256_DYNAMIC_ARRAY_TYPE: Type = Type::DynamicArray(DynamicArray { inner: U256 });
     const U256_TYPE: Type = Type::Base(U256);
     const BOOL_TYPE: Type = Type::Base(Base::Bool);
 
@@ -69,6 +81,7 @@ mod tests {
         index,
         expected,
         case(U256_ARRAY_TYPE, U256_TYPE, U256_TYPE),
+        case(U256_DYNAMIC_ARRAY_TYPE, U256_TYPE, U256_TYPE),
         case(u256_bool_map(), U256_TYPE, BOOL_TYPE)
     )]
     fn basic_index(value: Type, index: Type, expected: Type) {
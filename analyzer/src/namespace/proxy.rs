@@ -0,0 +1,62 @@
+//! Contracts that are marked with `@upgradeable`, which are deployed as the
+//! implementation behind a proxy.
+//!
+//! The proxy keeps the state, so a new version of an implementation has to
+//! keep the storage layout of the previous one. Storage gaps reserve nonces
+//! for fields that are added later.
+
+use crate::namespace::types::{
+    Array,
+    Type,
+    U256,
+};
+use fe_common::utils::keccak;
+
+/// The prefix of the names of storage gaps.
+pub const STORAGE_GAP_PREFIX: &str = "__gap";
+
+/// The storage slot of the flag that is set once the initializer has run, as
+/// a hex string. It is a hash, so it doesn't collide with the slots of fields.
+pub fn initialized_slot() -> String {
+    keccak::full(b"fe.proxy.initialized")
+}
+
+/// Whether a field with the given name is a storage gap.
+pub fn is_storage_gap(name: &str) -> bool {
+    name.starts_with(STORAGE_GAP_PREFIX)
+}
+
+/// The number of storage nonces that a storage gap of the given type
+/// reserves, which is one per item. Storage gaps have to be arrays of `u256`.
+pub fn storage_gap_size(typ: &Type) -> Option<usize> {
+    match typ {
+        Type::Array(Array { size, inner }) if *inner == U256 => Some(*size),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::namespace::proxy::{
+        is_storage_gap,
+        storage_gap_size,
+    };
+    use crate::namespace::types::{
+        Array,
+        Base,
+        Type,
+        U256,
+    };
+
+    #[test]
+    fn storage_gaps() {
+        assert!(is_storage_gap("__gap"));
+        assert!(is_storage_gap("__gap_v2"));
+        assert!(!is_storage_gap("gap"));
+
+        let gap = |inner| Type::Array(Array { size: 50, inner });
+        assert_eq!(storage_gap_size(&gap(U256)), Some(50));
+        assert_eq!(storage_gap_size(&gap(Base::Address)), None);
+        assert_eq!(storage_gap_size(&Type::Base(U256)), None);
+    }
+}
@@ -1,12 +1,22 @@
 use crate::errors::SemanticError;
+use crate::namespace::constants::Constant;
+use crate::namespace::custom_errors::CustomError;
 use crate::namespace::events::Event;
+use crate::namespace::generics::{
+    GenericFunctionDef,
+    GenericStructDef,
+    StructInstance,
+};
+use crate::namespace::traits::Trait;
 use crate::namespace::types::{
     FixedSize,
+    Tuple,
     Type,
 };
 use std::cell::RefCell;
 use std::collections::hash_map::Entry;
 use std::collections::{
+    BTreeSet,
     HashMap,
     HashSet,
 };
@@ -17,11 +27,19 @@ pub type Shared<T> = Rc<RefCell<T>>;
 #[derive(Clone, Debug, PartialEq)]
 pub struct ContractFunctionDef {
     pub is_public: bool,
+    pub is_payable: bool,
     pub param_types: Vec<FixedSize>,
     pub return_type: FixedSize,
     pub scope: Shared<BlockScope>,
 }
 
+/// A function that wraps the bodies of the functions it decorates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecoratorDef {
+    pub param_types: Vec<FixedSize>,
+    pub scope: Shared<BlockScope>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct ContractFieldDef {
     pub nonce: usize,
@@ -31,6 +49,14 @@ pub struct ContractFieldDef {
 #[derive(Clone, Debug, PartialEq)]
 pub struct ModuleScope {
     pub type_defs: HashMap<String, Type>,
+    pub generic_struct_defs: HashMap<String, GenericStructDef>,
+    pub struct_instances: HashMap<String, StructInstance>,
+    pub trait_defs: HashMap<String, Trait>,
+    pub constant_defs: HashMap<String, Constant>,
+    /// Names of the functions defined outside of any contract.
+    pub free_function_names: HashSet<String>,
+    /// Names of the libraries defined in the module.
+    pub library_names: HashSet<String>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -38,10 +64,28 @@ pub struct ContractScope {
     pub parent: Shared<ModuleScope>,
     pub interface: Vec<String>,
     pub event_defs: HashMap<String, Event>,
+    pub error_defs: HashMap<String, CustomError>,
+    pub constant_defs: HashMap<String, Constant>,
     pub field_defs: HashMap<String, ContractFieldDef>,
     pub function_defs: HashMap<String, ContractFunctionDef>,
+    pub generic_function_defs: HashMap<String, GenericFunctionDef>,
+    pub decorator_defs: HashMap<String, DecoratorDef>,
+    /// Instances of generic functions whose bodies have not been analyzed
+    /// yet, as pairs of generic function name and instance name.
+    pub pending_instances: Vec<(String, String)>,
     pub string_defs: HashSet<String>,
+    pub tuple_defs: BTreeSet<Tuple>,
     pub created_contracts: HashSet<String>,
+    /// Names of the functions defined outside of any contract that are called
+    /// within this contract.
+    pub free_function_calls: HashSet<String>,
+    /// Names of the libraries whose functions are called within this
+    /// contract.
+    pub library_calls: HashSet<String>,
+    /// Types of the values that are encoded with `abi_encode`.
+    pub abi_encodings: BTreeSet<FixedSize>,
+    /// Types of the values that are decoded from memory with `abi_decode`.
+    pub abi_decodings: BTreeSet<FixedSize>,
     num_fields: usize,
 }
 
@@ -50,6 +94,18 @@ pub struct BlockScope {
     pub name: String,
     pub parent: BlockScopeParent,
     pub variable_defs: HashMap<String, FixedSize>,
+    /// Names of the variables that refer to a value in storage instead of
+    /// holding a copy of it.
+    pub storage_refs: HashSet<String>,
+    /// Types that the type parameters of a generic function instance stand
+    /// for. Only set on function scopes.
+    pub type_args: HashMap<String, Type>,
+    /// Whether raw calls are allowed. Only set on the function scopes of
+    /// functions marked `@unsafe`.
+    pub is_unsafe: bool,
+    /// How much the function may access the contract state. Only restricted
+    /// on the function scopes of functions marked `@pure` or `@view`.
+    pub mutability: Mutability,
     pub typ: BlockScopeType,
 }
 
@@ -66,6 +122,18 @@ pub enum BlockScopeParent {
     Block(Shared<BlockScope>),
 }
 
+/// The access to the contract state that a function is allowed, ordered from
+/// the most to the least restrictive.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Mutability {
+    /// Neither reads nor writes the state.
+    Pure,
+    /// Reads but doesn't write the state.
+    View,
+    /// Reads and writes the state.
+    Mutable,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum BlockScopeType {
     Function,
@@ -81,12 +149,27 @@ impl Scope {
             Scope::Block(scope) => Rc::clone(&scope.borrow().contract_scope().borrow().parent),
         }
     }
+
+    /// Lookup a constant definition that is visible from the scope.
+    pub fn constant_def(&self, name: &str) -> Option<Constant> {
+        match self {
+            Scope::Module(scope) => scope.borrow().constant_def(name),
+            Scope::Contract(scope) => scope.borrow().constant_def(name),
+            Scope::Block(scope) => scope.borrow().contract_constant_def(name),
+        }
+    }
 }
 
 impl ModuleScope {
     pub fn new() -> Shared<Self> {
         Rc::new(RefCell::new(ModuleScope {
             type_defs: HashMap::new(),
+            generic_struct_defs: HashMap::new(),
+            struct_instances: HashMap::new(),
+            trait_defs: HashMap::new(),
+            constant_defs: HashMap::new(),
+            free_function_names: HashSet::new(),
+            library_names: HashSet::new(),
         }))
     }
 
@@ -104,6 +187,105 @@ impl ModuleScope {
     pub fn get_type_def(&self, name: &str) -> Option<Type> {
         self.type_defs.get(name).map(|typ| typ.to_owned())
     }
+
+    /// Add a trait definition to the scope.
+    pub fn add_trait_def(&mut self, name: &str, def: Trait) -> Result<(), SemanticError> {
+        if self.type_defs.contains_key(name) {
+            return Err(SemanticError::already_defined());
+        }
+
+        match self.trait_defs.entry(name.to_owned()) {
+            Entry::Occupied(_) => Err(SemanticError::already_defined()),
+            Entry::Vacant(e) => {
+                e.insert(def);
+                Ok(())
+            }
+        }
+    }
+
+    /// Add a constant definition to the scope.
+    pub fn add_constant(&mut self, name: &str, constant: Constant) -> Result<(), SemanticError> {
+        match self.constant_defs.entry(name.to_owned()) {
+            Entry::Occupied(_) => Err(SemanticError::already_defined()),
+            Entry::Vacant(e) => {
+                e.insert(constant);
+                Ok(())
+            }
+        }
+    }
+
+    /// Lookup a constant definition by its name.
+    pub fn constant_def(&self, name: &str) -> Option<Constant> {
+        self.constant_defs.get(name).map(|def| def.to_owned())
+    }
+
+    /// Add the name of a function defined outside of any contract.
+    pub fn add_free_function(&mut self, name: &str) -> Result<(), SemanticError> {
+        if !self.free_function_names.insert(name.to_owned()) {
+            return Err(SemanticError::already_defined());
+        }
+
+        Ok(())
+    }
+
+    /// Add the name of a library. Libraries are also contract types, which
+    /// are added once their definitions have been analyzed.
+    pub fn add_library(&mut self, name: &str) -> Result<(), SemanticError> {
+        if self.type_defs.contains_key(name) || !self.library_names.insert(name.to_owned()) {
+            return Err(SemanticError::already_defined());
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if a library with the given name has been defined.
+    pub fn is_library(&self, name: &str) -> bool {
+        self.library_names.contains(name)
+    }
+
+    /// Gets a trait definition by name.
+    pub fn get_trait_def(&self, name: &str) -> Option<Trait> {
+        self.trait_defs.get(name).map(|def| def.to_owned())
+    }
+
+    /// Add a generic struct definition to the scope.
+    pub fn add_generic_struct_def(
+        &mut self,
+        name: &str,
+        def: GenericStructDef,
+    ) -> Result<(), SemanticError> {
+        if self.type_defs.contains_key(name) {
+            return Err(SemanticError::already_defined());
+        }
+
+        match self.generic_struct_defs.entry(name.to_owned()) {
+            Entry::Occupied(_) => Err(SemanticError::already_defined()),
+            Entry::Vacant(e) => {
+                e.insert(def);
+                Ok(())
+            }
+        }
+    }
+
+    /// Gets a generic struct definition by name.
+    pub fn get_generic_struct_def(&self, name: &str) -> Option<GenericStructDef> {
+        self.generic_struct_defs.get(name).map(|def| def.to_owned())
+    }
+
+    /// Add an instance of a generic struct to the scope. The instance is also
+    /// added as a regular type definition.
+    pub fn add_struct_instance(&mut self, name: &str, typ: Type, instance: StructInstance) {
+        self.add_type_def(name, typ);
+        self.struct_instances.insert(name.to_owned(), instance);
+    }
+
+    /// Gets the type arguments of a generic struct instance by the name of the
+    /// instance.
+    pub fn get_struct_instance(&self, name: &str) -> Option<StructInstance> {
+        self.struct_instances
+            .get(name)
+            .map(|instance| instance.to_owned())
+    }
 }
 
 impl ContractScope {
@@ -111,11 +293,21 @@ impl ContractScope {
         Rc::new(RefCell::new(ContractScope {
             parent,
             function_defs: HashMap::new(),
+            generic_function_defs: HashMap::new(),
+            decorator_defs: HashMap::new(),
+            pending_instances: vec![],
             event_defs: HashMap::new(),
+            error_defs: HashMap::new(),
+            constant_defs: HashMap::new(),
             field_defs: HashMap::new(),
             string_defs: HashSet::new(),
+            tuple_defs: BTreeSet::new(),
             interface: vec![],
             created_contracts: HashSet::new(),
+            free_function_calls: HashSet::new(),
+            library_calls: HashSet::new(),
+            abi_encodings: BTreeSet::new(),
+            abi_decodings: BTreeSet::new(),
             num_fields: 0,
         }))
     }
@@ -135,6 +327,20 @@ impl ContractScope {
         self.event_defs.get(name).map(|def| (*def).clone())
     }
 
+    /// Lookup contract error definition by its name.
+    pub fn error_def(&self, name: &str) -> Option<CustomError> {
+        self.error_defs.get(name).map(|def| (*def).clone())
+    }
+
+    /// Lookup a constant definition by its name. Constants of the contract
+    /// take precedence over constants of the module.
+    pub fn constant_def(&self, name: &str) -> Option<Constant> {
+        self.constant_defs
+            .get(name)
+            .map(|def| (*def).clone())
+            .or_else(|| self.module_scope().borrow().constant_def(name))
+    }
+
     /// Lookup contract field definition by its name.
     pub fn field_def(&self, name: &str) -> Option<ContractFieldDef> {
         self.field_defs.get(name).map(|def| (*def).clone())
@@ -145,6 +351,18 @@ impl ContractScope {
         self.function_defs.get(name).map(|def| (*def).clone())
     }
 
+    /// Lookup generic contract function definition by its name.
+    pub fn generic_function_def(&self, name: &str) -> Option<GenericFunctionDef> {
+        self.generic_function_defs
+            .get(name)
+            .map(|def| (*def).clone())
+    }
+
+    /// Lookup a decorator definition by its name.
+    pub fn decorator_def(&self, name: &str) -> Option<DecoratorDef> {
+        self.decorator_defs.get(name).map(|def| (*def).clone())
+    }
+
     /// Add a contract field definition to the scope.
     pub fn add_field(&mut self, name: &str, typ: Type) -> Result<(), SemanticError> {
         match self.field_defs.entry(name.to_owned()) {
@@ -160,20 +378,32 @@ impl ContractScope {
         }
     }
 
+    /// Skip the given number of storage nonces, so that the next field is
+    /// stored after them.
+    pub fn reserve_nonces(&mut self, count: usize) {
+        self.num_fields += count;
+    }
+
     /// Add a function definition to the scope.
     pub fn add_function(
         &mut self,
         name: &str,
         is_public: bool,
+        is_payable: bool,
         param_types: Vec<FixedSize>,
         return_type: FixedSize,
         scope: Shared<BlockScope>,
     ) -> Result<(), SemanticError> {
+        if self.generic_function_defs.contains_key(name) || self.decorator_defs.contains_key(name) {
+            return Err(SemanticError::already_defined());
+        }
+
         match self.function_defs.entry(name.to_owned()) {
             Entry::Occupied(_) => Err(SemanticError::already_defined()),
             Entry::Vacant(e) => {
                 e.insert(ContractFunctionDef {
                     is_public,
+                    is_payable,
                     param_types,
                     return_type,
                     scope,
@@ -183,6 +413,62 @@ impl ContractScope {
         }
     }
 
+    /// Add a generic function definition to the scope.
+    pub fn add_generic_function(
+        &mut self,
+        name: &str,
+        def: GenericFunctionDef,
+    ) -> Result<(), SemanticError> {
+        if self.function_defs.contains_key(name) || self.decorator_defs.contains_key(name) {
+            return Err(SemanticError::already_defined());
+        }
+
+        match self.generic_function_defs.entry(name.to_owned()) {
+            Entry::Occupied(_) => Err(SemanticError::already_defined()),
+            Entry::Vacant(e) => {
+                e.insert(def);
+                Ok(())
+            }
+        }
+    }
+
+    /// Add a decorator definition to the scope.
+    pub fn add_decorator(
+        &mut self,
+        name: &str,
+        param_types: Vec<FixedSize>,
+        scope: Shared<BlockScope>,
+    ) -> Result<(), SemanticError> {
+        if self.function_defs.contains_key(name) || self.generic_function_defs.contains_key(name) {
+            return Err(SemanticError::already_defined());
+        }
+
+        match self.decorator_defs.entry(name.to_owned()) {
+            Entry::Occupied(_) => Err(SemanticError::already_defined()),
+            Entry::Vacant(e) => {
+                e.insert(DecoratorDef { param_types, scope });
+                Ok(())
+            }
+        }
+    }
+
+    /// Add an instance of a generic function whose body still needs to be
+    /// analyzed.
+    pub fn add_pending_instance(&mut self, generic_name: &str, instance_name: &str) {
+        self.pending_instances
+            .push((generic_name.to_owned(), instance_name.to_owned()));
+    }
+
+    /// Remove and return the generic function instance that was added first
+    /// among those whose bodies have not been analyzed yet.
+    pub fn take_pending_instance(&mut self) -> Option<(String, String)> {
+        if self.pending_instances.is_empty() {
+            None
+        } else {
+            Some(self.pending_instances.remove(0))
+        }
+    }
+
     /// Add an event definition to the scope.
     pub fn add_event(&mut self, name: &str, event: Event) -> Result<(), SemanticError> {
         match self.event_defs.entry(name.to_owned()) {
@@ -194,17 +480,66 @@ impl ContractScope {
         }
     }
 
+    /// Add a custom error definition to the scope.
+    pub fn add_error(&mut self, name: &str, error: CustomError) -> Result<(), SemanticError> {
+        match self.error_defs.entry(name.to_owned()) {
+            Entry::Occupied(_) => Err(SemanticError::already_defined()),
+            Entry::Vacant(e) => {
+                e.insert(error);
+                Ok(())
+            }
+        }
+    }
+
+    /// Add a constant definition to the scope.
+    pub fn add_constant(&mut self, name: &str, constant: Constant) -> Result<(), SemanticError> {
+        match self.constant_defs.entry(name.to_owned()) {
+            Entry::Occupied(_) => Err(SemanticError::already_defined()),
+            Entry::Vacant(e) => {
+                e.insert(constant);
+                Ok(())
+            }
+        }
+    }
+
     /// Add a static string definition to the scope.
     pub fn add_string(&mut self, value: &str) -> Result<(), SemanticError> {
         self.string_defs.insert(value.to_owned());
         Ok(())
     }
 
+    /// Add a tuple type that is constructed or destructured in the contract.
+    pub fn add_tuple(&mut self, typ: &Tuple) {
+        self.tuple_defs.insert(typ.to_owned());
+    }
+
+    /// Add the type of a value that is encoded with `abi_encode`.
+    pub fn add_abi_encoding(&mut self, typ: FixedSize) {
+        self.abi_encodings.insert(typ);
+    }
+
+    /// Add the type of a value that is decoded from memory with `abi_decode`.
+    pub fn add_abi_decoding(&mut self, typ: FixedSize) {
+        self.abi_decodings.insert(typ);
+    }
+
     /// Add the name of another contract that has been created within this
     /// contract.
     pub fn add_created_contract(&mut self, name: &str) {
         self.created_contracts.insert(name.to_owned());
     }
+
+    /// Add the name of a function defined outside of any contract that is
+    /// called within this contract.
+    pub fn add_free_function_call(&mut self, name: &str) {
+        self.free_function_calls.insert(name.to_owned());
+    }
+
+    /// Add the name of a library whose functions are called within this
+    /// contract.
+    pub fn add_library_call(&mut self, name: &str) {
+        self.library_calls.insert(name.to_owned());
+    }
 }
 
 impl BlockScope {
@@ -213,6 +548,10 @@ impl BlockScope {
             name: name.to_owned(),
             parent,
             variable_defs: HashMap::new(),
+            storage_refs: HashSet::new(),
+            type_args: HashMap::new(),
+            is_unsafe: false,
+            mutability: Mutability::Mutable,
             typ,
         }))
     }
@@ -265,11 +604,31 @@ impl BlockScope {
         function_scope
     }
 
+    /// Checks that the function containing the block is allowed to access the
+    /// contract state in the given way.
+    pub fn validate_mutability(&self, required: Mutability) -> Result<(), SemanticError> {
+        if required > self.function_scope().borrow().mutability {
+            Err(SemanticError::mutability_violation())
+        } else {
+            Ok(())
+        }
+    }
+
     /// Lookup an event definition on the inherited contract scope
     pub fn contract_event_def(&self, name: &str) -> Option<Event> {
         self.contract_scope().borrow().event_def(name)
     }
 
+    /// Lookup a custom error definition on the inherited contract scope
+    pub fn contract_error_def(&self, name: &str) -> Option<CustomError> {
+        self.contract_scope().borrow().error_def(name)
+    }
+
+    /// Lookup a constant definition on the inherited contract scope
+    pub fn contract_constant_def(&self, name: &str) -> Option<Constant> {
+        self.contract_scope().borrow().constant_def(name)
+    }
+
     /// Lookup a field definition on the inherited contract scope
     pub fn contract_field_def(&self, name: &str) -> Option<ContractFieldDef> {
         self.contract_scope().borrow().field_def(name)
@@ -286,6 +645,12 @@ impl BlockScope {
         self.contract_function_def(&self.function_scope().borrow().name)
     }
 
+    /// Return the type arguments of the function that the block scope belongs
+    /// to.
+    pub fn type_args(&self) -> HashMap<String, Type> {
+        self.function_scope().borrow().type_args.clone()
+    }
+
     /// Lookup a definition in current or inherited block scope
     pub fn get_variable_def(&self, name: &str) -> Option<FixedSize> {
         let block_def = self.variable_defs.get(name).map(|def| (*def).clone());
@@ -300,6 +665,23 @@ impl BlockScope {
         }
     }
 
+    /// Returns the names of all variables and constants that are visible in
+    /// the block scope.
+    pub fn visible_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.variable_defs.keys().cloned().collect();
+
+        match &self.parent {
+            BlockScopeParent::Block(scope) => names.extend(scope.borrow().visible_names()),
+            BlockScopeParent::Contract(scope) => {
+                let scope = scope.borrow();
+                names.extend(scope.constant_defs.keys().cloned());
+                names.extend(scope.module_scope().borrow().constant_defs.keys().cloned());
+            }
+        }
+
+        names
+    }
+
     /// Add a variable to the block scope.
     pub fn add_var(&mut self, name: &str, typ: FixedSize) -> Result<(), SemanticError> {
         match self.variable_defs.entry(name.to_owned()) {
@@ -311,6 +693,25 @@ impl BlockScope {
         }
     }
 
+    /// Add a variable that refers to a value in storage to the block scope.
+    pub fn add_storage_ref(&mut self, name: &str, typ: FixedSize) -> Result<(), SemanticError> {
+        self.add_var(name, typ)?;
+        self.storage_refs.insert(name.to_owned());
+        Ok(())
+    }
+
+    /// Return true if the variable refers to a value in storage. Variables of
+    /// inner scopes shadow those of their parents.
+    pub fn is_storage_ref(&self, name: &str) -> bool {
+        if self.variable_defs.contains_key(name) {
+            self.storage_refs.contains(name)
+        } else if let BlockScopeParent::Block(scope) = &self.parent {
+            scope.borrow().is_storage_ref(name)
+        } else {
+            false
+        }
+    }
+
     /// Return true if the scope or any of its parents is of the given type
     pub fn inherits_type(&self, typ: BlockScopeType) -> bool {
         if self.typ != typ {
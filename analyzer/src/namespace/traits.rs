@@ -0,0 +1,62 @@
+use crate::FunctionAttributes;
+
+/// A set of public functions that contracts can declare to implement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trait {
+    pub name: String,
+    pub functions: Vec<FunctionAttributes>,
+}
+
+impl Trait {
+    pub fn new(name: &str, functions: Vec<FunctionAttributes>) -> Self {
+        Self {
+            name: name.to_owned(),
+            functions,
+        }
+    }
+
+    /// The trait's functions that are not found with the same signature in
+    /// `functions`.
+    pub fn missing_functions(&self, functions: &[FunctionAttributes]) -> Vec<&FunctionAttributes> {
+        self.functions
+            .iter()
+            .filter(|function| !functions.contains(function))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::namespace::traits::Trait;
+    use crate::namespace::types::{
+        Base,
+        FixedSize,
+        U256,
+    };
+    use crate::FunctionAttributes;
+
+    fn balance_of(return_type: FixedSize) -> FunctionAttributes {
+        FunctionAttributes {
+            name: "balance_of".to_string(),
+            param_types: vec![FixedSize::Base(Base::Address)],
+            return_type,
+        }
+    }
+
+    #[test]
+    fn missing_functions() {
+        let token = Trait::new("Token", vec![balance_of(FixedSize::Base(U256))]);
+
+        assert!(token
+            .missing_functions(&[balance_of(FixedSize::Base(U256))])
+            .is_empty());
+        assert_eq!(
+            token.missing_functions(&[balance_of(FixedSize::Base(Base::Bool))]),
+            vec![&balance_of(FixedSize::Base(U256))]
+        );
+        assert_eq!(
+            token.missing_functions(&[]),
+            vec![&balance_of(FixedSize::Base(U256))]
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::errors::SemanticError;
+use crate::namespace::generics::instance_name;
 use fe_parser::ast as fe;
 use std::collections::{
     BTreeMap,
@@ -99,12 +100,29 @@ pub trait AbiEncoding {
 
     /// The ABI type of a Fe type.
     fn abi_type(&self) -> AbiType;
+
+    /// An exclusive upper bound for valid values of the type, if it is more
+    /// restrictive than the bound given by the type's size.
+    ///
+    /// Values decoded from external input are checked against this bound.
+    fn abi_upper_bound(&self) -> Option<usize> {
+        None
+    }
+
+    /// The size in bytes of a signed type that is narrower than a word.
+    ///
+    /// Values decoded from external input must be sign extended from this
+    /// size.
+    fn abi_signed_size(&self) -> Option<usize> {
+        None
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Type {
     Base(Base),
     Array(Array),
+    DynamicArray(DynamicArray),
     Map(Map),
     Tuple(Tuple),
     String(FeString),
@@ -116,6 +134,7 @@ pub enum Type {
 pub enum FixedSize {
     Base(Base),
     Array(Array),
+    DynamicArray(DynamicArray),
     Tuple(Tuple),
     String(FeString),
     Contract(Contract),
@@ -128,6 +147,9 @@ pub enum Base {
     Bool,
     Byte,
     Address,
+    Enum(Enum),
+    Fixed(FixedPoint),
+    Named(NamedInteger),
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, PartialOrd, Ord, Eq, IntoStaticStr)]
@@ -148,12 +170,42 @@ pub enum Integer {
 
 pub const U256: Base = Base::Numeric(Integer::U256);
 
+/// An integer type declared with an alias such as `type Wad = u256`.
+///
+/// Values are represented by the underlying integer type, but they can only be
+/// combined with values of the same named type.
+#[derive(Clone, Debug, Hash, PartialEq, PartialOrd, Ord, Eq)]
+pub struct NamedInteger {
+    pub name: String,
+    pub integer: Integer,
+}
+
+/// A decimal number with a fixed number of fractional digits.
+///
+/// Values are represented by the underlying integer type, scaled by
+/// `10 ** decimals`.
+#[derive(Clone, Debug, Hash, PartialEq, PartialOrd, Ord, Eq)]
+pub struct FixedPoint {
+    pub integer: Integer,
+    pub decimals: usize,
+}
+
 #[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq)]
 pub struct Array {
     pub size: usize,
     pub inner: Base,
 }
 
+/// An array whose length is only known at runtime.
+///
+/// In storage the length is kept at the array's address and the elements are
+/// packed starting at the hash of that address. In memory the length is
+/// followed by the packed elements.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq)]
+pub struct DynamicArray {
+    pub inner: Base,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Map {
     pub key: Base,
@@ -170,6 +222,13 @@ pub struct Struct {
     pub name: String,
     fields: BTreeMap<String, FixedSize>,
     order: Vec<String>,
+    eip712: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq)]
+pub struct Enum {
+    pub name: String,
+    pub variants: Vec<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq)]
@@ -181,6 +240,7 @@ pub struct FeString {
 pub struct Contract {
     pub name: String,
     pub functions: Vec<FunctionAttributes>,
+    pub init_params: Vec<FixedSize>,
 }
 
 impl Struct {
@@ -189,9 +249,20 @@ impl Struct {
             name: name.to_string(),
             fields: BTreeMap::new(),
             order: vec![],
+            eip712: false,
         }
     }
 
+    /// Mark the struct as EIP-712 typed data, which can be hashed for signing
+    pub fn set_eip712(&mut self) {
+        self.eip712 = true;
+    }
+
+    /// Return `true` if the struct is marked with `@eip712`
+    pub fn is_eip712(&self) -> bool {
+        self.eip712
+    }
+
     /// Return `true` if the struct has any fields, otherwise return `false`
     pub fn is_empty(&self) -> bool {
         self.fields.is_empty()
@@ -235,6 +306,30 @@ impl Struct {
     }
 }
 
+impl Enum {
+    pub fn new(name: &str) -> Enum {
+        Enum {
+            name: name.to_string(),
+            variants: vec![],
+        }
+    }
+
+    /// Add a variant to the enum. Returns `false` if the variant already exists.
+    pub fn add_variant(&mut self, name: &str) -> bool {
+        if self.get_variant_index(name).is_some() {
+            return false;
+        }
+
+        self.variants.push(name.to_string());
+        true
+    }
+
+    /// Return the index of the given variant, which is also its value
+    pub fn get_variant_index(&self, name: &str) -> Option<usize> {
+        self.variants.iter().position(|variant| variant == name)
+    }
+}
+
 impl TryFrom<&str> for FeString {
     type Error = String;
 
@@ -249,6 +344,98 @@ impl TryFrom<&str> for FeString {
     }
 }
 
+impl TryFrom<&str> for FixedPoint {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (signed, dimensions) = if let Some(rest) = value.strip_prefix("ufixed") {
+            (false, rest)
+        } else if let Some(rest) = value.strip_prefix("fixed") {
+            (true, rest)
+        } else {
+            return Err("Value must start with 'ufixed' or 'fixed'".to_string());
+        };
+
+        let (bits, decimals) = match dimensions.split_once('x') {
+            Some((bits, decimals)) => (
+                bits.parse::<usize>().map_err(|err| err.to_string())?,
+                decimals.parse::<usize>().map_err(|err| err.to_string())?,
+            ),
+            None => return Err("Value must be of the form '<bits>x<decimals>'".to_string()),
+        };
+
+        let integer = match (signed, bits) {
+            (false, 256) => Integer::U256,
+            (false, 128) => Integer::U128,
+            (false, 64) => Integer::U64,
+            (false, 32) => Integer::U32,
+            (false, 16) => Integer::U16,
+            (false, 8) => Integer::U8,
+            (true, 256) => Integer::I256,
+            (true, 128) => Integer::I128,
+            (true, 64) => Integer::I64,
+            (true, 32) => Integer::I32,
+            (true, 16) => Integer::I16,
+            (true, 8) => Integer::I8,
+            _ => return Err(format!("Unsupported number of bits: {}", bits)),
+        };
+
+        if decimals == 0 || decimals > 80 {
+            return Err(format!("Unsupported number of decimals: {}", decimals));
+        }
+
+        Ok(FixedPoint { integer, decimals })
+    }
+}
+
+impl FixedPoint {
+    /// The name of the type, e.g. `ufixed128x18`.
+    pub fn name(&self) -> String {
+        format!(
+            "{}fixed{}x{}",
+            if self.integer.is_signed() { "" } else { "u" },
+            self.integer.size() * 8,
+            self.decimals
+        )
+    }
+
+    /// The factor by which values are scaled, `10 ** decimals`.
+    pub fn scale(&self) -> BigInt {
+        BigInt::from(10).pow(self.decimals as u32)
+    }
+
+    /// Converts a decimal literal such as `1.25` into its scaled integer
+    /// representation.
+    ///
+    /// Fails if the literal has more fractional digits than the type or if the
+    /// scaled value does not fit the underlying integer.
+    pub fn scale_literal(&self, num: &str) -> Result<BigInt, SemanticError> {
+        let (negative, num) = match num.strip_prefix('-') {
+            Some(num) => (true, num),
+            None => (false, num),
+        };
+        let (whole, fraction) = num.split_once('.').unwrap_or((num, ""));
+        let (whole, fraction) = (whole.replace('_', ""), fraction.replace('_', ""));
+
+        if fraction.len() > self.decimals {
+            return Err(SemanticError::numeric_capacity_mismatch());
+        }
+
+        let digits = format!("{}{:0<width$}", whole, fraction, width = self.decimals);
+        let mut value = BigInt::parse_bytes(digits.as_bytes(), 10)
+            .ok_or_else(SemanticError::numeric_literal_expected)?;
+        if negative {
+            value = -value;
+        }
+
+        if !self.integer.fits(&value.to_string()) {
+            return Err(SemanticError::numeric_capacity_mismatch());
+        }
+
+        Ok(value)
+    }
+}
+
 impl Integer {
     pub fn is_signed(&self) -> bool {
         matches!(
@@ -262,6 +449,50 @@ impl Integer {
         )
     }
 
+    /// The smallest value of the integer type.
+    pub fn min_value(&self) -> BigInt {
+        if self.is_signed() {
+            -BigInt::from(2).pow(self.size() as u32 * 8 - 1)
+        } else {
+            BigInt::from(0)
+        }
+    }
+
+    /// The largest value of the integer type.
+    pub fn max_value(&self) -> BigInt {
+        if self.is_signed() {
+            BigInt::from(2).pow(self.size() as u32 * 8 - 1) - 1
+        } else {
+            BigInt::from(2).pow(self.size() as u32 * 8) - 1
+        }
+    }
+
+    /// The smallest integer type with the same signedness that can hold the
+    /// number, if there is one.
+    pub fn smallest_fitting(&self, num: &str) -> Option<Integer> {
+        let candidates = if self.is_signed() {
+            [
+                Integer::I8,
+                Integer::I16,
+                Integer::I32,
+                Integer::I64,
+                Integer::I128,
+                Integer::I256,
+            ]
+        } else {
+            [
+                Integer::U8,
+                Integer::U16,
+                Integer::U32,
+                Integer::U64,
+                Integer::U128,
+                Integer::U256,
+            ]
+        };
+
+        candidates.iter().find(|integer| integer.fits(num)).cloned()
+    }
+
     pub fn fits(&self, num: &str) -> bool {
         let radix = 10;
 
@@ -310,10 +541,20 @@ impl Type {
     }
 
     pub fn is_signed_integer(&self) -> bool {
-        if let Type::Base(Base::Numeric(integer)) = &self {
-            return integer.is_signed();
+        match &self {
+            Type::Base(Base::Numeric(integer))
+            | Type::Base(Base::Named(NamedInteger { integer, .. })) => integer.is_signed(),
+            _ => false,
         }
-        false
+    }
+
+    /// Returns true if the type is a signed integer or a signed fixed-point
+    /// number.
+    pub fn is_signed(&self) -> bool {
+        if let Type::Base(Base::Fixed(fixed)) = &self {
+            return fixed.integer.is_signed();
+        }
+        self.is_signed_integer()
     }
 }
 
@@ -321,6 +562,7 @@ impl From<FixedSize> for Type {
     fn from(value: FixedSize) -> Self {
         match value {
             FixedSize::Array(array) => Type::Array(array),
+            FixedSize::DynamicArray(array) => Type::DynamicArray(array),
             FixedSize::Base(base) => Type::Base(base),
             FixedSize::Tuple(tuple) => Type::Tuple(tuple),
             FixedSize::String(string) => Type::String(string),
@@ -341,6 +583,7 @@ impl FeSized for FixedSize {
         match self {
             FixedSize::Base(base) => base.size(),
             FixedSize::Array(array) => array.size(),
+            FixedSize::DynamicArray(array) => array.size(),
             FixedSize::Tuple(tuple) => tuple.size(),
             FixedSize::String(string) => string.size(),
             FixedSize::Contract(contract) => contract.size(),
@@ -372,6 +615,7 @@ impl AbiEncoding for FixedSize {
     fn abi_name(&self) -> String {
         match self {
             FixedSize::Array(array) => array.abi_name(),
+            FixedSize::DynamicArray(array) => array.abi_name(),
             FixedSize::Base(base) => base.abi_name(),
             FixedSize::Tuple(tuple) => tuple.abi_name(),
             FixedSize::String(string) => string.abi_name(),
@@ -383,6 +627,7 @@ impl AbiEncoding for FixedSize {
     fn abi_safe_name(&self) -> String {
         match self {
             FixedSize::Array(array) => array.abi_safe_name(),
+            FixedSize::DynamicArray(array) => array.abi_safe_name(),
             FixedSize::Base(base) => base.abi_safe_name(),
             FixedSize::Tuple(tuple) => tuple.abi_safe_name(),
             FixedSize::String(string) => string.abi_safe_name(),
@@ -395,12 +640,27 @@ impl AbiEncoding for FixedSize {
         match self {
             FixedSize::Base(base) => base.abi_type(),
             FixedSize::Array(array) => array.abi_type(),
+            FixedSize::DynamicArray(array) => array.abi_type(),
             FixedSize::Tuple(tuple) => tuple.abi_type(),
             FixedSize::String(string) => string.abi_type(),
             FixedSize::Contract(contract) => contract.abi_type(),
             FixedSize::Struct(val) => val.abi_type(),
         }
     }
+
+    fn abi_upper_bound(&self) -> Option<usize> {
+        match self {
+            FixedSize::Base(base) => base.abi_upper_bound(),
+            _ => None,
+        }
+    }
+
+    fn abi_signed_size(&self) -> Option<usize> {
+        match self {
+            FixedSize::Base(base) => base.abi_signed_size(),
+            _ => None,
+        }
+    }
 }
 
 impl FixedSize {
@@ -423,6 +683,7 @@ impl TryFrom<Type> for FixedSize {
     fn try_from(value: Type) -> Result<Self, SemanticError> {
         match value {
             Type::Array(array) => Ok(FixedSize::Array(array)),
+            Type::DynamicArray(array) => Ok(FixedSize::DynamicArray(array)),
             Type::Base(base) => Ok(FixedSize::Base(base)),
             Type::Tuple(tuple) => Ok(FixedSize::Tuple(tuple)),
             Type::String(string) => Ok(FixedSize::String(string)),
@@ -440,6 +701,9 @@ impl FeSized for Base {
             Base::Bool => 1,
             Base::Byte => 1,
             Base::Address => 32,
+            Base::Enum(_) => 1,
+            Base::Fixed(fixed) => fixed.integer.size(),
+            Base::Named(named) => named.integer.size(),
         }
     }
 }
@@ -462,11 +726,20 @@ impl AbiEncoding for Base {
             Base::Address => "address".to_string(),
             Base::Byte => "byte".to_string(),
             Base::Bool => "bool".to_string(),
+            Base::Enum(_) => "uint8".to_string(),
+            Base::Fixed(fixed) => fixed.name(),
+            Base::Named(named) => Base::Numeric(named.integer.clone()).abi_name(),
         }
     }
 
     fn abi_safe_name(&self) -> String {
-        self.abi_name()
+        match self {
+            // Enums get their own decoding functions, which check the range of the value.
+            Base::Enum(val) => val.name.clone(),
+            // Named integers are distinct types, so their functions need distinct names.
+            Base::Named(named) => named.name.clone(),
+            _ => self.abi_name(),
+        }
     }
 
     fn abi_type(&self) -> AbiType {
@@ -563,6 +836,34 @@ impl AbiEncoding for Base {
                     padded_size: 1,
                 },
             },
+            Base::Enum(_) => AbiType::Uint {
+                size: AbiUintSize {
+                    data_size: 1,
+                    padded_size: 32,
+                },
+            },
+            Base::Fixed(fixed) => Base::Numeric(fixed.integer.clone()).abi_type(),
+            Base::Named(named) => Base::Numeric(named.integer.clone()).abi_type(),
+        }
+    }
+
+    fn abi_upper_bound(&self) -> Option<usize> {
+        match self {
+            Base::Enum(val) => Some(val.variants.len()),
+            _ => None,
+        }
+    }
+
+    fn abi_signed_size(&self) -> Option<usize> {
+        match self {
+            Base::Numeric(integer)
+            | Base::Fixed(FixedPoint { integer, .. })
+            | Base::Named(NamedInteger { integer, .. })
+                if integer.is_signed() && integer.size() < 32 =>
+            {
+                Some(integer.size())
+            }
+            _ => None,
         }
     }
 }
@@ -598,13 +899,53 @@ impl AbiEncoding for Array {
     }
 }
 
+impl FeSized for DynamicArray {
+    /// Only the length word has a size that is known at compile time.
+    fn size(&self) -> usize {
+        32
+    }
+}
+
+impl AbiEncoding for DynamicArray {
+    fn abi_name(&self) -> String {
+        if self.inner == Base::Byte {
+            return "bytes".to_string();
+        }
+
+        format!("{}[]", self.inner.abi_name())
+    }
+
+    fn abi_safe_name(&self) -> String {
+        if self.inner == Base::Byte {
+            return "bytes".to_string();
+        }
+
+        format!("{}_array", self.inner.abi_name())
+    }
+
+    fn abi_type(&self) -> AbiType {
+        AbiType::Array {
+            inner: Box::new(self.inner.abi_type()),
+            size: AbiArraySize::Dynamic,
+        }
+    }
+}
+
 impl Tuple {
     pub fn empty() -> Tuple {
         Tuple { items: vec![] }
     }
 
     pub fn is_empty(&self) -> bool {
-        self.size() == 0
+        self.items.is_empty()
+    }
+
+    /// The offset of the item at `index`, relative to the start of the tuple.
+    ///
+    /// Like struct fields, each item occupies its own 32 byte word and is
+    /// left-padded.
+    pub fn item_offset(&self, index: usize) -> usize {
+        index * 32 + (32 - self.items[index].size())
     }
 }
 
@@ -616,7 +957,7 @@ impl From<Tuple> for FixedSize {
 
 impl FeSized for Tuple {
     fn size(&self) -> usize {
-        self.items.iter().map(|typ| typ.size()).sum()
+        self.items.len() * 32
     }
 }
 
@@ -654,15 +995,27 @@ impl AbiEncoding for Struct {
 
 impl AbiEncoding for Tuple {
     fn abi_name(&self) -> String {
-        unimplemented!();
+        let item_names = self
+            .items
+            .iter()
+            .map(|typ| typ.abi_name())
+            .collect::<Vec<String>>();
+        format!("({})", item_names.join(","))
     }
 
     fn abi_safe_name(&self) -> String {
-        unimplemented!();
+        let item_names = self
+            .items
+            .iter()
+            .map(|typ| typ.abi_safe_name())
+            .collect::<Vec<String>>();
+        format!("tuple_{}", item_names.join("_"))
     }
 
     fn abi_type(&self) -> AbiType {
-        unimplemented!();
+        AbiType::Tuple {
+            elems: self.items.iter().map(|typ| typ.abi_type()).collect(),
+        }
     }
 }
 
@@ -751,6 +1104,14 @@ pub fn type_desc(defs: &HashMap<String, Type>, typ: &fe::TypeDesc) -> Result<Typ
         fe::TypeDesc::Base { base } if base.starts_with("string") => Ok(Type::String(
             TryFrom::try_from(*base).map_err(|_| SemanticError::type_error())?,
         )),
+        fe::TypeDesc::Base { base } if base.starts_with("ufixed") || base.starts_with("fixed") => {
+            if let Ok(fixed) = FixedPoint::try_from(*base) {
+                return Ok(Type::Base(Base::Fixed(fixed)));
+            }
+            defs.get(base.to_owned())
+                .cloned()
+                .ok_or_else(SemanticError::undefined_value)
+        }
         fe::TypeDesc::Base { base } => {
             if let Some(typ) = defs.get(base.to_owned()) {
                 return Ok(typ.clone());
@@ -772,5 +1133,28 @@ pub fn type_desc(defs: &HashMap<String, Type>, typ: &fe::TypeDesc) -> Result<Typ
                 .map(|typ| type_desc_base(defs, &typ.node))
                 .collect::<Result<_, _>>()?,
         })),
+        fe::TypeDesc::Generic {
+            base: "Array",
+            args,
+        } => match args.as_slice() {
+            [inner] => Ok(Type::DynamicArray(DynamicArray {
+                inner: type_desc_base(defs, &inner.node)?,
+            })),
+            _ => Err(SemanticError::wrong_number_of_params()),
+        },
+        fe::TypeDesc::Generic { base, args } => {
+            let args = args
+                .iter()
+                .map(|arg| type_desc_fixed_size(defs, &arg.node))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            // Generic structs are added to `defs` under the name of their
+            // instance once they have been instantiated.
+            if let Some(typ) = defs.get(&instance_name(base, &args)) {
+                return Ok(typ.clone());
+            }
+
+            Err(SemanticError::undefined_value())
+        }
     }
 }
@@ -1,6 +1,7 @@
 use crate::errors::SemanticError;
 use crate::namespace::scopes::{
     BlockScope,
+    Mutability,
     Shared,
 };
 use crate::traversal::expressions;
@@ -26,6 +27,12 @@ pub fn assign(
         if let Some(target) = targets.first() {
             let target_attributes =
                 expressions::expr(Rc::clone(&scope), Rc::clone(&context), target)?;
+            if context.borrow().get_constant(target).is_some() {
+                return Err(SemanticError::assign_to_constant());
+            }
+            if matches!(target_attributes.location, Location::Storage { .. }) {
+                scope.borrow().validate_mutability(Mutability::Mutable)?;
+            }
             let value_attributes =
                 expressions::expr(Rc::clone(&scope), Rc::clone(&context), value)?;
 
@@ -0,0 +1,216 @@
+use crate::errors::SemanticError;
+use crate::namespace::constants::{
+    ConstValue,
+    Constant,
+};
+use crate::namespace::scopes::Scope;
+use crate::namespace::types::{
+    i256_max,
+    i256_min,
+    u256_max,
+    Base,
+    Type,
+};
+use crate::traversal::types;
+use fe_common::utils::keccak;
+use fe_parser::ast as fe;
+use fe_parser::span::Spanned;
+use num_bigint::{
+    BigInt,
+    Sign,
+};
+use std::convert::TryFrom;
+
+/// Evaluate the value of a constant definition and check that it fits the
+/// declared type.
+pub fn const_def(
+    scope: Scope,
+    typ: &Spanned<fe::TypeDesc>,
+    value: &Spanned<fe::Expr>,
+) -> Result<Constant, SemanticError> {
+    let typ = match types::type_desc(scope.clone(), typ)? {
+        Type::Base(base @ Base::Numeric(_)) | Type::Base(base @ Base::Bool) => base,
+        _ => return Err(SemanticError::type_error().with_context(typ.span)),
+    };
+    let value = eval(&scope, value)?;
+
+    match (&typ, &value) {
+        (Base::Numeric(integer), ConstValue::Int(int)) => {
+            if !integer.fits(&int.to_string()) {
+                return Err(SemanticError::numeric_capacity_mismatch());
+            }
+        }
+        (Base::Bool, ConstValue::Bool(_)) => {}
+        _ => return Err(SemanticError::type_error()),
+    }
+
+    Ok(Constant { typ, value })
+}
+
+/// Evaluate an expression at compile time.
+///
+/// Only literals, other constants, the hashes of string literals and
+/// operations on them can be evaluated. Integer values may not leave the range
+/// of 256 bit words at any point, except for the operations that wrap around
+/// at runtime as well.
+pub fn eval(scope: &Scope, exp: &Spanned<fe::Expr>) -> Result<ConstValue, SemanticError> {
+    eval_with(exp, &|name| match name.node {
+        fe::Expr::Name(name) => scope.constant_def(name).map(|constant| constant.value),
+        _ => None,
+    })
+}
+
+/// Evaluate an expression at compile time, looking up the values of names
+/// with the given function.
+pub fn eval_with<F>(exp: &Spanned<fe::Expr>, lookup: &F) -> Result<ConstValue, SemanticError>
+where
+    F: Fn(&Spanned<fe::Expr>) -> Option<ConstValue>,
+{
+    let eval = |exp: &Spanned<fe::Expr>| eval_with(exp, lookup);
+
+    match &exp.node {
+        fe::Expr::Num(num) => BigInt::parse_bytes(num.as_bytes(), 10)
+            .map(ConstValue::Int)
+            .ok_or_else(SemanticError::numeric_literal_expected),
+        fe::Expr::Bool(value) => Ok(ConstValue::Bool(*value)),
+        fe::Expr::Name(_) => lookup(exp).ok_or_else(SemanticError::undefined_value),
+        fe::Expr::Call { func, args } => match (&func.node, args.node.as_slice()) {
+            (fe::Expr::Name("keccak256"), [arg]) => match &arg.node {
+                fe::CallArg::Arg(fe::Expr::Str(lines)) => {
+                    let hash = keccak::full_as_bytes(lines.join("").as_bytes());
+                    Ok(ConstValue::Int(BigInt::from_bytes_be(Sign::Plus, &hash)))
+                }
+                _ => Err(SemanticError::type_error()),
+            },
+            _ => Err(SemanticError::type_error()),
+        },
+        fe::Expr::BinOperation { left, op, right } => match (eval(left)?, eval(right)?) {
+            (ConstValue::Int(left), ConstValue::Int(right)) => bin_operation(left, &op.node, right),
+            _ => Err(SemanticError::type_error()),
+        },
+        fe::Expr::UnaryOperation { op, operand } => match (&op.node, eval(operand)?) {
+            (fe::UnaryOperator::USub, ConstValue::Int(value)) => checked(-value),
+            (fe::UnaryOperator::UAdd, ConstValue::Int(value)) => Ok(ConstValue::Int(value)),
+            (fe::UnaryOperator::Not, ConstValue::Bool(value)) => Ok(ConstValue::Bool(!value)),
+            _ => Err(SemanticError::type_error()),
+        },
+        fe::Expr::BoolOperation { left, op, right } => match (eval(left)?, eval(right)?) {
+            (ConstValue::Bool(left), ConstValue::Bool(right)) => match op.node {
+                fe::BoolOperator::And => Ok(ConstValue::Bool(left && right)),
+                fe::BoolOperator::Or => Ok(ConstValue::Bool(left || right)),
+            },
+            _ => Err(SemanticError::type_error()),
+        },
+        fe::Expr::CompOperation { left, op, right } => {
+            comp_operation(eval(left)?, &op.node, eval(right)?)
+        }
+        fe::Expr::Ternary {
+            if_expr,
+            test,
+            else_expr,
+        } => match eval(test)? {
+            ConstValue::Bool(true) => eval(if_expr),
+            ConstValue::Bool(false) => eval(else_expr),
+            _ => Err(SemanticError::type_error()),
+        },
+        _ => Err(SemanticError::type_error()),
+    }
+    .map_err(|error| error.with_context(exp.span))
+}
+
+/// Evaluates a binary operation like the EVM: Arithmetic that is checked at
+/// runtime fails on overflow, division by zero results in zero, and shifts
+/// and powers wrap around modulo 2^256. Wrapped results are signed if the left
+/// operand is negative, since both operands are of the same type.
+fn bin_operation(
+    left: BigInt,
+    op: &fe::BinOperator,
+    right: BigInt,
+) -> Result<ConstValue, SemanticError> {
+    let zero = BigInt::from(0);
+    let signed = left < zero;
+
+    match op {
+        fe::BinOperator::Add => checked(left + right),
+        fe::BinOperator::Sub => checked(left - right),
+        fe::BinOperator::Mult => checked(left * right),
+        fe::BinOperator::Div | fe::BinOperator::Mod if right == zero => Ok(ConstValue::Int(zero)),
+        // both round towards zero like `sdiv` and `smod`
+        fe::BinOperator::Div => checked(left / right),
+        fe::BinOperator::Mod => checked(left % right),
+        fe::BinOperator::Pow => checked(from_word(
+            word(&left).modpow(&word(&right), &word_modulus()),
+            signed,
+        )),
+        fe::BinOperator::LShift => {
+            checked(from_word(word(&(left << shift_amount(&right))), signed))
+        }
+        fe::BinOperator::RShift => checked(left >> shift_amount(&right)),
+        fe::BinOperator::BitOr => checked(left | right),
+        fe::BinOperator::BitXor => checked(left ^ right),
+        fe::BinOperator::BitAnd => checked(left & right),
+        fe::BinOperator::FloorDiv => Err(SemanticError::type_error()),
+    }
+}
+
+fn comp_operation(
+    left: ConstValue,
+    op: &fe::CompOperator,
+    right: ConstValue,
+) -> Result<ConstValue, SemanticError> {
+    let result = match (left, right) {
+        (ConstValue::Int(left), ConstValue::Int(right)) => match op {
+            fe::CompOperator::Eq => left == right,
+            fe::CompOperator::NotEq => left != right,
+            fe::CompOperator::Lt => left < right,
+            fe::CompOperator::LtE => left <= right,
+            fe::CompOperator::Gt => left > right,
+            fe::CompOperator::GtE => left >= right,
+            _ => return Err(SemanticError::type_error()),
+        },
+        (ConstValue::Bool(left), ConstValue::Bool(right)) => match op {
+            fe::CompOperator::Eq => left == right,
+            fe::CompOperator::NotEq => left != right,
+            _ => return Err(SemanticError::type_error()),
+        },
+        _ => return Err(SemanticError::type_error()),
+    };
+
+    Ok(ConstValue::Bool(result))
+}
+
+/// The number of values of a 256 bit word.
+fn word_modulus() -> BigInt {
+    u256_max() + 1
+}
+
+/// The 256 bit word of a value, i.e. the two's complement of negative values.
+fn word(value: &BigInt) -> BigInt {
+    let modulus = word_modulus();
+    ((value % &modulus) + &modulus) % modulus
+}
+
+/// The value of a 256 bit word, which is negative if it is `signed` and the
+/// highest bit is set.
+fn from_word(word: BigInt, signed: bool) -> BigInt {
+    if signed && word > i256_max() {
+        word - word_modulus()
+    } else {
+        word
+    }
+}
+
+/// The amount of a shift by `value`, where shifts by 256 bits or more shift
+/// out every bit.
+fn shift_amount(value: &BigInt) -> usize {
+    usize::try_from(&word(value)).unwrap_or(256).min(256)
+}
+
+/// Checks that an intermediate result still fits into a 256 bit word.
+fn checked(value: BigInt) -> Result<ConstValue, SemanticError> {
+    if value < i256_min() || value > u256_max() {
+        Err(SemanticError::numeric_capacity_mismatch())
+    } else {
+        Ok(ConstValue::Int(value))
+    }
+}
@@ -1,8 +1,13 @@
+use crate::builtins;
 use crate::errors::SemanticError;
+use crate::namespace::custom_errors::CustomError;
+use crate::namespace::erc165;
 use crate::namespace::events::Event;
+use crate::namespace::proxy;
 use crate::namespace::scopes::{
     ContractScope,
     ModuleScope,
+    Mutability,
     Scope,
     Shared,
 };
@@ -12,48 +17,246 @@ use crate::namespace::types::{
     Type,
 };
 use crate::traversal::{
+    constants,
     functions,
     types,
 };
 use crate::{
     Context,
     ContractAttributes,
+    FunctionAttributes,
 };
 use fe_parser::ast as fe;
 use fe_parser::span::Spanned;
+use std::collections::HashMap;
 use std::rc::Rc;
 
+/// The bodies of the mixins defined in a module.
+pub type MixinDefs<'a, 'b> = HashMap<&'a str, &'b [Spanned<fe::ContractStmt<'a>>]>;
+
 /// Gather context information for contract definitions and check for type
 /// errors.
+///
+/// The items of the contract's mixins are added before the contract's own
+/// items, in the order the mixins are listed, so mixin fields take the first
+/// storage slots. The functions defined outside of any contract are added
+/// after them as private functions that can't access the contract's state.
+///
+/// All functions of a library are pure.
+///
+/// A contract marked with `@erc165` supports the ERC-165 interface, the
+/// traits it implements and the interface of all its public functions.
+///
+/// A contract marked with `@upgradeable` is initialized by its only
+/// `@initializer` function, since its `__init__` can't write to the storage
+/// of the proxy. Its storage gaps reserve a nonce for each of their items.
 pub fn contract_def(
     module_scope: Shared<ModuleScope>,
     context: Shared<Context>,
     stmt: &Spanned<fe::ModuleStmt>,
+    mixin_defs: &MixinDefs,
+    free_functions: &[Spanned<fe::ContractStmt>],
 ) -> Result<(), SemanticError> {
-    if let fe::ModuleStmt::ContractDef { name, body } = &stmt.node {
+    if let fe::ModuleStmt::ContractDef {
+        docs: _,
+        decorators,
+        name,
+        mixins,
+        traits,
+        body,
+    } = &stmt.node
+    {
+        let decorators = decorators
+            .iter()
+            .map(|decorator| builtins::Decorator::of(&decorator.node))
+            .collect::<Vec<_>>();
+        if decorators.iter().any(|decorator| {
+            !matches!(
+                decorator,
+                Some(builtins::Decorator::Erc165) | Some(builtins::Decorator::Upgradeable)
+            )
+        }) {
+            return Err(SemanticError::invalid_decorator().with_context(stmt.span));
+        }
+        let is_erc165 = decorators.contains(&Some(builtins::Decorator::Erc165));
+        let is_upgradeable = decorators.contains(&Some(builtins::Decorator::Upgradeable));
+
         let contract_scope = ContractScope::new(Rc::clone(&module_scope));
 
-        for stmt in body.iter() {
-            match &stmt.node {
-                fe::ContractStmt::ContractField { .. } => {
-                    contract_field(Rc::clone(&contract_scope), stmt)
+        let mut sources = vec![];
+        for mixin_name in mixins.iter() {
+            let mixin_body = mixin_defs
+                .get(mixin_name.node)
+                .ok_or_else(|| SemanticError::undefined_value().with_context(mixin_name.span))?;
+            sources.push((true, *mixin_body, Context::new_shared()));
+        }
+        sources.push((false, free_functions, Context::new_shared()));
+        sources.push((false, body.as_slice(), Rc::clone(&context)));
+
+        for (is_mixin, body, context) in sources.iter() {
+            for stmt in body.iter() {
+                match &stmt.node {
+                    fe::ContractStmt::ContractField { .. } => {
+                        contract_field(Rc::clone(&contract_scope), stmt, is_upgradeable)
+                    }
+                    fe::ContractStmt::ConstDef { .. } => {
+                        const_def(Rc::clone(&contract_scope), stmt)
+                    }
+                    fe::ContractStmt::EventDef { .. } => {
+                        event_def(Rc::clone(&contract_scope), stmt)
+                    }
+                    fe::ContractStmt::ErrorDef { .. } => {
+                        error_def(Rc::clone(&contract_scope), stmt)
+                    }
+                    fe::ContractStmt::FuncDef { name, .. }
+                        if *is_mixin && name.node == "__init__" =>
+                    {
+                        Err(SemanticError::init_in_mixin())
+                    }
+                    fe::ContractStmt::FuncDef { .. } => {
+                        functions::func_def(Rc::clone(&contract_scope), Rc::clone(context), stmt)
+                    }
+                    fe::ContractStmt::Error(_) => Ok(()),
                 }
-                fe::ContractStmt::EventDef { .. } => event_def(Rc::clone(&contract_scope), stmt),
-                fe::ContractStmt::FuncDef { .. } => {
-                    functions::func_def(Rc::clone(&contract_scope), Rc::clone(&context), stmt)
+                .map_err(|error| error.with_context(stmt.span))?;
+            }
+        }
+
+        let is_library = module_scope.borrow().is_library(name.node);
+        let pure_functions = free_functions
+            .iter()
+            .chain(body.iter().filter(|_| is_library));
+        for def in pure_functions {
+            if let fe::ContractStmt::FuncDef { name, .. } = &def.node {
+                if let Some(func_def) = contract_scope.borrow().function_def(name.node) {
+                    func_def.scope.borrow_mut().mutability = Mutability::Pure;
                 }
             }
-            .map_err(|error| error.with_context(stmt.span))?;
         }
 
-        for stmt in body.iter() {
-            if let fe::ContractStmt::FuncDef { .. } = &stmt.node {
-                functions::func_body(Rc::clone(&contract_scope), Rc::clone(&context), stmt)
-                    .map_err(|error| error.with_context(stmt.span))?;
-            };
+        for trait_name in traits.iter() {
+            trait_impl(Rc::clone(&contract_scope), trait_name.node)
+                .map_err(|error| error.with_context(trait_name.span))?;
         }
 
-        let contract_attributes = ContractAttributes::from(Rc::clone(&contract_scope));
+        let initializers = sources
+            .iter()
+            .flat_map(|(_, body, _)| body.iter())
+            .filter_map(|def| match &def.node {
+                fe::ContractStmt::FuncDef {
+                    decorators, name, ..
+                } if decorators.iter().any(|decorator| {
+                    builtins::Decorator::of(&decorator.node)
+                        == Some(builtins::Decorator::Initializer)
+                }) =>
+                {
+                    Some((def.span, name.node))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let initializer = match (is_upgradeable, initializers.as_slice()) {
+            (true, [(_, name)]) => Some(*name),
+            (true, _) => return Err(SemanticError::invalid_upgradeable().with_context(stmt.span)),
+            (false, []) => None,
+            (false, [(span, _), ..]) => {
+                return Err(SemanticError::invalid_decorator().with_context(*span))
+            }
+        };
+
+        // the state of an upgradeable contract is only initialized by its
+        // initializer
+        if is_upgradeable {
+            if let Some(init) = contract_scope.borrow().function_def("__init__") {
+                init.scope.borrow_mut().mutability = Mutability::View;
+            }
+        }
+
+        // Errors in function bodies are recorded, so that the bodies of the
+        // remaining functions are checked as well.
+        for (_, body, source_context) in sources.iter() {
+            let decorators = functions::decorator_names(body);
+
+            for stmt in body.iter() {
+                if let fe::ContractStmt::FuncDef { .. } = &stmt.node {
+                    if let Err(error) = functions::func_body(
+                        Rc::clone(&contract_scope),
+                        Rc::clone(source_context),
+                        stmt,
+                        &decorators,
+                    ) {
+                        context
+                            .borrow_mut()
+                            .add_error(error.with_context(stmt.span));
+                    }
+                };
+            }
+        }
+
+        // Analyzing an instance of a generic function may instantiate further
+        // generic functions, so this runs until no instances are left.
+        loop {
+            let next = contract_scope.borrow_mut().take_pending_instance();
+            if let Some((generic_name, instance_name)) = next {
+                let (stmt, context) = sources
+                    .iter()
+                    .find_map(|(_, body, context)| {
+                        body.iter()
+                            .find(|stmt| {
+                                matches!(
+                                    &stmt.node,
+                                    fe::ContractStmt::FuncDef { name, .. } if name.node == generic_name
+                                )
+                            })
+                            .map(|stmt| (stmt, context))
+                    })
+                    .unwrap_or_else(|| {
+                        panic!("Failed to lookup generic function {}", generic_name)
+                    });
+
+                let instance_context = Context::new_shared();
+                let attributes = functions::func_instance_body(
+                    Rc::clone(&contract_scope),
+                    Rc::clone(&instance_context),
+                    stmt,
+                    &instance_name,
+                )
+                .map_err(|error| error.with_context(stmt.span))?;
+
+                context.borrow_mut().add_function_instance(
+                    stmt,
+                    attributes,
+                    instance_context.take(),
+                );
+            } else {
+                break;
+            }
+        }
+
+        for (mixin_name, (_, _, mixin_context)) in mixins.iter().zip(sources.iter()) {
+            context
+                .borrow_mut()
+                .add_mixin(stmt, mixin_name.node, mixin_context.take());
+        }
+        let (_, _, free_functions_context) = &sources[mixins.len()];
+        context
+            .borrow_mut()
+            .add_free_functions(stmt, free_functions_context.take());
+
+        let mut contract_attributes = ContractAttributes::from(Rc::clone(&contract_scope));
+        contract_attributes.is_upgradeable = is_upgradeable;
+        contract_attributes.initializer = initializer.and_then(|name| {
+            contract_attributes
+                .public_functions
+                .iter()
+                .find(|function| function.name == name)
+                .cloned()
+        });
+        if is_erc165 {
+            contract_attributes.supported_interfaces =
+                supported_interfaces(&contract_scope, &contract_attributes, traits)
+                    .map_err(|error| error.with_context(stmt.span))?;
+        }
 
         contract_scope
             .borrow()
@@ -64,6 +267,10 @@ pub fn contract_def(
                 Type::Contract(Contract {
                     name: name.node.to_owned(),
                     functions: contract_attributes.public_functions.clone(),
+                    init_params: contract_attributes
+                        .init_function
+                        .as_ref()
+                        .map_or_else(Vec::new, |init| init.param_types.clone()),
                 }),
             );
 
@@ -75,12 +282,115 @@ pub fn contract_def(
     unreachable!()
 }
 
+/// The IDs of the interfaces that a contract marked with `@erc165` supports.
+/// The contract can't define `supportsInterface` itself.
+fn supported_interfaces(
+    scope: &Shared<ContractScope>,
+    attributes: &ContractAttributes,
+    traits: &[Spanned<&str>],
+) -> Result<Vec<u32>, SemanticError> {
+    if scope
+        .borrow()
+        .function_def(erc165::SUPPORTS_INTERFACE)
+        .is_some()
+    {
+        return Err(SemanticError::already_defined());
+    }
+
+    let module_scope = scope.borrow().module_scope();
+    let trait_ids = traits.iter().filter_map(|trait_name| {
+        module_scope
+            .borrow()
+            .get_trait_def(trait_name.node)
+            .map(|def| erc165::interface_id(&def.functions))
+    });
+
+    let mut ids = vec![];
+    for id in std::iter::once(erc165::ERC165_INTERFACE_ID)
+        .chain(trait_ids)
+        .chain(std::iter::once(erc165::interface_id(
+            &attributes.public_functions,
+        )))
+    {
+        if id != erc165::INVALID_INTERFACE_ID && !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+
+    Ok(ids)
+}
+
+/// Checks that the contract defines every function of the trait as a public
+/// function with the same signature.
+fn trait_impl(scope: Shared<ContractScope>, name: &str) -> Result<(), SemanticError> {
+    let def = scope
+        .borrow()
+        .module_scope()
+        .borrow()
+        .get_trait_def(name)
+        .ok_or_else(SemanticError::undefined_value)?;
+
+    let public_functions = scope
+        .borrow()
+        .function_defs
+        .iter()
+        .filter(|(_, def)| def.is_public)
+        .map(|(name, def)| FunctionAttributes {
+            name: name.to_owned(),
+            param_types: def.param_types.to_owned(),
+            return_type: def.return_type.to_owned(),
+        })
+        .collect::<Vec<_>>();
+
+    if def.missing_functions(&public_functions).is_empty() {
+        Ok(())
+    } else {
+        Err(SemanticError::trait_not_implemented())
+    }
+}
+
+fn const_def(
+    scope: Shared<ContractScope>,
+    stmt: &Spanned<fe::ContractStmt>,
+) -> Result<(), SemanticError> {
+    if let fe::ContractStmt::ConstDef {
+        decorators,
+        name,
+        typ,
+        value,
+        ..
+    } = &stmt.node
+    {
+        if !decorators.is_empty() {
+            return Err(SemanticError::invalid_decorator().with_context(stmt.span));
+        }
+        let constant = constants::const_def(Scope::Contract(Rc::clone(&scope)), typ, value)?;
+        return scope.borrow_mut().add_constant(name.node, constant);
+    }
+
+    unreachable!()
+}
+
 fn contract_field(
     scope: Shared<ContractScope>,
     stmt: &Spanned<fe::ContractStmt>,
+    is_upgradeable: bool,
 ) -> Result<(), SemanticError> {
-    if let fe::ContractStmt::ContractField { qual: _, name, typ } = &stmt.node {
+    if let fe::ContractStmt::ContractField {
+        docs: _,
+        qual: _,
+        name,
+        typ,
+    } = &stmt.node
+    {
         let typ = types::type_desc(Scope::Contract(Rc::clone(&scope)), typ)?;
+        if is_upgradeable && proxy::is_storage_gap(name.node) {
+            let size = proxy::storage_gap_size(&typ).ok_or_else(SemanticError::type_error)?;
+            scope.borrow_mut().add_field(name.node, typ)?;
+            scope.borrow_mut().reserve_nonces(size.saturating_sub(1));
+            return Ok(());
+        }
+
         return scope.borrow_mut().add_field(name.node, typ);
     }
 
@@ -91,7 +401,7 @@ fn event_def(
     scope: Shared<ContractScope>,
     stmt: &Spanned<fe::ContractStmt>,
 ) -> Result<(), SemanticError> {
-    if let fe::ContractStmt::EventDef { name, fields } = &stmt.node {
+    if let fe::ContractStmt::EventDef { name, fields, .. } = &stmt.node {
         let name = name.node;
 
         let (is_indexed_bools, fields): (Vec<bool>, Vec<FixedSize>) = fields
@@ -112,14 +422,6 @@ fn event_def(
             return Err(SemanticError::more_than_three_indexed_params());
         }
 
-        // check if they are trying to index an array type
-        for index in indexed_fields.clone() {
-            match fields[index].to_owned() {
-                FixedSize::Base(_) => {}
-                _ => unimplemented!("non-base type indexed event params"),
-            }
-        }
-
         return scope
             .borrow_mut()
             .add_event(name, Event::new(name, fields, indexed_fields));
@@ -128,6 +430,26 @@ fn event_def(
     unreachable!()
 }
 
+fn error_def(
+    scope: Shared<ContractScope>,
+    stmt: &Spanned<fe::ContractStmt>,
+) -> Result<(), SemanticError> {
+    if let fe::ContractStmt::ErrorDef { name, fields, .. } = &stmt.node {
+        let fields = fields
+            .iter()
+            .map(|field| {
+                types::type_desc_fixed_size(Scope::Contract(Rc::clone(&scope)), &field.node.typ)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        return scope
+            .borrow_mut()
+            .add_error(name.node, CustomError::new(name.node, fields));
+    }
+
+    unreachable!()
+}
+
 fn event_field(
     scope: Shared<ContractScope>,
     field: &Spanned<fe::EventField>,
@@ -4,12 +4,18 @@ use crate::namespace::scopes::{
     Scope,
     Shared,
 };
-use crate::namespace::types::Type;
+use crate::namespace::types::{
+    FixedSize,
+    Type,
+};
 use crate::traversal::{
     expressions,
     types,
 };
-use crate::Context;
+use crate::{
+    Context,
+    Location,
+};
 use fe_parser::ast as fe;
 use fe_parser::span::Spanned;
 use std::rc::Rc;
@@ -21,7 +27,14 @@ pub fn var_decl(
     stmt: &Spanned<fe::FuncStmt>,
 ) -> Result<(), SemanticError> {
     if let fe::FuncStmt::VarDecl { target, typ, value } = &stmt.node {
-        let name = expressions::expr_name_str(target)?;
+        if let fe::TypeDesc::Generic {
+            base: "Storage",
+            args,
+        } = &typ.node
+        {
+            return storage_ref_decl(scope, context, stmt, target, args, value);
+        }
+
         let declared_type = types::type_desc_fixed_size(Scope::Block(Rc::clone(&scope)), typ)?;
         if let Some(value) = value {
             let value_attributes =
@@ -32,7 +45,13 @@ pub fn var_decl(
             }
         }
 
-        scope.borrow_mut().add_var(name, declared_type.clone())?;
+        if let fe::Expr::Tuple { elts } = &target.node {
+            destructure(Rc::clone(&scope), elts, &declared_type)?;
+        } else {
+            let name = expressions::expr_name_str(target)?;
+            scope.borrow_mut().add_var(name, declared_type.clone())?;
+        }
+
         context.borrow_mut().add_declaration(stmt, declared_type);
 
         return Ok(());
@@ -41,6 +60,76 @@ pub fn var_decl(
     unreachable!()
 }
 
+/// Declares a variable of type `Storage<T>`, which refers to a struct or array
+/// in storage. Fields and elements are read and written in place, so only the
+/// parts of the value that are used are loaded or stored.
+fn storage_ref_decl(
+    scope: Shared<BlockScope>,
+    context: Shared<Context>,
+    stmt: &Spanned<fe::FuncStmt>,
+    target: &Spanned<fe::Expr>,
+    args: &[Spanned<fe::TypeDesc>],
+    value: &Option<Spanned<fe::Expr>>,
+) -> Result<(), SemanticError> {
+    let (inner, value) = match (args, value) {
+        ([inner], Some(value)) => (inner, value),
+        _ => return Err(SemanticError::type_error()),
+    };
+    let declared_type = types::type_desc_fixed_size(Scope::Block(Rc::clone(&scope)), inner)?;
+    if !matches!(declared_type, FixedSize::Struct(_) | FixedSize::Array(_)) {
+        return Err(SemanticError::type_error().with_context(inner.span));
+    }
+
+    let value_attributes = expressions::expr(Rc::clone(&scope), Rc::clone(&context), value)?;
+    if Type::from(declared_type.clone()) != value_attributes.typ {
+        return Err(SemanticError::type_error());
+    }
+    if !matches!(value_attributes.location, Location::Storage { .. }) {
+        return Err(SemanticError::type_error().with_context(value.span));
+    }
+
+    let name = match target.node {
+        fe::Expr::Name(name) => name,
+        _ => return Err(SemanticError::type_error().with_context(target.span)),
+    };
+    scope
+        .borrow_mut()
+        .add_storage_ref(name, declared_type.clone())?;
+    context.borrow_mut().add_declaration(stmt, declared_type);
+
+    Ok(())
+}
+
+/// Declares one variable for each item of a destructured tuple.
+fn destructure(
+    scope: Shared<BlockScope>,
+    targets: &[Spanned<fe::Expr>],
+    declared_type: &FixedSize,
+) -> Result<(), SemanticError> {
+    let tuple = match declared_type {
+        FixedSize::Tuple(tuple) if tuple.items.len() == targets.len() => tuple,
+        _ => return Err(SemanticError::type_error()),
+    };
+
+    for (target, item) in targets.iter().zip(tuple.items.iter()) {
+        if let fe::Expr::Name(name) = target.node {
+            scope
+                .borrow_mut()
+                .add_var(name, FixedSize::Base(item.to_owned()))?;
+        } else {
+            return Err(SemanticError::type_error());
+        }
+    }
+
+    scope
+        .borrow()
+        .contract_scope()
+        .borrow_mut()
+        .add_tuple(tuple);
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::errors::{
@@ -54,6 +143,7 @@ mod tests {
         Shared,
     };
     use crate::namespace::types::{
+        Base,
         FixedSize,
         U256,
     };
@@ -104,6 +194,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tuple_decl() {
+        let statement = "(foo, bar): (u256, bool) = (26, true)";
+        let scope = scope();
+        analyze(Rc::clone(&scope), statement).expect("analysis failed");
+        assert_eq!(
+            scope.borrow().get_variable_def("foo"),
+            Some(FixedSize::Base(U256))
+        );
+        assert_eq!(
+            scope.borrow().get_variable_def("bar"),
+            Some(FixedSize::Base(Base::Bool))
+        );
+    }
+
+    #[test]
+    fn tuple_arity_error_decl() {
+        let statement = "(foo, bar): (u256, bool, u256) = (26, true, 42)";
+        let result = analyze(scope(), statement);
+        assert_eq!(
+            result.expect_err("analysis didn't fail").kind,
+            ErrorKind::TypeError
+        );
+    }
+
     #[test]
     fn duplicate_var_decl() {
         let statement = "foo: u256 = 0";
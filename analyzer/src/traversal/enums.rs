@@ -0,0 +1,35 @@
+use fe_parser::span::Spanned;
+
+use crate::errors::SemanticError;
+use crate::namespace::scopes::{
+    ModuleScope,
+    Shared,
+};
+use crate::namespace::types::{
+    Base,
+    Enum,
+    Type,
+};
+
+pub fn enum_def(
+    module_scope: Shared<ModuleScope>,
+    name: &str,
+    variants: &[Spanned<&str>],
+) -> Result<(), SemanticError> {
+    let mut val = Enum::new(name);
+    for variant in variants {
+        if !val.add_variant(variant.node) {
+            return Err(SemanticError::already_defined().with_context(variant.span));
+        }
+    }
+
+    // enum variants are encoded as `uint8` values
+    if val.variants.len() > 256 {
+        return Err(SemanticError::numeric_capacity_mismatch());
+    }
+
+    module_scope
+        .borrow_mut()
+        .add_type_def(name, Type::Base(Base::Enum(val)));
+    Ok(())
+}
@@ -1,18 +1,35 @@
 use crate::builtins;
-use crate::errors::SemanticError;
+use crate::errors::{
+    ErrorKind,
+    SemanticError,
+    Suggestion,
+};
+use crate::namespace::constants::ConstValue;
+use crate::namespace::generics::{
+    infer_type_args,
+    GenericStructDef,
+};
 use crate::namespace::operations;
 use crate::namespace::scopes::{
     BlockScope,
     ContractFunctionDef,
+    Mutability,
     Shared,
 };
 use crate::namespace::types::{
+    AbiArraySize,
+    AbiEncoding,
+    AbiType,
+    AbiUintSize,
     Array,
     Base,
     Contract,
+    DynamicArray,
     FeString,
+    FixedPoint,
     FixedSize,
     Integer,
+    NamedInteger,
     Struct,
     Tuple,
     Type,
@@ -23,6 +40,11 @@ use crate::traversal::_utils::{
     fixed_sizes_to_types,
     spanned_expression,
 };
+use crate::traversal::{
+    constants,
+    functions,
+    structs,
+};
 use crate::{
     CallType,
     Context,
@@ -40,8 +62,12 @@ use builtins::{
     Object,
     TxField,
 };
+use fe_common::utils::edit_distance::closest;
 use fe_parser::ast as fe;
-use fe_parser::span::Spanned;
+use fe_parser::span::{
+    Span,
+    Spanned,
+};
 use std::convert::TryFrom;
 use std::rc::Rc;
 use std::str::FromStr;
@@ -53,7 +79,7 @@ pub fn expr(
     exp: &Spanned<fe::Expr>,
 ) -> Result<ExpressionAttributes, SemanticError> {
     let attributes = match &exp.node {
-        fe::Expr::Name(_) => expr_name(scope, exp),
+        fe::Expr::Name(_) => expr_name(scope, Rc::clone(&context), exp),
         fe::Expr::Num(_) => expr_num(exp),
         fe::Expr::Bool(_) => expr_bool(exp),
         fe::Expr::Subscript { .. } => expr_subscript(scope, Rc::clone(&context), exp),
@@ -72,11 +98,58 @@ pub fn expr(
     }
     .map_err(|error| error.with_context(exp.span))?;
 
+    fold_constant(&context, exp, &attributes).map_err(|error| error.with_context(exp.span))?;
     context.borrow_mut().add_expression(exp, attributes.clone());
 
     Ok(attributes)
 }
 
+/// Evaluates operations on literals and constants at compile time, so that
+/// they are replaced by their value. Results that don't fit the type of the
+/// expression are reported as errors instead of failing at runtime.
+///
+/// The operands have already been folded where possible, so only the
+/// expression itself has to be evaluated.
+fn fold_constant(
+    context: &Shared<Context>,
+    exp: &Spanned<fe::Expr>,
+    attributes: &ExpressionAttributes,
+) -> Result<(), SemanticError> {
+    // Ternary expressions are not folded, since that would skip the side
+    // effects of the branch that isn't taken.
+    if !matches!(
+        exp.node,
+        fe::Expr::BinOperation { .. }
+            | fe::Expr::UnaryOperation { .. }
+            | fe::Expr::BoolOperation { .. }
+            | fe::Expr::CompOperation { .. }
+            | fe::Expr::Call { .. }
+    ) || !matches!(
+        attributes.typ,
+        Type::Base(Base::Numeric(_)) | Type::Base(Base::Bool)
+    ) {
+        return Ok(());
+    }
+
+    let value = constants::eval_with(exp, &|name| context.borrow().get_constant(name).cloned());
+    let value = match value {
+        Ok(value) => value,
+        // Overflows of checked arithmetic can not succeed at runtime either.
+        Err(error) if error.kind == ErrorKind::NumericCapacityMismatch => return Err(error),
+        // The expression depends on values that are only known at runtime.
+        Err(_) => return Ok(()),
+    };
+
+    if let (Type::Base(Base::Numeric(integer)), ConstValue::Int(int)) = (&attributes.typ, &value) {
+        if !integer.fits(&int.to_string()) {
+            return Err(SemanticError::numeric_capacity_mismatch());
+        }
+    }
+
+    context.borrow_mut().add_constant(exp, value);
+    Ok(())
+}
+
 pub fn expr_list(
     scope: Shared<BlockScope>,
     context: Shared<Context>,
@@ -117,8 +190,8 @@ pub fn expr_list(
 
 /// Gather context information for a tuple expression and check for type errors.
 pub fn expr_tuple(
-    _scope: Shared<BlockScope>,
-    _context: Shared<Context>,
+    scope: Shared<BlockScope>,
+    context: Shared<Context>,
     exp: &Spanned<fe::Expr>,
 ) -> Result<ExpressionAttributes, SemanticError> {
     if let fe::Expr::Tuple { elts } = &exp.node {
@@ -127,9 +200,31 @@ pub fn expr_tuple(
                 Type::Tuple(Tuple::empty()),
                 Location::Memory,
             ));
-        } else {
-            todo!("Non-empty Tuples not supported yet")
         }
+
+        let items = elts
+            .iter()
+            .map(|elt| {
+                let attributes = value_expr(Rc::clone(&scope), Rc::clone(&context), elt)?;
+
+                match attributes.typ {
+                    Type::Base(base) => Ok(base),
+                    _ => Err(SemanticError::type_error()),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let tuple = Tuple { items };
+
+        scope
+            .borrow()
+            .contract_scope()
+            .borrow_mut()
+            .add_tuple(&tuple);
+
+        return Ok(ExpressionAttributes::new(
+            Type::Tuple(tuple),
+            Location::Memory,
+        ));
     }
     unreachable!()
 }
@@ -149,6 +244,28 @@ pub fn value_expr(
     Ok(attributes)
 }
 
+/// Gather context information for an operand of a binary or comparison
+/// operation and check for type errors.
+///
+/// Strings are operated on in memory, all other operands are moved to the
+/// stack.
+fn operand_expr(
+    scope: Shared<BlockScope>,
+    context: Shared<Context>,
+    exp: &Spanned<fe::Expr>,
+) -> Result<ExpressionAttributes, SemanticError> {
+    let attributes = expr(Rc::clone(&scope), Rc::clone(&context), exp)?;
+    let attributes = if let Type::String(_) = attributes.typ {
+        attributes.into_assignable()?
+    } else {
+        attributes.into_loaded()?
+    };
+
+    context.borrow_mut().add_expression(exp, attributes.clone());
+
+    Ok(attributes)
+}
+
 /// Gather context information for expressions and check for type errors.
 ///
 /// Also ensures that the expression is in the type's assigment location.
@@ -203,10 +320,31 @@ pub fn slice_index(
 
 fn expr_name(
     scope: Shared<BlockScope>,
+    context: Shared<Context>,
     exp: &Spanned<fe::Expr>,
 ) -> Result<ExpressionAttributes, SemanticError> {
     if let fe::Expr::Name(name) = exp.node {
-        return match scope.borrow().get_variable_def(name) {
+        let variable_def = scope.borrow().get_variable_def(name);
+        if variable_def.is_none() {
+            if let Some(constant) = scope.borrow().contract_constant_def(name) {
+                context.borrow_mut().add_constant(exp, constant.value);
+                return Ok(ExpressionAttributes::new(
+                    Type::Base(constant.typ),
+                    Location::Value,
+                ));
+            }
+        }
+
+        if let Some(typ) = variable_def.clone() {
+            if scope.borrow().is_storage_ref(name) {
+                return Ok(ExpressionAttributes::new(
+                    typ.into(),
+                    Location::Storage { nonce: None },
+                ));
+            }
+        }
+
+        return match variable_def {
             Some(FixedSize::Base(base)) => {
                 Ok(ExpressionAttributes::new(Type::Base(base), Location::Value))
             }
@@ -218,16 +356,27 @@ fn expr_name(
                 Type::Array(array),
                 Location::Memory,
             )),
+            Some(FixedSize::DynamicArray(array)) => Ok(ExpressionAttributes::new(
+                Type::DynamicArray(array),
+                Location::Memory,
+            )),
             Some(FixedSize::String(string)) => Ok(ExpressionAttributes::new(
                 Type::String(string),
                 Location::Memory,
             )),
-            Some(FixedSize::Tuple(_)) => unimplemented!(),
+            Some(FixedSize::Tuple(tuple)) => Ok(ExpressionAttributes::new(
+                Type::Tuple(tuple),
+                Location::Memory,
+            )),
             Some(FixedSize::Struct(val)) => Ok(ExpressionAttributes::new(
                 Type::Struct(val),
                 Location::Memory,
             )),
-            None => Err(SemanticError::undefined_value()),
+            None => Err(undefined_value(
+                exp.span,
+                name,
+                &scope.borrow().visible_names(),
+            )),
         };
     }
 
@@ -314,10 +463,16 @@ fn expr_attribute(
 
         // If the value is a name, check if it is a builtin object and attribute.
         if let fe::Expr::Name(name) = value.node {
-            match Object::from_str(name) {
+            let object = Object::from_str(name);
+            if object.is_ok() {
+                scope.borrow().validate_mutability(Mutability::View)?;
+            }
+
+            match object {
                 Ok(Object::Self_) => return expr_attribute_self(scope, attr),
                 Ok(Object::Block) => {
                     return match BlockField::from_str(attr.node) {
+                        Ok(BlockField::ChainId) => base_type(U256),
                         Ok(BlockField::Coinbase) => base_type(Base::Address),
                         Ok(BlockField::Difficulty) => base_type(U256),
                         Ok(BlockField::Number) => base_type(U256),
@@ -333,9 +488,12 @@ fn expr_attribute(
                 }
                 Ok(Object::Msg) => {
                     return match MsgField::from_str(attr.node) {
-                        Ok(MsgField::Data) => todo!(),
+                        Ok(MsgField::Data) => Ok(ExpressionAttributes::new(
+                            Type::DynamicArray(DynamicArray { inner: Base::Byte }),
+                            Location::Memory,
+                        )),
                         Ok(MsgField::Sender) => base_type(Base::Address),
-                        Ok(MsgField::Sig) => todo!(),
+                        Ok(MsgField::Sig) => base_type(U256),
                         Ok(MsgField::Value) => base_type(U256),
                         Err(_) => undefined_value_err,
                     }
@@ -349,6 +507,14 @@ fn expr_attribute(
                 }
                 Err(_) => {}
             }
+
+            // If the value is the name of an enum, the attribute is one of its variants.
+            if let Some(Type::Base(Base::Enum(val))) = scope.borrow().get_module_type_def(name) {
+                return match val.get_variant_index(attr.node) {
+                    Some(_) => base_type(Base::Enum(val)),
+                    None => undefined_value_err,
+                };
+            }
         }
 
         // We attempt to analyze the value as an expression. If this is succesfull, we
@@ -367,6 +533,14 @@ fn expr_attribute(
                     undefined_value_err
                 }
             }
+            // The balance of an account in wei.
+            ExpressionAttributes {
+                typ: Type::Base(Base::Address),
+                ..
+            } => match builtins::AddressField::from_str(attr.node) {
+                Ok(builtins::AddressField::Balance) => base_type(U256),
+                Err(_) => undefined_value_err,
+            },
             _ => undefined_value_err,
         };
     }
@@ -392,7 +566,27 @@ fn expr_attribute_self(
                 nonce: Some(field.nonce),
             },
         )),
-        None => Err(SemanticError::undefined_value()),
+        None => {
+            let contract_scope = scope.borrow().contract_scope();
+            let fields: Vec<String> = contract_scope.borrow().field_defs.keys().cloned().collect();
+            Err(undefined_value(attr.span, attr.node, &fields))
+        }
+    }
+}
+
+/// Create an `UndefinedValue` error for the name. If one of the candidates is
+/// spelled similarly, it is suggested as a replacement.
+fn undefined_value(span: Span, name: &str, candidates: &[String]) -> SemanticError {
+    let error = SemanticError::undefined_value();
+
+    match closest(name, candidates.iter().map(String::as_str)) {
+        Some(candidate) => error.with_suggestion(Suggestion {
+            message: "a value with a similar name exists".to_string(),
+            span,
+            replacement: candidate.to_string(),
+            machine_applicable: true,
+        }),
+        None => error,
     }
 }
 
@@ -401,12 +595,45 @@ fn expr_bin_operation(
     context: Shared<Context>,
     exp: &Spanned<fe::Expr>,
 ) -> Result<ExpressionAttributes, SemanticError> {
-    if let fe::Expr::BinOperation { left, op: _, right } = &exp.node {
-        let left_attributes = value_expr(Rc::clone(&scope), Rc::clone(&context), left)?;
-        let right_attributes = value_expr(Rc::clone(&scope), Rc::clone(&context), right)?;
+    if let fe::Expr::BinOperation { left, op, right } = &exp.node {
+        let left_attributes = operand_expr(Rc::clone(&scope), Rc::clone(&context), left)?;
+        let right_attributes = operand_expr(Rc::clone(&scope), Rc::clone(&context), right)?;
+
+        // strings of any size can be concatenated
+        if let (Type::String(left), Type::String(right)) =
+            (&left_attributes.typ, &right_attributes.typ)
+        {
+            if op.node != fe::BinOperator::Add {
+                return Err(SemanticError::type_error());
+            }
+
+            return Ok(ExpressionAttributes::new(
+                Type::String(FeString {
+                    max_size: left.max_size + right.max_size,
+                }),
+                Location::Memory,
+            ));
+        }
 
         validate_types_equal(&left_attributes, &right_attributes)?;
 
+        if let Type::Base(Base::Enum(_)) = left_attributes.typ {
+            return Err(SemanticError::type_error());
+        }
+
+        // fixed-point numbers only support basic arithmetic
+        if let Type::Base(Base::Fixed(_)) = left_attributes.typ {
+            if !matches!(
+                op.node,
+                fe::BinOperator::Add
+                    | fe::BinOperator::Sub
+                    | fe::BinOperator::Mult
+                    | fe::BinOperator::Div
+            ) {
+                return Err(SemanticError::type_error());
+            }
+        }
+
         // for now we assume these are the only possible attributes
         return Ok(ExpressionAttributes::new(
             right_attributes.typ,
@@ -427,15 +654,23 @@ fn expr_unary_operation(
 
         match &op.node {
             fe::UnaryOperator::USub => {
-                if !matches!(operand_attributes.typ, Type::Base(Base::Numeric(_))) {
-                    return Err(SemanticError::type_error());
-                }
-                // No matter what numeric type the operand was before, the minus symbol turns it
-                // into an I256 just like all positive values default to U256.
-                return Ok(ExpressionAttributes::new(
-                    Type::Base(Base::Numeric(Integer::I256)),
-                    Location::Value,
-                ));
+                return match operand_attributes.typ {
+                    // Negating a signed value keeps its type, so that negating the minimum
+                    // value overflows.
+                    Type::Base(Base::Numeric(integer)) if integer.is_signed() => {
+                        Ok(ExpressionAttributes::new(
+                            Type::Base(Base::Numeric(integer)),
+                            Location::Value,
+                        ))
+                    }
+                    // The minus symbol turns unsigned values into an I256 just like all
+                    // positive values default to U256.
+                    Type::Base(Base::Numeric(_)) => Ok(ExpressionAttributes::new(
+                        Type::Base(Base::Numeric(Integer::I256)),
+                        Location::Value,
+                    )),
+                    _ => Err(SemanticError::type_error()),
+                };
             }
             fe::UnaryOperator::Not => {
                 return if !matches!(operand_attributes.typ, Type::Base(Base::Bool)) {
@@ -454,6 +689,25 @@ fn expr_unary_operation(
     unreachable!()
 }
 
+/// Checks that a value is located in storage.
+fn validate_in_storage(location: &Location) -> Result<(), SemanticError> {
+    if let Location::Storage { .. } = location {
+        Ok(())
+    } else {
+        Err(SemanticError::cannot_move())
+    }
+}
+
+/// Checks that the value of an expression ends up in memory. Storage values
+/// need to be copied to memory explicitly.
+fn validate_in_memory(attributes: &ExpressionAttributes) -> Result<(), SemanticError> {
+    if attributes.final_location() == Location::Memory {
+        Ok(())
+    } else {
+        Err(SemanticError::cannot_move())
+    }
+}
+
 fn validate_types_equal(
     expression_a: &ExpressionAttributes,
     expression_b: &ExpressionAttributes,
@@ -477,7 +731,7 @@ pub fn call_arg(
 
             Ok(attributes)
         }
-        fe::CallArg::Kwarg(fe::Kwarg { name: _, value }) => expr(scope, context, value),
+        fe::CallArg::Kwarg(fe::Kwarg { name: _, value }) => assignable_expr(scope, context, value),
     }
 }
 
@@ -487,20 +741,35 @@ fn expr_call(
     exp: &Spanned<fe::Expr>,
 ) -> Result<ExpressionAttributes, SemanticError> {
     if let fe::Expr::Call { func, args } = &exp.node {
+        if let fe::Expr::Name(name) = &func.node {
+            let generic_struct_def = scope
+                .borrow()
+                .module_scope()
+                .borrow()
+                .get_generic_struct_def(name);
+
+            if let Some(def) = generic_struct_def {
+                return expr_call_generic_struct_constructor(scope, context, func, name, def, args);
+            }
+        }
+
         return match expr_call_type(Rc::clone(&scope), Rc::clone(&context), func)? {
             CallType::BuiltinFunction { func } => {
                 expr_call_builtin_function(scope, context, func, args)
             }
             CallType::TypeConstructor { typ } => {
-                expr_call_type_constructor(scope, context, typ, args)
+                expr_call_type_constructor(scope, context, func, typ, args)
             }
             CallType::SelfAttribute { func_name } => {
-                expr_call_self_attribute(scope, context, &func_name, args)
+                expr_call_self_attribute(scope, context, func, &func_name, args)
             }
             CallType::ValueAttribute => expr_call_value_attribute(scope, context, func, args),
             CallType::TypeAttribute { typ, func_name } => {
                 expr_call_type_attribute(scope, context, typ, &func_name, args)
             }
+            CallType::LibraryAttribute { library, func_name } => {
+                expr_call_library_attribute(scope, context, library, &func_name, args)
+            }
         };
     }
 
@@ -515,22 +784,148 @@ fn expr_call_builtin_function(
 ) -> Result<ExpressionAttributes, SemanticError> {
     let argument_attributes = expr_call_args(Rc::clone(&scope), Rc::clone(&context), args)?;
     match typ {
-        GlobalMethod::Keccak256 => {
+        // Byte arrays and strings are hashed in place, so they aren't copied.
+        GlobalMethod::Keccak256 | GlobalMethod::Sha256 => {
+            if argument_attributes.len() != 1 {
+                return Err(SemanticError::wrong_number_of_params());
+            }
+            if !matches!(
+                argument_attributes[0].typ,
+                Type::Array(Array {
+                    inner: Base::Byte,
+                    ..
+                }) | Type::String(_)
+            ) {
+                return Err(SemanticError::type_error());
+            }
+            Ok(ExpressionAttributes::new(Type::Base(U256), Location::Value))
+        }
+        GlobalMethod::Selfdestruct => {
+            scope.borrow().validate_mutability(Mutability::Mutable)?;
             if argument_attributes.len() != 1 {
                 return Err(SemanticError::wrong_number_of_params());
             }
+            if argument_attributes[0].typ != Type::Base(Base::Address) {
+                return Err(SemanticError::type_error());
+            }
+            Ok(ExpressionAttributes::new(
+                Type::Tuple(Tuple::empty()),
+                Location::Value,
+            ))
+        }
+        GlobalMethod::Ecrecover => {
+            let word = Type::Base(U256);
+            validate_builtin_args(
+                &argument_attributes,
+                &[word.clone(), word.clone(), word.clone(), word],
+            )?;
+            Ok(ExpressionAttributes::new(
+                Type::Base(Base::Address),
+                Location::Value,
+            ))
+        }
+        GlobalMethod::Modexp => {
+            let word = Type::Base(U256);
+            validate_builtin_args(&argument_attributes, &[word.clone(), word.clone(), word])?;
+            Ok(ExpressionAttributes::new(Type::Base(U256), Location::Value))
+        }
+        // The identity precompile returns a copy of any byte array.
+        GlobalMethod::Identity => {
+            if argument_attributes.len() != 1 {
+                return Err(SemanticError::wrong_number_of_params());
+            }
+            let typ = argument_attributes[0].typ.clone();
             if !matches!(
-                expression_attributes_to_types(argument_attributes).first(),
-                Some(Type::Array(Array {
+                typ,
+                Type::Array(Array {
                     inner: Base::Byte,
                     ..
-                }))
+                })
             ) {
                 return Err(SemanticError::type_error());
             }
+            validate_builtin_args(&argument_attributes, &[typ.clone()])?;
+            Ok(ExpressionAttributes::new(typ, Location::Memory))
+        }
+        GlobalMethod::Blake2f => {
+            let bytes = |size| {
+                Type::Array(Array {
+                    inner: Base::Byte,
+                    size,
+                })
+            };
+            validate_builtin_args(
+                &argument_attributes,
+                &[
+                    Type::Base(U256),
+                    bytes(64),
+                    bytes(128),
+                    bytes(16),
+                    Type::Base(Base::Bool),
+                ],
+            )?;
+            Ok(ExpressionAttributes::new(bytes(64), Location::Memory))
+        }
+        // Ranges can only be iterated over by `for` loops.
+        GlobalMethod::Range => Err(SemanticError::type_error()),
+        // Validates inputs and reverts with an optional reason.
+        GlobalMethod::Require => {
+            match argument_attributes.get(1).map(|attributes| &attributes.typ) {
+                None => validate_builtin_args(&argument_attributes, &[Type::Base(Base::Bool)])?,
+                Some(typ @ Type::String(_)) => validate_builtin_args(
+                    &argument_attributes,
+                    &[Type::Base(Base::Bool), typ.clone()],
+                )?,
+                Some(_) => return Err(SemanticError::type_error()),
+            }
+            Ok(ExpressionAttributes::new(
+                Type::Tuple(Tuple::empty()),
+                Location::Value,
+            ))
+        }
+        // The domain of EIP-712 signatures is given by the name and version of
+        // the signing domain, the current chain and the calling contract.
+        GlobalMethod::Eip712DomainSeparator => {
+            if argument_attributes.len() != 2 {
+                return Err(SemanticError::wrong_number_of_params());
+            }
+            for attributes in argument_attributes.iter() {
+                if !matches!(attributes.typ, Type::String(_)) {
+                    return Err(SemanticError::type_error());
+                }
+                validate_in_memory(attributes)?;
+            }
             Ok(ExpressionAttributes::new(Type::Base(U256), Location::Value))
         }
+        // The digest that is signed is computed from a domain separator and
+        // the hash of a struct.
+        GlobalMethod::Eip712Digest => {
+            validate_builtin_args(&argument_attributes, &[Type::Base(U256), Type::Base(U256)])?;
+            Ok(ExpressionAttributes::new(Type::Base(U256), Location::Value))
+        }
+    }
+}
+
+/// Checks that the arguments of a builtin function have the given types and
+/// that all arguments of reference types are in memory.
+fn validate_builtin_args(
+    argument_attributes: &[ExpressionAttributes],
+    param_types: &[Type],
+) -> Result<(), SemanticError> {
+    if argument_attributes.len() != param_types.len() {
+        return Err(SemanticError::wrong_number_of_params());
+    }
+
+    for (attributes, typ) in argument_attributes.iter().zip(param_types) {
+        if &attributes.typ != typ {
+            return Err(SemanticError::type_error());
+        }
+        if !matches!(typ, Type::Base(_)) {
+            validate_in_memory(attributes)?;
+        }
     }
+
+    Ok(())
 }
 
 fn expr_call_struct_constructor(
@@ -542,6 +937,56 @@ fn expr_call_struct_constructor(
     validate_are_kw_args(&args.node)?;
     let argument_attributes = expr_call_args(Rc::clone(&scope), Rc::clone(&context), args)?;
 
+    struct_constructor_attributes(typ, argument_attributes)
+}
+
+/// Instantiates a generic struct for the types of the constructor arguments.
+fn expr_call_generic_struct_constructor(
+    scope: Shared<BlockScope>,
+    context: Shared<Context>,
+    func: &Spanned<fe::Expr>,
+    name: &str,
+    def: GenericStructDef,
+    args: &Spanned<Vec<Spanned<fe::CallArg>>>,
+) -> Result<ExpressionAttributes, SemanticError> {
+    validate_are_kw_args(&args.node)?;
+    let argument_attributes = expr_call_args(Rc::clone(&scope), Rc::clone(&context), args)?;
+
+    if def.fields.len() != argument_attributes.len() {
+        return Err(SemanticError::wrong_number_of_params());
+    }
+
+    let module_scope = scope.borrow().module_scope();
+    let argument_types = expression_attributes_to_types(argument_attributes.clone());
+    let type_args = infer_type_args(
+        &module_scope.borrow(),
+        &def.type_params,
+        def.fields
+            .iter()
+            .map(|(_, template)| template)
+            .zip(argument_types.iter()),
+    )?;
+
+    let instance_name = structs::struct_instance(Rc::clone(&module_scope), name, type_args)?;
+    let instance = module_scope.borrow().get_type_def(&instance_name);
+    if let Some(Type::Struct(val)) = instance {
+        context.borrow_mut().add_call(
+            func,
+            CallType::TypeConstructor {
+                typ: Type::Struct(val.clone()),
+            },
+        );
+
+        return struct_constructor_attributes(val, argument_attributes);
+    }
+
+    unreachable!()
+}
+
+fn struct_constructor_attributes(
+    typ: Struct,
+    argument_attributes: Vec<ExpressionAttributes>,
+) -> Result<ExpressionAttributes, SemanticError> {
     if fixed_sizes_to_types(typ.get_field_types())
         != expression_attributes_to_types(argument_attributes)
     {
@@ -557,6 +1002,7 @@ fn expr_call_struct_constructor(
 fn expr_call_type_constructor(
     scope: Shared<BlockScope>,
     context: Shared<Context>,
+    func: &Spanned<fe::Expr>,
     typ: Type,
     args: &Spanned<Vec<Spanned<fe::CallArg>>>,
 ) -> Result<ExpressionAttributes, SemanticError> {
@@ -568,11 +1014,26 @@ fn expr_call_type_constructor(
         return Err(SemanticError::wrong_number_of_params());
     }
 
+    if let Type::Base(Base::Fixed(fixed)) = typ {
+        return expr_call_fixed_point_constructor(scope, context, fixed, &args.node[0]);
+    }
+
     let arg_attributes = call_arg(Rc::clone(&scope), Rc::clone(&context), &args.node[0])?;
 
     match typ {
         Type::String(ref fe_string) => {
-            validate_str_literal_fits_type(&args.node[0].node, &fe_string)?;
+            match &arg_attributes.typ {
+                // byte arrays in memory are converted to strings
+                Type::Array(Array {
+                    inner: Base::Byte,
+                    size,
+                }) => {
+                    if *size > fe_string.max_size {
+                        return Err(SemanticError::string_capacity_mismatch());
+                    }
+                }
+                _ => validate_str_literal_fits_type(&args.node[0].node, &fe_string)?,
+            }
             Ok(ExpressionAttributes::new(typ, Location::Memory))
         }
         Type::Contract(_) => {
@@ -582,9 +1043,43 @@ fn expr_call_type_constructor(
                 Ok(ExpressionAttributes::new(typ, Location::Value))
             }
         }
-        Type::Base(Base::Numeric(_)) => {
+        Type::Base(Base::Numeric(ref integer)) => {
+            // fixed-point numbers and named integers can be converted to their
+            // underlying integer
+            if let Type::Base(Base::Fixed(FixedPoint { integer: inner, .. }))
+            | Type::Base(Base::Named(NamedInteger { integer: inner, .. })) = &arg_attributes.typ
+            {
+                if inner != integer {
+                    return Err(SemanticError::type_error());
+                }
+                return Ok(ExpressionAttributes::new(typ, Location::Value));
+            }
+
             let num = validate_is_numeric_literal(&args.node[0].node)?;
-            validate_numeric_literal_fits_type(&num, &typ)?;
+            validate_numeric_literal_fits_type(&num, &typ).map_err(|error| {
+                // suggest a wider type if the literal fits one
+                match integer.smallest_fitting(&num) {
+                    Some(wider) => error.with_suggestion(Suggestion {
+                        message: "use an integer type that can hold the number".to_string(),
+                        span: func.span,
+                        replacement: <&str>::from(wider).to_lowercase(),
+                        machine_applicable: false,
+                    }),
+                    None => error,
+                }
+            })?;
+            Ok(ExpressionAttributes::new(typ, Location::Value))
+        }
+        Type::Base(Base::Named(ref named)) => {
+            // named integers are created from literals or from their
+            // underlying integer
+            if arg_attributes.typ != Type::Base(Base::Numeric(named.integer.clone())) {
+                let num = validate_is_numeric_literal(&args.node[0].node)?;
+                validate_numeric_literal_fits_type(
+                    &num,
+                    &Type::Base(Base::Numeric(named.integer.clone())),
+                )?;
+            }
             Ok(ExpressionAttributes::new(typ, Location::Value))
         }
         Type::Base(Base::Address) => {
@@ -602,6 +1097,37 @@ fn expr_call_type_constructor(
     }
 }
 
+/// Gathers context information for a fixed-point constructor.
+///
+/// Decimal literals are scaled at compile time, any other argument must be a
+/// value of the underlying integer type and is scaled at runtime.
+fn expr_call_fixed_point_constructor(
+    scope: Shared<BlockScope>,
+    context: Shared<Context>,
+    fixed: FixedPoint,
+    arg: &Spanned<fe::CallArg>,
+) -> Result<ExpressionAttributes, SemanticError> {
+    let attributes =
+        ExpressionAttributes::new(Type::Base(Base::Fixed(fixed.clone())), Location::Value);
+
+    let literal = validate_is_numeric_literal(&arg.node);
+    if let (Ok(num), fe::CallArg::Arg(value)) = (literal, &arg.node) {
+        let scaled = fixed.scale_literal(&num)?;
+        let spanned = spanned_expression(&arg.span, value);
+        let mut context = context.borrow_mut();
+        context.add_expression(&spanned, attributes.clone());
+        context.add_constant(&spanned, ConstValue::Int(scaled));
+        return Ok(attributes);
+    }
+
+    let arg_attributes = call_arg(scope, context, arg)?;
+    if arg_attributes.typ != Type::Base(Base::Numeric(fixed.integer)) {
+        return Err(SemanticError::type_error());
+    }
+
+    Ok(attributes)
+}
+
 fn expr_call_args(
     scope: Shared<BlockScope>,
     context: Shared<Context>,
@@ -616,42 +1142,82 @@ fn expr_call_args(
 fn expr_call_self_attribute(
     scope: Shared<BlockScope>,
     context: Shared<Context>,
+    func: &Spanned<fe::Expr>,
     func_name: &str,
     args: &Spanned<Vec<Spanned<fe::CallArg>>>,
 ) -> Result<ExpressionAttributes, SemanticError> {
-    if let Some(ContractFunctionDef {
+    let contract_scope = scope.borrow().contract_scope();
+
+    let generic_def = contract_scope.borrow().generic_function_def(func_name);
+    if let Some(generic_def) = generic_def {
+        let argument_attributes = expr_call_args(Rc::clone(&scope), Rc::clone(&context), args)?;
+        let instance_name = functions::func_instance(
+            Rc::clone(&contract_scope),
+            func_name,
+            generic_def,
+            &expression_attributes_to_types(argument_attributes.clone()),
+        )?;
+
+        // The call is mapped to the instance of the generic function.
+        context.borrow_mut().add_call(
+            func,
+            CallType::SelfAttribute {
+                func_name: instance_name.clone(),
+            },
+        );
+
+        let instance_def = contract_scope
+            .borrow()
+            .function_def(&instance_name)
+            .unwrap_or_else(|| panic!("Failed to lookup function instance {}", instance_name));
+
+        return self_call_attributes(scope, instance_def, argument_attributes);
+    }
+
+    let func_def = contract_scope.borrow().function_def(func_name);
+    if let Some(func_def) = func_def {
+        let argument_attributes = expr_call_args(Rc::clone(&scope), Rc::clone(&context), args)?;
+
+        return self_call_attributes(scope, func_def, argument_attributes);
+    }
+
+    Err(SemanticError::undefined_value())
+}
+
+/// The caller may only call functions that access the state at most as much
+/// as itself.
+fn self_call_attributes(
+    scope: Shared<BlockScope>,
+    func_def: ContractFunctionDef,
+    argument_attributes: Vec<ExpressionAttributes>,
+) -> Result<ExpressionAttributes, SemanticError> {
+    let ContractFunctionDef {
         is_public: _,
+        is_payable: _,
         param_types,
         return_type,
-        scope: _,
-    }) = scope
-        .borrow()
-        .contract_scope()
-        .borrow()
-        .function_def(func_name)
-    {
-        let argument_attributes = expr_call_args(Rc::clone(&scope), Rc::clone(&context), args)?;
+        scope: func_scope,
+    } = func_def;
 
-        if param_types.len() != argument_attributes.len() {
-            return Err(SemanticError::wrong_number_of_params());
-        }
+    let mutability = func_scope.borrow().mutability;
+    scope.borrow().validate_mutability(mutability)?;
 
-        if fixed_sizes_to_types(param_types) != expression_attributes_to_types(argument_attributes)
-        {
-            return Err(SemanticError::type_error());
-        }
+    if param_types.len() != argument_attributes.len() {
+        return Err(SemanticError::wrong_number_of_params());
+    }
 
-        let return_location = match &return_type {
-            FixedSize::Base(_) => Location::Value,
-            _ => Location::Memory,
-        };
-        Ok(ExpressionAttributes::new(
-            return_type.into(),
-            return_location,
-        ))
-    } else {
-        Err(SemanticError::undefined_value())
+    if fixed_sizes_to_types(param_types) != expression_attributes_to_types(argument_attributes) {
+        return Err(SemanticError::type_error());
     }
+
+    let return_location = match &return_type {
+        FixedSize::Base(_) => Location::Value,
+        _ => Location::Memory,
+    };
+    Ok(ExpressionAttributes::new(
+        return_type.into(),
+        return_location,
+    ))
 }
 
 fn expr_call_value_attribute(
@@ -667,39 +1233,210 @@ fn expr_call_value_attribute(
             return expr_call_contract_attribute(scope, context, contract, attr.node, args);
         }
 
-        // for now all of these function expect 0 arguments
+        if value_attributes.typ == Type::Base(Base::Address) {
+            if let Ok(method) = builtins::AddressMethod::from_str(attr.node) {
+                return expr_call_address_method(scope, context, method, args);
+            }
+        }
+
+        let method =
+            ValueMethod::from_str(attr.node).map_err(|_| SemanticError::undefined_value())?;
+
+        if method == ValueMethod::Push {
+            return expr_call_push(scope, context, value_attributes, args);
+        }
+
+        // all other functions expect 0 arguments
         if !args.node.is_empty() {
             return Err(SemanticError::wrong_number_of_params());
         }
 
-        return match ValueMethod::from_str(attr.node)
-            .map_err(|_| SemanticError::undefined_value())?
-        {
+        return match method {
             ValueMethod::Clone => value_attributes.into_cloned(),
             ValueMethod::ToMem => value_attributes.into_cloned_from_sto(),
-            ValueMethod::AbiEncode => match &value_attributes.typ {
-                Type::Struct(struct_) => {
-                    if value_attributes.final_location() != Location::Memory {
-                        todo!("encode structs from storage")
-                    }
+            ValueMethod::AbiEncode => {
+                let size = match &value_attributes.typ {
+                    Type::Struct(struct_) => struct_.get_num_fields() * 32,
+                    Type::Tuple(tuple) if !tuple.is_empty() => tuple.items.len() * 32,
+                    Type::Base(_) => 32,
+                    Type::Array(array) => match array.abi_type() {
+                        AbiType::Array {
+                            inner,
+                            size: AbiArraySize::Static { size },
+                        } => match *inner {
+                            AbiType::Uint {
+                                size: AbiUintSize { padded_size, .. },
+                            } => (padded_size * size + 31) / 32 * 32,
+                            _ => return Err(SemanticError::type_error()),
+                        },
+                        _ => return Err(SemanticError::type_error()),
+                    },
+                    _ => return Err(SemanticError::type_error()),
+                };
+
+                scope
+                    .borrow()
+                    .contract_scope()
+                    .borrow_mut()
+                    .add_abi_encoding(FixedSize::try_from(value_attributes.typ.clone())?);
+
+                // Base values in storage are loaded before being encoded. Other
+                // values have to be copied to memory explicitly with `to_mem`.
+                if let Location::Storage { .. } = value_attributes.final_location() {
+                    let moved = value_attributes.clone().into_loaded()?;
+                    context.borrow_mut().add_expression(value, moved);
+                }
 
+                Ok(ExpressionAttributes::new(
+                    Type::Array(Array {
+                        inner: Base::Byte,
+                        size,
+                    }),
+                    Location::Memory,
+                ))
+            }
+            ValueMethod::AbiEncodePacked => todo!(),
+            ValueMethod::Len => match &value_attributes.typ {
+                Type::String(_) => {
+                    validate_in_memory(&value_attributes)?;
+                    Ok(ExpressionAttributes::new(Type::Base(U256), Location::Value))
+                }
+                Type::DynamicArray(_) => {
+                    Ok(ExpressionAttributes::new(Type::Base(U256), Location::Value))
+                }
+                _ => Err(SemanticError::type_error()),
+            },
+            ValueMethod::Push => unreachable!(),
+            // The hash of a struct is computed from its fields in memory.
+            ValueMethod::Eip712Hash => match &value_attributes.typ {
+                Type::Struct(struct_) if struct_.is_eip712() => {
+                    validate_in_memory(&value_attributes)?;
+                    Ok(ExpressionAttributes::new(Type::Base(U256), Location::Value))
+                }
+                _ => Err(SemanticError::type_error()),
+            },
+            ValueMethod::Pop => match value_attributes.typ {
+                Type::DynamicArray(array) => {
+                    validate_in_storage(&value_attributes.location)?;
+                    scope.borrow().validate_mutability(Mutability::Mutable)?;
+                    Ok(ExpressionAttributes::new(
+                        Type::Base(array.inner),
+                        Location::Value,
+                    ))
+                }
+                _ => Err(SemanticError::type_error()),
+            },
+            ValueMethod::ToBytes => match &value_attributes.typ {
+                Type::String(string) => {
+                    validate_in_memory(&value_attributes)?;
                     Ok(ExpressionAttributes::new(
                         Type::Array(Array {
                             inner: Base::Byte,
-                            size: struct_.get_num_fields() * 32,
+                            size: string.max_size,
                         }),
                         Location::Memory,
                     ))
                 }
-                _ => todo!(),
+                _ => Err(SemanticError::type_error()),
             },
-            ValueMethod::AbiEncodePacked => todo!(),
         };
     }
 
     unreachable!()
 }
 
+/// Sends an amount of wei to an address or makes a raw call to it.
+///
+/// `send` returns whether the transfer succeeded, while `transfer` reverts if
+/// it failed. The raw calls take the gas to forward, the value for
+/// `raw_call`, and byte arrays in memory for the input and output data. They
+/// return whether the call succeeded and may only be used in functions marked
+/// `@unsafe`.
+fn expr_call_address_method(
+    scope: Shared<BlockScope>,
+    context: Shared<Context>,
+    method: builtins::AddressMethod,
+    args: &Spanned<Vec<Spanned<fe::CallArg>>>,
+) -> Result<ExpressionAttributes, SemanticError> {
+    let is_unsafe = scope.borrow().function_scope().borrow().is_unsafe;
+    scope.borrow().validate_mutability(match method {
+        builtins::AddressMethod::RawStaticcall => Mutability::View,
+        _ => Mutability::Mutable,
+    })?;
+    let argument_attributes = expr_call_args(scope, context, args)?;
+
+    let num_words = match method {
+        builtins::AddressMethod::Send | builtins::AddressMethod::Transfer => 1,
+        builtins::AddressMethod::RawCall => 2,
+        builtins::AddressMethod::RawStaticcall | builtins::AddressMethod::RawDelegatecall => 1,
+    };
+    let num_buffers = match method {
+        builtins::AddressMethod::Send | builtins::AddressMethod::Transfer => 0,
+        _ => 2,
+    };
+
+    if num_buffers > 0 && !is_unsafe {
+        return Err(SemanticError::unsafe_call());
+    }
+
+    if argument_attributes.len() != num_words + num_buffers {
+        return Err(SemanticError::wrong_number_of_params());
+    }
+
+    let (words, buffers) = argument_attributes.split_at(num_words);
+    if words.iter().any(|word| word.typ != Type::Base(U256)) {
+        return Err(SemanticError::type_error());
+    }
+    for buffer in buffers {
+        if !matches!(
+            buffer.typ,
+            Type::Array(Array {
+                inner: Base::Byte,
+                ..
+            })
+        ) {
+            return Err(SemanticError::type_error());
+        }
+        validate_in_memory(buffer)?;
+    }
+
+    Ok(match method {
+        builtins::AddressMethod::Transfer => {
+            ExpressionAttributes::new(Type::Tuple(Tuple::empty()), Location::Value)
+        }
+        _ => ExpressionAttributes::new(Type::Base(Base::Bool), Location::Value),
+    })
+}
+
+/// Appends a value to a dynamically sized array in storage.
+fn expr_call_push(
+    scope: Shared<BlockScope>,
+    context: Shared<Context>,
+    value_attributes: ExpressionAttributes,
+    args: &Spanned<Vec<Spanned<fe::CallArg>>>,
+) -> Result<ExpressionAttributes, SemanticError> {
+    let array = match value_attributes.typ {
+        Type::DynamicArray(array) => array,
+        _ => return Err(SemanticError::type_error()),
+    };
+    validate_in_storage(&value_attributes.location)?;
+    scope.borrow().validate_mutability(Mutability::Mutable)?;
+
+    if args.node.len() != 1 {
+        return Err(SemanticError::wrong_number_of_params());
+    }
+
+    let arg_attributes = call_arg(scope, context, &args.node[0])?;
+    if arg_attributes.typ != Type::Base(array.inner) {
+        return Err(SemanticError::type_error());
+    }
+
+    Ok(ExpressionAttributes::new(
+        Type::Tuple(Tuple::empty()),
+        Location::Memory,
+    ))
+}
+
 fn expr_call_type_attribute(
     scope: Shared<BlockScope>,
     context: Shared<Context>,
@@ -709,54 +1446,121 @@ fn expr_call_type_attribute(
 ) -> Result<ExpressionAttributes, SemanticError> {
     let arg_attributes = expr_call_args(Rc::clone(&scope), context, args)?;
 
+    // traits describe deployed contracts and have no code of their own, so
+    // the only thing they have is an interface ID
+    if let Type::Contract(contract) = &typ {
+        let module_scope = scope.borrow().module_scope();
+        if module_scope
+            .borrow()
+            .get_trait_def(&contract.name)
+            .is_some()
+            && ContractTypeMethod::from_str(func_name) != Ok(ContractTypeMethod::InterfaceId)
+        {
+            return Err(SemanticError::type_error());
+        }
+    }
+
     match (typ, ContractTypeMethod::from_str(func_name)) {
-        (Type::Contract(contract), Ok(ContractTypeMethod::Create2)) => {
-            if arg_attributes.len() != 2 {
+        (Type::Contract(_), Ok(ContractTypeMethod::InterfaceId)) => {
+            validate_builtin_args(&arg_attributes, &[])?;
+            Ok(ExpressionAttributes::new(Type::Base(U256), Location::Value))
+        }
+        (Type::Contract(contract), Ok(method)) => {
+            // `create` takes the value sent to the new contract and `create2` also
+            // takes a salt. The remaining arguments are passed to `__init__`.
+            let num_options = match method {
+                ContractTypeMethod::Create => 1,
+                ContractTypeMethod::Create2 => 2,
+                ContractTypeMethod::InterfaceId => unreachable!(),
+            };
+
+            if arg_attributes.len() != num_options + contract.init_params.len() {
                 return Err(SemanticError::wrong_number_of_params());
             }
 
-            if matches!(
-                (&arg_attributes[0].typ, &arg_attributes[1].typ),
-                (Type::Base(Base::Numeric(_)), Type::Base(Base::Numeric(_)))
-            ) {
-                scope
-                    .borrow()
-                    .contract_scope()
-                    .borrow_mut()
-                    .add_created_contract(&contract.name);
-
-                Ok(ExpressionAttributes::new(
-                    Type::Contract(contract),
-                    Location::Value,
-                ))
-            } else {
-                Err(SemanticError::type_error())
-            }
-        }
-        (Type::Contract(contract), Ok(ContractTypeMethod::Create)) => {
-            if arg_attributes.len() != 1 {
-                return Err(SemanticError::wrong_number_of_params());
+            let (options, init_args) = arg_attributes.split_at(num_options);
+            if !options
+                .iter()
+                .all(|option| matches!(option.typ, Type::Base(Base::Numeric(_))))
+                || fixed_sizes_to_types(contract.init_params.clone())
+                    != expression_attributes_to_types(init_args.to_vec())
+            {
+                return Err(SemanticError::type_error());
             }
 
-            if matches!(&arg_attributes[0].typ, Type::Base(Base::Numeric(_))) {
-                scope
-                    .borrow()
-                    .contract_scope()
-                    .borrow_mut()
-                    .add_created_contract(&contract.name);
+            scope.borrow().validate_mutability(Mutability::Mutable)?;
+            scope
+                .borrow()
+                .contract_scope()
+                .borrow_mut()
+                .add_created_contract(&contract.name);
 
-                Ok(ExpressionAttributes::new(
-                    Type::Contract(contract),
-                    Location::Value,
-                ))
-            } else {
-                Err(SemanticError::type_error())
-            }
+            Ok(ExpressionAttributes::new(
+                Type::Contract(contract),
+                Location::Value,
+            ))
         }
+        (Type::Struct(struct_), _) => match builtins::StructTypeMethod::from_str(func_name) {
+            Ok(builtins::StructTypeMethod::AbiDecode) => {
+                expr_call_abi_decode(scope, struct_, arg_attributes)
+            }
+            Ok(builtins::StructTypeMethod::Eip712TypeHash) => {
+                if !struct_.is_eip712() {
+                    return Err(SemanticError::type_error());
+                }
+                validate_builtin_args(&arg_attributes, &[])?;
+                Ok(ExpressionAttributes::new(Type::Base(U256), Location::Value))
+            }
+            Err(_) => Err(SemanticError::undefined_value()),
+        },
         _ => Err(SemanticError::undefined_value()),
     }
 }
 
+/// Decodes a struct from a byte array in memory. The array must be large
+/// enough to hold the encoding of every field.
+fn expr_call_abi_decode(
+    scope: Shared<BlockScope>,
+    struct_: Struct,
+    arg_attributes: Vec<ExpressionAttributes>,
+) -> Result<ExpressionAttributes, SemanticError> {
+    if arg_attributes.len() != 1 {
+        return Err(SemanticError::wrong_number_of_params());
+    }
+
+    let data = &arg_attributes[0];
+    match &data.typ {
+        Type::Array(Array {
+            inner: Base::Byte,
+            size,
+        }) if *size >= struct_.get_num_fields() * 32 => {}
+        _ => return Err(SemanticError::type_error()),
+    }
+    validate_in_memory(data)?;
+
+    // Each field is decoded on its own, so that values that are out of range
+    // for the field's type revert.
+    let field_types = struct_.get_field_types();
+    if !field_types
+        .iter()
+        .all(|typ| matches!(typ, FixedSize::Base(_)))
+    {
+        return Err(SemanticError::type_error());
+    }
+    for typ in field_types {
+        scope
+            .borrow()
+            .contract_scope()
+            .borrow_mut()
+            .add_abi_decoding(typ);
+    }
+
+    Ok(ExpressionAttributes::new(
+        Type::Struct(struct_),
+        Location::Memory,
+    ))
+}
+
 fn expr_call_contract_attribute(
     scope: Shared<BlockScope>,
     context: Shared<Context>,
@@ -777,6 +1581,9 @@ fn expr_call_contract_attribute(
             todo!("external call string returns")
         }
 
+        // External calls may change the state of the called contract.
+        scope.borrow().validate_mutability(Mutability::Mutable)?;
+
         let argument_attributes = args
             .node
             .iter()
@@ -799,7 +1606,54 @@ fn expr_call_contract_attribute(
         ));
     }
 
-    unreachable!()
+    // private functions aren't part of the contract type
+    Err(SemanticError::undefined_value())
+}
+
+/// Calls a public function of a library. Library functions run in the context
+/// of the calling contract, but they are pure so there is no state they could
+/// change.
+fn expr_call_library_attribute(
+    scope: Shared<BlockScope>,
+    context: Shared<Context>,
+    library: Contract,
+    func_name: &str,
+    args: &Spanned<Vec<Spanned<fe::CallArg>>>,
+) -> Result<ExpressionAttributes, SemanticError> {
+    let function = library
+        .functions
+        .iter()
+        .find(|function| function.name == func_name)
+        .ok_or_else(SemanticError::undefined_value)?;
+    let return_type = function.return_type.to_owned();
+
+    // the return data of a `delegatecall` isn't decoded into strings yet
+    if matches!(return_type, FixedSize::String(_)) {
+        return Err(SemanticError::library_string_return());
+    }
+
+    let argument_attributes = expr_call_args(Rc::clone(&scope), context, args)?;
+
+    if function.param_types.len() != argument_attributes.len() {
+        return Err(SemanticError::wrong_number_of_params());
+    }
+
+    if fixed_sizes_to_types(function.param_types.clone())
+        != expression_attributes_to_types(argument_attributes)
+    {
+        return Err(SemanticError::type_error());
+    }
+
+    scope
+        .borrow()
+        .contract_scope()
+        .borrow_mut()
+        .add_library_call(&library.name);
+
+    Ok(ExpressionAttributes::new(
+        return_type.clone().into(),
+        Location::assign_location(return_type.into())?,
+    ))
 }
 
 fn expr_call_type(
@@ -826,6 +1680,30 @@ fn expr_name_call_type(
         "keccak256" => Ok(CallType::BuiltinFunction {
             func: GlobalMethod::Keccak256,
         }),
+        "sha256" => Ok(CallType::BuiltinFunction {
+            func: GlobalMethod::Sha256,
+        }),
+        "selfdestruct" => Ok(CallType::BuiltinFunction {
+            func: GlobalMethod::Selfdestruct,
+        }),
+        "ecrecover" => Ok(CallType::BuiltinFunction {
+            func: GlobalMethod::Ecrecover,
+        }),
+        "modexp" => Ok(CallType::BuiltinFunction {
+            func: GlobalMethod::Modexp,
+        }),
+        "identity" => Ok(CallType::BuiltinFunction {
+            func: GlobalMethod::Identity,
+        }),
+        "blake2f" => Ok(CallType::BuiltinFunction {
+            func: GlobalMethod::Blake2f,
+        }),
+        "range" => Ok(CallType::BuiltinFunction {
+            func: GlobalMethod::Range,
+        }),
+        "require" => Ok(CallType::BuiltinFunction {
+            func: GlobalMethod::Require,
+        }),
         "address" => Ok(CallType::TypeConstructor {
             typ: Type::Base(Base::Address),
         }),
@@ -871,8 +1749,26 @@ fn expr_name_call_type(
             ),
         }),
         value => {
+            let is_free_function = scope
+                .borrow()
+                .module_scope()
+                .borrow()
+                .free_function_names
+                .contains(value);
+
             if let Some(typ) = scope.borrow().get_module_type_def(value) {
                 Ok(CallType::TypeConstructor { typ })
+            } else if is_free_function {
+                // Functions outside of contracts are compiled into every
+                // contract that calls them, like its own private functions.
+                scope
+                    .borrow()
+                    .contract_scope()
+                    .borrow_mut()
+                    .add_free_function_call(value);
+                Ok(CallType::SelfAttribute {
+                    func_name: value.to_owned(),
+                })
             } else {
                 Err(SemanticError::undefined_value())
             }
@@ -899,6 +1795,18 @@ fn expr_attribute_call_type(
                 Err(_) => {}
             }
 
+            let module_scope = scope.borrow().module_scope();
+            if module_scope.borrow().is_library(name) {
+                return match module_scope.borrow().get_type_def(name) {
+                    Some(Type::Contract(library)) => Ok(CallType::LibraryAttribute {
+                        library,
+                        func_name: attr.node.to_string(),
+                    }),
+                    // the library is being analyzed and can't call itself
+                    _ => Err(SemanticError::undefined_value()),
+                };
+            }
+
             if let Some(typ) = scope.borrow().get_module_type_def(name) {
                 return Ok(CallType::TypeAttribute {
                     typ,
@@ -969,13 +1877,31 @@ fn expr_comp_operation(
     context: Shared<Context>,
     exp: &Spanned<fe::Expr>,
 ) -> Result<ExpressionAttributes, SemanticError> {
-    if let fe::Expr::CompOperation { left, op: _, right } = &exp.node {
-        // comparison operands should be moved to the stack
-        let left_attributes = value_expr(Rc::clone(&scope), Rc::clone(&context), left)?;
-        let right_attributes = value_expr(Rc::clone(&scope), Rc::clone(&context), right)?;
+    if let fe::Expr::CompOperation { left, op, right } = &exp.node {
+        let left_attributes = operand_expr(Rc::clone(&scope), Rc::clone(&context), left)?;
+        let right_attributes = operand_expr(Rc::clone(&scope), Rc::clone(&context), right)?;
+
+        // strings of any size can be checked for equality
+        if let (Type::String(_), Type::String(_)) = (&left_attributes.typ, &right_attributes.typ) {
+            if !matches!(op.node, fe::CompOperator::Eq | fe::CompOperator::NotEq) {
+                return Err(SemanticError::type_error());
+            }
+
+            return Ok(ExpressionAttributes::new(
+                Type::Base(Base::Bool),
+                Location::Value,
+            ));
+        }
 
         validate_types_equal(&left_attributes, &right_attributes)?;
 
+        // enum variants are not ordered, so they can only be checked for equality
+        if let Type::Base(Base::Enum(_)) = left_attributes.typ {
+            if !matches!(op.node, fe::CompOperator::Eq | fe::CompOperator::NotEq) {
+                return Err(SemanticError::type_error());
+            }
+        }
+
         // for now we assume these are the only possible attributes
         return Ok(ExpressionAttributes::new(
             Type::Base(Base::Bool),
@@ -1056,6 +1982,7 @@ mod tests {
     use crate::namespace::types::{
         Array,
         Base,
+        Enum,
         FixedSize,
         Integer,
         Map,
@@ -1081,6 +2008,14 @@ mod tests {
         ExpressionAttributes::new(Type::Base(Base::Numeric(Integer::U128)), Location::Value)
     }
 
+    fn i256_val() -> ExpressionAttributes {
+        ExpressionAttributes::new(Type::Base(Base::Numeric(Integer::I256)), Location::Value)
+    }
+
+    fn i8_val() -> ExpressionAttributes {
+        ExpressionAttributes::new(Type::Base(Base::Numeric(Integer::I8)), Location::Value)
+    }
+
     fn u256_sto_with_move() -> ExpressionAttributes {
         let mut attributes =
             ExpressionAttributes::new(Type::Base(U256), Location::Storage { nonce: None });
@@ -1088,6 +2023,17 @@ mod tests {
         attributes
     }
 
+    fn color() -> Enum {
+        let mut color = Enum::new("Color");
+        color.add_variant("Red");
+        color.add_variant("Green");
+        color
+    }
+
+    fn color_val() -> ExpressionAttributes {
+        ExpressionAttributes::new(Type::Base(Base::Enum(color())), Location::Value)
+    }
+
     fn addr_val() -> ExpressionAttributes {
         ExpressionAttributes::new(Type::Base(Base::Address), Location::Value)
     }
@@ -1193,6 +2139,23 @@ mod tests {
                 ("0", &u256_val()),
                 ("u128(0)", &u128_val()),
             ]
+        ),
+        case(
+            "(42, my_addr)",
+            &[
+                ("42", &u256_val()),
+                ("my_addr", &addr_val()),
+            ]
+        ),
+        case("-42", &[("42", &u256_val()), ("-42", &i256_val())]),
+        case("-my_i8", &[("my_i8", &i8_val()), ("-my_i8", &i8_val())]),
+        case("Color.Green", &[("Color.Green", &color_val())]),
+        case(
+            "Color.Red == Color.Green",
+            &[
+                ("Color.Red", &color_val()),
+                ("Color.Green", &color_val()),
+            ]
         )
     )]
     fn exprs(expression: &str, expected_attributes: &[(&str, &ExpressionAttributes)]) {
@@ -1201,6 +2164,10 @@ mod tests {
             .borrow_mut()
             .add_var("my_addr", FixedSize::Base(Base::Address))
             .unwrap();
+        scope
+            .borrow_mut()
+            .add_var("my_i8", FixedSize::Base(Base::Numeric(Integer::I8)))
+            .unwrap();
         scope
             .borrow_mut()
             .add_var(
@@ -1223,6 +2190,11 @@ mod tests {
                 }),
             )
             .unwrap();
+        scope
+            .borrow()
+            .module_scope()
+            .borrow_mut()
+            .add_type_def("Color", Type::Base(Base::Enum(color())));
 
         let context = analyze(scope, expression);
 
@@ -1,16 +1,28 @@
+use crate::builtins;
 use crate::errors::SemanticError;
+use crate::namespace::constants::ConstValue;
+use crate::namespace::generics::{
+    infer_type_args,
+    instance_name,
+    GenericFunctionDef,
+    TypeTemplate,
+};
 use crate::namespace::scopes::{
     BlockScope,
     BlockScopeType,
     ContractScope,
+    Mutability,
     Scope,
     Shared,
 };
 use crate::namespace::types::{
+    Array,
     Base,
+    DynamicArray,
     FixedSize,
     Tuple,
     Type,
+    U256,
 };
 use crate::traversal::_utils::{
     expression_attributes_to_types,
@@ -19,19 +31,26 @@ use crate::traversal::_utils::{
 };
 use crate::traversal::{
     assignments,
+    constants,
     declarations,
     expressions,
     types,
 };
 use crate::{
+    CallType,
     Context,
     ExpressionAttributes,
     FunctionAttributes,
     Location,
+    MatchPattern,
 };
 use fe_parser::ast as fe;
 use fe_parser::span::Spanned;
+use num_bigint::BigInt;
+use std::collections::HashSet;
+use std::convert::TryFrom;
 use std::rc::Rc;
+use std::str::FromStr;
 
 /// Gather context information for a function definition and check for type
 /// errors. Does not inspect the function body.
@@ -41,15 +60,125 @@ pub fn func_def(
     def: &Spanned<fe::ContractStmt>,
 ) -> Result<(), SemanticError> {
     if let fe::ContractStmt::FuncDef {
+        docs: _,
+        decorators,
         qual,
         name,
+        generics,
         args,
         return_type,
-        body: _,
+        return_names,
+        body,
     } = &def.node
     {
         let name = name.node;
+        let is_public = qual.is_some();
+
+        if body.iter().any(|stmt| stmt.node.is_placeholder()) {
+            return decorator_def(contract_scope, def);
+        }
+
+        let is_payable = decorators
+            .iter()
+            .any(|decorator| builtin_decorator(decorator) == Some(builtins::Decorator::Payable));
+        let is_unsafe = decorators
+            .iter()
+            .any(|decorator| builtin_decorator(decorator) == Some(builtins::Decorator::Unsafe));
+        let has_user_decorators = decorators
+            .iter()
+            .any(|decorator| builtin_decorator(decorator).is_none());
+        let mutabilities = decorators
+            .iter()
+            .filter_map(|decorator| match builtin_decorator(decorator) {
+                Some(builtins::Decorator::Pure) => Some(Mutability::Pure),
+                Some(builtins::Decorator::View) => Some(Mutability::View),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let mutability = mutabilities.first().copied().unwrap_or(Mutability::Mutable);
+
+        // Only public functions and `__init__` can receive ether.
+        if ((has_user_decorators || is_unsafe || !mutabilities.is_empty())
+            && (name == "__init__" || !generics.is_empty()))
+            || (is_payable && !is_public && name != "__init__")
+        {
+            return Err(SemanticError::invalid_decorator());
+        }
+
+        // A function has at most one mutability, and receiving ether changes
+        // the state.
+        if mutabilities.len() > 1 || (is_payable && !mutabilities.is_empty()) {
+            return Err(SemanticError::invalid_decorator());
+        }
+
+        // Tests are called without arguments by the test runner, which only
+        // checks whether they revert.
+        let is_test = decorators
+            .iter()
+            .any(|decorator| builtin_decorator(decorator) == Some(builtins::Decorator::Test));
+        if is_test
+            && (name == "__init__"
+                || is_payable
+                || !generics.is_empty()
+                || !args.is_empty()
+                || return_type.is_some())
+        {
+            return Err(SemanticError::invalid_decorator());
+        }
+
+        // The message of a deprecation is optional, but has to be a string.
+        // Only structs can be EIP-712 typed data and only contracts can
+        // support ERC-165 or be upgradeable.
+        if decorators.iter().any(|decorator| {
+            !is_valid_deprecation(decorator)
+                || matches!(
+                    builtin_decorator(decorator),
+                    Some(builtins::Decorator::Eip712)
+                        | Some(builtins::Decorator::Erc165)
+                        | Some(builtins::Decorator::Upgradeable)
+                )
+        }) {
+            return Err(SemanticError::invalid_decorator());
+        }
+
+        // Initializers are called through a proxy once, instead of `__init__`,
+        // and they change the state.
+        let is_initializer = decorators.iter().any(|decorator| {
+            builtin_decorator(decorator) == Some(builtins::Decorator::Initializer)
+        });
+        if is_initializer
+            && (name == "__init__"
+                || !is_public
+                || !generics.is_empty()
+                || has_user_decorators
+                || !mutabilities.is_empty())
+        {
+            return Err(SemanticError::invalid_decorator());
+        }
+
+        let mut names = HashSet::new();
+        if let Some(duplicate) = return_names.iter().find(|name| !names.insert(name.node)) {
+            return Err(SemanticError::already_defined().with_context(duplicate.span));
+        }
+
+        // The constructor's inputs are decoded from the deployment data, but
+        // nothing is returned to the deployer.
+        if name == "__init__" {
+            if let Some(return_type) = return_type {
+                return Err(SemanticError::type_error().with_context(return_type.span));
+            }
+            if !generics.is_empty() {
+                return Err(SemanticError::type_error());
+            }
+        }
+
+        if !generics.is_empty() {
+            return generic_func_def(contract_scope, name, is_public, generics, args, return_type);
+        }
+
         let function_scope = BlockScope::from_contract_scope(name, Rc::clone(&contract_scope));
+        function_scope.borrow_mut().is_unsafe = is_unsafe;
+        function_scope.borrow_mut().mutability = mutability;
 
         let param_types = args
             .iter()
@@ -62,10 +191,10 @@ pub fn func_def(
             .transpose()?
             .unwrap_or_else(|| Tuple::empty().into());
 
-        let is_public = qual.is_some();
         contract_scope.borrow_mut().add_function(
             name,
             is_public,
+            is_payable,
             param_types.clone(),
             return_type.clone(),
             Rc::clone(&function_scope),
@@ -85,42 +214,378 @@ pub fn func_def(
     unreachable!();
 }
 
+/// Gather the signature of a decorator, a function whose body contains the
+/// placeholder statement `_`. The placeholder marks the point where the body
+/// of the decorated function is run.
+fn decorator_def(
+    contract_scope: Shared<ContractScope>,
+    def: &Spanned<fe::ContractStmt>,
+) -> Result<(), SemanticError> {
+    if let fe::ContractStmt::FuncDef {
+        docs: _,
+        decorators,
+        qual,
+        name,
+        generics,
+        args,
+        return_type,
+        return_names: _,
+        body,
+    } = &def.node
+    {
+        let placeholders = body
+            .iter()
+            .filter(|stmt| stmt.node.is_placeholder())
+            .count();
+
+        // The return value belongs to the decorated function, so decorators
+        // may neither declare one nor return early.
+        if !decorators.is_empty()
+            || qual.is_some()
+            || !generics.is_empty()
+            || return_type.is_some()
+            || name.node == "__init__"
+            || builtins::Decorator::from_str(name.node).is_ok()
+            || placeholders != 1
+            || body.iter().any(contains_return)
+        {
+            return Err(SemanticError::invalid_decorator());
+        }
+
+        let decorator_scope =
+            BlockScope::from_contract_scope(name.node, Rc::clone(&contract_scope));
+
+        let param_types = args
+            .iter()
+            .map(|arg| func_def_arg(Rc::clone(&decorator_scope), arg))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        return contract_scope
+            .borrow_mut()
+            .add_decorator(name.node, param_types, decorator_scope);
+    }
+
+    unreachable!()
+}
+
+fn contains_return(stmt: &Spanned<fe::FuncStmt>) -> bool {
+    match &stmt.node {
+        fe::FuncStmt::Return { .. } => true,
+        fe::FuncStmt::For { body, or_else, .. }
+        | fe::FuncStmt::While { body, or_else, .. }
+        | fe::FuncStmt::If { body, or_else, .. } => {
+            body.iter().chain(or_else.iter()).any(contains_return)
+        }
+        fe::FuncStmt::Match { arms, .. } => arms
+            .iter()
+            .any(|arm| arm.node.body.iter().any(contains_return)),
+        _ => false,
+    }
+}
+
+/// Gather the signature of a generic function. Types in the signature are
+/// only resolved once the function is instantiated.
+fn generic_func_def(
+    contract_scope: Shared<ContractScope>,
+    name: &str,
+    is_public: bool,
+    generics: &[Spanned<&str>],
+    args: &[Spanned<fe::FuncDefArg>],
+    return_type: &Option<Spanned<fe::TypeDesc>>,
+) -> Result<(), SemanticError> {
+    // Public functions need a single ABI signature.
+    if is_public {
+        return Err(SemanticError::generic_public_function());
+    }
+
+    let def = GenericFunctionDef {
+        type_params: generics.iter().map(|param| param.node.to_owned()).collect(),
+        params: args
+            .iter()
+            .map(|arg| {
+                (
+                    arg.node.name.node.to_owned(),
+                    TypeTemplate::from(&arg.node.typ.node),
+                )
+            })
+            .collect(),
+        return_type: return_type
+            .as_ref()
+            .map(|typ| TypeTemplate::from(&typ.node)),
+    };
+
+    contract_scope.borrow_mut().add_generic_function(name, def)
+}
+
+/// Instantiates the generic function `name` for the types of the arguments
+/// that it is called with and returns the name of the instance.
+///
+/// The body of a new instance is analyzed after all other function bodies of
+/// the contract, see `func_instance_body`.
+pub fn func_instance(
+    contract_scope: Shared<ContractScope>,
+    name: &str,
+    def: GenericFunctionDef,
+    arg_types: &[Type],
+) -> Result<String, SemanticError> {
+    if def.params.len() != arg_types.len() {
+        return Err(SemanticError::wrong_number_of_params());
+    }
+
+    let module_scope = contract_scope.borrow().module_scope();
+    let type_args = infer_type_args(
+        &module_scope.borrow(),
+        &def.type_params,
+        def.params
+            .iter()
+            .map(|(_, template)| template)
+            .zip(arg_types.iter()),
+    )?;
+
+    let instance_name = instance_name(name, &type_args);
+    if contract_scope
+        .borrow()
+        .function_def(&instance_name)
+        .is_some()
+    {
+        return Ok(instance_name);
+    }
+
+    let function_scope =
+        BlockScope::from_contract_scope(&instance_name, Rc::clone(&contract_scope));
+    function_scope.borrow_mut().type_args = def
+        .type_params
+        .iter()
+        .cloned()
+        .zip(type_args.into_iter().map(Type::from))
+        .collect();
+
+    let param_types = def
+        .params
+        .iter()
+        .map(|(param_name, template)| {
+            let typ = FixedSize::try_from(types::resolve(
+                Scope::Block(Rc::clone(&function_scope)),
+                &template.type_desc(),
+            )?)?;
+            function_scope
+                .borrow_mut()
+                .add_var(param_name, typ.clone())?;
+            Ok(typ)
+        })
+        .collect::<Result<Vec<_>, SemanticError>>()?;
+
+    let return_type = def
+        .return_type
+        .as_ref()
+        .map(|template| {
+            FixedSize::try_from(types::resolve(
+                Scope::Block(Rc::clone(&function_scope)),
+                &template.type_desc(),
+            )?)
+        })
+        .transpose()?
+        .unwrap_or_else(|| Tuple::empty().into());
+
+    contract_scope.borrow_mut().add_function(
+        &instance_name,
+        false,
+        false,
+        param_types,
+        return_type,
+        function_scope,
+    )?;
+    contract_scope
+        .borrow_mut()
+        .add_pending_instance(name, &instance_name);
+
+    Ok(instance_name)
+}
+
 /// Gather context information for a function body and check for type errors.
+///
+/// `local_decorators` are the names of the decorators defined in the same
+/// contract or mixin as the function, which are the only ones it may use.
 pub fn func_body(
     contract_scope: Shared<ContractScope>,
     context: Shared<Context>,
     def: &Spanned<fe::ContractStmt>,
+    local_decorators: &[&str],
 ) -> Result<(), SemanticError> {
     if let fe::ContractStmt::FuncDef {
+        docs: _,
+        decorators,
         qual: _,
         name,
+        generics,
         args: _,
         return_type: _,
+        return_names: _,
         body,
     } = &def.node
     {
-        let host_func_def = contract_scope
-            .borrow()
-            .function_def(name.node)
-            .unwrap_or_else(|| panic!("Failed to lookup function definition for {}", &name.node));
+        // Generic functions are analyzed once for each of their instances.
+        if !generics.is_empty() {
+            return Ok(());
+        }
 
-        // If the return type is an empty tuple we do not have to validate any further
-        // at this point because both returning (explicit) or not returning (implicit
-        // return) are valid syntax.
-        // If the return type is anything else, we do need to ensure that all code paths
-        // return or revert.
-        if !host_func_def.return_type.is_empty_tuple() {
-            validate_all_paths_return_or_revert(&body)?
+        let decorator_def = contract_scope.borrow().decorator_def(name.node);
+        if let Some(decorator_def) = decorator_def {
+            for stmt in body.iter().filter(|stmt| !stmt.node.is_placeholder()) {
+                func_stmt(Rc::clone(&decorator_def.scope), Rc::clone(&context), stmt)?;
+            }
+            return Ok(());
         }
 
-        traverse_statements(Rc::clone(&host_func_def.scope), Rc::clone(&context), body)?;
+        for decorator in decorators
+            .iter()
+            .filter(|decorator| builtin_decorator(decorator).is_none())
+        {
+            decorator_call(
+                Rc::clone(&contract_scope),
+                Rc::clone(&context),
+                name.node,
+                local_decorators,
+                decorator,
+            )
+            .map_err(|error| error.with_context(decorator.span))?;
+        }
 
-        return Ok(());
+        return func_body_with_name(contract_scope, context, name.node, body);
+    }
+
+    unreachable!()
+}
+
+/// The builtin decorator that `decorator` refers to, if any.
+fn builtin_decorator(decorator: &Spanned<fe::Expr>) -> Option<builtins::Decorator> {
+    builtins::Decorator::of(&decorator.node)
+}
+
+/// Whether the decorator is either not `deprecated` or `deprecated` without
+/// arguments or with a string literal as the only argument.
+pub fn is_valid_deprecation(decorator: &Spanned<fe::Expr>) -> bool {
+    match (&decorator.node, builtin_decorator(decorator)) {
+        (fe::Expr::Call { .. }, Some(builtins::Decorator::Deprecated)) => {
+            builtins::Decorator::deprecation_message(&decorator.node).is_some()
+        }
+        _ => true,
+    }
+}
+
+/// The names of the decorators defined in a contract or mixin body.
+pub fn decorator_names<'a>(body: &[Spanned<fe::ContractStmt<'a>>]) -> Vec<&'a str> {
+    body.iter()
+        .filter_map(|stmt| match &stmt.node {
+            fe::ContractStmt::FuncDef { name, body, .. }
+                if body.iter().any(|stmt| stmt.node.is_placeholder()) =>
+            {
+                Some(name.node)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Check the arguments that the function `name` passes to one of its
+/// decorators.
+fn decorator_call(
+    contract_scope: Shared<ContractScope>,
+    context: Shared<Context>,
+    name: &str,
+    local_decorators: &[&str],
+    decorator: &Spanned<fe::Expr>,
+) -> Result<(), SemanticError> {
+    let (decorator_name, args) = match &decorator.node {
+        fe::Expr::Name(decorator_name) => (*decorator_name, &[][..]),
+        fe::Expr::Call { func, args } => match &func.node {
+            fe::Expr::Name(decorator_name) => (*decorator_name, args.node.as_slice()),
+            _ => return Err(SemanticError::invalid_decorator()),
+        },
+        _ => return Err(SemanticError::invalid_decorator()),
+    };
+
+    if !local_decorators.contains(&decorator_name) {
+        return Err(SemanticError::undefined_value());
+    }
+
+    let decorator_def = contract_scope
+        .borrow()
+        .decorator_def(decorator_name)
+        .ok_or_else(SemanticError::undefined_value)?;
+    let function_scope = contract_scope
+        .borrow()
+        .function_def(name)
+        .unwrap_or_else(|| panic!("Failed to lookup function definition for {}", name))
+        .scope;
+
+    let argument_attributes = args
+        .iter()
+        .map(|arg| expressions::call_arg(Rc::clone(&function_scope), Rc::clone(&context), arg))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if decorator_def.param_types.len() != argument_attributes.len() {
+        return Err(SemanticError::wrong_number_of_params());
+    }
+
+    if fixed_sizes_to_types(decorator_def.param_types)
+        != expression_attributes_to_types(argument_attributes)
+    {
+        return Err(SemanticError::type_error());
+    }
+
+    Ok(())
+}
+
+/// Gather context information for the body of a generic function instance
+/// and check for type errors.
+pub fn func_instance_body(
+    contract_scope: Shared<ContractScope>,
+    context: Shared<Context>,
+    def: &Spanned<fe::ContractStmt>,
+    instance_name: &str,
+) -> Result<FunctionAttributes, SemanticError> {
+    if let fe::ContractStmt::FuncDef { body, .. } = &def.node {
+        func_body_with_name(Rc::clone(&contract_scope), context, instance_name, body)?;
+
+        let instance_def = contract_scope
+            .borrow()
+            .function_def(instance_name)
+            .unwrap_or_else(|| panic!("Failed to lookup function instance {}", instance_name));
+
+        return Ok(FunctionAttributes {
+            name: instance_name.to_owned(),
+            param_types: instance_def.param_types,
+            return_type: instance_def.return_type,
+        });
     }
 
     unreachable!()
 }
 
+fn func_body_with_name(
+    contract_scope: Shared<ContractScope>,
+    context: Shared<Context>,
+    name: &str,
+    body: &[Spanned<fe::FuncStmt>],
+) -> Result<(), SemanticError> {
+    let host_func_def = contract_scope
+        .borrow()
+        .function_def(name)
+        .unwrap_or_else(|| panic!("Failed to lookup function definition for {}", name));
+
+    // If the return type is an empty tuple we do not have to validate any further
+    // at this point because both returning (explicit) or not returning (implicit
+    // return) are valid syntax.
+    // If the return type is anything else, we do need to ensure that all code paths
+    // return or revert.
+    if !host_func_def.return_type.is_empty_tuple() {
+        validate_all_paths_return_or_revert(&body)?
+    }
+
+    traverse_statements(Rc::clone(&host_func_def.scope), Rc::clone(&context), body)
+}
+
 fn traverse_statements(
     scope: Shared<BlockScope>,
     context: Shared<Context>,
@@ -157,6 +622,16 @@ fn validate_all_paths_return_or_revert(
                 return Ok(());
             }
         }
+
+        // Matches are exhaustive, so it's enough if every arm returns.
+        if let fe::FuncStmt::Match { arms, .. } = &statement.node {
+            if arms
+                .iter()
+                .all(|arm| validate_all_paths_return_or_revert(&arm.node.body).is_ok())
+            {
+                return Ok(());
+            }
+        }
     }
 
     Err(SemanticError::missing_return())
@@ -188,12 +663,14 @@ fn func_stmt(
         fe::FuncStmt::For { .. } => for_loop(scope, context, stmt),
         fe::FuncStmt::While { .. } => while_loop(scope, context, stmt),
         fe::FuncStmt::If { .. } => if_statement(scope, context, stmt),
+        fe::FuncStmt::Match { .. } => match_statement(scope, context, stmt),
         fe::FuncStmt::Assert { .. } => assert(scope, context, stmt),
         fe::FuncStmt::Expr { .. } => expr(scope, context, stmt),
         fe::FuncStmt::Pass => Ok(()),
         fe::FuncStmt::Break => break_statement(scope, context, stmt),
         fe::FuncStmt::Continue => continue_statement(scope, context, stmt),
-        fe::FuncStmt::Revert => Ok(()),
+        fe::FuncStmt::Revert { .. } => revert(scope, context, stmt),
+        fe::FuncStmt::Error(_) => Ok(()),
     }
     .map_err(|error| error.with_context(stmt.span))
 }
@@ -217,8 +694,8 @@ fn for_loop(
             }
             // Step 2: Create the for loop body scope.
             let body_scope = BlockScope::from_block_scope(BlockScopeType::Loop, Rc::clone(&scope));
-            // Step 3: Make sure iter is in the function scope & it should be an array.
-            let target_type = verify_is_array(scope, Rc::clone(&context), iter)?;
+            // Step 3: Make sure iter is in the function scope & it should be a range or an array.
+            let target_type = iter_item_type(scope, Rc::clone(&context), iter)?;
             let target_name = expressions::expr_name_str(target)?;
             body_scope.borrow_mut().add_var(target_name, target_type)?;
             // Step 4: Traverse the statements within the `for loop` body scope.
@@ -228,17 +705,52 @@ fn for_loop(
     }
 }
 
-fn verify_is_array(
+/// Returns the type of the items that a `for` loop iterates over. Loops run
+/// over `range(end)` or `range(start, end)` with `u256` bounds, or over the
+/// items of an array in memory.
+fn iter_item_type(
     scope: Shared<BlockScope>,
     context: Shared<Context>,
-    expr: &Spanned<fe::Expr>,
+    iter: &Spanned<fe::Expr>,
 ) -> Result<FixedSize, SemanticError> {
-    let attributes = expressions::expr(Rc::clone(&scope), Rc::clone(&context), &expr)?;
-    if let Type::Array(array) = attributes.typ {
-        // TODO: (SA) Could add a support for tuple
-        return Ok(FixedSize::Base(array.inner));
+    if let fe::Expr::Call { func, args } = &iter.node {
+        if let fe::Expr::Name(name) = func.node {
+            if builtins::GlobalMethod::from_str(name) == Ok(builtins::GlobalMethod::Range) {
+                if args.node.is_empty() || args.node.len() > 2 {
+                    return Err(SemanticError::wrong_number_of_params());
+                }
+                for arg in args.node.iter() {
+                    let attributes =
+                        expressions::call_arg(Rc::clone(&scope), Rc::clone(&context), arg)?;
+                    if attributes.typ != Type::Base(U256) {
+                        return Err(SemanticError::type_error());
+                    }
+                }
+
+                context.borrow_mut().add_call(
+                    func,
+                    CallType::BuiltinFunction {
+                        func: builtins::GlobalMethod::Range,
+                    },
+                );
+                return Ok(FixedSize::Base(U256));
+            }
+        }
     }
-    Err(SemanticError::type_error())
+
+    let attributes = expressions::expr(Rc::clone(&scope), Rc::clone(&context), &iter)?;
+    // TODO: (SA) Could add a support for tuple
+    let inner = match attributes.typ {
+        Type::Array(Array { ref inner, .. }) | Type::DynamicArray(DynamicArray { ref inner }) => {
+            inner.clone()
+        }
+        _ => return Err(SemanticError::type_error()),
+    };
+    if attributes.final_location() != Location::Memory {
+        return Err(SemanticError::cannot_move());
+    }
+
+    Ok(FixedSize::Base(inner))
 }
 
 fn verify_is_boolean(
@@ -310,6 +822,157 @@ fn if_statement(
     }
 }
 
+fn match_statement(
+    scope: Shared<BlockScope>,
+    context: Shared<Context>,
+    stmt: &Spanned<fe::FuncStmt>,
+) -> Result<(), SemanticError> {
+    if let fe::FuncStmt::Match { value, arms } = &stmt.node {
+        let typ = expressions::value_expr(Rc::clone(&scope), Rc::clone(&context), value)?.typ;
+        // The inclusive bounds of the values that have to be covered by the
+        // arms.
+        let (min, max) = match &typ {
+            Type::Base(Base::Numeric(integer)) => (integer.min_value(), integer.max_value()),
+            Type::Base(Base::Enum(val)) if !val.variants.is_empty() => {
+                (BigInt::from(0), BigInt::from(val.variants.len() - 1))
+            }
+            _ => return Err(SemanticError::type_error().with_context(value.span)),
+        };
+
+        let mut covered = vec![];
+        for arm in arms {
+            let patterns = match_pattern(&scope, &context, &typ, &arm.node.pattern)?;
+            for pattern in patterns.iter() {
+                let (start, end) = match pattern {
+                    MatchPattern::Wildcard => (min.clone(), max.clone()),
+                    MatchPattern::Value(value) => (value.clone(), value.clone()),
+                    MatchPattern::Range { start, end } => (start.clone(), end - 1),
+                };
+                if ranges_cover(&covered, &start, &end) {
+                    return Err(SemanticError::unreachable_pattern().with_context(arm.span));
+                }
+                covered = merge_ranges(covered, (start, end));
+            }
+            context.borrow_mut().add_match_arm(arm, patterns);
+
+            let body_scope =
+                BlockScope::from_block_scope(BlockScopeType::IfElse, Rc::clone(&scope));
+            traverse_statements(body_scope, Rc::clone(&context), &arm.node.body)?;
+        }
+
+        if !ranges_cover(&covered, &min, &max) {
+            return Err(SemanticError::non_exhaustive_match());
+        }
+
+        return Ok(());
+    }
+
+    unreachable!()
+}
+
+/// Resolve the alternative patterns of a `match` statement arm.
+///
+/// Patterns are `_`, enum variants or compile-time integer values, either on
+/// their own or as a `range(start, end)`. Alternatives are separated by `|`.
+fn match_pattern(
+    scope: &Shared<BlockScope>,
+    context: &Shared<Context>,
+    typ: &Type,
+    pattern: &Spanned<fe::Expr>,
+) -> Result<Vec<MatchPattern>, SemanticError> {
+    let integer = match typ {
+        Type::Base(Base::Numeric(integer)) => Some(integer),
+        _ => None,
+    };
+    let eval_int =
+        |exp: &Spanned<fe::Expr>| match constants::eval(&Scope::Block(Rc::clone(scope)), exp)? {
+            ConstValue::Int(value) => Ok(value),
+            ConstValue::Bool(_) => Err(SemanticError::type_error().with_context(exp.span)),
+        };
+    let eval_arg = |arg: &Spanned<fe::CallArg>| match &arg.node {
+        fe::CallArg::Arg(value) => eval_int(&spanned_expression(&arg.span, value)),
+        fe::CallArg::Kwarg(_) => Err(SemanticError::type_error().with_context(arg.span)),
+    };
+
+    match &pattern.node {
+        fe::Expr::Name("_") => Ok(vec![MatchPattern::Wildcard]),
+        fe::Expr::BinOperation { left, op, right } if op.node == fe::BinOperator::BitOr => {
+            let mut patterns = match_pattern(scope, context, typ, left)?;
+            patterns.extend(match_pattern(scope, context, typ, right)?);
+            Ok(patterns)
+        }
+        fe::Expr::Call { func, args } if matches!(func.node, fe::Expr::Name("range")) => {
+            let integer =
+                integer.ok_or_else(|| SemanticError::type_error().with_context(pattern.span))?;
+            let (start, end) = match args.node.as_slice() {
+                [end] => (BigInt::from(0), eval_arg(end)?),
+                [start, end] => (eval_arg(start)?, eval_arg(end)?),
+                _ => return Err(SemanticError::wrong_number_of_params().with_context(args.span)),
+            };
+            if start >= end {
+                return Err(SemanticError::type_error().with_context(pattern.span));
+            }
+            let last = &end - BigInt::from(1);
+            if !integer.fits(&start.to_string()) || !integer.fits(&last.to_string()) {
+                return Err(SemanticError::numeric_capacity_mismatch().with_context(pattern.span));
+            }
+            Ok(vec![MatchPattern::Range { start, end }])
+        }
+        _ => match typ {
+            Type::Base(Base::Enum(val)) => {
+                let attributes = expressions::expr(Rc::clone(scope), Rc::clone(context), pattern)?;
+                match (&attributes.typ, &pattern.node) {
+                    (Type::Base(Base::Enum(pattern_enum)), fe::Expr::Attribute { attr, .. })
+                        if pattern_enum == val =>
+                    {
+                        let index = val
+                            .get_variant_index(attr.node)
+                            .expect("missing enum variant");
+                        Ok(vec![MatchPattern::Value(BigInt::from(index))])
+                    }
+                    _ => Err(SemanticError::type_error().with_context(pattern.span)),
+                }
+            }
+            _ => {
+                let value = eval_int(pattern)?;
+                if !integer.map_or(false, |integer| integer.fits(&value.to_string())) {
+                    return Err(
+                        SemanticError::numeric_capacity_mismatch().with_context(pattern.span)
+                    );
+                }
+                Ok(vec![MatchPattern::Value(value)])
+            }
+        },
+    }
+}
+
+/// Returns true if the inclusive range from `start` to `end` lies within one
+/// of the merged `ranges`.
+fn ranges_cover(ranges: &[(BigInt, BigInt)], start: &BigInt, end: &BigInt) -> bool {
+    ranges
+        .iter()
+        .any(|(covered_start, covered_end)| covered_start <= start && end <= covered_end)
+}
+
+/// Adds an inclusive range to a list of merged ranges, joining it with all
+/// ranges that it overlaps or touches.
+fn merge_ranges(
+    ranges: Vec<(BigInt, BigInt)>,
+    (mut start, mut end): (BigInt, BigInt),
+) -> Vec<(BigInt, BigInt)> {
+    let mut merged = vec![];
+    for (other_start, other_end) in ranges {
+        if other_end < &start - 1 || &end + 1 < other_start {
+            merged.push((other_start, other_end));
+        } else {
+            start = start.min(other_start);
+            end = end.max(other_end);
+        }
+    }
+    merged.push((start, end));
+    merged
+}
+
 fn while_loop(
     scope: Shared<BlockScope>,
     context: Shared<Context>,
@@ -358,6 +1021,7 @@ fn emit(
     } = &stmt.node
     {
         let event_name = expressions::expr_name_str(func)?;
+        scope.borrow().validate_mutability(Mutability::Mutable)?;
 
         if let Some(event) = scope.borrow().contract_event_def(event_name) {
             context.borrow_mut().add_emit(stmt, event.clone());
@@ -381,6 +1045,44 @@ fn emit(
     unreachable!()
 }
 
+fn revert(
+    scope: Shared<BlockScope>,
+    context: Shared<Context>,
+    stmt: &Spanned<fe::FuncStmt>,
+) -> Result<(), SemanticError> {
+    if let fe::FuncStmt::Revert { error } = &stmt.node {
+        if let Some(error) = error {
+            if let fe::Expr::Call { func, args } = &error.node {
+                let error_name = expressions::expr_name_str(func)?;
+                let error_def = scope
+                    .borrow()
+                    .contract_error_def(error_name)
+                    .ok_or_else(SemanticError::undefined_value)?;
+
+                let argument_attributes = args
+                    .node
+                    .iter()
+                    .map(|arg| expressions::call_arg(Rc::clone(&scope), Rc::clone(&context), arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                if fixed_sizes_to_types(error_def.field_types())
+                    != expression_attributes_to_types(argument_attributes)
+                {
+                    return Err(SemanticError::type_error());
+                }
+
+                context.borrow_mut().add_revert(stmt, error_def);
+            } else {
+                return Err(SemanticError::type_error());
+            }
+        }
+
+        return Ok(());
+    }
+
+    unreachable!()
+}
+
 fn assert(
     scope: Shared<BlockScope>,
     context: Shared<Context>,
@@ -388,9 +1090,14 @@ fn assert(
 ) -> Result<(), SemanticError> {
     if let fe::FuncStmt::Assert { test, msg } = &stmt.node {
         verify_is_boolean(Rc::clone(&scope), Rc::clone(&context), test)?;
+        // The message is either a string reason or a panic code.
         if let Some(msg) = msg {
-            // TODO: type check for a string once strings are supported
-            let _msg_attributes = expressions::expr(scope, context, msg)?;
+            let msg_attributes = expressions::assignable_expr(scope, context, msg)?;
+            if !matches!(msg_attributes.typ, Type::String(_))
+                && msg_attributes.typ != Type::Base(U256)
+            {
+                return Err(SemanticError::type_error().with_context(msg.span));
+            }
         }
 
         return Ok(());
@@ -435,12 +1142,15 @@ mod tests {
         Shared,
     };
     use crate::namespace::types::{
+        Base,
         FixedSize,
+        Type,
         U256,
     };
     use crate::traversal::functions::{
         func_body,
         func_def,
+        func_instance,
     };
     use crate::Context;
     use fe_parser as parser;
@@ -459,7 +1169,7 @@ mod tests {
             .1;
 
         func_def(Rc::clone(&scope), Rc::clone(&context), def).expect("Couldn't map func def AST");
-        func_body(scope, Rc::clone(&context), def).expect("Couldn't map func body AST");
+        func_body(scope, Rc::clone(&context), def, &[]).expect("Couldn't map func body AST");
         Rc::try_unwrap(context)
             .map_err(|_| "")
             .unwrap()
@@ -493,4 +1203,56 @@ mod tests {
             }
         ));
     }
+
+    #[test]
+    fn generic_func_def() {
+        let scope = scope();
+        let func_def = "\
+        def id<T>(x: T) -> T:\
+            return x\
+        ";
+        let context = analyze(Rc::clone(&scope), func_def);
+        assert_eq!(context.expressions.len(), 0);
+        assert_eq!(scope.borrow().function_def("id"), None);
+
+        let generic_def = scope
+            .borrow()
+            .generic_function_def("id")
+            .expect("No generic definition for id exists");
+        let instance_name = func_instance(
+            Rc::clone(&scope),
+            "id",
+            generic_def,
+            &[Type::Base(Base::Bool)],
+        )
+        .expect("Couldn't instantiate id");
+        assert_eq!(instance_name, "id$bool");
+
+        let def = scope
+            .borrow()
+            .function_def(&instance_name)
+            .expect("No definition for the instance of id exists");
+        assert_eq!(def.is_public, false);
+        assert_eq!(def.param_types, vec![FixedSize::Base(Base::Bool)]);
+        assert_eq!(def.return_type, FixedSize::Base(Base::Bool));
+        assert_eq!(
+            scope.borrow().pending_instances,
+            vec![("id".to_string(), "id$bool".to_string())]
+        );
+    }
+
+    #[test]
+    fn decorator_func_def() {
+        let scope = scope();
+        let func_def = "def at_least(x: u256):\n    assert x > 0\n    _\n";
+        let context = analyze(Rc::clone(&scope), func_def);
+        assert_eq!(context.expressions.len(), 3);
+        assert_eq!(scope.borrow().function_def("at_least"), None);
+
+        let def = scope
+            .borrow()
+            .decorator_def("at_least")
+            .expect("No decorator definition for at_least exists");
+        assert_eq!(def.param_types, vec![FixedSize::Base(U256)]);
+    }
 }
@@ -1,9 +1,12 @@
 mod _utils;
 mod assignments;
+mod constants;
 mod contracts;
 mod declarations;
+mod enums;
 mod expressions;
 mod functions;
 pub mod module;
 mod structs;
+mod traits;
 mod types;
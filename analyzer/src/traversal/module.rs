@@ -1,31 +1,126 @@
+use crate::builtins;
 use crate::errors::SemanticError;
 use crate::namespace::scopes::{
     ModuleScope,
+    Scope,
     Shared,
 };
-use crate::namespace::types;
+use crate::namespace::types::{
+    Base,
+    NamedInteger,
+    Type,
+};
+use crate::traversal::contracts::MixinDefs;
 use crate::traversal::{
+    constants,
     contracts,
+    enums,
+    functions,
     structs,
+    traits,
+    types,
 };
 use crate::Context;
 use fe_parser::ast as fe;
 use fe_parser::span::Spanned;
+use std::collections::HashMap;
 use std::rc::Rc;
 
 /// Gather context information for a module and check for type errors.
 pub fn module(context: Shared<Context>, module: &fe::Module) -> Result<(), SemanticError> {
     let scope = ModuleScope::new();
+    let mut mixins = HashMap::new();
+
+    // Functions outside of contracts may be called from contracts that are
+    // defined before them.
+    let mut free_functions = vec![];
+    for stmt in module.body.iter() {
+        if let fe::ModuleStmt::FuncDef { def } = &stmt.node {
+            free_func_def(Rc::clone(&scope), def).map_err(|error| error.with_context(stmt.span))?;
+            free_functions.push(def.as_ref().clone());
+        }
+    }
 
     for stmt in module.body.iter() {
         match &stmt.node {
             fe::ModuleStmt::TypeDef { .. } => type_def(Rc::clone(&scope), stmt)?,
-            fe::ModuleStmt::StructDef { name, body } => {
-                structs::struct_def(Rc::clone(&scope), name.node, body)?
+            fe::ModuleStmt::ConstDef {
+                decorators,
+                name,
+                typ,
+                value,
+                ..
+            } => {
+                // constants only take `cfg` decorators, which are removed
+                // before the module is analyzed
+                if !decorators.is_empty() {
+                    return Err(SemanticError::invalid_decorator().with_context(stmt.span));
+                }
+                let constant = constants::const_def(Scope::Module(Rc::clone(&scope)), typ, value)
+                    .map_err(|error| error.with_context(stmt.span))?;
+                scope
+                    .borrow_mut()
+                    .add_constant(name.node, constant)
+                    .map_err(|error| error.with_context(stmt.span))?;
+            }
+            fe::ModuleStmt::StructDef {
+                decorators,
+                name,
+                generics,
+                body,
+                ..
+            } => {
+                // structs can only be deprecated or marked as EIP-712 typed
+                // data, which generic structs can't be
+                let is_eip712 = decorators.iter().any(|decorator| {
+                    builtins::Decorator::of(&decorator.node) == Some(builtins::Decorator::Eip712)
+                });
+                if (is_eip712 && !generics.is_empty())
+                    || decorators.iter().any(|decorator| {
+                        !matches!(
+                            builtins::Decorator::of(&decorator.node),
+                            Some(builtins::Decorator::Deprecated)
+                                | Some(builtins::Decorator::Eip712)
+                        ) || !functions::is_valid_deprecation(decorator)
+                    })
+                {
+                    return Err(SemanticError::invalid_decorator().with_context(stmt.span));
+                }
+                structs::struct_def(Rc::clone(&scope), name.node, generics, body, is_eip712)?;
+                if let Some(Type::Struct(val)) = scope.borrow().get_type_def(name.node) {
+                    context.borrow_mut().add_struct(stmt, val);
+                }
+            }
+            fe::ModuleStmt::EnumDef { name, variants, .. } => {
+                enums::enum_def(Rc::clone(&scope), name.node, variants)?
             }
-            fe::ModuleStmt::ContractDef { .. } => {
-                contracts::contract_def(Rc::clone(&scope), Rc::clone(&context), stmt)?
+            // interfaces are analyzed like traits, so contracts may implement them
+            fe::ModuleStmt::TraitDef { name, body, .. }
+            | fe::ModuleStmt::InterfaceDef { name, body, .. } => {
+                traits::trait_def(Rc::clone(&scope), name.node, body)?
             }
+            fe::ModuleStmt::MixinDef { name, body, .. } => {
+                if mixins.insert(name.node, body.as_slice()).is_some() {
+                    return Err(SemanticError::already_defined().with_context(stmt.span));
+                }
+            }
+            fe::ModuleStmt::ContractDef { .. } => contracts::contract_def(
+                Rc::clone(&scope),
+                Rc::clone(&context),
+                stmt,
+                &mixins,
+                &free_functions,
+            )?,
+            fe::ModuleStmt::LibraryDef { .. } => library_def(
+                Rc::clone(&scope),
+                Rc::clone(&context),
+                stmt,
+                &free_functions,
+            )?,
+            fe::ModuleStmt::FuncDef { .. } => {}
+            // code with syntax errors is skipped, so that the rest of the
+            // module can still be analyzed
+            fe::ModuleStmt::Error(_) => {}
             fe::ModuleStmt::FromImport { .. } => unimplemented!(),
             fe::ModuleStmt::SimpleImport { .. } => unimplemented!(),
         }
@@ -34,12 +129,93 @@ pub fn module(context: Shared<Context>, module: &fe::Module) -> Result<(), Seman
     Ok(())
 }
 
+/// Gather the name of a function defined outside of any contract. Its
+/// signature and body are analyzed as part of every contract.
+///
+/// Such functions are private and can't access the state of the contracts
+/// that call them, so they can neither be public nor decorated.
+fn free_func_def(
+    scope: Shared<ModuleScope>,
+    def: &Spanned<fe::ContractStmt>,
+) -> Result<(), SemanticError> {
+    if let fe::ContractStmt::FuncDef {
+        decorators,
+        qual,
+        name,
+        generics,
+        body,
+        ..
+    } = &def.node
+    {
+        if !decorators.is_empty()
+            || qual.is_some()
+            || !generics.is_empty()
+            || name.node == "__init__"
+            || body.iter().any(|stmt| stmt.node.is_placeholder())
+        {
+            return Err(SemanticError::invalid_free_function());
+        }
+
+        return scope.borrow_mut().add_free_function(name.node);
+    }
+
+    unreachable!()
+}
+
+/// Gather context information for a library and check for type errors.
+///
+/// A library is analyzed as a contract with the same span, so the compiler can
+/// look it up like any other contract. It may only contain functions, which
+/// are all pure.
+fn library_def(
+    scope: Shared<ModuleScope>,
+    context: Shared<Context>,
+    stmt: &Spanned<fe::ModuleStmt>,
+    free_functions: &[Spanned<fe::ContractStmt>],
+) -> Result<(), SemanticError> {
+    if let fe::ModuleStmt::LibraryDef { name, body, .. } = &stmt.node {
+        for def in body.iter() {
+            match &def.node {
+                fe::ContractStmt::FuncDef { name, .. } if name.node != "__init__" => {}
+                _ => return Err(SemanticError::invalid_library().with_context(def.span)),
+            }
+        }
+
+        scope
+            .borrow_mut()
+            .add_library(name.node)
+            .map_err(|error| error.with_context(stmt.span))?;
+
+        let contract = Spanned {
+            node: stmt.node.library_contract().unwrap(),
+            span: stmt.span,
+        };
+        return contracts::contract_def(
+            scope,
+            context,
+            &contract,
+            &MixinDefs::new(),
+            free_functions,
+        );
+    }
+
+    unreachable!()
+}
+
 fn type_def(
     scope: Shared<ModuleScope>,
     def: &Spanned<fe::ModuleStmt>,
 ) -> Result<(), SemanticError> {
-    if let fe::ModuleStmt::TypeDef { name, typ } = &def.node {
-        let typ = types::type_desc(&scope.borrow().type_defs, &typ.node)?;
+    if let fe::ModuleStmt::TypeDef { name, typ, .. } = &def.node {
+        // aliases of integer types are distinct types, other aliases are just
+        // another name for the same type
+        let typ = match types::type_desc(Scope::Module(Rc::clone(&scope)), typ)? {
+            Type::Base(Base::Numeric(integer)) => Type::Base(Base::Named(NamedInteger {
+                name: name.node.to_owned(),
+                integer,
+            })),
+            typ => typ,
+        };
         scope.borrow_mut().add_type_def(name.node, typ);
         return Ok(());
     }
@@ -4,6 +4,13 @@ use fe_parser::{
 };
 
 use crate::errors::SemanticError;
+use crate::namespace::eip712;
+use crate::namespace::generics::{
+    instance_name,
+    GenericStructDef,
+    StructInstance,
+    TypeTemplate,
+};
 use crate::namespace::scopes::{
     ModuleScope,
     Shared,
@@ -18,20 +25,108 @@ use crate::namespace::types::{
 pub fn struct_def(
     module_scope: Shared<ModuleScope>,
     name: &str,
+    generics: &[Spanned<&str>],
     struct_stmts: &[Spanned<StructStmt>],
+    is_eip712: bool,
 ) -> Result<(), SemanticError> {
+    if !generics.is_empty() {
+        return generic_struct_def(module_scope, name, generics, struct_stmts);
+    }
+
     let mut val = Struct::new(name);
     for stmt in struct_stmts {
         let StructStmt::StructField { name, typ, .. } = &stmt.node;
         let field_type = type_desc(&module_scope.borrow().type_defs, &typ.node)?;
         if let Type::Base(base_typ) = field_type {
-            val.add_field(name.node, &FixedSize::Base(base_typ));
+            let field_type = FixedSize::Base(base_typ);
+            // every field of typed data needs an EIP-712 type
+            if is_eip712 && eip712::field_type(&field_type).is_none() {
+                return Err(SemanticError::type_error().with_context(stmt.span));
+            }
+            val.add_field(name.node, &field_type);
         } else {
             todo!("Non-Base type fields aren't yet supported")
         }
     }
+    if is_eip712 {
+        val.set_eip712();
+    }
     module_scope
         .borrow_mut()
         .add_type_def(name, Type::Struct(val));
     Ok(())
 }
+
+/// Generic structs are only checked once they are instantiated.
+fn generic_struct_def(
+    module_scope: Shared<ModuleScope>,
+    name: &str,
+    generics: &[Spanned<&str>],
+    struct_stmts: &[Spanned<StructStmt>],
+) -> Result<(), SemanticError> {
+    let fields = struct_stmts
+        .iter()
+        .map(|stmt| {
+            let StructStmt::StructField { name, typ, .. } = &stmt.node;
+            (name.node.to_owned(), TypeTemplate::from(&typ.node))
+        })
+        .collect();
+
+    module_scope.borrow_mut().add_generic_struct_def(
+        name,
+        GenericStructDef {
+            type_params: generics.iter().map(|param| param.node.to_owned()).collect(),
+            fields,
+        },
+    )
+}
+
+/// Instantiates the generic struct `name` with the given type arguments and
+/// returns the name of the instance.
+pub fn struct_instance(
+    module_scope: Shared<ModuleScope>,
+    name: &str,
+    type_args: Vec<FixedSize>,
+) -> Result<String, SemanticError> {
+    let def = module_scope
+        .borrow()
+        .get_generic_struct_def(name)
+        .ok_or_else(SemanticError::undefined_value)?;
+
+    if def.type_params.len() != type_args.len() {
+        return Err(SemanticError::wrong_number_of_params());
+    }
+
+    let instance_name = instance_name(name, &type_args);
+    if module_scope.borrow().get_type_def(&instance_name).is_some() {
+        return Ok(instance_name);
+    }
+
+    let mut defs = module_scope.borrow().type_defs.clone();
+    defs.extend(
+        def.type_params
+            .iter()
+            .cloned()
+            .zip(type_args.iter().map(|typ| typ.clone().into())),
+    );
+
+    let mut val = Struct::new(&instance_name);
+    for (field_name, field_type) in def.fields.iter() {
+        if let Type::Base(base_typ) = type_desc(&defs, &field_type.type_desc())? {
+            val.add_field(field_name, &FixedSize::Base(base_typ));
+        } else {
+            return Err(SemanticError::type_error());
+        }
+    }
+
+    module_scope.borrow_mut().add_struct_instance(
+        &instance_name,
+        Type::Struct(val),
+        StructInstance {
+            base: name.to_owned(),
+            type_args,
+        },
+    );
+
+    Ok(instance_name)
+}
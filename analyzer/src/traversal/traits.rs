@@ -0,0 +1,83 @@
+use crate::errors::SemanticError;
+use crate::namespace::scopes::{
+    ModuleScope,
+    Scope,
+    Shared,
+};
+use crate::namespace::traits::Trait;
+use crate::namespace::types::{
+    Contract,
+    FixedSize,
+    Type,
+};
+use crate::traversal::types;
+use crate::FunctionAttributes;
+use fe_parser::ast as fe;
+use fe_parser::span::Spanned;
+use std::rc::Rc;
+
+/// Gather context information for a trait definition and check for type
+/// errors.
+///
+/// A trait can also be used as a contract type, which allows calling the
+/// functions of any contract implementing it.
+pub fn trait_def(
+    module_scope: Shared<ModuleScope>,
+    name: &str,
+    body: &[Spanned<fe::FuncSig>],
+) -> Result<(), SemanticError> {
+    let mut functions: Vec<FunctionAttributes> = vec![];
+
+    for sig in body.iter() {
+        let function = func_sig(Rc::clone(&module_scope), sig)
+            .map_err(|error| error.with_context(sig.span))?;
+
+        if functions.iter().any(|other| other.name == function.name) {
+            return Err(SemanticError::already_defined().with_context(sig.span));
+        }
+
+        functions.push(function);
+    }
+
+    module_scope
+        .borrow_mut()
+        .add_trait_def(name, Trait::new(name, functions.clone()))?;
+    module_scope.borrow_mut().add_type_def(
+        name,
+        Type::Contract(Contract {
+            name: name.to_owned(),
+            functions,
+            init_params: vec![],
+        }),
+    );
+
+    Ok(())
+}
+
+fn func_sig(
+    module_scope: Shared<ModuleScope>,
+    sig: &Spanned<fe::FuncSig>,
+) -> Result<FunctionAttributes, SemanticError> {
+    let param_types = sig
+        .node
+        .args
+        .iter()
+        .map(|arg| {
+            types::type_desc_fixed_size(Scope::Module(Rc::clone(&module_scope)), &arg.node.typ)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let return_type = sig
+        .node
+        .return_type
+        .as_ref()
+        .map(|typ| types::type_desc_fixed_size(Scope::Module(Rc::clone(&module_scope)), typ))
+        .transpose()?
+        .unwrap_or_else(FixedSize::empty_tuple);
+
+    Ok(FunctionAttributes {
+        name: sig.node.name.node.to_owned(),
+        param_types,
+        return_type,
+    })
+}
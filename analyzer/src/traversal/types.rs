@@ -5,12 +5,15 @@ use crate::namespace::types::{
     FixedSize,
     Type,
 };
+use crate::traversal::structs;
 use fe_parser::ast as fe;
 use fe_parser::span::Spanned;
+use std::collections::HashMap;
+use std::convert::TryFrom;
 
 /// Maps a type description node to an enum type.
 pub fn type_desc(scope: Scope, typ: &Spanned<fe::TypeDesc>) -> Result<Type, SemanticError> {
-    types::type_desc(&scope.module_scope().borrow().type_defs, &typ.node)
+    resolve(scope, &typ.node)
 }
 
 /// Maps a type description node to a fixed size enum type.
@@ -18,5 +21,58 @@ pub fn type_desc_fixed_size(
     scope: Scope,
     typ: &Spanned<fe::TypeDesc>,
 ) -> Result<FixedSize, SemanticError> {
-    types::type_desc_fixed_size(&scope.module_scope().borrow().type_defs, &typ.node)
+    FixedSize::try_from(resolve(scope, &typ.node)?)
+}
+
+/// Maps a type description to an enum type.
+///
+/// Type parameters of the surrounding function instance are replaced by their
+/// type arguments and generic structs are instantiated as needed.
+pub fn resolve(scope: Scope, typ: &fe::TypeDesc) -> Result<Type, SemanticError> {
+    instantiate_structs(&scope, typ)?;
+    types::type_desc(&type_defs(&scope), typ)
+}
+
+/// The module type definitions along with the type arguments of the
+/// surrounding function instance, if any.
+fn type_defs(scope: &Scope) -> HashMap<String, Type> {
+    let mut defs = scope.module_scope().borrow().type_defs.clone();
+    if let Scope::Block(block_scope) = scope {
+        defs.extend(block_scope.borrow().type_args());
+    }
+
+    defs
+}
+
+/// Instantiates every generic struct that is used in the type description.
+fn instantiate_structs(scope: &Scope, typ: &fe::TypeDesc) -> Result<(), SemanticError> {
+    match typ {
+        fe::TypeDesc::Base { .. } => Ok(()),
+        fe::TypeDesc::Array { typ, .. } => instantiate_structs(scope, &typ.node),
+        fe::TypeDesc::Map { from, to } => {
+            instantiate_structs(scope, &from.node)?;
+            instantiate_structs(scope, &to.node)
+        }
+        fe::TypeDesc::Tuple { items } => items
+            .iter()
+            .try_for_each(|item| instantiate_structs(scope, &item.node)),
+        fe::TypeDesc::Generic { base, args } => {
+            for arg in args.iter() {
+                instantiate_structs(scope, &arg.node)?;
+            }
+
+            // `Array<T>` is built in and not a generic struct
+            if *base == "Array" {
+                return Ok(());
+            }
+
+            let type_args = args
+                .iter()
+                .map(|arg| types::type_desc_fixed_size(&type_defs(scope), &arg.node))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            structs::struct_instance(scope.module_scope(), base, type_args)?;
+            Ok(())
+        }
+    }
 }
@@ -8,6 +8,7 @@ use fe_analyzer::namespace::types::{
 use fe_analyzer::{
     ExpressionAttributes,
     Location,
+    StorageField,
 };
 use fe_parser::ast as fe;
 use fe_parser::span::{
@@ -116,3 +117,43 @@ fn guest_book_analysis() {
         "0xf95318ba442251854c1277ed370e2adb2cd6dc2156bdffdd75dc5d798b31ab0f"
     );
 }
+
+#[test]
+fn guest_book_queries() {
+    let tokens = fe_parser::get_parse_tokens(GUEST_BOOK).expect("Couldn't parse expression");
+    let fe_module = fe_parser::parsers::file_input(&tokens[..])
+        .expect("failed to parse guest book")
+        .1
+        .node;
+
+    let context = fe_analyzer::analyze(&fe_module).expect("failed to perform semantic analysis");
+
+    assert_eq!(
+        context.expression_at(339),
+        Some((
+            Span {
+                start: 338,
+                end: 342
+            },
+            &addr_val()
+        ))
+    );
+    assert_eq!(context.expression_at(0), None);
+
+    let (_, contract) = context.contracts().next().expect("couldn't find contract");
+    assert_eq!(
+        contract.storage_layout,
+        vec![StorageField {
+            name: "guest_book".to_string(),
+            nonce: 0,
+            typ: addr_bytes_map_sto().typ,
+        }]
+    );
+
+    let mut functions = context
+        .functions()
+        .map(|(_, function)| function.name.as_str())
+        .collect::<Vec<_>>();
+    functions.sort_unstable();
+    assert_eq!(functions, vec!["get_msg", "sign"]);
+}
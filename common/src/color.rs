@@ -0,0 +1,75 @@
+//! Whether diagnostics are colored.
+//!
+//! Colors are enabled by default. The CLI disables them according to its
+//! `--color` option and the `NO_COLOR` environment variable.
+
+use ansi_term::Color;
+use std::sync::atomic::{
+    AtomicBool,
+    Ordering,
+};
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// When to color the output.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorChoice {
+    /// Colors terminals, unless `NO_COLOR` is set.
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn parse(choice: &str) -> Option<Self> {
+        match choice {
+            "auto" => Some(ColorChoice::Auto),
+            "always" => Some(ColorChoice::Always),
+            "never" => Some(ColorChoice::Never),
+            _ => None,
+        }
+    }
+
+    /// Whether output should be colored, given whether it is written to a
+    /// terminal and whether `NO_COLOR` is set to a non-empty value.
+    pub fn resolve(self, terminal: bool, no_color: bool) -> bool {
+        match self {
+            ColorChoice::Auto => terminal && !no_color,
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed)
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Paints the text with the color if colors are enabled.
+pub fn paint(color: Color, text: &str) -> String {
+    if enabled() {
+        color.paint(text).to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::color::ColorChoice;
+
+    #[test]
+    fn resolve() {
+        assert!(ColorChoice::Auto.resolve(true, false));
+        assert!(!ColorChoice::Auto.resolve(true, true));
+        assert!(!ColorChoice::Auto.resolve(false, false));
+        assert!(ColorChoice::Always.resolve(false, true));
+        assert!(!ColorChoice::Never.resolve(true, false));
+        assert_eq!(ColorChoice::parse("never"), Some(ColorChoice::Never));
+        assert_eq!(ColorChoice::parse("sometimes"), None);
+    }
+}
@@ -0,0 +1,58 @@
+/// Returns the Levenshtein distance between two strings, i.e. the number of
+/// characters that have to be inserted, removed or replaced to turn one into
+/// the other.
+pub fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, b_char) in b.iter().enumerate() {
+            let replaced = diagonal + if a_char == *b_char { 0 } else { 1 };
+            diagonal = row[j + 1];
+            row[j + 1] = replaced.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+/// Find the candidate that is closest to `name`.
+///
+/// Candidates that differ in more than a third of the characters of `name`
+/// are ignored, so there are no candidates for names shorter than three
+/// characters. `None` is returned if there is no close candidate or if
+/// several candidates are equally close.
+pub fn closest<'a, I>(name: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let max_distance = name.chars().count() / 3;
+    let mut best: Option<(usize, &'a str)> = None;
+    let mut tied = false;
+
+    for candidate in candidates {
+        let distance = distance(name, candidate);
+        if distance == 0 || distance > max_distance {
+            continue;
+        }
+
+        match best {
+            Some((best_distance, best_candidate)) if distance == best_distance => {
+                tied = tied || best_candidate != candidate
+            }
+            Some((best_distance, _)) if distance > best_distance => {}
+            _ => {
+                best = Some((distance, candidate));
+                tied = false;
+            }
+        }
+    }
+
+    match best {
+        Some((_, candidate)) if !tied => Some(candidate),
+        _ => None,
+    }
+}
@@ -1,5 +1,7 @@
 use crate::abi::elements::{
+    Component,
     Contract,
+    CustomError,
     Event,
     EventField,
     FuncInput,
@@ -7,32 +9,115 @@ use crate::abi::elements::{
     FuncType,
     Function,
     ModuleAbis,
+    StateMutability,
     VarType,
 };
+use crate::abi::utils as abi_utils;
 use crate::errors::CompileError;
+use fe_analyzer::builtins;
+use fe_analyzer::namespace::erc165;
+use fe_analyzer::namespace::types::{
+    FeSized,
+    FixedPoint,
+};
 use fe_parser::ast as fe;
 use fe_parser::span::Spanned;
-use std::collections::HashMap;
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+/// Type aliases, structs and enums defined in a module.
+#[derive(Default)]
+struct TypeDefs<'a> {
+    aliases: HashMap<&'a str, &'a fe::TypeDesc<'a>>,
+    structs: HashMap<&'a str, StructDef<'a>>,
+    enums: HashSet<&'a str>,
+}
 
-type TypeDefs<'a> = HashMap<&'a str, &'a fe::TypeDesc<'a>>;
+/// The type parameters and fields of a struct definition.
+type StructDef<'a> = (&'a [Spanned<&'a str>], &'a [Spanned<fe::StructStmt<'a>>]);
 
 /// Parse a map of contract ABIs from the input `module`.
 pub fn module<'a>(module: &'a fe::Module<'a>) -> Result<ModuleAbis, CompileError> {
-    let mut type_defs = TypeDefs::new();
+    let mut type_defs = TypeDefs::default();
+    let mut mixins = HashMap::new();
 
     module
         .body
         .iter()
         .try_fold(ModuleAbis::new(), |mut abis, stmt| {
             match &stmt.node {
-                fe::ModuleStmt::TypeDef { name, typ } => {
-                    if type_defs.insert(name.node, &typ.node).is_some() {
+                fe::ModuleStmt::TypeDef { name, typ, .. } => {
+                    if type_defs.aliases.insert(name.node, &typ.node).is_some() {
                         return Err(CompileError::static_str("duplicate type definition"));
                     }
                 }
-                fe::ModuleStmt::ContractDef { name, body } => {
+                fe::ModuleStmt::StructDef {
+                    name,
+                    generics,
+                    body,
+                    ..
+                } => {
+                    if type_defs
+                        .structs
+                        .insert(name.node, (generics, body))
+                        .is_some()
+                    {
+                        return Err(CompileError::static_str("duplicate struct definition"));
+                    }
+                }
+                fe::ModuleStmt::EnumDef { name, .. } => {
+                    if !type_defs.enums.insert(name.node) {
+                        return Err(CompileError::static_str("duplicate enum definition"));
+                    }
+                }
+                fe::ModuleStmt::MixinDef { name, body, .. } => {
+                    if mixins.insert(name.node, body.as_slice()).is_some() {
+                        return Err(CompileError::static_str("duplicate mixin definition"));
+                    }
+                }
+                fe::ModuleStmt::ContractDef {
+                    decorators,
+                    name,
+                    mixins: mixin_names,
+                    body,
+                    ..
+                } => {
+                    // mixin items come first, matching the contract's storage layout
+                    let mut bodies = mixin_names
+                        .iter()
+                        .map(|mixin_name| {
+                            mixins
+                                .get(mixin_name.node)
+                                .copied()
+                                .ok_or_else(|| CompileError::static_str("undefined mixin"))
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    bodies.push(body.as_slice());
+                    let is_erc165 = decorators.iter().any(|decorator| {
+                        builtins::Decorator::of(&decorator.node)
+                            == Some(builtins::Decorator::Erc165)
+                    });
+
+                    if abis
+                        .insert(
+                            name.node.to_string(),
+                            contract_def(&type_defs, &bodies, is_erc165)?,
+                        )
+                        .is_some()
+                    {
+                        return Err(CompileError::static_str("duplicate contract definition"));
+                    }
+                }
+                fe::ModuleStmt::LibraryDef { name, body, .. } => {
                     if abis
-                        .insert(name.node.to_string(), contract_def(&type_defs, body)?)
+                        .insert(
+                            name.node.to_string(),
+                            contract_def(&type_defs, &[body.as_slice()], false)?,
+                        )
                         .is_some()
                     {
                         return Err(CompileError::static_str("duplicate contract definition"));
@@ -45,34 +130,103 @@ pub fn module<'a>(module: &'a fe::Module<'a>) -> Result<ModuleAbis, CompileError
         })
 }
 
+/// Contracts marked with `@erc165` also have the generated `supportsInterface`
+/// function.
 fn contract_def<'a>(
     type_defs: &'a TypeDefs<'a>,
-    body: &[Spanned<fe::ContractStmt<'a>>],
+    bodies: &[&[Spanned<fe::ContractStmt<'a>>]],
+    is_erc165: bool,
 ) -> Result<Contract, CompileError> {
-    body.iter().try_fold(Contract::new(), |mut c, s| {
-        match &s.node {
-            fe::ContractStmt::FuncDef {
-                qual,
-                name,
-                args,
-                return_type,
-                ..
-            } => {
-                if let Some(qual) = qual {
-                    if qual.node == fe::FuncQual::Pub {
-                        c.functions
-                            .push(func_def(type_defs, name.node, args, return_type)?)
+    let mut contract =
+        bodies
+            .iter()
+            .flat_map(|body| body.iter())
+            .try_fold(Contract::new(), |mut c, s| {
+                match &s.node {
+                    fe::ContractStmt::FuncDef {
+                        decorators,
+                        qual,
+                        name,
+                        args,
+                        return_type,
+                        return_names,
+                        ..
+                    } => {
+                        // The constructor is part of the ABI whether or not
+                        // `__init__` is public.
+                        let is_public =
+                            matches!(qual, Some(qual) if qual.node == fe::FuncQual::Pub);
+                        if is_public || name.node == "__init__" {
+                            c.functions.push(func_def(
+                                type_defs,
+                                name.node,
+                                decorators,
+                                args,
+                                return_type,
+                                return_names,
+                            )?)
+                        }
+                    }
+                    fe::ContractStmt::EventDef { name, fields, .. } => {
+                        c.events.push(event_def(type_defs, name.node, fields)?)
                     }
+                    fe::ContractStmt::ErrorDef { name, fields, .. } => {
+                        c.errors.push(error_def(type_defs, name.node, fields)?)
+                    }
+                    fe::ContractStmt::ContractField { .. } | fe::ContractStmt::Error(_) => {}
                 }
-            }
-            fe::ContractStmt::EventDef { name, fields } => {
-                c.events.push(event_def(type_defs, name.node, fields)?)
-            }
-            fe::ContractStmt::ContractField { .. } => {}
+
+                Ok(c)
+            })?;
+
+    if is_erc165 {
+        contract.functions.push(Function {
+            name: erc165::SUPPORTS_INTERFACE.to_string(),
+            typ: FuncType::Function,
+            inputs: vec![FuncInput {
+                name: "interfaceId".to_string(),
+                typ: VarType::FixedBytes(4),
+            }],
+            outputs: vec![FuncOutput {
+                name: "".to_string(),
+                typ: VarType::Bool,
+            }],
+            state_mutability: StateMutability::View,
+        });
+    }
+
+    check_selector_collisions(&contract)?;
+
+    Ok(contract)
+}
+
+/// Ensures that no two public functions of a contract share a 4 byte selector.
+/// Colliding selectors would make the ABI dispatcher ambiguous.
+fn check_selector_collisions(contract: &Contract) -> Result<(), CompileError> {
+    let mut selectors = HashMap::new();
+
+    for function in contract.functions.iter() {
+        if function.typ == FuncType::Constructor {
+            continue;
         }
 
-        Ok(c)
-    })
+        let params = function
+            .inputs
+            .iter()
+            .map(|input| input.typ.to_string())
+            .collect();
+        let selector = abi_utils::func_selector(&function.name, params);
+        let signature = function.signature();
+
+        if let Some(other) = selectors.insert(selector.clone(), signature.clone()) {
+            return Err(CompileError::str(&format!(
+                "function selector collision: {} and {} share the selector {}",
+                other, signature, selector
+            )));
+        }
+    }
+
+    Ok(())
 }
 
 fn event_def<'a>(
@@ -93,6 +247,23 @@ fn event_def<'a>(
     })
 }
 
+fn error_def<'a>(
+    type_defs: &'a TypeDefs<'a>,
+    name: &str,
+    fields: &'a [Spanned<fe::FuncDefArg<'a>>],
+) -> Result<CustomError, CompileError> {
+    let inputs = fields
+        .iter()
+        .map(|field| func_def_arg(type_defs, &field.node))
+        .collect::<Result<Vec<FuncInput>, CompileError>>()?;
+
+    Ok(CustomError {
+        name: name.to_owned(),
+        typ: "error".to_owned(),
+        inputs,
+    })
+}
+
 fn event_field<'a>(
     type_defs: &'a TypeDefs<'a>,
     field: &'a fe::EventField<'a>,
@@ -107,8 +278,10 @@ fn event_field<'a>(
 fn func_def<'a>(
     type_defs: &'a TypeDefs<'a>,
     name: &str,
+    decorators: &[Spanned<fe::Expr>],
     args: &[Spanned<fe::FuncDefArg<'a>>],
     return_type: &'a Option<Spanned<fe::TypeDesc<'a>>>,
+    return_names: &[Spanned<&str>],
 ) -> Result<Function, CompileError> {
     let inputs = args
         .iter()
@@ -117,11 +290,23 @@ fn func_def<'a>(
 
     let outputs = if let Some(return_type) = return_type {
         match type_desc(type_defs, &return_type.node)? {
-            // Should there be a different ABI for `pub def foo() -> ():` and `pub def foo():`?
-            VarType::Tuple(items) if items.is_empty() => vec![],
-            _ => vec![FuncOutput {
+            // A returned tuple is encoded the same way as its items returned separately, so
+            // each item is listed as its own output. This also means that `pub def foo() -> ():`
+            // and `pub def foo():` have the same ABI. The items keep their names, if they
+            // have any.
+            VarType::Tuple(items) => items
+                .into_iter()
+                .enumerate()
+                .map(|(index, typ)| FuncOutput {
+                    name: return_names
+                        .get(index)
+                        .map_or_else(String::new, |name| name.node.to_owned()),
+                    typ,
+                })
+                .collect(),
+            typ => vec![FuncOutput {
                 name: "".to_string(),
-                typ: type_desc(type_defs, &return_type.node)?,
+                typ,
             }],
         }
     } else {
@@ -134,11 +319,25 @@ fn func_def<'a>(
         (name, FuncType::Function)
     };
 
+    let state_mutability = decorators
+        .iter()
+        .find_map(|decorator| match &decorator.node {
+            fe::Expr::Name(name) => match builtins::Decorator::from_str(name) {
+                Ok(builtins::Decorator::Payable) => Some(StateMutability::Payable),
+                Ok(builtins::Decorator::Pure) => Some(StateMutability::Pure),
+                Ok(builtins::Decorator::View) => Some(StateMutability::View),
+                _ => None,
+            },
+            _ => None,
+        })
+        .unwrap_or(StateMutability::Nonpayable);
+
     Ok(Function {
         name: name.to_owned(),
         typ,
         inputs,
         outputs,
+        state_mutability,
     })
 }
 
@@ -157,9 +356,18 @@ fn type_desc<'a>(
     typ: &'a fe::TypeDesc<'a>,
 ) -> Result<VarType, CompileError> {
     if let fe::TypeDesc::Base { base } = typ {
-        if let Some(custom_type) = type_defs.get(base) {
+        if let Some(custom_type) = type_defs.aliases.get(base) {
             return type_desc(type_defs, custom_type);
         }
+
+        if let Some((_, fields)) = type_defs.structs.get(base) {
+            return struct_def(type_defs, fields, &HashMap::new());
+        }
+
+        // Enums are encoded by the index of their variant.
+        if type_defs.enums.contains(base) {
+            return Ok(VarType::Uint8);
+        }
     }
 
     match typ {
@@ -178,6 +386,14 @@ fn type_desc<'a>(
         fe::TypeDesc::Base { base: "bool" } => Ok(VarType::Bool),
         fe::TypeDesc::Base { base: "address" } => Ok(VarType::Address),
         fe::TypeDesc::Base { base } if base.starts_with("string") => Ok(VarType::String),
+        fe::TypeDesc::Base { base } if FixedPoint::try_from(*base).is_ok() => {
+            let fixed = FixedPoint::try_from(*base).expect("invalid fixed-point type");
+            Ok(VarType::Fixed {
+                signed: fixed.integer.is_signed(),
+                bits: fixed.integer.size() * 8,
+                decimals: fixed.decimals,
+            })
+        }
         fe::TypeDesc::Base { base } => {
             Err(CompileError::str(&format!("unrecognized type: {}", base)))
         }
@@ -197,13 +413,80 @@ fn type_desc<'a>(
                 .collect::<Result<_, _>>()?;
             Ok(VarType::Tuple(items))
         }
+        fe::TypeDesc::Generic {
+            base: "Array",
+            args,
+        } => match args.as_slice() {
+            [inner] => {
+                if let fe::TypeDesc::Base { base: "bytes" } = &inner.node {
+                    return Ok(VarType::Bytes);
+                }
+
+                let inner = type_desc(type_defs, &inner.node)?;
+                Ok(VarType::DynamicArray(Box::new(inner)))
+            }
+            _ => Err(CompileError::static_str(
+                "wrong number of type arguments for Array",
+            )),
+        },
+        fe::TypeDesc::Generic { base, args } => {
+            if let Some((params, fields)) = type_defs.structs.get(base) {
+                if params.len() != args.len() {
+                    return Err(CompileError::str(&format!(
+                        "wrong number of type arguments for {}",
+                        base
+                    )));
+                }
+
+                let type_args = params
+                    .iter()
+                    .zip(args.iter())
+                    .map(|(param, arg)| Ok((param.node, type_desc(type_defs, &arg.node)?)))
+                    .collect::<Result<_, CompileError>>()?;
+
+                return struct_def(type_defs, fields, &type_args);
+            }
+
+            Err(CompileError::str(&format!("unrecognized type: {}", base)))
+        }
     }
 }
 
+/// Structs are encoded as tuples of their fields. Fields of generic structs
+/// whose type is a type parameter are encoded as the given type argument.
+fn struct_def<'a>(
+    type_defs: &'a TypeDefs<'a>,
+    fields: &'a [Spanned<fe::StructStmt<'a>>],
+    type_args: &HashMap<&str, VarType>,
+) -> Result<VarType, CompileError> {
+    let components = fields
+        .iter()
+        .map(|field| match &field.node {
+            fe::StructStmt::StructField { name, typ, .. } => Ok(Component {
+                name: name.node.to_owned(),
+                typ: match &typ.node {
+                    fe::TypeDesc::Base { base } if type_args.contains_key(base) => {
+                        type_args[base].clone()
+                    }
+                    typ => type_desc(type_defs, typ)?,
+                },
+            }),
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(VarType::Struct(components))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::abi::builder;
-    use crate::abi::elements::VarType;
+    use crate::abi::elements::{
+        Component,
+        FuncOutput,
+        FuncType,
+        StateMutability,
+        VarType,
+    };
     use fe_parser::parsers;
 
     #[test]
@@ -255,4 +538,236 @@ mod tests {
             panic!("contract \"Foo\" not found in module")
         }
     }
+
+    #[test]
+    fn named_returns() {
+        let tokens = fe_parser::get_parse_tokens(
+            "\
+            \ncontract Foo:\
+            \n  pub def divmod(a: u256, b: u256) -> (quotient: u256, remainder: u256):\
+            \n    return (a / b, a % b)",
+        )
+        .expect("unable to parse contract");
+
+        let module = parsers::file_input(&tokens[..])
+            .expect("unable to build module AST")
+            .1
+            .node;
+        let abis = builder::module(&module).expect("unable to build ABIs");
+
+        assert_eq!(
+            abis["Foo"].functions[0].outputs,
+            vec![
+                FuncOutput {
+                    name: "quotient".to_string(),
+                    typ: VarType::Uint256
+                },
+                FuncOutput {
+                    name: "remainder".to_string(),
+                    typ: VarType::Uint256
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn state_mutability() {
+        let tokens = fe_parser::get_parse_tokens(
+            "\
+            \ncontract Foo:\
+            \n  x: u256\
+            \n  @payable\
+            \n  pub def deposit():\
+            \n    pass\
+            \n  @view\
+            \n  pub def get() -> u256:\
+            \n    return self.x\
+            \n  @pure\
+            \n  pub def double(y: u256) -> u256:\
+            \n    return y * 2\
+            \n  pub def set(y: u256):\
+            \n    self.x = y",
+        )
+        .expect("unable to parse contract");
+
+        let module = parsers::file_input(&tokens[..])
+            .expect("unable to build module AST")
+            .1
+            .node;
+        let abis = builder::module(&module).expect("unable to build ABIs");
+
+        assert_eq!(
+            abis["Foo"]
+                .functions
+                .iter()
+                .map(|function| function.state_mutability.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                StateMutability::Payable,
+                StateMutability::View,
+                StateMutability::Pure,
+                StateMutability::Nonpayable
+            ]
+        );
+    }
+
+    #[test]
+    fn private_constructor() {
+        let tokens = fe_parser::get_parse_tokens(
+            "\
+            \ncontract Foo:\
+            \n  def __init__(x: address, y: u256):\
+            \n    pass",
+        )
+        .expect("unable to parse contract");
+
+        let module = parsers::file_input(&tokens[..])
+            .expect("unable to build module AST")
+            .1
+            .node;
+        let abis = builder::module(&module).expect("unable to build ABIs");
+        let abi = &abis["Foo"];
+
+        assert_eq!(abi.functions.len(), 1, "constructor not found in ABI");
+        assert_eq!(abi.functions[0].typ, FuncType::Constructor);
+        assert_eq!(
+            abi.functions[0]
+                .inputs
+                .iter()
+                .map(|input| input.typ.clone())
+                .collect::<Vec<_>>(),
+            vec![VarType::Address, VarType::Uint256]
+        );
+    }
+
+    #[test]
+    fn selector_collision() {
+        // `burn(uint256)` and `collate_propagate_storage(bytes16)` both hash to
+        // the selector 0x42966c68.
+        let tokens = fe_parser::get_parse_tokens(
+            "\
+            \ncontract Foo:\
+            \n  pub def burn(x: u256):\
+            \n    pass\
+            \n  pub def collate_propagate_storage(x: bytes[16]):\
+            \n    pass",
+        )
+        .expect("unable to parse contract");
+
+        let module = parsers::file_input(&tokens[..])
+            .expect("unable to build module AST")
+            .1
+            .node;
+        let err = builder::module(&module).expect_err("selector collision not detected");
+
+        assert_eq!(
+            err.to_string(),
+            "function selector collision: burn(uint256) and \
+             collate_propagate_storage(bytes16) share the selector 0x42966c68"
+        );
+    }
+
+    #[test]
+    fn struct_param() {
+        let tokens = fe_parser::get_parse_tokens(
+            "\
+            \nstruct House:\
+            \n  price: u256\
+            \n  vacant: bool\
+            \ncontract Foo:\
+            \n  pub def bar(x: House) -> House:\
+            \n    return x",
+        )
+        .expect("unable to parse contract");
+
+        let module = parsers::file_input(&tokens[..])
+            .expect("unable to build module AST")
+            .1
+            .node;
+        let abis = builder::module(&module).expect("unable to build ABIs");
+        let house = VarType::Struct(vec![
+            Component {
+                name: "price".to_string(),
+                typ: VarType::Uint256,
+            },
+            Component {
+                name: "vacant".to_string(),
+                typ: VarType::Bool,
+            },
+        ]);
+
+        let function = &abis["Foo"].functions[0];
+        assert_eq!(function.inputs[0].typ, house);
+        assert_eq!(function.outputs[0].typ, house);
+        assert_eq!(function.signature(), "bar((uint256,bool))");
+    }
+
+    #[test]
+    fn generic_struct_param() {
+        let tokens = fe_parser::get_parse_tokens(
+            "\
+            \nstruct Pair<T>:\
+            \n  first: T\
+            \n  second: T\
+            \ncontract Foo:\
+            \n  pub def bar(x: Pair<address>) -> u256:\
+            \n    return 0",
+        )
+        .expect("unable to parse contract");
+
+        let module = parsers::file_input(&tokens[..])
+            .expect("unable to build module AST")
+            .1
+            .node;
+        let abis = builder::module(&module).expect("unable to build ABIs");
+
+        let function = &abis["Foo"].functions[0];
+        assert_eq!(function.signature(), "bar((address,address))");
+    }
+
+    #[test]
+    fn enum_param() {
+        let tokens = fe_parser::get_parse_tokens(
+            "\
+            \nenum Color:\
+            \n  Red\
+            \n  Green\
+            \ncontract Foo:\
+            \n  pub def bar(x: Color) -> Color:\
+            \n    return x",
+        )
+        .expect("unable to parse contract");
+
+        let module = parsers::file_input(&tokens[..])
+            .expect("unable to build module AST")
+            .1
+            .node;
+        let abis = builder::module(&module).expect("unable to build ABIs");
+
+        let function = &abis["Foo"].functions[0];
+        assert_eq!(function.inputs[0].typ, VarType::Uint8);
+        assert_eq!(function.outputs[0].typ, VarType::Uint8);
+    }
+
+    #[test]
+    fn tuple_return() {
+        let tokens = fe_parser::get_parse_tokens(
+            "\
+            \ncontract Foo:\
+            \n  pub def bar() -> (u256, bool):\
+            \n    return (42, true)",
+        )
+        .expect("unable to parse contract");
+
+        let module = parsers::file_input(&tokens[..])
+            .expect("unable to build module AST")
+            .1
+            .node;
+        let abis = builder::module(&module).expect("unable to build ABIs");
+
+        let function = &abis["Foo"].functions[0];
+        assert_eq!(function.outputs.len(), 2);
+        assert_eq!(function.outputs[0].typ, VarType::Uint256);
+        assert_eq!(function.outputs[1].typ, VarType::Bool);
+    }
 }
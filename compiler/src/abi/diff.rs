@@ -0,0 +1,301 @@
+//! Compares the ABIs of two builds of a module and classifies the changes as
+//! breaking or additive, so that a new version of a contract can be checked
+//! against the interface that its callers depend on.
+
+use crate::errors::CompileError;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A change between the ABI of a contract in an old build and in a new one.
+///
+/// Items are identified by their signature, so a function whose parameter
+/// types changed is reported as removed and added. Changes of parameter names
+/// don't affect callers and aren't reported.
+#[derive(Debug, PartialEq)]
+pub enum AbiChange {
+    ContractRemoved {
+        contract: String,
+    },
+    ContractAdded {
+        contract: String,
+    },
+    /// A function, event, error, constructor, fallback or receive function
+    /// no longer exists, e.g. `function transfer(address,uint256)`.
+    Removed {
+        contract: String,
+        item: String,
+    },
+    Added {
+        contract: String,
+        item: String,
+    },
+    /// The function returns different types.
+    OutputsChanged {
+        contract: String,
+        item: String,
+        old: String,
+        new: String,
+    },
+    /// The state mutability of the function changed, e.g. from `view` to
+    /// `nonpayable`.
+    MutabilityChanged {
+        contract: String,
+        item: String,
+        old: String,
+        new: String,
+    },
+}
+
+impl AbiChange {
+    /// Whether callers of the old ABI may fail against the new one.
+    pub fn is_breaking(&self) -> bool {
+        match self {
+            AbiChange::ContractRemoved { .. }
+            | AbiChange::Removed { .. }
+            | AbiChange::OutputsChanged { .. } => true,
+            AbiChange::ContractAdded { .. } | AbiChange::Added { .. } => false,
+            // calls that send ether fail if the function is no longer payable,
+            // and static calls fail if it may change the state
+            AbiChange::MutabilityChanged { old, new, .. } => {
+                (old == "payable" && new != "payable")
+                    || ((old == "view" || old == "pure")
+                        && (new == "nonpayable" || new == "payable"))
+            }
+        }
+    }
+}
+
+impl fmt::Display for AbiChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AbiChange::ContractRemoved { contract } => {
+                write!(f, "contract `{}` was removed", contract)
+            }
+            AbiChange::ContractAdded { contract } => write!(f, "contract `{}` was added", contract),
+            AbiChange::Removed { contract, item } => {
+                write!(f, "`{}`: {} was removed", contract, item)
+            }
+            AbiChange::Added { contract, item } => write!(f, "`{}`: {} was added", contract, item),
+            AbiChange::OutputsChanged {
+                contract,
+                item,
+                old,
+                new,
+            } => write!(
+                f,
+                "`{}`: {} returns ({}) instead of ({})",
+                contract, item, new, old
+            ),
+            AbiChange::MutabilityChanged {
+                contract,
+                item,
+                old,
+                new,
+            } => write!(
+                f,
+                "`{}`: {} changed from {} to {}",
+                contract, item, old, new
+            ),
+        }
+    }
+}
+
+/// An item of a JSON ABI, keyed by its signature.
+struct Item {
+    outputs: String,
+    mutability: String,
+}
+
+/// Finds the changes between the JSON ABIs of the contracts of two builds.
+pub fn changes(
+    old: &BTreeMap<String, String>,
+    new: &BTreeMap<String, String>,
+) -> Result<Vec<AbiChange>, CompileError> {
+    let mut changes = vec![];
+
+    for (contract, old_abi) in old.iter() {
+        let new_abi = match new.get(contract) {
+            Some(abi) => abi,
+            None => {
+                changes.push(AbiChange::ContractRemoved {
+                    contract: contract.clone(),
+                });
+                continue;
+            }
+        };
+        let (old_items, new_items) = (items(old_abi)?, items(new_abi)?);
+
+        for (item, old_item) in old_items.iter() {
+            let (contract, item) = (contract.clone(), item.clone());
+            match new_items.get(&item) {
+                None => changes.push(AbiChange::Removed { contract, item }),
+                Some(new_item) if new_item.outputs != old_item.outputs => {
+                    changes.push(AbiChange::OutputsChanged {
+                        contract,
+                        item,
+                        old: old_item.outputs.clone(),
+                        new: new_item.outputs.clone(),
+                    })
+                }
+                Some(new_item) if new_item.mutability != old_item.mutability => {
+                    changes.push(AbiChange::MutabilityChanged {
+                        contract,
+                        item,
+                        old: old_item.mutability.clone(),
+                        new: new_item.mutability.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        for item in new_items.keys() {
+            if !old_items.contains_key(item) {
+                changes.push(AbiChange::Added {
+                    contract: contract.clone(),
+                    item: item.clone(),
+                })
+            }
+        }
+    }
+
+    for contract in new.keys() {
+        if !old.contains_key(contract) {
+            changes.push(AbiChange::ContractAdded {
+                contract: contract.clone(),
+            })
+        }
+    }
+
+    Ok(changes)
+}
+
+fn items(json_abi: &str) -> Result<BTreeMap<String, Item>, CompileError> {
+    let entries: Vec<Value> = serde_json::from_str(json_abi)?;
+
+    Ok(entries
+        .iter()
+        .map(|entry| {
+            let kind = entry["type"].as_str().unwrap_or("function");
+            let signature = match kind {
+                "constructor" | "fallback" | "receive" => {
+                    format!("{}({})", kind, params(&entry["inputs"], false))
+                }
+                _ => format!(
+                    "{} {}({})",
+                    kind,
+                    entry["name"].as_str().unwrap_or_default(),
+                    params(&entry["inputs"], kind == "event")
+                ),
+            };
+            let item = Item {
+                outputs: params(&entry["outputs"], false),
+                mutability: entry["stateMutability"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            };
+
+            (signature, item)
+        })
+        .collect())
+}
+
+/// The canonical types of the parameters, separated by commas. The parameters
+/// of events are marked if they are indexed, which changes how they are
+/// logged.
+fn params(params: &Value, events: bool) -> String {
+    params
+        .as_array()
+        .map(|params| {
+            params
+                .iter()
+                .map(|param| {
+                    let typ = canonical_type(param);
+                    if events && param["indexed"] == true {
+                        format!("{} indexed", typ)
+                    } else {
+                        typ
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .unwrap_or_default()
+}
+
+/// The type of a parameter, with tuples written out as their components.
+fn canonical_type(param: &Value) -> String {
+    let typ = param["type"].as_str().unwrap_or_default();
+    match typ.strip_prefix("tuple") {
+        Some(dimensions) => format!("({}){}", params(&param["components"], false), dimensions),
+        None => typ.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::abi::diff::{
+        changes,
+        AbiChange,
+    };
+    use std::collections::BTreeMap;
+
+    const OLD: &str = r#"[
+        {"type": "function", "name": "transfer", "inputs": [{"name": "to", "type": "address"}, {"name": "value", "type": "uint256"}], "outputs": [{"name": "", "type": "bool"}], "stateMutability": "nonpayable"},
+        {"type": "function", "name": "balance_of", "inputs": [{"name": "owner", "type": "address"}], "outputs": [{"name": "", "type": "uint256"}], "stateMutability": "view"},
+        {"type": "function", "name": "deposit", "inputs": [], "outputs": [], "stateMutability": "payable"},
+        {"type": "event", "name": "Transfer", "inputs": [{"name": "from", "type": "address", "indexed": true}, {"name": "value", "type": "uint256", "indexed": false}], "anonymous": false}
+    ]"#;
+
+    const NEW: &str = r#"[
+        {"type": "function", "name": "transfer", "inputs": [{"name": "recipient", "type": "address"}, {"name": "value", "type": "uint256"}], "outputs": [{"name": "", "type": "bool"}], "stateMutability": "nonpayable"},
+        {"type": "function", "name": "balance_of", "inputs": [{"name": "owner", "type": "address"}], "outputs": [{"name": "", "type": "uint256"}], "stateMutability": "nonpayable"},
+        {"type": "function", "name": "deposit", "inputs": [{"name": "to", "type": "address"}], "outputs": [], "stateMutability": "payable"},
+        {"type": "event", "name": "Transfer", "inputs": [{"name": "from", "type": "address", "indexed": true}, {"name": "value", "type": "uint256", "indexed": false}], "anonymous": false}
+    ]"#;
+
+    fn abis(abi: &str) -> BTreeMap<String, String> {
+        vec![("Token".to_string(), abi.to_string())]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn classified_changes() {
+        assert_eq!(changes(&abis(OLD), &abis(OLD)).unwrap(), vec![]);
+
+        let changes = changes(&abis(OLD), &abis(NEW)).unwrap();
+        let descriptions = changes
+            .iter()
+            .map(|change| (change.is_breaking(), change.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            descriptions,
+            vec![
+                (
+                    true,
+                    "`Token`: function balance_of(address) changed from view to nonpayable"
+                        .to_string()
+                ),
+                (true, "`Token`: function deposit() was removed".to_string()),
+                (
+                    false,
+                    "`Token`: function deposit(address) was added".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn contracts() {
+        let changes = changes(&abis(OLD), &BTreeMap::new()).unwrap();
+        assert_eq!(
+            changes,
+            vec![AbiChange::ContractRemoved {
+                contract: "Token".to_string()
+            }]
+        );
+        assert!(changes[0].is_breaking());
+    }
+}
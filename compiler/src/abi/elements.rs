@@ -2,7 +2,10 @@ use crate::errors::CompileError;
 use core::fmt;
 use serde::export::fmt::Error;
 use serde::export::Formatter;
-use serde::ser::SerializeSeq;
+use serde::ser::{
+    SerializeMap,
+    SerializeSeq,
+};
 use serde::{
     Serialize,
     Serializer,
@@ -17,6 +20,8 @@ pub type ModuleAbis = HashMap<String, Contract>;
 pub struct Contract {
     /// All events defined in a contract.
     pub events: Vec<Event>,
+    /// All custom errors defined in a contract.
+    pub errors: Vec<CustomError>,
     /// All public functions defined in a contract.
     pub functions: Vec<Function>,
 }
@@ -31,6 +36,7 @@ impl Contract {
     pub fn new() -> Self {
         Self {
             events: vec![],
+            errors: vec![],
             functions: vec![],
         }
     }
@@ -58,6 +64,10 @@ impl Serialize for Contract {
             seq.serialize_element(event)?;
         }
 
+        for error in self.errors.iter() {
+            seq.serialize_element(error)?;
+        }
+
         for function in self.functions.iter() {
             seq.serialize_element(function)?;
         }
@@ -84,22 +94,34 @@ pub struct Event {
 }
 
 /// A single event field.
-#[derive(Serialize, Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct EventField {
     /// The event field's name.
     pub name: String,
     /// The type of an event (e.g. u256, address, bytes100,...)
-    #[serde(rename = "type")]
     pub typ: VarType,
     /// True if the field is part of the log’s topics, false if it is one of the
     /// log’s data segment.
     pub indexed: bool,
 }
 
+/// A custom error interface.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct CustomError {
+    /// The error's name.
+    pub name: String,
+    /// The type of an error (Always "error").
+    #[serde(rename = "type")]
+    pub typ: String,
+    /// All error fields.
+    pub inputs: Vec<FuncInput>,
+}
+
 /// A function interface.
 #[derive(Serialize, Debug, PartialEq, Clone)]
 pub struct Function {
-    /// The function's name.
+    /// The function's name. Constructors don't have a name.
+    #[serde(skip_serializing_if = "String::is_empty")]
     pub name: String,
     /// The type of a function (Function, Constructor, Receive, and Fallback)
     #[serde(rename = "type")]
@@ -108,25 +130,49 @@ pub struct Function {
     pub inputs: Vec<FuncInput>,
     /// All function outputs.
     pub outputs: Vec<FuncOutput>,
+    /// Whether the function can read or modify the state and receive ether.
+    #[serde(rename = "stateMutability")]
+    pub state_mutability: StateMutability,
+}
+
+impl Function {
+    /// The canonical signature of the function e.g. `transfer(address,uint256)`.
+    pub fn signature(&self) -> String {
+        let params = self
+            .inputs
+            .iter()
+            .map(|input| input.typ.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+
+        format!("{}({})", self.name, params)
+    }
 }
 
 /// A single function input.
-#[derive(Serialize, Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct FuncInput {
     /// The input's name.
     pub name: String,
     /// The input's type.
-    #[serde(rename = "type")]
     pub typ: VarType,
 }
 
 /// A single function output.
-#[derive(Serialize, Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct FuncOutput {
     /// The output's name.
     pub name: String,
     /// The output's type.
-    #[serde(rename = "type")]
+    pub typ: VarType,
+}
+
+/// A named component of a struct type.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Component {
+    /// The component's name.
+    pub name: String,
+    /// The component's type.
     pub typ: VarType,
 }
 
@@ -160,14 +206,45 @@ pub enum VarType {
     Int8,
     Bool,
     Address,
+    Fixed {
+        signed: bool,
+        bits: usize,
+        decimals: usize,
+    },
     FixedBytes(usize),
     FixedArray(Box<VarType>, usize),
+    Bytes,
+    DynamicArray(Box<VarType>),
     String,
     Tuple(Vec<VarType>),
+    Struct(Vec<Component>),
+}
+
+impl VarType {
+    /// The named components of a struct type, or of the elements of an array of
+    /// structs.
+    pub fn components(&self) -> Option<&[Component]> {
+        match self {
+            VarType::Struct(components) => Some(components),
+            VarType::FixedArray(inner, _) => inner.components(),
+            VarType::DynamicArray(inner) => inner.components(),
+            _ => None,
+        }
+    }
+
+    /// The type name used in JSON ABIs. Structs are written as `tuple` and their
+    /// field types are listed separately as components.
+    fn json_name(&self) -> String {
+        match self {
+            VarType::Struct(_) => "tuple".to_string(),
+            VarType::FixedArray(inner, dim) => format!("{}[{}]", inner.json_name(), dim),
+            VarType::DynamicArray(inner) => format!("{}[]", inner.json_name()),
+            _ => self.to_string(),
+        }
+    }
 }
 
 /// The mutability of a public function.
-#[allow(dead_code)]
 #[derive(Serialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum StateMutability {
@@ -194,8 +271,21 @@ impl fmt::Display for VarType {
             VarType::Int16 => write!(formatter, "int16"),
             VarType::Int8 => write!(formatter, "int8"),
             VarType::Address => write!(formatter, "address"),
+            VarType::Fixed {
+                signed,
+                bits,
+                decimals,
+            } => write!(
+                formatter,
+                "{}fixed{}x{}",
+                if *signed { "" } else { "u" },
+                bits,
+                decimals
+            ),
             VarType::FixedBytes(size) => write!(formatter, "bytes{}", size),
             VarType::FixedArray(inner, dim) => write!(formatter, "{}[{}]", inner, dim),
+            VarType::Bytes => write!(formatter, "bytes"),
+            VarType::DynamicArray(inner) => write!(formatter, "{}[]", inner),
             VarType::String => write!(formatter, "string"),
             VarType::Tuple(items) => {
                 let items = items
@@ -205,6 +295,14 @@ impl fmt::Display for VarType {
                     .join(",");
                 write!(formatter, "({})", items)
             }
+            VarType::Struct(components) => {
+                let items = components
+                    .iter()
+                    .map(|component| component.typ.to_string())
+                    .collect::<Vec<String>>()
+                    .join(",");
+                write!(formatter, "({})", items)
+            }
         }
     }
 }
@@ -214,20 +312,85 @@ impl Serialize for VarType {
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        serializer.serialize_str(&self.json_name())
+    }
+}
+
+/// Serializes a named and typed ABI parameter, including the components of
+/// struct types.
+fn serialize_param<S>(
+    serializer: S,
+    name: &str,
+    typ: &VarType,
+    indexed: Option<bool>,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut map = serializer.serialize_map(None)?;
+    map.serialize_entry("name", name)?;
+    map.serialize_entry("type", typ)?;
+
+    if let Some(components) = typ.components() {
+        map.serialize_entry("components", components)?;
+    }
+
+    if let Some(indexed) = indexed {
+        map.serialize_entry("indexed", &indexed)?;
+    }
+
+    map.end()
+}
+
+impl Serialize for EventField {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_param(serializer, &self.name, &self.typ, Some(self.indexed))
+    }
+}
+
+impl Serialize for FuncInput {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_param(serializer, &self.name, &self.typ, None)
+    }
+}
+
+impl Serialize for FuncOutput {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_param(serializer, &self.name, &self.typ, None)
+    }
+}
+
+impl Serialize for Component {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_param(serializer, &self.name, &self.typ, None)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::abi::elements::{
+        Component,
         Contract,
+        CustomError,
         Event,
         EventField,
         FuncInput,
         FuncOutput,
         FuncType,
         Function,
+        StateMutability,
         VarType,
     };
 
@@ -244,6 +407,14 @@ mod tests {
                 }],
                 anonymous: false,
             }],
+            errors: vec![CustomError {
+                name: "error_name".to_string(),
+                typ: "error".to_string(),
+                inputs: vec![FuncInput {
+                    name: "input_name".to_string(),
+                    typ: VarType::Uint256,
+                }],
+            }],
             functions: vec![Function {
                 name: "function_name".to_string(),
                 typ: FuncType::Function,
@@ -255,6 +426,7 @@ mod tests {
                     name: "output_name".to_string(),
                     typ: VarType::Uint256,
                 }],
+                state_mutability: StateMutability::Payable,
             }],
         };
 
@@ -267,11 +439,17 @@ mod tests {
                     "inputs":[{"name":"input_name","type":"uint256","indexed":true}],
                     "anonymous":false
                 },
+                {
+                    "name":"error_name",
+                    "type":"error",
+                    "inputs":[{"name":"input_name","type":"uint256"}]
+                },
                 {
                     "name":"function_name",
                     "type":"function",
                     "inputs":[{"name":"input_name","type":"address"}],
-                    "outputs":[{"name":"output_name","type":"uint256"}]
+                    "outputs":[{"name":"output_name","type":"uint256"}],
+                    "stateMutability":"payable"
                 }
             ]"#
             .split_whitespace()
@@ -294,4 +472,57 @@ mod tests {
             r#""address[42]""#
         )
     }
+
+    #[test]
+    fn dynamic_array() {
+        assert_eq!(
+            serde_json::to_string(&VarType::DynamicArray(Box::new(VarType::Uint256))).unwrap(),
+            r#""uint256[]""#
+        )
+    }
+
+    #[test]
+    fn fixed_point() {
+        assert_eq!(
+            serde_json::to_string(&VarType::Fixed {
+                signed: false,
+                bits: 128,
+                decimals: 18,
+            })
+            .unwrap(),
+            r#""ufixed128x18""#
+        )
+    }
+
+    #[test]
+    fn struct_input() {
+        let input = FuncInput {
+            name: "house".to_string(),
+            typ: VarType::Struct(vec![
+                Component {
+                    name: "price".to_string(),
+                    typ: VarType::Uint256,
+                },
+                Component {
+                    name: "vacant".to_string(),
+                    typ: VarType::Bool,
+                },
+            ]),
+        };
+
+        assert_eq!(input.typ.to_string(), "(uint256,bool)");
+        assert_eq!(
+            serde_json::to_string(&input).unwrap(),
+            r#"{
+                "name":"house",
+                "type":"tuple",
+                "components":[
+                    {"name":"price","type":"uint256"},
+                    {"name":"vacant","type":"bool"}
+                ]
+            }"#
+            .split_whitespace()
+            .collect::<String>(),
+        )
+    }
 }
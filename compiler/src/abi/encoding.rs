@@ -0,0 +1,231 @@
+//! ABI encoding of calls whose arguments are written as text, e.g. in a
+//! script or on the command line.
+
+use crate::errors::CompileError;
+use ethabi::param_type::Reader;
+use ethabi::token::{
+    LenientTokenizer,
+    Tokenizer,
+};
+use ethabi::{
+    Contract,
+    Function,
+    Param,
+};
+use serde_json::Value;
+
+/// Loads a JSON ABI. Events and errors are left out, since they aren't needed
+/// to encode calls.
+pub fn load(json_abi: &str) -> Result<Contract, CompileError> {
+    let entries: Vec<Value> = serde_json::from_str(json_abi)?;
+    let callable = entries
+        .into_iter()
+        .filter(|entry| entry["type"] != "event" && entry["type"] != "error")
+        .collect::<Vec<_>>();
+
+    Ok(Contract::load(
+        serde_json::to_string(&callable)?.as_bytes(),
+    )?)
+}
+
+/// Encodes a call of the function with the given arguments. Numbers may be
+/// written in decimal, and addresses and bytes in hex without a `0x` prefix.
+pub fn encode_call(
+    abi: &Contract,
+    function: &str,
+    args: &[String],
+) -> Result<Vec<u8>, CompileError> {
+    let function = abi
+        .function(function)
+        .map_err(|_| CompileError::str(&format!("no function named `{}`", function)))?;
+
+    Ok(function.encode_input(&tokenize(&function.inputs, args)?)?)
+}
+
+/// Encodes a call of the function with the given signature, e.g.
+/// `transfer(address,uint256)`, for functions whose ABI isn't at hand.
+pub fn encode_signature_call(signature: &str, args: &[String]) -> Result<Vec<u8>, CompileError> {
+    let function = parse_signature(signature)?;
+
+    Ok(function.encode_input(&tokenize(&function.inputs, args)?)?)
+}
+
+/// Parses a function signature into a function whose parameters are named by
+/// their position.
+fn parse_signature(signature: &str) -> Result<Function, CompileError> {
+    let invalid = || CompileError::str(&format!("invalid function signature `{}`", signature));
+    let signature = signature.trim();
+    let (name, params) = signature
+        .strip_suffix(')')
+        .and_then(|signature| signature.split_once('('))
+        .ok_or_else(invalid)?;
+    if name.is_empty() {
+        return Err(invalid());
+    }
+
+    // tuples contain commas themselves
+    let mut types = vec![];
+    let (mut depth, mut start) = (0, 0);
+    for (index, character) in params.char_indices() {
+        match character {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                types.push(&params[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    if !params.trim().is_empty() {
+        types.push(&params[start..]);
+    }
+
+    let inputs = types
+        .iter()
+        .enumerate()
+        .map(|(index, typ)| {
+            Ok(Param {
+                name: (index + 1).to_string(),
+                kind: Reader::read(typ.trim()).map_err(|_| {
+                    CompileError::str(&format!("invalid type `{}` in `{}`", typ.trim(), signature))
+                })?,
+            })
+        })
+        .collect::<Result<Vec<_>, CompileError>>()?;
+
+    Ok(Function {
+        name: name.trim().to_string(),
+        inputs,
+        outputs: vec![],
+        constant: false,
+    })
+}
+
+/// Appends the encoded constructor arguments to the creation bytecode.
+pub fn encode_constructor(
+    abi: &Contract,
+    bytecode: Vec<u8>,
+    args: &[String],
+) -> Result<Vec<u8>, CompileError> {
+    match &abi.constructor {
+        Some(constructor) => {
+            Ok(constructor.encode_input(bytecode, &tokenize(&constructor.inputs, args)?)?)
+        }
+        None if args.is_empty() => Ok(bytecode),
+        None => Err(CompileError::static_str(
+            "the contract has no constructor that takes arguments",
+        )),
+    }
+}
+
+/// Decodes the return data of a call of the function into a text
+/// representation of each returned value.
+pub fn decode_output(
+    abi: &Contract,
+    function: &str,
+    output: &[u8],
+) -> Result<Vec<String>, CompileError> {
+    let function = abi
+        .function(function)
+        .map_err(|_| CompileError::str(&format!("no function named `{}`", function)))?;
+
+    Ok(function
+        .decode_output(output)?
+        .iter()
+        .map(|token| token.to_string())
+        .collect())
+}
+
+fn tokenize(params: &[Param], args: &[String]) -> Result<Vec<ethabi::Token>, CompileError> {
+    if params.len() != args.len() {
+        return Err(CompileError::str(&format!(
+            "expected {} argument(s), found {}",
+            params.len(),
+            args.len()
+        )));
+    }
+
+    params
+        .iter()
+        .zip(args.iter())
+        .map(|(param, arg)| {
+            LenientTokenizer::tokenize(&param.kind, arg.trim_start_matches("0x")).map_err(|_| {
+                CompileError::str(&format!(
+                    "invalid value `{}` for parameter `{}` of type `{}`",
+                    arg, param.name, param.kind
+                ))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::abi::encoding::{
+        decode_output,
+        encode_call,
+        encode_signature_call,
+        load,
+    };
+
+    const ABI: &str = r#"[
+        {"type": "event", "name": "Transfer", "inputs": [], "anonymous": false},
+        {
+            "type": "function",
+            "name": "transfer",
+            "inputs": [{"name": "to", "type": "address"}, {"name": "value", "type": "uint256"}],
+            "outputs": [{"name": "", "type": "bool"}],
+            "stateMutability": "nonpayable"
+        }
+    ]"#;
+
+    #[test]
+    fn transfer_call() {
+        let abi = load(ABI).unwrap();
+        let args = vec![
+            "0x0000000000000000000000000000000000000002".to_string(),
+            "100".to_string(),
+        ];
+        let encoded = encode_call(&abi, "transfer", &args).unwrap();
+
+        assert_eq!(hex::encode(&encoded[..4]), "a9059cbb");
+        assert_eq!(encoded.len(), 4 + 2 * 32);
+        assert_eq!(encoded[4 + 31], 2);
+        assert_eq!(encoded[4 + 63], 100);
+
+        let mut output = [0; 32];
+        output[31] = 1;
+        assert_eq!(
+            decode_output(&abi, "transfer", &output).unwrap(),
+            vec!["true"]
+        );
+    }
+
+    #[test]
+    fn signature_call() {
+        let args = vec![
+            "0x0000000000000000000000000000000000000002".to_string(),
+            "100".to_string(),
+        ];
+        assert_eq!(
+            encode_signature_call("transfer(address,uint256)", &args).unwrap(),
+            encode_call(&load(ABI).unwrap(), "transfer", &args).unwrap()
+        );
+
+        assert_eq!(
+            hex::encode(encode_signature_call("pause()", &[]).unwrap()),
+            "8456cb59"
+        );
+        assert!(encode_signature_call("transfer(address,uint256", &args).is_err());
+        assert!(encode_signature_call("transfer(address,u256)", &args).is_err());
+    }
+
+    #[test]
+    fn wrong_arguments() {
+        let abi = load(ABI).unwrap();
+        assert!(encode_call(&abi, "transfer", &["2".to_string()]).is_err());
+        assert!(encode_call(&abi, "transfer", &["2".to_string(), "x".to_string()]).is_err());
+        assert!(encode_call(&abi, "mint", &[]).is_err());
+    }
+}
@@ -7,6 +7,8 @@ use crate::types::{
 };
 
 mod builder;
+pub mod diff;
+pub mod encoding;
 pub mod utils;
 
 /// Elements used to define contract ABIs.
@@ -0,0 +1,167 @@
+//! The index of the files written to the output directory
+//! (`artifacts.json`).
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use sha2::{
+    Digest,
+    Sha256,
+};
+use std::fs;
+use std::path::{
+    Component,
+    Path,
+    PathBuf,
+};
+
+pub const ARTIFACTS_FILE_NAME: &str = "artifacts.json";
+
+/// A file that is written to the output directory.
+pub struct Output {
+    /// The path relative to the output directory.
+    pub path: PathBuf,
+    /// The contract that the file was compiled from, if it isn't an output of
+    /// the whole module.
+    pub contract: Option<String>,
+    /// The name of the emit target, e.g. `abi`.
+    pub target: String,
+    pub content: Vec<u8>,
+}
+
+/// An entry of the index.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Artifact {
+    /// The path relative to the output directory, separated by `/` on all
+    /// platforms.
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract: Option<String>,
+    pub target: String,
+    /// The hex encoded SHA-256 hash of the file.
+    pub sha256: String,
+}
+
+/// The contents of an index file.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Artifacts {
+    pub artifacts: Vec<Artifact>,
+}
+
+impl Artifacts {
+    /// Creates the index of the outputs, in the order they are given.
+    pub fn new(outputs: &[Output]) -> Self {
+        let artifacts = outputs
+            .iter()
+            .map(|output| Artifact {
+                path: index_path(&output.path),
+                contract: output.contract.clone(),
+                target: output.target.clone(),
+                sha256: format!("{:x}", Sha256::digest(&output.content)),
+            })
+            .collect();
+
+        Self { artifacts }
+    }
+
+    /// Loads the index of the given output directory, if there is a valid
+    /// one.
+    pub fn load(output_dir: &Path) -> Option<Self> {
+        let content = fs::read_to_string(output_dir.join(ARTIFACTS_FILE_NAME)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("unable to serialize artifacts")
+    }
+
+    /// Returns the paths of the indexed files that aren't among the outputs.
+    ///
+    /// Paths that would point outside of the output directory are ignored,
+    /// so a modified index can't be used to remove other files.
+    pub fn stale(&self, outputs: &[Output]) -> Vec<PathBuf> {
+        let current = outputs
+            .iter()
+            .map(|output| index_path(&output.path))
+            .collect::<Vec<_>>();
+
+        self.artifacts
+            .iter()
+            .filter(|artifact| !current.contains(&artifact.path))
+            .map(|artifact| PathBuf::from(&artifact.path))
+            .filter(|path| {
+                path.components()
+                    .all(|component| matches!(component, Component::Normal(_)))
+            })
+            .collect()
+    }
+}
+
+fn index_path(path: &Path) -> String {
+    path.iter()
+        .map(|component| component.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::artifacts::{
+        Artifacts,
+        Output,
+    };
+    use std::path::PathBuf;
+
+    fn output(path: &str) -> Output {
+        Output {
+            path: PathBuf::from(path),
+            contract: None,
+            target: "abi".to_string(),
+            content: vec![],
+        }
+    }
+
+    #[test]
+    fn stale() {
+        let previous = Artifacts::new(&[
+            output("Foo/Foo_abi.json"),
+            output("Bar/Bar_abi.json"),
+            output("../escape.json"),
+        ]);
+        let stale = previous.stale(&[output("Foo/Foo_abi.json")]);
+
+        assert_eq!(stale, vec![PathBuf::from("Bar/Bar_abi.json")]);
+    }
+
+    #[test]
+    fn checksums() {
+        let outputs = vec![
+            Output {
+                path: PathBuf::from("module.ast"),
+                contract: None,
+                target: "ast".to_string(),
+                content: vec![],
+            },
+            Output {
+                path: PathBuf::from("Foo").join("Foo_abi.json"),
+                contract: Some("Foo".to_string()),
+                target: "abi".to_string(),
+                content: b"abc".to_vec(),
+            },
+        ];
+        let artifacts = Artifacts::new(&outputs).artifacts;
+
+        assert_eq!(artifacts[0].path, "module.ast");
+        assert_eq!(
+            artifacts[0].sha256,
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(artifacts[1].path, "Foo/Foo_abi.json");
+        assert_eq!(artifacts[1].contract.as_deref(), Some("Foo"));
+        assert_eq!(
+            artifacts[1].sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
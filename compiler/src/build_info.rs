@@ -0,0 +1,133 @@
+//! Build info files, which contain the complete input and output of a
+//! compilation so that tools can reproduce or inspect it.
+
+use crate::artifacts::Output;
+use crate::types::CompiledModule;
+use serde::Serialize;
+use sha2::{
+    Digest,
+    Sha256,
+};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+pub const BUILD_INFO_DIR_NAME: &str = "build-info";
+pub(crate) const FORMAT: &str = "fe-build-info-1";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BuildInfo<'a> {
+    #[serde(rename = "_format")]
+    format: &'static str,
+    /// The hash of the input, which is also the name of the file.
+    id: String,
+    fe_version: &'static str,
+    input: &'a Input<'a>,
+    output: Outputs<'a>,
+}
+
+#[derive(Serialize)]
+struct Input<'a> {
+    language: &'static str,
+    /// The sources by path.
+    sources: BTreeMap<&'a str, Source<'a>>,
+    settings: Settings<'a>,
+}
+
+#[derive(Serialize)]
+struct Source<'a> {
+    content: &'a str,
+}
+
+#[derive(Serialize)]
+struct Settings<'a> {
+    optimize: bool,
+    emit: &'a [String],
+}
+
+#[derive(Serialize)]
+struct Outputs<'a> {
+    /// The contracts by source path and contract name.
+    contracts: BTreeMap<&'a str, BTreeMap<&'a str, Contract<'a>>>,
+}
+
+#[derive(Serialize)]
+struct Contract<'a> {
+    abi: serde_json::Value,
+    yul: &'a str,
+    #[cfg(feature = "solc-backend")]
+    bytecode: &'a str,
+    #[cfg(feature = "solc-backend")]
+    #[serde(rename = "runtimeBytecode")]
+    runtime_bytecode: &'a str,
+}
+
+/// Creates the build info of a compiled module.
+///
+/// The file is named after the SHA-256 hash of the input, so compiling the
+/// same sources with the same settings always produces the same file.
+/// `src_path` is the path of the source file as it should appear in the
+/// build info.
+pub fn build_info(
+    src_path: &str,
+    src: &str,
+    optimize: bool,
+    emit: &[String],
+    module: &CompiledModule,
+) -> Output {
+    let mut sources = BTreeMap::new();
+    sources.insert(src_path, Source { content: src });
+    let input = Input {
+        language: "Fe",
+        sources,
+        settings: Settings { optimize, emit },
+    };
+    let id = format!(
+        "{:x}",
+        Sha256::digest(
+            serde_json::to_string(&input)
+                .expect("unable to serialize build input")
+                .as_bytes()
+        )
+    );
+
+    let contracts = module
+        .contracts
+        .iter()
+        .map(|(name, contract)| {
+            (
+                name.as_str(),
+                Contract {
+                    abi: serde_json::from_str(&contract.json_abi)
+                        .expect("the compiler produced an invalid ABI"),
+                    yul: &contract.yul,
+                    #[cfg(feature = "solc-backend")]
+                    bytecode: &contract.bytecode,
+                    #[cfg(feature = "solc-backend")]
+                    runtime_bytecode: &contract.runtime_bytecode,
+                },
+            )
+        })
+        .collect();
+    let mut output = Outputs {
+        contracts: BTreeMap::new(),
+    };
+    output.contracts.insert(src_path, contracts);
+
+    let info = BuildInfo {
+        format: FORMAT,
+        id: id.clone(),
+        fe_version: env!("CARGO_PKG_VERSION"),
+        input: &input,
+        output,
+    };
+
+    Output {
+        path: PathBuf::from(BUILD_INFO_DIR_NAME).join(format!("{}.json", id)),
+        contract: None,
+        target: "build-info".to_string(),
+        content: serde_json::to_string_pretty(&info)
+            .expect("unable to serialize build info")
+            .into_bytes(),
+    }
+}
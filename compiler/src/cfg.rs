@@ -0,0 +1,253 @@
+//! Conditional compilation of definitions marked with the `cfg` decorator,
+//! e.g. `@cfg(test)` or `@cfg(feature = "mainnet")`.
+//!
+//! Contracts, structs, functions and constants whose conditions don't hold
+//! are removed from the module before it is analyzed. The `cfg` decorators of
+//! the remaining definitions are removed with them, so that the analyzer
+//! never sees them.
+
+use crate::errors::{
+    CompileError,
+    Diagnostic,
+};
+use fe_parser::ast as fe;
+use fe_parser::span::{
+    Span,
+    Spanned,
+};
+use std::collections::BTreeSet;
+
+/// The settings that the conditions of `cfg` decorators are checked against.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Config {
+    /// Whether the module is compiled to run its tests.
+    pub test: bool,
+    /// The enabled features.
+    pub features: BTreeSet<String>,
+}
+
+impl Config {
+    /// Whether the condition holds. A condition is `test`, `feature = "name"`,
+    /// or `not`, `any` or `all` of other conditions.
+    fn holds(&self, condition: &fe::CallArg, span: Span) -> Result<bool, Diagnostic> {
+        match condition {
+            fe::CallArg::Kwarg(fe::Kwarg { name, value }) if name.node == "feature" => {
+                match &value.node {
+                    fe::Expr::Str(lines) => Ok(self.features.contains(&lines.join(""))),
+                    _ => Err(invalid(
+                        value.span,
+                        "the name of a feature has to be a string",
+                    )),
+                }
+            }
+            fe::CallArg::Arg(fe::Expr::Name("test")) => Ok(self.test),
+            fe::CallArg::Arg(fe::Expr::Call { func, args }) => {
+                let values = args
+                    .node
+                    .iter()
+                    .map(|arg| self.holds(&arg.node, arg.span))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                match (&func.node, values.as_slice()) {
+                    (fe::Expr::Name("all"), values) => Ok(values.iter().all(|value| *value)),
+                    (fe::Expr::Name("any"), values) => Ok(values.iter().any(|value| *value)),
+                    (fe::Expr::Name("not"), [value]) => Ok(!value),
+                    (fe::Expr::Name("not"), _) => {
+                        Err(invalid(args.span, "`not` takes a single condition"))
+                    }
+                    _ => Err(invalid(func.span, "unknown condition")),
+                }
+            }
+            _ => Err(invalid(span, "unknown condition")),
+        }
+    }
+
+    /// Removes the `cfg` decorators and returns whether all of their
+    /// conditions hold.
+    fn take(&self, decorators: &mut Vec<Spanned<fe::Expr>>, errors: &mut Vec<Diagnostic>) -> bool {
+        let mut enabled = true;
+        decorators.retain(|decorator| match &decorator.node {
+            fe::Expr::Call { func, args } if func.node == fe::Expr::Name("cfg") => {
+                match args.node.as_slice() {
+                    [condition] => match self.holds(&condition.node, condition.span) {
+                        Ok(holds) => enabled &= holds,
+                        Err(error) => errors.push(error),
+                    },
+                    _ => errors.push(invalid(args.span, "`cfg` takes a single condition")),
+                }
+                false
+            }
+            fe::Expr::Name("cfg") => {
+                errors.push(invalid(decorator.span, "`cfg` takes a single condition"));
+                false
+            }
+            _ => true,
+        });
+
+        enabled
+    }
+}
+
+/// Removes the definitions whose `cfg` conditions don't hold.
+pub fn strip(module: &mut fe::Module, config: &Config) -> Result<(), CompileError> {
+    let mut errors = vec![];
+
+    let keep = module
+        .body
+        .iter_mut()
+        .map(|stmt| match &mut stmt.node {
+            fe::ModuleStmt::ContractDef {
+                decorators, body, ..
+            } => config.take(decorators, &mut errors) && strip_body(body, config, &mut errors),
+            fe::ModuleStmt::MixinDef { body, .. } | fe::ModuleStmt::LibraryDef { body, .. } => {
+                strip_body(body, config, &mut errors)
+            }
+            fe::ModuleStmt::StructDef { decorators, .. }
+            | fe::ModuleStmt::ConstDef { decorators, .. } => config.take(decorators, &mut errors),
+            fe::ModuleStmt::FuncDef { def } => match &mut def.node {
+                fe::ContractStmt::FuncDef { decorators, .. } => {
+                    config.take(decorators, &mut errors)
+                }
+                _ => true,
+            },
+            _ => true,
+        })
+        .collect::<Vec<_>>();
+    let mut keep = keep.into_iter();
+    module.body.retain(|_| keep.next().unwrap_or(true));
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(CompileError::from_diagnostics(errors))
+    }
+}
+
+/// Removes the functions and constants of a contract, mixin or library body
+/// whose conditions don't hold. Always returns true, so that the body itself
+/// is kept.
+fn strip_body(
+    body: &mut Vec<Spanned<fe::ContractStmt>>,
+    config: &Config,
+    errors: &mut Vec<Diagnostic>,
+) -> bool {
+    let keep = body
+        .iter_mut()
+        .map(|stmt| match &mut stmt.node {
+            fe::ContractStmt::FuncDef { decorators, .. }
+            | fe::ContractStmt::ConstDef { decorators, .. } => config.take(decorators, errors),
+            _ => true,
+        })
+        .collect::<Vec<_>>();
+    let mut keep = keep.into_iter();
+    body.retain(|_| keep.next().unwrap_or(true));
+
+    true
+}
+
+fn invalid(span: Span, message: &str) -> Diagnostic {
+    Diagnostic::error("InvalidDecorator", message).with_label(span, "")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cfg::{
+        strip,
+        Config,
+    };
+    use fe_parser::ast as fe;
+    use fe_parser::tokenizer::Token;
+
+    const SRC: &str = "\
+@cfg(feature = \"mainnet\")
+const CHAIN_ID: u256 = 1
+
+@cfg(not(feature = \"mainnet\"))
+const CHAIN_ID: u256 = 5
+
+contract Foo:
+    pub def chain() -> u256:
+        return CHAIN_ID
+
+    @cfg(test)
+    def mock():
+        pass
+
+@cfg(any(test, feature = \"mocks\"))
+contract Mock:
+    calls: u256
+";
+
+    fn parse<'a>(tokens: &'a [Token<'a>]) -> fe::Module<'a> {
+        fe_parser::parsers::file_input(tokens).unwrap().1.node
+    }
+
+    /// The names of the definitions in the module and in the bodies of its
+    /// contracts, which have no decorators left.
+    fn names(module: &fe::Module) -> Vec<String> {
+        let mut names = vec![];
+        for stmt in module.body.iter() {
+            match &stmt.node {
+                fe::ModuleStmt::ConstDef {
+                    decorators, value, ..
+                } => {
+                    assert!(decorators.is_empty());
+                    if let fe::Expr::Num(value) = value.node {
+                        names.push(format!("CHAIN_ID = {}", value))
+                    }
+                }
+                fe::ModuleStmt::ContractDef {
+                    decorators,
+                    name,
+                    body,
+                    ..
+                } => {
+                    assert!(decorators.is_empty());
+                    names.push(name.node.to_string());
+                    for stmt in body.iter() {
+                        if let fe::ContractStmt::FuncDef { name, .. } = &stmt.node {
+                            names.push(name.node.to_string())
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        names
+    }
+
+    #[test]
+    fn conditions() {
+        let tokens = fe_parser::get_parse_tokens(SRC).unwrap();
+        let mut module = parse(&tokens);
+        strip(&mut module, &Config::default()).unwrap();
+        assert_eq!(names(&module), vec!["CHAIN_ID = 5", "Foo", "chain"]);
+
+        let mut module = parse(&tokens);
+        let config = Config {
+            test: true,
+            features: vec!["mainnet".to_string()].into_iter().collect(),
+        };
+        strip(&mut module, &config).unwrap();
+        assert_eq!(
+            names(&module),
+            vec!["CHAIN_ID = 1", "Foo", "chain", "mock", "Mock"]
+        );
+    }
+
+    #[test]
+    fn invalid_conditions() {
+        for condition in &[
+            "@cfg",
+            "@cfg(debug)",
+            "@cfg(feature = 1)",
+            "@cfg(not(test, test))",
+        ] {
+            let src = format!("{}\ncontract Foo:\n    x: u256\n", condition);
+            let tokens = fe_parser::get_parse_tokens(&src).unwrap();
+            let mut module = parse(&tokens);
+            assert!(strip(&mut module, &Config::default()).is_err());
+        }
+    }
+}
@@ -0,0 +1,346 @@
+//! Disassembly of EVM bytecode into annotated opcodes.
+
+use crate::errors::CompileError;
+use std::collections::BTreeMap;
+
+/// A single decoded instruction.
+#[derive(Debug, PartialEq)]
+pub struct Instruction {
+    /// The position of the opcode in the bytecode.
+    pub offset: usize,
+    pub opcode: u8,
+    /// The bytes pushed by a `PUSH` instruction.
+    pub immediate: Vec<u8>,
+}
+
+impl Instruction {
+    /// The mnemonic of the opcode, e.g. `PUSH1`. Unassigned opcodes are
+    /// written as `INVALID`.
+    pub fn name(&self) -> String {
+        match self.opcode {
+            0x60..=0x7f => format!("PUSH{}", self.opcode - 0x5f),
+            0x80..=0x8f => format!("DUP{}", self.opcode - 0x7f),
+            0x90..=0x9f => format!("SWAP{}", self.opcode - 0x8f),
+            0xa0..=0xa4 => format!("LOG{}", self.opcode - 0xa0),
+            opcode => opcode_name(opcode).unwrap_or("INVALID").to_string(),
+        }
+    }
+
+    /// The value pushed by a `PUSH` instruction.
+    fn immediate_value(&self) -> Option<usize> {
+        if self.immediate.is_empty() || self.immediate.len() > 8 {
+            return None;
+        }
+        Some(
+            self.immediate
+                .iter()
+                .fold(0, |value, byte| (value << 8) | *byte as usize),
+        )
+    }
+}
+
+/// The location of an instruction in a source file, as given by an entry of a
+/// solc style source map.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SourceLocation {
+    pub start: usize,
+    pub length: usize,
+    /// The index of the source file, which is negative for instructions that
+    /// don't belong to any source.
+    pub file: i64,
+}
+
+/// Decodes bytecode that is either hex encoded, with or without a `0x`
+/// prefix, or raw.
+pub fn decode(content: &[u8]) -> Result<Vec<u8>, CompileError> {
+    let text = match std::str::from_utf8(content) {
+        Ok(text) => text.trim(),
+        Err(_) => return Ok(content.to_vec()),
+    };
+    let digits = text.trim_start_matches("0x");
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        if digits.contains("__$") {
+            return Err(CompileError::static_str(
+                "the bytecode contains placeholders for library addresses, which have to be linked first",
+            ));
+        }
+        return Ok(content.to_vec());
+    }
+
+    hex::decode(digits).map_err(|_| CompileError::static_str("invalid hex encoded bytecode"))
+}
+
+/// Splits bytecode into instructions. A `PUSH` at the end of the bytecode
+/// that is missing some of its bytes takes the bytes that are there.
+pub fn disassemble(bytecode: &[u8]) -> Vec<Instruction> {
+    let mut instructions = vec![];
+
+    let mut offset = 0;
+    while offset < bytecode.len() {
+        let opcode = bytecode[offset];
+        let size = match opcode {
+            0x60..=0x7f => (opcode - 0x5f) as usize,
+            _ => 0,
+        };
+        let end = (offset + 1 + size).min(bytecode.len());
+
+        instructions.push(Instruction {
+            offset,
+            opcode,
+            immediate: bytecode[offset + 1..end].to_vec(),
+        });
+        offset = end;
+    }
+
+    instructions
+}
+
+/// Parses a source map in the compressed format of solc, in which each entry
+/// is `start:length:file:jump:modifier_depth` and fields that are left out
+/// are copied from the previous entry.
+pub fn parse_source_map(map: &str) -> Result<Vec<SourceLocation>, CompileError> {
+    // unknown locations are written as -1
+    let number = |field: &str| {
+        field
+            .parse::<i64>()
+            .map_err(|_| CompileError::static_str("invalid source map"))
+    };
+
+    let mut locations = vec![];
+    let mut current = SourceLocation {
+        start: 0,
+        length: 0,
+        file: -1,
+    };
+    for entry in map.trim().split(';') {
+        let fields = entry.split(':').collect::<Vec<_>>();
+        let field = |index: usize| fields.get(index).filter(|field| !field.is_empty());
+
+        if let Some(start) = field(0) {
+            current.start = number(start)?.max(0) as usize;
+        }
+        if let Some(length) = field(1) {
+            current.length = number(length)?.max(0) as usize;
+        }
+        if let Some(file) = field(2) {
+            current.file = number(file)?;
+        }
+        locations.push(current);
+    }
+
+    Ok(locations)
+}
+
+/// Formats instructions as one line each. The targets of jumps to constant
+/// destinations are annotated on both ends.
+///
+/// With a source map and the source it refers to, the source line of each
+/// instruction is written above the first instruction of a run of
+/// instructions from that line.
+pub fn format(instructions: &[Instruction], source: Option<(&[SourceLocation], &str)>) -> String {
+    // jumps preceded by a push of their destination
+    let mut jumps_to = BTreeMap::<usize, Vec<usize>>::new();
+    for pair in instructions.windows(2) {
+        if let (Some(dest), 0x56..=0x57) = (pair[0].immediate_value(), pair[1].opcode) {
+            jumps_to.entry(dest).or_default().push(pair[1].offset);
+        }
+    }
+
+    let mut lines = vec![];
+    let mut last_line = None;
+    for (index, instruction) in instructions.iter().enumerate() {
+        if let Some((locations, src)) = source {
+            let location = locations
+                .get(index)
+                .filter(|location| location.file >= 0 && location.start <= src.len());
+            if let Some(location) = location {
+                let line = src[..location.start].matches('\n').count();
+                if last_line != Some(line) {
+                    let text = src.lines().nth(line).unwrap_or_default().trim();
+                    lines.push(format!("          # {}: {}", line + 1, text));
+                    last_line = Some(line);
+                }
+            }
+        }
+
+        let mut text = format!("{:#06x}    {}", instruction.offset, instruction.name());
+        if !instruction.immediate.is_empty() {
+            text.push_str(&format!(" 0x{}", hex::encode(&instruction.immediate)));
+        }
+
+        let comment = match instruction.opcode {
+            0x5b => jumps_to.get(&instruction.offset).map(|sources| {
+                let sources = sources
+                    .iter()
+                    .map(|offset| format!("{:#06x}", offset))
+                    .collect::<Vec<_>>();
+                format!("jump destination from {}", sources.join(", "))
+            }),
+            0x56 | 0x57 => index
+                .checked_sub(1)
+                .and_then(|previous| instructions[previous].immediate_value())
+                .map(|dest| {
+                    let valid = instructions
+                        .iter()
+                        .any(|other| other.offset == dest && other.opcode == 0x5b);
+                    if valid {
+                        format!("to {:#06x}", dest)
+                    } else {
+                        format!("to {:#06x}, which is not a jump destination", dest)
+                    }
+                }),
+            _ => None,
+        };
+        if let Some(comment) = comment {
+            text = format!("{:<40} ; {}", text, comment);
+        }
+        lines.push(text);
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// The names of the opcodes that aren't part of a numbered family like
+/// `PUSH1` to `PUSH32`.
+fn opcode_name(opcode: u8) -> Option<&'static str> {
+    let name = match opcode {
+        0x00 => "STOP",
+        0x01 => "ADD",
+        0x02 => "MUL",
+        0x03 => "SUB",
+        0x04 => "DIV",
+        0x05 => "SDIV",
+        0x06 => "MOD",
+        0x07 => "SMOD",
+        0x08 => "ADDMOD",
+        0x09 => "MULMOD",
+        0x0a => "EXP",
+        0x0b => "SIGNEXTEND",
+        0x10 => "LT",
+        0x11 => "GT",
+        0x12 => "SLT",
+        0x13 => "SGT",
+        0x14 => "EQ",
+        0x15 => "ISZERO",
+        0x16 => "AND",
+        0x17 => "OR",
+        0x18 => "XOR",
+        0x19 => "NOT",
+        0x1a => "BYTE",
+        0x1b => "SHL",
+        0x1c => "SHR",
+        0x1d => "SAR",
+        0x20 => "SHA3",
+        0x30 => "ADDRESS",
+        0x31 => "BALANCE",
+        0x32 => "ORIGIN",
+        0x33 => "CALLER",
+        0x34 => "CALLVALUE",
+        0x35 => "CALLDATALOAD",
+        0x36 => "CALLDATASIZE",
+        0x37 => "CALLDATACOPY",
+        0x38 => "CODESIZE",
+        0x39 => "CODECOPY",
+        0x3a => "GASPRICE",
+        0x3b => "EXTCODESIZE",
+        0x3c => "EXTCODECOPY",
+        0x3d => "RETURNDATASIZE",
+        0x3e => "RETURNDATACOPY",
+        0x3f => "EXTCODEHASH",
+        0x40 => "BLOCKHASH",
+        0x41 => "COINBASE",
+        0x42 => "TIMESTAMP",
+        0x43 => "NUMBER",
+        0x44 => "DIFFICULTY",
+        0x45 => "GASLIMIT",
+        0x46 => "CHAINID",
+        0x47 => "SELFBALANCE",
+        0x48 => "BASEFEE",
+        0x50 => "POP",
+        0x51 => "MLOAD",
+        0x52 => "MSTORE",
+        0x53 => "MSTORE8",
+        0x54 => "SLOAD",
+        0x55 => "SSTORE",
+        0x56 => "JUMP",
+        0x57 => "JUMPI",
+        0x58 => "PC",
+        0x59 => "MSIZE",
+        0x5a => "GAS",
+        0x5b => "JUMPDEST",
+        0xf0 => "CREATE",
+        0xf1 => "CALL",
+        0xf2 => "CALLCODE",
+        0xf3 => "RETURN",
+        0xf4 => "DELEGATECALL",
+        0xf5 => "CREATE2",
+        0xfa => "STATICCALL",
+        0xfd => "REVERT",
+        0xfe => "INVALID",
+        0xff => "SELFDESTRUCT",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::disasm::{
+        decode,
+        disassemble,
+        format,
+        parse_source_map,
+        SourceLocation,
+    };
+
+    #[test]
+    fn disassemble_jump() {
+        // PUSH1 0x04 JUMP INVALID JUMPDEST STOP
+        let bytecode = decode(b"0x600456fe5b00\n").unwrap();
+        let instructions = disassemble(&bytecode);
+        let names = instructions
+            .iter()
+            .map(|instruction| instruction.name())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["PUSH1", "JUMP", "INVALID", "JUMPDEST", "STOP"]);
+
+        assert_eq!(
+            format(&instructions, None),
+            "\
+0x0000    PUSH1 0x04
+0x0002    JUMP                           ; to 0x0004
+0x0003    INVALID
+0x0004    JUMPDEST                       ; jump destination from 0x0002
+0x0005    STOP
+"
+        );
+    }
+
+    #[test]
+    fn truncated_push() {
+        let instructions = disassemble(&[0x61, 0x01]);
+        assert_eq!(instructions.len(), 1);
+        assert_eq!(instructions[0].immediate, vec![0x01]);
+    }
+
+    #[test]
+    fn source_map() {
+        let locations = parse_source_map("0:10:0;;12:3;:::-;-1:-1:-1").unwrap();
+        let location = |start, length, file| SourceLocation {
+            start,
+            length,
+            file,
+        };
+        assert_eq!(
+            locations,
+            vec![
+                location(0, 10, 0),
+                location(0, 10, 0),
+                location(12, 3, 0),
+                location(12, 3, 0),
+                location(0, 0, -1),
+            ]
+        );
+    }
+}
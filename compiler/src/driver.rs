@@ -0,0 +1,872 @@
+//! A configurable compiler that produces the files of a build, for tools that
+//! embed the compiler.
+//!
+//! ```ignore
+//! let build = Compiler::new()
+//!     .optimize(true)
+//!     .emit(&[Target::Abi, Target::Bytecode])
+//!     .compile(src)?;
+//! build.write(Path::new("output"), true)?;
+//! ```
+
+use crate::artifacts::{
+    Artifacts,
+    Output,
+    ARTIFACTS_FILE_NAME,
+};
+use crate::build_info;
+use crate::cfg;
+use crate::errors::CompileError;
+use crate::files::FileLoader;
+use crate::metadata;
+use crate::passes::Pass;
+use crate::types::{
+    CompiledModule,
+    Progress,
+};
+use fe_analyzer::lints::{
+    LintPack,
+    LintParams,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{
+    ErrorKind,
+    Write,
+};
+use std::path::{
+    Path,
+    PathBuf,
+};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// The path that names the source in outputs unless it is set.
+const DEFAULT_SRC_PATH: &str = "main.fe";
+
+/// A kind of output of the compiler.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+    Abi,
+    Ast,
+    Bytecode,
+    /// The EIP-712 schemas of the structs marked with `@eip712`.
+    Eip712,
+    /// The metadata of each contract, which refers to the source by its IPFS
+    /// hash.
+    Metadata,
+    /// The storage layouts and initializers of the contracts marked with
+    /// `@upgradeable`.
+    Proxy,
+    Tokens,
+    Yul,
+}
+
+impl Target {
+    /// Every target, including those that need the `solc-backend` feature.
+    pub const ALL: [Target; 8] = [
+        Target::Abi,
+        Target::Ast,
+        Target::Bytecode,
+        Target::Eip712,
+        Target::Metadata,
+        Target::Proxy,
+        Target::Tokens,
+        Target::Yul,
+    ];
+
+    /// The targets this build of the compiler can produce, which leaves out
+    /// bytecode without the `solc-backend` feature.
+    pub fn available() -> Vec<Target> {
+        Target::ALL
+            .iter()
+            .copied()
+            .filter(|target| cfg!(feature = "solc-backend") || *target != Target::Bytecode)
+            .collect()
+    }
+
+    /// The name of the target, e.g. as passed to `--emit`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Target::Abi => "abi",
+            Target::Ast => "ast",
+            Target::Bytecode => "bytecode",
+            Target::Eip712 => "eip712",
+            Target::Metadata => "metadata",
+            Target::Proxy => "proxy",
+            Target::Tokens => "tokens",
+            Target::Yul => "yul",
+        }
+    }
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "abi" => Ok(Target::Abi),
+            "ast" => Ok(Target::Ast),
+            "bytecode" => Ok(Target::Bytecode),
+            "eip712" => Ok(Target::Eip712),
+            "metadata" => Ok(Target::Metadata),
+            "proxy" => Ok(Target::Proxy),
+            "tokens" => Ok(Target::Tokens),
+            "yul" => Ok(Target::Yul),
+            _ => Err(format!("unknown target `{}`", name)),
+        }
+    }
+}
+
+/// The encoding of bytecode outputs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BytecodeFormat {
+    /// Hex without a prefix, as produced by solc.
+    Hex,
+    /// Hex prefixed with `0x`.
+    Hex0x,
+    /// The bytes themselves.
+    Raw,
+}
+
+impl BytecodeFormat {
+    /// Encodes the hex encoded bytecode produced by solc.
+    pub fn encode(self, bytecode: &str) -> Result<Vec<u8>, String> {
+        match self {
+            BytecodeFormat::Hex => Ok(bytecode.as_bytes().to_vec()),
+            BytecodeFormat::Hex0x => Ok(format!("0x{}", bytecode).into_bytes()),
+            BytecodeFormat::Raw => hex::decode(bytecode).map_err(|_| {
+                "the bytecode contains placeholders for library addresses, which can only be written as hex".to_string()
+            }),
+        }
+    }
+}
+
+impl Default for BytecodeFormat {
+    fn default() -> Self {
+        BytecodeFormat::Hex
+    }
+}
+
+impl FromStr for BytecodeFormat {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "hex" => Ok(BytecodeFormat::Hex),
+            "hex0x" => Ok(BytecodeFormat::Hex0x),
+            "raw" => Ok(BytecodeFormat::Raw),
+            _ => Err(format!("unknown bytecode format `{}`", name)),
+        }
+    }
+}
+
+/// The settings of a compilation.
+#[derive(Clone, Debug)]
+pub struct Compiler {
+    optimize: bool,
+    targets: Vec<Target>,
+    require_bytecode: bool,
+    flat_output: bool,
+    #[cfg_attr(not(feature = "solc-backend"), allow(dead_code))]
+    bytecode_format: BytecodeFormat,
+    build_info: Option<String>,
+    src_path: String,
+    progress: Option<fn(Progress)>,
+    cfg: cfg::Config,
+    passes: Vec<Arc<dyn Pass>>,
+    lint_packs: Vec<Arc<dyn LintPack>>,
+    lint_params: LintParams,
+}
+
+impl Default for Compiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Compiler {
+    /// Creates a compiler that emits the ABIs and bytecode of the contracts
+    /// without optimizing them.
+    pub fn new() -> Self {
+        Self {
+            optimize: false,
+            targets: vec![Target::Abi, Target::Bytecode],
+            require_bytecode: false,
+            flat_output: false,
+            bytecode_format: BytecodeFormat::default(),
+            build_info: None,
+            src_path: DEFAULT_SRC_PATH.to_owned(),
+            progress: None,
+            cfg: cfg::Config::default(),
+            passes: vec![],
+            lint_packs: vec![],
+            lint_params: LintParams::new(),
+        }
+    }
+
+    /// Enables the Yul optimizer.
+    pub fn optimize(mut self, optimize: bool) -> Self {
+        self.optimize = optimize;
+        self
+    }
+
+    /// Sets the outputs to produce.
+    pub fn emit(mut self, targets: &[Target]) -> Self {
+        self.targets = targets.to_vec();
+        self
+    }
+
+    /// Compiles to bytecode even if it isn't emitted, e.g. to check the sizes
+    /// of the contracts.
+    pub fn require_bytecode(mut self, require_bytecode: bool) -> Self {
+        self.require_bytecode = require_bytecode;
+        self
+    }
+
+    /// Places all outputs in the output directory instead of a directory per
+    /// contract.
+    pub fn flat_output(mut self, flat_output: bool) -> Self {
+        self.flat_output = flat_output;
+        self
+    }
+
+    /// Sets the encoding of bytecode outputs.
+    pub fn bytecode_format(mut self, bytecode_format: BytecodeFormat) -> Self {
+        self.bytecode_format = bytecode_format;
+        self
+    }
+
+    /// Adds a build info file to the outputs, in which the source is named
+    /// `src_path`.
+    pub fn build_info(mut self, src_path: &str) -> Self {
+        self.build_info = Some(src_path.to_owned());
+        self
+    }
+
+    /// Sets the path that names the source in the metadata of the contracts,
+    /// which is `main.fe` by default.
+    pub fn src_path(mut self, src_path: &str) -> Self {
+        self.src_path = src_path.to_owned();
+        self
+    }
+
+    /// Reports each phase of a compilation to `report` as it starts.
+    pub fn progress(mut self, report: fn(Progress)) -> Self {
+        self.progress = Some(report);
+        self
+    }
+
+    /// Enables a feature, which compiles the definitions marked with
+    /// `@cfg(feature = "name")`.
+    pub fn feature(mut self, name: &str) -> Self {
+        self.cfg.features.insert(name.to_owned());
+        self
+    }
+
+    /// Adds a pass that runs after the built-in ones.
+    pub fn pass(mut self, pass: Arc<dyn Pass>) -> Self {
+        self.passes.push(pass);
+        self
+    }
+
+    /// Adds a pack of lints that run alongside the built-in ones.
+    pub fn lint_pack(mut self, lint_pack: Arc<dyn LintPack>) -> Self {
+        self.lint_packs.push(lint_pack);
+        self
+    }
+
+    /// Sets the parameters of the built-in lints.
+    pub fn lint_params(mut self, lint_params: LintParams) -> Self {
+        self.lint_params = lint_params;
+        self
+    }
+
+    /// Whether the source is compiled to bytecode.
+    pub fn with_bytecode(&self) -> bool {
+        self.targets.contains(&Target::Bytecode) || self.require_bytecode
+    }
+
+    /// Compiles the source and creates the output files.
+    pub fn compile(&self, src: &str) -> Result<Build, CompileError> {
+        let report = |progress| {
+            if let Some(report) = self.progress {
+                report(progress)
+            }
+        };
+        let module = crate::compile_with_extensions(
+            src,
+            self.with_bytecode(),
+            self.optimize,
+            &self.cfg,
+            &self.passes,
+            &self.lint_packs,
+            &self.lint_params,
+            &report,
+        )?;
+        let mut outputs = self.output_files(&module, src)?;
+
+        if let Some(src_path) = &self.build_info {
+            let emit = self
+                .targets
+                .iter()
+                .map(|target| target.name().to_owned())
+                .collect::<Vec<_>>();
+            outputs.push(build_info::build_info(
+                src_path,
+                src,
+                self.optimize,
+                &emit,
+                &module,
+            ));
+        }
+
+        Ok(Build {
+            module,
+            outputs,
+            src: src.to_owned(),
+        })
+    }
+
+    /// Loads the source file at `path` with the loader and compiles it.
+    pub fn compile_file(&self, loader: &dyn FileLoader, path: &str) -> Result<Build, CompileError> {
+        let src = loader
+            .load(path)
+            .map_err(|error| CompileError::str(&format!("unable to load `{}`: {}", path, error)))?;
+        self.compile(&src)
+    }
+
+    /// Returns the files to write to the output directory, ordered by
+    /// contract name.
+    ///
+    /// With `flat_output` all files are placed directly in the output
+    /// directory, which fails if the names of two files only differ in case,
+    /// so the outputs are the same on case-insensitive file systems.
+    fn output_files(
+        &self,
+        module: &CompiledModule,
+        src: &str,
+    ) -> Result<Vec<Output>, CompileError> {
+        let mut outputs = vec![];
+        let mut push = |path: PathBuf, contract: Option<&str>, target: Target, content: Vec<u8>| {
+            outputs.push(Output {
+                path,
+                contract: contract.map(str::to_owned),
+                target: target.name().to_owned(),
+                content,
+            })
+        };
+
+        if self.targets.contains(&Target::Ast) {
+            push(
+                PathBuf::from("module.ast"),
+                None,
+                Target::Ast,
+                module.fe_ast.clone().into_bytes(),
+            );
+        }
+
+        if self.targets.contains(&Target::Tokens) {
+            push(
+                PathBuf::from("module.tokens"),
+                None,
+                Target::Tokens,
+                module.fe_tokens.clone().into_bytes(),
+            );
+        }
+
+        if self.targets.contains(&Target::Eip712) {
+            push(
+                PathBuf::from("module_eip712.json"),
+                None,
+                Target::Eip712,
+                module.eip712.clone().into_bytes(),
+            );
+        }
+
+        if self.targets.contains(&Target::Proxy) {
+            push(
+                PathBuf::from("module_proxy.json"),
+                None,
+                Target::Proxy,
+                module.proxy.clone().into_bytes(),
+            );
+        }
+
+        let mut names = module.contracts.keys().collect::<Vec<_>>();
+        names.sort();
+
+        for name in names {
+            let contract = &module.contracts[name];
+            let contract_output_dir = if self.flat_output {
+                PathBuf::new()
+            } else {
+                PathBuf::from(name)
+            };
+
+            if self.targets.contains(&Target::Abi) {
+                let file_name = format!("{}_abi.json", name);
+                push(
+                    contract_output_dir.join(file_name),
+                    Some(name),
+                    Target::Abi,
+                    contract.json_abi.clone().into_bytes(),
+                );
+            }
+
+            if self.targets.contains(&Target::Yul) {
+                let file_name = format!("{}_ir.yul", name);
+                push(
+                    contract_output_dir.join(file_name),
+                    Some(name),
+                    Target::Yul,
+                    pretty_curly_print(&contract.yul, 4).into_bytes(),
+                );
+            }
+
+            if self.targets.contains(&Target::Metadata) {
+                let file_name = format!("{}_metadata.json", name);
+                push(
+                    contract_output_dir.join(file_name),
+                    Some(name),
+                    Target::Metadata,
+                    metadata::metadata(&self.src_path, src, self.optimize, name, contract)
+                        .into_bytes(),
+                );
+            }
+
+            #[cfg(feature = "solc-backend")]
+            if self.targets.contains(&Target::Bytecode) {
+                let file_name = format!("{}.bin", name);
+                let bytecode =
+                    self.bytecode_format
+                        .encode(&contract.bytecode)
+                        .map_err(|error| {
+                            CompileError::str(&format!(
+                                "Unable to write the bytecode of {}: {}",
+                                name, error
+                            ))
+                        })?;
+                push(
+                    contract_output_dir.join(file_name),
+                    Some(name),
+                    Target::Bytecode,
+                    bytecode,
+                );
+            }
+        }
+
+        if self.flat_output {
+            let mut seen = HashMap::new();
+            for Output { path, .. } in outputs.iter() {
+                let key = path.to_string_lossy().to_lowercase();
+                if let Some(other) = seen.insert(key, path) {
+                    return Err(CompileError::str(&format!(
+                        "The outputs `{}` and `{}` have the same name in the flat output directory. Rename one of the contracts or omit --flat-output.",
+                        other.display(),
+                        path.display()
+                    )));
+                }
+            }
+        }
+
+        Ok(outputs)
+    }
+}
+
+/// The result of a compilation.
+pub struct Build {
+    /// The compiled module that the outputs were created from.
+    pub module: CompiledModule,
+    /// The files to write to the output directory, ordered by contract name.
+    pub outputs: Vec<Output>,
+    /// The source that was compiled.
+    pub src: String,
+}
+
+impl Build {
+    /// Writes the outputs and their index to the output directory, which has
+    /// to be empty unless `overwrite` is set.
+    ///
+    /// Files of previous builds that this build doesn't produce are removed
+    /// and their paths relative to the output directory are returned.
+    pub fn write(&self, output_dir: &Path, overwrite: bool) -> Result<Vec<PathBuf>, CompileError> {
+        if output_dir.is_file() {
+            return Err(CompileError::str(&format!(
+                "A file exists at path `{}`, the location of the output directory. Refusing to overwrite.",
+                output_dir.display()
+            )));
+        }
+
+        if !overwrite {
+            verify_nonexistent_or_empty(output_dir)?;
+        }
+
+        // The outputs of a previous build are known from its index, which is
+        // removed until the outputs of this build have been written.
+        let previous = Artifacts::load(output_dir);
+        if previous.is_some() {
+            fs::remove_file(output_dir.join(ARTIFACTS_FILE_NAME)).map_err(ioerr)?;
+        }
+
+        fs::create_dir_all(output_dir).map_err(ioerr)?;
+
+        for output in self.outputs.iter() {
+            let path = output_dir.join(&output.path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(ioerr)?;
+            }
+            write_output(&path, &output.content)?;
+        }
+
+        // Files of previous builds would be mistaken for outputs of this one.
+        let mut removed = vec![];
+        if let Some(previous) = previous {
+            for path in previous.stale(&self.outputs) {
+                if remove_stale_output(output_dir, &path)? {
+                    removed.push(path);
+                }
+            }
+        }
+
+        // The index is written last, so it only exists if all outputs do.
+        write_output(
+            &output_dir.join(ARTIFACTS_FILE_NAME),
+            Artifacts::new(&self.outputs).to_json().as_bytes(),
+        )?;
+
+        Ok(removed)
+    }
+}
+
+fn write_output(path: &Path, content: &[u8]) -> Result<(), CompileError> {
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+        .map_err(ioerr)?;
+    file.write_all(content).map_err(ioerr)?;
+    Ok(())
+}
+
+/// Removes a file of a previous build and the directories containing it if
+/// they are empty afterwards. Returns whether the file existed.
+fn remove_stale_output(output_dir: &Path, path: &Path) -> Result<bool, CompileError> {
+    match fs::remove_file(output_dir.join(path)) {
+        Ok(()) => {}
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(false),
+        Err(error) => return Err(ioerr(error)),
+    }
+
+    let mut dir = path.parent();
+    while let Some(parent) = dir.filter(|parent| parent != &Path::new("")) {
+        if fs::remove_dir(output_dir.join(parent)).is_err() {
+            break;
+        }
+        dir = parent.parent();
+    }
+
+    Ok(true)
+}
+
+fn verify_nonexistent_or_empty(dir: &Path) -> Result<(), CompileError> {
+    if !dir.exists() || dir.read_dir().map_err(ioerr)?.next().is_none() {
+        Ok(())
+    } else {
+        Err(CompileError::str(&format!(
+            "Directory '{}' is not empty. Use --overwrite to overwrite.",
+            dir.display()
+        )))
+    }
+}
+
+/// Formats any kind of structured text that uses curly braces blocks
+fn pretty_curly_print(text: &str, indent: usize) -> String {
+    let mut formatted = String::new();
+    let mut level = 0;
+    let mut previous_char: Option<char> = None;
+
+    const CURLY_OPEN: char = '{';
+    const CURLY_CLOSE: char = '}';
+    const WHITESPACE: char = ' ';
+    const NEWLINE: &str = "\n";
+
+    for character in text.chars() {
+        match character {
+            CURLY_OPEN => {
+                level += 1;
+                formatted.push(character);
+                formatted.push_str(NEWLINE);
+                formatted.push_str(&WHITESPACE.to_string().repeat(indent * level));
+            }
+            CURLY_CLOSE => {
+                level -= 1;
+                formatted.push_str(NEWLINE);
+                formatted.push_str(&WHITESPACE.to_string().repeat(indent * level));
+                formatted.push(character);
+                formatted.push_str(NEWLINE);
+                formatted.push_str(&WHITESPACE.to_string().repeat(indent * level));
+            }
+            WHITESPACE => {
+                if !matches!(previous_char, Some(CURLY_CLOSE)) {
+                    formatted.push(character)
+                }
+            }
+            _ => formatted.push(character),
+        }
+        previous_char = Some(character);
+    }
+
+    formatted
+}
+
+fn ioerr(error: std::io::Error) -> CompileError {
+    CompileError::str(&error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::driver::{
+        Compiler,
+        Target,
+    };
+    use crate::errors::Diagnostic;
+    use crate::files::MemoryLoader;
+    use crate::passes::Pass;
+    use crate::types::Progress;
+    use fe_analyzer::lints::{
+        LintInfo,
+        LintPack,
+        Warning,
+    };
+    use fe_analyzer::Context;
+    use fe_parser::ast as fe;
+    use std::cell::RefCell;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    const SRC: &str = "contract Foo:\
+         \n  pub def bar() -> u256:\
+         \n    return 1\
+         \ncontract foo:\
+         \n  pub def bar() -> u256:\
+         \n    return 2\n";
+
+    fn paths(compiler: Compiler) -> Vec<PathBuf> {
+        compiler
+            .compile(SRC)
+            .expect("unable to compile")
+            .outputs
+            .into_iter()
+            .map(|output| output.path)
+            .collect()
+    }
+
+    #[test]
+    fn output_paths() {
+        let compiler = Compiler::new().emit(&[Target::Ast, Target::Abi]);
+
+        assert_eq!(
+            paths(compiler),
+            vec![
+                PathBuf::from("module.ast"),
+                PathBuf::from("Foo").join("Foo_abi.json"),
+                PathBuf::from("foo").join("foo_abi.json"),
+            ]
+        );
+    }
+
+    #[test]
+    fn metadata() {
+        let build = Compiler::new()
+            .emit(&[Target::Metadata])
+            .src_path("src/foo.fe")
+            .compile(SRC)
+            .expect("unable to compile");
+
+        assert_eq!(
+            build.outputs[0].path,
+            PathBuf::from("Foo").join("Foo_metadata.json")
+        );
+        let metadata: serde_json::Value =
+            serde_json::from_slice(&build.outputs[0].content).unwrap();
+        assert_eq!(
+            metadata["settings"]["compilationTarget"]["src/foo.fe"],
+            "Foo"
+        );
+        assert_eq!(
+            metadata["sources"]["src/foo.fe"]["urls"][0],
+            format!(
+                "dweb:/ipfs/{}",
+                crate::metadata::ipfs_hash(SRC.as_bytes()).unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn compile_file() {
+        let loader = MemoryLoader::new().with_file("src/main.fe", SRC);
+        let compiler = Compiler::new().emit(&[Target::Abi]);
+
+        assert!(compiler.compile_file(&loader, "src/main.fe").is_ok());
+        assert!(compiler.compile_file(&loader, "src/other.fe").is_err());
+    }
+
+    #[test]
+    fn target_names() {
+        for target in Target::ALL.iter() {
+            assert_eq!(target.name().parse::<Target>(), Ok(*target));
+        }
+        assert!(Target::available().contains(&Target::Proxy));
+    }
+
+    #[derive(Debug)]
+    struct Instrument {
+        reject: bool,
+    }
+
+    impl Pass for Instrument {
+        fn name(&self) -> &str {
+            "instrument"
+        }
+
+        fn check(&self, module: &fe::Module) -> Result<Vec<Diagnostic>, String> {
+            Ok(if self.reject {
+                vec![Diagnostic::error(
+                    self.name(),
+                    &format!("{} items", module.body.len()),
+                )]
+            } else {
+                vec![]
+            })
+        }
+
+        fn transform_yul(&self, _contract: &str, yul: String) -> Result<String, String> {
+            Ok(format!("/* instrumented */ {}", yul))
+        }
+    }
+
+    #[test]
+    fn passes() {
+        let build = Compiler::new()
+            .emit(&[Target::Yul])
+            .pass(Arc::new(Instrument { reject: false }))
+            .compile(SRC)
+            .expect("unable to compile");
+        assert!(build.module.contracts["Foo"]
+            .yul
+            .starts_with("/* instrumented */ "));
+
+        let error = Compiler::new()
+            .pass(Arc::new(Instrument { reject: true }))
+            .compile(SRC)
+            .err()
+            .expect("the pass didn't reject the module");
+        assert_eq!(error.diagnostics()[0].message, "2 items");
+    }
+
+    #[test]
+    fn features() {
+        let src = "\
+contract Foo:
+    pub def bar() -> u256:
+        return 1
+
+@cfg(feature = \"mocks\")
+contract MockFoo:
+    pub def bar() -> u256:
+        return 2
+";
+        let contracts = |compiler: Compiler| {
+            let build = compiler
+                .emit(&[Target::Abi])
+                .compile(src)
+                .expect("unable to compile");
+            let mut names = build.module.contracts.keys().cloned().collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+
+        assert_eq!(contracts(Compiler::new()), vec!["Foo"]);
+        assert_eq!(
+            contracts(Compiler::new().feature("mocks")),
+            vec!["Foo", "MockFoo"]
+        );
+    }
+
+    #[derive(Debug)]
+    struct ContractNames;
+
+    impl LintPack for ContractNames {
+        fn lints(&self) -> Vec<LintInfo> {
+            vec![LintInfo {
+                name: "names::lowercase_contract",
+                description: "contracts with lowercase names",
+            }]
+        }
+
+        fn check(&self, module: &fe::Module, _context: &Context) -> Vec<Warning> {
+            module
+                .body
+                .iter()
+                .filter_map(|stmt| match &stmt.node {
+                    fe::ModuleStmt::ContractDef { name, .. }
+                        if name.node.starts_with(char::is_lowercase) =>
+                    {
+                        Some(Warning {
+                            lint: "names::lowercase_contract",
+                            message: format!("`{}` is lowercase", name.node),
+                            help: None,
+                            span: name.span,
+                            notes: vec![],
+                        })
+                    }
+                    _ => None,
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn lint_packs() {
+        let build = Compiler::new()
+            .emit(&[Target::Abi])
+            .lint_pack(Arc::new(ContractNames))
+            .compile(SRC)
+            .expect("unable to compile");
+        let messages = build
+            .module
+            .warnings
+            .iter()
+            .map(|warning| warning.message.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(messages, vec!["`foo` is lowercase"]);
+    }
+
+    #[test]
+    fn flat_output_collision() {
+        let compiler = Compiler::new().emit(&[Target::Abi]).flat_output(true);
+
+        assert!(compiler.compile(SRC).is_err());
+    }
+
+    #[test]
+    fn progress() {
+        let phases = RefCell::new(vec![]);
+        crate::compile_with_progress(SRC, false, false, &|progress| {
+            phases.borrow_mut().push(progress)
+        })
+        .expect("unable to compile");
+
+        assert_eq!(
+            phases.into_inner(),
+            vec![
+                Progress::Parsing,
+                Progress::Analyzing,
+                Progress::CompilingYul
+            ]
+        );
+    }
+}
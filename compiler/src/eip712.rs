@@ -0,0 +1,69 @@
+//! The EIP-712 schemas of the structs that are marked with `@eip712`, which
+//! wallets and other tools need to sign instances of them.
+
+use crate::errors::CompileError;
+use fe_analyzer::namespace::eip712;
+use fe_analyzer::Context;
+use fe_parser::ast as fe;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A field of a typed data struct.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct TypedDataField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub typ: String,
+}
+
+/// The schema of a typed data struct, in the form of the `types` and
+/// `primaryType` members of the typed data that is passed to
+/// `eth_signTypedData_v4`.
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedDataSchema {
+    pub primary_type: String,
+    pub types: BTreeMap<String, Vec<TypedDataField>>,
+}
+
+/// Builds the schemas of all typed data structs in a module, keyed by the
+/// name of the struct.
+pub fn schemas(context: &Context, module: &fe::Module) -> BTreeMap<String, TypedDataSchema> {
+    let into_fields = |fields: Vec<(String, String)>| {
+        fields
+            .into_iter()
+            .map(|(name, typ)| TypedDataField { name, typ })
+            .collect::<Vec<_>>()
+    };
+
+    let mut schemas = BTreeMap::new();
+    for stmt in module.body.iter() {
+        if let Some(val) = context.get_struct(stmt).filter(|val| val.is_eip712()) {
+            let fields = eip712::fields(val).expect("typed data struct can't be encoded");
+            let domain_fields = eip712::DOMAIN_FIELDS
+                .iter()
+                .map(|(name, typ)| (name.to_string(), typ.to_string()))
+                .collect();
+
+            let mut types = BTreeMap::new();
+            types.insert("EIP712Domain".to_string(), into_fields(domain_fields));
+            types.insert(val.name.clone(), into_fields(fields));
+
+            schemas.insert(
+                val.name.clone(),
+                TypedDataSchema {
+                    primary_type: val.name.clone(),
+                    types,
+                },
+            );
+        }
+    }
+
+    schemas
+}
+
+/// Builds the schemas of all typed data structs in a module as a JSON object.
+pub fn build(context: &Context, module: &fe::Module) -> Result<String, CompileError> {
+    serde_json::to_string_pretty(&schemas(context, module))
+        .map_err(|_| CompileError::static_str("unable to serialize the EIP-712 schemas"))
+}
@@ -1,13 +1,26 @@
 //! Errors returned by the compilers and ABI builder.
 
+use ansi_term::Color::{
+    Red,
+    Yellow,
+};
+use fe_analyzer::errors::SemanticError;
+use fe_analyzer::lints::Warning;
+use fe_common::color::paint;
+use fe_parser::ast::SyntaxError;
+use fe_parser::errors::ParseError;
+use fe_parser::span::Span;
 use fe_parser::tokenizer::TokenizeError;
 use serde::export::Formatter;
+use serde::Serialize;
 
 /// Errors can either be an object or static reference.
 #[derive(Debug)]
 pub enum ErrorKind {
     StaticStr(&'static str),
     Str(String),
+    /// An error in the source code, which can be rendered with its location.
+    Diagnostic(Diagnostic),
 }
 
 /// List of errors encountered during compilation.
@@ -16,11 +29,203 @@ pub struct CompileError {
     pub errors: Vec<ErrorKind>,
 }
 
+/// How severe a diagnostic is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A span of source code and what it has to do with a diagnostic.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+/// A proposed fix for a diagnostic, which replaces the code of a span.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Suggestion {
+    pub message: String,
+    pub span: Span,
+    pub replacement: String,
+    /// Whether tools may apply the replacement without asking the user.
+    pub machine_applicable: bool,
+}
+
+/// A problem found in the source code.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// The name of the kind of problem, e.g. `TypeError` or the name of a
+    /// lint.
+    pub kind: String,
+    pub message: String,
+    /// The primary label comes first and is followed by the labels of the
+    /// code surrounding it.
+    pub labels: Vec<Label>,
+    /// Additional explanations.
+    pub notes: Vec<String>,
+    /// Fixes that are likely to resolve the problem.
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl Diagnostic {
+    /// Create an error diagnostic without labels.
+    pub fn error(kind: &str, message: &str) -> Self {
+        Self {
+            severity: Severity::Error,
+            kind: kind.to_owned(),
+            message: message.to_owned(),
+            labels: vec![],
+            notes: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    /// Add a label to the diagnostic.
+    pub fn with_label(mut self, span: Span, message: &str) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.to_owned(),
+        });
+        self
+    }
+
+    /// Add a note to the diagnostic.
+    pub fn with_note(mut self, note: &str) -> Self {
+        self.notes.push(note.to_owned());
+        self
+    }
+
+    /// Add a suggested fix to the diagnostic.
+    pub fn with_suggestion(mut self, suggestion: Suggestion) -> Self {
+        self.suggestions.push(suggestion);
+        self
+    }
+
+    /// Create a diagnostic from an error found by the parser.
+    pub fn from_parse_error(error: &ParseError, src: &str) -> Self {
+        let (span, description) = error.innermost(src);
+        Self::error("ParseError", description).with_label(span, "")
+    }
+
+    /// Create a diagnostic from an error that the parser recovered from.
+    pub fn from_syntax_error(error: &SyntaxError) -> Self {
+        Self::error("ParseError", &error.message).with_label(error.span, "")
+    }
+
+    /// Create a diagnostic from an error found by the analyzer.
+    ///
+    /// The innermost span of the error is the primary label and the span
+    /// containing it is labeled as context.
+    pub fn from_semantic_error(error: &SemanticError) -> Self {
+        let mut diagnostic = Self::error(&format!("{:?}", error.kind), error.kind.message());
+        diagnostic.suggestions = error
+            .suggestions
+            .iter()
+            .map(|suggestion| Suggestion {
+                message: suggestion.message.clone(),
+                span: suggestion.span,
+                replacement: suggestion.replacement.clone(),
+                machine_applicable: suggestion.machine_applicable,
+            })
+            .collect();
+
+        match (error.context.get(0), error.context.get(1)) {
+            (Some(inner), Some(outer)) => diagnostic
+                .with_label(*inner, "")
+                .with_label(*outer, "in this code"),
+            (Some(span), None) => diagnostic.with_label(*span, ""),
+            _ => diagnostic,
+        }
+    }
+
+    /// Formats the diagnostic using the source code.
+    ///
+    /// The string will contain the severity, kind, message and line number
+    /// followed by the code of the second label, or the lines of the primary
+    /// label if there is none, with the primary label highlighted. Labels that
+    /// don't contain the primary label, notes and suggestions come last.
+    pub fn format_user(&self, src: &str) -> String {
+        let (label, color) = match self.severity {
+            Severity::Error => ("error", Red),
+            Severity::Warning => ("warning", Yellow),
+        };
+        let mut formatted = format!("{}[{}]: {}", label, self.kind, self.message);
+
+        if let Some(primary) = self.labels.first() {
+            let inner = primary.span;
+            let outer = match self.labels.get(1) {
+                Some(outer) if outer.span.start <= inner.start && inner.end <= outer.span.end => {
+                    outer.span
+                }
+                _ => surrounding_lines(src, inner),
+            };
+
+            formatted.push_str(&format!(
+                " on line {}\n{}{}{}",
+                line_number(src, inner),
+                &src[outer.start..inner.start],
+                paint(color, &src[inner.start..inner.end]),
+                &src[inner.end..outer.end]
+            ));
+
+            // labels elsewhere in the code, e.g. at a related definition
+            for label in self.labels.iter().skip(1) {
+                if label.span.start > inner.start || inner.end > label.span.end {
+                    formatted.push_str(&format!(
+                        "\nnote: {} on line {}\n{}",
+                        label.message,
+                        line_number(src, label.span),
+                        &src[label.span.start..label.span.end]
+                    ));
+                }
+            }
+        }
+        for note in self.notes.iter() {
+            formatted.push_str(&format!("\nnote: {}", note));
+        }
+        for suggestion in self.suggestions.iter() {
+            formatted.push_str(&format!(
+                "\nhelp: {}: `{}`",
+                suggestion.message, suggestion.replacement
+            ));
+        }
+
+        formatted
+    }
+}
+
+impl From<&Warning> for Diagnostic {
+    fn from(warning: &Warning) -> Self {
+        let mut labels = vec![Label {
+            span: warning.span,
+            message: "".to_owned(),
+        }];
+        labels.extend(warning.notes.iter().map(|note| Label {
+            span: note.span,
+            message: note.node.clone(),
+        }));
+
+        Self {
+            severity: Severity::Warning,
+            kind: warning.lint.to_owned(),
+            message: warning.message.clone(),
+            labels,
+            notes: warning.help.iter().cloned().collect(),
+            suggestions: vec![],
+        }
+    }
+}
+
 impl std::fmt::Display for ErrorKind {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::StaticStr(s) => write!(f, "{}", s),
             Self::Str(s) => write!(f, "{}", s),
+            Self::Diagnostic(d) => write!(f, "error[{}]: {}", d.kind, d.message),
         }
     }
 }
@@ -59,11 +264,50 @@ impl CompileError {
             errors: vec![ErrorKind::Str(val.to_owned())],
         }
     }
+
+    /// Create a single error from a diagnostic.
+    pub fn diagnostic(diagnostic: Diagnostic) -> Self {
+        Self {
+            errors: vec![ErrorKind::Diagnostic(diagnostic)],
+        }
+    }
+
+    /// Create an error for each of the diagnostics.
+    pub fn from_diagnostics(diagnostics: Vec<Diagnostic>) -> Self {
+        Self {
+            errors: diagnostics.into_iter().map(ErrorKind::Diagnostic).collect(),
+        }
+    }
+
+    /// Returns the diagnostics of all errors. Errors that aren't caused by
+    /// the source code, e.g. failures of the Yul compiler, are turned into
+    /// diagnostics without labels.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.errors
+            .iter()
+            .map(|error| match error {
+                ErrorKind::Diagnostic(diagnostic) => diagnostic.clone(),
+                _ => Diagnostic::error("CompileError", &error.to_string()),
+            })
+            .collect()
+    }
+
+    /// Formats all errors using the source code, separated by empty lines.
+    pub fn format_user(&self, src: &str) -> String {
+        self.diagnostics()
+            .iter()
+            .map(|diagnostic| diagnostic.format_user(src))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
 }
 
 impl<'a> From<TokenizeError> for CompileError {
-    fn from(_: TokenizeError) -> Self {
-        CompileError::static_str("tokenize error")
+    fn from(error: TokenizeError) -> Self {
+        CompileError::diagnostic(
+            Diagnostic::error("TokenizeError", error.msg)
+                .with_label(Span::new(error.offset, error.offset), ""),
+        )
     }
 }
 
@@ -78,3 +322,18 @@ impl<'a> From<ethabi::Error> for CompileError {
         CompileError::str(&format!("ethabi error: {}", e))
     }
 }
+
+fn line_number(src: &str, span: Span) -> usize {
+    src[..span.start].lines().count()
+}
+
+/// Returns the span of the lines that the given span is part of, without the
+/// final line break.
+fn surrounding_lines(src: &str, span: Span) -> Span {
+    let start = src[..span.start].rfind('\n').map_or(0, |pos| pos + 1);
+    let end = src[span.end..]
+        .find('\n')
+        .map_or(src.len(), |pos| span.end + pos);
+
+    Span::new(start, end)
+}
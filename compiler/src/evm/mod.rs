@@ -3,10 +3,15 @@
 use crate::errors::CompileError;
 use crate::types::{
     Bytecode,
+    ContractBytecode,
     NamedBytecodeContracts,
     NamedYulContracts,
     YulIr,
 };
+use fe_common::utils::keccak;
+
+/// The maximum size in bytes of deployed contract code, as defined by EIP-170.
+pub const MAX_RUNTIME_SIZE: usize = 0x6000;
 
 /// Compile a map of Yul contracts to a map of bytecode contracts.
 pub fn compile(
@@ -16,7 +21,7 @@ pub fn compile(
     contracts
         .drain()
         .map(|(name, yul_src)| {
-            compile_single_contract(&name, yul_src, optimize).map(|bytecode| (name, bytecode))
+            compile_contract_bytecode(&name, yul_src, optimize).map(|bytecode| (name, bytecode))
         })
         .collect::<Result<NamedBytecodeContracts, _>>()
 }
@@ -27,6 +32,15 @@ pub fn compile_single_contract(
     yul_src: YulIr,
     optimize: bool,
 ) -> Result<Bytecode, CompileError> {
+    compile_contract_bytecode(name, yul_src, optimize).map(|bytecode| bytecode.creation)
+}
+
+/// Compiles a single Yul contract to its creation and runtime bytecode.
+pub fn compile_contract_bytecode(
+    name: &str,
+    yul_src: YulIr,
+    optimize: bool,
+) -> Result<ContractBytecode, CompileError> {
     let solc_temp = include_str!("solc_temp.json");
     let input = solc_temp
         .replace("{optimizer_enabled}", &optimize.to_string())
@@ -34,15 +48,163 @@ pub fn compile_single_contract(
     let raw_output = solc::compile(&input);
     let output: serde_json::Value = serde_json::from_str(&raw_output)?;
 
-    let bytecode = output["contracts"]["input.yul"][name]["evm"]["bytecode"]["object"]
+    let evm_output = &output["contracts"]["input.yul"][name]["evm"];
+    let bytecode = evm_output["bytecode"]["object"]
         .to_string()
         .replace("\"", "");
 
     if bytecode == "null" {
+        if let Some(error) = stack_too_deep_error(&yul_src, &output) {
+            return Err(error);
+        }
+
         return Err(CompileError::str(&output.to_string()));
     }
 
-    Ok(bytecode)
+    // solc treats the only sub-object of a Yul object as its runtime code.
+    let runtime = evm_output["deployedBytecode"]["object"]
+        .as_str()
+        .unwrap_or("")
+        .to_string();
+
+    Ok(ContractBytecode {
+        creation: bytecode,
+        runtime,
+    })
+}
+
+/// A contract compiled from Solidity, e.g. to compare its behavior with that
+/// of an equivalent Fe contract.
+pub struct SolidityContract {
+    /// The hex encoded creation bytecode.
+    pub bytecode: Bytecode,
+    pub json_abi: String,
+}
+
+/// Compiles the contract with the given name from Solidity source code.
+pub fn compile_solidity(
+    src: &str,
+    name: &str,
+    optimize: bool,
+) -> Result<SolidityContract, CompileError> {
+    let input = serde_json::json!({
+        "language": "Solidity",
+        "sources": { "input.sol": { "content": src } },
+        "settings": {
+            "optimizer": { "enabled": optimize },
+            "outputSelection": { "*": { "*": ["abi", "evm.bytecode.object"] } }
+        }
+    });
+    let output: serde_json::Value = serde_json::from_str(&solc::compile(&input.to_string()))?;
+
+    let errors = output["errors"]
+        .as_array()
+        .map(|errors| {
+            errors
+                .iter()
+                .filter(|error| error["severity"] == "error")
+                .filter_map(|error| error["formattedMessage"].as_str())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    if !errors.is_empty() {
+        return Err(CompileError::str(&errors.join("\n")));
+    }
+
+    let contract = &output["contracts"]["input.sol"][name];
+    match contract["evm"]["bytecode"]["object"].as_str() {
+        Some(bytecode) => Ok(SolidityContract {
+            bytecode: bytecode.to_string(),
+            json_abi: contract["abi"].to_string(),
+        }),
+        None => Err(CompileError::str(&format!(
+            "no Solidity contract named `{}`",
+            name
+        ))),
+    }
+}
+
+/// The placeholder that solc leaves in hex encoded bytecode for the address
+/// of a library that has not been linked yet.
+pub fn library_placeholder(library_name: &str) -> String {
+    let hash = keccak::full(library_name.as_bytes());
+    format!("__${}$__", &hash[2..36])
+}
+
+/// Replaces the placeholders of a library in hex encoded bytecode with the
+/// hex encoded address the library has been deployed to.
+pub fn link_library(bytecode: &str, library_name: &str, address: &str) -> String {
+    bytecode.replace(
+        &library_placeholder(library_name),
+        address.trim_start_matches("0x"),
+    )
+}
+
+/// The size in bytes of hex encoded bytecode.
+pub fn bytecode_size(bytecode: &str) -> usize {
+    bytecode.len() / 2
+}
+
+/// Translates a solc "stack too deep" error into an error that names the Fe
+/// function and variable responsible for it.
+///
+/// Returns `None` if the output does not contain a stack depth error.
+fn stack_too_deep_error(yul_src: &str, output: &serde_json::Value) -> Option<CompileError> {
+    let error = output["errors"]
+        .as_array()?
+        .iter()
+        .find(|error| error["message"].as_str().unwrap_or("").contains("too deep"))?;
+    let message = error["message"].as_str().unwrap_or("");
+
+    // Yul variables and functions generated from Fe are prefixed with `$` and
+    // `$$` respectively (see `yul::names`).
+    let variable = message
+        .split_whitespace()
+        .find(|word| word.starts_with('$'))
+        .map(|word| {
+            word.trim_start_matches('$')
+                .trim_end_matches(|c: char| !c.is_alphanumeric() && c != '_')
+                .to_string()
+        });
+    let function = error["sourceLocation"]["start"]
+        .as_u64()
+        .and_then(|start| enclosing_function(yul_src, start as usize));
+
+    let location = match (&function, &variable) {
+        (Some(function), Some(variable)) => {
+            format!("variable `{}` in function `{}`", variable, function)
+        }
+        (Some(function), None) => format!("function `{}`", function),
+        (None, Some(variable)) => format!("variable `{}`", variable),
+        (None, None) => "a value".to_string(),
+    };
+    let subject = match &function {
+        Some(function) => format!("`{}`", function),
+        None => "the function".to_string(),
+    };
+
+    Some(CompileError::str(&format!(
+        "stack too deep: {} could not be reached on the stack. Consider splitting {} into \
+         smaller functions or reducing the number of local variables and parameters it uses",
+        location, subject
+    )))
+}
+
+/// Finds the name of the Fe function whose Yul definition encloses `offset`.
+fn enclosing_function(yul_src: &str, offset: usize) -> Option<String> {
+    let prefix = "function $$";
+    let preceding = yul_src.get(..offset)?;
+    let start = preceding.rfind(prefix)? + prefix.len();
+    let name = yul_src[start..]
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect::<String>();
+
+    if name.is_empty() {
+        None
+    } else {
+        Some(name)
+    }
 }
 
 #[test]
@@ -62,3 +224,49 @@ fn test_solc_sanity() {
 
     assert_eq!(bytecode, "6000600055", "incorrect bytecode",);
 }
+
+#[test]
+fn test_bytecode_size() {
+    assert_eq!(bytecode_size("6000600055"), 5);
+    assert_eq!(bytecode_size(""), 0);
+}
+
+#[test]
+fn test_link_library() {
+    let placeholder = library_placeholder("Foo");
+    assert_eq!(placeholder.len(), 40);
+    assert!(placeholder.starts_with("__$") && placeholder.ends_with("$__"));
+
+    let bytecode = format!("73{}3f", placeholder);
+    assert_eq!(
+        link_library(
+            &bytecode,
+            "Foo",
+            "0x0102030405060708090a0b0c0d0e0f1011121314"
+        ),
+        "730102030405060708090a0b0c0d0e0f10111213143f"
+    );
+    assert_eq!(link_library(&bytecode, "Bar", "0x00"), bytecode);
+}
+
+#[test]
+fn test_stack_too_deep_error() {
+    let yul_src = "object \"Foo\" { code { function $$bar($a, $b) -> return_val { } } }";
+    let offset = yul_src.find("return_val").unwrap();
+    let output = serde_json::json!({
+        "errors": [{
+            "message": "Variable $b is 1 slot(s) too deep inside the stack.",
+            "severity": "error",
+            "sourceLocation": { "file": "input.yul", "start": offset, "end": offset + 1 }
+        }]
+    });
+
+    let error = stack_too_deep_error(yul_src, &output).expect("stack error not detected");
+    assert_eq!(
+        error.to_string(),
+        "stack too deep: variable `b` in function `bar` could not be reached on the stack. \
+         Consider splitting `bar` into smaller functions or reducing the number of local \
+         variables and parameters it uses"
+    );
+    assert!(stack_too_deep_error(yul_src, &serde_json::json!({ "errors": [] })).is_none());
+}
@@ -0,0 +1,360 @@
+//! Loading of source files.
+//!
+//! The compiler never reads source files itself, so embedders can provide
+//! them from memory, archives or the network instead of the file system.
+
+use crate::errors::{
+    Diagnostic,
+    Label,
+    Suggestion,
+};
+use fe_analyzer::lints::{
+    Level,
+    Warning,
+};
+use fe_parser::span::{
+    Span,
+    Spanned,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const UTF8_BOM: &[u8] = &[0xef, 0xbb, 0xbf];
+
+/// Provides the content of source files by path.
+pub trait FileLoader {
+    /// Returns the content of the file at `path`, which is the path of the
+    /// module being compiled.
+    fn load(&self, path: &str) -> io::Result<String>;
+}
+
+/// Loads files from the file system, relative to the working directory.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FsLoader;
+
+impl FileLoader for FsLoader {
+    fn load(&self, path: &str) -> io::Result<String> {
+        read_source(path)
+    }
+}
+
+/// Reads and decodes a source file from the file system.
+pub fn read_source<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    let bytes = fs::read(path)?;
+    decode_source(&bytes).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+/// Decodes the content of a source file, which has to be UTF-8. A byte order
+/// mark is removed and line endings are normalized to `\n`, so that spans and
+/// line numbers don't depend on the editor that wrote the file.
+///
+/// Invalid UTF-8 is reported with the offset of the first invalid byte in the
+/// file and its line and column.
+pub fn decode_source(bytes: &[u8]) -> Result<String, String> {
+    if bytes.starts_with(&[0xff, 0xfe]) || bytes.starts_with(&[0xfe, 0xff]) {
+        return Err("the file is encoded as UTF-16, but sources have to be UTF-8".to_string());
+    }
+    let bom = if bytes.starts_with(UTF8_BOM) {
+        UTF8_BOM.len()
+    } else {
+        0
+    };
+
+    let text = std::str::from_utf8(&bytes[bom..]).map_err(|error| {
+        let valid = &bytes[bom..bom + error.valid_up_to()];
+        let line_start = valid
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map_or(0, |index| index + 1);
+        format!(
+            "invalid UTF-8 byte 0x{:02x} at byte offset {} (line {}, column {})",
+            bytes[bom + valid.len()],
+            bom + valid.len(),
+            valid.iter().filter(|byte| **byte == b'\n').count() + 1,
+            String::from_utf8_lossy(&valid[line_start..])
+                .chars()
+                .count()
+                + 1
+        )
+    })?;
+
+    Ok(text.replace("\r\n", "\n").replace('\r', "\n"))
+}
+
+/// Encodes source text that was decoded from `original` with `decode_source`,
+/// e.g. after it has been edited, restoring the byte order mark and the line
+/// endings of the original. Files with mixed line endings get `\r\n` if they
+/// have any.
+pub fn encode_source(text: &str, original: &[u8]) -> Vec<u8> {
+    let line_ending = if original.windows(2).any(|pair| pair == b"\r\n") {
+        "\r\n"
+    } else if original.contains(&b'\r') {
+        "\r"
+    } else {
+        "\n"
+    };
+
+    let mut bytes = vec![];
+    if original.starts_with(UTF8_BOM) {
+        bytes.extend_from_slice(UTF8_BOM);
+    }
+    bytes.extend_from_slice(text.replace('\n', line_ending).as_bytes());
+    bytes
+}
+
+/// Loads files that have been added to it.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryLoader {
+    files: HashMap<String, String>,
+}
+
+impl MemoryLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a file, replacing any file with the same path.
+    pub fn with_file(mut self, path: &str, content: &str) -> Self {
+        self.files.insert(path.to_owned(), content.to_owned());
+        self
+    }
+}
+
+impl FileLoader for MemoryLoader {
+    fn load(&self, path: &str) -> io::Result<String> {
+        self.files.get(path).cloned().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no file with path `{}`", path),
+            )
+        })
+    }
+}
+
+/// A source file of a `SourceTree`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SourceFile {
+    pub path: String,
+    /// The path of the module in the tree, e.g. `tokens::erc20` for
+    /// `tokens/erc20.fe`.
+    pub module: String,
+    pub content: String,
+    /// The offset of the file in the source of the tree.
+    pub(crate) offset: usize,
+}
+
+/// The source files of a directory, which are compiled together as a single
+/// module.
+///
+/// Fe doesn't resolve imports yet, so the items of all files share one
+/// namespace. The files are joined in the order of their module paths, and
+/// diagnostics are formatted against the file that they point into.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SourceTree {
+    files: Vec<SourceFile>,
+    src: String,
+}
+
+impl SourceTree {
+    /// Creates a tree of the files, given by their path relative to the root
+    /// of the tree and their content.
+    pub fn new(files: Vec<(String, String)>) -> Self {
+        let mut files = files
+            .into_iter()
+            .map(|(path, content)| SourceFile {
+                module: module_path(&path),
+                path,
+                content,
+                offset: 0,
+            })
+            .collect::<Vec<_>>();
+        files.sort_by(|a, b| a.module.cmp(&b.module));
+
+        let mut src = String::new();
+        for file in files.iter_mut() {
+            file.offset = src.len();
+            src.push_str(&file.content);
+            if !src.ends_with('\n') {
+                src.push('\n');
+            }
+        }
+
+        SourceTree { files, src }
+    }
+
+    /// The source of all files joined together.
+    pub fn src(&self) -> &str {
+        &self.src
+    }
+
+    pub fn files(&self) -> &[SourceFile] {
+        &self.files
+    }
+
+    /// The file containing the offset in the joined source.
+    pub(crate) fn file_at(&self, offset: usize) -> Option<&SourceFile> {
+        self.files
+            .iter()
+            .rev()
+            .find(|file| file.offset <= offset)
+            .filter(|file| offset <= file.offset + file.content.len())
+    }
+
+    /// Moves a span of the joined source into the file, or returns `None` if
+    /// it doesn't lie within the file.
+    fn relocate(file: &SourceFile, span: Span) -> Option<Span> {
+        if span.start < file.offset || span.end > file.offset + file.content.len() {
+            return None;
+        }
+        Some(Span::new(span.start - file.offset, span.end - file.offset))
+    }
+
+    /// Formats the diagnostic using the source of the file that its primary
+    /// label points into, prefixed with the path of the file if the tree has
+    /// more than one.
+    pub fn format_diagnostic(&self, diagnostic: &Diagnostic) -> String {
+        let file = match diagnostic.labels.first() {
+            Some(label) => self.file_at(label.span.start),
+            None => None,
+        };
+        let file = match file {
+            Some(file) if self.files.len() > 1 => file,
+            _ => return diagnostic.format_user(&self.src),
+        };
+
+        let mut relocated = diagnostic.clone();
+        relocated.labels = diagnostic
+            .labels
+            .iter()
+            .filter_map(|label| {
+                Some(Label {
+                    span: Self::relocate(file, label.span)?,
+                    message: label.message.clone(),
+                })
+            })
+            .collect();
+        relocated.suggestions = diagnostic
+            .suggestions
+            .iter()
+            .filter_map(|suggestion| {
+                Some(Suggestion {
+                    span: Self::relocate(file, suggestion.span)?,
+                    ..suggestion.clone()
+                })
+            })
+            .collect();
+
+        format!("{}: {}", file.path, relocated.format_user(&file.content))
+    }
+
+    /// Formats the lint warning like `format_diagnostic`.
+    pub fn format_warning(&self, warning: &Warning, level: Level) -> String {
+        let file = self
+            .file_at(warning.span.start)
+            .filter(|_| self.files.len() > 1);
+        let (file, span) =
+            match file.and_then(|file| Some((file, Self::relocate(file, warning.span)?))) {
+                Some(located) => located,
+                None => return warning.format_user(&self.src, level),
+            };
+
+        let mut relocated = warning.clone();
+        relocated.span = span;
+        relocated.notes = warning
+            .notes
+            .iter()
+            .filter_map(|note| {
+                Some(Spanned {
+                    node: note.node.clone(),
+                    span: Self::relocate(file, note.span)?,
+                })
+            })
+            .collect();
+
+        format!(
+            "{}: {}",
+            file.path,
+            relocated.format_user(&file.content, level)
+        )
+    }
+}
+
+/// The module path of a source file, e.g. `tokens::erc20` for
+/// `tokens/erc20.fe`.
+fn module_path(path: &str) -> String {
+    path.trim_end_matches(".fe")
+        .split(|c| c == '/' || c == '\\')
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::Diagnostic;
+    use crate::files::{
+        decode_source,
+        encode_source,
+        SourceTree,
+    };
+    use fe_parser::span::Span;
+
+    #[test]
+    fn source_tree() {
+        let tree = SourceTree::new(vec![
+            (
+                "tokens/erc20.fe".to_string(),
+                "contract Token:\n  x: u256\n".to_string(),
+            ),
+            (
+                "main.fe".to_string(),
+                "contract Main:\n  y: u256".to_string(),
+            ),
+        ]);
+        let modules = tree
+            .files()
+            .iter()
+            .map(|file| file.module.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(modules, vec!["main", "tokens::erc20"]);
+        assert_eq!(
+            tree.src(),
+            "contract Main:\n  y: u256\ncontract Token:\n  x: u256\n"
+        );
+
+        let start = tree.src().find("Token").unwrap();
+        let diagnostic =
+            Diagnostic::error("TypeError", "bad token").with_label(Span::new(start, start + 5), "");
+        let formatted = tree.format_diagnostic(&diagnostic);
+        assert!(formatted.starts_with("tokens/erc20.fe: error[TypeError]: bad token on line 1"));
+    }
+
+    #[test]
+    fn source_encoding() {
+        assert_eq!(
+            decode_source(b"\xef\xbb\xbfcontract Foo:\r\n  x: u256\r  y: u256\n").unwrap(),
+            "contract Foo:\n  x: u256\n  y: u256\n"
+        );
+        assert_eq!(
+            decode_source(b"# \xc3\xa9\n  x: \xff"),
+            Err("invalid UTF-8 byte 0xff at byte offset 10 (line 2, column 6)".to_string())
+        );
+        assert!(decode_source(b"\xff\xfec\x00").is_err());
+    }
+
+    #[test]
+    fn reencoded_source() {
+        let original = b"\xef\xbb\xbfcontract Foo:\r\n  x: u256\r\n";
+        let text = decode_source(original).unwrap().replace("x", "y");
+        assert_eq!(
+            encode_source(&text, original),
+            b"\xef\xbb\xbfcontract Foo:\r\n  y: u256\r\n".to_vec()
+        );
+
+        let original = b"contract Foo:\n  x: u256\n";
+        let text = decode_source(original).unwrap();
+        assert_eq!(encode_source(&text, original), original.to_vec());
+    }
+}
@@ -0,0 +1,151 @@
+//! Applies the suggested fixes of diagnostics to source code, so that obvious
+//! mistakes like misspelled names don't have to be corrected by hand.
+
+use crate::cfg;
+use crate::errors::{
+    CompileError,
+    Diagnostic,
+    Suggestion,
+};
+use crate::types::FeSrc;
+
+/// The number of times the source code is checked again after fixes were
+/// applied. Later rounds pick up errors that were hidden by the fixed ones.
+const MAX_ROUNDS: usize = 4;
+
+/// Source code with suggested fixes applied.
+#[derive(Debug, PartialEq)]
+pub struct Fixed {
+    pub src: String,
+    /// The number of suggestions that were applied.
+    pub fixes: usize,
+}
+
+/// Applies the machine-applicable suggestions of all errors in the source
+/// code. The code is checked again after each round of fixes until there is
+/// nothing left to fix.
+pub fn fix(src: FeSrc) -> Fixed {
+    let mut fixed = Fixed {
+        src: src.to_string(),
+        fixes: 0,
+    };
+
+    for _ in 0..MAX_ROUNDS {
+        let suggestions = check(&fixed.src)
+            .err()
+            .map(|error| {
+                error
+                    .diagnostics()
+                    .into_iter()
+                    .flat_map(|diagnostic| diagnostic.suggestions)
+                    .filter(|suggestion| suggestion.machine_applicable)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let (src, applied) = apply_suggestions(&fixed.src, &suggestions);
+        if applied == 0 {
+            break;
+        }
+        fixed.src = src;
+        fixed.fixes += applied;
+    }
+
+    fixed
+}
+
+/// Replaces the spans of the suggestions in the source code. Suggestions that
+/// overlap one that starts earlier are skipped. Returns the new source code
+/// and the number of suggestions that were applied.
+pub fn apply_suggestions(src: &str, suggestions: &[Suggestion]) -> (String, usize) {
+    let mut suggestions = suggestions.iter().collect::<Vec<_>>();
+    suggestions.sort_by_key(|suggestion| (suggestion.span.start, suggestion.span.end));
+
+    let mut fixed = String::new();
+    let mut end = 0;
+    let mut applied = 0;
+    for suggestion in suggestions {
+        if suggestion.span.start < end {
+            continue;
+        }
+        fixed.push_str(&src[end..suggestion.span.start]);
+        fixed.push_str(&suggestion.replacement);
+        end = suggestion.span.end;
+        applied += 1;
+    }
+    fixed.push_str(&src[end..]);
+
+    (fixed, applied)
+}
+
+/// Parses and analyzes the source code without compiling it.
+fn check(src: FeSrc) -> Result<(), CompileError> {
+    let fe_tokens = fe_parser::get_parse_tokens(src)?;
+    let mut fe_module = crate::parse(&fe_tokens)?;
+    cfg::strip(&mut fe_module, &cfg::Config::default())?;
+
+    fe_analyzer::analyze(&fe_module)
+        .map(|_| ())
+        .map_err(|errors| {
+            CompileError::from_diagnostics(
+                errors.iter().map(Diagnostic::from_semantic_error).collect(),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::Suggestion;
+    use crate::fix::{
+        apply_suggestions,
+        fix,
+    };
+    use fe_parser::span::Span;
+
+    fn suggestion(start: usize, end: usize, replacement: &str) -> Suggestion {
+        Suggestion {
+            message: "".to_string(),
+            span: Span::new(start, end),
+            replacement: replacement.to_string(),
+            machine_applicable: true,
+        }
+    }
+
+    #[test]
+    fn apply() {
+        let suggestions = vec![suggestion(4, 5, "b"), suggestion(0, 1, "a")];
+        assert_eq!(
+            apply_suggestions("x + y", &suggestions),
+            ("a + b".to_string(), 2)
+        );
+    }
+
+    #[test]
+    fn apply_overlapping() {
+        let suggestions = vec![suggestion(0, 3, "a"), suggestion(2, 5, "b")];
+        assert_eq!(
+            apply_suggestions("x + y", &suggestions),
+            ("a + y".to_string(), 1)
+        );
+    }
+
+    #[test]
+    fn fix_misspelled_names() {
+        let src = "\
+contract Foo:
+    balance: u256
+
+    pub def bar(amount: u256) -> u256:
+        return amuont + self.balanse
+";
+        let fixed = fix(src);
+        assert_eq!(fixed.fixes, 2);
+        assert!(fixed.src.contains("return amount + self.balance\n"));
+    }
+
+    #[test]
+    fn nothing_to_fix() {
+        let src = "contract Foo:\n    pub def bar(x: u256) -> u256:\n        return y\n";
+        assert_eq!(fix(src).fixes, 0);
+    }
+}
@@ -0,0 +1,256 @@
+//! Flattening of a source tree into a single source file (`fe flatten`),
+//! which some verification and audit tools require.
+//!
+//! Imports aren't resolved yet, so the files of a tree already share one
+//! namespace and nothing has to be renamed. Imports of modules in the tree
+//! are removed, and definitions that are repeated word for word are kept
+//! once. The definitions are ordered by kind: imports of other modules,
+//! types, constants, functions, interfaces and traits, mixins, and finally
+//! libraries and contracts, each after the ones it deploys or uses. The text
+//! of each definition is copied with its comments, except for comments after
+//! the last definition of a file.
+
+use crate::errors::CompileError;
+use crate::files::SourceTree;
+use crate::graph::contract_references;
+use fe_parser::ast as fe;
+use fe_parser::span::Spanned;
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+};
+
+/// A definition of the tree with the source text that is copied for it.
+struct Definition<'a> {
+    kind: usize,
+    /// The name of a library or contract.
+    name: Option<&'a str>,
+    text: &'a str,
+    stmt: &'a fe::ModuleStmt<'a>,
+}
+
+/// Flattens the files of the tree into the source of a single module.
+pub fn flatten(tree: &SourceTree) -> Result<String, CompileError> {
+    let src = tree.src();
+    let tokens = fe_parser::get_parse_tokens(src)?;
+    let ast = crate::parse(&tokens)?;
+    let modules = tree
+        .files()
+        .iter()
+        .map(|file| file.module.as_str())
+        .collect::<BTreeSet<_>>();
+
+    let mut definitions = vec![];
+    let mut codes = BTreeSet::new();
+    let mut text_start = 0;
+    let mut current_file = None;
+    for stmt in ast.body.iter() {
+        let file = match tree.file_at(stmt.span.start) {
+            Some(file) => file,
+            None => continue,
+        };
+        if current_file != Some(&file.path) {
+            current_file = Some(&file.path);
+            text_start = file.offset;
+        }
+        // the text runs to the end of the line, to keep trailing comments
+        let file_end = file.offset + file.content.len();
+        let text_end = src[stmt.span.end..file_end]
+            .find('\n')
+            .map_or(file_end, |index| stmt.span.end + index);
+        let text = &src[text_start..text_end];
+        text_start = text_end;
+
+        // duplicates are found without their comments
+        let code = &src[stmt.span.start..stmt.span.end];
+        if is_tree_import(&stmt.node, &file.module, &modules) || !codes.insert(code) {
+            continue;
+        }
+        definitions.push(Definition {
+            kind: kind(&stmt.node),
+            name: contract_name(&stmt.node),
+            text: text.trim_start_matches('\n').trim_end(),
+            stmt: &stmt.node,
+        });
+    }
+    definitions.sort_by_key(|definition| definition.kind);
+
+    let mut out = "# Flattened by `fe flatten` from:\n".to_string();
+    for file in tree.files() {
+        out.push_str(&format!("#   {}\n", file.path));
+    }
+
+    let mut previous_kind = None;
+    for definition in in_dependency_order(&definitions) {
+        let separator = match previous_kind {
+            Some(0) if definition.kind == 0 => "\n",
+            _ => "\n\n",
+        };
+        out.push_str(separator);
+        out.push_str(definition.text);
+        out.push('\n');
+        previous_kind = Some(definition.kind);
+    }
+
+    Ok(out)
+}
+
+fn kind(stmt: &fe::ModuleStmt) -> usize {
+    match stmt {
+        fe::ModuleStmt::SimpleImport { .. } | fe::ModuleStmt::FromImport { .. } => 0,
+        fe::ModuleStmt::TypeDef { .. }
+        | fe::ModuleStmt::StructDef { .. }
+        | fe::ModuleStmt::EnumDef { .. } => 1,
+        fe::ModuleStmt::ConstDef { .. } => 2,
+        fe::ModuleStmt::FuncDef { .. } | fe::ModuleStmt::Error(_) => 3,
+        fe::ModuleStmt::InterfaceDef { .. } | fe::ModuleStmt::TraitDef { .. } => 4,
+        fe::ModuleStmt::MixinDef { .. } => 5,
+        fe::ModuleStmt::LibraryDef { .. } | fe::ModuleStmt::ContractDef { .. } => 6,
+    }
+}
+
+fn contract_name<'a>(stmt: &fe::ModuleStmt<'a>) -> Option<&'a str> {
+    match stmt {
+        fe::ModuleStmt::LibraryDef { name, .. } | fe::ModuleStmt::ContractDef { name, .. } => {
+            Some(name.node)
+        }
+        _ => None,
+    }
+}
+
+/// Whether the statement only imports modules of the tree, which are part of
+/// the flattened module.
+fn is_tree_import(stmt: &fe::ModuleStmt, module: &str, modules: &BTreeSet<&str>) -> bool {
+    match stmt {
+        fe::ModuleStmt::SimpleImport { names } => names
+            .iter()
+            .all(|name| modules.contains(join(&name.node.path).as_str())),
+        fe::ModuleStmt::FromImport { path, .. } => {
+            let imported = match &path.node {
+                fe::FromImportPath::Absolute { path } => join(path),
+                fe::FromImportPath::Relative { parent_level, path } => {
+                    // `.` is the package of the module, and each further dot
+                    // its parent
+                    let mut parts = module.split("::").collect::<Vec<_>>();
+                    parts.truncate(parts.len().saturating_sub(*parent_level));
+                    parts.extend(path.iter().map(|part| part.node));
+                    parts.join("::")
+                }
+            };
+            modules.contains(imported.as_str())
+        }
+        _ => false,
+    }
+}
+
+fn join(path: &[Spanned<&str>]) -> String {
+    path.iter()
+        .map(|part| part.node)
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// Orders the libraries and contracts after those they deploy or use, and
+/// keeps the order of everything else. Contracts that depend on each other
+/// stay in the order of their files.
+fn in_dependency_order<'a, 'b>(definitions: &'b [Definition<'a>]) -> Vec<&'b Definition<'a>> {
+    let contracts = definitions
+        .iter()
+        .filter_map(|definition| Some((definition.name?, definition)))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut ordered = vec![];
+    let mut visited = BTreeSet::new();
+    for definition in definitions {
+        visit(definition, &contracts, &mut visited, &mut ordered);
+    }
+
+    ordered
+}
+
+fn visit<'a, 'b>(
+    definition: &'b Definition<'a>,
+    contracts: &BTreeMap<&str, &'b Definition<'a>>,
+    visited: &mut BTreeSet<&'a str>,
+    ordered: &mut Vec<&'b Definition<'a>>,
+) {
+    let name = match definition.name {
+        Some(name) => name,
+        None => return ordered.push(definition),
+    };
+    if !visited.insert(name) {
+        return;
+    }
+
+    let body = match definition.stmt {
+        fe::ModuleStmt::LibraryDef { body, .. } | fe::ModuleStmt::ContractDef { body, .. } => body,
+        _ => return ordered.push(definition),
+    };
+    for other in contract_references(body).keys() {
+        if let Some(other) = contracts.get(other.as_str()) {
+            visit(other, contracts, visited, ordered);
+        }
+    }
+    ordered.push(definition);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::files::SourceTree;
+    use crate::flatten::flatten;
+
+    #[test]
+    fn flattened_tree() {
+        let tree = SourceTree::new(vec![
+            (
+                "factory.fe".to_string(),
+                "from tokens import Token\n\
+                 from std.math import max\n\
+                 \n\
+                 # deploys tokens\n\
+                 contract Factory:\n    \
+                     pub def deploy() -> address:\n        \
+                         return address(Token.create(0))\n\
+                 \n\
+                 struct Pair:\n    \
+                     a: u256\n"
+                    .to_string(),
+            ),
+            (
+                "tokens.fe".to_string(),
+                "struct Pair:\n    \
+                     a: u256\n\
+                 \n\
+                 const SUPPLY: u256 = 100  # fixed\n\
+                 \n\
+                 contract Token:\n    \
+                     supply: u256\n"
+                    .to_string(),
+            ),
+        ]);
+
+        assert_eq!(
+            flatten(&tree).unwrap(),
+            "\
+# Flattened by `fe flatten` from:
+#   factory.fe
+#   tokens.fe
+
+from std.math import max
+
+struct Pair:
+    a: u256
+
+const SUPPLY: u256 = 100  # fixed
+
+contract Token:
+    supply: u256
+
+# deploys tokens
+contract Factory:
+    pub def deploy() -> address:
+        return address(Token.create(0))
+"
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! The graph of the modules of a source tree, their imports and the
+//! contracts that deploy or use each other (`fe graph`).
+//!
+//! Imports aren't resolved yet, so imported paths are shown as they are
+//! written.
+
+use crate::errors::CompileError;
+use crate::files::SourceTree;
+use fe_analyzer::lints::for_each_expr;
+use fe_parser::ast as fe;
+use fe_parser::span::Spanned;
+use serde::Serialize;
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+};
+
+/// A module of the source tree.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Module {
+    /// The module path, e.g. `tokens::erc20`.
+    pub name: String,
+    pub path: String,
+    /// The contracts defined in the module.
+    pub contracts: Vec<String>,
+}
+
+/// How one node of the graph depends on another.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum EdgeKind {
+    /// A module imports another module or an item of it.
+    Imports,
+    /// A contract creates instances of another contract.
+    Deploys,
+    /// A contract refers to another contract, e.g. to call it at an address.
+    Uses,
+}
+
+/// A dependency of a module on a module, or of a contract on a contract.
+#[derive(Serialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Graph {
+    pub modules: Vec<Module>,
+    pub edges: Vec<Edge>,
+}
+
+/// Builds the graph of the source tree, whose files only have to parse.
+pub fn build(tree: &SourceTree) -> Result<Graph, CompileError> {
+    // the files are parsed together, so that errors point into the tree
+    let tokens = fe_parser::get_parse_tokens(tree.src())?;
+    let ast = crate::parse(&tokens)?;
+
+    let mut modules = tree
+        .files()
+        .iter()
+        .map(|file| Module {
+            name: file.module.clone(),
+            path: file.path.clone(),
+            contracts: vec![],
+        })
+        .collect::<Vec<_>>();
+    let mut edges = BTreeSet::new();
+    let mut references = BTreeMap::new();
+
+    for stmt in ast.body.iter() {
+        let module = match tree.file_at(stmt.span.start) {
+            Some(file) => modules
+                .iter_mut()
+                .find(|module| module.name == file.module)
+                .expect("every file has a module"),
+            None => continue,
+        };
+
+        match &stmt.node {
+            fe::ModuleStmt::ContractDef { name, body, .. } => {
+                module.contracts.push(name.node.to_string());
+                references.insert(name.node.to_string(), contract_references(body));
+            }
+            fe::ModuleStmt::SimpleImport { names } => {
+                for name in names.iter() {
+                    edges.insert(Edge {
+                        from: module.name.clone(),
+                        to: join(&name.node.path),
+                        kind: EdgeKind::Imports,
+                    });
+                }
+            }
+            fe::ModuleStmt::FromImport { path, .. } => {
+                let to = match &path.node {
+                    fe::FromImportPath::Absolute { path } => join(path),
+                    fe::FromImportPath::Relative { parent_level, path } => {
+                        format!("{}{}", ".".repeat(*parent_level), join(path))
+                    }
+                };
+                edges.insert(Edge {
+                    from: module.name.clone(),
+                    to,
+                    kind: EdgeKind::Imports,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    // only names of contracts are dependencies
+    for (contract, names) in references.iter() {
+        for (other, kind) in names.iter() {
+            if other != contract && references.contains_key(other) {
+                edges.insert(Edge {
+                    from: contract.clone(),
+                    to: other.clone(),
+                    kind: *kind,
+                });
+            }
+        }
+    }
+
+    Ok(Graph {
+        modules,
+        edges: edges.into_iter().collect(),
+    })
+}
+
+fn join(path: &[Spanned<&str>]) -> String {
+    path.iter()
+        .map(|part| part.node)
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// The names referred to by the functions of a contract, and whether the
+/// contract deploys them, which is what a name used in both ways counts as.
+pub(crate) fn contract_references(
+    body: &[Spanned<fe::ContractStmt>],
+) -> BTreeMap<String, EdgeKind> {
+    let mut references = BTreeMap::new();
+
+    for contract_stmt in body.iter() {
+        if let fe::ContractStmt::FuncDef { body, .. } = &contract_stmt.node {
+            for_each_expr(body, &mut |exp| match &exp.node {
+                fe::Expr::Attribute { value, attr }
+                    if attr.node == "create" || attr.node == "create2" =>
+                {
+                    if let fe::Expr::Name(other) = value.node {
+                        references.insert(other.to_string(), EdgeKind::Deploys);
+                    }
+                }
+                fe::Expr::Name(other) => {
+                    references
+                        .entry(other.to_string())
+                        .or_insert(EdgeKind::Uses);
+                }
+                _ => {}
+            });
+        }
+    }
+
+    references
+}
+
+impl Graph {
+    /// Formats the graph in the DOT language of Graphviz, with the contracts
+    /// of each module in a cluster.
+    pub fn to_dot(&self) -> String {
+        let mut dot = "digraph fe {\n    node [shape=box];\n".to_string();
+
+        for (index, module) in self.modules.iter().enumerate() {
+            dot.push_str(&format!(
+                "    subgraph cluster_{} {{\n        label={:?};\n        {:?} [shape=folder];\n",
+                index,
+                module.path,
+                module_node(&module.name)
+            ));
+            for contract in module.contracts.iter() {
+                dot.push_str(&format!("        {:?};\n", contract));
+            }
+            dot.push_str("    }\n");
+        }
+
+        for edge in self.edges.iter() {
+            let (from, to, style) = match edge.kind {
+                EdgeKind::Imports => (module_node(&edge.from), module_node(&edge.to), "dashed"),
+                EdgeKind::Deploys => (edge.from.clone(), edge.to.clone(), "bold"),
+                EdgeKind::Uses => (edge.from.clone(), edge.to.clone(), "solid"),
+            };
+            dot.push_str(&format!(
+                "    {:?} -> {:?} [label={:?}, style={}];\n",
+                from,
+                to,
+                format!("{:?}", edge.kind).to_lowercase(),
+                style
+            ));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("unable to serialize the graph")
+    }
+}
+
+/// The DOT node of a module, which is named apart from contracts.
+fn module_node(module: &str) -> String {
+    format!("mod {}", module)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::files::SourceTree;
+    use crate::graph::{
+        build,
+        Edge,
+        EdgeKind,
+    };
+
+    #[test]
+    fn contract_graph() {
+        let tree = SourceTree::new(vec![
+            (
+                "factory.fe".to_string(),
+                "from tokens import Token\n\
+                 contract Factory:\n    \
+                     pub def deploy() -> address:\n        \
+                         return address(Token.create(0))\n"
+                    .to_string(),
+            ),
+            (
+                "tokens.fe".to_string(),
+                "contract Token:\n    \
+                     pub def owner(factory: address) -> address:\n        \
+                         return Factory(factory).owner()\n"
+                    .to_string(),
+            ),
+        ]);
+        let graph = build(&tree).unwrap();
+
+        assert_eq!(graph.modules[0].contracts, vec!["Factory"]);
+        let edge = |from: &str, to: &str, kind| Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind,
+        };
+        assert_eq!(
+            graph.edges,
+            vec![
+                edge("Factory", "Token", EdgeKind::Deploys),
+                edge("Token", "Factory", EdgeKind::Uses),
+                edge("factory", "tokens", EdgeKind::Imports),
+            ]
+        );
+        assert!(graph
+            .to_dot()
+            .contains("\"Factory\" -> \"Token\" [label=\"deploys\", style=bold];"));
+    }
+}
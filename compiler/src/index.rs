@@ -0,0 +1,777 @@
+//! An index of the definitions of a source tree and of the references to them
+//! (`fe index`), which code navigation tools can read instead of running a
+//! language server.
+//!
+//! The analyzer doesn't record what each name refers to, so names are
+//! resolved by their scopes: a name refers to the innermost local variable or
+//! parameter with that name, then to a member of the contract, and then to a
+//! definition of the module. `self.x` refers to a field or function of the
+//! contract or of one of its mixins, and `Foo.x` to a member of the
+//! definition `Foo`. Names that resolve to nothing, e.g. builtins, aren't
+//! indexed.
+
+use crate::errors::CompileError;
+use crate::files::SourceTree;
+use fe_parser::ast as fe;
+use fe_parser::span::{
+    Span,
+    Spanned,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolKind {
+    Contract,
+    Mixin,
+    Library,
+    Interface,
+    Trait,
+    Struct,
+    Enum,
+    Variant,
+    Type,
+    Constant,
+    /// A field of a contract, which is kept in storage.
+    StorageField,
+    /// A field of a struct.
+    Field,
+    Function,
+    Event,
+    Error,
+    Parameter,
+    Variable,
+}
+
+/// A position in a file, counted from zero like in the language server
+/// protocol. Characters are counted in UTF-16 code units.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub line: usize,
+    pub character: usize,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Location {
+    /// The path of the file in the source tree.
+    pub path: String,
+    pub start: Position,
+    pub end: Position,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Symbol {
+    /// The name, qualified with the names of the definitions containing it,
+    /// e.g. `Token.transfer.to`.
+    pub name: String,
+    pub kind: SymbolKind,
+    /// The location of the name in the definition.
+    pub definition: Location,
+    pub references: Vec<Location>,
+    /// The first line of the definition, e.g. `pub def transfer(to: address)`.
+    pub signature: String,
+    /// The doc comments of the definition.
+    pub docs: String,
+    /// Whether the symbol is assigned to after its definition, which is only
+    /// tracked for variables, parameters and storage fields.
+    pub assigned: bool,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Index {
+    /// The paths of the files of the source tree.
+    pub files: Vec<String>,
+    pub symbols: Vec<Symbol>,
+}
+
+impl Index {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("unable to serialize the index")
+    }
+}
+
+/// Indexes the source tree, whose files only have to parse.
+pub fn build(tree: &SourceTree) -> Result<Index, CompileError> {
+    let tokens = fe_parser::get_parse_tokens(tree.src())?;
+    let ast = crate::parse(&tokens)?;
+
+    let mut indexer = Indexer {
+        src: tree.src(),
+        entries: vec![],
+        globals: HashMap::new(),
+        members: HashMap::new(),
+        mixins: HashMap::new(),
+        scopes: vec![],
+        container: None,
+        function: String::new(),
+    };
+    for stmt in ast.body.iter() {
+        indexer.define_module_stmt(stmt);
+    }
+    for stmt in ast.body.iter() {
+        indexer.module_stmt(&stmt.node);
+    }
+
+    Ok(Index {
+        files: tree.files().iter().map(|file| file.path.clone()).collect(),
+        symbols: indexer
+            .entries
+            .into_iter()
+            .filter_map(|entry| {
+                Some(Symbol {
+                    name: entry.name,
+                    kind: entry.kind,
+                    definition: location(tree, entry.definition)?,
+                    references: entry
+                        .references
+                        .into_iter()
+                        .filter_map(|span| location(tree, span))
+                        .collect(),
+                    signature: entry.signature,
+                    docs: entry.docs,
+                    assigned: entry.assigned,
+                })
+            })
+            .collect(),
+    })
+}
+
+fn location(tree: &SourceTree, span: Span) -> Option<Location> {
+    let file = tree.file_at(span.start)?;
+    let position = |offset: usize| {
+        let before = &file.content[..offset - file.offset];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        Position {
+            line: before.matches('\n').count(),
+            character: before[line_start..].encode_utf16().count(),
+        }
+    };
+
+    Some(Location {
+        path: file.path.clone(),
+        start: position(span.start),
+        end: position(span.end),
+    })
+}
+
+/// A symbol whose locations are still spans of the joined source.
+struct Entry {
+    name: String,
+    kind: SymbolKind,
+    definition: Span,
+    references: Vec<Span>,
+    signature: String,
+    docs: String,
+    assigned: bool,
+}
+
+struct Indexer<'a> {
+    src: &'a str,
+    entries: Vec<Entry>,
+    /// The definitions of the module by name.
+    globals: HashMap<&'a str, usize>,
+    /// The members of contracts, mixins, libraries, interfaces, traits,
+    /// structs and enums by the name of their definition.
+    members: HashMap<&'a str, HashMap<&'a str, usize>>,
+    /// The mixins used by each contract.
+    mixins: HashMap<&'a str, Vec<&'a str>>,
+    /// The local variables and parameters of the blocks around the current
+    /// statement.
+    scopes: Vec<HashMap<&'a str, usize>>,
+    /// The definition whose body is being indexed.
+    container: Option<&'a str>,
+    /// The qualified name of the function being indexed.
+    function: String,
+}
+
+impl<'a> Indexer<'a> {
+    /// Adds a symbol that is defined at the name span, by the statement at
+    /// the definition span.
+    fn define(
+        &mut self,
+        name: String,
+        kind: SymbolKind,
+        name_span: Span,
+        definition: Span,
+        docs: &[Spanned<&str>],
+    ) -> usize {
+        let signature = self.src[definition.start..definition.end]
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .trim_end_matches(':')
+            .to_string();
+        self.entries.push(Entry {
+            name,
+            kind,
+            definition: name_span,
+            references: vec![],
+            signature,
+            docs: fe::doc_string(docs),
+            assigned: false,
+        });
+
+        self.entries.len() - 1
+    }
+
+    fn define_global(
+        &mut self,
+        name: &Spanned<&'a str>,
+        kind: SymbolKind,
+        definition: Span,
+        docs: &[Spanned<&str>],
+    ) {
+        let id = self.define(name.node.to_string(), kind, name.span, definition, docs);
+        self.globals.insert(name.node, id);
+    }
+
+    fn define_member(
+        &mut self,
+        container: &'a str,
+        name: &Spanned<&'a str>,
+        kind: SymbolKind,
+        definition: Span,
+        docs: &[Spanned<&str>],
+    ) {
+        let qualified = format!("{}.{}", container, name.node);
+        let id = self.define(qualified, kind, name.span, definition, docs);
+        self.members
+            .entry(container)
+            .or_default()
+            .insert(name.node, id);
+    }
+
+    /// Defines a local variable or parameter in the innermost scope.
+    fn define_local(&mut self, name: &'a str, kind: SymbolKind, name_span: Span, definition: Span) {
+        let qualified = format!("{}.{}", self.function, name);
+        let id = self.define(qualified, kind, name_span, definition, &[]);
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name, id);
+        }
+    }
+
+    fn reference(&mut self, id: Option<usize>, span: Span) {
+        if let Some(id) = id {
+            self.entries[id].references.push(span)
+        }
+    }
+
+    fn define_module_stmt(&mut self, stmt: &Spanned<fe::ModuleStmt<'a>>) {
+        match &stmt.node {
+            fe::ModuleStmt::TypeDef { docs, name, .. } => {
+                self.define_global(name, SymbolKind::Type, stmt.span, docs)
+            }
+            fe::ModuleStmt::ConstDef { docs, name, .. } => {
+                self.define_global(name, SymbolKind::Constant, stmt.span, docs)
+            }
+            fe::ModuleStmt::ContractDef {
+                docs,
+                name,
+                mixins,
+                body,
+                ..
+            } => {
+                self.define_global(name, SymbolKind::Contract, stmt.span, docs);
+                self.mixins
+                    .insert(name.node, mixins.iter().map(|mixin| mixin.node).collect());
+                self.define_members(name.node, body);
+            }
+            fe::ModuleStmt::MixinDef { docs, name, body } => {
+                self.define_global(name, SymbolKind::Mixin, stmt.span, docs);
+                self.define_members(name.node, body);
+            }
+            fe::ModuleStmt::LibraryDef { docs, name, body } => {
+                self.define_global(name, SymbolKind::Library, stmt.span, docs);
+                self.define_members(name.node, body);
+            }
+            fe::ModuleStmt::TraitDef { docs, name, body }
+            | fe::ModuleStmt::InterfaceDef { docs, name, body } => {
+                let kind = match stmt.node {
+                    fe::ModuleStmt::TraitDef { .. } => SymbolKind::Trait,
+                    _ => SymbolKind::Interface,
+                };
+                self.define_global(name, kind, stmt.span, docs);
+                for sig in body.iter() {
+                    self.define_member(
+                        name.node,
+                        &sig.node.name,
+                        SymbolKind::Function,
+                        sig.span,
+                        &sig.node.docs,
+                    );
+                }
+            }
+            fe::ModuleStmt::StructDef {
+                docs, name, body, ..
+            } => {
+                self.define_global(name, SymbolKind::Struct, stmt.span, docs);
+                for field in body.iter() {
+                    let fe::StructStmt::StructField {
+                        docs,
+                        name: field_name,
+                        ..
+                    } = &field.node;
+                    self.define_member(name.node, field_name, SymbolKind::Field, field.span, docs);
+                }
+            }
+            fe::ModuleStmt::EnumDef {
+                docs,
+                name,
+                variants,
+            } => {
+                self.define_global(name, SymbolKind::Enum, stmt.span, docs);
+                for variant in variants.iter() {
+                    self.define_member(name.node, variant, SymbolKind::Variant, variant.span, &[]);
+                }
+            }
+            fe::ModuleStmt::FuncDef { def } => {
+                if let fe::ContractStmt::FuncDef { docs, name, .. } = &def.node {
+                    self.define_global(name, SymbolKind::Function, def.span, docs)
+                }
+            }
+            fe::ModuleStmt::SimpleImport { .. }
+            | fe::ModuleStmt::FromImport { .. }
+            | fe::ModuleStmt::Error(_) => {}
+        }
+    }
+
+    fn define_members(&mut self, container: &'a str, body: &[Spanned<fe::ContractStmt<'a>>]) {
+        for stmt in body.iter() {
+            let (docs, name, kind) = match &stmt.node {
+                fe::ContractStmt::ContractField {
+                    docs,
+                    qual:
+                        Some(Spanned {
+                            node: fe::ContractFieldQual::Const,
+                            ..
+                        }),
+                    name,
+                    ..
+                } => (docs, name, SymbolKind::Constant),
+                fe::ContractStmt::ContractField { docs, name, .. } => {
+                    (docs, name, SymbolKind::StorageField)
+                }
+                fe::ContractStmt::ConstDef { docs, name, .. } => (docs, name, SymbolKind::Constant),
+                fe::ContractStmt::EventDef { docs, name, .. } => (docs, name, SymbolKind::Event),
+                fe::ContractStmt::ErrorDef { docs, name, .. } => (docs, name, SymbolKind::Error),
+                fe::ContractStmt::FuncDef { docs, name, .. } => (docs, name, SymbolKind::Function),
+                fe::ContractStmt::Error(_) => continue,
+            };
+            self.define_member(container, name, kind, stmt.span, docs);
+        }
+    }
+
+    fn module_stmt(&mut self, stmt: &fe::ModuleStmt<'a>) {
+        match stmt {
+            fe::ModuleStmt::TypeDef { typ, .. } => self.type_desc(typ),
+            fe::ModuleStmt::ConstDef { typ, value, .. } => {
+                self.type_desc(typ);
+                self.expr(&value.node, value.span);
+            }
+            fe::ModuleStmt::ContractDef { name, mixins, .. } => {
+                for mixin in mixins.iter() {
+                    let id = self.globals.get(mixin.node).copied();
+                    self.reference(id, mixin.span);
+                }
+                self.contract_body(stmt, name.node);
+            }
+            fe::ModuleStmt::MixinDef { name, .. } | fe::ModuleStmt::LibraryDef { name, .. } => {
+                self.contract_body(stmt, name.node)
+            }
+            fe::ModuleStmt::TraitDef { body, .. } | fe::ModuleStmt::InterfaceDef { body, .. } => {
+                for sig in body.iter() {
+                    for arg in sig.node.args.iter() {
+                        self.type_desc(&arg.node.typ);
+                    }
+                    if let Some(return_type) = &sig.node.return_type {
+                        self.type_desc(return_type);
+                    }
+                }
+            }
+            fe::ModuleStmt::StructDef { body, .. } => {
+                for field in body.iter() {
+                    let fe::StructStmt::StructField { typ, .. } = &field.node;
+                    self.type_desc(typ);
+                }
+            }
+            fe::ModuleStmt::FuncDef { def } => {
+                self.container = None;
+                self.contract_stmt(def);
+            }
+            _ => {}
+        }
+    }
+
+    fn contract_body(&mut self, stmt: &fe::ModuleStmt<'a>, name: &'a str) {
+        let body = match stmt {
+            fe::ModuleStmt::ContractDef { body, .. }
+            | fe::ModuleStmt::MixinDef { body, .. }
+            | fe::ModuleStmt::LibraryDef { body, .. } => body,
+            _ => return,
+        };
+
+        self.container = Some(name);
+        for stmt in body.iter() {
+            self.contract_stmt(stmt);
+        }
+        self.container = None;
+    }
+
+    fn contract_stmt(&mut self, stmt: &Spanned<fe::ContractStmt<'a>>) {
+        match &stmt.node {
+            fe::ContractStmt::ContractField { typ, .. } => self.type_desc(typ),
+            fe::ContractStmt::ConstDef { typ, value, .. } => {
+                self.type_desc(typ);
+                self.expr(&value.node, value.span);
+            }
+            fe::ContractStmt::EventDef { fields, .. } => {
+                for field in fields.iter() {
+                    self.type_desc(&field.node.typ);
+                }
+            }
+            fe::ContractStmt::ErrorDef { fields, .. } => {
+                for field in fields.iter() {
+                    self.type_desc(&field.node.typ);
+                }
+            }
+            fe::ContractStmt::FuncDef {
+                name,
+                args,
+                return_type,
+                body,
+                ..
+            } => {
+                self.function = match self.container {
+                    Some(container) => format!("{}.{}", container, name.node),
+                    None => name.node.to_string(),
+                };
+                self.scopes.push(HashMap::new());
+                for arg in args.iter() {
+                    self.type_desc(&arg.node.typ);
+                    self.define_local(
+                        arg.node.name.node,
+                        SymbolKind::Parameter,
+                        arg.node.name.span,
+                        arg.span,
+                    );
+                }
+                if let Some(return_type) = return_type {
+                    self.type_desc(return_type);
+                }
+                self.block(body);
+                self.scopes.pop();
+            }
+            fe::ContractStmt::Error(_) => {}
+        }
+    }
+
+    fn block(&mut self, body: &[Spanned<fe::FuncStmt<'a>>]) {
+        self.scopes.push(HashMap::new());
+        for stmt in body.iter() {
+            self.func_stmt(stmt);
+        }
+        self.scopes.pop();
+    }
+
+    fn func_stmt(&mut self, stmt: &Spanned<fe::FuncStmt<'a>>) {
+        match &stmt.node {
+            fe::FuncStmt::VarDecl { target, typ, value } => {
+                self.type_desc(typ);
+                if let Some(value) = value {
+                    self.expr(&value.node, value.span);
+                }
+                self.declare(target, stmt.span);
+            }
+            fe::FuncStmt::For {
+                target,
+                iter,
+                body,
+                or_else,
+            } => {
+                self.expr(&iter.node, iter.span);
+                self.scopes.push(HashMap::new());
+                self.declare(target, stmt.span);
+                self.block(body);
+                self.scopes.pop();
+                self.block(or_else);
+            }
+            fe::FuncStmt::While {
+                test,
+                body,
+                or_else,
+            }
+            | fe::FuncStmt::If {
+                test,
+                body,
+                or_else,
+            } => {
+                self.expr(&test.node, test.span);
+                self.block(body);
+                self.block(or_else);
+            }
+            fe::FuncStmt::Match { value, arms } => {
+                self.expr(&value.node, value.span);
+                for arm in arms.iter() {
+                    self.expr(&arm.node.pattern.node, arm.node.pattern.span);
+                    self.block(&arm.node.body);
+                }
+            }
+            fe::FuncStmt::Assign { targets, value } => {
+                for target in targets.iter() {
+                    self.expr(&target.node, target.span);
+                    self.assign(&target.node);
+                }
+                self.expr(&value.node, value.span);
+            }
+            fe::FuncStmt::AugAssign { target, value, .. } => {
+                self.expr(&target.node, target.span);
+                self.assign(&target.node);
+                self.expr(&value.node, value.span);
+            }
+            fe::FuncStmt::Assert { test, msg } => {
+                self.expr(&test.node, test.span);
+                if let Some(msg) = msg {
+                    self.expr(&msg.node, msg.span);
+                }
+            }
+            fe::FuncStmt::Return { value: Some(value) }
+            | fe::FuncStmt::Emit { value }
+            | fe::FuncStmt::Revert { error: Some(value) } => self.expr(&value.node, value.span),
+            fe::FuncStmt::Expr { value } => self.expr(value, stmt.span),
+            _ => {}
+        }
+    }
+
+    /// Defines the names of the target of a declaration as local variables.
+    fn declare(&mut self, target: &Spanned<fe::Expr<'a>>, definition: Span) {
+        match &target.node {
+            fe::Expr::Name(name) => {
+                self.define_local(*name, SymbolKind::Variable, target.span, definition)
+            }
+            fe::Expr::Tuple { elts } => {
+                for elt in elts.iter() {
+                    self.declare(elt, definition);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Marks the variable, parameter or storage field that the target of an
+    /// assignment changes, e.g. `self.balances` for `self.balances[to]`.
+    fn assign(&mut self, target: &fe::Expr<'a>) {
+        let id = match target {
+            fe::Expr::Name(name) => self.resolve(name),
+            fe::Expr::Attribute { value, attr } if value.node == fe::Expr::Name("self") => {
+                self.member(self.container.unwrap_or_default(), attr.node)
+            }
+            fe::Expr::Attribute { value, .. } | fe::Expr::Subscript { value, .. } => {
+                return self.assign(&value.node)
+            }
+            fe::Expr::Tuple { elts } => {
+                for elt in elts.iter() {
+                    self.assign(&elt.node);
+                }
+                return;
+            }
+            _ => None,
+        };
+        if let Some(id) = id {
+            self.entries[id].assigned = true;
+        }
+    }
+
+    /// The symbol that a name refers to in the current scope.
+    fn resolve(&self, name: &str) -> Option<usize> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).copied())
+            .or_else(|| self.member(self.container?, name))
+            .or_else(|| self.globals.get(name).copied())
+    }
+
+    /// A member of a definition, or of the mixins of a contract.
+    fn member(&self, container: &str, name: &str) -> Option<usize> {
+        self.members
+            .get(container)
+            .and_then(|members| members.get(name).copied())
+            .or_else(|| {
+                self.mixins.get(container)?.iter().find_map(|mixin| {
+                    self.members
+                        .get(mixin)
+                        .and_then(|members| members.get(name).copied())
+                })
+            })
+    }
+
+    /// Indexes the expression, whose span is given separately for the
+    /// expressions that the AST doesn't span.
+    fn expr(&mut self, expr: &fe::Expr<'a>, span: Span) {
+        match expr {
+            fe::Expr::Name(name) => {
+                let id = self.resolve(name);
+                self.reference(id, span);
+            }
+            fe::Expr::Attribute { value, attr } => {
+                let container = match value.node {
+                    fe::Expr::Name("self") => self.container,
+                    fe::Expr::Name(name) if self.globals.contains_key(name) => Some(name),
+                    _ => None,
+                };
+                if let Some(container) = container {
+                    let id = self.member(container, attr.node);
+                    self.reference(id, attr.span);
+                }
+                self.expr(&value.node, value.span);
+            }
+            fe::Expr::Ternary {
+                if_expr,
+                test,
+                else_expr,
+            } => {
+                self.expr(&if_expr.node, if_expr.span);
+                self.expr(&test.node, test.span);
+                self.expr(&else_expr.node, else_expr.span);
+            }
+            fe::Expr::BoolOperation { left, right, .. }
+            | fe::Expr::BinOperation { left, right, .. }
+            | fe::Expr::CompOperation { left, right, .. } => {
+                self.expr(&left.node, left.span);
+                self.expr(&right.node, right.span);
+            }
+            fe::Expr::UnaryOperation { operand, .. } => self.expr(&operand.node, operand.span),
+            fe::Expr::Subscript { value, slices } => {
+                self.expr(&value.node, value.span);
+                for slice in slices.node.iter() {
+                    match &slice.node {
+                        fe::Slice::Index(index) => self.expr(index, slice.span),
+                        fe::Slice::Slice { lower, upper, step } => {
+                            for bound in lower.iter().chain(upper.iter()).chain(step.iter()) {
+                                self.expr(&bound.node, bound.span)
+                            }
+                        }
+                    }
+                }
+            }
+            fe::Expr::Call { func, args } => {
+                self.expr(&func.node, func.span);
+                for arg in args.node.iter() {
+                    match &arg.node {
+                        fe::CallArg::Arg(value) => self.expr(value, arg.span),
+                        fe::CallArg::Kwarg(kwarg) => self.expr(&kwarg.value.node, kwarg.value.span),
+                    }
+                }
+            }
+            fe::Expr::List { elts } | fe::Expr::Tuple { elts } => {
+                for elt in elts.iter() {
+                    self.expr(&elt.node, elt.span);
+                }
+            }
+            fe::Expr::ListComp { elt, comps } => {
+                self.scopes.push(HashMap::new());
+                for comp in comps.iter() {
+                    self.expr(&comp.node.iter.node, comp.node.iter.span);
+                    self.declare(&comp.node.target, comp.span);
+                    for test in comp.node.ifs.iter() {
+                        self.expr(&test.node, test.span);
+                    }
+                }
+                self.expr(&elt.node, elt.span);
+                self.scopes.pop();
+            }
+            fe::Expr::Bool(_) | fe::Expr::Num(_) | fe::Expr::Str(_) | fe::Expr::Ellipsis => {}
+        }
+    }
+
+    fn type_desc(&mut self, typ: &Spanned<fe::TypeDesc<'a>>) {
+        match &typ.node {
+            fe::TypeDesc::Base { base } => {
+                let id = self.globals.get(base).copied();
+                self.reference(id, typ.span);
+            }
+            fe::TypeDesc::Generic { base, args } => {
+                let id = self.globals.get(base).copied();
+                let start = typ.span.start;
+                self.reference(id, Span::new(start, start + base.len()));
+                for arg in args.iter() {
+                    self.type_desc(arg);
+                }
+            }
+            fe::TypeDesc::Array { typ, .. } => self.type_desc(typ),
+            fe::TypeDesc::Map { from, to } => {
+                self.type_desc(from);
+                self.type_desc(to);
+            }
+            fe::TypeDesc::Tuple { items } => {
+                for item in items.iter() {
+                    self.type_desc(item);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::files::SourceTree;
+    use crate::index::{
+        build,
+        Position,
+        SymbolKind,
+    };
+
+    const SRC: &str = "\
+struct Pair:
+    a: u256
+
+contract Token:
+    /// The balance of each owner.
+    balances: Map<address, u256>
+
+    pub def transfer(to: address, value: u256):
+        pair: Pair = Pair(a=value)
+        self.balances[to] += pair.a
+        self.balances[msg.sender] -= value
+";
+
+    #[test]
+    fn symbols() {
+        let tree = SourceTree::new(vec![("token.fe".to_string(), SRC.to_string())]);
+        let index = build(&tree).unwrap();
+
+        let symbol = |name: &str| {
+            index
+                .symbols
+                .iter()
+                .find(|symbol| symbol.name == name)
+                .unwrap_or_else(|| panic!("no symbol named {}", name))
+        };
+
+        let balances = symbol("Token.balances");
+        assert_eq!(balances.kind, SymbolKind::StorageField);
+        assert!(balances.assigned);
+        assert_eq!(balances.signature, "balances: Map<address, u256>");
+        assert_eq!(balances.docs, "The balance of each owner.");
+        assert_eq!(balances.references.len(), 2);
+        assert_eq!(
+            balances.references[0].start,
+            Position {
+                line: 9,
+                character: 13
+            }
+        );
+
+        assert_eq!(symbol("Pair").references.len(), 2);
+        assert_eq!(symbol("Token.transfer.to").references.len(), 1);
+        assert_eq!(symbol("Token.transfer.value").references.len(), 2);
+        assert_eq!(symbol("Token.transfer.pair").kind, SymbolKind::Variable);
+        assert!(!symbol("Token.transfer.pair").assigned);
+        assert_eq!(
+            symbol("Token.transfer").signature,
+            "pub def transfer(to: address, value: u256)"
+        );
+    }
+}
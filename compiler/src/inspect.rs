@@ -0,0 +1,300 @@
+//! Summaries of the interfaces, storage layouts and bytecode sizes of
+//! contracts, which are built from a source file or from the artifacts of an
+//! earlier compilation.
+
+use crate::abi::utils as abi_utils;
+use crate::build_info;
+use crate::cfg;
+use crate::errors::{
+    CompileError,
+    Diagnostic,
+};
+use crate::proxy::{
+    self,
+    StorageSlot,
+};
+use crate::testing;
+use crate::types::CompiledModule;
+use fe_parser::ast as fe;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// A function, event or error of a contract.
+#[derive(Debug, PartialEq)]
+pub struct Entry {
+    /// The canonical signature, e.g. `transfer(address,uint256)`.
+    pub signature: String,
+    /// The selector of a function or error, or the topic of an event.
+    pub hash: String,
+    /// The state mutability of a function, or the indexed fields of an event.
+    pub details: String,
+}
+
+/// The summary of a single contract.
+#[derive(Debug, Default, PartialEq)]
+pub struct ContractSummary {
+    pub functions: Vec<Entry>,
+    pub events: Vec<Entry>,
+    pub errors: Vec<Entry>,
+    /// Empty if the summary was built from an ABI, which doesn't contain the
+    /// storage layout.
+    pub storage_layout: Vec<StorageSlot>,
+    /// The creation and runtime bytecode sizes in bytes, if the bytecode was
+    /// available.
+    pub bytecode_sizes: Option<(usize, usize)>,
+}
+
+/// The summaries of all contracts of a build.
+#[derive(Debug, Default, PartialEq)]
+pub struct Inspection {
+    /// Information about the build itself, e.g. the compiler version.
+    pub metadata: Vec<(String, String)>,
+    pub contracts: BTreeMap<String, ContractSummary>,
+}
+
+/// Compiles a source file and summarizes its contracts.
+pub fn from_src(src: &str, with_bytecode: bool) -> Result<Inspection, CompileError> {
+    let module = crate::compile(src, with_bytecode, false)?;
+
+    let mut inspection = Inspection {
+        metadata: vec![(
+            "compiler".to_string(),
+            format!("fe {}", env!("CARGO_PKG_VERSION")),
+        )],
+        contracts: summarize_module(&module)?,
+    };
+    add_storage_layouts(&mut inspection, src)?;
+
+    Ok(inspection)
+}
+
+/// Summarizes the contracts of a build info file or of a JSON ABI file, in
+/// which case the contract is called `name`.
+pub fn from_artifact(name: &str, json: &str) -> Result<Inspection, CompileError> {
+    let artifact: Value = serde_json::from_str(json)?;
+    if artifact.get("_format").is_some() {
+        return from_build_info(&artifact);
+    }
+
+    let mut inspection = Inspection::default();
+    inspection
+        .contracts
+        .insert(name.to_string(), summarize_abi(&artifact)?);
+
+    Ok(inspection)
+}
+
+/// Summarizes the contracts of a build info file, whose sources are analyzed
+/// again to find the storage layouts.
+fn from_build_info(info: &Value) -> Result<Inspection, CompileError> {
+    if info["_format"] != build_info::FORMAT {
+        return Err(CompileError::static_str("unsupported build info format"));
+    }
+
+    let mut inspection = Inspection::default();
+    let metadata = [
+        (
+            "compiler",
+            info["feVersion"].as_str().map(|v| format!("fe {}", v)),
+        ),
+        ("build id", info["id"].as_str().map(str::to_string)),
+        (
+            "optimize",
+            info["input"]["settings"]["optimize"]
+                .as_bool()
+                .map(|optimize| optimize.to_string()),
+        ),
+    ];
+    for (key, value) in metadata.iter() {
+        if let Some(value) = value {
+            inspection.metadata.push((key.to_string(), value.clone()));
+        }
+    }
+
+    let sources = info["output"]["contracts"]
+        .as_object()
+        .into_iter()
+        .flatten();
+    for (path, contracts) in sources {
+        inspection
+            .metadata
+            .push(("source".to_string(), path.clone()));
+
+        for (name, contract) in contracts.as_object().into_iter().flatten() {
+            let mut summary = summarize_abi(&contract["abi"])?;
+            if let (Some(creation), Some(runtime)) = (
+                contract["bytecode"].as_str(),
+                contract["runtimeBytecode"].as_str(),
+            ) {
+                summary.bytecode_sizes = Some((hex_size(creation), hex_size(runtime)));
+            }
+            inspection.contracts.insert(name.clone(), summary);
+        }
+
+        if let Some(src) = info["input"]["sources"][path]["content"].as_str() {
+            add_storage_layouts(&mut inspection, src)?;
+        }
+    }
+
+    Ok(inspection)
+}
+
+fn summarize_module(
+    module: &CompiledModule,
+) -> Result<BTreeMap<String, ContractSummary>, CompileError> {
+    let mut contracts = BTreeMap::new();
+    for (name, contract) in module.contracts.iter() {
+        #[allow(unused_mut)]
+        let mut summary = summarize_abi(&serde_json::from_str(&contract.json_abi)?)?;
+        #[cfg(feature = "solc-backend")]
+        if !contract.bytecode.is_empty() {
+            summary.bytecode_sizes = Some((
+                hex_size(&contract.bytecode),
+                hex_size(&contract.runtime_bytecode),
+            ));
+        }
+        contracts.insert(name.clone(), summary);
+    }
+
+    Ok(contracts)
+}
+
+fn summarize_abi(abi: &Value) -> Result<ContractSummary, CompileError> {
+    let entries = abi
+        .as_array()
+        .ok_or_else(|| CompileError::static_str("the ABI is not an array"))?;
+
+    let mut summary = ContractSummary::default();
+    for entry in entries {
+        let name = entry["name"].as_str().unwrap_or_default();
+        let inputs = entry["inputs"].as_array().map(Vec::as_slice).unwrap_or(&[]);
+        let types = inputs.iter().map(canonical_type).collect::<Vec<_>>();
+        let signature = format!("{}({})", name, types.join(","));
+
+        match entry["type"].as_str() {
+            Some("function") => summary.functions.push(Entry {
+                signature,
+                hash: abi_utils::func_selector(name, types),
+                details: entry["stateMutability"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            }),
+            Some("event") => {
+                let indexed = inputs
+                    .iter()
+                    .filter(|input| input["indexed"] == true)
+                    .filter_map(|input| input["name"].as_str())
+                    .collect::<Vec<_>>();
+                summary.events.push(Entry {
+                    signature,
+                    hash: abi_utils::event_topic(name, types),
+                    details: indexed.join(", "),
+                })
+            }
+            Some("error") => summary.errors.push(Entry {
+                signature,
+                hash: abi_utils::func_selector(name, types),
+                details: String::new(),
+            }),
+            _ => {}
+        }
+    }
+
+    Ok(summary)
+}
+
+/// The type of an ABI input as it appears in signatures, where tuples are
+/// written as their component types in parentheses.
+fn canonical_type(input: &Value) -> String {
+    let typ = input["type"].as_str().unwrap_or_default();
+    if typ.starts_with("tuple") {
+        let components = input["components"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+            .iter()
+            .map(canonical_type)
+            .collect::<Vec<_>>();
+        format!("({}){}", components.join(","), &typ["tuple".len()..])
+    } else {
+        typ.to_string()
+    }
+}
+
+/// Analyzes the source to add the storage layout of each contract.
+fn add_storage_layouts(inspection: &mut Inspection, src: &str) -> Result<(), CompileError> {
+    let tokens = fe_parser::get_parse_tokens(src)?;
+    let mut module = crate::parse(&tokens)?;
+    cfg::strip(&mut module, &cfg::Config::default())?;
+    testing::strip_tests(&mut module);
+    let context = fe_analyzer::analyze(&module).map_err(|errors| {
+        CompileError::from_diagnostics(errors.iter().map(Diagnostic::from_semantic_error).collect())
+    })?;
+
+    for stmt in module.body.iter() {
+        if let (fe::ModuleStmt::ContractDef { name, .. }, Some(attributes)) =
+            (&stmt.node, context.get_contract(stmt))
+        {
+            if let Some(summary) = inspection.contracts.get_mut(name.node) {
+                summary.storage_layout =
+                    attributes.storage_layout.iter().map(proxy::slot).collect();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The number of bytes of hex encoded bytecode.
+fn hex_size(bytecode: &str) -> usize {
+    bytecode.trim_start_matches("0x").len() / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::inspect::from_src;
+
+    const SRC: &str = "\
+contract Foo:
+    owner: address
+    balances: Map<address, u256>
+
+    event Transfer:
+        idx to: address
+        value: u256
+
+    pub def transfer(to: address, value: u256) -> bool:
+        emit Transfer(to, value)
+        return true
+
+    pub def get_owner() -> address:
+        return self.owner
+";
+
+    #[test]
+    fn inspect_src() {
+        let inspection = from_src(SRC, false).unwrap();
+        let foo = &inspection.contracts["Foo"];
+
+        let functions = foo
+            .functions
+            .iter()
+            .map(|entry| (entry.signature.as_str(), entry.hash.as_str()))
+            .collect::<Vec<_>>();
+        assert!(functions.contains(&("transfer(address,uint256)", "0xa9059cbb")));
+        assert!(functions.contains(&("get_owner()", "0x0ac298dc")));
+
+        assert_eq!(foo.events.len(), 1);
+        assert_eq!(foo.events[0].signature, "Transfer(address,uint256)");
+        assert_eq!(foo.events[0].details, "to");
+
+        let layout = foo
+            .storage_layout
+            .iter()
+            .map(|slot| (slot.name.as_str(), slot.nonce))
+            .collect::<Vec<_>>();
+        assert_eq!(layout, vec![("owner", 0), ("balances", 1)]);
+        assert_eq!(foo.bytecode_sizes, None);
+    }
+}
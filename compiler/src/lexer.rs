@@ -0,0 +1,178 @@
+//! A token stream for tools that only need the tokens of a source, e.g.
+//! syntax highlighters.
+
+use fe_parser::span::Span;
+use fe_parser::tokenizer::{
+    tokenize_partial,
+    Token,
+    TokenType,
+    TokenizeError,
+};
+
+/// Names that have a special meaning in Fe.
+pub const KEYWORDS: &[&str] = &[
+    "and",
+    "as",
+    "assert",
+    "break",
+    "case",
+    "const",
+    "continue",
+    "contract",
+    "def",
+    "elif",
+    "else",
+    "emit",
+    "enum",
+    "error",
+    "event",
+    "false",
+    "for",
+    "from",
+    "idx",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "interface",
+    "is",
+    "library",
+    "map",
+    "match",
+    "mixin",
+    "not",
+    "or",
+    "pass",
+    "pub",
+    "return",
+    "revert",
+    "struct",
+    "trait",
+    "true",
+    "type",
+    "uses",
+    "while",
+];
+
+/// What a token represents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    Keyword,
+    Name,
+    Number,
+    String,
+    /// An operator or delimiter, e.g. `+` or `(`.
+    Operator,
+    Comment,
+    DocComment,
+    /// The end of a statement.
+    Newline,
+    Indent,
+    Dedent,
+    EndOfFile,
+    /// Text that isn't part of any token.
+    Unknown,
+}
+
+/// A token of the source.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LexToken<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    pub span: Span,
+}
+
+/// An iterator over the tokens of a source.
+///
+/// Whitespace newlines, e.g. on empty lines or within brackets, are skipped.
+/// If the source can't be tokenized entirely, the tokens preceding the error
+/// are followed by the error.
+pub struct Tokens<'a> {
+    tokens: std::vec::IntoIter<Token<'a>>,
+    error: Option<TokenizeError>,
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Result<LexToken<'a>, TokenizeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for token in self.tokens.by_ref() {
+            let kind = match token.typ {
+                TokenType::NAME if KEYWORDS.contains(&token.string) => TokenKind::Keyword,
+                TokenType::NAME => TokenKind::Name,
+                TokenType::NUMBER => TokenKind::Number,
+                TokenType::STRING => TokenKind::String,
+                TokenType::OP => TokenKind::Operator,
+                TokenType::COMMENT => TokenKind::Comment,
+                TokenType::DOCCOMMENT => TokenKind::DocComment,
+                TokenType::NEWLINE => TokenKind::Newline,
+                TokenType::NL => continue,
+                TokenType::INDENT => TokenKind::Indent,
+                TokenType::DEDENT => TokenKind::Dedent,
+                TokenType::ENDMARKER => TokenKind::EndOfFile,
+                TokenType::ERRORTOKEN => TokenKind::Unknown,
+            };
+
+            return Some(Ok(LexToken {
+                kind,
+                text: token.string,
+                span: token.span,
+            }));
+        }
+
+        self.error.take().map(Err)
+    }
+}
+
+/// Returns the tokens of the source.
+pub fn lex(src: &str) -> Tokens {
+    let (tokens, error) = tokenize_partial(src);
+
+    Tokens {
+        tokens: tokens.into_iter(),
+        error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexer::{
+        lex,
+        TokenKind,
+    };
+
+    #[test]
+    fn kinds() {
+        let kinds = lex("contract Foo: # bar\n  x: u256 = 1\n")
+            .map(|token| token.expect("unable to lex").kind)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Keyword,
+                TokenKind::Name,
+                TokenKind::Operator,
+                TokenKind::Comment,
+                TokenKind::Newline,
+                TokenKind::Indent,
+                TokenKind::Name,
+                TokenKind::Operator,
+                TokenKind::Name,
+                TokenKind::Operator,
+                TokenKind::Number,
+                TokenKind::Newline,
+                TokenKind::Dedent,
+                TokenKind::EndOfFile,
+            ]
+        );
+    }
+
+    #[test]
+    fn error_after_tokens() {
+        let tokens = lex("x = (1").collect::<Vec<_>>();
+
+        assert!(tokens[0].is_ok());
+        assert!(tokens.last().unwrap().is_err());
+    }
+}
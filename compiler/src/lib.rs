@@ -1,53 +1,268 @@
 //! Modules for compiling Fe and building ABIs.
 
-use crate::errors::CompileError;
+use crate::errors::{
+    CompileError,
+    Diagnostic,
+};
+use crate::passes::Pass;
 use crate::types::{
     CompiledContract,
     CompiledModule,
+    CompiledTests,
     FeSrc,
     NamedContracts,
+    Progress,
+};
+use fe_analyzer::lints::{
+    LintPack,
+    LintParams,
 };
+use fe_parser::ast as fe;
+use fe_parser::tokenizer::Token;
+use std::sync::Arc;
 
 pub mod abi;
+pub mod artifacts;
+mod build_info;
+pub mod cfg;
+pub mod disasm;
+pub mod driver;
+pub mod eip712;
 pub mod errors;
 #[cfg(feature = "solc-backend")]
 pub mod evm;
+pub mod files;
+pub mod fix;
+pub mod flatten;
+pub mod graph;
+pub mod index;
+pub mod inspect;
+pub mod lexer;
+pub mod metadata;
+pub mod passes;
+pub mod proxy;
+pub mod semantic_tokens;
+pub mod smt;
+pub mod testing;
 pub mod types;
 pub mod yul;
 
+pub use driver::Compiler;
+pub use fe_analyzer::lints;
+
 /// Compiles the given Fe source code to all targets.
 ///
 /// If `with_bytecode` is set to false, the compiler will skip the final Yul ->
 /// Bytecode pass. This is useful when debugging invalid Yul code.
+///
+/// Test functions are not part of the compiled contracts.
 pub fn compile(
     src: FeSrc,
-    _with_bytecode: bool,
-    _optimize: bool,
+    with_bytecode: bool,
+    optimize: bool,
+) -> Result<CompiledModule, CompileError> {
+    compile_with_progress(src, with_bytecode, optimize, &|_| {})
+}
+
+/// Compiles the given Fe source code like `compile`, reporting each phase of
+/// the compilation to `progress` as it starts.
+pub fn compile_with_progress(
+    src: FeSrc,
+    with_bytecode: bool,
+    optimize: bool,
+    progress: &dyn Fn(Progress),
+) -> Result<CompiledModule, CompileError> {
+    compile_with_extensions(
+        src,
+        with_bytecode,
+        optimize,
+        &cfg::Config::default(),
+        &[],
+        &[],
+        &LintParams::new(),
+        progress,
+    )
+}
+
+/// Compiles the given Fe source code like `compile_with_progress`, leaving
+/// out the definitions whose `cfg` conditions don't hold, running the passes
+/// on the analyzed module and on the Yul of its contracts, and the lint packs
+/// alongside the built-in lints, which are configured with `lint_params`.
+#[allow(clippy::too_many_arguments)]
+pub fn compile_with_extensions(
+    src: FeSrc,
+    with_bytecode: bool,
+    optimize: bool,
+    config: &cfg::Config,
+    passes: &[Arc<dyn Pass>],
+    lint_packs: &[Arc<dyn LintPack>],
+    lint_params: &LintParams,
+    progress: &dyn Fn(Progress),
 ) -> Result<CompiledModule, CompileError> {
-    // parse source
+    progress(Progress::Parsing);
+    let fe_tokens = fe_parser::get_parse_tokens(src)?;
+    let mut fe_module = parse(&fe_tokens)?;
+    cfg::strip(&mut fe_module, config)?;
+    testing::strip_tests(&mut fe_module);
+
+    compile_module(
+        &fe_tokens,
+        fe_module,
+        with_bytecode,
+        optimize,
+        passes,
+        lint_packs,
+        lint_params,
+        progress,
+    )
+}
+
+/// Compiles the given Fe source code with all test functions made public, so
+/// that each contract can be deployed as a harness that runs its tests.
+pub fn compile_tests(src: FeSrc, optimize: bool) -> Result<CompiledTests, CompileError> {
+    compile_tests_with_config(src, optimize, &cfg::Config::default())
+}
+
+/// Compiles the given Fe source code like `compile_tests`, with the features
+/// of `config` enabled. Definitions marked with `@cfg(test)` are always
+/// compiled.
+pub fn compile_tests_with_config(
+    src: FeSrc,
+    optimize: bool,
+    config: &cfg::Config,
+) -> Result<CompiledTests, CompileError> {
+    let fe_tokens = fe_parser::get_parse_tokens(src)?;
+    let mut fe_module = parse(&fe_tokens)?;
+    cfg::strip(
+        &mut fe_module,
+        &cfg::Config {
+            test: true,
+            ..config.clone()
+        },
+    )?;
+    let tests = testing::expose_tests(&mut fe_module);
+
+    Ok(CompiledTests {
+        module: compile_module(
+            &fe_tokens,
+            fe_module,
+            true,
+            optimize,
+            &[],
+            &[],
+            &LintParams::new(),
+            &|_| {},
+        )?,
+        tests,
+    })
+}
+
+/// Tokenizes and parses the given Fe source code without analyzing or
+/// compiling it, which reports all syntax errors.
+pub fn check_syntax(src: FeSrc) -> Result<(), CompileError> {
     let fe_tokens = fe_parser::get_parse_tokens(src)?;
-    let fe_module = fe_parser::parsers::file_input(&fe_tokens[..])
-        .map_err(|error| CompileError::str(&error.format_user(src)))?
-        .1
-        .node;
+    parse(&fe_tokens).map(|_| ())
+}
+
+/// Parses the tokens of a module. The parser recovers from syntax errors, so
+/// that all of them are reported.
+fn parse<'a>(fe_tokens: &'a [Token<'a>]) -> Result<fe::Module<'a>, CompileError> {
+    let (module, errors) = fe_parser::parsers::file_input_with_errors(fe_tokens);
+    if !errors.is_empty() {
+        return Err(CompileError::from_diagnostics(
+            errors.iter().map(Diagnostic::from_syntax_error).collect(),
+        ));
+    }
 
+    Ok(module.node)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn compile_module(
+    fe_tokens: &[Token],
+    fe_module: fe::Module,
+    _with_bytecode: bool,
+    _optimize: bool,
+    passes: &[Arc<dyn Pass>],
+    lint_packs: &[Arc<dyn LintPack>],
+    lint_params: &LintParams,
+    progress: &dyn Fn(Progress),
+) -> Result<CompiledModule, CompileError> {
     // build abi
     let json_abis = abi::build(&fe_module)?;
 
     // analyze source code
-    let context = fe_analyzer::analyze(&fe_module)
-        .map_err(|error| CompileError::str(&error.format_user(src)))?;
+    progress(Progress::Analyzing);
+    let context = fe_analyzer::analyze(&fe_module).map_err(|errors| {
+        CompileError::from_diagnostics(errors.iter().map(Diagnostic::from_semantic_error).collect())
+    })?;
+
+    // check for likely mistakes
+    let mut warnings = fe_analyzer::lints::check_with(&fe_module, lint_params);
+    if !lint_packs.is_empty() {
+        for pack in lint_packs {
+            warnings.extend(pack.check(&fe_module, &context));
+        }
+        warnings.sort_by_key(|warning| warning.span.start);
+    }
+
+    // build the schemas of typed data structs
+    let eip712 = eip712::build(&context, &fe_module)?;
+
+    // describe the implementations of upgradeable proxies
+    let proxy = proxy::build(&context, &fe_module)?;
+
+    // run the checks of external passes
+    let mut diagnostics = vec![];
+    for pass in passes {
+        diagnostics.extend(pass.check(&fe_module).map_err(|error| {
+            CompileError::str(&format!("pass `{}` failed: {}", pass.name(), error))
+        })?);
+    }
+    if !diagnostics.is_empty() {
+        return Err(CompileError::from_diagnostics(diagnostics));
+    }
 
     // compile to yul
-    let yul_contracts = yul::compile(context, &fe_module)?;
+    progress(Progress::CompilingYul);
+    let mut yul_contracts = yul::compile(context, &fe_module)?;
+
+    // let external passes transform the yul, which is escaped to be embedded
+    // in the JSON input of solc
+    for pass in passes {
+        for (name, yul) in yul_contracts.iter_mut() {
+            let transformed = pass
+                .transform_yul(name, yul.replace("\\\"", "\""))
+                .map_err(|error| {
+                    CompileError::str(&format!(
+                        "pass `{}` failed on {}: {}",
+                        pass.name(),
+                        name,
+                        error
+                    ))
+                })?;
+            *yul = transformed.replace("\"", "\\\"");
+        }
+    }
 
     // compile to bytecode if required
     #[cfg(feature = "solc-backend")]
     let bytecode_contracts = if _with_bytecode {
-        match evm::compile(yul_contracts.clone(), _optimize) {
-            Err(error) => panic!("Yul compilation failed: {}", error),
-            Ok(contracts) => contracts,
+        // one contract at a time, so that the progress of a slow build shows
+        let mut names = yul_contracts.keys().collect::<Vec<_>>();
+        names.sort();
+        let mut bytecode_contracts = std::collections::HashMap::new();
+        for (index, name) in names.iter().enumerate() {
+            progress(Progress::CompilingBytecode {
+                contract: name.to_string(),
+                index: index + 1,
+                total: names.len(),
+            });
+            let bytecode =
+                evm::compile_contract_bytecode(name, yul_contracts[*name].clone(), _optimize)?;
+            bytecode_contracts.insert(name.to_string(), bytecode);
         }
+        bytecode_contracts
     } else {
         std::collections::HashMap::new()
     };
@@ -63,7 +278,13 @@ pub fn compile(
                     yul: yul_contracts[name].to_owned(),
                     #[cfg(feature = "solc-backend")]
                     bytecode: if _with_bytecode {
-                        bytecode_contracts[name].to_owned()
+                        bytecode_contracts[name].creation.to_owned()
+                    } else {
+                        "".to_string()
+                    },
+                    #[cfg(feature = "solc-backend")]
+                    runtime_bytecode: if _with_bytecode {
+                        bytecode_contracts[name].runtime.to_owned()
                     } else {
                         "".to_string()
                     },
@@ -75,6 +296,9 @@ pub fn compile(
     Ok(CompiledModule {
         fe_tokens: format!("{:#?}", fe_tokens),
         fe_ast: format!("{:#?}", fe_module),
+        eip712,
+        proxy,
         contracts,
+        warnings,
     })
 }
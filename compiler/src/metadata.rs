@@ -0,0 +1,184 @@
+//! Metadata files of contracts, which describe how they were compiled and
+//! refer to their sources by IPFS hash, so that a contract can be verified
+//! once the sources have been pinned on IPFS.
+
+use crate::types::CompiledContract;
+use serde::Serialize;
+use sha2::{
+    Digest,
+    Sha256,
+};
+use std::collections::BTreeMap;
+
+const VERSION: u32 = 1;
+
+/// The largest file that is stored in a single IPFS block. Larger files are
+/// split into chunks by IPFS, which isn't supported.
+const MAX_BLOCK_SIZE: usize = 256 * 1024;
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Metadata<'a> {
+    compiler: Compiler,
+    language: &'static str,
+    output: Output,
+    settings: Settings<'a>,
+    /// The sources by path.
+    sources: BTreeMap<&'a str, Source>,
+    version: u32,
+}
+
+#[derive(Serialize)]
+struct Compiler {
+    version: &'static str,
+}
+
+#[derive(Serialize)]
+struct Output {
+    abi: serde_json::Value,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Settings<'a> {
+    /// The name of the contract by source path.
+    compilation_target: BTreeMap<&'a str, &'a str>,
+    optimize: bool,
+}
+
+#[derive(Serialize)]
+struct Source {
+    sha256: String,
+    /// The URL of the source on IPFS, if it fits into a single block.
+    urls: Vec<String>,
+}
+
+/// Creates the metadata of the contract named `name`, in which the source is
+/// named `src_path`.
+pub fn metadata(
+    src_path: &str,
+    src: &str,
+    optimize: bool,
+    name: &str,
+    contract: &CompiledContract,
+) -> String {
+    let mut sources = BTreeMap::new();
+    sources.insert(
+        src_path,
+        Source {
+            sha256: format!("0x{:x}", Sha256::digest(src.as_bytes())),
+            urls: ipfs_hash(src.as_bytes())
+                .map(|hash| format!("dweb:/ipfs/{}", hash))
+                .into_iter()
+                .collect(),
+        },
+    );
+    let mut compilation_target = BTreeMap::new();
+    compilation_target.insert(src_path, name);
+
+    let metadata = Metadata {
+        compiler: Compiler {
+            version: env!("CARGO_PKG_VERSION"),
+        },
+        language: "Fe",
+        output: Output {
+            abi: serde_json::from_str(&contract.json_abi)
+                .expect("the compiler produced an invalid ABI"),
+        },
+        settings: Settings {
+            compilation_target,
+            optimize,
+        },
+        sources,
+        version: VERSION,
+    };
+
+    serde_json::to_string(&metadata).expect("unable to serialize metadata")
+}
+
+/// The IPFS hash (a version 0 CID) that `ipfs add` reports for a file with
+/// the given content, or `None` if IPFS would split the file into chunks.
+pub fn ipfs_hash(content: &[u8]) -> Option<String> {
+    if content.len() > MAX_BLOCK_SIZE {
+        return None;
+    }
+
+    // The file is a UnixFS node of type file, wrapped in the data field of a
+    // DAG-PB node without links.
+    let mut file = vec![0x08, 0x02];
+    if !content.is_empty() {
+        file.push(0x12);
+        file.extend(varint(content.len()));
+        file.extend_from_slice(content);
+    }
+    file.push(0x18);
+    file.extend(varint(content.len()));
+
+    let mut node = vec![0x0a];
+    node.extend(varint(file.len()));
+    node.extend(file);
+
+    // a multihash of the SHA-256 digest
+    let mut multihash = vec![0x12, 0x20];
+    multihash.extend_from_slice(&Sha256::digest(&node));
+    Some(base58(&multihash))
+}
+
+fn varint(mut value: usize) -> Vec<u8> {
+    let mut bytes = vec![];
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+    bytes
+}
+
+fn base58(bytes: &[u8]) -> String {
+    // digits of the number in base 58, least significant first
+    let mut digits: Vec<u8> = vec![];
+    for byte in bytes.iter() {
+        let mut carry = *byte as usize;
+        for digit in digits.iter_mut() {
+            carry += (*digit as usize) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+    std::iter::repeat(BASE58_ALPHABET[0])
+        .take(zeros)
+        .chain(
+            digits
+                .iter()
+                .rev()
+                .map(|digit| BASE58_ALPHABET[*digit as usize]),
+        )
+        .map(char::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::metadata::ipfs_hash;
+
+    #[test]
+    fn ipfs_hashes() {
+        assert_eq!(
+            ipfs_hash(b"").unwrap(),
+            "QmbFMke1KXqnYyBBWxB74N4c5SBnJMVAiMNRcGu6x1AwQH"
+        );
+        assert_eq!(
+            ipfs_hash(b"hello world").unwrap(),
+            "Qmf412jQZiuVUtdgnB36FXFX7xg5V6KEbSJ4dpQuhkLyfD"
+        );
+        assert!(ipfs_hash(&[0; 256 * 1024 + 1]).is_none());
+    }
+}
@@ -0,0 +1,28 @@
+//! Passes that run as part of a compilation without being built into the
+//! compiler, e.g. ones loaded from plugins.
+
+use crate::errors::Diagnostic;
+use crate::types::YulIr;
+use fe_parser::ast as fe;
+use std::fmt::Debug;
+
+/// A pass that checks the AST of a module or transforms the Yul of its
+/// contracts before they are compiled to bytecode.
+///
+/// Passes run in the order they were added, after the module has been
+/// analyzed, so they only see modules without semantic errors.
+pub trait Pass: Debug {
+    /// The name of the pass, which is the kind of the diagnostics it reports.
+    fn name(&self) -> &str;
+
+    /// Checks the module. Any diagnostics that are returned fail the
+    /// compilation.
+    fn check(&self, _module: &fe::Module) -> Result<Vec<Diagnostic>, String> {
+        Ok(vec![])
+    }
+
+    /// Transforms the Yul object of a contract.
+    fn transform_yul(&self, _contract: &str, yul: YulIr) -> Result<YulIr, String> {
+        Ok(yul)
+    }
+}
@@ -0,0 +1,363 @@
+//! The information that the admin tools of upgradeable proxies need about
+//! the contracts marked with `@upgradeable`, which are deployed as their
+//! implementations, and the checks that an upgrade to a new implementation
+//! keeps the storage of the proxy intact.
+
+use crate::abi::utils as abi_utils;
+use crate::errors::CompileError;
+use crate::yul::constants;
+use fe_analyzer::namespace::proxy;
+use fe_analyzer::namespace::types::{
+    AbiEncoding,
+    FixedSize,
+    Type,
+};
+use fe_analyzer::{
+    Context,
+    StorageField,
+};
+use fe_parser::ast as fe;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
+
+/// A field in the storage of the proxy.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct StorageSlot {
+    pub name: String,
+    pub nonce: usize,
+    /// The storage pointer of the field as a hex string. For maps, this is
+    /// the nonce that is hashed with the keys.
+    pub slot: String,
+    #[serde(rename = "type")]
+    pub typ: String,
+    /// The number of nonces that a storage gap reserves.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gap: Option<usize>,
+}
+
+/// The function that the proxy calls to initialize its state.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Initializer {
+    pub name: String,
+    pub signature: String,
+    /// The 4 byte selector as a hex string.
+    pub selector: String,
+}
+
+/// What a proxy admin tool needs to know about an implementation contract.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Implementation {
+    pub initializer: Initializer,
+    /// The storage slot of the flag that is set once the initializer has run.
+    pub initialized_slot: String,
+    pub storage_layout: Vec<StorageSlot>,
+}
+
+/// Collects the implementation information of all upgradeable contracts in a
+/// module, keyed by the name of the contract.
+pub fn implementations(context: &Context, module: &fe::Module) -> BTreeMap<String, Implementation> {
+    let mut implementations = BTreeMap::new();
+
+    for stmt in module.body.iter() {
+        if let fe::ModuleStmt::ContractDef { name, .. } = &stmt.node {
+            let attributes = context
+                .get_contract(stmt)
+                .filter(|attributes| attributes.is_upgradeable);
+            if let Some(attributes) = attributes {
+                let initializer = attributes
+                    .initializer
+                    .as_ref()
+                    .expect("upgradeable contract without initializer");
+                let params = initializer
+                    .param_types
+                    .iter()
+                    .map(|typ| typ.abi_name())
+                    .collect::<Vec<_>>();
+
+                implementations.insert(
+                    name.node.to_string(),
+                    Implementation {
+                        initializer: Initializer {
+                            name: initializer.name.clone(),
+                            signature: format!("{}({})", initializer.name, params.join(",")),
+                            selector: abi_utils::func_selector(&initializer.name, params),
+                        },
+                        initialized_slot: proxy::initialized_slot(),
+                        storage_layout: attributes.storage_layout.iter().map(slot).collect(),
+                    },
+                );
+            }
+        }
+    }
+
+    implementations
+}
+
+/// Builds the implementation information of all upgradeable contracts in a
+/// module as a JSON object.
+pub fn build(context: &Context, module: &fe::Module) -> Result<String, CompileError> {
+    serde_json::to_string_pretty(&implementations(context, module))
+        .map_err(|_| CompileError::static_str("unable to serialize the proxy information"))
+}
+
+/// Parses the implementation information that the `proxy` target emits.
+pub fn parse(json: &str) -> Result<BTreeMap<String, Implementation>, CompileError> {
+    serde_json::from_str(json)
+        .map_err(|_| CompileError::static_str("unable to parse the proxy information"))
+}
+
+/// A change to the storage layout of a contract that corrupts the state of
+/// its proxies when they are upgraded to the new implementation.
+#[derive(Debug, PartialEq)]
+pub enum LayoutChange {
+    /// The contract is no longer upgradeable or no longer exists.
+    MissingContract { contract: String },
+    /// The field no longer exists. Renamed fields are reported as removed,
+    /// since they can't be told apart from replaced ones.
+    Removed { contract: String, field: String },
+    /// The field is stored at a different nonce, e.g. because the fields were
+    /// reordered or a field was inserted before it.
+    Moved {
+        contract: String,
+        field: String,
+        old_nonce: usize,
+        new_nonce: usize,
+    },
+    /// The field has a different type, so its stored value is decoded
+    /// differently.
+    TypeChanged {
+        contract: String,
+        field: String,
+        old_type: String,
+        new_type: String,
+    },
+}
+
+impl fmt::Display for LayoutChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutChange::MissingContract { contract } => {
+                write!(f, "upgradeable contract `{}` is missing", contract)
+            }
+            LayoutChange::Removed { contract, field } => {
+                write!(f, "field `{}.{}` was removed", contract, field)
+            }
+            LayoutChange::Moved {
+                contract,
+                field,
+                old_nonce,
+                new_nonce,
+            } => write!(
+                f,
+                "field `{}.{}` moved from nonce {} to nonce {}",
+                contract, field, old_nonce, new_nonce
+            ),
+            LayoutChange::TypeChanged {
+                contract,
+                field,
+                old_type,
+                new_type,
+            } => write!(
+                f,
+                "field `{}.{}` changed type from `{}` to `{}`",
+                contract, field, old_type, new_type
+            ),
+        }
+    }
+}
+
+/// Finds the changes between the storage layouts of two builds that make it
+/// unsafe to upgrade the proxies of the old implementations to the new ones.
+///
+/// Every field of an old implementation has to keep its nonce and type. New
+/// fields may be appended or take the place of storage gaps, which may shrink
+/// for that reason.
+pub fn layout_changes(
+    old: &BTreeMap<String, Implementation>,
+    new: &BTreeMap<String, Implementation>,
+) -> Vec<LayoutChange> {
+    let mut changes = vec![];
+
+    for (contract, old_implementation) in old.iter() {
+        let new_implementation = match new.get(contract) {
+            Some(implementation) => implementation,
+            None => {
+                changes.push(LayoutChange::MissingContract {
+                    contract: contract.clone(),
+                });
+                continue;
+            }
+        };
+
+        let fields = old_implementation
+            .storage_layout
+            .iter()
+            .filter(|slot| slot.gap.is_none());
+        for old_slot in fields {
+            let new_slot = new_implementation
+                .storage_layout
+                .iter()
+                .find(|slot| slot.name == old_slot.name);
+
+            let field = old_slot.name.clone();
+            let contract = contract.clone();
+            match new_slot {
+                None => changes.push(LayoutChange::Removed { contract, field }),
+                Some(new_slot) if new_slot.nonce != old_slot.nonce => {
+                    changes.push(LayoutChange::Moved {
+                        contract,
+                        field,
+                        old_nonce: old_slot.nonce,
+                        new_nonce: new_slot.nonce,
+                    })
+                }
+                Some(new_slot) if new_slot.typ != old_slot.typ => {
+                    changes.push(LayoutChange::TypeChanged {
+                        contract,
+                        field,
+                        old_type: old_slot.typ.clone(),
+                        new_type: new_slot.typ.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    changes
+}
+
+pub(crate) fn slot(field: &StorageField) -> StorageSlot {
+    let slot = match field.typ {
+        Type::Map(_) => format!("0x{:x}", field.nonce),
+        _ => constants::storage_ptr(field.nonce),
+    };
+    let gap = Some(&field.name)
+        .filter(|name| proxy::is_storage_gap(name))
+        .and_then(|_| proxy::storage_gap_size(&field.typ));
+
+    StorageSlot {
+        name: field.name.clone(),
+        nonce: field.nonce,
+        slot,
+        typ: type_name(&field.typ),
+        gap,
+    }
+}
+
+/// The ABI name of a type. Maps are named `map<key, value>`.
+fn type_name(typ: &Type) -> String {
+    match typ {
+        Type::Map(map) => format!("map<{}, {}>", map.key.abi_name(), type_name(&map.value)),
+        _ => FixedSize::try_from(typ.clone())
+            .map(|typ| typ.abi_name())
+            .expect("storage field without ABI type"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::proxy::{
+        implementations,
+        layout_changes,
+        Implementation,
+        LayoutChange,
+    };
+    use std::collections::BTreeMap;
+
+    const SRC: &str = "\
+@upgradeable
+contract Foo:
+    owner: address
+    __gap: u256[10]
+    balances: Map<address, u256>
+
+    @initializer
+    pub def initialize(owner: address):
+        self.owner = owner
+";
+
+    fn compile(src: &str) -> BTreeMap<String, Implementation> {
+        let tokens = fe_parser::get_parse_tokens(src).unwrap();
+        let module = fe_parser::parsers::file_input(&tokens[..]).unwrap().1.node;
+        let context = fe_analyzer::analyze(&module).unwrap();
+
+        implementations(&context, &module)
+    }
+
+    #[test]
+    fn implementation() {
+        let implementations = compile(SRC);
+        let foo = &implementations["Foo"];
+        assert_eq!(foo.initializer.signature, "initialize(address)");
+        assert_eq!(foo.initializer.selector, "0xc4d66de8");
+
+        let layout = foo
+            .storage_layout
+            .iter()
+            .map(|slot| (slot.name.as_str(), slot.nonce, slot.typ.as_str(), slot.gap))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            layout,
+            vec![
+                ("owner", 0, "address", None),
+                ("__gap", 1, "uint256[10]", Some(10)),
+                ("balances", 11, "map<address, uint256>", None),
+            ]
+        );
+        assert_eq!(foo.storage_layout[2].slot, "0xb");
+    }
+
+    #[test]
+    fn compatible_upgrade() {
+        // `admin` takes the place of the first nonce of the gap
+        let new = SRC.replace("__gap: u256[10]", "admin: address\n    __gap: u256[9]");
+
+        assert_eq!(layout_changes(&compile(SRC), &compile(&new)), vec![]);
+    }
+
+    #[test]
+    fn incompatible_upgrade() {
+        let new = "\
+@upgradeable
+contract Foo:
+    owner: u256
+    balances: Map<address, u256>
+    __gap: u256[10]
+
+    @initializer
+    pub def initialize(owner: u256):
+        self.owner = owner
+";
+
+        let changes = layout_changes(&compile(SRC), &compile(new));
+        assert_eq!(
+            changes,
+            vec![
+                LayoutChange::TypeChanged {
+                    contract: "Foo".to_string(),
+                    field: "owner".to_string(),
+                    old_type: "address".to_string(),
+                    new_type: "uint256".to_string(),
+                },
+                LayoutChange::Moved {
+                    contract: "Foo".to_string(),
+                    field: "balances".to_string(),
+                    old_nonce: 11,
+                    new_nonce: 1,
+                },
+            ]
+        );
+        assert_eq!(
+            layout_changes(&compile(SRC), &BTreeMap::new()),
+            vec![LayoutChange::MissingContract {
+                contract: "Foo".to_string()
+            }]
+        );
+    }
+}
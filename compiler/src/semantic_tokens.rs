@@ -0,0 +1,257 @@
+//! Semantic tokens of the names in a source tree (`fe semantic-tokens`),
+//! which let editors highlight Fe by what names refer to rather than by
+//! regular expressions: storage fields apart from local variables, types
+//! apart from functions, and names that are assigned apart from those that
+//! aren't.
+//!
+//! Tokens are classified with the symbols of the index and can be encoded
+//! like the `semanticTokens` responses of the language server protocol,
+//! whose legend is given by `legend`.
+
+use crate::index::{
+    Index,
+    Location,
+    SymbolKind,
+};
+use serde::Serialize;
+
+/// The token types, in the order of their indices in encoded tokens. All of
+/// them are predefined by the language server protocol.
+pub const TOKEN_TYPES: &[&str] = &[
+    "namespace",
+    "class",
+    "interface",
+    "struct",
+    "enum",
+    "enumMember",
+    "type",
+    "function",
+    "method",
+    "property",
+    "event",
+    "parameter",
+    "variable",
+];
+
+/// The token modifiers, in the order of their bits in encoded tokens.
+/// `storage` and `mutable` aren't predefined by the language server protocol.
+pub const TOKEN_MODIFIERS: &[&str] = &["declaration", "readonly", "storage", "mutable"];
+
+/// The legend that a language server advertises for the encoded tokens.
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Legend {
+    pub token_types: Vec<&'static str>,
+    pub token_modifiers: Vec<&'static str>,
+}
+
+pub fn legend() -> Legend {
+    Legend {
+        token_types: TOKEN_TYPES.to_vec(),
+        token_modifiers: TOKEN_MODIFIERS.to_vec(),
+    }
+}
+
+/// A classified name, whose position is counted like in the language server
+/// protocol.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SemanticToken {
+    pub line: usize,
+    pub character: usize,
+    /// The length of the name in UTF-16 code units.
+    pub length: usize,
+    pub token_type: &'static str,
+    pub modifiers: Vec<&'static str>,
+}
+
+/// The tokens of a file in the order of their positions.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct FileTokens {
+    pub path: String,
+    pub tokens: Vec<SemanticToken>,
+}
+
+impl FileTokens {
+    /// Encodes the tokens as the `data` of a language server protocol
+    /// response: five integers per token, which are the line and start
+    /// character relative to the previous token, the length, the index of
+    /// the type and the bits of the modifiers.
+    pub fn encode(&self) -> Vec<u32> {
+        let mut data = vec![];
+        let (mut line, mut character) = (0, 0);
+        for token in self.tokens.iter() {
+            if token.line != line {
+                character = 0;
+            }
+            let token_type = TOKEN_TYPES
+                .iter()
+                .position(|typ| *typ == token.token_type)
+                .expect("unknown token type");
+            let modifiers = token.modifiers.iter().fold(0, |bits, modifier| {
+                let bit = TOKEN_MODIFIERS
+                    .iter()
+                    .position(|known| known == modifier)
+                    .expect("unknown token modifier");
+                bits | 1 << bit
+            });
+
+            data.extend_from_slice(&[
+                (token.line - line) as u32,
+                (token.character - character) as u32,
+                token.length as u32,
+                token_type as u32,
+                modifiers,
+            ]);
+            line = token.line;
+            character = token.character;
+        }
+
+        data
+    }
+}
+
+/// The tokens of the definitions and references of the symbols of the index,
+/// grouped by the files of the index.
+pub fn tokens(index: &Index) -> Vec<FileTokens> {
+    let mut files = index
+        .files
+        .iter()
+        .map(|path| FileTokens {
+            path: path.clone(),
+            tokens: vec![],
+        })
+        .collect::<Vec<_>>();
+
+    for symbol in index.symbols.iter() {
+        let token_type = match symbol.kind {
+            SymbolKind::Library => "namespace",
+            SymbolKind::Contract | SymbolKind::Mixin => "class",
+            SymbolKind::Interface | SymbolKind::Trait => "interface",
+            SymbolKind::Struct => "struct",
+            SymbolKind::Enum => "enum",
+            SymbolKind::Variant => "enumMember",
+            SymbolKind::Type | SymbolKind::Error => "type",
+            SymbolKind::Function if symbol.name.contains('.') => "method",
+            SymbolKind::Function => "function",
+            SymbolKind::StorageField | SymbolKind::Field => "property",
+            SymbolKind::Event => "event",
+            SymbolKind::Parameter => "parameter",
+            SymbolKind::Constant | SymbolKind::Variable => "variable",
+        };
+        let mut modifiers = vec![];
+        if symbol.kind == SymbolKind::StorageField {
+            modifiers.push("storage");
+        }
+        match symbol.kind {
+            SymbolKind::Constant => modifiers.push("readonly"),
+            SymbolKind::StorageField | SymbolKind::Parameter | SymbolKind::Variable => modifiers
+                .push(if symbol.assigned {
+                    "mutable"
+                } else {
+                    "readonly"
+                }),
+            _ => {}
+        }
+
+        let occurrences = std::iter::once((&symbol.definition, true))
+            .chain(symbol.references.iter().map(|location| (location, false)));
+        for (location, declaration) in occurrences {
+            let file = match files.iter_mut().find(|file| file.path == location.path) {
+                Some(file) => file,
+                None => continue,
+            };
+            let mut modifiers = modifiers.clone();
+            if declaration {
+                modifiers.insert(0, "declaration");
+            }
+            if let Some(length) = length(location) {
+                file.tokens.push(SemanticToken {
+                    line: location.start.line,
+                    character: location.start.character,
+                    length,
+                    token_type,
+                    modifiers,
+                });
+            }
+        }
+    }
+
+    for file in files.iter_mut() {
+        file.tokens
+            .sort_by_key(|token| (token.line, token.character));
+        file.tokens
+            .dedup_by_key(|token| (token.line, token.character));
+    }
+    files
+}
+
+/// The length of a name, which never spans lines.
+fn length(location: &Location) -> Option<usize> {
+    if location.start.line == location.end.line {
+        Some(location.end.character - location.start.character)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::files::SourceTree;
+    use crate::semantic_tokens::tokens;
+
+    #[test]
+    fn classified_tokens() {
+        let src = "\
+contract Counter:
+    count: u256
+
+    pub def add(by: u256):
+        total: u256 = self.count + by
+        self.count = total
+";
+        let tree = SourceTree::new(vec![("counter.fe".to_string(), src.to_string())]);
+        let index = crate::index::build(&tree).unwrap();
+        let files = tokens(&index);
+
+        let classified = files[0]
+            .tokens
+            .iter()
+            .map(|token| {
+                (
+                    token.line,
+                    token.character,
+                    token.token_type,
+                    token.modifiers.join(" "),
+                )
+            })
+            .collect::<Vec<_>>();
+        let expected = vec![
+            (0, 9, "class", "declaration"),
+            (1, 4, "property", "declaration storage mutable"),
+            (3, 12, "method", "declaration"),
+            (3, 16, "parameter", "declaration readonly"),
+            (4, 8, "variable", "declaration readonly"),
+            (4, 27, "property", "storage mutable"),
+            (4, 35, "parameter", "readonly"),
+            (5, 13, "property", "storage mutable"),
+            (5, 21, "variable", "readonly"),
+        ];
+        assert_eq!(
+            classified,
+            expected
+                .into_iter()
+                .map(|(line, character, typ, modifiers)| (
+                    line,
+                    character,
+                    typ,
+                    modifiers.to_string()
+                ))
+                .collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            files[0].encode()[..10].to_vec(),
+            vec![0, 9, 7, 1, 1, 1, 4, 5, 9, 0b1101]
+        );
+    }
+}
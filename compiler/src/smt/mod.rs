@@ -0,0 +1,834 @@
+//! Encodes `assert` statements of Fe functions into SMT-LIB queries and
+//! checks them with an external z3 solver.
+//!
+//! The encoding is intentionally conservative. Function parameters and local
+//! variables are tracked symbolically, storage reads are treated as unknown
+//! values of the field's type and the analysis of a function stops at the
+//! first statement it is unable to model.
+
+use crate::errors::CompileError;
+use crate::types::FeSrc;
+use fe_parser::ast as fe;
+use fe_parser::span::{
+    Span,
+    Spanned,
+};
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::{
+    Command,
+    Stdio,
+};
+
+/// The name of the solver binary that is invoked to check queries.
+const SOLVER: &str = "z3";
+
+/// A single property that should be checked by the solver.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Query {
+    /// The name of the contract containing the property.
+    pub contract: String,
+    /// The name of the function containing the property.
+    pub function: String,
+    /// The location of the property in the Fe source.
+    pub span: Span,
+    /// The SMT-LIB script or the reason why the property can not be encoded.
+    pub kind: QueryKind,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum QueryKind {
+    /// A script that is satisfiable iff the property can be violated.
+    Script(String),
+    /// The property could not be encoded.
+    Unsupported(String),
+}
+
+/// The result of checking a single query.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Outcome {
+    /// The property holds for all inputs.
+    Proved,
+    /// The property is violated by the given variable assignments.
+    Counterexample(Vec<(String, String)>),
+    /// The solver was unable to decide the property.
+    Unknown(String),
+}
+
+/// Parses the Fe source and checks all of its assertions.
+pub fn check_module(src: FeSrc) -> Result<Vec<(Query, Outcome)>, CompileError> {
+    let tokens = fe_parser::get_parse_tokens(src)?;
+    let module = crate::parse(&tokens)?;
+
+    build(&module)
+        .into_iter()
+        .map(|query| check(&query).map(|outcome| (query, outcome)))
+        .collect()
+}
+
+/// Builds queries for the assertions of all functions in a module.
+pub fn build(module: &fe::Module) -> Vec<Query> {
+    let mut queries = vec![];
+
+    for stmt in module.body.iter() {
+        if let fe::ModuleStmt::ContractDef { name, body, .. } = &stmt.node {
+            let fields = body
+                .iter()
+                .filter_map(|contract_stmt| match &contract_stmt.node {
+                    fe::ContractStmt::ContractField { name, typ, .. } => {
+                        Some((name.node, &typ.node))
+                    }
+                    _ => None,
+                })
+                .collect::<HashMap<_, _>>();
+
+            for contract_stmt in body.iter() {
+                if let fe::ContractStmt::FuncDef {
+                    name: func_name,
+                    args,
+                    body,
+                    ..
+                } = &contract_stmt.node
+                {
+                    let mut encoder = Encoder::new(name.node, func_name.node, fields.clone());
+                    encoder.func_def(args, body);
+                    queries.append(&mut encoder.queries);
+                }
+            }
+        }
+    }
+
+    queries
+}
+
+/// Checks a single query using the z3 solver.
+pub fn check(query: &Query) -> Result<Outcome, CompileError> {
+    let script = match &query.kind {
+        QueryKind::Script(script) => script,
+        QueryKind::Unsupported(reason) => return Ok(Outcome::Unknown(reason.to_owned())),
+    };
+
+    let mut child = Command::new(SOLVER)
+        .args(&["-in", "-smt2"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|_| CompileError::static_str("unable to run z3, make sure it is installed"))?;
+
+    child
+        .stdin
+        .as_mut()
+        .expect("solver stdin not captured")
+        .write_all(script.as_bytes())
+        .map_err(|_| CompileError::static_str("unable to write query to z3"))?;
+
+    let output = child
+        .wait_with_output()
+        .map_err(|_| CompileError::static_str("unable to read z3 output"))?;
+
+    Ok(parse_solver_output(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Interprets the output of `(check-sat)` followed by `(get-model)`.
+fn parse_solver_output(output: &str) -> Outcome {
+    let mut lines = output.lines();
+
+    match lines.next().map(str::trim) {
+        Some("unsat") => Outcome::Proved,
+        Some("sat") => {
+            let model = lines.collect::<Vec<_>>().join(" ");
+            Outcome::Counterexample(parse_model(&model))
+        }
+        Some(other) => Outcome::Unknown(format!("solver returned \"{}\"", other)),
+        None => Outcome::Unknown("solver returned no result".to_string()),
+    }
+}
+
+/// Extracts the variable assignments of Fe variables from a z3 model.
+fn parse_model(model: &str) -> Vec<(String, String)> {
+    let tokens = model
+        .replace("(", " ( ")
+        .replace(")", " ) ")
+        .split_whitespace()
+        .map(String::from)
+        .collect::<Vec<_>>();
+
+    let mut assignments = vec![];
+    for (index, token) in tokens.iter().enumerate() {
+        if token != "define-fun" || index + 1 >= tokens.len() {
+            continue;
+        }
+
+        let symbol = &tokens[index + 1];
+        // Skip to the end of the sort declaration; the value follows it.
+        let value = tokens[index + 2..]
+            .iter()
+            .find(|token| token.starts_with("#x") || *token == "true" || *token == "false");
+
+        if let (Some(name), Some(value)) = (fe_name(symbol), value) {
+            assignments.push((name, format_value(value)));
+        }
+    }
+
+    assignments.sort();
+    assignments
+}
+
+/// Maps an SMT symbol back to the Fe variable it represents.
+fn fe_name(symbol: &str) -> Option<String> {
+    let name = symbol.strip_prefix("fe_")?;
+    let index = name.rfind('_')?;
+    let (name, version) = (&name[..index], &name[index + 1..]);
+
+    match version {
+        "0" => Some(name.to_string()),
+        _ => Some(format!("{} (assignment {})", name, version)),
+    }
+}
+
+fn format_value(value: &str) -> String {
+    match value.strip_prefix("#x") {
+        Some(hex) => {
+            let trimmed = hex.trim_start_matches('0');
+            if trimmed.is_empty() {
+                "0x0".to_string()
+            } else {
+                format!("0x{}", trimmed)
+            }
+        }
+        None => value.to_string(),
+    }
+}
+
+/// The sort of an encoded value.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Sort {
+    /// An unsigned integer with the given number of bits.
+    Uint(usize),
+    /// An integer literal whose size is determined by the other operand.
+    Literal,
+    Bool,
+}
+
+impl Sort {
+    fn from_type_desc(typ: &fe::TypeDesc) -> Option<Sort> {
+        match typ {
+            fe::TypeDesc::Base { base: "bool" } => Some(Sort::Bool),
+            fe::TypeDesc::Base { base } if base.starts_with('u') => {
+                base[1..].parse().ok().map(Sort::Uint)
+            }
+            _ => None,
+        }
+    }
+
+    fn is_int(self) -> bool {
+        matches!(self, Sort::Uint(_) | Sort::Literal)
+    }
+
+    /// The sort of an arithmetic result, which is `u256` for literals.
+    fn into_uint(self) -> Sort {
+        match self {
+            Sort::Literal => Sort::Uint(256),
+            sort => sort,
+        }
+    }
+
+    /// The largest value of the sort as an SMT term.
+    fn max(self) -> Option<String> {
+        match self {
+            Sort::Uint(256) | Sort::Literal | Sort::Bool => None,
+            Sort::Uint(bits) => Some(bv_max(bits)),
+        }
+    }
+}
+
+fn bv_max(bits: usize) -> String {
+    format!(
+        "(bvsub (bvshl #x{:064x} (_ bv{} 256)) #x{:064x})",
+        1, bits, 1
+    )
+}
+
+/// An encoded expression along with its sort.
+struct Value {
+    term: String,
+    sort: Sort,
+}
+
+/// Encodes the statements of a single function.
+struct Encoder<'a> {
+    contract: String,
+    function: String,
+    /// The types of the storage fields of the contract.
+    fields: HashMap<&'a str, &'a fe::TypeDesc<'a>>,
+    /// SMT declarations of all symbols.
+    declarations: Vec<String>,
+    /// Facts that hold at the current point of the function.
+    assumptions: Vec<String>,
+    /// The current SMT symbol and sort of each Fe variable.
+    variables: HashMap<String, (String, Sort)>,
+    /// The number of assignments made to each Fe variable.
+    versions: HashMap<String, usize>,
+    /// The number of unconstrained values introduced so far.
+    unknowns: usize,
+    queries: Vec<Query>,
+}
+
+impl<'a> Encoder<'a> {
+    fn new(contract: &str, function: &str, fields: HashMap<&'a str, &'a fe::TypeDesc<'a>>) -> Self {
+        Self {
+            contract: contract.to_string(),
+            function: function.to_string(),
+            fields,
+            declarations: vec![],
+            assumptions: vec![],
+            variables: HashMap::new(),
+            versions: HashMap::new(),
+            unknowns: 0,
+            queries: vec![],
+        }
+    }
+
+    fn func_def(&mut self, args: &[Spanned<fe::FuncDefArg>], body: &[Spanned<fe::FuncStmt>]) {
+        for arg in args.iter() {
+            if let Some(sort) = Sort::from_type_desc(&arg.node.typ.node) {
+                self.define(arg.node.name.node, sort);
+            }
+        }
+
+        self.func_stmts(body);
+    }
+
+    /// Encodes statements until one is reached that can not be modeled.
+    fn func_stmts(&mut self, stmts: &[Spanned<fe::FuncStmt>]) {
+        for stmt in stmts.iter() {
+            if let Err(reason) = self.func_stmt(stmt) {
+                self.add_unsupported_asserts(stmts, stmt, &reason);
+                return;
+            }
+
+            if let fe::FuncStmt::Return { .. } | fe::FuncStmt::Revert { .. } = &stmt.node {
+                return;
+            }
+        }
+    }
+
+    /// Records all assertions following an unsupported statement as unknown.
+    fn add_unsupported_asserts(
+        &mut self,
+        stmts: &[Spanned<fe::FuncStmt>],
+        unsupported: &Spanned<fe::FuncStmt>,
+        reason: &str,
+    ) {
+        for stmt in stmts
+            .iter()
+            .skip_while(|stmt| stmt.span != unsupported.span)
+        {
+            if let fe::FuncStmt::Assert { .. } = &stmt.node {
+                self.push_query(stmt.span, QueryKind::Unsupported(reason.to_string()));
+            }
+        }
+    }
+
+    fn func_stmt(&mut self, stmt: &Spanned<fe::FuncStmt>) -> Result<(), String> {
+        match &stmt.node {
+            fe::FuncStmt::Assert { test, .. } => {
+                match self.expr(test) {
+                    Ok(Value {
+                        term,
+                        sort: Sort::Bool,
+                    }) => {
+                        let script = self.script(&format!("(not {})", term));
+                        self.push_query(stmt.span, QueryKind::Script(script));
+                        self.assumptions.push(term);
+                    }
+                    Ok(_) => return Err("assertion is not a boolean".to_string()),
+                    Err(reason) => {
+                        self.push_query(stmt.span, QueryKind::Unsupported(reason));
+                    }
+                }
+                Ok(())
+            }
+            fe::FuncStmt::VarDecl { target, typ, value } => {
+                let name = match &target.node {
+                    fe::Expr::Name(name) => name,
+                    _ => return Err("unsupported declaration target".to_string()),
+                };
+                let sort = Sort::from_type_desc(&typ.node)
+                    .ok_or_else(|| "unsupported variable type".to_string())?;
+
+                let term = match value {
+                    Some(value) => self.expr(value).map(|value| value.term),
+                    None if sort == Sort::Bool => Ok("false".to_string()),
+                    None => Ok("(_ bv0 256)".to_string()),
+                };
+                let symbol = self.define(name, sort);
+                if let Ok(term) = term {
+                    self.assumptions.push(format!("(= {} {})", symbol, term));
+                }
+                Ok(())
+            }
+            fe::FuncStmt::Assign { targets, value } => {
+                let name = match targets.as_slice() {
+                    [Spanned {
+                        node: fe::Expr::Name(name),
+                        ..
+                    }] => name,
+                    // Writes to storage are not modeled since storage reads are unconstrained.
+                    [Spanned {
+                        node: fe::Expr::Attribute { .. },
+                        ..
+                    }]
+                    | [Spanned {
+                        node: fe::Expr::Subscript { .. },
+                        ..
+                    }] => return Ok(()),
+                    _ => return Err("unsupported assignment target".to_string()),
+                };
+                let value = self.expr(value).ok();
+                self.assign(name, value)
+            }
+            fe::FuncStmt::AugAssign { target, op, value } => {
+                let name = match &target.node {
+                    fe::Expr::Name(name) => name,
+                    fe::Expr::Attribute { .. } | fe::Expr::Subscript { .. } => return Ok(()),
+                    _ => return Err("unsupported assignment target".to_string()),
+                };
+                let result = self
+                    .expr(target)
+                    .and_then(|left| self.expr(value).map(|right| (left, right)))
+                    .and_then(|(left, right)| self.bin_op(&op.node, left, right));
+                self.assign(name, result.ok())
+            }
+            fe::FuncStmt::If {
+                test,
+                body,
+                or_else,
+            } => {
+                // `if test: revert` is treated as a precondition on the remaining statements.
+                if let (
+                    [Spanned {
+                        node: fe::FuncStmt::Revert { .. },
+                        ..
+                    }],
+                    [],
+                ) = (body.as_slice(), or_else.as_slice())
+                {
+                    if let Ok(Value {
+                        term,
+                        sort: Sort::Bool,
+                    }) = self.expr(test)
+                    {
+                        self.assumptions.push(format!("(not {})", term));
+                        return Ok(());
+                    }
+                }
+                Err("unsupported conditional".to_string())
+            }
+            fe::FuncStmt::Emit { .. }
+            | fe::FuncStmt::Expr { .. }
+            | fe::FuncStmt::Pass
+            | fe::FuncStmt::Return { .. }
+            | fe::FuncStmt::Revert { .. } => Ok(()),
+            fe::FuncStmt::For { .. } | fe::FuncStmt::While { .. } => {
+                Err("loops are not supported".to_string())
+            }
+            fe::FuncStmt::Match { .. } => Err("unsupported conditional".to_string()),
+            fe::FuncStmt::Error(_) => Err("syntax error".to_string()),
+            fe::FuncStmt::Break | fe::FuncStmt::Continue => {
+                Err("unsupported control flow".to_string())
+            }
+        }
+    }
+
+    /// Assigns a new value to a variable. The value is unconstrained if it is
+    /// `None`.
+    fn assign(&mut self, name: &str, value: Option<Value>) -> Result<(), String> {
+        let sort = match self.variables.get(name) {
+            Some((_, sort)) => *sort,
+            None => return Err(format!("unknown variable `{}`", name)),
+        };
+        let symbol = self.define(name, sort);
+
+        if let Some(value) = value {
+            self.assumptions
+                .push(format!("(= {} {})", symbol, value.term));
+        }
+        Ok(())
+    }
+
+    /// Declares a new version of a variable and returns its symbol.
+    fn define(&mut self, name: &str, sort: Sort) -> String {
+        let version = self.versions.entry(name.to_string()).or_insert(0);
+        let symbol = format!("fe_{}_{}", name, version);
+        *version += 1;
+
+        self.declare(&symbol, sort);
+        self.variables
+            .insert(name.to_string(), (symbol.clone(), sort));
+        symbol
+    }
+
+    fn declare(&mut self, symbol: &str, sort: Sort) {
+        let smt_sort = match sort {
+            Sort::Bool => "Bool",
+            _ => "(_ BitVec 256)",
+        };
+        self.declarations
+            .push(format!("(declare-const {} {})", symbol, smt_sort));
+
+        if let Some(max) = sort.max() {
+            self.assumptions.push(format!("(bvule {} {})", symbol, max));
+        }
+    }
+
+    /// Introduces an unconstrained value, e.g. for storage reads.
+    fn unknown(&mut self, sort: Sort) -> Value {
+        let symbol = format!("unknown_{}", self.unknowns);
+        self.unknowns += 1;
+        self.declare(&symbol, sort);
+
+        Value { term: symbol, sort }
+    }
+
+    /// Introduces an unconstrained integer that may exceed the bounds of its
+    /// sort.
+    fn unbounded(&mut self, sort: Sort) -> Value {
+        let symbol = format!("unknown_{}", self.unknowns);
+        self.unknowns += 1;
+        self.declarations
+            .push(format!("(declare-const {} (_ BitVec 256))", symbol));
+
+        Value { term: symbol, sort }
+    }
+
+    fn expr(&mut self, exp: &Spanned<fe::Expr>) -> Result<Value, String> {
+        match &exp.node {
+            fe::Expr::Name(name) => match self.variables.get(*name) {
+                Some((symbol, sort)) => Ok(Value {
+                    term: symbol.to_owned(),
+                    sort: *sort,
+                }),
+                None => Err(format!("unknown variable `{}`", name)),
+            },
+            fe::Expr::Num(num) => literal(num),
+            fe::Expr::Bool(value) => Ok(Value {
+                term: value.to_string(),
+                sort: Sort::Bool,
+            }),
+            fe::Expr::BinOperation { left, op, right } => {
+                let left = self.expr(left)?;
+                let right = self.expr(right)?;
+                self.bin_op(&op.node, left, right)
+            }
+            fe::Expr::CompOperation { left, op, right } => {
+                let left = self.expr(left)?;
+                let right = self.expr(right)?;
+                comp_op(&op.node, left, right)
+            }
+            fe::Expr::BoolOperation { left, op, right } => {
+                let left = self.expr(left)?;
+                let right = self.expr(right)?;
+                if left.sort != Sort::Bool || right.sort != Sort::Bool {
+                    return Err("boolean operation on non-boolean values".to_string());
+                }
+                let smt_op = match op.node {
+                    fe::BoolOperator::And => "and",
+                    fe::BoolOperator::Or => "or",
+                };
+                Ok(Value {
+                    term: format!("({} {} {})", smt_op, left.term, right.term),
+                    sort: Sort::Bool,
+                })
+            }
+            fe::Expr::UnaryOperation { op, operand } => {
+                let operand = self.expr(operand)?;
+                match (&op.node, operand.sort) {
+                    (fe::UnaryOperator::Not, Sort::Bool) => Ok(Value {
+                        term: format!("(not {})", operand.term),
+                        sort: Sort::Bool,
+                    }),
+                    (fe::UnaryOperator::UAdd, sort) if sort.is_int() => Ok(operand),
+                    _ => Err("unsupported unary operation".to_string()),
+                }
+            }
+            fe::Expr::Ternary {
+                if_expr,
+                test,
+                else_expr,
+            } => {
+                let test = self.expr(test)?;
+                let if_value = self.expr(if_expr)?;
+                let else_value = self.expr(else_expr)?;
+                let sort = result_sort(if_value.sort, else_value.sort)?;
+                Ok(Value {
+                    term: format!("(ite {} {} {})", test.term, if_value.term, else_value.term),
+                    sort,
+                })
+            }
+            // Storage reads may return any value of the field's type.
+            fe::Expr::Attribute { value, .. } | fe::Expr::Subscript { value, .. }
+                if is_self(value) =>
+            {
+                let sort = self
+                    .storage_type(exp)
+                    .and_then(Sort::from_type_desc)
+                    .ok_or_else(|| "unsupported storage read".to_string())?;
+                Ok(self.unknown(sort))
+            }
+            _ => Err("unsupported expression".to_string()),
+        }
+    }
+
+    /// The declared type of a storage field, or of the values of the maps
+    /// and arrays stored in it.
+    fn storage_type(&self, exp: &Spanned<fe::Expr>) -> Option<&'a fe::TypeDesc<'a>> {
+        match &exp.node {
+            fe::Expr::Attribute { value, attr } if matches!(value.node, fe::Expr::Name("self")) => {
+                self.fields.get(attr.node).copied()
+            }
+            fe::Expr::Subscript { value, .. } => match self.storage_type(value)? {
+                fe::TypeDesc::Map { to, .. } => Some(&to.node),
+                fe::TypeDesc::Array { typ, .. } => Some(&typ.node),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Encodes a checked binary operation. Since Fe reverts on overflow, its
+    /// absence is assumed for the remaining statements. Division by zero
+    /// yields zero like it does on the EVM.
+    fn bin_op(&mut self, op: &fe::BinOperator, left: Value, right: Value) -> Result<Value, String> {
+        let sort = result_sort(left.sort, right.sort)?;
+        if !sort.is_int() {
+            return Err("arithmetic on non-integer values".to_string());
+        }
+
+        let (l, r) = (&left.term, &right.term);
+        let (term, no_overflow) = match op {
+            fe::BinOperator::Add => (format!("(bvadd {} {})", l, r), None),
+            fe::BinOperator::Sub => (
+                format!("(bvsub {} {})", l, r),
+                Some(format!("(bvuge {} {})", l, r)),
+            ),
+            fe::BinOperator::Mult => (
+                format!("(bvmul {} {})", l, r),
+                Some(format!("(bvumul_noovfl {} {})", l, r)),
+            ),
+            fe::BinOperator::Div | fe::BinOperator::FloorDiv => (
+                format!(
+                    "(ite (= {1} (_ bv0 256)) (_ bv0 256) (bvudiv {0} {1}))",
+                    l, r
+                ),
+                None,
+            ),
+            fe::BinOperator::Mod => (
+                format!(
+                    "(ite (= {1} (_ bv0 256)) (_ bv0 256) (bvurem {0} {1}))",
+                    l, r
+                ),
+                None,
+            ),
+            fe::BinOperator::BitAnd => (format!("(bvand {} {})", l, r), None),
+            fe::BinOperator::BitOr => (format!("(bvor {} {})", l, r), None),
+            fe::BinOperator::BitXor => (format!("(bvxor {} {})", l, r), None),
+            // `shl` leaves the bits above smaller integers set, so the
+            // result is left unconstrained.
+            fe::BinOperator::LShift => return Ok(self.unbounded(sort.into_uint())),
+            fe::BinOperator::RShift => (format!("(bvlshr {} {})", l, r), None),
+            fe::BinOperator::Pow => return Err("exponentiation is not supported".to_string()),
+        };
+
+        if let Some(condition) = no_overflow {
+            self.assumptions.push(condition);
+        }
+
+        // Model addition overflow (and the bounds of smaller integers) by
+        // constraining the result.
+        let result = self.unknown(sort);
+        self.assumptions
+            .push(format!("(= {} {})", result.term, term));
+        if let fe::BinOperator::Add = op {
+            self.assumptions
+                .push(format!("(bvuge {} {})", result.term, l));
+        }
+        Ok(Value {
+            term: result.term,
+            sort: sort.into_uint(),
+        })
+    }
+
+    /// Builds a script that is satisfiable iff `negated_property` can hold.
+    fn script(&self, negated_property: &str) -> String {
+        let mut script = self.declarations.join("\n");
+        for assumption in self.assumptions.iter() {
+            script.push_str(&format!("\n(assert {})", assumption));
+        }
+        script.push_str(&format!("\n(assert {})", negated_property));
+        script.push_str("\n(check-sat)\n(get-model)\n");
+        script
+    }
+
+    fn push_query(&mut self, span: Span, kind: QueryKind) {
+        self.queries.push(Query {
+            contract: self.contract.clone(),
+            function: self.function.clone(),
+            span,
+            kind,
+        })
+    }
+}
+
+fn is_self(exp: &Spanned<fe::Expr>) -> bool {
+    match &exp.node {
+        fe::Expr::Name("self") => true,
+        fe::Expr::Attribute { value, .. } | fe::Expr::Subscript { value, .. } => is_self(value),
+        _ => false,
+    }
+}
+
+fn literal(num: &str) -> Result<Value, String> {
+    let value = if let Some(hex) = num.strip_prefix("0x") {
+        u128::from_str_radix(hex, 16)
+    } else {
+        num.parse::<u128>()
+    }
+    .map_err(|_| format!("unsupported literal `{}`", num))?;
+
+    Ok(Value {
+        term: format!("(_ bv{} 256)", value),
+        sort: Sort::Literal,
+    })
+}
+
+fn result_sort(left: Sort, right: Sort) -> Result<Sort, String> {
+    match (left, right) {
+        (Sort::Literal, other) | (other, Sort::Literal) => Ok(other),
+        (left, right) if left == right => Ok(left),
+        _ => Err("operands have different types".to_string()),
+    }
+}
+
+fn comp_op(op: &fe::CompOperator, left: Value, right: Value) -> Result<Value, String> {
+    let sort = result_sort(left.sort, right.sort)?;
+    let (l, r) = (&left.term, &right.term);
+
+    let term = match op {
+        fe::CompOperator::Eq => format!("(= {} {})", l, r),
+        fe::CompOperator::NotEq => format!("(not (= {} {}))", l, r),
+        fe::CompOperator::Lt if sort.is_int() => format!("(bvult {} {})", l, r),
+        fe::CompOperator::LtE if sort.is_int() => format!("(bvule {} {})", l, r),
+        fe::CompOperator::Gt if sort.is_int() => format!("(bvugt {} {})", l, r),
+        fe::CompOperator::GtE if sort.is_int() => format!("(bvuge {} {})", l, r),
+        _ => return Err("unsupported comparison".to_string()),
+    };
+
+    Ok(Value {
+        term,
+        sort: Sort::Bool,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smt::{
+        build,
+        parse_solver_output,
+        Outcome,
+        QueryKind,
+    };
+    use fe_parser::parsers;
+
+    fn queries(src: &str) -> Vec<QueryKind> {
+        let tokens = fe_parser::get_parse_tokens(src).expect("unable to parse contract");
+        let module = parsers::file_input(&tokens[..])
+            .expect("unable to build module AST")
+            .1
+            .node;
+
+        build(&module).into_iter().map(|query| query.kind).collect()
+    }
+
+    #[test]
+    fn assert_query() {
+        let kinds = queries(
+            "\
+            \ncontract Foo:\
+            \n  pub def bar(x: u256, y: u256):\
+            \n    z: u256 = x + y\
+            \n    assert z >= x",
+        );
+
+        assert_eq!(kinds.len(), 1);
+        if let QueryKind::Script(script) = &kinds[0] {
+            assert!(script.contains("(declare-const fe_x_0 (_ BitVec 256))"));
+            assert!(script.contains("(assert (not (bvuge fe_z_0 fe_x_0)))"));
+            assert!(script.ends_with("(check-sat)\n(get-model)\n"));
+        } else {
+            panic!("expected an SMT script")
+        }
+    }
+
+    #[test]
+    fn division_and_storage_reads() {
+        let kinds = queries(
+            "\
+            \ncontract Foo:\
+            \n  limit: u8\
+            \n  pub def bar(x: u256):\
+            \n    y: u256 = x / 0\
+            \n    assert y == 0\
+            \n    z: u8 = self.limit\
+            \n    assert z <= 255",
+        );
+
+        assert_eq!(kinds.len(), 2);
+        if let (QueryKind::Script(division), QueryKind::Script(read)) = (&kinds[0], &kinds[1]) {
+            assert!(division.contains(
+                "(ite (= (_ bv0 256) (_ bv0 256)) (_ bv0 256) (bvudiv fe_x_0 (_ bv0 256)))"
+            ));
+            assert!(!division.contains("(not (= (_ bv0 256) (_ bv0 256)))"));
+            assert!(read.contains("(assert (bvule unknown_1 (bvsub (bvshl"));
+        } else {
+            panic!("expected SMT scripts")
+        }
+    }
+
+    #[test]
+    fn unsupported_statements() {
+        let kinds = queries(
+            "\
+            \ncontract Foo:\
+            \n  pub def bar(x: u256):\
+            \n    assert x == x\
+            \n    while x > 0:\
+            \n      x = x - 1\
+            \n    assert x == 0",
+        );
+
+        assert_eq!(kinds.len(), 2);
+        assert!(matches!(kinds[0], QueryKind::Script(_)));
+        assert_eq!(
+            kinds[1],
+            QueryKind::Unsupported("loops are not supported".to_string())
+        );
+    }
+
+    #[test]
+    fn solver_output() {
+        assert_eq!(parse_solver_output("unsat\n"), Outcome::Proved);
+        assert_eq!(
+            parse_solver_output(
+                "sat\n(\n  (define-fun fe_x_0 () (_ BitVec 256)\n    #x000000000000000000000000000000000000000000000000000000000000002a)\n  (define-fun unknown_0 () (_ BitVec 256)\n    #x0000000000000000000000000000000000000000000000000000000000000000)\n)"
+            ),
+            Outcome::Counterexample(vec![("x".to_string(), "0x2a".to_string())]),
+        );
+        assert!(matches!(
+            parse_solver_output("unknown\n"),
+            Outcome::Unknown(_)
+        ));
+    }
+}
@@ -0,0 +1,136 @@
+//! Discovery of test functions, which are written next to the contract code
+//! they test and marked with the `@test` decorator.
+//!
+//! Tests are removed from the module before it is compiled to production
+//! artifacts. To run them, they are made public instead, so that the
+//! compiled contract serves as a harness that calls each test in turn.
+
+use crate::types::TestFunction;
+use fe_analyzer::builtins;
+use fe_parser::ast as fe;
+use fe_parser::span::Spanned;
+use std::str::FromStr;
+
+/// Removes all test functions from the contracts in the module.
+pub fn strip_tests(module: &mut fe::Module) {
+    for stmt in module.body.iter_mut() {
+        if let Some(body) = contract_body(&mut stmt.node) {
+            body.retain(|stmt| !is_test(stmt));
+        }
+    }
+}
+
+/// Makes all test functions public and returns them in order of
+/// definition.
+pub fn expose_tests(module: &mut fe::Module) -> Vec<TestFunction> {
+    let mut tests = vec![];
+
+    for stmt in module.body.iter_mut() {
+        if let fe::ModuleStmt::ContractDef { name, body, .. } = &mut stmt.node {
+            let contract = name.node.to_string();
+
+            for stmt in body.iter_mut().filter(|stmt| is_test(stmt)) {
+                if let fe::ContractStmt::FuncDef { qual, name, .. } = &mut stmt.node {
+                    *qual = Some(Spanned {
+                        node: fe::FuncQual::Pub,
+                        span: name.span,
+                    });
+                    tests.push(TestFunction {
+                        contract: contract.clone(),
+                        name: name.node.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    tests
+}
+
+/// Test functions may only be defined in the bodies of contracts, mixins and
+/// libraries.
+fn contract_body<'a, 'b>(
+    stmt: &'b mut fe::ModuleStmt<'a>,
+) -> Option<&'b mut Vec<Spanned<fe::ContractStmt<'a>>>> {
+    match stmt {
+        fe::ModuleStmt::ContractDef { body, .. }
+        | fe::ModuleStmt::MixinDef { body, .. }
+        | fe::ModuleStmt::LibraryDef { body, .. } => Some(body),
+        _ => None,
+    }
+}
+
+fn is_test(stmt: &Spanned<fe::ContractStmt>) -> bool {
+    if let fe::ContractStmt::FuncDef { decorators, .. } = &stmt.node {
+        decorators.iter().any(|decorator| match &decorator.node {
+            fe::Expr::Name(name) => matches!(
+                builtins::Decorator::from_str(name),
+                Ok(builtins::Decorator::Test)
+            ),
+            _ => false,
+        })
+    } else {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{
+        expose_tests,
+        strip_tests,
+    };
+    use fe_parser::ast as fe;
+    use fe_parser::tokenizer::Token;
+
+    const SRC: &str = "\
+contract Foo:
+    pub def bar() -> u256:
+        return 1
+
+    @test
+    def test_bar():
+        assert self.bar() == 1
+";
+
+    fn parse<'a>(tokens: &'a [Token<'a>]) -> fe::Module<'a> {
+        fe_parser::parsers::file_input(tokens).unwrap().1.node
+    }
+
+    fn function_names(module: &fe::Module) -> Vec<(String, bool)> {
+        match &module.body[0].node {
+            fe::ModuleStmt::ContractDef { body, .. } => body
+                .iter()
+                .filter_map(|stmt| match &stmt.node {
+                    fe::ContractStmt::FuncDef { qual, name, .. } => {
+                        Some((name.node.to_string(), qual.is_some()))
+                    }
+                    _ => None,
+                })
+                .collect(),
+            _ => panic!("expected a contract"),
+        }
+    }
+
+    #[test]
+    fn strip() {
+        let tokens = fe_parser::get_parse_tokens(SRC).unwrap();
+        let mut module = parse(&tokens);
+        strip_tests(&mut module);
+        assert_eq!(function_names(&module), vec![("bar".to_string(), true)]);
+    }
+
+    #[test]
+    fn expose() {
+        let tokens = fe_parser::get_parse_tokens(SRC).unwrap();
+        let mut module = parse(&tokens);
+        let tests = expose_tests(&mut module);
+        assert_eq!(tests.len(), 1);
+        assert_eq!(tests[0].contract, "Foo");
+        assert_eq!(tests[0].name, "test_bar");
+        assert_eq!(
+            function_names(&module),
+            vec![("bar".to_string(), true), ("test_bar".to_string(), true)]
+        );
+    }
+}
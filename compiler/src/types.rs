@@ -1,3 +1,4 @@
+use fe_analyzer::lints::Warning;
 use fe_parser::ast as fe;
 use std::collections::HashMap;
 
@@ -19,7 +20,16 @@ pub type NamedAbis = HashMap<ContractName, JsonAbi>;
 /// A mapping of contract names and their Yul IR.
 pub type NamedYulContracts = HashMap<ContractName, YulIr>;
 /// A mapping of contract names and their bytecode.
-pub type NamedBytecodeContracts = HashMap<ContractName, Bytecode>;
+pub type NamedBytecodeContracts = HashMap<ContractName, ContractBytecode>;
+
+/// The creation and runtime bytecode of a contract.
+#[derive(Clone)]
+pub struct ContractBytecode {
+    /// The bytecode executed when deploying the contract.
+    pub creation: Bytecode,
+    /// The bytecode stored on chain once the contract has been deployed.
+    pub runtime: Bytecode,
+}
 
 /// The artifacts of a compiled contract.
 pub struct CompiledContract {
@@ -27,6 +37,8 @@ pub struct CompiledContract {
     pub yul: YulIr,
     #[cfg(feature = "solc-backend")]
     pub bytecode: Bytecode,
+    #[cfg(feature = "solc-backend")]
+    pub runtime_bytecode: Bytecode,
 }
 
 /// A mapping of contract names and their artifacts.
@@ -36,5 +48,59 @@ pub type NamedContracts = HashMap<ContractName, CompiledContract>;
 pub struct CompiledModule {
     pub fe_tokens: String,
     pub fe_ast: String,
+    /// The EIP-712 schemas of the typed data structs as JSON.
+    pub eip712: String,
+    /// The storage layouts and initializers of the upgradeable contracts as
+    /// JSON.
+    pub proxy: String,
     pub contracts: NamedContracts,
+    /// Warnings produced by lints.
+    pub warnings: Vec<Warning>,
+}
+
+/// A function marked with `@test`, which passes if calling it does not revert.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestFunction {
+    pub contract: ContractName,
+    pub name: String,
+}
+
+/// A module compiled with its test functions exposed as public functions.
+pub struct CompiledTests {
+    pub module: CompiledModule,
+    pub tests: Vec<TestFunction>,
+}
+
+/// A phase of a compilation, as reported to a progress callback.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Progress {
+    Parsing,
+    Analyzing,
+    CompilingYul,
+    /// The contract is being compiled to bytecode, as the `index`th of
+    /// `total` contracts, counting from 1.
+    CompilingBytecode {
+        contract: ContractName,
+        index: usize,
+        total: usize,
+    },
+}
+
+impl std::fmt::Display for Progress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Progress::Parsing => write!(f, "Parsing"),
+            Progress::Analyzing => write!(f, "Analyzing"),
+            Progress::CompilingYul => write!(f, "Compiling to Yul"),
+            Progress::CompilingBytecode {
+                contract,
+                index,
+                total,
+            } => write!(
+                f,
+                "Compiling {} to bytecode ({}/{})",
+                contract, index, total
+            ),
+        }
+    }
 }
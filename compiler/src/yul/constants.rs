@@ -1,8 +1,18 @@
 use fe_analyzer::namespace::types::Integer;
+use fe_common::utils::keccak;
 use maplit::hashmap;
 use std::collections::HashMap;
 use yultsur::*;
 
+/// The storage pointer of the contract field with the given nonce as a hex
+/// string, which is based on the keccak256 hash of the nonce.
+///
+/// The last byte of the pointer is set to zero. This is to ensure that our
+/// byte pointer sits at the start of a word (32 | `ptr` ).
+pub fn storage_ptr(nonce: usize) -> String {
+    keccak::partial_right_padded(nonce.to_string().as_bytes(), 31)
+}
+
 /// Return a hashmap containing min/max YUL literals for each supported integer
 /// size
 pub fn numeric_min_max() -> HashMap<Integer, (yul::Expression, yul::Expression)> {
@@ -1,4 +1,5 @@
 use crate::yul::operations::abi as abi_operations;
+use crate::yul::runtime::abi_dispatcher;
 use fe_analyzer::namespace::types::{
     AbiDecodeLocation,
     FixedSize,
@@ -8,23 +9,32 @@ use yultsur::*;
 /// Builds a constructor for a contract with no init function.
 ///
 /// The contract is simply deployed by loading the code into memory and
-/// returning it.
+/// returning it. Deployments that send ether are reverted.
 pub fn build() -> yul::Code {
     // we get the deployment statements and wrap them in a code block
+    let value_check = abi_dispatcher::callvalue_check();
     let deployment = deployment();
-    code! { [deployment...] }
+    code! { [value_check] [deployment...] }
 }
 
 /// Builds a constructor for a contract with an init function.
 ///
 /// We include the entire contact runtime inside of the constructor (without the
 /// ABI dispatcher), run the init function, and return the contract code.
+/// Deployments that send ether are reverted unless the init function is
+/// payable.
 pub fn build_with_init(
     contract_name: &str,
     init_func: yul::Statement,
     init_params: Vec<FixedSize>,
+    init_is_payable: bool,
     runtime: Vec<yul::Statement>,
 ) -> yul::Code {
+    let value_check = if init_is_payable {
+        vec![]
+    } else {
+        vec![abi_dispatcher::callvalue_check()]
+    };
     // get the deplyment statements
     let deployment = deployment();
     // we need to decode the init parameters before passing them into `__init__`
@@ -46,6 +56,8 @@ pub fn build_with_init(
     // `mem_start`. From there, parameters are decoded and passed into the
     // init function.
     code! {
+        [value_check...]
+
         // copy params to memory where they can be decoded
         (let params_start_code := datasize([contract_name]))
         (let params_end_code := codesize())
@@ -77,6 +89,6 @@ fn deployment() -> Vec<yul::Statement> {
 fn test_constructor_without_func() {
     assert_eq!(
         build().to_string(),
-        r#"code { let size := datasize("runtime") datacopy(0, dataoffset("runtime"), size) return(0, size) }"#,
+        r#"code { if callvalue() { revert(0, 0) } let size := datasize("runtime") datacopy(0, dataoffset("runtime"), size) return(0, size) }"#,
     )
 }
@@ -37,9 +37,12 @@ pub fn assign(
                         value_attributes.final_location(),
                         target_attributes.final_location(),
                     ) {
-                        (Location::Memory, Location::Storage { .. }) => {
-                            data_operations::mcopys(typ, target, value)
-                        }
+                        (Location::Memory, Location::Storage { .. }) => match typ {
+                            FixedSize::DynamicArray(array) => {
+                                data_operations::dyn_array_mcopys(array, target, value)
+                            }
+                            typ => data_operations::mcopys(typ, target, value),
+                        },
                         (Location::Memory, Location::Value) => {
                             let target = expr_as_ident(target)?;
                             let value = data_operations::mload(typ, value);
@@ -49,9 +52,12 @@ pub fn assign(
                             let target = expr_as_ident(target)?;
                             statement! { [target] := [value] }
                         }
-                        (Location::Storage { .. }, Location::Storage { .. }) => {
-                            data_operations::scopys(typ, target, value)
-                        }
+                        (Location::Storage { .. }, Location::Storage { .. }) => match typ {
+                            FixedSize::DynamicArray(array) => {
+                                data_operations::dyn_array_scopys(array, target, value)
+                            }
+                            typ => data_operations::scopys(typ, target, value),
+                        },
                         (Location::Storage { .. }, Location::Value) => {
                             let target = expr_as_ident(target)?;
                             let value = data_operations::sload(typ, value);
@@ -6,39 +6,104 @@ use fe_analyzer::Context;
 use fe_common::utils::keccak;
 use fe_parser::ast as fe;
 use fe_parser::span::Spanned;
+use std::collections::HashMap;
 use yultsur::*;
 
 /// Builds a Yul object from a Fe contract.
 pub fn contract_def(
     context: &Context,
     stmt: &Spanned<fe::ModuleStmt>,
+    mixin_defs: &HashMap<&str, &[Spanned<fe::ContractStmt>]>,
+    free_functions: &[Spanned<fe::ContractStmt>],
     created_contracts: Vec<yul::Object>,
 ) -> Result<yul::Object, CompileError> {
-    if let fe::ModuleStmt::ContractDef { name, body } = &stmt.node {
+    if let fe::ModuleStmt::ContractDef {
+        name, mixins, body, ..
+    } = &stmt.node
+    {
         let mut init = None;
         let mut user_functions = vec![];
         let contract_name = name.node;
 
+        // map functions defined in mixins, which can't contain an `__init__`
+        for mixin_name in mixins.iter() {
+            let mixin_context = context
+                .get_mixin(stmt, mixin_name.node)
+                .expect("missing mixin context");
+
+            let mixin_body = mixin_defs[mixin_name.node];
+            let decorator_defs = functions::decorator_defs(mixin_body);
+
+            for stmt in mixin_body.iter() {
+                if let fe::ContractStmt::FuncDef { .. } = &stmt.node {
+                    if mixin_context.get_function(stmt).is_some() {
+                        user_functions.push(functions::func_def(
+                            mixin_context,
+                            stmt,
+                            &decorator_defs,
+                        )?)
+                    }
+
+                    user_functions.append(&mut functions::func_instances(mixin_context, stmt)?)
+                }
+            }
+        }
+
+        // map the functions defined outside of any contract that are called,
+        // each contract gets its own copy of them
+        let free_functions_context = context
+            .get_free_functions(stmt)
+            .expect("missing free functions context");
+        let called_free_functions = &context
+            .get_contract(stmt)
+            .expect("missing contract attributes")
+            .free_functions;
+        for def in free_functions.iter() {
+            if let fe::ContractStmt::FuncDef { name, .. } = &def.node {
+                if called_free_functions.contains(name.node) {
+                    user_functions.push(functions::func_def(
+                        free_functions_context,
+                        def,
+                        &functions::DecoratorDefs::new(),
+                    )?)
+                }
+            }
+        }
+
         // map user defined functions
+        let decorator_defs = functions::decorator_defs(body);
         for stmt in body.iter() {
             if let (Some(attributes), fe::ContractStmt::FuncDef { name, .. }) =
                 (context.get_function(stmt), &stmt.node)
             {
                 if name.node == "__init__" {
                     init = Some((
-                        functions::func_def(context, stmt)?,
+                        functions::func_def(context, stmt, &decorator_defs)?,
                         attributes.param_types.clone(),
                     ))
                 } else {
-                    user_functions.push(functions::func_def(context, stmt)?)
+                    user_functions.push(functions::func_def(context, stmt, &decorator_defs)?)
                 }
             }
+
+            if let fe::ContractStmt::FuncDef { .. } = &stmt.node {
+                user_functions.append(&mut functions::func_instances(context, stmt)?)
+            }
         }
 
         // build the contract's constructor
         let constructor = if let Some((init_func, init_params)) = init {
             let init_runtime = [runtime::build(context, stmt), user_functions.clone()].concat();
-            constructor::build_with_init(contract_name, init_func, init_params, init_runtime)
+            let init_is_payable = context.get_contract(stmt).map_or(false, |attributes| {
+                attributes.payable_functions.contains("__init__")
+            });
+            constructor::build_with_init(
+                contract_name,
+                init_func,
+                init_params,
+                init_is_payable,
+                init_runtime,
+            )
         } else {
             constructor::build()
         };
@@ -1,6 +1,7 @@
 use crate::errors::CompileError;
 use crate::yul::mappers::expressions;
 use crate::yul::names;
+use crate::yul::operations::tuples as tuple_operations;
 use fe_analyzer::namespace::types::{
     FeSized,
     FixedSize,
@@ -18,6 +19,22 @@ pub fn var_decl(
     let decl_type = context.get_declaration(stmt).expect("missing attributes");
 
     if let fe::FuncStmt::VarDecl { target, value, .. } = &stmt.node {
+        if let (fe::Expr::Tuple { elts }, FixedSize::Tuple(tuple)) = (&target.node, decl_type) {
+            let targets = elts
+                .iter()
+                .map(|elt| names::var_name(expressions::expr_name_str(elt)))
+                .collect::<Vec<_>>();
+            let value = match value {
+                Some(value) => expressions::expr(context, &value)?,
+                None => {
+                    let size = literal_expression! { (tuple.size()) };
+                    expression! { alloc([size]) }
+                }
+            };
+
+            return Ok(tuple_operations::unpack(tuple, targets, value));
+        }
+
         let target = names::var_name(expressions::expr_name_str(&target));
 
         return Ok(if let Some(value) = value {
@@ -26,6 +43,8 @@ pub fn var_decl(
         } else {
             match decl_type {
                 FixedSize::Base(_) => statement! { let [target] := 0 },
+                // dynamically sized arrays start out empty
+                FixedSize::DynamicArray(_) => statement! { let [target] := alloc_mstoren(0, 32) },
                 typ => {
                     let size = literal_expression! { (typ.size()) };
                     statement! { let [target] := alloc([size]) }
@@ -44,6 +63,7 @@ mod tests {
         Array,
         Base,
         FixedSize,
+        Tuple,
         Type,
         U256,
     };
@@ -94,4 +114,25 @@ mod tests {
             "let $foo := alloc(320)"
         );
     }
+
+    #[test]
+    fn decl_tuple() {
+        let mut harness = ContextHarness::new("(foo, bar): (u256, bool) = baz");
+        let tuple = Tuple {
+            items: vec![U256, Base::Bool],
+        };
+        harness.add_declaration(
+            "(foo, bar): (u256, bool) = baz",
+            FixedSize::Tuple(tuple.clone()),
+        );
+        harness.add_expression(
+            "baz",
+            ExpressionAttributes::new(Type::Tuple(tuple), Location::Memory),
+        );
+
+        assert_eq!(
+            map(&harness.context, &harness.src),
+            "let $foo, $bar := tuple_uint256_bool_unpack($baz)"
+        );
+    }
 }
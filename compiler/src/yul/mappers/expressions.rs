@@ -1,10 +1,12 @@
 use crate::errors::CompileError;
+use crate::yul::constants;
 use crate::yul::names;
 use crate::yul::operations::{
     abi as abi_operations,
     contracts as contract_operations,
     data as data_operations,
     structs as struct_operations,
+    tuples as tuple_operations,
 };
 use crate::yul::utils;
 use builtins::{
@@ -18,16 +20,22 @@ use fe_analyzer::builtins;
 use fe_analyzer::builtins::{
     ContractTypeMethod,
     GlobalMethod,
+    StructTypeMethod,
 };
+use fe_analyzer::namespace::eip712;
+use fe_analyzer::namespace::erc165;
 use fe_analyzer::namespace::types::{
+    AbiDecodeLocation,
     Base,
     FeSized,
     FixedSize,
+    NamedInteger,
     Type,
 };
 use fe_analyzer::{
     CallType,
     Context,
+    ExpressionAttributes,
     Location,
 };
 use fe_common::utils::keccak;
@@ -40,6 +48,10 @@ use yultsur::*;
 /// Builds a Yul expression from a Fe expression.
 pub fn expr(context: &Context, exp: &Spanned<fe::Expr>) -> Result<yul::Expression, CompileError> {
     if let Some(attributes) = context.get_expression(exp) {
+        if let Some(value) = context.get_constant(exp) {
+            return Ok(literal_expression! {(value.to_word())});
+        }
+
         let expression = match &exp.node {
             fe::Expr::Name(_) => Ok(expr_name(exp)),
             fe::Expr::Num(_) => expr_num(exp),
@@ -54,7 +66,7 @@ pub fn expr(context: &Context, exp: &Spanned<fe::Expr>) -> Result<yul::Expressio
             fe::Expr::Call { .. } => expr_call(context, exp),
             fe::Expr::List { .. } => unimplemented!(),
             fe::Expr::ListComp { .. } => unimplemented!(),
-            fe::Expr::Tuple { .. } => expr_tuple(exp),
+            fe::Expr::Tuple { .. } => expr_tuple(context, exp),
             fe::Expr::Str(_) => expr_str(exp),
             fe::Expr::Ellipsis => unimplemented!(),
         }?;
@@ -79,6 +91,21 @@ fn move_expression(
 ) -> Result<yul::Expression, CompileError> {
     let typ = FixedSize::try_from(typ).expect("Invalid type");
 
+    if let FixedSize::DynamicArray(array) = typ {
+        return match (from.clone(), to.clone()) {
+            (Location::Memory, Location::Memory) => {
+                Ok(data_operations::dyn_array_mcopym(array, val))
+            }
+            (Location::Storage { .. }, Location::Memory) => {
+                Ok(data_operations::dyn_array_scopym(array, val))
+            }
+            _ => Err(CompileError::str(&format!(
+                "invalid expression move: {:?} {:?}",
+                from, to
+            ))),
+        };
+    }
+
     match (from.clone(), to.clone()) {
         (Location::Storage { .. }, Location::Value) => Ok(data_operations::sload(typ, val)),
         (Location::Memory, Location::Value) => Ok(data_operations::mload(typ, val)),
@@ -115,27 +142,122 @@ fn expr_call(context: &Context, exp: &Spanned<fe::Expr>) -> Result<yul::Expressi
 
             return match call_type {
                 CallType::BuiltinFunction { func } => match func {
-                    GlobalMethod::Keccak256 => {
+                    GlobalMethod::Keccak256 | GlobalMethod::Sha256 => {
                         let first_arg = args.node.first().expect("Missing argument");
                         let arg_expr = context
                             .get_expression(first_arg)
                             .expect("invalid attributes");
-                        let size = FixedSize::try_from(arg_expr.typ.clone()).expect("Invalid type");
-                        let func_name: &str = func.into();
+                        let data = yul_args[0].to_owned();
 
-                        let func_name = identifier! { (func_name) };
-                        let size = identifier_expression! { (size.size()) };
-                        Ok(expression! { [func_name]([yul_args[0].to_owned()], [size]) })
+                        // strings are hashed without their length prefix
+                        Ok(match (func, &arg_expr.typ) {
+                            (GlobalMethod::Keccak256, Type::String(_)) => {
+                                expression! { string_keccak256([data]) }
+                            }
+                            (GlobalMethod::Sha256, Type::String(_)) => {
+                                expression! { string_sha256([data]) }
+                            }
+                            (_, typ) => {
+                                let size = FixedSize::try_from(typ.clone()).expect("Invalid type");
+                                let size = literal_expression! { (size.size()) };
+                                if func == &GlobalMethod::Keccak256 {
+                                    expression! { keccak256([data], [size]) }
+                                } else {
+                                    expression! { sha256([data], [size]) }
+                                }
+                            }
+                        })
+                    }
+                    GlobalMethod::Selfdestruct => {
+                        Ok(expression! { selfdestruct([yul_args[0].to_owned()]) })
+                    }
+                    GlobalMethod::Ecrecover => Ok(expression! { ecrecover([yul_args...]) }),
+                    GlobalMethod::Modexp => Ok(expression! { modexp([yul_args...]) }),
+                    GlobalMethod::Identity => {
+                        let first_arg = args.node.first().expect("Missing argument");
+                        let arg_expr = context
+                            .get_expression(first_arg)
+                            .expect("invalid attributes");
+                        let size = FixedSize::try_from(arg_expr.typ.clone()).expect("Invalid type");
+                        let size = literal_expression! { (size.size()) };
+                        Ok(expression! { identity([yul_args[0].to_owned()], [size]) })
                     }
+                    GlobalMethod::Blake2f => Ok(expression! { blake2f([yul_args...]) }),
+                    GlobalMethod::Eip712DomainSeparator => {
+                        Ok(expression! { eip712_domain_separator([yul_args...]) })
+                    }
+                    GlobalMethod::Eip712Digest => Ok(expression! { eip712_digest([yul_args...]) }),
+                    // ranges are mapped by the `for` loops that iterate over them
+                    GlobalMethod::Range => unreachable!(),
+                    GlobalMethod::Require => Ok(match &yul_args[..] {
+                        [condition, reason] => {
+                            expression! { require_with_reason([condition.to_owned()], [reason.to_owned()]) }
+                        }
+                        _ => expression! { require([yul_args[0].to_owned()]) },
+                    }),
                 },
                 CallType::TypeConstructor {
                     typ: Type::Struct(val),
                 } => Ok(struct_operations::new(val, yul_args)),
+                CallType::TypeConstructor {
+                    typ: Type::String(_),
+                } => {
+                    let first_arg = args.node.first().expect("Missing argument");
+                    let arg_attributes = context
+                        .get_expression(first_arg)
+                        .expect("invalid attributes");
+
+                    match &arg_attributes.typ {
+                        Type::Array(array) => {
+                            let size = literal_expression! { (array.size) };
+                            Ok(expression! { bytes_to_string([yul_args[0].to_owned()], [size]) })
+                        }
+                        _ => Ok(yul_args[0].to_owned()),
+                    }
+                }
+                CallType::TypeConstructor {
+                    typ: Type::Base(Base::Fixed(fixed)),
+                } => {
+                    let first_arg = args.node.first().expect("Missing argument");
+
+                    // decimal literals have already been scaled by the analyzer
+                    if context.get_constant(first_arg).is_some() {
+                        return Ok(yul_args[0].to_owned());
+                    }
+
+                    let scale = literal_expression! { (fixed.scale()) };
+                    let func_name = names::fixed_from_int(&fixed.integer);
+                    Ok(expression! { [func_name]([yul_args[0].to_owned()], [scale]) })
+                }
+                CallType::TypeConstructor {
+                    typ: Type::Base(Base::Numeric(integer)),
+                } => {
+                    let first_arg = args.node.first().expect("Missing argument");
+                    let arg_attributes = context
+                        .get_expression(first_arg)
+                        .expect("invalid attributes");
+
+                    // fixed-point numbers are truncated towards zero
+                    match &arg_attributes.typ {
+                        Type::Base(Base::Fixed(fixed)) => {
+                            let scale = literal_expression! { (fixed.scale()) };
+                            if integer.is_signed() {
+                                Ok(expression! { sdiv([yul_args[0].to_owned()], [scale]) })
+                            } else {
+                                Ok(expression! { div([yul_args[0].to_owned()], [scale]) })
+                            }
+                        }
+                        _ => Ok(yul_args[0].to_owned()),
+                    }
+                }
                 CallType::TypeConstructor { .. } => Ok(yul_args[0].to_owned()),
                 CallType::SelfAttribute { func_name } => {
                     let func_name = names::func_name(func_name);
                     Ok(expression! { [func_name]([yul_args...]) })
                 }
+                CallType::LibraryAttribute { library, func_name } => Ok(
+                    contract_operations::library_call(library, func_name, yul_args),
+                ),
                 CallType::ValueAttribute => {
                     if let fe::Expr::Attribute { value, attr } = &func.node {
                         let value_attributes =
@@ -148,6 +270,14 @@ fn expr_call(context: &Context, exp: &Spanned<fe::Expr>) -> Result<yul::Expressi
                                 expr(context, value)?,
                                 yul_args,
                             )),
+                            (Type::Base(Base::Address), func_name)
+                                if builtins::AddressMethod::from_str(func_name).is_ok() =>
+                            {
+                                let address = expr(context, value)?;
+                                let method = builtins::AddressMethod::from_str(func_name)
+                                    .expect("invalid attributes");
+                                Ok(address_method(context, method, address, args, yul_args))
+                            }
                             (typ, func_name) => {
                                 match builtins::ValueMethod::from_str(func_name)
                                     .expect("uncaught analyzer error")
@@ -157,14 +287,66 @@ fn expr_call(context: &Context, exp: &Spanned<fe::Expr>) -> Result<yul::Expressi
                                     // `to_mem` and `clone`.
                                     builtins::ValueMethod::ToMem => expr(context, value),
                                     builtins::ValueMethod::Clone => expr(context, value),
-                                    builtins::ValueMethod::AbiEncode => match typ {
-                                        Type::Struct(struct_) => Ok(abi_operations::encode(
-                                            vec![struct_],
-                                            vec![expr(context, value)?],
-                                        )),
+                                    builtins::ValueMethod::AbiEncode => Ok(abi_operations::encode(
+                                        vec![FixedSize::try_from(typ).expect("invalid attributes")],
+                                        vec![expr(context, value)?],
+                                    )),
+                                    builtins::ValueMethod::AbiEncodePacked => todo!(),
+                                    builtins::ValueMethod::Len => match typ {
+                                        Type::DynamicArray(_) => {
+                                            Ok(data_operations::dyn_array_len(
+                                                value_attributes.location.to_owned(),
+                                                expr(context, value)?,
+                                            ))
+                                        }
+                                        _ => Ok(expression! { mload([expr(context, value)?]) }),
+                                    },
+                                    builtins::ValueMethod::Push => match typ {
+                                        Type::DynamicArray(array) => {
+                                            let inner_size =
+                                                literal_expression! { (array.inner.size()) };
+                                            Ok(expression! {
+                                                dyn_array_push(
+                                                    [expr(context, value)?],
+                                                    [yul_args[0].to_owned()],
+                                                    [inner_size]
+                                                )
+                                            })
+                                        }
+                                        _ => panic!("invalid attributes"),
+                                    },
+                                    builtins::ValueMethod::Pop => match typ {
+                                        Type::DynamicArray(array) => {
+                                            let inner_size =
+                                                literal_expression! { (array.inner.size()) };
+                                            let popped = expression! {
+                                                dyn_array_pop([expr(context, value)?], [inner_size])
+                                            };
+                                            Ok(data_operations::sign_extend(
+                                                &FixedSize::Base(array.inner.clone()),
+                                                popped,
+                                            ))
+                                        }
+                                        _ => panic!("invalid attributes"),
+                                    },
+                                    builtins::ValueMethod::Eip712Hash => match typ {
+                                        Type::Struct(struct_) => {
+                                            Ok(struct_operations::eip712_hash(
+                                                &struct_,
+                                                expr(context, value)?,
+                                            ))
+                                        }
+                                        _ => panic!("invalid attributes"),
+                                    },
+                                    builtins::ValueMethod::ToBytes => match typ {
+                                        Type::String(string) => {
+                                            let size = literal_expression! { (string.max_size) };
+                                            Ok(expression! {
+                                                string_to_bytes([expr(context, value)?], [size])
+                                            })
+                                        }
                                         _ => panic!("invalid attributes"),
                                     },
-                                    builtins::ValueMethod::AbiEncodePacked => todo!(),
                                 }
                             }
                         };
@@ -172,6 +354,26 @@ fn expr_call(context: &Context, exp: &Spanned<fe::Expr>) -> Result<yul::Expressi
 
                     panic!("invalid attributes")
                 }
+                CallType::TypeAttribute {
+                    typ: Type::Struct(struct_),
+                    func_name,
+                } => match StructTypeMethod::from_str(func_name.as_str())
+                    .expect("invalid attributes")
+                {
+                    StructTypeMethod::AbiDecode => {
+                        // the fields are decoded separately and then copied into a new struct
+                        let fields = abi_operations::decode(
+                            struct_.get_field_types(),
+                            yul_args[0].to_owned(),
+                            AbiDecodeLocation::Memory,
+                        );
+                        Ok(struct_operations::new(struct_, fields))
+                    }
+                    StructTypeMethod::Eip712TypeHash => {
+                        let type_hash = eip712::type_hash(struct_).expect("invalid attributes");
+                        Ok(literal_expression! { (type_hash) })
+                    }
+                },
                 CallType::TypeAttribute { typ, func_name } => {
                     match (
                         typ,
@@ -183,11 +385,20 @@ fn expr_call(context: &Context, exp: &Spanned<fe::Expr>) -> Result<yul::Expressi
                                 &contract,
                                 yul_args[0].to_owned(),
                                 yul_args[1].to_owned(),
+                                yul_args[2..].to_vec(),
                             ))
                         }
-                        (Type::Contract(contract), ContractTypeMethod::Create) => Ok(
-                            contract_operations::create(&contract, yul_args[0].to_owned()),
-                        ),
+                        (Type::Contract(contract), ContractTypeMethod::Create) => {
+                            Ok(contract_operations::create(
+                                &contract,
+                                yul_args[0].to_owned(),
+                                yul_args[1..].to_vec(),
+                            ))
+                        }
+                        (Type::Contract(contract), ContractTypeMethod::InterfaceId) => {
+                            let id = erc165::interface_id(&contract.functions);
+                            Ok(literal_expression! { (format!("0x{:08x}", id)) })
+                        }
                         _ => panic!("invalid attributes"),
                     }
                 }
@@ -198,6 +409,42 @@ fn expr_call(context: &Context, exp: &Spanned<fe::Expr>) -> Result<yul::Expressi
     unreachable!()
 }
 
+/// Sends wei to an address or makes a raw call to it. The input and output
+/// buffers of raw calls are byte arrays in memory.
+fn address_method(
+    context: &Context,
+    method: builtins::AddressMethod,
+    address: yul::Expression,
+    args: &Spanned<Vec<Spanned<fe::CallArg>>>,
+    yul_args: Vec<yul::Expression>,
+) -> yul::Expression {
+    let buffer_size = |index: usize| match &context
+        .get_expression(&args.node[index])
+        .expect("invalid attributes")
+        .typ
+    {
+        Type::Array(array) => literal_expression! { (array.size) },
+        _ => panic!("invalid attributes"),
+    };
+    let arg = |index: usize| yul_args[index].to_owned();
+
+    match method {
+        builtins::AddressMethod::Send => expression! { address_send([address], [arg(0)]) },
+        builtins::AddressMethod::Transfer => {
+            expression! { address_transfer([address], [arg(0)]) }
+        }
+        builtins::AddressMethod::RawCall => expression! {
+            call([arg(0)], [address], [arg(1)], [arg(2)], [buffer_size(2)], [arg(3)], [buffer_size(3)])
+        },
+        builtins::AddressMethod::RawStaticcall => expression! {
+            staticcall([arg(0)], [address], [arg(1)], [buffer_size(1)], [arg(2)], [buffer_size(2)])
+        },
+        builtins::AddressMethod::RawDelegatecall => expression! {
+            delegatecall([arg(0)], [address], [arg(1)], [buffer_size(1)], [arg(2)], [buffer_size(2)])
+        },
+    }
+}
+
 pub fn expr_comp_operation(
     context: &Context,
     exp: &Spanned<fe::Expr>,
@@ -211,22 +458,32 @@ pub fn expr_comp_operation(
             .expect("Missing `left` expression in context")
             .typ;
 
+        if let Type::String(_) = typ {
+            return match op.node {
+                fe::CompOperator::Eq => Ok(expression! { string_eq([yul_left], [yul_right]) }),
+                fe::CompOperator::NotEq => {
+                    Ok(expression! { iszero((string_eq([yul_left], [yul_right]))) })
+                }
+                _ => panic!("invalid attributes"),
+            };
+        }
+
         return match op.node {
             fe::CompOperator::Eq => Ok(expression! { eq([yul_left], [yul_right]) }),
             fe::CompOperator::NotEq => Ok(expression! { iszero((eq([yul_left], [yul_right]))) }),
-            fe::CompOperator::Lt => match typ.is_signed_integer() {
+            fe::CompOperator::Lt => match typ.is_signed() {
                 true => Ok(expression! { slt([yul_left], [yul_right]) }),
                 false => Ok(expression! { lt([yul_left], [yul_right]) }),
             },
-            fe::CompOperator::LtE => match typ.is_signed_integer() {
+            fe::CompOperator::LtE => match typ.is_signed() {
                 true => Ok(expression! { iszero((sgt([yul_left], [yul_right]))) }),
                 false => Ok(expression! { iszero((gt([yul_left], [yul_right]))) }),
             },
-            fe::CompOperator::Gt => match typ.is_signed_integer() {
+            fe::CompOperator::Gt => match typ.is_signed() {
                 true => Ok(expression! { sgt([yul_left], [yul_right]) }),
                 false => Ok(expression! { gt([yul_left], [yul_right]) }),
             },
-            fe::CompOperator::GtE => match typ.is_signed_integer() {
+            fe::CompOperator::GtE => match typ.is_signed() {
                 true => Ok(expression! { iszero((slt([yul_left], [yul_right]))) }),
                 false => Ok(expression! { iszero((lt([yul_left], [yul_right]))) }),
             },
@@ -255,18 +512,52 @@ pub fn expr_bin_operation(
                 Type::Base(Base::Numeric(integer)) => {
                     Ok(expression! { [names::checked_add(integer)]([yul_left], [yul_right]) })
                 }
+                Type::Base(Base::Fixed(fixed)) => Ok(
+                    expression! { [names::checked_add(&fixed.integer)]([yul_left], [yul_right]) },
+                ),
+                Type::Base(Base::Named(named)) => Ok(
+                    expression! { [names::checked_add(&named.integer)]([yul_left], [yul_right]) },
+                ),
+                Type::String(_) => Ok(expression! { string_concat([yul_left], [yul_right]) }),
                 _ => unimplemented!("Addition for non-numeric types not yet supported"),
             },
             fe::BinOperator::Sub => match typ {
                 Type::Base(Base::Numeric(integer)) => {
                     Ok(expression! { [names::checked_sub(integer)]([yul_left], [yul_right]) })
                 }
+                Type::Base(Base::Fixed(fixed)) => Ok(
+                    expression! { [names::checked_sub(&fixed.integer)]([yul_left], [yul_right]) },
+                ),
+                Type::Base(Base::Named(named)) => Ok(
+                    expression! { [names::checked_sub(&named.integer)]([yul_left], [yul_right]) },
+                ),
                 _ => unimplemented!("Subtraction for non-numeric types not yet supported"),
             },
-            fe::BinOperator::Mult => Ok(expression! { mul([yul_left], [yul_right]) }),
-            fe::BinOperator::Div => match typ.is_signed_integer() {
-                true => Ok(expression! { sdiv([yul_left], [yul_right]) }),
-                false => Ok(expression! { div([yul_left], [yul_right]) }),
+            fe::BinOperator::Mult => match typ {
+                Type::Base(Base::Fixed(fixed)) => {
+                    let scale = literal_expression! { (fixed.scale()) };
+                    let func_name = names::fixed_mul(&fixed.integer);
+                    Ok(expression! { [func_name]([yul_left], [yul_right], [scale]) })
+                }
+                Type::Base(Base::Numeric(integer))
+                | Type::Base(Base::Named(NamedInteger { integer, .. })) => {
+                    Ok(expression! { [names::checked_mul(integer)]([yul_left], [yul_right]) })
+                }
+                _ => unimplemented!("Multiplication for non-numeric types not yet supported"),
+            },
+            fe::BinOperator::Div => match typ {
+                Type::Base(Base::Fixed(fixed)) => {
+                    let scale = literal_expression! { (fixed.scale()) };
+                    let func_name = names::fixed_div(&fixed.integer);
+                    Ok(expression! { [func_name]([yul_left], [yul_right], [scale]) })
+                }
+                Type::Base(Base::Numeric(integer))
+                | Type::Base(Base::Named(NamedInteger { integer, .. }))
+                    if integer.is_signed() =>
+                {
+                    Ok(expression! { [names::checked_div(integer)]([yul_left], [yul_right]) })
+                }
+                _ => Ok(expression! { div([yul_left], [yul_right]) }),
             },
             fe::BinOperator::BitAnd => Ok(expression! { and([yul_left], [yul_right]) }),
             fe::BinOperator::BitOr => Ok(expression! { or([yul_left], [yul_right]) }),
@@ -297,8 +588,17 @@ pub fn expr_unary_operation(
 
         return match &op.node {
             fe::UnaryOperator::USub => {
+                let typ = &context
+                    .get_expression(exp)
+                    .expect("Missing unary operation expression in context")
+                    .typ;
+                let integer = match typ {
+                    Type::Base(Base::Numeric(integer)) => integer,
+                    _ => unreachable!("Negation of a non-numeric type"),
+                };
                 let zero = literal_expression! {0};
-                Ok(expression! { sub([zero], [yul_operand]) })
+                let func_name = names::checked_sub(integer);
+                Ok(expression! { [func_name]([zero], [yul_operand]) })
             }
             fe::UnaryOperator::Not => Ok(expression! { iszero([yul_operand]) }),
             _ => todo!(),
@@ -341,13 +641,25 @@ pub fn slice_index(
     unreachable!()
 }
 
-fn expr_tuple(exp: &Spanned<fe::Expr>) -> Result<yul::Expression, CompileError> {
-    if let fe::Expr::Tuple { elts } = &exp.node {
-        if !elts.is_empty() {
-            todo!("Non empty Tuples aren't yet supported")
-        } else {
+fn expr_tuple(context: &Context, exp: &Spanned<fe::Expr>) -> Result<yul::Expression, CompileError> {
+    if let (
+        fe::Expr::Tuple { elts },
+        Some(ExpressionAttributes {
+            typ: Type::Tuple(tuple),
+            ..
+        }),
+    ) = (&exp.node, context.get_expression(exp))
+    {
+        if elts.is_empty() {
             return Ok(literal_expression! {0x0});
         }
+
+        let items = elts
+            .iter()
+            .map(|elt| expr(context, elt))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        return Ok(tuple_operations::new(tuple, items));
     }
 
     unreachable!()
@@ -401,6 +713,12 @@ fn expr_subscript(
             return match value_attributes.typ.to_owned() {
                 Type::Map(_) => Ok(data_operations::keyed_map(value, index)),
                 Type::Array(array) => Ok(data_operations::indexed_array(array, value, index)),
+                Type::DynamicArray(array) => Ok(data_operations::indexed_dyn_array(
+                    array,
+                    value_attributes.location.to_owned(),
+                    value,
+                    index,
+                )),
                 _ => Err(CompileError::static_str("invalid attributes")),
             };
         }
@@ -429,36 +747,63 @@ fn expr_attribute(
                 Type::Struct(struct_) => {
                     Ok(struct_operations::get_attribute(struct_, attr.node, value))
                 }
+                Type::Base(Base::Address) => match builtins::AddressField::from_str(attr.node) {
+                    Ok(builtins::AddressField::Balance) => Ok(expression! { balance([value]) }),
+                    Err(_) => Err(CompileError::static_str("invalid `address` attribute name")),
+                },
                 _ => panic!("invalid attributes"),
             }
-        } else {
-            match Object::from_str(expr_name_str(value)) {
-                Ok(Object::Self_) => expr_attribute_self(context, exp),
-                Ok(Object::Block) => match BlockField::from_str(attr.node) {
-                    Ok(BlockField::Coinbase) => Ok(expression! { coinbase() }),
-                    Ok(BlockField::Difficulty) => Ok(expression! { difficulty() }),
-                    Ok(BlockField::Number) => Ok(expression! { number() }),
-                    Ok(BlockField::Timestamp) => Ok(expression! { timestamp() }),
-                    Err(_) => Err(CompileError::static_str("invalid `block` attribute name")),
-                },
-                Ok(Object::Chain) => match ChainField::from_str(attr.node) {
-                    Ok(ChainField::Id) => Ok(expression! { chainid() }),
-                    Err(_) => Err(CompileError::static_str("invalid `chain` attribute name")),
-                },
-                Ok(Object::Msg) => match MsgField::from_str(attr.node) {
-                    Ok(MsgField::Data) => todo!(),
-                    Ok(MsgField::Sender) => Ok(expression! { caller() }),
-                    Ok(MsgField::Sig) => todo!(),
-                    Ok(MsgField::Value) => Ok(expression! { callvalue() }),
-                    Err(_) => Err(CompileError::static_str("invalid `msg` attribute name")),
-                },
-                Ok(Object::Tx) => match TxField::from_str(attr.node) {
-                    Ok(TxField::GasPrice) => Ok(expression! { gasprice() }),
-                    Ok(TxField::Origin) => Ok(expression! { origin() }),
-                    Err(_) => Err(CompileError::static_str("invalid `msg` attribute name")),
-                },
-                Err(_) => Err(CompileError::static_str("invalid attributes")),
+        } else if let Some(Type::Base(Base::Enum(val))) = context
+            .get_expression(exp)
+            .map(|attributes| &attributes.typ)
+        {
+            // Enum variants are represented by their index.
+            match val.get_variant_index(attr.node) {
+                Some(index) if val.name == expr_name_str(value) => {
+                    Ok(literal_expression! { (index) })
+                }
+                _ => expr_attribute_builtin(context, exp),
             }
+        } else {
+            expr_attribute_builtin(context, exp)
+        };
+    }
+
+    unreachable!()
+}
+
+fn expr_attribute_builtin(
+    context: &Context,
+    exp: &Spanned<fe::Expr>,
+) -> Result<yul::Expression, CompileError> {
+    if let fe::Expr::Attribute { value, attr } = &exp.node {
+        return match Object::from_str(expr_name_str(value)) {
+            Ok(Object::Self_) => expr_attribute_self(context, exp),
+            Ok(Object::Block) => match BlockField::from_str(attr.node) {
+                Ok(BlockField::ChainId) => Ok(expression! { chainid() }),
+                Ok(BlockField::Coinbase) => Ok(expression! { coinbase() }),
+                Ok(BlockField::Difficulty) => Ok(expression! { difficulty() }),
+                Ok(BlockField::Number) => Ok(expression! { number() }),
+                Ok(BlockField::Timestamp) => Ok(expression! { timestamp() }),
+                Err(_) => Err(CompileError::static_str("invalid `block` attribute name")),
+            },
+            Ok(Object::Chain) => match ChainField::from_str(attr.node) {
+                Ok(ChainField::Id) => Ok(expression! { chainid() }),
+                Err(_) => Err(CompileError::static_str("invalid `chain` attribute name")),
+            },
+            Ok(Object::Msg) => match MsgField::from_str(attr.node) {
+                Ok(MsgField::Data) => Ok(expression! { msg_data() }),
+                Ok(MsgField::Sender) => Ok(expression! { caller() }),
+                Ok(MsgField::Sig) => Ok(expression! { shr(224, (calldataload(0))) }),
+                Ok(MsgField::Value) => Ok(expression! { callvalue() }),
+                Err(_) => Err(CompileError::static_str("invalid `msg` attribute name")),
+            },
+            Ok(Object::Tx) => match TxField::from_str(attr.node) {
+                Ok(TxField::GasPrice) => Ok(expression! { gasprice() }),
+                Ok(TxField::Origin) => Ok(expression! { origin() }),
+                Err(_) => Err(CompileError::static_str("invalid `tx` attribute name")),
+            },
+            Err(_) => Err(CompileError::static_str("invalid attributes")),
         };
     }
 
@@ -492,12 +837,8 @@ fn expr_attribute_self(
 }
 
 /// Converts a storage nonce into a pointer based on the keccak256 hash
-///
-/// Pointers created here have the last byte set to zero. This is to ensure that
-/// our byte pointer sits at the start of a word (32 | `ptr` ).
 pub fn nonce_to_ptr(nonce: usize) -> yul::Expression {
-    // set the last byte to `0x00` to ensure our pointer sits at the start of a word
-    let ptr = keccak::partial_right_padded(nonce.to_string().as_bytes(), 31);
+    let ptr = constants::storage_ptr(nonce);
     literal_expression! { (ptr) }
 }
 
@@ -546,7 +887,10 @@ mod tests {
     use fe_analyzer::namespace::types::{
         Array,
         Base,
+        Enum,
+        Integer,
         Map,
+        Tuple,
         Type,
         U256,
     };
@@ -654,6 +998,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tuple_new() {
+        let mut harness = ContextHarness::new("(foo, true)");
+        let tuple = Tuple {
+            items: vec![U256, Base::Bool],
+        };
+
+        harness.add_expression(
+            "foo",
+            ExpressionAttributes::new(Type::Base(U256), Location::Value),
+        );
+        harness.add_expression(
+            "true",
+            ExpressionAttributes::new(Type::Base(Base::Bool), Location::Value),
+        );
+        harness.add_expression(
+            "(foo, true)",
+            ExpressionAttributes::new(Type::Tuple(tuple), Location::Memory),
+        );
+
+        let result = map(&harness.context, &harness.src);
+
+        assert_eq!(result, "tuple_uint256_bool_new($foo, true)");
+    }
+
     #[rstest(
         expression,
         expected_yul,
@@ -675,12 +1044,29 @@ mod tests {
         assert_eq!(result, expected_yul);
     }
 
+    #[test]
+    fn enum_variant() {
+        let mut color = Enum::new("Color");
+        color.add_variant("Red");
+        color.add_variant("Green");
+
+        let mut harness = ContextHarness::new("Color.Green");
+        harness.add_expression(
+            "Color.Green",
+            ExpressionAttributes::new(Type::Base(Base::Enum(color)), Location::Value),
+        );
+
+        let result = map(&harness.context, &harness.src);
+
+        assert_eq!(result, "1");
+    }
+
     #[rstest(
         expression,
         expected_yul,
         case("1 + 2", "checked_add_u256(1, 2)"),
         case("1 - 2", "checked_sub_unsigned(1, 2)"),
-        case("1 * 2", "mul(1, 2)"),
+        case("1 * 2", "checked_mul_u256(1, 2)"),
         case("1 / 2", "div(1, 2)"),
         case("1 ** 2", "exp(1, 2)"),
         case("1 % 2", "mod(1, 2)"),
@@ -702,6 +1088,47 @@ mod tests {
         assert_eq!(result, expected_yul);
     }
 
+    #[rstest(
+        expression,
+        expected_yul,
+        case("1 + 2", "checked_add_i8(1, 2)"),
+        case("1 - 2", "checked_sub_i8(1, 2)"),
+        case("1 * 2", "checked_mul_i8(1, 2)"),
+        case("1 / 2", "checked_div_i8(1, 2)"),
+        case("1 % 2", "smod(1, 2)"),
+        case("1 >> 2", "sar(2, 1)")
+    )]
+    fn signed_arithmetic_expression(expression: &str, expected_yul: &str) {
+        let mut harness = ContextHarness::new(expression);
+        harness.add_expressions(
+            vec!["1", "2", expression],
+            ExpressionAttributes::new(Type::Base(Base::Numeric(Integer::I8)), Location::Value),
+        );
+
+        let result = map(&harness.context, expression);
+
+        assert_eq!(result, expected_yul);
+    }
+
+    #[rstest(
+        integer,
+        expected_yul,
+        case(Integer::I8, "checked_sub_i8(0, 1)"),
+        case(Integer::I16, "checked_sub_i16(0, 1)"),
+        case(Integer::I256, "checked_sub_i256(0, 1)")
+    )]
+    fn negation_expression(integer: Integer, expected_yul: &str) {
+        let mut harness = ContextHarness::new("-1");
+        harness.add_expressions(
+            vec!["1", "-1"],
+            ExpressionAttributes::new(Type::Base(Base::Numeric(integer)), Location::Value),
+        );
+
+        let result = map(&harness.context, "-1");
+
+        assert_eq!(result, expected_yul);
+    }
+
     #[rstest(
         expression,
         expected_yul,
@@ -7,18 +7,43 @@ use crate::yul::mappers::{
 use crate::yul::names;
 use crate::yul::operations::data as data_operations;
 use crate::yul::utils;
+use fe_analyzer::builtins;
+use fe_analyzer::namespace::constants::ConstValue;
+use fe_analyzer::namespace::proxy;
 use fe_analyzer::namespace::types::{
     FeSized,
+    FixedSize,
     Type,
 };
 use fe_analyzer::{
+    CallType,
     Context,
     ExpressionAttributes,
+    FunctionAttributes,
+    MatchPattern,
 };
 use fe_parser::ast as fe;
 use fe_parser::span::Spanned;
+use std::collections::HashMap;
 use yultsur::*;
 
+/// The decorators defined in a contract or mixin body.
+pub type DecoratorDefs<'a, 'b> = HashMap<&'a str, &'b Spanned<fe::ContractStmt<'a>>>;
+
+/// Collects the decorators defined in a contract or mixin body.
+pub fn decorator_defs<'a, 'b>(body: &'b [Spanned<fe::ContractStmt<'a>>]) -> DecoratorDefs<'a, 'b> {
+    body.iter()
+        .filter_map(|stmt| match &stmt.node {
+            fe::ContractStmt::FuncDef { name, body, .. }
+                if body.iter().any(|stmt| stmt.node.is_placeholder()) =>
+            {
+                Some((name.node, stmt))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
 pub fn multiple_func_stmt(
     context: &Context,
     statements: &[Spanned<fe::FuncStmt>],
@@ -33,40 +58,285 @@ pub fn multiple_func_stmt(
 pub fn func_def(
     context: &Context,
     def: &Spanned<fe::ContractStmt>,
+    decorator_defs: &DecoratorDefs,
 ) -> Result<yul::Statement, CompileError> {
     if let (
         Some(attributes),
         fe::ContractStmt::FuncDef {
+            docs: _,
+            decorators,
             qual: _,
-            name,
+            name: _,
+            generics: _,
             args,
             return_type: _,
+            return_names: _,
             body,
         },
     ) = (context.get_function(def).to_owned(), &def.node)
     {
-        let function_name = names::func_name(name.node);
-        let param_names = args.iter().map(|arg| func_def_arg(arg)).collect::<Vec<_>>();
-        let function_statements = multiple_func_stmt(context, body)?;
-
-        return if attributes.return_type.is_empty_tuple() {
-            Ok(function_definition! {
-                function [function_name]([param_names...]) {
-                    [function_statements...]
-                }
-            })
+        // The initializer of an upgradeable contract can only be called once.
+        let prologue = if decorators.iter().any(|decorator| {
+            builtins::Decorator::of(&decorator.node) == Some(builtins::Decorator::Initializer)
+        }) {
+            initializer_guard()
         } else {
-            Ok(function_definition! {
-                function [function_name]([param_names...]) -> return_val {
-                    [function_statements...]
-                }
+            vec![]
+        };
+
+        // Builtin decorators such as `payable` don't wrap the function body.
+        let decorators = decorators
+            .iter()
+            .filter(|decorator| builtins::Decorator::of(&decorator.node).is_none())
+            .collect::<Vec<_>>();
+
+        if !decorators.is_empty() {
+            return decorated_func_def(
+                context,
+                attributes,
+                args,
+                body,
+                &decorators,
+                decorator_defs,
+            );
+        }
+
+        return func_def_with_attributes(context, attributes, args, body, prologue);
+    }
+
+    unreachable!()
+}
+
+/// Builds a Yul function definition for every instance of a generic Fe
+/// function definition.
+pub fn func_instances(
+    context: &Context,
+    def: &Spanned<fe::ContractStmt>,
+) -> Result<Vec<yul::Statement>, CompileError> {
+    if let fe::ContractStmt::FuncDef { args, body, .. } = &def.node {
+        return context
+            .get_function_instances(def)
+            .iter()
+            .map(|(attributes, instance_context)| {
+                func_def_with_attributes(instance_context, attributes, args, body, vec![])
             })
+            .collect();
+    }
+
+    unreachable!()
+}
+
+/// The `prologue` is run before the statements of the body.
+fn func_def_with_attributes(
+    context: &Context,
+    attributes: &FunctionAttributes,
+    args: &[Spanned<fe::FuncDefArg>],
+    body: &[Spanned<fe::FuncStmt>],
+    prologue: Vec<yul::Statement>,
+) -> Result<yul::Statement, CompileError> {
+    let function_name = names::func_name(&attributes.name);
+    let param_names = args.iter().map(|arg| func_def_arg(arg)).collect::<Vec<_>>();
+    let function_statements = [prologue, multiple_func_stmt(context, body)?].concat();
+
+    if attributes.return_type.is_empty_tuple() {
+        Ok(function_definition! {
+            function [function_name]([param_names...]) {
+                [function_statements...]
+            }
+        })
+    } else {
+        Ok(function_definition! {
+            function [function_name]([param_names...]) -> return_val {
+                [function_statements...]
+            }
+        })
+    }
+}
+
+/// Reverts if the contract has been initialized already and marks it as
+/// initialized otherwise.
+fn initializer_guard() -> Vec<yul::Statement> {
+    let slot = literal_expression! { (proxy::initialized_slot()) };
+
+    vec![
+        statement! { if (sload([slot.clone()])) { (revert(0, 0)) } },
+        statement! { sstore([slot], 1) },
+    ]
+}
+
+/// Builds a Yul function definition from a decorated Fe function definition.
+///
+/// The body of the function and each of its decorators become nested
+/// functions. A decorator calls the next decorator at its placeholder and the
+/// last decorator calls the function body, so the first decorator in the list
+/// is the outermost one. The arguments of all decorators are evaluated before
+/// the first decorator runs.
+fn decorated_func_def(
+    context: &Context,
+    attributes: &FunctionAttributes,
+    args: &[Spanned<fe::FuncDefArg>],
+    body: &[Spanned<fe::FuncStmt>],
+    decorators: &[&Spanned<fe::Expr>],
+    decorator_defs: &DecoratorDefs,
+) -> Result<yul::Statement, CompileError> {
+    let returns = !attributes.return_type.is_empty_tuple();
+    let function_name = names::func_name(&attributes.name);
+    let param_names = args.iter().map(|arg| func_def_arg(arg)).collect::<Vec<_>>();
+    let forwarded_names = (0..args.len())
+        .map(|index| format!("arg_{}", index))
+        .collect::<Vec<_>>();
+
+    let decorators = decorators
+        .iter()
+        .map(|decorator| decorator_parts(decorator, decorator_defs))
+        .collect::<Vec<_>>();
+    let decorator_arg_names = decorators
+        .iter()
+        .enumerate()
+        .map(|(index, (_, _, call_args))| {
+            (0..call_args.len())
+                .map(|arg_index| format!("decorator_arg_{}_{}", index, arg_index))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut statements = vec![nested_func_def(
+        identifier! { decorated_body },
+        param_names.clone(),
+        returns,
+        multiple_func_stmt(context, body)?,
+    )];
+
+    for (index, (decorator_params, decorator_body, _)) in decorators.iter().enumerate() {
+        let placeholder = decorator_body
+            .iter()
+            .position(|stmt| stmt.node.is_placeholder())
+            .expect("decorator without placeholder");
+
+        let pending_arg_names = decorator_arg_names[index + 1..].concat();
+        let next_call = if index + 1 < decorators.len() {
+            let next_name = identifier! {(format!("decorator_{}", index + 1))};
+            let next_args = [pending_arg_names.clone(), forwarded_names.clone()]
+                .concat()
+                .into_iter()
+                .map(|name| identifier_expression! {(name)})
+                .collect::<Vec<_>>();
+            expression! { [next_name]([next_args...]) }
+        } else {
+            let body_args = forwarded_names
+                .iter()
+                .map(|name| identifier_expression! {(name)})
+                .collect::<Vec<_>>();
+            expression! { decorated_body([body_args...]) }
+        };
+        let next_call = if returns {
+            statement! { return_val := [next_call] }
+        } else {
+            yul::Statement::Expression(next_call)
         };
+
+        let params = decorator_params
+            .iter()
+            .map(|arg| func_def_arg(arg))
+            .chain(
+                [pending_arg_names, forwarded_names.clone()]
+                    .concat()
+                    .into_iter()
+                    .map(|name| identifier! {(name)}),
+            )
+            .collect();
+
+        statements.push(nested_func_def(
+            identifier! {(format!("decorator_{}", index))},
+            params,
+            returns,
+            [
+                multiple_func_stmt(context, &decorator_body[..placeholder])?,
+                vec![next_call],
+                multiple_func_stmt(context, &decorator_body[placeholder + 1..])?,
+            ]
+            .concat(),
+        ));
+    }
+
+    let mut first_args = vec![];
+    for (_, _, call_args) in decorators.iter() {
+        for arg in call_args.iter() {
+            first_args.push(expressions::call_arg(context, arg)?);
+        }
+    }
+    first_args.extend(
+        param_names
+            .iter()
+            .map(|name| yul::Expression::Identifier(name.clone())),
+    );
+    let first_call = expression! { decorator_0([first_args...]) };
+
+    if returns {
+        Ok(function_definition! {
+            function [function_name]([param_names...]) -> return_val {
+                [statements...]
+                (return_val := [first_call])
+            }
+        })
+    } else {
+        statements.push(yul::Statement::Expression(first_call));
+        Ok(function_definition! {
+            function [function_name]([param_names...]) {
+                [statements...]
+            }
+        })
+    }
+}
+
+/// The parameters and body of a decorator's definition together with the
+/// arguments that are passed to it.
+fn decorator_parts<'a, 'b>(
+    decorator: &'b Spanned<fe::Expr<'a>>,
+    decorator_defs: &DecoratorDefs<'a, 'b>,
+) -> (
+    &'b [Spanned<fe::FuncDefArg<'a>>],
+    &'b [Spanned<fe::FuncStmt<'a>>],
+    &'b [Spanned<fe::CallArg<'a>>],
+) {
+    let (name, call_args) = match &decorator.node {
+        fe::Expr::Name(name) => (*name, &[][..]),
+        fe::Expr::Call { func, args } => match &func.node {
+            fe::Expr::Name(name) => (*name, args.node.as_slice()),
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    };
+
+    let def: &'b Spanned<fe::ContractStmt<'a>> = decorator_defs[name];
+    if let fe::ContractStmt::FuncDef { args, body, .. } = &def.node {
+        return (args, body, call_args);
     }
 
     unreachable!()
 }
 
+fn nested_func_def(
+    name: yul::Identifier,
+    params: Vec<yul::Identifier>,
+    returns: bool,
+    statements: Vec<yul::Statement>,
+) -> yul::Statement {
+    if returns {
+        function_definition! {
+            function [name]([params...]) -> return_val {
+                [statements...]
+            }
+        }
+    } else {
+        function_definition! {
+            function [name]([params...]) {
+                [statements...]
+            }
+        }
+    }
+}
+
 fn func_def_arg(arg: &Spanned<fe::FuncDefArg>) -> yul::Identifier {
     let name = arg.node.name.node;
 
@@ -86,12 +356,16 @@ fn func_stmt(
         fe::FuncStmt::For { .. } => for_loop(context, stmt),
         fe::FuncStmt::While { .. } => while_loop(context, stmt),
         fe::FuncStmt::If { .. } => if_statement(context, stmt),
+        fe::FuncStmt::Match { .. } => match_statement(context, stmt),
         fe::FuncStmt::Assert { .. } => assert(context, stmt),
         fe::FuncStmt::Expr { .. } => expr(context, stmt),
         fe::FuncStmt::Pass => Ok(statement! { pop(0) }),
         fe::FuncStmt::Break => break_statement(context, stmt),
         fe::FuncStmt::Continue => continue_statement(context, stmt),
-        fe::FuncStmt::Revert => revert(stmt),
+        fe::FuncStmt::Revert { .. } => revert(context, stmt),
+        fe::FuncStmt::Error(_) => Err(CompileError::static_str(
+            "can not compile code with syntax errors",
+        )),
     }
 }
 
@@ -106,27 +380,79 @@ fn for_loop(
         or_else: _,
     } = &stmt.node
     {
-        let iterator = expressions::expr(context, iter)?;
-        let target_var = names::var_name(expressions::expr_name_str(target));
+        let target_name = expressions::expr_name_str(target);
+        let target_var = names::var_name(target_name);
         let yul_body = multiple_func_stmt(context, body)?;
-        if let Some(ExpressionAttributes {
-            typ: Type::Array(array),
-            ..
-        }) = context.get_expression(iter.span)
-        {
-            let size = literal_expression! { (array.size) };
-            let inner_size = literal_expression! { (array.inner.size()) };
-            return Ok(block_statement! {
-                (for {(let i := 0)} (lt(i, [size])) {(i := add(i, 1))}
-                {
-                    // Below yul statement to load values from memory to `target_var`.
-                    (let [target_var] := [expression! { mloadn([expression! { add([iterator], (mul(i, [inner_size.clone()]))) }], [inner_size]) }])
-                    [yul_body...]
-                })
-            });
-        } else {
-            return Err(CompileError::static_str("missing iter expression"));
+
+        // The iterator and the number of iterations are evaluated once, before
+        // the first iteration.
+        let index = names::loop_var_name(target_name, "index");
+        let index_exp = yul::Expression::Identifier(index.clone());
+        let end = names::loop_var_name(target_name, "end");
+        let end_exp = yul::Expression::Identifier(end.clone());
+
+        if let fe::Expr::Call { func, args } = &iter.node {
+            if let Some(CallType::BuiltinFunction {
+                func: builtins::GlobalMethod::Range,
+            }) = context.get_call(func)
+            {
+                let mut bounds = args
+                    .node
+                    .iter()
+                    .map(|arg| expressions::call_arg(context, arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let stop = bounds.pop().expect("missing range end");
+                let start = bounds.pop().unwrap_or_else(|| literal_expression! { 0 });
+
+                return Ok(block_statement! {
+                    (let [end] := [stop])
+                    (for {(let [index.clone()] := [start])} (lt([index_exp.clone()], [end_exp])) {([index] := add([index_exp.clone()], 1))}
+                    {
+                        (let [target_var] := [index_exp])
+                        [yul_body...]
+                    })
+                });
+            }
         }
+
+        let iterator = expressions::expr(context, iter)?;
+        let array = names::loop_var_name(target_name, "array");
+        let array_exp = yul::Expression::Identifier(array.clone());
+        let (inner, data_ptr, len) = match context.get_expression(iter.span) {
+            Some(ExpressionAttributes {
+                typ: Type::Array(array),
+                ..
+            }) => (
+                array.inner.clone(),
+                array_exp,
+                literal_expression! { (array.size) },
+            ),
+            // The length of a dynamically sized array is stored in front of
+            // its items.
+            Some(ExpressionAttributes {
+                typ: Type::DynamicArray(array),
+                ..
+            }) => (
+                array.inner.clone(),
+                expression! { add([array_exp.clone()], 32) },
+                expression! { mload([array_exp]) },
+            ),
+            _ => return Err(CompileError::static_str("missing iter expression")),
+        };
+
+        let inner_size = literal_expression! { (inner.size()) };
+        let elem_ptr = expression! { add([data_ptr], (mul([index_exp.clone()], [inner_size]))) };
+        let elem = data_operations::mload(FixedSize::Base(inner), elem_ptr);
+        return Ok(block_statement! {
+            (let [array] := [iterator])
+            (let [end] := [len])
+            (for {(let [index.clone()] := 0)} (lt([index_exp.clone()], [end_exp])) {([index] := add([index_exp], 1))}
+            {
+                // Below yul statement to load values from memory to `target_var`.
+                (let [target_var] := [elem])
+                [yul_body...]
+            })
+        });
     }
     unreachable!()
 }
@@ -155,6 +481,105 @@ fn if_statement(
     unreachable!()
 }
 
+fn match_statement(
+    context: &Context,
+    stmt: &Spanned<fe::FuncStmt>,
+) -> Result<yul::Statement, CompileError> {
+    if let fe::FuncStmt::Match { value, arms } = &stmt.node {
+        let yul_value = expressions::expr(context, value)?;
+        let is_signed = context
+            .get_expression(value.span)
+            .map_or(false, |attributes| attributes.typ.is_signed_integer());
+        let arms = arms
+            .iter()
+            .map(|arm| {
+                let patterns = context
+                    .get_match_arm(arm.span)
+                    .ok_or_else(|| CompileError::static_str("missing match arm patterns"))?;
+                Ok((patterns, multiple_func_stmt(context, &arm.node.body)?))
+            })
+            .collect::<Result<Vec<_>, CompileError>>()?;
+
+        // The analyzer ensures that the arms are exhaustive, so the last arm
+        // never needs to be tested.
+        let is_single_value = |patterns: &[MatchPattern]| {
+            matches!(
+                patterns,
+                [MatchPattern::Value(_)] | [MatchPattern::Wildcard]
+            )
+        };
+        if arms.len() > 1 && arms.iter().all(|(patterns, _)| is_single_value(patterns)) {
+            let last = arms.len() - 1;
+            let cases = arms
+                .into_iter()
+                .enumerate()
+                .map(|(index, (patterns, statements))| yul::Case {
+                    literal: match &patterns[0] {
+                        MatchPattern::Value(value) if index != last => {
+                            Some(literal! { (ConstValue::Int(value.clone()).to_word()) })
+                        }
+                        _ => None,
+                    },
+                    block: yul::Block { statements },
+                })
+                .collect::<Vec<_>>();
+
+            return Ok(switch! {
+                switch ([yul_value])
+                [cases...]
+            });
+        }
+
+        // Ranges and alternatives are tested one after another.
+        let match_value = names::match_value_name(stmt.span.start);
+        let match_value_exp = yul::Expression::Identifier(match_value.clone());
+        let mut arms = arms.into_iter().rev();
+        let (_, mut chain) = arms.next().expect("match without arms");
+        for (patterns, statements) in arms {
+            let test = patterns
+                .iter()
+                .map(|pattern| match_pattern_test(&match_value_exp, pattern, is_signed))
+                .reduce(|left, right| expression! { or([left], [right]) })
+                .expect("match arm without patterns");
+            chain = vec![switch! {
+                switch ([test])
+                (case 1 {[statements...]})
+                (case 0 {[chain...]})
+            }];
+        }
+
+        return Ok(block_statement! {
+            (let [match_value] := [yul_value])
+            [chain...]
+        });
+    }
+
+    unreachable!()
+}
+
+/// Builds an expression that is `1` if the value matches the pattern and `0`
+/// otherwise.
+fn match_pattern_test(
+    value: &yul::Expression,
+    pattern: &MatchPattern,
+    is_signed: bool,
+) -> yul::Expression {
+    let word = |value| literal_expression! { (ConstValue::Int(value).to_word()) };
+
+    match pattern {
+        MatchPattern::Wildcard => literal_expression! { 1 },
+        MatchPattern::Value(expected) => {
+            expression! { eq([value.clone()], [word(expected.clone())]) }
+        }
+        MatchPattern::Range { start, end } if is_signed => expression! {
+            and((iszero((slt([value.clone()], [word(start.clone())])))), (slt([value.clone()], [word(end.clone())])))
+        },
+        MatchPattern::Range { start, end } => expression! {
+            and((iszero((lt([value.clone()], [word(start.clone())])))), (lt([value.clone()], [word(end.clone())])))
+        },
+    }
+}
+
 fn expr(context: &Context, stmt: &Spanned<fe::FuncStmt>) -> Result<yul::Statement, CompileError> {
     if let fe::FuncStmt::Expr { value } = &stmt.node {
         let spanned = utils::spanned_expression(&stmt.span, value);
@@ -171,8 +596,29 @@ fn expr(context: &Context, stmt: &Spanned<fe::FuncStmt>) -> Result<yul::Statemen
     unreachable!()
 }
 
-fn revert(stmt: &Spanned<fe::FuncStmt>) -> Result<yul::Statement, CompileError> {
-    if let fe::FuncStmt::Revert = &stmt.node {
+fn revert(context: &Context, stmt: &Spanned<fe::FuncStmt>) -> Result<yul::Statement, CompileError> {
+    if let fe::FuncStmt::Revert { error } = &stmt.node {
+        if let Some(Spanned {
+            node: fe::Expr::Call { args, .. },
+            ..
+        }) = error
+        {
+            let error_values = args
+                .node
+                .iter()
+                .map(|arg| expressions::call_arg(context, arg))
+                .collect::<Result<_, _>>()?;
+
+            if let Some(error) = context.get_revert(stmt) {
+                return Ok(data_operations::revert_with_error(
+                    error.to_owned(),
+                    error_values,
+                ));
+            }
+
+            return Err(CompileError::static_str("missing error definition"));
+        }
+
         return Ok(statement! { revert(0, 0) });
     }
 
@@ -204,10 +650,28 @@ fn emit(context: &Context, stmt: &Spanned<fe::FuncStmt>) -> Result<yul::Statemen
 }
 
 fn assert(context: &Context, stmt: &Spanned<fe::FuncStmt>) -> Result<yul::Statement, CompileError> {
-    if let fe::FuncStmt::Assert { test, msg: _ } = &stmt.node {
+    if let fe::FuncStmt::Assert { test, msg } = &stmt.node {
         let test = expressions::expr(context, test)?;
 
-        return Ok(statement! { if (iszero([test])) { (revert(0, 0)) } });
+        // Assertions without a message fail with the panic code of Solidity's
+        // `assert`, so that they can be told apart from input validation.
+        return Ok(match msg {
+            Some(msg) => {
+                let is_reason = matches!(
+                    context
+                        .get_expression(msg)
+                        .map(|attributes| &attributes.typ),
+                    Some(Type::String(_))
+                );
+                let msg = expressions::expr(context, msg)?;
+                if is_reason {
+                    statement! { if (iszero([test])) { (revert_with_reason([msg])) } }
+                } else {
+                    statement! { if (iszero([test])) { (revert_with_panic([msg])) } }
+                }
+            }
+            None => statement! { if (iszero([test])) { (revert_with_panic(0x01)) } },
+        });
     }
 
     unreachable!()
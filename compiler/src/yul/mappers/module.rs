@@ -2,6 +2,7 @@ use crate::errors::CompileError;
 use crate::yul::mappers::contracts;
 use fe_analyzer::Context;
 use fe_parser::ast as fe;
+use fe_parser::span::Spanned;
 use std::collections::HashMap;
 use yultsur::yul;
 
@@ -9,12 +10,23 @@ pub type YulContracts = HashMap<String, yul::Object>;
 
 /// Builds a vector of Yul contracts from a Fe module.
 pub fn module(context: &Context, module: &fe::Module) -> Result<YulContracts, CompileError> {
+    let mut mixins = HashMap::new();
+    let free_functions = module
+        .body
+        .iter()
+        .filter_map(|stmt| match &stmt.node {
+            fe::ModuleStmt::FuncDef { def } => Some(def.as_ref().clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
     module
         .body
         .iter()
         .try_fold(YulContracts::new(), |mut contracts, stmt| {
             match &stmt.node {
                 fe::ModuleStmt::TypeDef { .. } => {}
+                fe::ModuleStmt::ConstDef { .. } => {}
                 fe::ModuleStmt::ContractDef { name, .. } => {
                     // Map the set of created contract names to their Yul objects so they can be
                     // included in the Yul contract that deploys them.
@@ -26,13 +38,49 @@ pub fn module(context: &Context, module: &fe::Module) -> Result<YulContracts, Co
                         .map(|contract_name| contracts[contract_name].clone())
                         .collect::<Vec<_>>();
 
-                    let contract = contracts::contract_def(context, stmt, created_contracts)?;
+                    let contract = contracts::contract_def(
+                        context,
+                        stmt,
+                        &mixins,
+                        &free_functions,
+                        created_contracts,
+                    )?;
 
                     if contracts.insert(name.node.to_string(), contract).is_some() {
                         panic!("duplicate contract definition");
                     }
                 }
+                fe::ModuleStmt::LibraryDef { name, .. } => {
+                    // libraries are analyzed as contracts with the same span
+                    let library = Spanned {
+                        node: stmt.node.library_contract().unwrap(),
+                        span: stmt.span,
+                    };
+                    let contract = contracts::contract_def(
+                        context,
+                        &library,
+                        &HashMap::new(),
+                        &free_functions,
+                        vec![],
+                    )?;
+
+                    if contracts.insert(name.node.to_string(), contract).is_some() {
+                        panic!("duplicate contract definition");
+                    }
+                }
+                fe::ModuleStmt::MixinDef { name, body, .. } => {
+                    mixins.insert(name.node, body.as_slice());
+                }
+                fe::ModuleStmt::TraitDef { .. } => {}
+                fe::ModuleStmt::InterfaceDef { .. } => {}
                 fe::ModuleStmt::StructDef { .. } => {}
+                fe::ModuleStmt::EnumDef { .. } => {}
+                fe::ModuleStmt::FuncDef { .. } => {}
+                fe::ModuleStmt::Error(_) => {
+                    return Err(CompileError::static_str(
+                        "can not compile code with syntax errors",
+                    ))
+                }
                 fe::ModuleStmt::FromImport { .. } => unimplemented!(),
                 fe::ModuleStmt::SimpleImport { .. } => unimplemented!(),
             }
@@ -2,6 +2,7 @@ use fe_analyzer::namespace::types::{
     AbiDecodeLocation,
     AbiEncoding,
     Integer,
+    Tuple,
 };
 use yultsur::*;
 
@@ -19,6 +20,31 @@ pub fn checked_sub(size: &Integer) -> yul::Identifier {
     identifier! {(format!("checked_sub_{}", size.to_lowercase()))}
 }
 
+pub fn checked_mul(size: &Integer) -> yul::Identifier {
+    let size: &str = size.into();
+    identifier! {(format!("checked_mul_{}", size.to_lowercase()))}
+}
+
+pub fn checked_div(size: &Integer) -> yul::Identifier {
+    let size: &str = size.into();
+    identifier! {(format!("checked_div_{}", size.to_lowercase()))}
+}
+
+pub fn fixed_mul(size: &Integer) -> yul::Identifier {
+    let size: &str = size.into();
+    identifier! {(format!("fixed_mul_{}", size.to_lowercase()))}
+}
+
+pub fn fixed_div(size: &Integer) -> yul::Identifier {
+    let size: &str = size.into();
+    identifier! {(format!("fixed_div_{}", size.to_lowercase()))}
+}
+
+pub fn fixed_from_int(size: &Integer) -> yul::Identifier {
+    let size: &str = size.into();
+    identifier! {(format!("fixed_from_int_{}", size.to_lowercase()))}
+}
+
 pub fn func_name(name: &str) -> yul::Identifier {
     identifier! { (format!("$${}", name)) }
 }
@@ -27,6 +53,20 @@ pub fn var_name(name: &str) -> yul::Identifier {
     identifier! { (format!("${}", name)) }
 }
 
+/// Generates the name of a value that a `for` loop over the given target keeps
+/// track of, such as the index of the current item. Fe names can't contain
+/// dots, so it doesn't collide with any variable.
+pub fn loop_var_name(target: &str, purpose: &str) -> yul::Identifier {
+    identifier! { (format!("${}.{}", target, purpose)) }
+}
+
+/// Generates the name of the value that is compared against the patterns of
+/// a `match` statement. The position of the statement in the source keeps
+/// the names of nested statements apart.
+pub fn match_value_name(position: usize) -> yul::Identifier {
+    identifier! { (format!("$match.{}", position)) }
+}
+
 /// Generates an ABI encoding function name for a given set of types.
 pub fn encode_name<T: AbiEncoding>(types: &[T]) -> yul::Identifier {
     let mut full_name = "abi_encode".to_string();
@@ -60,6 +100,12 @@ pub fn contract_call(contract_name: &str, func_name: &str) -> yul::Identifier {
     identifier! { (name) }
 }
 
+/// Generates a library call function name for a given library and function.
+pub fn library_call(library_name: &str, func_name: &str) -> yul::Identifier {
+    let name = format!("library_{}_{}", library_name, func_name);
+    identifier! { (name) }
+}
+
 /// Generates a function name for to interact with a certain struct type
 pub fn struct_function_name(struct_name: &str, func_name: &str) -> yul::Identifier {
     let name = format!("struct_{}_{}", struct_name, func_name);
@@ -77,6 +123,28 @@ pub fn struct_getter_call(struct_name: &str, field_name: &str) -> yul::Identifie
     struct_function_name(struct_name, &format!("get_{}_ptr", field_name))
 }
 
+/// Generates a function name for hashing a certain struct type as EIP-712
+/// typed data
+pub fn struct_eip712_hash_call(struct_name: &str) -> yul::Identifier {
+    struct_function_name(struct_name, "eip712_hash")
+}
+
+/// Generates a function name to interact with a certain tuple type
+pub fn tuple_function_name(tuple: &Tuple, func_name: &str) -> yul::Identifier {
+    let name = format!("{}_{}", tuple.abi_safe_name(), func_name);
+    identifier! { (name) }
+}
+
+/// Generates a function name for creating a certain tuple type
+pub fn tuple_new_call(tuple: &Tuple) -> yul::Identifier {
+    tuple_function_name(tuple, "new")
+}
+
+/// Generates a function name for reading all items of a certain tuple type
+pub fn tuple_unpack_call(tuple: &Tuple) -> yul::Identifier {
+    tuple_function_name(tuple, "unpack")
+}
+
 #[cfg(test)]
 mod tests {
     use crate::yul::names::{
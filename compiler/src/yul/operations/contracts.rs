@@ -1,4 +1,5 @@
 use crate::yul::names;
+use crate::yul::operations::abi as abi_operations;
 use fe_analyzer::namespace::types::Contract;
 use yultsur::*;
 
@@ -14,32 +15,70 @@ pub fn call(
     expression! { [func_name]([address], [params...]) }
 }
 
-/// Executes the `create2` operation for a given contract with the given value
-/// and salt.
+/// Make a call to a function of the given library with a set of parameters.
+pub fn library_call(
+    library: &Contract,
+    func_name: &str,
+    params: Vec<yul::Expression>,
+) -> yul::Expression {
+    let func_name = names::library_call(&library.name, func_name);
+    expression! { [func_name]([params...]) }
+}
+
+/// Executes the `create2` operation for a given contract with the given value,
+/// salt and init arguments.
 pub fn create2(
     contract: &Contract,
     value: yul::Expression,
     salt: yul::Expression,
+    init_args: Vec<yul::Expression>,
 ) -> yul::Expression {
     let name = literal_expression! { (format!("\"{}\"", contract.name)) };
+    let (args_ptr, args_size) = encode_init_args(contract, init_args);
     expression! {
         contract_create2(
             (dataoffset([name.clone()])),
             (datasize([name])),
             [value],
-            [salt]
+            [salt],
+            [args_ptr],
+            [args_size]
         )
     }
 }
 
-/// Executes the `create` operation for a given contract with the given value.
-pub fn create(contract: &Contract, value: yul::Expression) -> yul::Expression {
+/// Executes the `create` operation for a given contract with the given value
+/// and init arguments.
+pub fn create(
+    contract: &Contract,
+    value: yul::Expression,
+    init_args: Vec<yul::Expression>,
+) -> yul::Expression {
     let name = literal_expression! { (format!("\"{}\"", contract.name)) };
+    let (args_ptr, args_size) = encode_init_args(contract, init_args);
     expression! {
         contract_create(
             (dataoffset([name.clone()])),
             (datasize([name])),
-            [value]
+            [value],
+            [args_ptr],
+            [args_size]
         )
     }
 }
+
+/// Returns expressions for the pointer to and size of the ABI encoded init
+/// arguments.
+fn encode_init_args(
+    contract: &Contract,
+    init_args: Vec<yul::Expression>,
+) -> (yul::Expression, yul::Expression) {
+    if contract.init_params.is_empty() {
+        return (literal_expression! { 0 }, literal_expression! { 0 });
+    }
+
+    (
+        abi_operations::encode(contract.init_params.clone(), init_args.clone()),
+        abi_operations::encode_size(contract.init_params.clone(), init_args),
+    )
+}
@@ -1,16 +1,21 @@
 use crate::yul::operations::abi as abi_operations;
+use fe_analyzer::namespace::custom_errors::CustomError;
 use fe_analyzer::namespace::events::Event;
 use fe_analyzer::namespace::types::{
+    AbiEncoding,
     Array,
+    Base,
+    DynamicArray,
     FeSized,
     FixedSize,
 };
+use fe_analyzer::Location;
 use yultsur::*;
 
 /// Loads a value of the given type from storage.
-pub fn sload<T: FeSized>(typ: T, sptr: yul::Expression) -> yul::Expression {
+pub fn sload(typ: FixedSize, sptr: yul::Expression) -> yul::Expression {
     let size = literal_expression! { (typ.size()) };
-    expression! { bytes_sloadn([sptr], [size]) }
+    sign_extend(&typ, expression! { bytes_sloadn([sptr], [size]) })
 }
 
 /// Stores a value of the given type in storage.
@@ -20,9 +25,21 @@ pub fn sstore<T: FeSized>(typ: T, sptr: yul::Expression, value: yul::Expression)
 }
 
 /// Loads a value of the given type from memory.
-pub fn mload<T: FeSized>(typ: T, mptr: yul::Expression) -> yul::Expression {
+pub fn mload(typ: FixedSize, mptr: yul::Expression) -> yul::Expression {
     let size = literal_expression! { (typ.size()) };
-    expression! { mloadn([mptr], [size]) }
+    sign_extend(&typ, expression! { mloadn([mptr], [size]) })
+}
+
+/// Sign extends a value that has been loaded from storage or memory, if its
+/// type is a signed type that is narrower than a word.
+pub fn sign_extend(typ: &FixedSize, value: yul::Expression) -> yul::Expression {
+    match typ.abi_signed_size() {
+        Some(size) => {
+            let byte = literal_expression! { (size - 1) };
+            expression! { signextend([byte], [value]) }
+        }
+        None => value,
+    }
 }
 
 /// Stores a value of the given type in memory.
@@ -65,6 +82,40 @@ pub fn mcopym<T: FeSized>(typ: T, ptr: yul::Expression) -> yul::Expression {
     expression! { mcopym([ptr], [size]) }
 }
 
+/// Copies a dynamically sized array in memory into storage.
+pub fn dyn_array_mcopys(
+    typ: DynamicArray,
+    sptr: yul::Expression,
+    mptr: yul::Expression,
+) -> yul::Statement {
+    let inner_size = literal_expression! { (typ.inner.size()) };
+    statement! { dyn_array_mcopys([mptr], [sptr], [inner_size]) }
+}
+
+/// Copies a dynamically sized array in storage into memory.
+///
+/// Returns the address of the array in memory.
+pub fn dyn_array_scopym(typ: DynamicArray, sptr: yul::Expression) -> yul::Expression {
+    let inner_size = literal_expression! { (typ.inner.size()) };
+    expression! { dyn_array_scopym([sptr], [inner_size]) }
+}
+
+/// Copies a dynamically sized array in storage to another array in storage.
+pub fn dyn_array_scopys(
+    typ: DynamicArray,
+    dest_ptr: yul::Expression,
+    origin_ptr: yul::Expression,
+) -> yul::Statement {
+    let mptr = dyn_array_scopym(typ.clone(), origin_ptr);
+    dyn_array_mcopys(typ, dest_ptr, mptr)
+}
+
+/// Copies a dynamically sized array in memory to another segment of memory.
+pub fn dyn_array_mcopym(typ: DynamicArray, ptr: yul::Expression) -> yul::Expression {
+    let inner_size = literal_expression! { (typ.inner.size()) };
+    expression! { dyn_array_mcopym([ptr], [inner_size]) }
+}
+
 /// Logs an event.
 pub fn emit_event(event: Event, vals: Vec<yul::Expression>) -> yul::Statement {
     let mut topics = vec![literal_expression! { (event.topic) }];
@@ -75,23 +126,101 @@ pub fn emit_event(event: Event, vals: Vec<yul::Expression>) -> yul::Statement {
         .map(|(index, typ)| (vals[index].to_owned(), typ))
         .unzip();
 
-    // field types will be relevant when we implement indexed array values
-    let (mut indexed_field_vals, _): (Vec<yul::Expression>, Vec<FixedSize>) = event
+    // The topics of hashed values may refer to the value more than once, so
+    // these values are bound to variables and evaluated only once.
+    let mut statements = vec![];
+    let mut indexed_field_topics = event
         .indexed_fields()
         .into_iter()
-        .map(|(index, typ)| (vals[index].to_owned(), typ))
-        .unzip();
+        .map(|(index, typ)| match typ {
+            FixedSize::Base(_) | FixedSize::Contract(_) => event_topic(typ, vals[index].to_owned()),
+            _ => {
+                let name = format!("indexed_{}", index);
+                let var = identifier! { (name) };
+                statements.push(statement! { let [var] := [vals[index].to_owned()] });
+                event_topic(typ, identifier_expression! { (name) })
+            }
+        })
+        .collect::<Vec<_>>();
 
-    let encoding = abi_operations::encode(field_types.clone(), field_vals);
-    let encoding_size = abi_operations::encode_size(field_types, vals);
+    let encoding = abi_operations::encode(field_types.clone(), field_vals.clone());
+    let encoding_size = abi_operations::encode_size(field_types, field_vals);
 
-    // for now we assume these are all base type values and therefore do not need to
-    // be hashed
-    topics.append(&mut indexed_field_vals);
+    topics.append(&mut indexed_field_topics);
 
     let log_func = identifier! { (format!("log{}", topics.len())) };
+    let log = statement! { [log_func]([encoding], [encoding_size], [topics...]) };
+    if statements.is_empty() {
+        return log;
+    }
+
+    statements.push(log);
+    yul::Statement::Block(yul::Block { statements })
+}
+
+/// Reverts with a custom error. The revert data is the error's selector
+/// followed by the encoded error fields.
+pub fn revert_with_error(error: CustomError, vals: Vec<yul::Expression>) -> yul::Statement {
+    let selector = literal_expression! { (error.selector) };
+    let encoding = abi_operations::encode(error.field_types(), vals.clone());
+    let encoding_size = abi_operations::encode_size(error.field_types(), vals);
+
+    block_statement! {
+        (let error_start := alloc_mstoren([selector], 4))
+        (let error_size := add(4, [encoding_size]))
+        (pop([encoding]))
+        (revert(error_start, error_size))
+    }
+}
+
+/// Converts the value of an indexed event field into a topic.
+///
+/// Base type values are logged as they are. Strings and byte arrays are
+/// replaced by the hash of their content and all other types by the hash of
+/// their encoding, which matches how Solidity indexes these types. The value
+/// of a hashed type is used more than once, so it should be a variable.
+fn event_topic(typ: FixedSize, val: yul::Expression) -> yul::Expression {
+    match typ {
+        FixedSize::Base(_) | FixedSize::Contract(_) => val,
+        FixedSize::String(_) | FixedSize::DynamicArray(DynamicArray { inner: Base::Byte }) => {
+            expression! { keccak256((add([val.clone()], 32)), (mload([val]))) }
+        }
+        FixedSize::Array(Array {
+            inner: Base::Byte,
+            size,
+        }) => {
+            let size = literal_expression! { (size) };
+            expression! { keccak256([val], [size]) }
+        }
+        typ => {
+            let encoding = abi_operations::encode(vec![typ.clone()], vec![val.clone()]);
+            let encoding_size = abi_operations::encode_size(vec![typ], vec![val]);
+            expression! { keccak256([encoding], [encoding_size]) }
+        }
+    }
+}
 
-    return statement! { [log_func]([encoding], [encoding_size], [topics...]) };
+/// The types of indexed event fields that are encoded before being hashed
+/// into a topic.
+pub fn event_topic_encodings(event: &Event) -> Vec<Vec<FixedSize>> {
+    event
+        .indexed_fields()
+        .into_iter()
+        .filter(|(_, typ)| {
+            !matches!(
+                typ,
+                FixedSize::Base(_)
+                    | FixedSize::Contract(_)
+                    | FixedSize::String(_)
+                    | FixedSize::DynamicArray(DynamicArray { inner: Base::Byte })
+                    | FixedSize::Array(Array {
+                        inner: Base::Byte,
+                        ..
+                    })
+            )
+        })
+        .map(|(_, typ)| vec![typ])
+        .collect()
 }
 
 /// Sums a list of expressions using nested add operations.
@@ -122,15 +251,43 @@ pub fn indexed_array(
     expression! { add([array], (mul([index], [inner_size]))) }
 }
 
+/// Finds the location of an element of a dynamically sized array. Reverts if
+/// the index is out of bounds.
+pub fn indexed_dyn_array(
+    typ: DynamicArray,
+    location: Location,
+    array: yul::Expression,
+    index: yul::Expression,
+) -> yul::Expression {
+    let inner_size = literal_expression! { (typ.inner.size()) };
+    match location {
+        Location::Storage { .. } => {
+            expression! { dyn_array_sindex([array], [index], [inner_size]) }
+        }
+        _ => expression! { dyn_array_mindex([array], [index], [inner_size]) },
+    }
+}
+
+/// Returns the number of elements in a dynamically sized array.
+pub fn dyn_array_len(location: Location, array: yul::Expression) -> yul::Expression {
+    match location {
+        Location::Storage { .. } => expression! { sload((div([array], 32))) },
+        _ => expression! { mload([array]) },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::yul::operations::data::{
         emit_event,
+        revert_with_error,
         sum,
     };
+    use fe_analyzer::namespace::custom_errors::CustomError;
     use fe_analyzer::namespace::events::Event;
     use fe_analyzer::namespace::types::{
         Base,
+        FeString,
         FixedSize,
         U256,
     };
@@ -164,6 +321,30 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_emit_event_indexed_string() {
+        let event = Event::new(
+            "MyEvent",
+            vec![FixedSize::String(FeString { max_size: 5 })],
+            vec![0],
+        );
+
+        assert_eq!(
+            emit_event(event, vec![expression! { 0x80 }]).to_string(),
+            "{ let indexed_0 := 0x80 log2(abi_encode(), add(0, 0), 0x695a9e8aa4a54f62feafe2a96f107e287ede90c04f975155faf83eacbb68d27c, keccak256(add(indexed_0, 32), mload(indexed_0))) }"
+        )
+    }
+
+    #[test]
+    fn test_revert_with_error() {
+        let error = CustomError::new("MyError", vec![FixedSize::Base(U256)]);
+
+        assert_eq!(
+            revert_with_error(error, vec![expression! { 26 }]).to_string(),
+            "{ let error_start := alloc_mstoren(0x30b1b565, 4) let error_size := add(4, add(32, 0)) pop(abi_encode_uint256(26)) revert(error_start, error_size) }"
+        )
+    }
+
     #[test]
     fn test_sum() {
         assert_eq!(
@@ -2,3 +2,4 @@ pub mod abi;
 pub mod contracts;
 pub mod data;
 pub mod structs;
+pub mod tuples;
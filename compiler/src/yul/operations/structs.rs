@@ -16,6 +16,11 @@ pub fn get_attribute(
     expression! { [function_name]([val]) }
 }
 
+pub fn eip712_hash(struct_type: &Struct, val: yul::Expression) -> yul::Expression {
+    let function_name = names::struct_eip712_hash_call(&struct_type.name);
+    expression! { [function_name]([val]) }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::yul::operations::structs;
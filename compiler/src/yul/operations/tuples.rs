@@ -0,0 +1,60 @@
+use crate::yul::names;
+use fe_analyzer::namespace::types::Tuple;
+use yultsur::*;
+
+/// Creates a new tuple in memory from the given item values.
+pub fn new(tuple: &Tuple, items: Vec<yul::Expression>) -> yul::Expression {
+    let function_name = names::tuple_new_call(tuple);
+    expression! { [function_name]([items...]) }
+}
+
+/// Declares one variable for each item of the tuple at `val`.
+pub fn unpack(
+    tuple: &Tuple,
+    targets: Vec<yul::Identifier>,
+    val: yul::Expression,
+) -> yul::Statement {
+    let function_name = names::tuple_unpack_call(tuple);
+    yul::Statement::VariableDeclaration(yul::VariableDeclaration {
+        identifiers: targets,
+        expression: Some(expression! { [function_name]([val]) }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::yul::operations::tuples;
+    use fe_analyzer::namespace::types::{
+        Base,
+        Tuple,
+        U256,
+    };
+    use yultsur::*;
+
+    fn tuple() -> Tuple {
+        Tuple {
+            items: vec![U256, Base::Bool],
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        let items = vec![
+            identifier_expression! { (1) },
+            identifier_expression! { (2) },
+        ];
+        assert_eq!(
+            tuples::new(&tuple(), items).to_string(),
+            "tuple_uint256_bool_new(1, 2)"
+        )
+    }
+
+    #[test]
+    fn test_unpack() {
+        let targets = vec![identifier! { a }, identifier! { b }];
+        assert_eq!(
+            tuples::unpack(&tuple(), targets, identifier_expression! { foo }).to_string(),
+            "let a, b := tuple_uint256_bool_unpack(foo)"
+        )
+    }
+}
@@ -1,21 +1,35 @@
 use crate::abi::utils as abi_utils;
 use crate::yul::names;
 use crate::yul::operations::abi as abi_operations;
+use fe_analyzer::namespace::erc165;
 use fe_analyzer::namespace::types::{
     AbiDecodeLocation,
     AbiEncoding,
     FixedSize,
 };
 use fe_analyzer::FunctionAttributes;
+use std::collections::HashSet;
 use yultsur::*;
 
 /// Builds a switch statement that dispatches calls to the contract.
-pub fn dispatcher(attributes: Vec<FunctionAttributes>) -> yul::Statement {
-    let arms = attributes
+///
+/// Calls that send ether to a function that isn't in `payable_functions`
+/// are reverted. If `supported_interfaces` isn't empty, the ERC-165
+/// `supportsInterface` function is dispatched as well.
+pub fn dispatcher(
+    attributes: Vec<FunctionAttributes>,
+    payable_functions: &HashSet<String>,
+    supported_interfaces: &[u32],
+) -> yul::Statement {
+    let mut arms = attributes
         .iter()
-        .map(|arm| dispatch_arm(arm.to_owned()))
+        .map(|arm| dispatch_arm(arm.to_owned(), payable_functions.contains(&arm.name)))
         .collect::<Vec<_>>();
 
+    if !supported_interfaces.is_empty() {
+        arms.push(supports_interface_arm(supported_interfaces));
+    }
+
     if arms.is_empty() {
         return statement! { pop(0) };
     } else {
@@ -26,8 +40,13 @@ pub fn dispatcher(attributes: Vec<FunctionAttributes>) -> yul::Statement {
     }
 }
 
-fn dispatch_arm(attributes: FunctionAttributes) -> yul::Case {
+fn dispatch_arm(attributes: FunctionAttributes, is_payable: bool) -> yul::Case {
     let selector = selector(&attributes.name, &attributes.param_types);
+    let value_check = if is_payable {
+        vec![]
+    } else {
+        vec![callvalue_check()]
+    };
 
     if !attributes.return_type.is_empty_tuple() {
         let selection = selection(&attributes.name, &attributes.param_types);
@@ -45,6 +64,7 @@ fn dispatch_arm(attributes: FunctionAttributes) -> yul::Case {
 
         return case! {
             case [selector] {
+                [value_check...]
                 (let raw_return := [selection])
                 ([selection_with_return])
             }
@@ -53,7 +73,40 @@ fn dispatch_arm(attributes: FunctionAttributes) -> yul::Case {
 
     let selection = selection_as_statement(&attributes.name, &attributes.param_types);
 
-    case! { case [selector] { [selection] } }
+    case! { case [selector] { [value_check...] [selection] } }
+}
+
+/// Returns whether the interface ID passed as `bytes4` is one of the supported
+/// interfaces.
+fn supports_interface_arm(supported_interfaces: &[u32]) -> yul::Case {
+    let selector = interface_id_literal(erc165::ERC165_INTERFACE_ID);
+    let is_supported = supported_interfaces
+        .iter()
+        .map(|id| {
+            let id = interface_id_literal(*id);
+            expression! { eq(interface_id, [id]) }
+        })
+        .reduce(|supported, is_id| expression! { or([supported], [is_id]) })
+        .expect("no supported interfaces");
+    let value_check = callvalue_check();
+
+    case! {
+        case [selector] {
+            ([value_check])
+            (let interface_id := shr(224, (calldataload(4))))
+            (mstore(0, [is_supported]))
+            (return(0, 32))
+        }
+    }
+}
+
+fn interface_id_literal(id: u32) -> yul::Literal {
+    literal! {(format!("0x{:08x}", id))}
+}
+
+/// Reverts if the call sends any ether.
+pub fn callvalue_check() -> yul::Statement {
+    statement! { if (callvalue()) { (revert(0, 0)) } }
 }
 
 fn selector(name: &str, params: &[FixedSize]) -> yul::Literal {
@@ -122,10 +122,33 @@ fn decode<T: AbiEncoding>(typ: T, location: AbiDecodeLocation) -> yul::Statement
         AbiType::Tuple { elems } => decode_tuple(elems, location),
     };
 
+    // values that are out of range for the type (e.g. an invalid enum variant) are
+    // rejected
+    let range_check = match typ.abi_upper_bound() {
+        Some(bound) => {
+            let bound = literal_expression! { (bound) };
+            vec![statement! { if (iszero((lt(decoded_ptr, [bound])))) { (revert(0, 0)) } }]
+        }
+        None => vec![],
+    };
+
+    // signed values must be sign extended from their size
+    let sign_check = match typ.abi_signed_size() {
+        Some(size) => {
+            let byte = literal_expression! { (size - 1) };
+            vec![
+                statement! { if (iszero((eq(decoded_ptr, (signextend([byte], decoded_ptr)))))) { (revert(0, 0)) } },
+            ]
+        }
+        None => vec![],
+    };
+
     function_definition! {
          function [func_name](start_ptr, offset) -> decoded_ptr {
             (let head_ptr := add(start_ptr, offset))
             (decoded_ptr := [decode_expr])
+            [range_check...]
+            [sign_check...]
          }
     }
 }
@@ -170,6 +193,31 @@ pub fn pack(location: AbiDecodeLocation) -> yul::Statement {
     }
 }
 
+/// Removes padding from the elements of a dynamically sized array. The packed
+/// elements are preceded by the length of the array.
+pub fn pack_dyn(location: AbiDecodeLocation) -> yul::Statement {
+    let (name, pack_name, load) = match location {
+        AbiDecodeLocation::Calldata => (
+            identifier! { abi_pack_dyn_calldata },
+            identifier! { abi_pack_calldata },
+            identifier! { calldataload },
+        ),
+        AbiDecodeLocation::Memory => (
+            identifier! { abi_pack_dyn_mem },
+            identifier! { abi_pack_mem },
+            identifier! { mload },
+        ),
+    };
+
+    function_definition! {
+        function [name](ptr, inner_data_size) -> packed_ptr {
+            (let array_size := [load](ptr))
+            (packed_ptr := alloc_mstoren(array_size, 32))
+            (pop(([pack_name]((add(ptr, 32)), array_size, inner_data_size))))
+        }
+    }
+}
+
 fn dyn_array_data_size(val: yul::Expression, inner: AbiType) -> yul::Expression {
     match inner {
         AbiType::Array { .. } => todo!(),
@@ -290,7 +338,14 @@ fn decode_dyn_array(inner: AbiType, location: AbiDecodeLocation) -> yul::Express
                     }
                 }
             } else {
-                unimplemented!("packing of dynamically sized arrays")
+                match location {
+                    AbiDecodeLocation::Calldata => {
+                        expression! { abi_pack_dyn_calldata([encoding_start], [inner_data_size]) }
+                    }
+                    AbiDecodeLocation::Memory => {
+                        expression! { abi_pack_dyn_mem([encoding_start], [inner_data_size]) }
+                    }
+                }
             }
         }
     }
@@ -362,6 +417,7 @@ mod tests {
     use fe_analyzer::namespace::types::{
         AbiDecodeLocation,
         Base,
+        Enum,
         FeString,
         U256,
     };
@@ -397,4 +453,16 @@ mod tests {
             "function abi_decode_uint256_mem(start_ptr, offset) -> decoded_ptr { let head_ptr := add(start_ptr, offset) decoded_ptr := mload(head_ptr) }"
         )
     }
+
+    #[test]
+    fn test_decode_enum_calldata() {
+        let mut color = Enum::new("Color");
+        color.add_variant("Red");
+        color.add_variant("Green");
+
+        assert_eq!(
+            decode(Base::Enum(color), AbiDecodeLocation::Calldata).to_string(),
+            "function abi_decode_Color_calldata(start_ptr, offset) -> decoded_ptr { let head_ptr := add(start_ptr, offset) decoded_ptr := calldataload(head_ptr) if iszero(lt(decoded_ptr, 2)) { revert(0, 0) } }"
+        )
+    }
 }
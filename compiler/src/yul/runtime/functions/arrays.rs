@@ -0,0 +1,101 @@
+use yultsur::*;
+
+/// Derives the byte address of the first element of a dynamically sized array
+/// in storage.
+///
+/// The address is always divisible by 32, so it points to a word.
+pub fn dyn_array_data_ptr() -> yul::Statement {
+    function_definition! {
+        function dyn_array_data_ptr(sptr) -> return_val {
+            (let ptr := avail())
+            (mstore(ptr, sptr))
+            (let hash := keccak256(ptr, 32))
+            (return_val := set_zero(248, 256, hash))
+        }
+    }
+}
+
+/// Appends an element to a dynamically sized array in storage.
+pub fn dyn_array_push() -> yul::Statement {
+    function_definition! {
+        function dyn_array_push(sptr, val, inner_size) {
+            (let len := sload((div(sptr, 32))))
+            (let elem_ptr := add((dyn_array_data_ptr(sptr)), (mul(len, inner_size))))
+            (bytes_sstoren(elem_ptr, inner_size, val))
+            (sstore((div(sptr, 32)), (add(len, 1))))
+        }
+    }
+}
+
+/// Removes the last element of a dynamically sized array in storage and
+/// returns it. Reverts if the array is empty.
+pub fn dyn_array_pop() -> yul::Statement {
+    function_definition! {
+        function dyn_array_pop(sptr, inner_size) -> val {
+            (let len := sload((div(sptr, 32))))
+            (if (iszero(len)) { (revert(0, 0)) })
+            (len := sub(len, 1))
+            (let elem_ptr := add((dyn_array_data_ptr(sptr)), (mul(len, inner_size))))
+            (val := bytes_sloadn(elem_ptr, inner_size))
+            (bytes_sstoren(elem_ptr, inner_size, 0))
+            (sstore((div(sptr, 32)), len))
+        }
+    }
+}
+
+/// Finds the byte address of an element of a dynamically sized array in
+/// storage. Reverts if the index is out of bounds.
+pub fn dyn_array_sindex() -> yul::Statement {
+    function_definition! {
+        function dyn_array_sindex(sptr, index, inner_size) -> elem_ptr {
+            (if (iszero((lt(index, (sload((div(sptr, 32)))))))) { (revert(0, 0)) })
+            (elem_ptr := add((dyn_array_data_ptr(sptr)), (mul(index, inner_size))))
+        }
+    }
+}
+
+/// Finds the address of an element of a dynamically sized array in memory.
+/// Reverts if the index is out of bounds.
+pub fn dyn_array_mindex() -> yul::Statement {
+    function_definition! {
+        function dyn_array_mindex(mptr, index, inner_size) -> elem_ptr {
+            (if (iszero((lt(index, (mload(mptr)))))) { (revert(0, 0)) })
+            (elem_ptr := add((add(mptr, 32)), (mul(index, inner_size))))
+        }
+    }
+}
+
+/// Copies a dynamically sized array in storage to a newly allocated segment of
+/// memory.
+pub fn dyn_array_scopym() -> yul::Statement {
+    function_definition! {
+        function dyn_array_scopym(sptr, inner_size) -> mptr {
+            (let len := sload((div(sptr, 32))))
+            (let data_word_ptr := div((dyn_array_data_ptr(sptr)), 32))
+            (mptr := alloc_mstoren(len, 32))
+            (pop((scopym(data_word_ptr, (mul(len, inner_size))))))
+        }
+    }
+}
+
+/// Copies a dynamically sized array in memory into storage.
+pub fn dyn_array_mcopys() -> yul::Statement {
+    function_definition! {
+        function dyn_array_mcopys(mptr, sptr, inner_size) {
+            (let len := mload(mptr))
+            (let data_word_ptr := div((dyn_array_data_ptr(sptr)), 32))
+            (sstore((div(sptr, 32)), len))
+            (mcopys((add(mptr, 32)), data_word_ptr, (mul(len, inner_size))))
+        }
+    }
+}
+
+/// Copies a dynamically sized array in memory to a newly allocated segment of
+/// memory.
+pub fn dyn_array_mcopym() -> yul::Statement {
+    function_definition! {
+        function dyn_array_mcopym(mptr, inner_size) -> ptr {
+            (ptr := mcopym(mptr, (add(32, (mul((mload(mptr)), inner_size))))))
+        }
+    }
+}
@@ -10,6 +10,9 @@ use yultsur::*;
 
 /// Builds a set of functions used to make calls to the given contract's public
 /// functions.
+///
+/// Reverts of the called contract are bubbled up to the caller. Calls also
+/// revert if the called contract returns less data than expected.
 pub fn calls(contract: Contract) -> Vec<yul::Statement> {
     let contract_name = contract.name;
     contract
@@ -53,7 +56,10 @@ pub fn calls(contract: Contract) -> Vec<yul::Statement> {
                         (let instart := alloc_mstoren([selector], 4))
                         (let insize := add(4, [encoding_size]))
                         (pop([encoding_operation]))
-                        (pop((call((gas()), addr, 0, instart, insize, 0, 0))))
+                        (if (iszero((call((gas()), addr, 0, instart, insize, 0, 0)))) {
+                            (returndatacopy(0, 0, (returndatasize())))
+                            (revert(0, (returndatasize())))
+                        })
                     }
                 }
             } else {
@@ -74,7 +80,11 @@ pub fn calls(contract: Contract) -> Vec<yul::Statement> {
                         (pop([encoding_operation]))
                         (let outsize := [decoding_size])
                         (let outstart := alloc(outsize))
-                        (pop((call((gas()), addr, 0, instart, insize, outstart, outsize))))
+                        (if (iszero((call((gas()), addr, 0, instart, insize, outstart, outsize)))) {
+                            (returndatacopy(0, 0, (returndatasize())))
+                            (revert(0, (returndatasize())))
+                        })
+                        (if (lt((returndatasize()), outsize)) { (revert(0, 0)) })
                         (return_val := [decoding_operation])
                     }
                 }
@@ -83,24 +93,139 @@ pub fn calls(contract: Contract) -> Vec<yul::Statement> {
         .collect()
 }
 
+/// Builds a set of functions used to make calls to the given library's public
+/// functions.
+///
+/// The functions run in the context of the calling contract using
+/// `delegatecall`. The library's address is left as a link placeholder in the
+/// bytecode and reverts are bubbled up to the caller.
+pub fn library_calls(library: Contract) -> Vec<yul::Statement> {
+    let library_name = library.name;
+    library
+        .functions
+        .into_iter()
+        .map(|function| {
+            // get the name of the call function and its parameters
+            let function_name = names::library_call(&library_name, &function.name);
+            let param_names = function
+                .param_types
+                .iter()
+                .map(|typ| typ.abi_name())
+                .collect::<Vec<String>>();
+            let address = {
+                let name = literal_expression! { (format!("\"{}\"", library_name)) };
+                expression! { linkersymbol([name]) }
+            };
+
+            // create a pair of identifiers and expressions for the parameters
+            let (param_idents, param_exprs): (Vec<yul::Identifier>, Vec<yul::Expression>) = (0
+                ..function.param_types.len())
+                .into_iter()
+                .map(|n| {
+                    let name = format!("val_{}", n);
+                    (identifier! { (name) }, identifier_expression! { (name) })
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .unzip();
+            // the function selector must be added to the first 4 bytes of the calldata
+            let selector = {
+                let selector = abi_utils::func_selector(&function.name, param_names);
+                literal_expression! { (selector) }
+            };
+            // the operations used to encode the parameters
+            let encoding_operation =
+                abi_operations::encode(function.param_types.clone(), param_exprs.clone());
+            // the size of the encoded data
+            let encoding_size = abi_operations::encode_size(function.param_types, param_exprs);
+
+            if function.return_type.is_empty_tuple() {
+                // there is no return data to handle
+                function_definition! {
+                    function [function_name]([param_idents...]) {
+                        (let instart := alloc_mstoren([selector], 4))
+                        (let insize := add(4, [encoding_size]))
+                        (pop([encoding_operation]))
+                        (if (iszero((delegatecall((gas()), [address], instart, insize, 0, 0)))) {
+                            (returndatacopy(0, 0, (returndatasize())))
+                            (revert(0, (returndatasize())))
+                        })
+                    }
+                }
+            } else {
+                let decoding_size =
+                    abi_operations::static_encode_size(vec![function.return_type.clone()])
+                        .expect("failed to get the static encoding size");
+                let decoding_operation = abi_operations::decode(
+                    vec![function.return_type],
+                    identifier_expression! { outstart },
+                    AbiDecodeLocation::Memory,
+                )[0]
+                .to_owned();
+                // return data must be captured and decoded
+                function_definition! {
+                    function [function_name]([param_idents...]) -> return_val {
+                        (let instart := alloc_mstoren([selector], 4))
+                        (let insize := add(4, [encoding_size]))
+                        (pop([encoding_operation]))
+                        (let outsize := [decoding_size])
+                        (let outstart := alloc(outsize))
+                        (if (iszero((delegatecall((gas()), [address], instart, insize, outstart, outsize)))) {
+                            (returndatacopy(0, 0, (returndatasize())))
+                            (revert(0, (returndatasize())))
+                        })
+                        (if (lt((returndatasize()), outsize)) { (revert(0, 0)) })
+                        (return_val := [decoding_operation])
+                    }
+                }
+            }
+        })
+        .collect()
+}
+
+/// Sends wei to an address and returns whether it succeeded. Like Solidity's
+/// `send`, the recipient only gets the stipend of 2300 gas.
+pub fn address_send() -> yul::Statement {
+    function_definition! {
+        function address_send(addr, value) -> success {
+            (success := call((mul((iszero(value)), 2300)), addr, value, 0, 0, 0, 0))
+        }
+    }
+}
+
+/// Sends wei to an address and reverts if it failed.
+pub fn address_transfer() -> yul::Statement {
+    function_definition! {
+        function address_transfer(addr, value) {
+            (if (iszero((address_send(addr, value)))) { (revert(0, 0)) })
+        }
+    }
+}
+
 /// Function that executes the `create2` operation.
+///
+/// The encoded init arguments are appended to the init code.
 pub fn create2() -> yul::Statement {
     function_definition! {
-        function contract_create2(data_ptr, data_size, value, salt) -> return_address {
-            (let mptr := alloc(data_size))
+        function contract_create2(data_ptr, data_size, value, salt, args_ptr, args_size) -> return_address {
+            (let mptr := alloc((add(data_size, (ceil32(args_size))))))
             (datacopy(mptr, data_ptr, data_size))
-            (return_address := create2(value, mptr, data_size, salt))
+            (mcopym_padded(args_ptr, (add(mptr, data_size)), args_size))
+            (return_address := create2(value, mptr, (add(data_size, args_size)), salt))
         }
     }
 }
 
 /// Function that executes the `create` operation.
+///
+/// The encoded init arguments are appended to the init code.
 pub fn create() -> yul::Statement {
     function_definition! {
-        function contract_create(data_ptr, data_size, value) -> return_address {
-            (let mptr := alloc(data_size))
+        function contract_create(data_ptr, data_size, value, args_ptr, args_size) -> return_address {
+            (let mptr := alloc((add(data_size, (ceil32(args_size))))))
             (datacopy(mptr, data_ptr, data_size))
-            (return_address := create(value, mptr, data_size))
+            (mcopym_padded(args_ptr, (add(mptr, data_size)), args_size))
+            (return_address := create(value, mptr, (add(data_size, args_size))))
         }
     }
 }
@@ -66,6 +66,17 @@ pub fn ccopym() -> yul::Statement {
     }
 }
 
+/// Copy the entire calldata to a newly allocated byte array in memory.
+pub fn msg_data() -> yul::Statement {
+    function_definition! {
+        function msg_data() -> ptr {
+            (let size := calldatasize())
+            (ptr := alloc_mstoren(size, 32))
+            (pop((ccopym(0, size))))
+        }
+    }
+}
+
 /// Copy memory to a given segment of storage.
 ///
 /// The storage pointer addresses a word.
@@ -227,9 +238,9 @@ pub fn sstoren() -> yul::Statement {
                 (add(bits_offset, bits_size)),
                 old_word
             ))
-            // get the number of bits we need to shift to get the value to the correct offset
-            (let left_shift_dist := sub((sub(256, bits_size)), bits_offset))
-            (let offset_val := shl(left_shift_dist, val))
+            // truncate the value to its size and shift it to the correct offset, so
+            // that the bits of sign extended values don't leak into other sections
+            (let offset_val := shr(bits_offset, (shl((sub(256, bits_size)), val))))
             // use or to place the new value in the zeroed out section
             (let new_word := or(zeroed_word, offset_val))
             (sstore(word_ptr, new_word))
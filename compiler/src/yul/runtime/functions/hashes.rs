@@ -0,0 +1,66 @@
+use fe_analyzer::namespace::eip712;
+use yultsur::*;
+
+/// Hashes a segment of memory with the SHA-256 precompile.
+///
+/// The hash is written to the available memory without allocating it, since
+/// it is only read once.
+pub fn sha256() -> yul::Statement {
+    function_definition! {
+        function sha256(ptr, size) -> hash {
+            (let out := avail())
+            (if (iszero((staticcall((gas()), 2, ptr, size, out, 32)))) { (revert(0, 0)) })
+            (hash := mload(out))
+        }
+    }
+}
+
+/// Computes the Keccak-256 hash of the content of a string in memory.
+pub fn string_keccak256() -> yul::Statement {
+    function_definition! {
+        function string_keccak256(s) -> hash {
+            (hash := keccak256((add(s, 32)), (mload(s))))
+        }
+    }
+}
+
+/// Computes the SHA-256 hash of the content of a string in memory.
+pub fn string_sha256() -> yul::Statement {
+    function_definition! {
+        function string_sha256(s) -> hash {
+            (hash := sha256((add(s, 32)), (mload(s))))
+        }
+    }
+}
+
+/// Computes the EIP-712 domain separator of the calling contract on the
+/// current chain, given the name and version of the signing domain as strings
+/// in memory.
+pub fn eip712_domain_separator() -> yul::Statement {
+    let domain_type_hash = literal_expression! { (eip712::domain_type_hash()) };
+    function_definition! {
+        function eip712_domain_separator(name, version) -> hash {
+            (let data := alloc(160))
+            (mstore(data, [domain_type_hash]))
+            (mstore((add(data, 32)), (string_keccak256(name))))
+            (mstore((add(data, 64)), (string_keccak256(version))))
+            (mstore((add(data, 96)), (chainid())))
+            (mstore((add(data, 128)), (address())))
+            (hash := keccak256(data, 160))
+        }
+    }
+}
+
+/// Computes the EIP-712 digest that is signed for a struct, which is the hash
+/// of `0x1901` followed by the domain separator and the hash of the struct.
+pub fn eip712_digest() -> yul::Statement {
+    function_definition! {
+        function eip712_digest(domain_separator, struct_hash) -> hash {
+            (let data := alloc(66))
+            (mstore(data, (shl(240, 0x1901))))
+            (mstore((add(data, 2)), domain_separator))
+            (mstore((add(data, 34)), struct_hash))
+            (hash := keccak256(data, 66))
+        }
+    }
+}
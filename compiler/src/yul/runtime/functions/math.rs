@@ -36,9 +36,86 @@ pub fn checked_sub_fns() -> Vec<yul::Statement> {
     ]
 }
 
+/// Return a vector of runtime functions for multiplication with
+/// over-/underflow protection
+pub fn checked_mul_fns() -> Vec<yul::Statement> {
+    vec![
+        checked_mul_unsigned(Integer::U256),
+        checked_mul_unsigned(Integer::U128),
+        checked_mul_unsigned(Integer::U64),
+        checked_mul_unsigned(Integer::U32),
+        checked_mul_unsigned(Integer::U16),
+        checked_mul_unsigned(Integer::U8),
+        checked_mul_signed(Integer::I256),
+        checked_mul_signed(Integer::I128),
+        checked_mul_signed(Integer::I64),
+        checked_mul_signed(Integer::I32),
+        checked_mul_signed(Integer::I16),
+        checked_mul_signed(Integer::I8),
+    ]
+}
+
+/// Return a vector of runtime functions for signed division with overflow
+/// protection
+pub fn checked_div_fns() -> Vec<yul::Statement> {
+    vec![
+        checked_div_signed(Integer::I256),
+        checked_div_signed(Integer::I128),
+        checked_div_signed(Integer::I64),
+        checked_div_signed(Integer::I32),
+        checked_div_signed(Integer::I16),
+        checked_div_signed(Integer::I8),
+    ]
+}
+
+/// Return a vector of runtime functions for fixed-point multiplication,
+/// division and conversion from integers with over-/underflow protection
+pub fn fixed_point_fns() -> Vec<yul::Statement> {
+    let integers = vec![
+        Integer::U256,
+        Integer::U128,
+        Integer::U64,
+        Integer::U32,
+        Integer::U16,
+        Integer::U8,
+        Integer::I256,
+        Integer::I128,
+        Integer::I64,
+        Integer::I32,
+        Integer::I16,
+        Integer::I8,
+    ];
+
+    integers
+        .into_iter()
+        .flat_map(|integer| {
+            if integer.is_signed() {
+                vec![
+                    fixed_mul_signed(integer.clone()),
+                    fixed_div_signed(integer.clone()),
+                    fixed_from_int_signed(integer),
+                ]
+            } else {
+                vec![
+                    fixed_mul_unsigned(integer.clone()),
+                    fixed_div_unsigned(integer.clone()),
+                    fixed_from_int_unsigned(integer),
+                ]
+            }
+        })
+        .collect()
+}
+
 // Return all math runtime functions
 pub fn all() -> Vec<yul::Statement> {
-    [checked_add_fns(), checked_sub_fns()].concat()
+    [
+        checked_add_fns(),
+        checked_sub_fns(),
+        checked_mul_fns(),
+        checked_div_fns(),
+        fixed_point_fns(),
+    ]
+    .concat()
 }
 
 fn checked_add_unsigned(size: Integer) -> yul::Statement {
@@ -103,6 +180,152 @@ fn checked_sub_signed(size: Integer) -> yul::Statement {
     }
 }
 
+fn checked_mul_unsigned(size: Integer) -> yul::Statement {
+    if size.is_signed() {
+        panic!("Expected unsigned integer")
+    }
+    let fn_name = names::checked_mul(&size);
+    let max_value = get_max(size);
+
+    function_definition! {
+        function [fn_name](val1, val2) -> product {
+            // overflow, if val1 != 0 and val2 > (max_value / val1)
+            (if (and((iszero((iszero(val1)))), (gt(val2, (div([max_value], val1)))))) { (revert(0, 0)) })
+            (product := mul(val1, val2))
+        }
+    }
+}
+
+fn checked_mul_signed(size: Integer) -> yul::Statement {
+    if !size.is_signed() {
+        panic!("Expected signed integer")
+    }
+    let fn_name = names::checked_mul(&size);
+    let (min_value, max_value) = get_min_max(size);
+
+    function_definition! {
+        function [fn_name](val1, val2) -> product {
+            // overflow, if val1 > 0, val2 > 0 and val1 > (max_value / val2)
+            (if (and((and((sgt(val1, 0)), (sgt(val2, 0)))), (gt(val1, (div([max_value.clone()], val2)))))) { (revert(0, 0)) })
+            // underflow, if val1 > 0, val2 < 0 and val2 < (min_value / val1)
+            (if (and((and((sgt(val1, 0)), (slt(val2, 0)))), (slt(val2, (sdiv([min_value.clone()], val1)))))) { (revert(0, 0)) })
+            // underflow, if val1 < 0, val2 > 0 and val1 < (min_value / val2)
+            (if (and((and((slt(val1, 0)), (sgt(val2, 0)))), (slt(val1, (sdiv([min_value], val2)))))) { (revert(0, 0)) })
+            // overflow, if val1 < 0, val2 < 0 and val1 < (max_value / val2)
+            (if (and((and((slt(val1, 0)), (slt(val2, 0)))), (slt(val1, (sdiv([max_value], val2)))))) { (revert(0, 0)) })
+            (product := mul(val1, val2))
+        }
+    }
+}
+
+fn checked_div_signed(size: Integer) -> yul::Statement {
+    if !size.is_signed() {
+        panic!("Expected signed integer")
+    }
+    let fn_name = names::checked_div(&size);
+    let (min_value, _) = get_min_max(size);
+
+    function_definition! {
+        function [fn_name](val1, val2) -> quotient {
+            // overflow, if val1 is the minimum value and val2 is -1
+            (if (and((eq(val1, [min_value])), (eq(val2, (not(0)))))) { (revert(0, 0)) })
+            (quotient := sdiv(val1, val2))
+        }
+    }
+}
+
+fn fixed_mul_unsigned(size: Integer) -> yul::Statement {
+    let fn_name = names::fixed_mul(&size);
+    let max_value = get_max(size);
+
+    function_definition! {
+        function [fn_name](val1, val2, scale) -> product {
+            (let full := mul(val1, val2))
+            // overflow of the unscaled product
+            (if (and((iszero((iszero(val1)))), (iszero((eq((div(full, val1)), val2)))))) { (revert(0, 0)) })
+            (product := div(full, scale))
+            (if (gt(product, [max_value])) { (revert(0, 0)) })
+        }
+    }
+}
+
+fn fixed_mul_signed(size: Integer) -> yul::Statement {
+    let fn_name = names::fixed_mul(&size);
+    let (min_value, max_value) = get_min_max(size);
+    let (word_min, _) = get_min_max(Integer::I256);
+
+    function_definition! {
+        function [fn_name](val1, val2, scale) -> product {
+            (let full := mul(val1, val2))
+            // overflow of the unscaled product
+            (if (and((iszero((iszero(val1)))), (iszero((eq((sdiv(full, val1)), val2)))))) { (revert(0, 0)) })
+            (if (and((eq(val1, (not(0)))), (eq(val2, [word_min])))) { (revert(0, 0)) })
+            (product := sdiv(full, scale))
+            (if (or((slt(product, [min_value])), (sgt(product, [max_value])))) { (revert(0, 0)) })
+        }
+    }
+}
+
+fn fixed_div_unsigned(size: Integer) -> yul::Statement {
+    let fn_name = names::fixed_div(&size);
+    let max_value = get_max(size);
+
+    function_definition! {
+        function [fn_name](val1, val2, scale) -> quotient {
+            (if (iszero(val2)) { (revert(0, 0)) })
+            (let scaled := mul(val1, scale))
+            // overflow of the scaled dividend
+            (if (iszero((eq((div(scaled, scale)), val1)))) { (revert(0, 0)) })
+            (quotient := div(scaled, val2))
+            (if (gt(quotient, [max_value])) { (revert(0, 0)) })
+        }
+    }
+}
+
+fn fixed_div_signed(size: Integer) -> yul::Statement {
+    let fn_name = names::fixed_div(&size);
+    let (min_value, max_value) = get_min_max(size);
+    let (word_min, _) = get_min_max(Integer::I256);
+
+    function_definition! {
+        function [fn_name](val1, val2, scale) -> quotient {
+            (if (iszero(val2)) { (revert(0, 0)) })
+            (let scaled := mul(val1, scale))
+            // overflow of the scaled dividend
+            (if (iszero((eq((sdiv(scaled, scale)), val1)))) { (revert(0, 0)) })
+            (if (and((eq(scaled, [word_min])), (eq(val2, (not(0)))))) { (revert(0, 0)) })
+            (quotient := sdiv(scaled, val2))
+            (if (or((slt(quotient, [min_value])), (sgt(quotient, [max_value])))) { (revert(0, 0)) })
+        }
+    }
+}
+
+fn fixed_from_int_unsigned(size: Integer) -> yul::Statement {
+    let fn_name = names::fixed_from_int(&size);
+    let max_value = get_max(size);
+
+    function_definition! {
+        function [fn_name](val, scale) -> scaled {
+            (scaled := mul(val, scale))
+            (if (iszero((eq((div(scaled, scale)), val)))) { (revert(0, 0)) })
+            (if (gt(scaled, [max_value])) { (revert(0, 0)) })
+        }
+    }
+}
+
+fn fixed_from_int_signed(size: Integer) -> yul::Statement {
+    let fn_name = names::fixed_from_int(&size);
+    let (min_value, max_value) = get_min_max(size);
+
+    function_definition! {
+        function [fn_name](val, scale) -> scaled {
+            (scaled := mul(val, scale))
+            (if (iszero((eq((sdiv(scaled, scale)), val)))) { (revert(0, 0)) })
+            (if (or((slt(scaled, [min_value])), (sgt(scaled, [max_value])))) { (revert(0, 0)) })
+        }
+    }
+}
+
 fn get_min_max(integer: Integer) -> (yul::Expression, yul::Expression) {
     let map = numeric_min_max();
 
@@ -2,10 +2,16 @@ use fe_analyzer::namespace::types::AbiDecodeLocation;
 use yultsur::*;
 
 pub mod abi;
+pub mod arrays;
 pub mod contracts;
 pub mod data;
+pub mod hashes;
 pub mod math;
+pub mod precompiles;
+pub mod reverts;
+pub mod strings;
 pub mod structs;
+pub mod tuples;
 
 /// Returns all functions that should be available during runtime.
 pub fn std() -> Vec<yul::Statement> {
@@ -15,6 +21,7 @@ pub fn std() -> Vec<yul::Statement> {
         data::alloc_mstoren(),
         data::free(),
         data::ccopym(),
+        data::msg_data(),
         data::load_data_string(),
         data::mcopys(),
         data::scopym(),
@@ -34,11 +41,41 @@ pub fn std() -> Vec<yul::Statement> {
         data::ceil32(),
         data::ternary(),
         data::set_zero(),
+        strings::mcopym_padded(),
+        strings::string_concat(),
+        strings::string_eq(),
+        strings::string_to_bytes(),
+        strings::bytes_to_string(),
+        arrays::dyn_array_data_ptr(),
+        arrays::dyn_array_push(),
+        arrays::dyn_array_pop(),
+        arrays::dyn_array_sindex(),
+        arrays::dyn_array_mindex(),
+        arrays::dyn_array_scopym(),
+        arrays::dyn_array_mcopys(),
+        arrays::dyn_array_mcopym(),
         abi::unpack(),
         abi::pack(AbiDecodeLocation::Calldata),
         abi::pack(AbiDecodeLocation::Memory),
+        abi::pack_dyn(AbiDecodeLocation::Calldata),
+        abi::pack_dyn(AbiDecodeLocation::Memory),
         contracts::create2(),
         contracts::create(),
+        contracts::address_send(),
+        contracts::address_transfer(),
+        hashes::sha256(),
+        hashes::string_keccak256(),
+        hashes::string_sha256(),
+        hashes::eip712_domain_separator(),
+        hashes::eip712_digest(),
+        precompiles::ecrecover(),
+        precompiles::modexp(),
+        precompiles::identity(),
+        precompiles::blake2f(),
+        reverts::revert_with_reason(),
+        reverts::revert_with_panic(),
+        reverts::require(),
+        reverts::require_with_reason(),
     ];
     [fns, math::all()].concat()
 }
@@ -0,0 +1,68 @@
+use yultsur::*;
+
+/// Recovers the address that signed a message hash with the ECRECOVER
+/// precompile. Returns the zero address if the signature is invalid.
+pub fn ecrecover() -> yul::Statement {
+    function_definition! {
+        function ecrecover(hash, v, r, s) -> addr {
+            (let ptr := avail())
+            (mstore(ptr, hash))
+            (mstore((add(ptr, 32)), v))
+            (mstore((add(ptr, 64)), r))
+            (mstore((add(ptr, 96)), s))
+            (let out := add(ptr, 128))
+            (mstore(out, 0))
+            (if (iszero((staticcall((gas()), 1, ptr, 128, out, 32)))) { (revert(0, 0)) })
+            (addr := mload(out))
+        }
+    }
+}
+
+/// Computes `(base ** exponent) % modulus` with the MODEXP precompile.
+pub fn modexp() -> yul::Statement {
+    function_definition! {
+        function modexp(base, exponent, modulus) -> result {
+            (let ptr := avail())
+            (mstore(ptr, 32))
+            (mstore((add(ptr, 32)), 32))
+            (mstore((add(ptr, 64)), 32))
+            (mstore((add(ptr, 96)), base))
+            (mstore((add(ptr, 128)), exponent))
+            (mstore((add(ptr, 160)), modulus))
+            (if (iszero((staticcall((gas()), 5, ptr, 192, ptr, 32)))) { (revert(0, 0)) })
+            (result := mload(ptr))
+        }
+    }
+}
+
+/// Copies a segment of memory into a newly allocated segment with the
+/// IDENTITY precompile.
+pub fn identity() -> yul::Statement {
+    function_definition! {
+        function identity(ptr, size) -> out {
+            (out := alloc(size))
+            (if (iszero((staticcall((gas()), 4, ptr, size, out, size)))) { (revert(0, 0)) })
+        }
+    }
+}
+
+/// Runs the BLAKE2 compression function F with the BLAKE2F precompile and
+/// returns the new state vector in a newly allocated segment of memory.
+///
+/// The state vector `h` is 64 bytes, the message block `m` 128 bytes and the
+/// offset counters `t` 16 bytes. Reverts if `rounds` doesn't fit in 4 bytes.
+pub fn blake2f() -> yul::Statement {
+    function_definition! {
+        function blake2f(rounds, h, m, t, f) -> out {
+            (if (gt(rounds, 0xffffffff)) { (revert(0, 0)) })
+            (out := alloc(64))
+            (let ptr := avail())
+            (mstore(ptr, (shl(224, rounds))))
+            (mcopym_padded(h, (add(ptr, 4)), 64))
+            (mcopym_padded(m, (add(ptr, 68)), 128))
+            (mcopym_padded(t, (add(ptr, 196)), 16))
+            (mstore8((add(ptr, 212)), f))
+            (if (iszero((staticcall((gas()), 9, ptr, 213, out, 64)))) { (revert(0, 0)) })
+        }
+    }
+}
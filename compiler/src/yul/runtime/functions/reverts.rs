@@ -0,0 +1,46 @@
+use yultsur::*;
+
+/// Reverts with a string reason, encoded as a call to `Error(string)` like
+/// Solidity's `require` and `revert` with a message.
+pub fn revert_with_reason() -> yul::Statement {
+    function_definition! {
+        function revert_with_reason(reason) {
+            (let size := mload(reason))
+            (let ptr := alloc_mstoren(0x08c379a0, 4))
+            (pop((alloc_mstoren(32, 32))))
+            (pop((alloc_mstoren(size, 32))))
+            (mcopym_padded((add(reason, 32)), (alloc((ceil32(size)))), size))
+            (revert(ptr, (add(68, (ceil32(size))))))
+        }
+    }
+}
+
+/// Reverts with a panic code, encoded as a call to `Panic(uint256)` like the
+/// assertion failures of Solidity.
+pub fn revert_with_panic() -> yul::Statement {
+    function_definition! {
+        function revert_with_panic(code) {
+            (let ptr := alloc_mstoren(0x4e487b71, 4))
+            (pop((alloc_mstoren(code, 32))))
+            (revert(ptr, 36))
+        }
+    }
+}
+
+/// Reverts without any data if the condition does not hold.
+pub fn require() -> yul::Statement {
+    function_definition! {
+        function require(condition) {
+            (if (iszero(condition)) { (revert(0, 0)) })
+        }
+    }
+}
+
+/// Reverts with the given reason if the condition does not hold.
+pub fn require_with_reason() -> yul::Statement {
+    function_definition! {
+        function require_with_reason(condition, reason) {
+            (if (iszero(condition)) { (revert_with_reason(reason)) })
+        }
+    }
+}
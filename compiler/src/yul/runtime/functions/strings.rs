@@ -0,0 +1,75 @@
+use yultsur::*;
+
+/// Copies a segment of memory to a given segment of memory and sets the
+/// remaining bytes of the last word to zero.
+///
+/// The destination must have enough room to be written to in whole words.
+pub fn mcopym_padded() -> yul::Statement {
+    function_definition! {
+        function mcopym_padded(ptr1, ptr2, size) {
+            (for {(let offset := 0)} (lt(offset, size)) {(offset := add(offset, 32))}
+            {
+                (mstore((add(ptr2, offset)), (mload((add(ptr1, offset))))))
+            })
+
+            (let rem := mod(size, 32))
+            (if (gt(rem, 0)) {
+                (let last := add(ptr2, (sub(size, rem))))
+                (let pad_bits := mul(8, (sub(32, rem))))
+                (mstore(last, (shl(pad_bits, (shr(pad_bits, (mload(last))))))))
+            })
+        }
+    }
+}
+
+/// Concatenates two strings in memory into a newly allocated string.
+pub fn string_concat() -> yul::Statement {
+    function_definition! {
+        function string_concat(a, b) -> ptr {
+            (let a_size := mload(a))
+            (let b_size := mload(b))
+            (ptr := alloc((add(32, (add((ceil32(a_size)), (ceil32(b_size))))))))
+            (mstore(ptr, (add(a_size, b_size))))
+            (mcopym_padded((add(a, 32)), (add(ptr, 32)), a_size))
+            (mcopym_padded((add(b, 32)), (add((add(ptr, 32)), a_size)), b_size))
+        }
+    }
+}
+
+/// Returns 1 if two strings in memory have the same content, otherwise 0.
+pub fn string_eq() -> yul::Statement {
+    function_definition! {
+        function string_eq(a, b) -> result {
+            (let a_hash := keccak256((add(a, 32)), (mload(a))))
+            (let b_hash := keccak256((add(b, 32)), (mload(b))))
+            (result := and((eq((mload(a)), (mload(b)))), (eq(a_hash, b_hash))))
+        }
+    }
+}
+
+/// Copies the content of a string in memory into a newly allocated byte array
+/// of the given size. The bytes following the content are set to zero.
+pub fn string_to_bytes() -> yul::Statement {
+    function_definition! {
+        function string_to_bytes(s, size) -> ptr {
+            (ptr := alloc((ceil32(size))))
+            (for {(let offset := 0)} (lt(offset, size)) {(offset := add(offset, 32))}
+            {
+                (mstore((add(ptr, offset)), 0))
+            })
+            (mcopym_padded((add(s, 32)), ptr, (mload(s))))
+        }
+    }
+}
+
+/// Copies a byte array of the given size in memory into a newly allocated
+/// string.
+pub fn bytes_to_string() -> yul::Statement {
+    function_definition! {
+        function bytes_to_string(b, size) -> ptr {
+            (ptr := alloc((add(32, (ceil32(size))))))
+            (mstore(ptr, size))
+            (mcopym_padded(b, (add(ptr, 32)), size))
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use crate::yul::names;
+use crate::yul::operations::data as data_operations;
+use fe_analyzer::namespace::eip712;
 use fe_analyzer::namespace::types::{
     FeSized,
     Struct,
@@ -85,6 +87,44 @@ pub fn generate_get_fn(struct_type: &Struct, field_name: &str) -> yul::Statement
     }
 }
 
+/// Generate a YUL function that computes the EIP-712 hash of an instance of
+/// `struct_type`, which is the hash of its type hash followed by its fields.
+pub fn generate_eip712_hash_fn(struct_type: &Struct) -> yul::Statement {
+    let function_name = names::struct_eip712_hash_call(&struct_type.name);
+    let type_hash = eip712::type_hash(struct_type)
+        .unwrap_or_else(|| panic!("{} can't be hashed as typed data", struct_type.name));
+    let type_hash = literal_expression! { (type_hash) };
+    let size = literal_expression! { ((struct_type.get_num_fields() + 1) * 32) };
+
+    // Each field is encoded as a word. Fields that are smaller than a word
+    // are loaded on their own, like in `generate_get_fn`, and signed values
+    // are sign-extended.
+    let copy_fields = struct_type
+        .get_field_types()
+        .iter()
+        .enumerate()
+        .map(|(index, field_type)| {
+            let offset = literal_expression! { (index * 32 + (32 - field_type.size())) };
+            let size = literal_expression! { (field_type.size()) };
+            let data_offset = literal_expression! { ((index + 1) * 32) };
+            let value = data_operations::sign_extend(
+                field_type,
+                expression! { mloadn((add(ptr, [offset])), [size]) },
+            );
+            statement! { mstore((add(data, [data_offset])), [value]) }
+        })
+        .collect::<Vec<_>>();
+
+    function_definition! {
+        function [function_name](ptr) -> return_val {
+            (let data := alloc([size.clone()]))
+            (mstore(data, [type_hash]))
+            [copy_fields...]
+            (return_val := keccak256(data, [size]))
+        }
+    }
+}
+
 /// Builds a set of functions used to interact with structs used in a contract
 pub fn struct_apis(struct_type: Struct) -> Vec<yul::Statement> {
     [
@@ -94,6 +134,11 @@ pub fn struct_apis(struct_type: Struct) -> Vec<yul::Statement> {
             .iter()
             .map(|field| generate_get_fn(&struct_type, &field))
             .collect(),
+        if struct_type.is_eip712() {
+            vec![generate_eip712_hash_fn(&struct_type)]
+        } else {
+            vec![]
+        },
     ]
     .concat()
 }
@@ -101,9 +146,11 @@ pub fn struct_apis(struct_type: Struct) -> Vec<yul::Statement> {
 #[cfg(test)]
 mod tests {
     use crate::yul::runtime::functions::structs;
+    use fe_analyzer::namespace::eip712;
     use fe_analyzer::namespace::types::{
         Base,
         FixedSize,
+        Integer,
         Struct,
     };
 
@@ -139,4 +186,19 @@ mod tests {
             "function struct_Foo_get_bar2_ptr(ptr) -> return_val { return_val := add(ptr, 63) }"
         );
     }
+
+    #[test]
+    fn test_struct_eip712_hash_generation() {
+        let mut val = Struct::new("Foo");
+        val.add_field("bar", &FixedSize::Base(Base::Numeric(Integer::U256)));
+        val.add_field("baz", &FixedSize::Base(Base::Numeric(Integer::I8)));
+        val.set_eip712();
+        assert_eq!(
+            structs::generate_eip712_hash_fn(&val).to_string(),
+            format!(
+                "function struct_Foo_eip712_hash(ptr) -> return_val {{ let data := alloc(96) mstore(data, {}) mstore(add(data, 32), mloadn(add(ptr, 0), 32)) mstore(add(data, 64), signextend(0, mloadn(add(ptr, 63), 1))) return_val := keccak256(data, 96) }}",
+                eip712::type_hash(&val).unwrap()
+            )
+        );
+    }
 }
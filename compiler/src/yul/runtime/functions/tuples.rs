@@ -0,0 +1,101 @@
+use crate::yul::names;
+use crate::yul::operations::data as data_operations;
+use fe_analyzer::namespace::types::{
+    FeSized,
+    FixedSize,
+    Tuple,
+};
+use yultsur::*;
+
+/// Generate a YUL function that can be used to create an instance of `tuple`
+pub fn generate_new_fn(tuple: &Tuple) -> yul::Statement {
+    let function_name = names::tuple_new_call(tuple);
+    let size = literal_expression! { (tuple.size()) };
+
+    let params = (0..tuple.items.len())
+        .map(|index| identifier! { (format!("item{}", index)) })
+        .collect::<Vec<_>>();
+
+    // Each item is stored in its own 32 byte word, just like struct fields.
+    let body = params
+        .iter()
+        .enumerate()
+        .map(|(index, param)| {
+            let offset = literal_expression! { (index * 32) };
+            let param = identifier_expression! { [param.to_owned()] };
+            statement! { mstore((add(return_val, [offset])), [param]) }
+        })
+        .collect::<Vec<_>>();
+
+    function_definition! {
+        function [function_name]([params...]) -> return_val {
+            (return_val := alloc([size]))
+            [body...]
+        }
+    }
+}
+
+/// Generate a YUL function that reads every item of `tuple` onto the stack
+pub fn generate_unpack_fn(tuple: &Tuple) -> yul::Statement {
+    let returns = (0..tuple.items.len())
+        .map(|index| identifier! { (format!("item{}", index)) })
+        .collect::<Vec<_>>();
+
+    let statements = returns
+        .iter()
+        .zip(tuple.items.iter())
+        .enumerate()
+        .map(|(index, (item, typ))| {
+            let offset = literal_expression! { (tuple.item_offset(index)) };
+            let value = data_operations::mload(
+                FixedSize::Base(typ.to_owned()),
+                expression! { add(ptr, [offset]) },
+            );
+            statement! { [item.to_owned()] := [value] }
+        })
+        .collect::<Vec<_>>();
+
+    yul::Statement::FunctionDefinition(yul::FunctionDefinition {
+        name: names::tuple_unpack_call(tuple),
+        parameters: vec![identifier! { ptr }],
+        returns,
+        block: yul::Block { statements },
+    })
+}
+
+/// Builds a set of functions used to interact with tuples used in a contract
+pub fn tuple_apis(tuple: Tuple) -> Vec<yul::Statement> {
+    vec![generate_new_fn(&tuple), generate_unpack_fn(&tuple)]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::yul::runtime::functions::tuples;
+    use fe_analyzer::namespace::types::{
+        Base,
+        Tuple,
+        U256,
+    };
+
+    fn tuple() -> Tuple {
+        Tuple {
+            items: vec![U256, Base::Bool],
+        }
+    }
+
+    #[test]
+    fn test_tuple_new_generation() {
+        assert_eq!(
+            tuples::generate_new_fn(&tuple()).to_string(),
+            "function tuple_uint256_bool_new(item0, item1) -> return_val { return_val := alloc(64) mstore(add(return_val, 0), item0) mstore(add(return_val, 32), item1) }"
+        )
+    }
+
+    #[test]
+    fn test_tuple_unpack_generation() {
+        assert_eq!(
+            tuples::generate_unpack_fn(&tuple()).to_string(),
+            "function tuple_uint256_bool_unpack(ptr) -> item0, item1 { item0 := mloadn(add(ptr, 0), 32) item1 := mloadn(add(ptr, 63), 1) }"
+        )
+    }
+}
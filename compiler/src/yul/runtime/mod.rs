@@ -1,6 +1,7 @@
-mod abi_dispatcher;
+pub mod abi_dispatcher;
 pub mod functions;
 
+use crate::yul::operations::data as data_operations;
 use fe_analyzer::namespace::types::{
     AbiDecodeLocation,
     Contract,
@@ -34,6 +35,18 @@ pub fn build(context: &Context, contract: &Spanned<fe::ModuleStmt>) -> Vec<yul::
                 .events
                 .iter()
                 .map(|event| event.non_indexed_field_types())
+                .chain(
+                    attributes
+                        .events
+                        .iter()
+                        .flat_map(data_operations::event_topic_encodings),
+                )
+                .collect::<Vec<_>>();
+
+            let errors_batch = attributes
+                .errors
+                .iter()
+                .map(|error| error.field_types())
                 .collect::<Vec<_>>();
 
             let contracts_batch = external_functions
@@ -42,6 +55,13 @@ pub fn build(context: &Context, contract: &Spanned<fe::ModuleStmt>) -> Vec<yul::
                 .map(|function| function.param_types)
                 .collect();
 
+            let created_contracts_batch = attributes
+                .external_contracts
+                .iter()
+                .filter(|contract| !contract.init_params.is_empty())
+                .map(|contract| contract.init_params.clone())
+                .collect();
+
             let structs_batch = attributes
                 .structs
                 .clone()
@@ -49,11 +69,20 @@ pub fn build(context: &Context, contract: &Spanned<fe::ModuleStmt>) -> Vec<yul::
                 .map(|struct_| vec![FixedSize::Struct(struct_)])
                 .collect::<Vec<Vec<_>>>();
 
+            let abi_encodings_batch = attributes
+                .abi_encodings
+                .iter()
+                .map(|typ| vec![typ.clone()])
+                .collect();
+
             let batch = [
                 public_functions_batch,
                 events_batch,
+                errors_batch,
                 contracts_batch,
+                created_contracts_batch,
                 structs_batch,
+                abi_encodings_batch,
             ]
             .concat();
             functions::abi::batch_encode(batch)
@@ -87,7 +116,19 @@ pub fn build(context: &Context, contract: &Spanned<fe::ModuleStmt>) -> Vec<yul::
                 .map(|function| (function.return_type, AbiDecodeLocation::Memory))
                 .collect();
 
-            let batch = [public_functions_batch, init_params_batch, contracts_batch].concat();
+            let abi_decodings_batch = attributes
+                .abi_decodings
+                .iter()
+                .map(|typ| (typ.clone(), AbiDecodeLocation::Memory))
+                .collect();
+
+            let batch = [
+                public_functions_batch,
+                init_params_batch,
+                contracts_batch,
+                abi_decodings_batch,
+            ]
+            .concat();
             functions::abi::batch_decode(batch)
         };
         let contract_calls = {
@@ -98,6 +139,14 @@ pub fn build(context: &Context, contract: &Spanned<fe::ModuleStmt>) -> Vec<yul::
                 .collect::<Vec<_>>()
                 .concat()
         };
+        let library_calls = {
+            attributes
+                .libraries
+                .iter()
+                .map(|library| functions::contracts::library_calls(library.to_owned()))
+                .collect::<Vec<_>>()
+                .concat()
+        };
 
         let struct_apis = attributes
             .structs
@@ -106,7 +155,23 @@ pub fn build(context: &Context, contract: &Spanned<fe::ModuleStmt>) -> Vec<yul::
             .collect::<Vec<_>>()
             .concat();
 
-        return [std, encoding, decoding, contract_calls, struct_apis].concat();
+        let tuple_apis = attributes
+            .tuples
+            .iter()
+            .map(|val| functions::tuples::tuple_apis(val.to_owned()))
+            .collect::<Vec<_>>()
+            .concat();
+
+        return [
+            std,
+            encoding,
+            decoding,
+            contract_calls,
+            library_calls,
+            struct_apis,
+            tuple_apis,
+        ]
+        .concat();
     }
 
     panic!("missing contract attributes")
@@ -131,6 +196,8 @@ pub fn build_with_abi_dispatcher(
         let mut runtime = build(context, contract);
         runtime.push(abi_dispatcher::dispatcher(
             attributes.public_functions.to_owned(),
+            &attributes.payable_functions,
+            &attributes.supported_interfaces,
         ));
 
         return runtime;
@@ -1,5 +1,6 @@
 #![cfg(feature = "solc-backend")]
 
+use fe_compiler::errors::Severity;
 use rstest::rstest;
 use std::fs;
 
@@ -45,6 +46,14 @@ use std::fs;
         "numeric_capacity_mismatch/literal_too_big.fe",
         "NumericCapacityMismatch"
     ),
+    case(
+        "numeric_capacity_mismatch/constant_overflow.fe",
+        "NumericCapacityMismatch"
+    ),
+    case(
+        "numeric_capacity_mismatch/folded_overflow.fe",
+        "NumericCapacityMismatch"
+    ),
     case("numeric_capacity_mismatch/i8_neg.fe", "NumericCapacityMismatch"),
     case("numeric_capacity_mismatch/i8_pos.fe", "NumericCapacityMismatch"),
     case("numeric_capacity_mismatch/i16_neg.fe", "NumericCapacityMismatch"),
@@ -61,10 +70,74 @@ use std::fs;
         "numeric_capacity_mismatch/literal_too_small.fe",
         "NumericCapacityMismatch"
     ),
+    case(
+        "numeric_capacity_mismatch/fixed_too_many_decimals.fe",
+        "NumericCapacityMismatch"
+    ),
     case("external_call_type_error.fe", "TypeError"),
     case("external_call_wrong_number_of_params.fe", "WrongNumberOfParams"),
     case("non_bool_and.fe", "TypeError"),
-    case("non_bool_or.fe", "TypeError")
+    case("non_bool_or.fe", "TypeError"),
+    case("enum_ordering.fe", "TypeError"),
+    case("duplicate_enum_variant.fe", "AlreadyDefined"),
+    case("undefined_enum_variant.fe", "UndefinedValue"),
+    case("tuple_destructuring_arity.fe", "TypeError"),
+    case("generic_public_function.fe", "GenericPublicFunction"),
+    case("cannot_infer_type_args.fe", "CannotInferTypeArgs"),
+    case("trait_not_implemented.fe", "TraitNotImplemented"),
+    case("init_in_mixin.fe", "InitInMixin"),
+    case("duplicate_mixin_field.fe", "AlreadyDefined"),
+    case("revert_with_wrong_types.fe", "TypeError"),
+    case("undefined_error.fe", "UndefinedValue"),
+    case("assign_to_constant.fe", "AssignToConstant"),
+    case("string_ordering.fe", "TypeError"),
+    case("push_to_memory_array.fe", "CannotMove"),
+    case("fixed_point_mod.fe", "TypeError"),
+    case("return_in_decorator.fe", "InvalidDecorator"),
+    case("payable_private_function.fe", "InvalidDecorator"),
+    case("init_return_type.fe", "TypeError"),
+    case("selfdestruct_wrong_type.fe", "TypeError"),
+    case("create_missing_init_args.fe", "WrongNumberOfParams"),
+    case("raw_call_not_unsafe.fe", "UnsafeCall"),
+    case("abi_decode_too_short.fe", "TypeError"),
+    case("modexp_wrong_number_of_params.fe", "WrongNumberOfParams"),
+    case("external_call_private_function.fe", "UndefinedValue"),
+    case("view_function_writes_storage.fe", "MutabilityViolation"),
+    case("view_function_emits_event.fe", "MutabilityViolation"),
+    case("view_function_calls_mutable_function.fe", "MutabilityViolation"),
+    case("pure_function_reads_storage.fe", "MutabilityViolation"),
+    case("pure_function_reads_context.fe", "MutabilityViolation"),
+    case("payable_view_function.fe", "InvalidDecorator"),
+    case("duplicate_return_name.fe", "AlreadyDefined"),
+    case("range_outside_for_loop.fe", "TypeError"),
+    case("for_loop_over_storage_array.fe", "CannotMove"),
+    case("non_exhaustive_match.fe", "NonExhaustiveMatch"),
+    case("non_exhaustive_integer_match.fe", "NonExhaustiveMatch"),
+    case("unreachable_match_pattern.fe", "UnreachablePattern"),
+    case("public_free_function.fe", "InvalidFreeFunction"),
+    case("free_function_reads_context.fe", "MutabilityViolation"),
+    case("library_with_field.fe", "InvalidLibrary"),
+    case("library_string_return.fe", "LibraryStringReturn"),
+    case("mismatched_type_aliases.fe", "TypeError"),
+    case("type_alias_from_integer.fe", "TypeError"),
+    case("test_with_args.fe", "InvalidDecorator"),
+    case("require_with_numeric_reason.fe", "TypeError"),
+    case("storage_ref_to_memory.fe", "TypeError"),
+    case("abi_encode_from_storage.fe", "CannotMove"),
+    case("misspelled_name.fe", "UndefinedValue"),
+    case("deprecated_with_number.fe", "InvalidDecorator"),
+    case("decorated_struct.fe", "InvalidDecorator"),
+    case("eip712_fixed_point_field.fe", "TypeError"),
+    case("eip712_untyped_struct.fe", "TypeError"),
+    case("eip712_function.fe", "InvalidDecorator"),
+    case("erc165_supports_interface_defined.fe", "AlreadyDefined"),
+    case("erc165_struct.fe", "InvalidDecorator"),
+    case("contract_invalid_decorator.fe", "InvalidDecorator"),
+    case("upgradeable_without_initializer.fe", "InvalidUpgradeable"),
+    case("upgradeable_init_writes_storage.fe", "MutabilityViolation"),
+    case("upgradeable_invalid_gap.fe", "TypeError"),
+    case("initializer_not_upgradeable.fe", "InvalidDecorator"),
+    case("private_initializer.fe", "InvalidDecorator")
 )]
 fn test_compile_errors(fixture_file: &str, expected_error: &str) {
     let src = fs::read_to_string(format!("tests/fixtures/compile_errors/{}", fixture_file))
@@ -83,3 +156,117 @@ fn test_compile_errors(fixture_file: &str, expected_error: &str) {
         ),
     }
 }
+
+#[test]
+fn test_compile_error_diagnostics() {
+    let src = fs::read_to_string("tests/fixtures/compile_errors/not_in_scope.fe")
+        .expect("Unable to read fixture file");
+
+    let diagnostics = match fe_compiler::compile(&src, false, false) {
+        Err(compile_error) => compile_error.diagnostics(),
+        _ => panic!("Compiling succeeded when it was expected to fail"),
+    };
+
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic.severity, Severity::Error);
+    assert_eq!(diagnostic.kind, "UndefinedValue");
+    assert_eq!(diagnostic.message, "undefined value");
+
+    let primary = diagnostic.labels[0].span;
+    assert_eq!(&src[primary.start..primary.end], "y");
+    assert!(src[..primary.start].ends_with("return "));
+}
+
+#[test]
+fn test_parse_error_diagnostics() {
+    let src = "contract Foo:\n    pub def bar() -> :\n        pass\n";
+
+    let diagnostics = match fe_compiler::compile(src, false, false) {
+        Err(compile_error) => compile_error.diagnostics(),
+        _ => panic!("Compiling succeeded when it was expected to fail"),
+    };
+
+    assert_eq!(diagnostics[0].kind, "ParseError");
+    assert_eq!(diagnostics[0].labels.len(), 1);
+}
+
+#[test]
+fn test_multiple_errors() {
+    let src = fs::read_to_string("tests/fixtures/compile_errors/multiple_errors.fe")
+        .expect("Unable to read fixture file");
+
+    let kinds = match fe_compiler::compile(&src, false, false) {
+        Err(compile_error) => compile_error
+            .diagnostics()
+            .into_iter()
+            .map(|diagnostic| diagnostic.kind)
+            .collect::<Vec<_>>(),
+        _ => panic!("Compiling succeeded when it was expected to fail"),
+    };
+
+    assert_eq!(
+        kinds,
+        vec!["TypeError", "UndefinedValue", "BreakWithoutLoop"]
+    );
+}
+
+#[test]
+fn test_multiple_parse_errors() {
+    let src = "contract Foo:\n    pub def bar() -> :\n        pass\n\ncontract Bar:\n    x: u256\n\ncontract Baz\n";
+
+    let diagnostics = match fe_compiler::compile(src, false, false) {
+        Err(compile_error) => compile_error.diagnostics(),
+        _ => panic!("Compiling succeeded when it was expected to fail"),
+    };
+
+    assert_eq!(diagnostics.len(), 2);
+    assert!(diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.kind == "ParseError"));
+}
+
+#[test]
+fn test_check_syntax() {
+    let src = "contract Foo:\n    pub def bar() -> :\n        pass\n\ncontract Baz\n";
+    let diagnostics = fe_compiler::check_syntax(src)
+        .expect_err("Checking succeeded when it was expected to fail")
+        .diagnostics();
+    assert_eq!(diagnostics.len(), 2);
+
+    // semantic errors aren't reported
+    let src = fs::read_to_string("tests/fixtures/compile_errors/multiple_errors.fe")
+        .expect("Unable to read fixture file");
+    assert!(fe_compiler::check_syntax(&src).is_ok());
+}
+
+#[test]
+fn test_misspelled_name_suggestion() {
+    let src = fs::read_to_string("tests/fixtures/compile_errors/misspelled_name.fe")
+        .expect("Unable to read fixture file");
+
+    let diagnostics = match fe_compiler::compile(&src, false, false) {
+        Err(compile_error) => compile_error.diagnostics(),
+        _ => panic!("Compiling succeeded when it was expected to fail"),
+    };
+
+    let suggestion = &diagnostics[0].suggestions[0];
+    assert_eq!(&src[suggestion.span.start..suggestion.span.end], "amuont");
+    assert_eq!(suggestion.replacement, "amount");
+    assert!(suggestion.machine_applicable);
+}
+
+#[test]
+fn test_integer_type_suggestion() {
+    let src = "contract Foo:\n    pub def bar() -> u8:\n        return u8(256)\n";
+
+    let diagnostics = match fe_compiler::compile(src, false, false) {
+        Err(compile_error) => compile_error.diagnostics(),
+        _ => panic!("Compiling succeeded when it was expected to fail"),
+    };
+
+    let suggestion = &diagnostics[0].suggestions[0];
+    assert_eq!(&src[suggestion.span.start..suggestion.span.end], "u8");
+    assert_eq!(suggestion.replacement, "u16");
+    assert!(!suggestion.machine_applicable);
+}
@@ -7,6 +7,7 @@ use primitive_types::{
 use rstest::rstest;
 use std::collections::BTreeMap;
 use std::iter;
+use std::str::FromStr;
 
 mod utils;
 use fe_common::utils::keccak;
@@ -73,6 +74,7 @@ fn test_assert() {
     case("for_loop_with_static_array.fe", &[], uint_token(30)),
     case("for_loop_with_break.fe", &[], uint_token(15)),
     case("for_loop_with_continue.fe", &[], uint_token(17)),
+    case("for_loop_with_range.fe", &[], uint_token(14)),
     case("while_loop_with_continue.fe", &[], uint_token(1)),
     case("while_loop.fe", &[], uint_token(3)),
     case("while_loop_with_break.fe", &[], uint_token(1)),
@@ -347,15 +349,17 @@ fn return_builtin_attributes() {
         block_gas_limit: primitive_types::U256::MAX,
     };
 
+    let value = 55555;
+    let sender = address_token("1234000000000000000000000000000000005678");
+    let state = iter::once((sender.clone().to_address().unwrap(), funded_account(value)))
+        .collect::<BTreeMap<_, _>>();
+
     with_executor_backend(
-        evm::backend::MemoryBackend::new(&vicinity, BTreeMap::new()),
+        evm::backend::MemoryBackend::new(&vicinity, state),
         &|mut executor| {
             let mut harness =
                 deploy_contract(&mut executor, "return_builtin_attributes.fe", "Foo", &[]);
-            let sender = address_token("1234000000000000000000000000000000005678");
             harness.caller = sender.clone().to_address().unwrap();
-            let value = 55555;
-            harness.value = U256::from(value);
             harness.test_function(&mut executor, "coinbase", &[], Some(&block_coinbase));
             harness.test_function(
                 &mut executor,
@@ -376,8 +380,16 @@ fn return_builtin_attributes() {
                 Some(&uint_token(block_timestamp)),
             );
             harness.test_function(&mut executor, "chainid", &[], Some(&uint_token(chain_id)));
+            harness.test_function(
+                &mut executor,
+                "block_chain_id",
+                &[],
+                Some(&uint_token(chain_id)),
+            );
             harness.test_function(&mut executor, "sender", &[], Some(&sender));
+            harness.set_value(value);
             harness.test_function(&mut executor, "value", &[], Some(&uint_token(value)));
+            harness.set_value(0);
             harness.test_function(&mut executor, "origin", &[], Some(&origin));
             harness.test_function(
                 &mut executor,
@@ -385,6 +397,20 @@ fn return_builtin_attributes() {
                 &[],
                 Some(&uint_token(gas_price)),
             );
+            harness.test_function(
+                &mut executor,
+                "sig",
+                &[],
+                Some(&ethabi::Token::Uint(U256::from_big_endian(
+                    &keccak::full_as_bytes(b"sig()")[..4],
+                ))),
+            );
+            harness.test_function(
+                &mut executor,
+                "data_length",
+                &[uint_token(1)],
+                Some(&uint_token(36)),
+            );
         },
     )
 }
@@ -509,6 +535,21 @@ fn events() {
             &[addr1.clone(), addr2],
             None,
         );
+        harness.test_function(
+            &mut executor,
+            "emit_indexed",
+            &[string_token("hello"), uint_token(26), uint_token(42)],
+            None,
+        );
+
+        // indexed strings and arrays are logged as hashes
+        let name_topic = ethabi::Token::FixedBytes(keccak::full_as_bytes(b"hello").to_vec());
+        let nums_topic = ethabi::Token::FixedBytes(
+            keccak::full_as_bytes(
+                &[U256::from(26).to_be_bytes(), U256::from(42).to_be_bytes()].concat(),
+            )
+            .to_vec(),
+        );
 
         harness.events_emitted(
             executor,
@@ -517,6 +558,7 @@ fn events() {
                 ("Bases", &[uint_token(26), addr1.clone()]),
                 ("Mix", &[uint_token(26), addr1, uint_token(42), bytes]),
                 ("Addresses", &[addr_array]),
+                ("Indexed", &[name_topic, nums_topic, uint_token(26)]),
             ],
         );
     })
@@ -536,6 +578,28 @@ fn constructor() {
     })
 }
 
+#[test]
+fn private_constructor() {
+    with_executor(&|mut executor| {
+        let owner = address_token("2000000000000000000000000000000000000002");
+        let harness = deploy_contract(
+            &mut executor,
+            "private_constructor.fe",
+            "Token",
+            &[string_token("Fe Token"), owner.clone(), uint_token(1000)],
+        );
+
+        harness.test_function(
+            &mut executor,
+            "get_name",
+            &[],
+            Some(&string_token("Fe Token")),
+        );
+        harness.test_function(&mut executor, "get_owner", &[], Some(&owner));
+        harness.test_function(&mut executor, "get_supply", &[], Some(&uint_token(1000)));
+    })
+}
+
 #[test]
 fn strings() {
     with_executor(&|mut executor| {
@@ -729,57 +793,1145 @@ fn checked_arithmetic() {
                 &[config.i_min.clone(), int_token(1)],
             );
 
-            // signed: min_value - 0 works
+            // signed: min_value - 0 works
+            harness.test_function(
+                &mut executor,
+                &format!("sub_i{}", config.size),
+                &[config.i_min.clone(), int_token(0)],
+                Some(&config.i_min),
+            );
+
+            // signed: max_value - -1 fails
+            harness.test_function_reverts(
+                &mut executor,
+                &format!("sub_i{}", config.size),
+                &[config.i_max.clone(), int_token(-1)],
+            );
+
+            // signed: max_value - -0 works
+            harness.test_function(
+                &mut executor,
+                &format!("sub_i{}", config.size),
+                &[config.i_max.clone(), int_token(-0)],
+                Some(&config.i_max),
+            );
+
+            // MULTIPLICATION
+            // unsigned: max_value * 2 fails
+            harness.test_function_reverts(
+                &mut executor,
+                &format!("mul_u{}", config.size),
+                &[config.u_max.clone(), uint_token(2)],
+            );
+
+            // unsigned: max_value * 1 works
+            harness.test_function(
+                &mut executor,
+                &format!("mul_u{}", config.size),
+                &[config.u_max.clone(), uint_token(1)],
+                Some(&config.u_max),
+            );
+
+            // signed: max_value * 2 fails
+            harness.test_function_reverts(
+                &mut executor,
+                &format!("mul_i{}", config.size),
+                &[config.i_max.clone(), int_token(2)],
+            );
+
+            // signed: max_value * -1 works
+            harness.test_function(
+                &mut executor,
+                &format!("mul_i{}", config.size),
+                &[config.i_max.clone(), int_token(-1)],
+                Some(&negate_int_token(&config.i_max)),
+            );
+
+            // signed: min_value * -1 fails
+            harness.test_function_reverts(
+                &mut executor,
+                &format!("mul_i{}", config.size),
+                &[config.i_min.clone(), int_token(-1)],
+            );
+
+            // signed: min_value * 2 fails
+            harness.test_function_reverts(
+                &mut executor,
+                &format!("mul_i{}", config.size),
+                &[config.i_min.clone(), int_token(2)],
+            );
+
+            // signed: min_value * 1 works
+            harness.test_function(
+                &mut executor,
+                &format!("mul_i{}", config.size),
+                &[config.i_min.clone(), int_token(1)],
+                Some(&config.i_min),
+            );
+
+            // DIVISION
+            // signed: min_value / -1 fails
+            harness.test_function_reverts(
+                &mut executor,
+                &format!("div_i{}", config.size),
+                &[config.i_min.clone(), int_token(-1)],
+            );
+
+            // signed: min_value / 1 works
+            harness.test_function(
+                &mut executor,
+                &format!("div_i{}", config.size),
+                &[config.i_min.clone(), int_token(1)],
+                Some(&config.i_min),
+            );
+
+            // signed: max_value / -1 works
+            harness.test_function(
+                &mut executor,
+                &format!("div_i{}", config.size),
+                &[config.i_max.clone(), int_token(-1)],
+                Some(&negate_int_token(&config.i_max)),
+            );
+        }
+    });
+}
+
+#[test]
+fn structs() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "structs.fe", "Foo", &[]);
+
+        harness.test_function(&mut executor, "create_house", &[], None);
+        harness.test_function(&mut executor, "bar", &[], Some(&uint_token(2)));
+
+        let encoded_house = [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 1, 44, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 1, 244, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
+        ];
+        let token = ethabi::Token::FixedBytes(ethabi::FixedBytes::from(encoded_house));
+        harness.test_function(&mut executor, "encode_house", &[], Some(&token));
+
+        harness.test_function(
+            &mut executor,
+            "hashed_house",
+            &[],
+            Some(&uint_token_from_dec_str(
+                "46276961562062403346660092841258592376337652487249021183958956662511039738107",
+            )),
+        );
+
+        let house = ethabi::Token::Tuple(vec![
+            uint_token(300),
+            uint_token(500),
+            uint_token(20),
+            bool_token(true),
+        ]);
+        harness.test_function(&mut executor, "set_house", &[house.clone()], None);
+        harness.test_function(&mut executor, "get_house", &[], Some(&house));
+        harness.test_function(&mut executor, "encode_stored_house", &[], Some(&token));
+        harness.test_function(
+            &mut executor,
+            "copy_stored_price",
+            &[],
+            Some(&uint_token(300)),
+        );
+    });
+}
+
+#[test]
+fn tuples() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "tuples.fe", "Foo", &[]);
+
+        harness.test_function_outputs(
+            &mut executor,
+            "divmod",
+            &[uint_token(17), uint_token(5)],
+            &[uint_token(3), uint_token(2)],
+        );
+        harness.test_function_outputs(
+            &mut executor,
+            "swap",
+            &[uint_token(42), bool_token(true)],
+            &[bool_token(true), uint_token(42)],
+        );
+        harness.test_function_outputs(
+            &mut executor,
+            "named_divmod",
+            &[uint_token(17), uint_token(5)],
+            &[uint_token(3), uint_token(2)],
+        );
+        harness.test_function(
+            &mut executor,
+            "sum_divmod",
+            &[uint_token(17), uint_token(5)],
+            Some(&uint_token(5)),
+        );
+        harness.test_function(
+            &mut executor,
+            "swapped_flag",
+            &[uint_token(42), bool_token(true)],
+            Some(&bool_token(true)),
+        );
+    })
+}
+
+#[test]
+fn generics() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "generics.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "max_u256",
+            &[uint_token(3), uint_token(42)],
+            Some(&uint_token(42)),
+        );
+        harness.test_function(
+            &mut executor,
+            "max_u8",
+            &[uint_token(7), uint_token(2)],
+            Some(&uint_token(7)),
+        );
+        harness.test_function(
+            &mut executor,
+            "pair_max",
+            &[uint_token(26), uint_token(5)],
+            Some(&uint_token(26)),
+        );
+    })
+}
+
+#[test]
+fn traits() {
+    with_executor(&|mut executor| {
+        let simple_harness = deploy_contract(&mut executor, "traits.fe", "SimpleCounter", &[]);
+        let double_harness = deploy_contract(&mut executor, "traits.fe", "DoubleCounter", &[]);
+        let user_harness = deploy_contract(&mut executor, "traits.fe", "CounterUser", &[]);
+
+        user_harness.test_function(
+            &mut executor,
+            "bump",
+            &[
+                ethabi::Token::Address(simple_harness.address),
+                uint_token(5),
+            ],
+            Some(&uint_token(5)),
+        );
+        user_harness.test_function(
+            &mut executor,
+            "bump",
+            &[
+                ethabi::Token::Address(double_harness.address),
+                uint_token(5),
+            ],
+            Some(&uint_token(10)),
+        );
+        simple_harness.test_function(&mut executor, "count", &[], Some(&uint_token(5)));
+    })
+}
+
+#[test]
+fn mixins() {
+    with_executor(&|mut executor| {
+        let mut harness = deploy_contract(&mut executor, "mixins.fe", "Vault", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "get_owner",
+            &[],
+            Some(&address_token(DEFAULT_CALLER)),
+        );
+        harness.test_function(&mut executor, "set_value", &[uint_token(26)], None);
+        harness.test_function(&mut executor, "get_value", &[], Some(&uint_token(26)));
+
+        harness.set_caller(address("2000000000000000000000000000000000000002"));
+        harness.test_function_reverts(&mut executor, "pause", &[]);
+
+        harness.set_caller(address(DEFAULT_CALLER));
+        harness.test_function(&mut executor, "pause", &[], None);
+        harness.test_function(&mut executor, "is_paused", &[], Some(&bool_token(true)));
+        harness.test_function_reverts(&mut executor, "set_value", &[uint_token(42)]);
+    })
+}
+
+#[test]
+fn decorators() {
+    with_executor(&|mut executor| {
+        let mut harness = deploy_contract(&mut executor, "decorators.fe", "Vault", &[]);
+
+        harness.test_function_reverts(&mut executor, "deposit", &[uint_token(5)]);
+        harness.test_function(&mut executor, "deposit", &[uint_token(20)], None);
+        harness.test_function(&mut executor, "get_deposits", &[], Some(&uint_token(1)));
+
+        // the post logic of `non_reentrant` runs after the body returns
+        harness.test_function(
+            &mut executor,
+            "withdraw",
+            &[uint_token(5)],
+            Some(&uint_token(15)),
+        );
+        harness.test_function(&mut executor, "is_locked", &[], Some(&bool_token(false)));
+
+        harness.set_caller(address("2000000000000000000000000000000000000002"));
+        harness.test_function_reverts(&mut executor, "withdraw", &[uint_token(5)]);
+    })
+}
+
+#[test]
+fn payable() {
+    with_funded_executor(&[(DEFAULT_CALLER, 1000)], &|mut executor| {
+        let mut harness = deploy_contract(&mut executor, "payable.fe", "Vault", &[]);
+
+        harness.set_value(300);
+        harness.test_function(&mut executor, "deposit", &[], Some(&uint_token(300)));
+
+        // functions that aren't payable reject ether
+        harness.test_function_reverts(&mut executor, "total", &[]);
+
+        harness.set_value(0);
+        harness.test_function(&mut executor, "total", &[], Some(&uint_token(300)));
+        harness.test_function(
+            &mut executor,
+            "deposit_of",
+            &[address_token(DEFAULT_CALLER)],
+            Some(&uint_token(300)),
+        );
+        harness.test_function(
+            &mut executor,
+            "balance_of",
+            &[address_token(DEFAULT_CALLER)],
+            Some(&uint_token(700)),
+        );
+    })
+}
+
+#[test]
+fn send_ether() {
+    with_funded_executor(&[(DEFAULT_CALLER, 1000)], &|mut executor| {
+        let mut harness = deploy_contract(&mut executor, "send_ether.fe", "Wallet", &[]);
+        let recipient = "2000000000000000000000000000000000000002";
+
+        harness.set_value(500);
+        harness.test_function(&mut executor, "deposit", &[], None);
+        harness.set_value(0);
+
+        harness.test_function(
+            &mut executor,
+            "withdraw",
+            &[address_token(recipient), uint_token(200)],
+            None,
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "withdraw",
+            &[address_token(recipient), uint_token(1000)],
+        );
+        harness.test_function(
+            &mut executor,
+            "try_withdraw",
+            &[address_token(recipient), uint_token(1000)],
+            Some(&bool_token(false)),
+        );
+        harness.test_function(
+            &mut executor,
+            "try_withdraw",
+            &[address_token(recipient), uint_token(100)],
+            Some(&bool_token(true)),
+        );
+        harness.test_function(
+            &mut executor,
+            "balance_of",
+            &[address_token(recipient)],
+            Some(&uint_token(300)),
+        );
+
+        harness.test_function(&mut executor, "destroy", &[address_token(recipient)], None);
+        assert_eq!(executor.balance(address(recipient)), U256::from(500));
+        assert_eq!(executor.balance(harness.address), U256::zero());
+    })
+}
+
+#[test]
+fn raw_calls() {
+    with_executor(&|mut executor| {
+        let target_harness = deploy_contract(&mut executor, "raw_calls.fe", "Target", &[]);
+        let proxy_harness = deploy_contract(&mut executor, "raw_calls.fe", "Proxy", &[]);
+        let target = ethabi::Token::Address(target_harness.address);
+
+        let call_data = |signature: &[u8], args: &[usize]| {
+            let args = args
+                .iter()
+                .flat_map(|arg| U256::from(*arg).to_be_bytes().to_vec());
+            ethabi::Token::FixedBytes(
+                keccak::full_as_bytes(signature)[..4]
+                    .iter()
+                    .cloned()
+                    .chain(args)
+                    .collect(),
+            )
+        };
+        let word =
+            |value: usize| ethabi::Token::FixedBytes(U256::from(value).to_be_bytes().to_vec());
+
+        proxy_harness.test_function(
+            &mut executor,
+            "forward",
+            &[
+                target.clone(),
+                call_data(b"set_value(uint256)", &[21]),
+                word(0),
+            ],
+            Some(&word(42)),
+        );
+        target_harness.test_function(&mut executor, "get_value", &[], Some(&uint_token(21)));
+
+        proxy_harness.test_function(
+            &mut executor,
+            "forward_static",
+            &[target.clone(), call_data(b"get_value()", &[]), word(0)],
+            Some(&bool_token(true)),
+        );
+
+        // the target's code runs with the proxy's storage
+        proxy_harness.test_function(
+            &mut executor,
+            "delegate",
+            &[target, call_data(b"set_value(uint256)", &[7]), word(0)],
+            Some(&word(14)),
+        );
+        proxy_harness.test_function(&mut executor, "get_value", &[], Some(&uint_token(7)));
+        target_harness.test_function(&mut executor, "get_value", &[], Some(&uint_token(21)));
+    })
+}
+
+#[test]
+fn abi_encode_decode() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "abi_encode_decode.fe", "Foo", &[]);
+        let maker = "2000000000000000000000000000000000000002";
+        let word = |value: U256| value.to_be_bytes().to_vec();
+
+        harness.test_function(
+            &mut executor,
+            "encode_u256",
+            &[uint_token(42)],
+            Some(&ethabi::Token::FixedBytes(word(U256::from(42)))),
+        );
+        harness.test_function(
+            &mut executor,
+            "encode_tuple",
+            &[address_token(maker), uint_token(42)],
+            Some(&ethabi::Token::FixedBytes(
+                [
+                    word(U256::from_big_endian(address(maker).as_bytes())),
+                    word(U256::from(42)),
+                ]
+                .concat(),
+            )),
+        );
+        harness.test_function(
+            &mut executor,
+            "round_trip",
+            &[address_token(maker), uint_token(26)],
+            Some(&uint_token(26)),
+        );
+
+        let order = |side: usize| {
+            ethabi::Token::FixedBytes(
+                [
+                    word(U256::from_big_endian(address(maker).as_bytes())),
+                    word(U256::from(26)),
+                    word(U256::from(side)),
+                ]
+                .concat(),
+            )
+        };
+        harness.test_function(
+            &mut executor,
+            "decode_maker",
+            &[order(1)],
+            Some(&address_token(maker)),
+        );
+        // there is no third side
+        harness.test_function_reverts(&mut executor, "decode_maker", &[order(2)]);
+    })
+}
+
+#[test]
+fn custom_errors() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "custom_errors.fe", "Foo", &[]);
+
+        harness.test_function(&mut executor, "withdraw", &[uint_token(60)], None);
+
+        let insufficient_balance = [
+            keccak::full_as_bytes(b"InsufficientBalance(uint256,uint256)")[..4].to_vec(),
+            U256::from(50).to_be_bytes().to_vec(),
+            U256::from(40).to_be_bytes().to_vec(),
+        ]
+        .concat();
+        harness.test_function_reverts_with(
+            &mut executor,
+            "withdraw",
+            &[uint_token(50)],
+            &insufficient_balance,
+        );
+
+        let unauthorized = keccak::full_as_bytes(b"Unauthorized()")[..4].to_vec();
+        harness.test_function_reverts_with(&mut executor, "fail", &[], &unauthorized);
+    })
+}
+
+#[test]
+fn constants() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "constants.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "max_supply",
+            &[],
+            Some(&uint_token(1_000_000_000)),
+        );
+        harness.test_function(
+            &mut executor,
+            "half_supply",
+            &[],
+            Some(&uint_token(500_000_000)),
+        );
+        harness.test_function(&mut executor, "min_balance", &[], Some(&int_token(-256)));
+        harness.test_function(&mut executor, "enabled", &[], Some(&bool_token(true)));
+        harness.test_function(
+            &mut executor,
+            "with_fee",
+            &[uint_token(10)],
+            Some(&uint_token(14)),
+        );
+    })
+}
+
+#[test]
+fn constant_folding() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "constant_folding.fe", "Foo", &[]);
+        let one = U256::from(10).pow(U256::from(18));
+        let topic =
+            U256::from_big_endian(&keccak::full_as_bytes(b"Transfer(address,address,uint256)"));
+
+        harness.test_function(&mut executor, "one", &[], Some(&ethabi::Token::Uint(one)));
+        harness.test_function(
+            &mut executor,
+            "scaled",
+            &[uint_token(3)],
+            Some(&ethabi::Token::Uint(one / 100 * 3)),
+        );
+        harness.test_function(
+            &mut executor,
+            "topic",
+            &[],
+            Some(&ethabi::Token::Uint(topic)),
+        );
+        harness.test_function(
+            &mut executor,
+            "hashed",
+            &[],
+            Some(&ethabi::Token::Uint(topic)),
+        );
+        harness.test_function(&mut executor, "is_large", &[], Some(&bool_token(true)));
+
+        // folded operations that wrap around or divide by zero have the same
+        // value as at runtime
+        let high_bit = U256::from(1) << 255;
+        let wrapped_pow = U256::from(3).overflowing_pow(U256::from(170)).0;
+        for (folded, runtime, args, expected) in vec![
+            (
+                "wrapped",
+                "pow",
+                vec![uint_token(2), uint_token(256)],
+                uint_token(0),
+            ),
+            (
+                "folded_shift",
+                "shift",
+                vec![uint_token(3), uint_token(255)],
+                ethabi::Token::Uint(high_bit),
+            ),
+            (
+                "folded_double_shift",
+                "shift",
+                vec![uint_token(1), uint_token(256)],
+                uint_token(0),
+            ),
+            (
+                "folded_pow",
+                "pow",
+                vec![uint_token(3), uint_token(170)],
+                ethabi::Token::Uint(wrapped_pow),
+            ),
+            (
+                "folded_div",
+                "div",
+                vec![uint_token(7), uint_token(0)],
+                uint_token(0),
+            ),
+            (
+                "folded_mod",
+                "modulo",
+                vec![uint_token(7), uint_token(0)],
+                uint_token(0),
+            ),
+            (
+                "folded_signed_div",
+                "signed_div",
+                vec![int_token(-7), int_token(0)],
+                int_token(0),
+            ),
+            (
+                "folded_signed_mod",
+                "signed_mod",
+                vec![int_token(-7), int_token(-2)],
+                int_token(-1),
+            ),
+            (
+                "folded_signed_shift",
+                "signed_shift",
+                vec![int_token(-1), int_token(255)],
+                ethabi::Token::Int(high_bit),
+            ),
+        ] {
+            harness.test_function(&mut executor, folded, &[], Some(&expected));
+            harness.test_function(&mut executor, runtime, &args, Some(&expected));
+        }
+    })
+}
+
+#[test]
+fn string_operations() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "string_operations.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "concat",
+            &[string_token("foo"), string_token("bar")],
+            Some(&string_token("foobar")),
+        );
+        harness.test_function(
+            &mut executor,
+            "greet",
+            &[string_token("fe")],
+            Some(&string_token("hello fe")),
+        );
+        harness.test_function(
+            &mut executor,
+            "length",
+            &[string_token("hello")],
+            Some(&uint_token(5)),
+        );
+        harness.test_function(
+            &mut executor,
+            "equals",
+            &[string_token("abc"), string_token("abc")],
+            Some(&bool_token(true)),
+        );
+        harness.test_function(
+            &mut executor,
+            "equals",
+            &[string_token("abc"), string_token("abd")],
+            Some(&bool_token(false)),
+        );
+        // the whole byte array is converted, including the zero padding
+        harness.test_function(
+            &mut executor,
+            "round_trip",
+            &[string_token("abc")],
+            Some(&string_token("abc\0\0\0\0\0\0\0")),
+        );
+        harness.test_function(
+            &mut executor,
+            "stored_length",
+            &[string_token("hey")],
+            Some(&uint_token(3)),
+        );
+    })
+}
+
+#[test]
+fn signed_integers() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "signed_integers.fe", "Foo", &[]);
+
+        // negative values are sign extended when they are loaded from storage
+        harness.test_function(&mut executor, "set_small", &[int_token(-5)], None);
+        harness.test_function(&mut executor, "get_small", &[], Some(&int_token(-5)));
+
+        // packed values don't overwrite their neighbours
+        harness.test_function(
+            &mut executor,
+            "set_values",
+            &[int_token(-1), int_token(7)],
+            None,
+        );
+        harness.test_function(
+            &mut executor,
+            "get_value",
+            &[uint_token(0)],
+            Some(&int_token(0)),
+        );
+        harness.test_function(
+            &mut executor,
+            "get_value",
+            &[uint_token(1)],
+            Some(&int_token(-1)),
+        );
+        harness.test_function(
+            &mut executor,
+            "get_value",
+            &[uint_token(2)],
+            Some(&int_token(7)),
+        );
+        harness.test_function(
+            &mut executor,
+            "get_value",
+            &[uint_token(3)],
+            Some(&int_token(0)),
+        );
+
+        // negative values are sign extended when they are loaded from memory
+        harness.test_function(
+            &mut executor,
+            "memory_sum",
+            &[int_token(-100), int_token(-28)],
+            Some(&int_token(-128)),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "memory_sum",
+            &[int_token(-100), int_token(-29)],
+        );
+
+        // negating the minimum value of a type overflows
+        harness.test_function(
+            &mut executor,
+            "negate_i8",
+            &[int_token(-127)],
+            Some(&int_token(127)),
+        );
+        harness.test_function_reverts(&mut executor, "negate_i8", &[int_token(-128)]);
+        harness.test_function(
+            &mut executor,
+            "negate_i16",
+            &[int_token(-32767)],
+            Some(&int_token(32767)),
+        );
+        harness.test_function_reverts(&mut executor, "negate_i16", &[int_token(-32768)]);
+
+        // inputs that are not sign extended are rejected
+        harness.test_function(
+            &mut executor,
+            "echo",
+            &[int_token(-300)],
+            Some(&int_token(-300)),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "echo",
+            &[ethabi::Token::Int(U256::from(65535))],
+        );
+    })
+}
+
+#[test]
+fn fixed_point() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "fixed_point.fe", "Foo", &[]);
+
+        harness.test_function(&mut executor, "set_rate", &[uint_token(5)], None);
+        harness.test_function(
+            &mut executor,
+            "interest",
+            &[uint_token(1000)],
+            Some(&uint_token(50)),
+        );
+        harness.test_function(
+            &mut executor,
+            "compound",
+            &[uint_token(1000), uint_token(2)],
+            Some(&uint_token(1102)),
+        );
+        harness.test_function(
+            &mut executor,
+            "half",
+            &[uint_token(7)],
+            Some(&uint_token(3)),
+        );
+        harness.test_function(
+            &mut executor,
+            "split",
+            &[uint_token(10), uint_token(4)],
+            Some(&uint_token(2)),
+        );
+        harness.test_function_reverts(&mut executor, "split", &[uint_token(10), uint_token(0)]);
+        harness.test_function(
+            &mut executor,
+            "difference",
+            &[int_token(4), int_token(1)],
+            Some(&int_token(5)),
+        );
+        harness.test_function(
+            &mut executor,
+            "difference",
+            &[int_token(-4), int_token(1)],
+            Some(&int_token(-7)),
+        );
+        harness.test_function(
+            &mut executor,
+            "is_below_one",
+            &[uint_token(1), uint_token(2)],
+            Some(&bool_token(true)),
+        );
+        harness.test_function(
+            &mut executor,
+            "is_below_one",
+            &[uint_token(3), uint_token(2)],
+            Some(&bool_token(false)),
+        );
+        harness.test_function(
+            &mut executor,
+            "round_trip",
+            &[uint_token(42)],
+            Some(&uint_token(42)),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "round_trip",
+            &[uint_token_from_dec_str(
+                "170141183460469231731687303715884105728",
+            )],
+        );
+    })
+}
+
+#[test]
+fn nested_map_values() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "nested_map_values.fe", "Foo", &[]);
+
+        let address1 = address_token("1000000000000000000000000000000000000001");
+        let address2 = address_token("2000000000000000000000000000000000000002");
+
+        // map of structs
+        harness.test_function(
+            &mut executor,
+            "open",
+            &[address1.clone(), uint_token(100)],
+            None,
+        );
+        harness.test_function(&mut executor, "lock", &[address1.clone()], None);
+        harness.test_function(
+            &mut executor,
+            "position_amount",
+            &[address1.clone()],
+            Some(&uint_token(100)),
+        );
+        harness.test_function(
+            &mut executor,
+            "position_locked",
+            &[address1.clone()],
+            Some(&bool_token(true)),
+        );
+        harness.test_function(
+            &mut executor,
+            "position_locked",
+            &[address2.clone()],
+            Some(&bool_token(false)),
+        );
+
+        // map of maps of structs
+        harness.test_function(
+            &mut executor,
+            "approve",
+            &[address1.clone(), address2.clone(), uint_token(42)],
+            None,
+        );
+        harness.test_function(
+            &mut executor,
+            "allowance",
+            &[address1.clone(), address2.clone()],
+            Some(&uint_token(42)),
+        );
+        harness.test_function(
+            &mut executor,
+            "allowance",
+            &[address2.clone(), address1.clone()],
+            Some(&uint_token(0)),
+        );
+
+        // three levels of maps
+        harness.test_function(
+            &mut executor,
+            "deposit",
+            &[
+                address1.clone(),
+                uint_token(7),
+                bool_token(true),
+                uint_token(9),
+            ],
+            None,
+        );
+        harness.test_function(
+            &mut executor,
+            "deposited",
+            &[address1.clone(), uint_token(7), bool_token(true)],
+            Some(&uint_token(9)),
+        );
+        harness.test_function(
+            &mut executor,
+            "deposited",
+            &[address1.clone(), uint_token(7), bool_token(false)],
+            Some(&uint_token(0)),
+        );
+
+        // map of dynamically sized arrays
+        harness.test_function(
+            &mut executor,
+            "record",
+            &[address1.clone(), uint_token(1)],
+            None,
+        );
+        harness.test_function(
+            &mut executor,
+            "record",
+            &[address1.clone(), uint_token(2)],
+            None,
+        );
+        harness.test_function(
+            &mut executor,
+            "record",
+            &[address2.clone(), uint_token(3)],
+            None,
+        );
+        harness.test_function(
+            &mut executor,
+            "recorded",
+            &[address1],
+            Some(&u256_dyn_array_token(&[1, 2])),
+        );
+        harness.test_function(
+            &mut executor,
+            "recorded",
+            &[address2],
+            Some(&u256_dyn_array_token(&[3])),
+        );
+    })
+}
+
+#[test]
+fn dynamic_arrays() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "dynamic_arrays.fe", "Foo", &[]);
+
+        harness.test_function(&mut executor, "length", &[], Some(&uint_token(0)));
+        harness.test_function_reverts(&mut executor, "pop", &[]);
+        harness.test_function_reverts(&mut executor, "get", &[uint_token(0)]);
+
+        harness.test_function(&mut executor, "push", &[uint_token(4)], None);
+        harness.test_function(&mut executor, "push", &[uint_token(2)], None);
+        harness.test_function(&mut executor, "push", &[uint_token(42)], None);
+        harness.test_function(&mut executor, "length", &[], Some(&uint_token(3)));
+        harness.test_function(&mut executor, "get", &[uint_token(1)], Some(&uint_token(2)));
+        harness.test_function(&mut executor, "set", &[uint_token(1), uint_token(7)], None);
+        harness.test_function(
+            &mut executor,
+            "all_items",
+            &[],
+            Some(&u256_dyn_array_token(&[4, 7, 42])),
+        );
+        harness.test_function_reverts(&mut executor, "get", &[uint_token(3)]);
+
+        harness.test_function(&mut executor, "pop", &[], Some(&uint_token(42)));
+        harness.test_function(&mut executor, "length", &[], Some(&uint_token(2)));
+
+        harness.test_function(
+            &mut executor,
+            "replace",
+            &[u256_dyn_array_token(&[1, 2, 3, 4, 5])],
+            None,
+        );
+        harness.test_function(
+            &mut executor,
+            "all_items",
+            &[],
+            Some(&u256_dyn_array_token(&[1, 2, 3, 4, 5])),
+        );
+        harness.test_function(
+            &mut executor,
+            "sum",
+            &[u256_dyn_array_token(&[10, 20, 30])],
+            Some(&uint_token(60)),
+        );
+        harness.test_function(
+            &mut executor,
+            "sum_items",
+            &[u256_dyn_array_token(&[10, 20, 30])],
+            Some(&uint_token(60)),
+        );
+        harness.test_function(
+            &mut executor,
+            "sum_items",
+            &[u256_dyn_array_token(&[])],
+            Some(&uint_token(0)),
+        );
+        harness.test_function(
+            &mut executor,
+            "push_flags",
+            &[uint_token(1), uint_token(255)],
+            Some(&u256_dyn_array_token(&[1, 255])),
+        );
+    })
+}
+
+#[test]
+fn enums() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "enums.fe", "Foo", &[]);
+
+        harness.test_function(&mut executor, "get_status", &[], Some(&uint_token(0)));
+        harness.test_function(&mut executor, "activate", &[], None);
+        harness.test_function(&mut executor, "get_status", &[], Some(&uint_token(1)));
+        harness.test_function_reverts(&mut executor, "activate", &[]);
+
+        harness.test_function(&mut executor, "set_status", &[uint_token(2)], None);
+        harness.test_function(&mut executor, "get_status", &[], Some(&uint_token(2)));
+        // there is no fourth variant
+        harness.test_function_reverts(&mut executor, "set_status", &[uint_token(3)]);
+
+        harness.test_function(
+            &mut executor,
+            "is_closed",
+            &[uint_token(2)],
+            Some(&bool_token(true)),
+        );
+        harness.test_function(
+            &mut executor,
+            "is_closed",
+            &[uint_token(0)],
+            Some(&bool_token(false)),
+        );
+
+        let account = address_token("1234000000000000000000000000000000005678");
+        harness.test_function(
+            &mut executor,
+            "get_account_status",
+            &[account.clone()],
+            Some(&uint_token(0)),
+        );
+        harness.test_function(&mut executor, "close", &[account.clone()], None);
+        harness.test_function(
+            &mut executor,
+            "get_account_status",
+            &[account],
+            Some(&uint_token(2)),
+        );
+    });
+}
+
+#[test]
+fn free_functions() {
+    with_executor(&|mut executor| {
+        let foo = deploy_contract(&mut executor, "free_functions.fe", "Foo", &[]);
+        foo.test_function(
+            &mut executor,
+            "bar",
+            &[uint_token(3), uint_token(4)],
+            Some(&uint_token(50)),
+        );
+
+        let baz = deploy_contract(&mut executor, "free_functions.fe", "Baz", &[]);
+        baz.test_function(&mut executor, "set_value", &[uint_token(7)], None);
+        baz.test_function(&mut executor, "get_value", &[], Some(&uint_token(49)));
+    });
+}
+
+#[test]
+fn library_calls() {
+    with_executor(&|mut executor| {
+        let library = deploy_contract(&mut executor, "library.fe", "Math", &[]);
+        let harness = deploy_linked_contract(
+            &mut executor,
+            "library.fe",
+            "Foo",
+            &[],
+            &[("Math", library.address)],
+        );
+
+        harness.test_function(
+            &mut executor,
+            "bar",
+            &[uint_token(3), uint_token(4)],
+            Some(&uint_token(25)),
+        );
+        harness.test_function(
+            &mut executor,
+            "divide",
+            &[uint_token(12), uint_token(4)],
+            Some(&uint_token(3)),
+        );
+        harness.test_function_reverts(&mut executor, "divide", &[uint_token(1), uint_token(0)]);
+    });
+}
+
+#[test]
+fn match_statement() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "match.fe", "Foo", &[]);
+
+        for (status, code) in &[(0, 10), (1, 20), (2, 30)] {
             harness.test_function(
                 &mut executor,
-                &format!("sub_i{}", config.size),
-                &[config.i_min.clone(), int_token(0)],
-                Some(&config.i_min),
+                "status_code",
+                &[uint_token(*status)],
+                Some(&uint_token(*code)),
             );
+        }
 
-            // signed: max_value - -1 fails
-            harness.test_function_reverts(
+        harness.test_function(&mut executor, "is_open", &[], Some(&bool_token(true)));
+        harness.test_function(&mut executor, "set_status", &[uint_token(2)], None);
+        harness.test_function(&mut executor, "is_open", &[], Some(&bool_token(false)));
+
+        for (value, bucket) in &[(0, 0), (1, 1), (9, 1), (10, 2), (99, 2), (100, 3), (255, 2)] {
+            harness.test_function(
                 &mut executor,
-                &format!("sub_i{}", config.size),
-                &[config.i_max.clone(), int_token(-1)],
+                "bucket",
+                &[uint_token(*value)],
+                Some(&uint_token(*bucket)),
             );
+        }
 
-            // signed: max_value - -0 works
+        for (value, sign) in &[(-128, -1), (-1, -1), (0, 0), (1, 1), (127, 1)] {
             harness.test_function(
                 &mut executor,
-                &format!("sub_i{}", config.size),
-                &[config.i_max.clone(), int_token(-0)],
-                Some(&config.i_max),
+                "sign",
+                &[int_token(*value)],
+                Some(&int_token(*sign)),
             );
         }
-    });
-}
-
-#[test]
-fn structs() {
-    with_executor(&|mut executor| {
-        let harness = deploy_contract(&mut executor, "structs.fe", "Foo", &[]);
-
-        harness.test_function(&mut executor, "create_house", &[], None);
-        harness.test_function(&mut executor, "bar", &[], Some(&uint_token(2)));
-
-        let encoded_house = [
-            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-            0, 1, 44, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-            0, 0, 0, 0, 1, 244, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-            0, 0, 0, 0, 0, 0, 0, 0, 0, 20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
-        ];
-        let token = ethabi::Token::FixedBytes(ethabi::FixedBytes::from(encoded_house));
-        harness.test_function(&mut executor, "encode_house", &[], Some(&token));
 
+        let values = ethabi::Token::FixedArray(
+            [3, 10, 0, 200, 9]
+                .iter()
+                .map(|value| uint_token(*value))
+                .collect(),
+        );
         harness.test_function(
             &mut executor,
-            "hashed_house",
-            &[],
-            Some(&uint_token_from_dec_str(
-                "46276961562062403346660092841258592376337652487249021183958956662511039738107",
-            )),
+            "count_small",
+            &[values],
+            Some(&uint_token(3)),
         );
     });
 }
@@ -841,6 +1993,237 @@ fn keccak() {
     });
 }
 
+#[test]
+fn hashes() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "hashes.fe", "Hashes", &[]);
+        let sha256_abc = ethabi::Token::Uint(
+            U256::from_str("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+                .unwrap(),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "keccak256_string",
+            &[string_token("abc")],
+            Some(&ethabi::Token::Uint(keccak::full_as_bytes(b"abc").into())),
+        );
+
+        let account = address("2000000000000000000000000000000000000002");
+        let encoded = [
+            U256::from_big_endian(account.as_bytes()).to_be_bytes(),
+            U256::from(42).to_be_bytes(),
+        ]
+        .concat();
+        harness.test_function(
+            &mut executor,
+            "keccak256_tuple",
+            &[ethabi::Token::Address(account), uint_token(42)],
+            Some(&ethabi::Token::Uint(keccak::full_as_bytes(&encoded).into())),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "sha256_bytes",
+            &[bytes_token("abc")],
+            Some(&sha256_abc),
+        );
+        harness.test_function(
+            &mut executor,
+            "sha256_string",
+            &[string_token("abc")],
+            Some(&sha256_abc),
+        );
+    });
+}
+
+#[test]
+fn eip712() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "eip712.fe", "Foo", &[]);
+        let hash = |content: &[u8]| keccak::full_as_bytes(content);
+        let hash_token = |content: &[u8]| ethabi::Token::Uint(hash(content).into());
+        let word = |value: U256| value.to_be_bytes().to_vec();
+        let owner = address("2000000000000000000000000000000000000002");
+        let spender = address("3000000000000000000000000000000000000003");
+
+        let permit_type_hash = hash(b"Permit(address owner,address spender,uint256 value)");
+        harness.test_function(
+            &mut executor,
+            "permit_type_hash",
+            &[],
+            Some(&ethabi::Token::Uint(permit_type_hash.into())),
+        );
+
+        let permit = [
+            permit_type_hash.to_vec(),
+            word(U256::from_big_endian(owner.as_bytes())),
+            word(U256::from_big_endian(spender.as_bytes())),
+            word(U256::from(42)),
+        ]
+        .concat();
+        harness.test_function(
+            &mut executor,
+            "permit_hash",
+            &[
+                ethabi::Token::Address(owner),
+                ethabi::Token::Address(spender),
+                uint_token(42),
+            ],
+            Some(&hash_token(&permit)),
+        );
+
+        // signed fields are sign-extended to a word
+        let vote = [
+            hash(b"Vote(uint256 proposal,int8 support)").to_vec(),
+            word(U256::from(7)),
+            vec![0xff; 32],
+        ]
+        .concat();
+        harness.test_function(
+            &mut executor,
+            "vote_hash",
+            &[uint_token(7), int_token(-1)],
+            Some(&hash_token(&vote)),
+        );
+
+        let domain = [
+            hash(
+                b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
+            )
+            .to_vec(),
+            hash(b"Foo").to_vec(),
+            hash(b"1").to_vec(),
+            word(U256::zero()),
+            word(U256::from_big_endian(harness.address.as_bytes())),
+        ]
+        .concat();
+        harness.test_function(
+            &mut executor,
+            "domain_separator",
+            &[string_token("Foo"), string_token("1")],
+            Some(&hash_token(&domain)),
+        );
+
+        let digest = [vec![0x19, 0x01], word(U256::from(1)), word(U256::from(2))].concat();
+        harness.test_function(
+            &mut executor,
+            "digest",
+            &[uint_token(1), uint_token(2)],
+            Some(&hash_token(&digest)),
+        );
+    });
+}
+
+#[test]
+fn erc165() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "erc165.fe", "Foo", &[]);
+        let selector = |signature: &str| {
+            let hash = keccak::full_as_bytes(signature.as_bytes());
+            u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]])
+        };
+        let supports = |id: u32| ethabi::Token::FixedBytes(id.to_be_bytes().to_vec());
+
+        let counter = selector("count()") ^ selector("increment(uint256)");
+        let foo = counter ^ selector("counter_interface_id()");
+
+        harness.test_function(
+            &mut executor,
+            "counter_interface_id",
+            &[],
+            Some(&uint_token(counter as usize)),
+        );
+
+        for id in [0x01ffc9a7, counter, foo].iter() {
+            harness.test_function(
+                &mut executor,
+                "supportsInterface",
+                &[supports(*id)],
+                Some(&bool_token(true)),
+            );
+        }
+        for id in [0xffffffff, 0x12345678].iter() {
+            harness.test_function(
+                &mut executor,
+                "supportsInterface",
+                &[supports(*id)],
+                Some(&bool_token(false)),
+            );
+        }
+    });
+}
+
+#[test]
+fn upgradeable() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "upgradeable.fe", "Counter", &[]);
+        let owner = address_token("2000000000000000000000000000000000000002");
+
+        harness.test_function(&mut executor, "initialize", &[owner.clone()], None);
+        harness.test_function(&mut executor, "get_owner", &[], Some(&owner));
+
+        // the initializer can only be called once
+        harness.test_function_reverts(&mut executor, "initialize", &[owner]);
+
+        harness.test_function(&mut executor, "increment", &[], None);
+        harness.test_function(&mut executor, "get_count", &[], Some(&uint_token(1)));
+    });
+}
+
+#[test]
+fn mutability() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "mutability.fe", "Foo", &[]);
+
+        harness.test_function(&mut executor, "set", &[uint_token(21)], None);
+        harness.test_function(&mut executor, "get_doubled", &[], Some(&uint_token(42)));
+        harness.test_function(
+            &mut executor,
+            "get_sender",
+            &[],
+            Some(&address_token(DEFAULT_CALLER)),
+        );
+        harness.test_function(
+            &mut executor,
+            "double",
+            &[uint_token(4)],
+            Some(&uint_token(8)),
+        );
+    });
+}
+
+#[test]
+fn precompiles() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "precompiles.fe", "Precompiles", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "modular_pow",
+            &[uint_token(3), uint_token(5), uint_token(7)],
+            Some(&uint_token(5)),
+        );
+        harness.test_function(
+            &mut executor,
+            "modular_pow",
+            &[uint_token(2), uint_token(255), uint_token(0)],
+            Some(&uint_token(0)),
+        );
+
+        let data = bytes_token("forty bytes of data that are copied over");
+        harness.test_function(&mut executor, "copy", &[data.clone()], Some(&data));
+
+        // an invalid signature recovers the zero address
+        harness.test_function(
+            &mut executor,
+            "recover",
+            &[uint_token(1), uint_token(27), uint_token(0), uint_token(0)],
+            Some(&address_token("0000000000000000000000000000000000000000")),
+        );
+    });
+}
+
 #[test]
 fn math() {
     with_executor(&|mut executor| {
@@ -1220,6 +2603,98 @@ fn external_contract() {
     })
 }
 
+#[test]
+fn type_aliases() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "type_aliases.fe", "Vault", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "deposit",
+            &[uint_token(40)],
+            Some(&uint_token(40)),
+        );
+        harness.test_function(
+            &mut executor,
+            "deposit",
+            &[uint_token(2)],
+            Some(&uint_token(42)),
+        );
+        harness.test_function(
+            &mut executor,
+            "double",
+            &[uint_token(21)],
+            Some(&uint_token(42)),
+        );
+        harness.test_function(
+            &mut executor,
+            "is_large",
+            &[uint_token(101)],
+            Some(&bool_token(true)),
+        );
+        harness.test_function(
+            &mut executor,
+            "is_large",
+            &[uint_token(100)],
+            Some(&bool_token(false)),
+        );
+        harness.test_function(
+            &mut executor,
+            "is_negative",
+            &[int_token(-3)],
+            Some(&bool_token(true)),
+        );
+    })
+}
+
+#[test]
+fn interface_calls() {
+    with_executor(&|mut executor| {
+        let wallet = deploy_contract(&mut executor, "interface.fe", "Wallet", &[]);
+        let token = deploy_contract(
+            &mut executor,
+            "interface.fe",
+            "Token",
+            &[ethabi::Token::Address(wallet.address)],
+        );
+        let token_address = ethabi::Token::Address(token.address);
+        let bob = address_token("42");
+
+        wallet.test_function(
+            &mut executor,
+            "pay",
+            &[token_address.clone(), bob.clone(), uint_token(30)],
+            Some(&bool_token(true)),
+        );
+        wallet.test_function(
+            &mut executor,
+            "balance",
+            &[token_address.clone()],
+            Some(&uint_token(70)),
+        );
+        token.test_function(
+            &mut executor,
+            "balanceOf",
+            &[bob.clone()],
+            Some(&uint_token(30)),
+        );
+
+        // the revert data of the token is passed on by the wallet
+        let insufficient_balance = [
+            keccak::full_as_bytes(b"InsufficientBalance(uint256,uint256)")[..4].to_vec(),
+            U256::from(100).to_be_bytes().to_vec(),
+            U256::from(70).to_be_bytes().to_vec(),
+        ]
+        .concat();
+        wallet.test_function_reverts_with(
+            &mut executor,
+            "pay",
+            &[token_address, bob, uint_token(100)],
+            &insufficient_balance,
+        );
+    })
+}
+
 #[test]
 fn create2_contract() {
     with_executor(&|mut executor| {
@@ -1256,6 +2731,34 @@ fn create_contract() {
     })
 }
 
+#[rstest(factory_function, case("create_foo"), case("create2_foo"))]
+fn create_contract_with_args(factory_function: &str) {
+    with_executor(&|mut executor| {
+        let factory_harness = deploy_contract(
+            &mut executor,
+            "create_contract_with_args.fe",
+            "FooFactory",
+            &[],
+        );
+
+        let foo_address = factory_harness
+            .call_function(&mut executor, factory_function, &[uint_token(26)])
+            .expect("factory did not return an address")
+            .to_address()
+            .expect("not an address");
+
+        let foo_harness = load_contract(foo_address, "create_contract_with_args.fe", "Foo");
+
+        foo_harness.test_function(
+            &mut executor,
+            "get_owner",
+            &[],
+            Some(&address_token(DEFAULT_CALLER)),
+        );
+        foo_harness.test_function(&mut executor, "get_num", &[], Some(&uint_token(26)));
+    })
+}
+
 #[rstest(
     fixture_file,
     contract_name,
@@ -1281,3 +2784,105 @@ fn self_address() {
         );
     });
 }
+
+#[test]
+fn test_functions() {
+    let src = std::fs::read_to_string("tests/fixtures/test_functions.fe").unwrap();
+    let compiled_module = fe_compiler::compile(&src, false, false).unwrap();
+    assert!(!compiled_module.contracts["Counter"]
+        .json_abi
+        .contains("test_increment"));
+
+    with_executor(&|mut executor| {
+        let harness = deploy_test_harness(&mut executor, "test_functions.fe", "Counter");
+        harness.test_function(&mut executor, "test_starts_at_zero", &[], None);
+        harness.test_function(&mut executor, "test_increment", &[], None);
+        harness.test_function_reverts(&mut executor, "test_overflow", &[]);
+    })
+}
+
+#[test]
+fn assert_require() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "assert_require.fe", "Foo", &[]);
+
+        let error = |reason: &str| {
+            [
+                keccak::full_as_bytes(b"Error(string)")[..4].to_vec(),
+                U256::from(32).to_be_bytes().to_vec(),
+                U256::from(reason.len()).to_be_bytes().to_vec(),
+                ethabi::encode(&[ethabi::Token::FixedBytes(reason.as_bytes().to_vec())]),
+            ]
+            .concat()
+        };
+        let panic = |code: u64| {
+            [
+                keccak::full_as_bytes(b"Panic(uint256)")[..4].to_vec(),
+                U256::from(code).to_be_bytes().to_vec(),
+            ]
+            .concat()
+        };
+
+        harness.test_function_reverts_with(&mut executor, "withdraw", &[uint_token(0)], &[]);
+        harness.test_function_reverts_with(
+            &mut executor,
+            "withdraw",
+            &[uint_token(1)],
+            &error("insufficient balance"),
+        );
+        harness.test_function(&mut executor, "deposit", &[uint_token(10)], None);
+        harness.test_function(&mut executor, "withdraw", &[uint_token(10)], None);
+
+        harness.test_function_reverts_with(
+            &mut executor,
+            "check",
+            &[uint_token(0)],
+            &error("value is zero"),
+        );
+        harness.test_function_reverts_with(&mut executor, "check", &[uint_token(1)], &panic(42));
+        harness.test_function_reverts_with(&mut executor, "check", &[uint_token(2)], &panic(1));
+        harness.test_function(&mut executor, "check", &[uint_token(3)], None);
+    })
+}
+
+#[test]
+fn storage_refs() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "storage_refs.fe", "Bank", &[]);
+        let owner = address_token("2000000000000000000000000000000000000002");
+
+        harness.test_function(
+            &mut executor,
+            "deposit",
+            &[owner.clone(), uint_token(40)],
+            Some(&uint_token(40)),
+        );
+        harness.test_function(
+            &mut executor,
+            "deposit",
+            &[owner.clone(), uint_token(2)],
+            Some(&uint_token(42)),
+        );
+        harness.test_function(
+            &mut executor,
+            "nonce",
+            &[owner.clone()],
+            Some(&uint_token(2)),
+        );
+        harness.test_function(&mut executor, "freeze", &[owner.clone()], None);
+        harness.test_function_reverts(&mut executor, "deposit", &[owner, uint_token(1)]);
+
+        harness.test_function(
+            &mut executor,
+            "add_to_totals",
+            &[uint_token(5)],
+            Some(&uint_token(15)),
+        );
+        harness.test_function(
+            &mut executor,
+            "add_to_totals",
+            &[uint_token(5)],
+            Some(&uint_token(30)),
+        );
+    })
+}
@@ -247,6 +247,36 @@ fn test_runtime_ternary() {
     })
 }
 
+#[test]
+fn test_runtime_strings() {
+    with_executor(&|mut executor| {
+        test_runtime_functions(
+            &mut executor,
+            functions::std(),
+            statements! {
+                (let a := alloc(64))
+                (mstore(a, 3))
+                (mstore((add(a, 32)), 0x6162630000000000000000000000000000000000000000000000000000000000))
+
+                (let b := alloc(64))
+                (mstore(b, 2))
+                (mstore((add(b, 32)), 0x6465000000000000000000000000000000000000000000000000000000000000))
+
+                (let c := string_concat(a, b))
+                [assert_eq!((mload(c)), 5)]
+                [assert_eq!((mload((add(c, 32)))), 0x6162636465000000000000000000000000000000000000000000000000000000)]
+
+                (let d := bytes_to_string((add(c, 32)), 5))
+                [assert_eq!((string_eq(c, d)), 1)]
+                [assert_eq!((string_eq(a, b)), 0)]
+
+                (let e := string_to_bytes(a, 10))
+                [assert_eq!((mload(e)), 0x6162630000000000000000000000000000000000000000000000000000000000)]
+            },
+        );
+    })
+}
+
 #[test]
 fn test_runtime_abi_unpack() {
     with_executor(&|mut executor| {
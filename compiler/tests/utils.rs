@@ -1,12 +1,19 @@
 #![cfg(feature = "solc-backend")]
 use evm_runtime::{
+    ExitError,
     ExitReason,
+    ExitSucceed,
     Handler,
 };
 use fe_compiler as compiler;
 use primitive_types::{
     H160,
     U256,
+    U512,
+};
+use sha2::{
+    Digest,
+    Sha256,
 };
 use std::collections::BTreeMap;
 use std::fs;
@@ -71,7 +78,17 @@ impl ContractHarness {
             .encode_input(input)
             .expect("Unable to encode input");
 
-        executor.call(self.address, None, input, None, false, context)
+        let transfer = if self.value.is_zero() {
+            None
+        } else {
+            Some(evm::Transfer {
+                source: self.caller,
+                target: self.address,
+                value: self.value,
+            })
+        };
+
+        executor.call(self.address, transfer, input, None, false, context)
     }
 
     pub fn test_function(
@@ -106,6 +123,28 @@ impl ContractHarness {
         }
     }
 
+    /// Tests a function that returns several values, such as a tuple.
+    pub fn test_function_outputs(
+        &self,
+        executor: &mut Executor,
+        name: &str,
+        input: &[ethabi::Token],
+        outputs: &[ethabi::Token],
+    ) {
+        let function = &self.abi.functions[name][0];
+
+        match self.capture_call(executor, name, &input) {
+            evm::Capture::Exit((ExitReason::Succeed(_), output)) => {
+                let actual_outputs = function
+                    .decode_output(&output)
+                    .expect(&format!("unable to decode output: {:?}", &output));
+                assert_eq!(outputs, &actual_outputs[..])
+            }
+            evm::Capture::Exit((reason, _)) => panic!("failed to run \"{}\": {:?}", name, reason),
+            _ => panic!("trap"),
+        }
+    }
+
     pub fn test_function_reverts(
         &self,
         executor: &mut Executor,
@@ -118,6 +157,21 @@ impl ContractHarness {
         }
     }
 
+    pub fn test_function_reverts_with(
+        &self,
+        executor: &mut Executor,
+        name: &str,
+        input: &[ethabi::Token],
+        revert_data: &[u8],
+    ) {
+        match self.capture_call(executor, name, input) {
+            evm::Capture::Exit((ExitReason::Revert(_), output)) => {
+                assert_eq!(output, revert_data, "unexpected revert data")
+            }
+            _ => panic!("function did not revert"),
+        }
+    }
+
     // Executor must be passed by value to get emitted events.
     pub fn events_emitted(&self, executor: Executor, events: &[(&str, &[ethabi::Token])]) {
         let raw_logs = executor
@@ -158,10 +212,21 @@ impl ContractHarness {
     pub fn set_caller(&mut self, caller: H160) {
         self.caller = caller;
     }
+
+    /// Sets the amount of wei that is sent along with the following calls.
+    pub fn set_value(&mut self, value: usize) {
+        self.value = U256::from(value);
+    }
 }
 
 #[allow(dead_code)]
 pub fn with_executor(test: &dyn Fn(Executor)) {
+    with_funded_executor(&[], test)
+}
+
+/// Runs the test with the given accounts each holding a balance of wei.
+#[allow(dead_code)]
+pub fn with_funded_executor(balances: &[(&str, usize)], test: &dyn Fn(Executor)) {
     let vicinity = evm::backend::MemoryVicinity {
         gas_price: U256::zero(),
         origin: H160::zero(),
@@ -173,26 +238,103 @@ pub fn with_executor(test: &dyn Fn(Executor)) {
         block_difficulty: U256::zero(),
         block_gas_limit: primitive_types::U256::MAX,
     };
-    let state: BTreeMap<primitive_types::H160, evm::backend::MemoryAccount> = BTreeMap::new();
+    let state = balances
+        .iter()
+        .map(|(account, balance)| (address(account), funded_account(*balance)))
+        .collect::<BTreeMap<_, _>>();
     let backend = evm::backend::MemoryBackend::new(&vicinity, state);
 
     with_executor_backend(backend, test)
 }
 
+#[allow(dead_code)]
+pub fn funded_account(balance: usize) -> evm::backend::MemoryAccount {
+    evm::backend::MemoryAccount {
+        nonce: U256::zero(),
+        balance: U256::from(balance),
+        storage: BTreeMap::new(),
+        code: vec![],
+    }
+}
+
 #[allow(dead_code)]
 pub fn with_executor_backend(backend: evm::backend::MemoryBackend, test: &dyn Fn(Executor)) {
     let config = evm::Config::istanbul();
-    let executor = evm::executor::StackExecutor::new(&backend, usize::max_value(), &config);
+    let executor = evm::executor::StackExecutor::new_with_precompile(
+        &backend,
+        usize::max_value(),
+        &config,
+        precompiles,
+    );
 
     test(executor)
 }
 
+/// The precompiled contracts that tests rely on. Calls to other precompile
+/// addresses go to empty accounts.
+#[allow(clippy::type_complexity)]
+fn precompiles(
+    address: H160,
+    input: &[u8],
+    _target_gas: Option<usize>,
+) -> Option<Result<(ExitSucceed, Vec<u8>, usize), ExitError>> {
+    let output = if address == H160::from_low_u64_be(2) {
+        Sha256::digest(input).to_vec()
+    } else if address == H160::from_low_u64_be(4) {
+        input.to_vec()
+    } else if address == H160::from_low_u64_be(5) {
+        modexp(input).to_be_bytes().to_vec()
+    } else {
+        return None;
+    };
+
+    Some(Ok((ExitSucceed::Returned, output, 0)))
+}
+
+/// Computes the MODEXP precompile for 32 byte operands, which is the only
+/// form the compiler emits.
+fn modexp(input: &[u8]) -> U256 {
+    let base = U256::from_big_endian(&input[96..128]);
+    let exponent = U256::from_big_endian(&input[128..160]);
+    let modulus = U256::from_big_endian(&input[160..192]);
+    if modulus.is_zero() {
+        return U256::zero();
+    }
+
+    let mulmod = |a: U256, b: U256| {
+        let mut bytes = [0; 64];
+        (a.full_mul(b) % U512::from(modulus)).to_big_endian(&mut bytes);
+        U256::from_big_endian(&bytes[32..])
+    };
+    let mut result = U256::one() % modulus;
+    for bit in (0..exponent.bits()).rev() {
+        result = mulmod(result, result);
+        if exponent.bit(bit) {
+            result = mulmod(result, base);
+        }
+    }
+    result
+}
+
 #[allow(dead_code)]
 pub fn deploy_contract(
     executor: &mut Executor,
     fixture: &str,
     contract_name: &str,
     init_params: &[ethabi::Token],
+) -> ContractHarness {
+    deploy_linked_contract(executor, fixture, contract_name, init_params, &[])
+}
+
+/// Deploys a contract after linking the given library addresses into its
+/// bytecode.
+#[allow(dead_code)]
+pub fn deploy_linked_contract(
+    executor: &mut Executor,
+    fixture: &str,
+    contract_name: &str,
+    init_params: &[ethabi::Token],
+    libraries: &[(&str, H160)],
 ) -> ContractHarness {
     let src = fs::read_to_string(format!("tests/fixtures/{}", fixture))
         .expect("unable to read fixture file");
@@ -201,9 +343,44 @@ pub fn deploy_contract(
         .contracts
         .get(contract_name)
         .expect("could not find contract in fixture");
+
+    deploy_compiled_contract(executor, compiled_contract, init_params, libraries)
+}
+
+/// Deploys a contract of the fixture with its test functions made public.
+#[allow(dead_code)]
+pub fn deploy_test_harness(
+    executor: &mut Executor,
+    fixture: &str,
+    contract_name: &str,
+) -> ContractHarness {
+    let src = fs::read_to_string(format!("tests/fixtures/{}", fixture))
+        .expect("unable to read fixture file");
+    let compiled_tests = compiler::compile_tests(&src, true).expect("failed to compile module");
+    let compiled_contract = compiled_tests
+        .module
+        .contracts
+        .get(contract_name)
+        .expect("could not find contract in fixture");
+
+    deploy_compiled_contract(executor, compiled_contract, &[], &[])
+}
+
+fn deploy_compiled_contract(
+    executor: &mut Executor,
+    compiled_contract: &compiler::types::CompiledContract,
+    init_params: &[ethabi::Token],
+    libraries: &[(&str, H160)],
+) -> ContractHarness {
     let abi = ethabi::Contract::load(StringReader::new(&compiled_contract.json_abi))
         .expect("unable to load the ABI");
-    let mut bytecode = hex::decode(&compiled_contract.bytecode).expect("failed to decode bytecode");
+    let linked_bytecode = libraries.iter().fold(
+        compiled_contract.bytecode.clone(),
+        |bytecode, (name, address)| {
+            compiler::evm::link_library(&bytecode, name, &hex::encode(address.as_bytes()))
+        },
+    );
+    let mut bytecode = hex::decode(&linked_bytecode).expect("failed to decode bytecode");
 
     if let Some(constructor) = &abi.constructor {
         bytecode = constructor.encode_input(bytecode, init_params).unwrap()
@@ -321,6 +498,11 @@ pub fn u256_array_token(v: &[usize]) -> ethabi::Token {
     ethabi::Token::FixedArray(v.iter().map(|n| uint_token(*n)).collect())
 }
 
+#[allow(dead_code)]
+pub fn u256_dyn_array_token(v: &[usize]) -> ethabi::Token {
+    ethabi::Token::Array(v.iter().map(|n| uint_token(*n)).collect())
+}
+
 #[allow(dead_code)]
 pub fn address_array_token(v: &[&str]) -> ethabi::Token {
     ethabi::Token::FixedArray(v.iter().map(|s| address_token(s)).collect())
@@ -351,6 +533,14 @@ pub fn get_2s_complement_for_negative(assume_negative: U256) -> U256 {
     negated + 1
 }
 
+#[allow(dead_code)]
+pub fn negate_int_token(token: &ethabi::Token) -> ethabi::Token {
+    match token {
+        ethabi::Token::Int(val) => ethabi::Token::Int(get_2s_complement_for_negative(*val)),
+        _ => panic!("expected an int token"),
+    }
+}
+
 #[allow(dead_code)]
 pub struct NumericAbiTokenBounds {
     pub size: usize,
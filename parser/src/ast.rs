@@ -3,7 +3,10 @@ use serde::{
     Serialize,
 };
 
-use crate::span::Spanned;
+use crate::span::{
+    Span,
+    Spanned,
+};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct Module<'a> {
@@ -11,9 +14,86 @@ pub struct Module<'a> {
     pub body: Vec<Spanned<ModuleStmt<'a>>>,
 }
 
+impl Module<'_> {
+    /// Returns the syntax errors of all error nodes in the module, in the
+    /// order they appear in.
+    pub fn syntax_errors(&self) -> Vec<&SyntaxError> {
+        let mut errors = vec![];
+
+        for stmt in self.body.iter() {
+            match &stmt.node {
+                ModuleStmt::Error(error) => errors.push(error),
+                ModuleStmt::ContractDef { body, .. }
+                | ModuleStmt::MixinDef { body, .. }
+                | ModuleStmt::LibraryDef { body, .. } => contract_syntax_errors(body, &mut errors),
+                ModuleStmt::FuncDef { def } => {
+                    contract_syntax_errors(std::slice::from_ref(def.as_ref()), &mut errors)
+                }
+                _ => {}
+            }
+        }
+
+        errors
+    }
+}
+
+fn contract_syntax_errors<'a>(
+    body: &'a [Spanned<ContractStmt>],
+    errors: &mut Vec<&'a SyntaxError>,
+) {
+    for stmt in body.iter() {
+        match &stmt.node {
+            ContractStmt::Error(error) => errors.push(error),
+            ContractStmt::FuncDef { body, .. } => func_syntax_errors(body, errors),
+            _ => {}
+        }
+    }
+}
+
+fn func_syntax_errors<'a>(body: &'a [Spanned<FuncStmt>], errors: &mut Vec<&'a SyntaxError>) {
+    for stmt in body.iter() {
+        match &stmt.node {
+            FuncStmt::Error(error) => errors.push(error),
+            FuncStmt::For { body, or_else, .. }
+            | FuncStmt::While { body, or_else, .. }
+            | FuncStmt::If { body, or_else, .. } => {
+                func_syntax_errors(body, errors);
+                func_syntax_errors(or_else, errors);
+            }
+            FuncStmt::Match { arms, .. } => {
+                for arm in arms.iter() {
+                    func_syntax_errors(&arm.node.body, errors);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A syntax error that the parser recovered from. The code that couldn't be
+/// parsed is kept in the AST as an error node, which spans the skipped
+/// tokens.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct SyntaxError {
+    pub message: String,
+    /// The span of the token at which parsing failed.
+    pub span: Span,
+}
+
+/// Joins the lines of the `///` doc comments attached to an item. The lines
+/// are stored without the leading `///` and a single following space.
+pub fn doc_string(docs: &[Spanned<&str>]) -> String {
+    docs.iter()
+        .map(|line| line.node)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub enum ModuleStmt<'a> {
     TypeDef {
+        #[serde(borrow, default, skip_serializing_if = "Vec::is_empty")]
+        docs: Vec<Spanned<&'a str>>,
         name: Spanned<&'a str>,
         #[serde(borrow)]
         typ: Spanned<TypeDesc<'a>>,
@@ -28,16 +108,107 @@ pub enum ModuleStmt<'a> {
         #[serde(borrow)]
         names: Spanned<FromImportNames<'a>>,
     },
+    ConstDef {
+        #[serde(borrow, default, skip_serializing_if = "Vec::is_empty")]
+        docs: Vec<Spanned<&'a str>>,
+        #[serde(borrow, default, skip_serializing_if = "Vec::is_empty")]
+        decorators: Vec<Spanned<Expr<'a>>>,
+        name: Spanned<&'a str>,
+        #[serde(borrow)]
+        typ: Spanned<TypeDesc<'a>>,
+        #[serde(borrow)]
+        value: Spanned<Expr<'a>>,
+    },
     ContractDef {
+        #[serde(borrow, default, skip_serializing_if = "Vec::is_empty")]
+        docs: Vec<Spanned<&'a str>>,
+        #[serde(borrow, default, skip_serializing_if = "Vec::is_empty")]
+        decorators: Vec<Spanned<Expr<'a>>>,
+        name: Spanned<&'a str>,
+        #[serde(borrow)]
+        mixins: Vec<Spanned<&'a str>>,
+        #[serde(borrow)]
+        traits: Vec<Spanned<&'a str>>,
+        #[serde(borrow)]
+        body: Vec<Spanned<ContractStmt<'a>>>,
+    },
+    MixinDef {
+        #[serde(borrow, default, skip_serializing_if = "Vec::is_empty")]
+        docs: Vec<Spanned<&'a str>>,
         name: Spanned<&'a str>,
         #[serde(borrow)]
         body: Vec<Spanned<ContractStmt<'a>>>,
     },
+    /// A stateless contract that is deployed once and whose public functions
+    /// are called by other contracts with `delegatecall`.
+    LibraryDef {
+        #[serde(borrow, default, skip_serializing_if = "Vec::is_empty")]
+        docs: Vec<Spanned<&'a str>>,
+        name: Spanned<&'a str>,
+        #[serde(borrow)]
+        body: Vec<Spanned<ContractStmt<'a>>>,
+    },
+    TraitDef {
+        #[serde(borrow, default, skip_serializing_if = "Vec::is_empty")]
+        docs: Vec<Spanned<&'a str>>,
+        name: Spanned<&'a str>,
+        #[serde(borrow)]
+        body: Vec<Spanned<FuncSig<'a>>>,
+    },
+    /// The public functions of a contract that is defined elsewhere, which
+    /// may be called through a contract type with the same name.
+    InterfaceDef {
+        #[serde(borrow, default, skip_serializing_if = "Vec::is_empty")]
+        docs: Vec<Spanned<&'a str>>,
+        name: Spanned<&'a str>,
+        #[serde(borrow)]
+        body: Vec<Spanned<FuncSig<'a>>>,
+    },
     StructDef {
+        #[serde(borrow, default, skip_serializing_if = "Vec::is_empty")]
+        docs: Vec<Spanned<&'a str>>,
+        #[serde(borrow, default, skip_serializing_if = "Vec::is_empty")]
+        decorators: Vec<Spanned<Expr<'a>>>,
         name: Spanned<&'a str>,
         #[serde(borrow)]
+        generics: Vec<Spanned<&'a str>>,
+        #[serde(borrow)]
         body: Vec<Spanned<StructStmt<'a>>>,
     },
+    EnumDef {
+        #[serde(borrow, default, skip_serializing_if = "Vec::is_empty")]
+        docs: Vec<Spanned<&'a str>>,
+        name: Spanned<&'a str>,
+        #[serde(borrow)]
+        variants: Vec<Spanned<&'a str>>,
+    },
+    /// A function defined outside of any contract, which is shared by all
+    /// contracts in the module.
+    FuncDef {
+        #[serde(borrow)]
+        def: Box<Spanned<ContractStmt<'a>>>,
+    },
+    /// Code that couldn't be parsed.
+    Error(SyntaxError),
+}
+
+impl<'a> ModuleStmt<'a> {
+    /// Returns the contract definition that a library definition is analyzed
+    /// and compiled as. Libraries have no mixins and implement no traits.
+    pub fn library_contract(&self) -> Option<ModuleStmt<'a>> {
+        if let ModuleStmt::LibraryDef { docs, name, body } = self {
+            Some(ModuleStmt::ContractDef {
+                docs: docs.clone(),
+                decorators: vec![],
+                name: name.clone(),
+                mixins: vec![],
+                traits: vec![],
+                body: body.clone(),
+            })
+        } else {
+            None
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -56,6 +227,10 @@ pub enum TypeDesc<'a> {
     Tuple {
         items: Vec<Spanned<TypeDesc<'a>>>,
     },
+    Generic {
+        base: &'a str,
+        args: Vec<Spanned<TypeDesc<'a>>>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -95,27 +270,57 @@ pub struct FromImportName<'a> {
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub enum ContractStmt<'a> {
     ContractField {
+        #[serde(borrow, default, skip_serializing_if = "Vec::is_empty")]
+        docs: Vec<Spanned<&'a str>>,
         qual: Option<Spanned<ContractFieldQual>>,
         #[serde(borrow)]
         name: Spanned<&'a str>,
         typ: Spanned<TypeDesc<'a>>,
     },
+    ConstDef {
+        #[serde(borrow, default, skip_serializing_if = "Vec::is_empty")]
+        docs: Vec<Spanned<&'a str>>,
+        #[serde(borrow, default, skip_serializing_if = "Vec::is_empty")]
+        decorators: Vec<Spanned<Expr<'a>>>,
+        name: Spanned<&'a str>,
+        typ: Spanned<TypeDesc<'a>>,
+        value: Spanned<Expr<'a>>,
+    },
     EventDef {
+        #[serde(borrow, default, skip_serializing_if = "Vec::is_empty")]
+        docs: Vec<Spanned<&'a str>>,
         name: Spanned<&'a str>,
         fields: Vec<Spanned<EventField<'a>>>,
     },
+    ErrorDef {
+        #[serde(borrow, default, skip_serializing_if = "Vec::is_empty")]
+        docs: Vec<Spanned<&'a str>>,
+        name: Spanned<&'a str>,
+        fields: Vec<Spanned<FuncDefArg<'a>>>,
+    },
     FuncDef {
+        #[serde(borrow, default, skip_serializing_if = "Vec::is_empty")]
+        docs: Vec<Spanned<&'a str>>,
+        decorators: Vec<Spanned<Expr<'a>>>,
         qual: Option<Spanned<FuncQual>>,
         name: Spanned<&'a str>,
+        generics: Vec<Spanned<&'a str>>,
         args: Vec<Spanned<FuncDefArg<'a>>>,
         return_type: Option<Spanned<TypeDesc<'a>>>,
+        /// The names of the items of a returned tuple, if they are named.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        return_names: Vec<Spanned<&'a str>>,
         body: Vec<Spanned<FuncStmt<'a>>>,
     },
+    /// Code that couldn't be parsed.
+    Error(SyntaxError),
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub enum StructStmt<'a> {
     StructField {
+        #[serde(borrow, default, skip_serializing_if = "Vec::is_empty")]
+        docs: Vec<Spanned<&'a str>>,
         qual: Option<Spanned<StructFieldQual>>,
         #[serde(borrow)]
         name: Spanned<&'a str>,
@@ -153,6 +358,16 @@ pub enum FuncQual {
     Pub,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct FuncSig<'a> {
+    #[serde(borrow, default, skip_serializing_if = "Vec::is_empty")]
+    pub docs: Vec<Spanned<&'a str>>,
+    #[serde(borrow)]
+    pub name: Spanned<&'a str>,
+    pub args: Vec<Spanned<FuncDefArg<'a>>>,
+    pub return_type: Option<Spanned<TypeDesc<'a>>>,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct FuncDefArg<'a> {
     #[serde(borrow)]
@@ -196,6 +411,10 @@ pub enum FuncStmt<'a> {
         body: Vec<Spanned<FuncStmt<'a>>>,
         or_else: Vec<Spanned<FuncStmt<'a>>>,
     },
+    Match {
+        value: Spanned<Expr<'a>>,
+        arms: Vec<Spanned<MatchArm<'a>>>,
+    },
     Assert {
         test: Spanned<Expr<'a>>,
         msg: Option<Spanned<Expr<'a>>>,
@@ -209,7 +428,31 @@ pub enum FuncStmt<'a> {
     Pass,
     Break,
     Continue,
-    Revert,
+    Revert {
+        error: Option<Spanned<Expr<'a>>>,
+    },
+    /// Code that couldn't be parsed.
+    Error(SyntaxError),
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct MatchArm<'a> {
+    #[serde(borrow)]
+    pub pattern: Spanned<Expr<'a>>,
+    pub body: Vec<Spanned<FuncStmt<'a>>>,
+}
+
+impl FuncStmt<'_> {
+    /// Returns true if the statement is the placeholder `_`, which marks the
+    /// point where a decorator runs the body of the decorated function.
+    pub fn is_placeholder(&self) -> bool {
+        matches!(
+            self,
+            FuncStmt::Expr {
+                value: Expr::Name("_")
+            }
+        )
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
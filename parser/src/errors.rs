@@ -1,3 +1,5 @@
+use crate::ast::SyntaxError;
+use crate::span::Span;
 use crate::string_utils::StringPositions;
 use crate::Cursor;
 
@@ -93,6 +95,35 @@ impl<'a> ParseError<'a> {
         result
     }
 
+    /// Returns the span and description of the innermost error, which is the
+    /// one reported to users. Errors at the end of the input have an empty
+    /// span at the end of `input`.
+    pub fn innermost(&self, input: &str) -> (Span, &str) {
+        self.innermost_at(input.len())
+    }
+
+    /// Converts the innermost error into a syntax error, which can be kept in
+    /// the AST. Errors at the end of the input are located at the offset
+    /// `eof`.
+    pub fn syntax_error(&self, eof: usize) -> SyntaxError {
+        let (span, description) = self.innermost_at(eof);
+
+        SyntaxError {
+            message: description.to_owned(),
+            span,
+        }
+    }
+
+    fn innermost_at(&self, eof: usize) -> (Span, &str) {
+        let (parser_input, err_kind) = self.errors.first().unwrap();
+        let span = match parser_input.first() {
+            Some(tok) => tok.span,
+            None => Span::new(eof, eof),
+        };
+
+        (span, err_kind.description())
+    }
+
     /// Format an error into a user-facing error message.
     ///
     /// Uses the innermost error to build a user-facing error message and
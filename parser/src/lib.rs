@@ -4,9 +4,11 @@ pub mod ast;
 pub mod builders;
 pub mod errors;
 pub mod parsers;
+pub mod printer;
 pub mod span;
 pub mod string_utils;
 pub mod tokenizer;
+pub mod visit;
 
 mod ast_traits;
 
@@ -107,7 +107,26 @@ pub fn endmarker_token(input: Cursor) -> ParseResult<&Token> {
     token(TokenType::ENDMARKER)(input)
 }
 
-/// Parse a module definition.
+/// Parse a doc comment token.
+pub fn doc_comment_token(input: Cursor) -> ParseResult<&Token> {
+    token(TokenType::DOCCOMMENT)(input)
+}
+
+/// Parse the doc comments preceding an item. The leading `///` and a single
+/// following space are stripped from each line.
+pub fn doc_comments(input: Cursor) -> ParseResult<Vec<Spanned<&str>>> {
+    many0(map(doc_comment_token, |tok| {
+        let text = &tok.string[3..];
+
+        Spanned {
+            node: text.strip_prefix(' ').unwrap_or(text),
+            span: tok.span,
+        }
+    }))(input)
+}
+
+/// Parse a module definition. Statements of contract and function bodies that
+/// can't be parsed are kept as error nodes.
 pub fn file_input(input: Cursor) -> ParseResult<Spanned<Module>> {
     alt((empty_file_input, non_empty_file_input))(input)
 }
@@ -150,9 +169,182 @@ pub fn non_empty_file_input(input: Cursor) -> ParseResult<Spanned<Module>> {
     ))
 }
 
+/// Parse a module definition, recovering from all syntax errors. Module
+/// statements that can't be parsed are kept as error nodes, just like the
+/// statements of contract and function bodies. Returns the module along with
+/// the syntax errors of all error nodes, so that they can be reported at once
+/// and tools can work with the rest of the module.
+pub fn file_input_with_errors<'a>(input: Cursor<'a>) -> (Spanned<Module<'a>>, Vec<SyntaxError>) {
+    if let Ok((_, module)) = empty_file_input(input) {
+        return (module, vec![]);
+    }
+
+    let eof = input.last().map_or(0, |tok| tok.span.end);
+    let mut input = input;
+    let mut body = vec![];
+    while !input.is_empty() && input[0].typ != TokenType::ENDMARKER {
+        match module_stmt(input) {
+            Ok((rest, stmt)) => {
+                body.push(stmt);
+                input = rest;
+            }
+            Err(error) => {
+                let rest = skip_module_stmt(input);
+                body.push(Spanned {
+                    node: ModuleStmt::Error(error.syntax_error(eof)),
+                    span: skipped_span(&input[..input.len() - rest.len()]),
+                });
+                input = rest;
+            }
+        }
+    }
+
+    let span = match (body.first(), body.last()) {
+        (Some(first), Some(last)) => Span::from_pair(first, last),
+        _ => input.first().map_or(Span::new(0, 0), |tok| tok.span),
+    };
+    let module = Spanned {
+        node: Module { body },
+        span,
+    };
+    let errors = module.node.syntax_errors().into_iter().cloned().collect();
+
+    (module, errors)
+}
+
+/// Skip the tokens of a module statement that can't be parsed. The next
+/// statement starts with the first token after a newline that isn't indented.
+fn skip_module_stmt(input: Cursor) -> Cursor {
+    let mut depth = 0;
+    for (index, tok) in input.iter().enumerate().skip(1) {
+        let prev = &input[index - 1];
+        match prev.typ {
+            TokenType::INDENT => depth += 1,
+            TokenType::DEDENT => depth -= 1,
+            _ => {}
+        }
+        let at_line_start = matches!(prev.typ, TokenType::NEWLINE | TokenType::DEDENT);
+        if depth <= 0 && at_line_start && tok.typ != TokenType::DEDENT {
+            return &input[index..];
+        }
+    }
+
+    &input[input.len()..]
+}
+
+/// Skip the tokens of a statement in an indented block that can't be parsed.
+/// The next statement starts with the first token after a newline on the same
+/// level of indentation, unless the block ends there.
+fn skip_block_stmt(input: Cursor) -> Cursor {
+    let mut depth = 0;
+    for (index, tok) in input.iter().enumerate().skip(1) {
+        let prev = &input[index - 1];
+        match prev.typ {
+            TokenType::INDENT => depth += 1,
+            TokenType::DEDENT => depth -= 1,
+            _ => {}
+        }
+        let at_line_start = matches!(prev.typ, TokenType::NEWLINE | TokenType::DEDENT);
+        if (depth == 0 && at_line_start && tok.typ != TokenType::INDENT)
+            || tok.typ == TokenType::ENDMARKER
+        {
+            return &input[index..];
+        }
+    }
+
+    &input[input.len()..]
+}
+
+/// The span of skipped tokens, without the newline and indentation tokens
+/// that follow them.
+fn skipped_span(skipped: Cursor) -> Span {
+    let last = skipped
+        .iter()
+        .rev()
+        .find(|tok| {
+            !matches!(
+                tok.typ,
+                TokenType::NEWLINE | TokenType::INDENT | TokenType::DEDENT
+            )
+        })
+        .unwrap_or(&skipped[0]);
+
+    Span::from_pair(&skipped[0], last)
+}
+
+/// Parse the statements of an indented block with `parser`, up to the dedent
+/// that ends the block. A statement that can't be parsed is skipped and
+/// replaced by the node that `error` creates from the syntax error and the
+/// span of the skipped tokens, so that the rest of the block is still parsed.
+fn recovering_many1<'a, O, P, E>(parser: P, error: E) -> impl Fn(Cursor<'a>) -> ParseResult<Vec<O>>
+where
+    P: Fn(Cursor<'a>) -> ParseResult<O>,
+    E: Fn(SyntaxError, Span) -> O,
+{
+    move |input| {
+        let eof = input.last().map_or(0, |tok| tok.span.end);
+        let mut input = input;
+        let mut results = vec![];
+
+        while let Some(tok) = input.first() {
+            if matches!(tok.typ, TokenType::DEDENT | TokenType::ENDMARKER) {
+                break;
+            }
+
+            match parser(input) {
+                Ok((rest, result)) => {
+                    results.push(result);
+                    input = rest;
+                }
+                Err(err) => {
+                    let rest = skip_block_stmt(input);
+                    let span = skipped_span(&input[..input.len() - rest.len()]);
+                    results.push(error(err.syntax_error(eof), span));
+                    input = rest;
+                }
+            }
+        }
+
+        if results.is_empty() {
+            return Err(ParseError::static_str(
+                input,
+                "expected at least one statement",
+            ));
+        }
+
+        Ok((input, results))
+    }
+}
+
 /// Parse a module statement, such as a contract definition.
 pub fn module_stmt(input: Cursor) -> ParseResult<Spanned<ModuleStmt>> {
-    alt((import_stmt, type_def, contract_def, struct_def))(input)
+    alt((
+        import_stmt,
+        type_def,
+        const_def,
+        contract_def,
+        mixin_def,
+        library_def,
+        trait_def,
+        interface_def,
+        struct_def,
+        enum_def,
+        free_func_def,
+    ))(input)
+}
+
+/// Parse a function definition outside of a contract.
+pub fn free_func_def(input: Cursor) -> ParseResult<Spanned<ModuleStmt>> {
+    let (input, def) = func_def(input)?;
+    let span = def.span;
+
+    Ok((
+        input,
+        Spanned {
+            node: ModuleStmt::FuncDef { def: Box::new(def) },
+            span,
+        },
+    ))
 }
 
 /// Parse an import statement.
@@ -405,24 +597,80 @@ pub fn dots_to_int(input: Cursor) -> ParseResult<Spanned<usize>> {
 
 /// Parse a contract definition statement.
 pub fn contract_def(input: Cursor) -> ParseResult<Spanned<ModuleStmt>> {
-    // "contract" name ":" NEWLINE
+    // decorator* "contract" name ["uses" name ("," name)*]
+    //     ["implements" name ("," name)*] ":" NEWLINE
+    let (input, docs) = doc_comments(input)?;
+    let (input, decorators) = many0(decorator)(input)?;
     let (input, contract_kw) = name("contract")(input)?;
     let (input, name_tok) = name_token(input)?;
+    let (input, mixins) = opt(preceded(
+        name("uses"),
+        separated(name_token, op(","), false),
+    ))(input)?;
+    let (input, traits) = opt(preceded(
+        name("implements"),
+        separated(name_token, op(","), false),
+    ))(input)?;
     let (input, _) = op(":")(input)?;
     let (input, _) = newline_token(input)?;
 
     // INDENT contract_stmt+ DEDENT
     let (input, _) = indent_token(input)?;
-    let (input, body) = many1(contract_stmt)(input)?;
+    let (input, body) = recovering_many1(contract_stmt, contract_error)(input)?;
     let (input, _) = dedent_token(input)?;
 
     let last_stmt = body.last().unwrap();
-    let span = Span::from_pair(contract_kw, last_stmt);
+    let span = match decorators.first() {
+        Some(decorator) => Span::from_pair(decorator, last_stmt),
+        None => Span::from_pair(contract_kw, last_stmt),
+    };
 
     Ok((
         input,
         Spanned {
             node: ContractDef {
+                docs,
+                decorators,
+                name: name_tok.into(),
+                mixins: mixins
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|tok| tok.into())
+                    .collect(),
+                traits: traits
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|tok| tok.into())
+                    .collect(),
+                body,
+            },
+            span,
+        },
+    ))
+}
+
+/// Parse a library definition.
+pub fn library_def(input: Cursor) -> ParseResult<Spanned<ModuleStmt>> {
+    // "library" name ":" NEWLINE
+    let (input, docs) = doc_comments(input)?;
+    let (input, library_kw) = name("library")(input)?;
+    let (input, name_tok) = name_token(input)?;
+    let (input, _) = op(":")(input)?;
+    let (input, _) = newline_token(input)?;
+
+    // INDENT contract_stmt+ DEDENT
+    let (input, _) = indent_token(input)?;
+    let (input, body) = recovering_many1(contract_stmt, contract_error)(input)?;
+    let (input, _) = dedent_token(input)?;
+
+    let last_stmt = body.last().unwrap();
+    let span = Span::from_pair(library_kw, last_stmt);
+
+    Ok((
+        input,
+        Spanned {
+            node: LibraryDef {
+                docs,
                 name: name_tok.into(),
                 body,
             },
@@ -431,13 +679,169 @@ pub fn contract_def(input: Cursor) -> ParseResult<Spanned<ModuleStmt>> {
     ))
 }
 
+/// Parse a mixin definition.
+pub fn mixin_def(input: Cursor) -> ParseResult<Spanned<ModuleStmt>> {
+    // "mixin" name ":" NEWLINE
+    let (input, docs) = doc_comments(input)?;
+    let (input, mixin_kw) = name("mixin")(input)?;
+    let (input, name_tok) = name_token(input)?;
+    let (input, _) = op(":")(input)?;
+    let (input, _) = newline_token(input)?;
+
+    // INDENT contract_stmt+ DEDENT
+    let (input, _) = indent_token(input)?;
+    let (input, body) = recovering_many1(contract_stmt, contract_error)(input)?;
+    let (input, _) = dedent_token(input)?;
+
+    let last_stmt = body.last().unwrap();
+    let span = Span::from_pair(mixin_kw, last_stmt);
+
+    Ok((
+        input,
+        Spanned {
+            node: MixinDef {
+                docs,
+                name: name_tok.into(),
+                body,
+            },
+            span,
+        },
+    ))
+}
+
+/// Parse a trait definition.
+pub fn trait_def(input: Cursor) -> ParseResult<Spanned<ModuleStmt>> {
+    // "trait" name ":" NEWLINE
+    let (input, docs) = doc_comments(input)?;
+    let (input, trait_kw) = name("trait")(input)?;
+    let (input, name_tok) = name_token(input)?;
+    let (input, _) = op(":")(input)?;
+    let (input, _) = newline_token(input)?;
+
+    // INDENT func_sig+ DEDENT
+    let (input, _) = indent_token(input)?;
+    let (input, body) = many1(terminated(func_sig, newline_token))(input)?;
+    let (input, _) = dedent_token(input)?;
+
+    let last_sig = body.last().unwrap();
+    let span = Span::from_pair(trait_kw, last_sig);
+
+    Ok((
+        input,
+        Spanned {
+            node: TraitDef {
+                docs,
+                name: name_tok.into(),
+                body,
+            },
+            span,
+        },
+    ))
+}
+
+/// Parse an interface definition.
+pub fn interface_def(input: Cursor) -> ParseResult<Spanned<ModuleStmt>> {
+    // "interface" name ":" NEWLINE
+    let (input, docs) = doc_comments(input)?;
+    let (input, interface_kw) = name("interface")(input)?;
+    let (input, name_tok) = name_token(input)?;
+    let (input, _) = op(":")(input)?;
+    let (input, _) = newline_token(input)?;
+
+    // INDENT func_sig+ DEDENT
+    let (input, _) = indent_token(input)?;
+    let (input, body) = many1(terminated(func_sig, newline_token))(input)?;
+    let (input, _) = dedent_token(input)?;
+
+    let last_sig = body.last().unwrap();
+    let span = Span::from_pair(interface_kw, last_sig);
+
+    Ok((
+        input,
+        Spanned {
+            node: InterfaceDef {
+                docs,
+                name: name_tok.into(),
+                body,
+            },
+            span,
+        },
+    ))
+}
+
+/// Parse a function signature without a body e.g.
+/// "def transfer(to: address, value: u256) -> bool".
+pub fn func_sig(input: Cursor) -> ParseResult<Spanned<FuncSig>> {
+    let (input, docs) = doc_comments(input)?;
+    let (input, def_kw) = name("def")(input)?;
+    let (input, name_tok) = name_token(input)?;
+
+    let (input, _) = op("(")(input)?;
+    let (input, args) = arg_list(input)?;
+    let (input, r_paren) = op(")")(input)?;
+
+    let (input, return_type) = opt(preceded(op("->"), base_or_tuple_type))(input)?;
+
+    let span = match &return_type {
+        Some(typ) => Span::from_pair(def_kw, typ),
+        None => Span::from_pair(def_kw, r_paren),
+    };
+
+    Ok((
+        input,
+        Spanned {
+            node: FuncSig {
+                docs,
+                name: name_tok.into(),
+                args,
+                return_type,
+            },
+            span,
+        },
+    ))
+}
+
 /// Parse a contract statement.
 pub fn contract_stmt(input: Cursor) -> ParseResult<Spanned<ContractStmt>> {
-    alt((contract_field, event_def, func_def))(input)
+    alt((
+        contract_const_def,
+        contract_field,
+        event_def,
+        error_def,
+        func_def,
+    ))(input)
+}
+
+/// Create the error node for a contract statement that can't be parsed.
+fn contract_error<'a>(error: SyntaxError, span: Span) -> Spanned<ContractStmt<'a>> {
+    Spanned {
+        node: ContractStmt::Error(error),
+        span,
+    }
+}
+
+/// Parse a contract constant definition.
+pub fn contract_const_def(input: Cursor) -> ParseResult<Spanned<ContractStmt>> {
+    let (input, (span, docs, decorators, name, typ, value)) = const_def_parts(input)?;
+
+    Ok((
+        input,
+        Spanned {
+            node: ContractStmt::ConstDef {
+                docs,
+                decorators,
+                name,
+                typ,
+                value,
+            },
+            span,
+        },
+    ))
 }
 
 /// Parse a contract field definition.
 pub fn contract_field(input: Cursor) -> ParseResult<Spanned<ContractStmt>> {
+    let (input, docs) = doc_comments(input)?;
     let (input, (qual, name_tok)) = alt((
         // Look for a qualifier and field name first...
         map(pair(contract_field_qual, name_token), |res| {
@@ -461,6 +865,7 @@ pub fn contract_field(input: Cursor) -> ParseResult<Spanned<ContractStmt>> {
         input,
         Spanned {
             node: ContractStmt::ContractField {
+                docs,
                 qual,
                 name: name_tok.into(),
                 typ,
@@ -472,9 +877,12 @@ pub fn contract_field(input: Cursor) -> ParseResult<Spanned<ContractStmt>> {
 
 /// Parse a struct definition statement.
 pub fn struct_def(input: Cursor) -> ParseResult<Spanned<ModuleStmt>> {
-    // "struct" name ":" NEWLINE
+    // decorator* "struct" name ":" NEWLINE
+    let (input, docs) = doc_comments(input)?;
+    let (input, decorators) = many0(decorator)(input)?;
     let (input, contract_kw) = name("struct")(input)?;
     let (input, name_tok) = name_token(input)?;
+    let (input, generics) = opt(type_params)(input)?;
     let (input, _) = op(":")(input)?;
     let (input, _) = newline_token(input)?;
 
@@ -484,13 +892,19 @@ pub fn struct_def(input: Cursor) -> ParseResult<Spanned<ModuleStmt>> {
     let (input, _) = dedent_token(input)?;
 
     let last_stmt = body.last().unwrap();
-    let span = Span::from_pair(contract_kw, last_stmt);
+    let span = match decorators.first() {
+        Some(decorator) => Span::from_pair(decorator, last_stmt),
+        None => Span::from_pair(contract_kw, last_stmt),
+    };
 
     Ok((
         input,
         Spanned {
             node: StructDef {
+                docs,
+                decorators,
                 name: name_tok.into(),
+                generics: generics.unwrap_or_default(),
                 body,
             },
             span,
@@ -498,8 +912,39 @@ pub fn struct_def(input: Cursor) -> ParseResult<Spanned<ModuleStmt>> {
     ))
 }
 
+/// Parse an enum definition.
+pub fn enum_def(input: Cursor) -> ParseResult<Spanned<ModuleStmt>> {
+    // "enum" name ":" NEWLINE
+    let (input, docs) = doc_comments(input)?;
+    let (input, enum_kw) = name("enum")(input)?;
+    let (input, name_tok) = name_token(input)?;
+    let (input, _) = op(":")(input)?;
+    let (input, _) = newline_token(input)?;
+
+    // INDENT (name NEWLINE)+ DEDENT
+    let (input, _) = indent_token(input)?;
+    let (input, variants) = many1(terminated(name_token, newline_token))(input)?;
+    let (input, _) = dedent_token(input)?;
+
+    let last_variant = variants.last().unwrap();
+    let span = Span::from_pair(enum_kw, *last_variant);
+
+    Ok((
+        input,
+        Spanned {
+            node: EnumDef {
+                docs,
+                name: name_tok.into(),
+                variants: variants.into_iter().map(|tok| tok.into()).collect(),
+            },
+            span,
+        },
+    ))
+}
+
 /// Parse a struct field definition.
 pub fn struct_field(input: Cursor) -> ParseResult<Spanned<StructStmt>> {
+    let (input, docs) = doc_comments(input)?;
     let (input, (qual, name_tok)) = alt((
         // Look for a qualifier and field name first...
         map(pair(struct_field_qual, name_token), |res| {
@@ -523,6 +968,7 @@ pub fn struct_field(input: Cursor) -> ParseResult<Spanned<StructStmt>> {
         input,
         Spanned {
             node: StructStmt::StructField {
+                docs,
                 qual,
                 name: name_tok.into(),
                 typ,
@@ -535,6 +981,7 @@ pub fn struct_field(input: Cursor) -> ParseResult<Spanned<StructStmt>> {
 /// Parse an event definition statement.
 pub fn event_def(input: Cursor) -> ParseResult<Spanned<ContractStmt>> {
     // "event" name ":" NEWLINE
+    let (input, docs) = doc_comments(input)?;
     let (input, event_kw) = name("event")(input)?;
     let (input, name_tok) = name_token(input)?;
     let (input, _) = op(":")(input)?;
@@ -552,6 +999,51 @@ pub fn event_def(input: Cursor) -> ParseResult<Spanned<ContractStmt>> {
         input,
         Spanned {
             node: ContractStmt::EventDef {
+                docs,
+                name: name_tok.into(),
+                fields,
+            },
+            span,
+        },
+    ))
+}
+
+/// Parse a custom error definition statement.
+pub fn error_def(input: Cursor) -> ParseResult<Spanned<ContractStmt>> {
+    // "error" name NEWLINE
+    let (input, docs) = doc_comments(input)?;
+    let (input, error_kw) = name("error")(input)?;
+    let (input, name_tok) = name_token(input)?;
+    if let Ok((input, _)) = newline_token(input) {
+        let span = Span::from_pair(error_kw, name_tok);
+        return Ok((
+            input,
+            Spanned {
+                node: ContractStmt::ErrorDef {
+                    docs,
+                    name: name_tok.into(),
+                    fields: vec![],
+                },
+                span,
+            },
+        ));
+    }
+
+    // "error" name ":" NEWLINE INDENT (arg_def NEWLINE)+ DEDENT
+    let (input, _) = op(":")(input)?;
+    let (input, _) = newline_token(input)?;
+    let (input, _) = indent_token(input)?;
+    let (input, fields) = many1(terminated(arg_def, newline_token))(input)?;
+    let (input, _) = dedent_token(input)?;
+
+    let last_field = fields.last().unwrap();
+    let span = Span::from_pair(error_kw, last_field);
+
+    Ok((
+        input,
+        Spanned {
+            node: ContractStmt::ErrorDef {
+                docs,
                 name: name_tok.into(),
                 fields,
             },
@@ -595,34 +1087,46 @@ pub fn event_field(input: Cursor) -> ParseResult<Spanned<EventField>> {
 }
 
 pub fn func_def(input: Cursor) -> ParseResult<Spanned<ContractStmt>> {
+    let (input, docs) = doc_comments(input)?;
+    let (input, decorators) = many0(decorator)(input)?;
     let (input, qual) = opt(func_qual)(input)?;
     let (input, def_kw) = name("def")(input)?;
     let (input, name_tok) = name_token(input)?;
+    let (input, generics) = opt(type_params)(input)?;
 
     let (input, _) = op("(")(input)?;
     let (input, args) = arg_list(input)?;
     let (input, _) = op(")")(input)?;
 
-    let (input, return_type) = opt(preceded(op("->"), base_or_tuple_type))(input)?;
+    let (input, return_type) = opt(preceded(op("->"), func_return_type))(input)?;
+    let (return_names, return_type) = match return_type {
+        Some((names, typ)) => (names, Some(typ)),
+        None => (vec![], None),
+    };
 
     let (input, _) = op(":")(input)?;
 
     let (input, body) = block(input)?;
 
     let last = body.last().unwrap();
-    let span = match &qual {
-        Some(qual) => Span::from_pair(qual, last),
-        None => Span::from_pair(def_kw, last),
+    let span = match (decorators.first(), &qual) {
+        (Some(decorator), _) => Span::from_pair(decorator, last),
+        (None, Some(qual)) => Span::from_pair(qual, last),
+        (None, None) => Span::from_pair(def_kw, last),
     };
 
     Ok((
         input,
         Spanned {
             node: ContractStmt::FuncDef {
+                docs,
+                decorators,
                 qual,
                 name: name_tok.into(),
+                generics: generics.unwrap_or_default(),
                 args,
                 return_type,
+                return_names,
                 body,
             },
             span,
@@ -630,6 +1134,49 @@ pub fn func_def(input: Cursor) -> ParseResult<Spanned<ContractStmt>> {
     ))
 }
 
+/// Parse the return type of a function definition. The items of a returned
+/// tuple may be named e.g. "(quotient: u256, remainder: u256)", in which case
+/// the names are returned along with the type.
+pub fn func_return_type(input: Cursor) -> ParseResult<(Vec<Spanned<&str>>, Spanned<TypeDesc>)> {
+    alt((
+        named_tuple_type,
+        map(base_or_tuple_type, |typ| (vec![], typ)),
+    ))(input)
+}
+
+/// Parse a tuple type with named items e.g. "(quotient: u256, remainder: u256)".
+pub fn named_tuple_type(input: Cursor) -> ParseResult<(Vec<Spanned<&str>>, Spanned<TypeDesc>)> {
+    let (input, opening) = op("(")(input)?;
+    let (input, items) = separated(
+        pair(terminated(name_token, op(":")), base_type),
+        op(","),
+        true,
+    )(input)?;
+    let (input, closing) = op(")")(input)?;
+
+    let (names, items): (Vec<_>, Vec<_>) = items
+        .into_iter()
+        .map(|(name, typ)| (Spanned::from(name), typ))
+        .unzip();
+
+    Ok((
+        input,
+        (
+            names,
+            Spanned {
+                node: TypeDesc::Tuple { items },
+                span: Span::from_pair(opening, closing),
+            },
+        ),
+    ))
+}
+
+/// Parse a function decorator such as `@only_owner` or `@min_amount(10)` on
+/// its own line.
+pub fn decorator(input: Cursor) -> ParseResult<Spanned<Expr>> {
+    terminated(preceded(op("@"), expr), newline_token)(input)
+}
+
 pub fn arg_list(input: Cursor) -> ParseResult<Vec<Spanned<FuncDefArg>>> {
     match input[0] {
         Token { string: ")", .. } => Ok((input, vec![])),
@@ -658,6 +1205,7 @@ pub fn arg_def(input: Cursor) -> ParseResult<Spanned<FuncDefArg>> {
 
 /// Parse a type definition (type alias).
 pub fn type_def(input: Cursor) -> ParseResult<Spanned<ModuleStmt>> {
+    let (input, docs) = doc_comments(input)?;
     let (input, type_kw) = name("type")(input)?;
     let (input, name) = name_token(input)?;
     let (input, _) = op("=")(input)?;
@@ -670,6 +1218,7 @@ pub fn type_def(input: Cursor) -> ParseResult<Spanned<ModuleStmt>> {
         input,
         Spanned {
             node: ModuleStmt::TypeDef {
+                docs,
                 name: name.into(),
                 typ: type_desc,
             },
@@ -678,19 +1227,133 @@ pub fn type_def(input: Cursor) -> ParseResult<Spanned<ModuleStmt>> {
     ))
 }
 
+/// Parse a module constant definition.
+pub fn const_def(input: Cursor) -> ParseResult<Spanned<ModuleStmt>> {
+    let (input, (span, docs, decorators, name, typ, value)) = const_def_parts(input)?;
+
+    Ok((
+        input,
+        Spanned {
+            node: ModuleStmt::ConstDef {
+                docs,
+                decorators,
+                name,
+                typ,
+                value,
+            },
+            span,
+        },
+    ))
+}
+
+/// Parse the parts of a constant definition that are shared between module
+/// and contract constants.
+#[allow(clippy::type_complexity)]
+pub fn const_def_parts(
+    input: Cursor,
+) -> ParseResult<(
+    Span,
+    Vec<Spanned<&str>>,
+    Vec<Spanned<Expr>>,
+    Spanned<&str>,
+    Spanned<TypeDesc>,
+    Spanned<Expr>,
+)> {
+    // decorator* "const" name ":" type_desc "=" expr NEWLINE
+    let (input, docs) = doc_comments(input)?;
+    let (input, decorators) = many0(decorator)(input)?;
+    let (input, const_kw) = name("const")(input)?;
+    let (input, name_tok) = name_token(input)?;
+    let (input, _) = op(":")(input)?;
+    let (input, typ) = type_desc(input)?;
+    let (input, _) = op("=")(input)?;
+    let (input, value) = expr(input)?;
+    let (input, _) = newline_token(input)?;
+
+    let span = match decorators.first() {
+        Some(decorator) => Span::from_pair(decorator, &value),
+        None => Span::from_pair(const_kw, &value),
+    };
+
+    Ok((input, (span, docs, decorators, name_tok.into(), typ, value)))
+}
+
 /// Parse a type description e.g. "u256" or "map<address, bool>".
 pub fn type_desc(input: Cursor) -> ParseResult<Spanned<TypeDesc>> {
-    alt((map_type, base_type, tuple_type))(input)
+    alt((map_type, generic_type, base_type, tuple_type))(input)
 }
 
-/// Parse all base and tuple types but not map types
+/// Parse all base, generic and tuple types but not map types
 pub fn base_or_tuple_type(input: Cursor) -> ParseResult<Spanned<TypeDesc>> {
-    alt((base_type, tuple_type))(input)
+    alt((generic_type, base_type, tuple_type))(input)
+}
+
+/// Parse a list of type parameters e.g. "<T, U>".
+pub fn type_params(input: Cursor) -> ParseResult<Vec<Spanned<&str>>> {
+    let (input, _) = op("<")(input)?;
+    let (input, params) = separated(name_token, op(","), false)(input)?;
+    let (input, _) = op(">")(input)?;
+
+    Ok((input, params.into_iter().map(|tok| tok.into()).collect()))
+}
+
+/// Parse an instance of a generic type e.g. "Pair<u256, bool>".
+pub fn generic_type(input: Cursor) -> ParseResult<Spanned<TypeDesc>> {
+    let (input, base) = name_token(input)?;
+    let (input, _) = op("<")(input)?;
+    let (input, args) = separated(base_type, op(","), false)(input)?;
+    let (input, r_bracket) = op(">")(input)?;
+
+    Ok((
+        input,
+        Spanned {
+            node: TypeDesc::Generic {
+                base: base.string,
+                args,
+            },
+            span: Span::from_pair(base, r_bracket),
+        },
+    ))
 }
 
 /// Parse a map type e.g. "map<address, bool".
 pub fn map_type(input: Cursor) -> ParseResult<Spanned<TypeDesc>> {
-    alt((map_type_double, map_type_single))(input)
+    alt((map_type_double, map_generic_double, map_type_single))(input)
+}
+
+/// Parse a map type with a generic value type ending with a right-shift token.
+///
+/// Example:
+/// map<address, Array<u256>>
+pub fn map_generic_double(input: Cursor) -> ParseResult<Spanned<TypeDesc>> {
+    let (input, map_kw) = name("map")(input)?;
+    let (input, _) = op("<")(input)?;
+    let (input, from) = base_type(input)?;
+    let (input, _) = op(",")(input)?;
+
+    let (input, base) = name_token(input)?;
+    let (input, _) = op("<")(input)?;
+    let (input, args) = separated(base_type, op(","), false)(input)?;
+    let (input, r_bracket) = op(">>")(input)?;
+
+    let generic = Spanned {
+        node: TypeDesc::Generic {
+            base: base.string,
+            args,
+        },
+        span: Span::new(base.span.start, r_bracket.span.end - 1),
+    };
+
+    Ok((
+        input,
+        Spanned {
+            node: TypeDesc::Map {
+                from: Box::new(from),
+                to: Box::new(generic),
+            },
+            span: Span::from_pair(map_kw, r_bracket),
+        },
+    ))
 }
 
 /// Parse a map type ending with a right-shift token.
@@ -864,6 +1527,8 @@ pub fn func_qual(input: Cursor) -> ParseResult<Spanned<FuncQual>> {
 }
 
 pub fn func_stmt(input: Cursor) -> ParseResult<Vec<Spanned<FuncStmt>>> {
+    // Doc comments on statements don't document anything, so they are ignored.
+    let (input, _) = doc_comments(input)?;
     alt((map(compound_stmt, |stmt| vec![stmt]), simple_stmt))(input)
 }
 
@@ -972,7 +1637,21 @@ pub fn continue_stmt(input: Cursor) -> ParseResult<Spanned<FuncStmt>> {
 }
 
 pub fn revert_stmt(input: Cursor) -> ParseResult<Spanned<FuncStmt>> {
-    keyword_statement("revert", || FuncStmt::Revert)(input)
+    let (input, revert_kw) = name("revert")(input)?;
+    let (input, error) = opt(expr)(input)?;
+
+    let span = match &error {
+        Some(error) => Span::from_pair(revert_kw, error),
+        None => revert_kw.span,
+    };
+
+    Ok((
+        input,
+        Spanned {
+            node: FuncStmt::Revert { error },
+            span,
+        },
+    ))
 }
 
 pub fn vardecl_stmt(input: Cursor) -> ParseResult<Spanned<FuncStmt>> {
@@ -1052,7 +1731,7 @@ pub fn augassign_stmt(input: Cursor) -> ParseResult<Spanned<FuncStmt>> {
 }
 
 pub fn compound_stmt(input: Cursor) -> ParseResult<Spanned<FuncStmt>> {
-    alt((if_stmt, while_stmt, for_stmt))(input)
+    alt((if_stmt, while_stmt, for_stmt, match_stmt))(input)
 }
 
 #[allow(clippy::needless_lifetimes)]
@@ -1129,6 +1808,45 @@ pub fn else_block(input: Cursor) -> ParseResult<Vec<Spanned<FuncStmt>>> {
     Ok((input, stmts))
 }
 
+/// Parse a `match` statement. Each arm starts with `case` followed by a
+/// pattern and the block that runs if the value matches it.
+pub fn match_stmt(input: Cursor) -> ParseResult<Spanned<FuncStmt>> {
+    let (input, match_kw) = name("match")(input)?;
+    let (input, value) = expr(input)?;
+    let (input, _) = op(":")(input)?;
+    let (input, _) = newline_token(input)?;
+    let (input, _) = indent_token(input)?;
+    let (input, arms) = many1(match_arm)(input)?;
+    let (input, _) = dedent_token(input)?;
+
+    let span = Span::from_pair(match_kw, arms.last().unwrap());
+
+    Ok((
+        input,
+        Spanned {
+            node: FuncStmt::Match { value, arms },
+            span,
+        },
+    ))
+}
+
+pub fn match_arm(input: Cursor) -> ParseResult<Spanned<MatchArm>> {
+    let (input, case_kw) = name("case")(input)?;
+    let (input, pattern) = expr(input)?;
+    let (input, _) = op(":")(input)?;
+    let (input, body) = block(input)?;
+
+    let span = Span::from_pair(case_kw, body.last().unwrap());
+
+    Ok((
+        input,
+        Spanned {
+            node: MatchArm { pattern, body },
+            span,
+        },
+    ))
+}
+
 pub fn while_stmt(input: Cursor) -> ParseResult<Spanned<FuncStmt>> {
     let (input, while_kw) = name("while")(input)?;
     let (input, test) = expr(input)?;
@@ -1190,7 +1908,12 @@ pub fn block(input: Cursor) -> ParseResult<Vec<Spanned<FuncStmt>>> {
     alt((simple_stmt, |input| {
         let (input, _) = newline_token(input)?;
         let (input, _) = indent_token(input)?;
-        let (input, stmts) = many1(func_stmt)(input)?;
+        let (input, stmts) = recovering_many1(func_stmt, |error, span| {
+            vec![Spanned {
+                node: FuncStmt::Error(error),
+                span,
+            }]
+        })(input)?;
         let (input, _) = dedent_token(input)?;
 
         let result: Vec<_> = stmts.into_iter().flatten().collect();
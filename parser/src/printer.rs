@@ -0,0 +1,1084 @@
+//! Printing of ASTs as Fe source code, e.g. for formatters and for tools that
+//! rewrite code.
+//!
+//! The printed code is canonical: it's indented with four spaces, and its
+//! spacing and parentheses don't depend on how the code was written, so
+//! printing the AST of printed code yields the same code again. Comments
+//! aren't part of the AST, so they can only be printed if the source code
+//! of the AST is given. In that case, blank lines between statements are kept
+//! as well, and statements that can't be parsed are copied from the source.
+
+use crate::ast::*;
+use crate::span::{
+    Span,
+    Spanned,
+};
+use crate::tokenizer::{
+    tokenize,
+    TokenType,
+};
+
+const INDENT: &str = "    ";
+
+/// Prints the module without comments.
+pub fn print_module(module: &Module) -> String {
+    Printer::new(None).module(module)
+}
+
+/// Prints the module with the comments of `src`, which is the source code
+/// that the module was parsed from. Each comment is printed on its own line
+/// before the statement that follows it, unless it's on the same line as the
+/// end of a simple statement, in which case it stays there.
+pub fn print_module_with_comments(module: &Module, src: &str) -> String {
+    Printer::new(Some(src)).module(module)
+}
+
+/// Prints an expression.
+pub fn print_expr(expr: &Expr) -> String {
+    expr_with_precedence(expr, 0)
+}
+
+/// Prints a type description.
+pub fn print_type_desc(typ: &TypeDesc) -> String {
+    match typ {
+        TypeDesc::Base { base } => base.to_string(),
+        TypeDesc::Array { typ, dimension } => {
+            format!("{}[{}]", print_type_desc(&typ.node), dimension)
+        }
+        TypeDesc::Map { from, to } => format!(
+            "map<{}, {}>",
+            print_type_desc(&from.node),
+            print_type_desc(&to.node)
+        ),
+        TypeDesc::Tuple { items } => format!("({})", type_list(items)),
+        TypeDesc::Generic { base, args } => format!("{}<{}>", base, type_list(args)),
+    }
+}
+
+struct Printer<'a> {
+    src: Option<&'a str>,
+    /// The comments of the source code, in order.
+    comments: Vec<Spanned<&'a str>>,
+    /// The index of the first comment that hasn't been printed yet.
+    next_comment: usize,
+    indent: usize,
+    out: String,
+}
+
+impl<'a> Printer<'a> {
+    fn new(src: Option<&'a str>) -> Self {
+        let comments = src
+            .and_then(|src| tokenize(src).ok())
+            .map(|tokens| {
+                tokens
+                    .into_iter()
+                    .filter(|token| token.typ == TokenType::COMMENT)
+                    .map(|token| Spanned {
+                        node: token.string,
+                        span: token.span,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Printer {
+            src,
+            comments,
+            next_comment: 0,
+            indent: 0,
+            out: String::new(),
+        }
+    }
+
+    fn module(mut self, module: &Module) -> String {
+        let mut prev: Option<&Spanned<ModuleStmt>> = None;
+        for stmt in module.body.iter() {
+            if let Some(prev) = prev {
+                let grouped = matches!(
+                    (&prev.node, &stmt.node),
+                    (
+                        ModuleStmt::SimpleImport { .. } | ModuleStmt::FromImport { .. },
+                        ModuleStmt::SimpleImport { .. } | ModuleStmt::FromImport { .. }
+                    ) | (
+                        ModuleStmt::ConstDef { .. } | ModuleStmt::TypeDef { .. },
+                        ModuleStmt::ConstDef { .. } | ModuleStmt::TypeDef { .. }
+                    )
+                );
+                self.separate(prev.span, stmt.span, grouped);
+            }
+            self.module_stmt(stmt);
+            prev = Some(stmt);
+        }
+        self.comments_before(usize::MAX);
+
+        self.out
+    }
+
+    fn module_stmt(&mut self, stmt: &Spanned<ModuleStmt>) {
+        self.comments_before(stmt.span.start);
+
+        match &stmt.node {
+            ModuleStmt::TypeDef { docs, name, typ } => {
+                self.docs(docs);
+                self.simple_line(
+                    &format!("type {} = {}", name.node, print_type_desc(&typ.node)),
+                    stmt.span,
+                );
+            }
+            ModuleStmt::SimpleImport { names } => {
+                let names = names
+                    .iter()
+                    .map(|name| aliased(&dotted(&name.node.path), &name.node.alias))
+                    .collect::<Vec<_>>();
+                self.simple_line(&format!("import {}", names.join(", ")), stmt.span);
+            }
+            ModuleStmt::FromImport { path, names } => {
+                let path = match &path.node {
+                    FromImportPath::Absolute { path } => dotted(path),
+                    FromImportPath::Relative { parent_level, path } => {
+                        format!("{}{}", ".".repeat(parent_level + 1), dotted(path))
+                    }
+                };
+                let names = match &names.node {
+                    FromImportNames::Star => "*".to_string(),
+                    FromImportNames::List(names) => names
+                        .iter()
+                        .map(|name| aliased(name.node.name.node, &name.node.alias))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                };
+                self.simple_line(&format!("from {} import {}", path, names), stmt.span);
+            }
+            ModuleStmt::ConstDef {
+                docs,
+                decorators,
+                name,
+                typ,
+                value,
+            } => {
+                self.docs(docs);
+                self.decorators(decorators);
+                self.simple_line(&const_def(name.node, &typ.node, &value.node), stmt.span);
+            }
+            ModuleStmt::ContractDef {
+                docs,
+                decorators,
+                name,
+                mixins,
+                traits,
+                body,
+            } => {
+                self.docs(docs);
+                self.decorators(decorators);
+                let mut header = format!("contract {}", name.node);
+                if !mixins.is_empty() {
+                    header.push_str(&format!(" uses {}", names(mixins)));
+                }
+                if !traits.is_empty() {
+                    header.push_str(&format!(" implements {}", names(traits)));
+                }
+                self.line(&format!("{}:", header));
+                self.contract_body(body);
+            }
+            ModuleStmt::MixinDef { docs, name, body } => {
+                self.docs(docs);
+                self.line(&format!("mixin {}:", name.node));
+                self.contract_body(body);
+            }
+            ModuleStmt::LibraryDef { docs, name, body } => {
+                self.docs(docs);
+                self.line(&format!("library {}:", name.node));
+                self.contract_body(body);
+            }
+            ModuleStmt::TraitDef { docs, name, body } => {
+                self.docs(docs);
+                self.line(&format!("trait {}:", name.node));
+                self.func_sigs(body);
+            }
+            ModuleStmt::InterfaceDef { docs, name, body } => {
+                self.docs(docs);
+                self.line(&format!("interface {}:", name.node));
+                self.func_sigs(body);
+            }
+            ModuleStmt::StructDef {
+                docs,
+                decorators,
+                name,
+                generics,
+                body,
+            } => {
+                self.docs(docs);
+                self.decorators(decorators);
+                self.line(&format!("struct {}{}:", name.node, type_params(generics)));
+                self.indent += 1;
+                for field in body.iter() {
+                    let StructStmt::StructField {
+                        docs,
+                        qual,
+                        name,
+                        typ,
+                    } = &field.node;
+                    let qual = match qual.as_ref().map(|qual| &qual.node) {
+                        Some(StructFieldQual::Const) => "const ",
+                        Some(StructFieldQual::Pub) => "pub ",
+                        None => "",
+                    };
+                    self.comments_before(field.span.start);
+                    self.docs(docs);
+                    self.simple_line(
+                        &format!("{}{}: {}", qual, name.node, print_type_desc(&typ.node)),
+                        field.span,
+                    );
+                }
+                self.indent -= 1;
+            }
+            ModuleStmt::EnumDef {
+                docs,
+                name,
+                variants,
+            } => {
+                self.docs(docs);
+                self.line(&format!("enum {}:", name.node));
+                self.indent += 1;
+                for variant in variants.iter() {
+                    self.comments_before(variant.span.start);
+                    self.simple_line(variant.node, variant.span);
+                }
+                self.indent -= 1;
+            }
+            ModuleStmt::FuncDef { def } => self.contract_stmt(def),
+            ModuleStmt::Error(error) => self.syntax_error(error, stmt.span),
+        }
+    }
+
+    fn contract_body(&mut self, body: &[Spanned<ContractStmt>]) {
+        self.indent += 1;
+        let mut prev: Option<&Spanned<ContractStmt>> = None;
+        for stmt in body.iter() {
+            if let Some(prev) = prev {
+                let grouped = matches!(
+                    (&prev.node, &stmt.node),
+                    (
+                        ContractStmt::ContractField { .. } | ContractStmt::ConstDef { .. },
+                        ContractStmt::ContractField { .. } | ContractStmt::ConstDef { .. }
+                    )
+                );
+                self.separate(prev.span, stmt.span, grouped);
+            }
+            self.contract_stmt(stmt);
+            prev = Some(stmt);
+        }
+        self.indent -= 1;
+    }
+
+    fn contract_stmt(&mut self, stmt: &Spanned<ContractStmt>) {
+        self.comments_before(stmt.span.start);
+
+        match &stmt.node {
+            ContractStmt::ContractField {
+                docs,
+                qual,
+                name,
+                typ,
+            } => {
+                let qual = match qual.as_ref().map(|qual| &qual.node) {
+                    Some(ContractFieldQual::Const) => "const ",
+                    Some(ContractFieldQual::Pub) => "pub ",
+                    None => "",
+                };
+                self.docs(docs);
+                self.simple_line(
+                    &format!("{}{}: {}", qual, name.node, print_type_desc(&typ.node)),
+                    stmt.span,
+                );
+            }
+            ContractStmt::ConstDef {
+                docs,
+                decorators,
+                name,
+                typ,
+                value,
+            } => {
+                self.docs(docs);
+                self.decorators(decorators);
+                self.simple_line(&const_def(name.node, &typ.node, &value.node), stmt.span);
+            }
+            ContractStmt::EventDef { docs, name, fields } => {
+                self.docs(docs);
+                self.line(&format!("event {}:", name.node));
+                self.indent += 1;
+                for field in fields.iter() {
+                    let qual = match &field.node.qual {
+                        Some(_) => "idx ",
+                        None => "",
+                    };
+                    self.comments_before(field.span.start);
+                    self.simple_line(
+                        &format!(
+                            "{}{}: {}",
+                            qual,
+                            field.node.name.node,
+                            print_type_desc(&field.node.typ.node)
+                        ),
+                        field.span,
+                    );
+                }
+                self.indent -= 1;
+            }
+            ContractStmt::ErrorDef { docs, name, fields } => {
+                self.docs(docs);
+                if fields.is_empty() {
+                    self.simple_line(&format!("error {}", name.node), stmt.span);
+                } else {
+                    self.line(&format!("error {}:", name.node));
+                    self.indent += 1;
+                    for field in fields.iter() {
+                        self.comments_before(field.span.start);
+                        self.simple_line(&arg(&field.node), field.span);
+                    }
+                    self.indent -= 1;
+                }
+            }
+            ContractStmt::FuncDef {
+                docs,
+                decorators,
+                qual,
+                name,
+                generics,
+                args,
+                return_type,
+                return_names,
+                body,
+            } => {
+                self.docs(docs);
+                self.decorators(decorators);
+                let qual = match qual {
+                    Some(_) => "pub ",
+                    None => "",
+                };
+                self.line(&format!(
+                    "{}def {}{}:",
+                    qual,
+                    name.node,
+                    signature(generics, args, return_type, return_names)
+                ));
+                self.block(body);
+            }
+            ContractStmt::Error(error) => self.syntax_error(error, stmt.span),
+        }
+    }
+
+    fn func_sigs(&mut self, sigs: &[Spanned<FuncSig>]) {
+        self.indent += 1;
+        let mut prev: Option<Span> = None;
+        for sig in sigs.iter() {
+            if let Some(prev) = prev {
+                self.separate(prev, sig.span, true);
+            }
+            self.comments_before(sig.span.start);
+            self.docs(&sig.node.docs);
+            self.simple_line(
+                &format!(
+                    "def {}{}",
+                    sig.node.name.node,
+                    signature(&[], &sig.node.args, &sig.node.return_type, &[])
+                ),
+                sig.span,
+            );
+            prev = Some(sig.span);
+        }
+        self.indent -= 1;
+    }
+
+    fn block(&mut self, body: &[Spanned<FuncStmt>]) {
+        self.indent += 1;
+        let mut prev: Option<Span> = None;
+        for stmt in body.iter() {
+            if let Some(prev) = prev {
+                self.separate(prev, stmt.span, true);
+            }
+            self.func_stmt(stmt);
+            prev = Some(stmt.span);
+        }
+        self.indent -= 1;
+    }
+
+    fn func_stmt(&mut self, stmt: &Spanned<FuncStmt>) {
+        self.comments_before(stmt.span.start);
+
+        let line = match &stmt.node {
+            FuncStmt::Return { value: None } => "return".to_string(),
+            FuncStmt::Return { value: Some(value) } => {
+                format!("return {}", print_expr(&value.node))
+            }
+            FuncStmt::VarDecl { target, typ, value } => {
+                let decl = format!(
+                    "{}: {}",
+                    print_expr(&target.node),
+                    print_type_desc(&typ.node)
+                );
+                match value {
+                    Some(value) => format!("{} = {}", decl, print_expr(&value.node)),
+                    None => decl,
+                }
+            }
+            FuncStmt::Assign { targets, value } => {
+                let mut parts = targets
+                    .iter()
+                    .map(|target| print_expr(&target.node))
+                    .collect::<Vec<_>>();
+                parts.push(print_expr(&value.node));
+                parts.join(" = ")
+            }
+            FuncStmt::AugAssign { target, op, value } => format!(
+                "{} {}= {}",
+                print_expr(&target.node),
+                bin_op(&op.node),
+                print_expr(&value.node)
+            ),
+            FuncStmt::For {
+                target,
+                iter,
+                body,
+                or_else,
+            } => {
+                self.line(&format!(
+                    "for {} in {}:",
+                    print_expr(&target.node),
+                    print_expr(&iter.node)
+                ));
+                self.block(body);
+                self.else_block(or_else);
+                return;
+            }
+            FuncStmt::While {
+                test,
+                body,
+                or_else,
+            } => {
+                self.line(&format!("while {}:", print_expr(&test.node)));
+                self.block(body);
+                self.else_block(or_else);
+                return;
+            }
+            FuncStmt::If {
+                test,
+                body,
+                or_else,
+            } => {
+                self.if_stmt("if", test, body, or_else);
+                return;
+            }
+            FuncStmt::Match { value, arms } => {
+                self.line(&format!("match {}:", print_expr(&value.node)));
+                self.indent += 1;
+                for arm in arms.iter() {
+                    self.comments_before(arm.span.start);
+                    self.line(&format!("case {}:", print_expr(&arm.node.pattern.node)));
+                    self.block(&arm.node.body);
+                }
+                self.indent -= 1;
+                return;
+            }
+            FuncStmt::Assert { test, msg: None } => format!("assert {}", print_expr(&test.node)),
+            FuncStmt::Assert {
+                test,
+                msg: Some(msg),
+            } => format!(
+                "assert {}, {}",
+                print_expr(&test.node),
+                print_expr(&msg.node)
+            ),
+            FuncStmt::Emit { value } => format!("emit {}", print_expr(&value.node)),
+            FuncStmt::Expr { value } => print_expr(value),
+            FuncStmt::Pass => "pass".to_string(),
+            FuncStmt::Break => "break".to_string(),
+            FuncStmt::Continue => "continue".to_string(),
+            FuncStmt::Revert { error: None } => "revert".to_string(),
+            FuncStmt::Revert { error: Some(error) } => {
+                format!("revert {}", print_expr(&error.node))
+            }
+            FuncStmt::Error(error) => {
+                self.syntax_error(error, stmt.span);
+                return;
+            }
+        };
+
+        self.simple_line(&line, stmt.span);
+    }
+
+    /// Prints an `if` or `elif` statement. An `else` block that only contains
+    /// another `if` statement is printed as an `elif`.
+    fn if_stmt(
+        &mut self,
+        keyword: &str,
+        test: &Spanned<Expr>,
+        body: &[Spanned<FuncStmt>],
+        or_else: &[Spanned<FuncStmt>],
+    ) {
+        self.line(&format!("{} {}:", keyword, print_expr(&test.node)));
+        self.block(body);
+
+        match or_else {
+            [Spanned {
+                node:
+                    FuncStmt::If {
+                        test,
+                        body,
+                        or_else,
+                    },
+                span,
+            }] => {
+                self.comments_before(span.start);
+                self.if_stmt("elif", test, body, or_else);
+            }
+            _ => self.else_block(or_else),
+        }
+    }
+
+    fn else_block(&mut self, or_else: &[Spanned<FuncStmt>]) {
+        if !or_else.is_empty() {
+            self.line("else:");
+            self.block(or_else);
+        }
+    }
+
+    fn docs(&mut self, docs: &[Spanned<&str>]) {
+        for doc in docs.iter() {
+            if doc.node.is_empty() {
+                self.line("///");
+            } else {
+                self.line(&format!("/// {}", doc.node));
+            }
+        }
+    }
+
+    fn decorators(&mut self, decorators: &[Spanned<Expr>]) {
+        for decorator in decorators.iter() {
+            self.line(&format!("@{}", print_expr(&decorator.node)));
+        }
+    }
+
+    /// Copies a statement that couldn't be parsed from the source code, or
+    /// prints its error as a comment if there is no source code.
+    fn syntax_error(&mut self, error: &SyntaxError, span: Span) {
+        match self.src.and_then(|src| src.get(span.start..span.end)) {
+            Some(code) => {
+                let mut lines = code.lines();
+                if let Some(first) = lines.next() {
+                    self.line(first);
+                }
+                for line in lines {
+                    self.out.push_str(line);
+                    self.out.push('\n');
+                }
+                // the comments of the statement were copied with it
+                while let Some(comment) = self.comments.get(self.next_comment) {
+                    if comment.span.start >= span.end {
+                        break;
+                    }
+                    self.next_comment += 1;
+                }
+            }
+            None => self.line(&format!("# syntax error: {}", error.message)),
+        }
+    }
+
+    /// Prints a line that ends a statement, along with a comment that follows
+    /// it on the same line of the source code.
+    fn simple_line(&mut self, text: &str, span: Span) {
+        match (self.src, self.comments.get(self.next_comment)) {
+            (Some(src), Some(comment))
+                if comment.span.start >= span.end
+                    && !src[span.end..comment.span.start].contains('\n') =>
+            {
+                let line = format!("{}  {}", text, comment.node);
+                self.next_comment += 1;
+                self.line(&line);
+            }
+            _ => self.line(text),
+        }
+    }
+
+    /// Prints the comments that start before the given position.
+    fn comments_before(&mut self, position: usize) {
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            if comment.span.start >= position {
+                break;
+            }
+            let comment = comment.node;
+            self.next_comment += 1;
+            self.line(comment);
+        }
+    }
+
+    /// Separates two statements with a blank line, unless they're grouped
+    /// and there is no blank line between them in the source code.
+    fn separate(&mut self, prev: Span, next: Span, grouped: bool) {
+        let blank_in_src = self
+            .src
+            .and_then(|src| src.get(prev.end..next.start))
+            .map_or(false, |gap| {
+                let lines = gap.split('\n').collect::<Vec<_>>();
+                lines.len() > 2
+                    && lines[1..lines.len() - 1]
+                        .iter()
+                        .any(|line| line.trim().is_empty())
+            });
+
+        if !grouped || blank_in_src {
+            self.out.push('\n');
+        }
+    }
+
+    fn line(&mut self, text: &str) {
+        for _ in 0..self.indent {
+            self.out.push_str(INDENT);
+        }
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+}
+
+fn const_def(name: &str, typ: &TypeDesc, value: &Expr) -> String {
+    format!(
+        "const {}: {} = {}",
+        name,
+        print_type_desc(typ),
+        print_expr(value)
+    )
+}
+
+/// The generic parameters, arguments and return type of a function.
+fn signature(
+    generics: &[Spanned<&str>],
+    args: &[Spanned<FuncDefArg>],
+    return_type: &Option<Spanned<TypeDesc>>,
+    return_names: &[Spanned<&str>],
+) -> String {
+    let args = args
+        .iter()
+        .map(|arg_def| arg(&arg_def.node))
+        .collect::<Vec<_>>();
+    let mut signature = format!("{}({})", type_params(generics), args.join(", "));
+
+    match return_type.as_ref().map(|typ| &typ.node) {
+        Some(TypeDesc::Tuple { items }) if !return_names.is_empty() => {
+            let items = return_names
+                .iter()
+                .zip(items.iter())
+                .map(|(name, typ)| format!("{}: {}", name.node, print_type_desc(&typ.node)))
+                .collect::<Vec<_>>();
+            signature.push_str(&format!(" -> ({})", items.join(", ")));
+        }
+        Some(typ) => signature.push_str(&format!(" -> {}", print_type_desc(typ))),
+        None => {}
+    }
+
+    signature
+}
+
+fn arg(arg: &FuncDefArg) -> String {
+    format!("{}: {}", arg.name.node, print_type_desc(&arg.typ.node))
+}
+
+fn type_params(params: &[Spanned<&str>]) -> String {
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", names(params))
+    }
+}
+
+fn type_list(types: &[Spanned<TypeDesc>]) -> String {
+    types
+        .iter()
+        .map(|typ| print_type_desc(&typ.node))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn names(names: &[Spanned<&str>]) -> String {
+    names
+        .iter()
+        .map(|name| name.node)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn dotted(path: &[Spanned<&str>]) -> String {
+    path.iter()
+        .map(|name| name.node)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn aliased(name: &str, alias: &Option<Spanned<&str>>) -> String {
+    match alias {
+        Some(alias) => format!("{} as {}", name, alias.node),
+        None => name.to_string(),
+    }
+}
+
+/// How tightly an expression binds, from 1 for ternary expressions to 14 for
+/// atoms and the expressions built from them by attributes, subscripts and
+/// calls.
+fn precedence(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Ternary { .. } => 1,
+        Expr::BoolOperation { op, .. } => match op.node {
+            BoolOperator::Or => 2,
+            BoolOperator::And => 3,
+        },
+        Expr::UnaryOperation { op, .. } if op.node == UnaryOperator::Not => 4,
+        Expr::CompOperation { .. } => 5,
+        Expr::BinOperation { op, .. } => match op.node {
+            BinOperator::BitOr => 6,
+            BinOperator::BitXor => 7,
+            BinOperator::BitAnd => 8,
+            BinOperator::LShift | BinOperator::RShift => 9,
+            BinOperator::Add | BinOperator::Sub => 10,
+            BinOperator::Mult | BinOperator::Div | BinOperator::FloorDiv | BinOperator::Mod => 11,
+            BinOperator::Pow => 13,
+        },
+        Expr::UnaryOperation { .. } => 12,
+        _ => 14,
+    }
+}
+
+/// Prints an expression, in parentheses if it binds less tightly than
+/// `precedence`.
+fn expr_with_precedence(expr: &Expr, min_precedence: u8) -> String {
+    let printed = match expr {
+        Expr::Ternary {
+            if_expr,
+            test,
+            else_expr,
+        } => format!(
+            "{} if {} else {}",
+            operand(if_expr, 2),
+            operand(test, 2),
+            operand(else_expr, 1)
+        ),
+        Expr::BoolOperation { left, op, right } => {
+            let op_precedence = precedence(expr);
+            let op = match op.node {
+                BoolOperator::And => "and",
+                BoolOperator::Or => "or",
+            };
+            format!(
+                "{} {} {}",
+                operand(left, op_precedence),
+                op,
+                operand(right, op_precedence + 1)
+            )
+        }
+        // `**` is right-associative and binds less tightly than a unary
+        // operator on its right
+        Expr::BinOperation {
+            left,
+            op: Spanned {
+                node: BinOperator::Pow,
+                ..
+            },
+            right,
+        } => format!("{} ** {}", operand(left, 14), operand(right, 12)),
+        Expr::BinOperation { left, op, right } => {
+            let op_precedence = precedence(expr);
+            format!(
+                "{} {} {}",
+                operand(left, op_precedence),
+                bin_op(&op.node),
+                operand(right, op_precedence + 1)
+            )
+        }
+        Expr::UnaryOperation { op, operand: value } => match op.node {
+            UnaryOperator::Not => format!("not {}", operand(value, 4)),
+            UnaryOperator::Invert => format!("~{}", operand(value, 12)),
+            UnaryOperator::UAdd => format!("+{}", operand(value, 12)),
+            UnaryOperator::USub => format!("-{}", operand(value, 12)),
+        },
+        Expr::CompOperation { left, op, right } => {
+            let op = match op.node {
+                CompOperator::Eq => "==",
+                CompOperator::NotEq => "!=",
+                CompOperator::Lt => "<",
+                CompOperator::LtE => "<=",
+                CompOperator::Gt => ">",
+                CompOperator::GtE => ">=",
+                CompOperator::Is => "is",
+                CompOperator::IsNot => "is not",
+                CompOperator::In => "in",
+                CompOperator::NotIn => "not in",
+            };
+            format!("{} {} {}", operand(left, 5), op, operand(right, 6))
+        }
+        Expr::Attribute { value, attr } => format!("{}.{}", operand(value, 14), attr.node),
+        Expr::Subscript { value, slices } => {
+            let slices = slices
+                .node
+                .iter()
+                .map(|slice| match &slice.node {
+                    Slice::Index(index) => print_expr(index),
+                    Slice::Slice { lower, upper, step } => {
+                        let bound = |bound: Option<&Spanned<Expr>>| {
+                            bound.map_or(String::new(), |bound| print_expr(&bound.node))
+                        };
+                        let (lower, upper, step) =
+                            (lower.as_deref(), upper.as_deref(), step.as_deref());
+                        match step {
+                            Some(_) => format!("{}:{}:{}", bound(lower), bound(upper), bound(step)),
+                            None => format!("{}:{}", bound(lower), bound(upper)),
+                        }
+                    }
+                })
+                .collect::<Vec<_>>();
+            format!("{}[{}]", operand(value, 14), slices.join(", "))
+        }
+        Expr::Call { func, args } => {
+            let args = args
+                .node
+                .iter()
+                .map(|arg| match &arg.node {
+                    CallArg::Arg(value) => print_expr(value),
+                    CallArg::Kwarg(Kwarg { name, value }) => {
+                        format!("{}={}", name.node, print_expr(&value.node))
+                    }
+                })
+                .collect::<Vec<_>>();
+            format!("{}({})", operand(func, 14), args.join(", "))
+        }
+        // a list of a single tuple needs a trailing comma, since the items of
+        // the tuple would be taken as the items of the list otherwise
+        Expr::List { elts } => match elts.as_slice() {
+            [Spanned {
+                node: Expr::Tuple { .. },
+                ..
+            }] => format!("[{},]", list(elts)),
+            _ => format!("[{}]", list(elts)),
+        },
+        Expr::ListComp { elt, comps } => {
+            let mut printed = print_expr(&elt.node);
+            for comp in comps.iter() {
+                printed.push_str(&format!(
+                    " for {} in {}",
+                    print_expr(&comp.node.target.node),
+                    operand(&comp.node.iter, 2)
+                ));
+                for cond in comp.node.ifs.iter() {
+                    printed.push_str(&format!(" if {}", operand(cond, 2)));
+                }
+            }
+            format!("[{}]", printed)
+        }
+        Expr::Tuple { elts } => match elts.len() {
+            1 => format!("({},)", list(elts)),
+            _ => format!("({})", list(elts)),
+        },
+        Expr::Bool(true) => "true".to_string(),
+        Expr::Bool(false) => "false".to_string(),
+        Expr::Name(name) => name.to_string(),
+        Expr::Num(num) => num.to_string(),
+        Expr::Str(lines) => lines
+            .iter()
+            .map(|line| quoted(line))
+            .collect::<Vec<_>>()
+            .join(" "),
+        Expr::Ellipsis => "...".to_string(),
+    };
+
+    if precedence(expr) < min_precedence {
+        format!("({})", printed)
+    } else {
+        printed
+    }
+}
+
+fn operand(expr: &Spanned<Expr>, min_precedence: u8) -> String {
+    expr_with_precedence(&expr.node, min_precedence)
+}
+
+fn list(elts: &[Spanned<Expr>]) -> String {
+    elts.iter()
+        .map(|elt| print_expr(&elt.node))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Puts the contents of a string literal back in quotes. The parser strips a
+/// single quote from each side, so two of the three quotes of each side of a
+/// triple-quoted string are still there.
+fn quoted(string: &str) -> String {
+    let single = (string.len() >= 4 && string.starts_with("''") && string.ends_with("''"))
+        || (string.contains('"') && !string.starts_with('"') && !string.contains('\''));
+
+    if single {
+        format!("'{}'", string)
+    } else {
+        format!("\"{}\"", string)
+    }
+}
+
+fn bin_op(op: &BinOperator) -> &'static str {
+    match op {
+        BinOperator::Add => "+",
+        BinOperator::Sub => "-",
+        BinOperator::Mult => "*",
+        BinOperator::Div => "/",
+        BinOperator::Mod => "%",
+        BinOperator::Pow => "**",
+        BinOperator::LShift => "<<",
+        BinOperator::RShift => ">>",
+        BinOperator::BitOr => "|",
+        BinOperator::BitXor => "^",
+        BinOperator::BitAnd => "&",
+        BinOperator::FloorDiv => "//",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::printer::{
+        print_module,
+        print_module_with_comments,
+    };
+    use serde_json::Value;
+
+    const SRC: &str = "\
+import foo.bar as baz, qux
+from ..utils import (a as b, c)
+from . import *
+type Addresses = address[10]
+const MAX: u256 = 2 ** 8 - 1
+
+struct Pair<T>:
+    pub first: T
+    second: map<address, map<address, u256>>
+
+enum Color:
+    Red
+    Blue
+
+/// A token.
+@cfg(feature=\"mainnet\")
+contract Token uses Ownable implements ERC20:
+    pub balances: map<address, u256>
+    const SUPPLY: u256 = 100
+
+    event Transfer:
+        idx sender: address
+        value: u256
+
+    error Unauthorized
+
+    error TooLow:
+        amount: u256
+
+    /// Divides.
+    pub def div(a: u256, b: u256) -> (quotient: u256, remainder: u256):
+        assert b != 0, \"division by zero\"
+        return (a // b, a % b)
+
+    def run(xs: u256[3]):
+        x: u256 = -(xs[0] + 1) * 2
+        y, z = a = (1, 2)
+        x += 1 if not (y and z) else ~x
+        for i in xs[1:]:
+            if i < 10 or i in xs:
+                continue
+            elif not i is not y:
+                break
+            else:
+                pass
+        while x > 0:
+            x -= 1
+        match x:
+            case 1:
+                revert
+            case _:
+                revert Unauthorized()
+        emit Transfer(sender=msg.sender, value=(x,))
+        self.call(-2 ** x, [(1, 2),], 'say \"hi\"', \"a\" \"b\", ...)
+
+trait Sized:
+    def size() -> u256
+";
+
+    /// The AST of the source code as JSON, without spans.
+    fn ast(src: &str) -> Value {
+        fn strip_spans(value: &mut Value) {
+            match value {
+                Value::Object(object) => {
+                    object.remove("span");
+                    object.values_mut().for_each(strip_spans);
+                }
+                Value::Array(array) => array.iter_mut().for_each(strip_spans),
+                _ => {}
+            }
+        }
+
+        let tokens = crate::get_parse_tokens(src).unwrap();
+        let module = crate::parsers::file_input(&tokens[..]).unwrap().1.node;
+        let mut value = serde_json::to_value(&module).unwrap();
+        strip_spans(&mut value);
+
+        value
+    }
+
+    fn print(src: &str, comments: bool) -> String {
+        let tokens = crate::get_parse_tokens(src).unwrap();
+        let module = crate::parsers::file_input(&tokens[..]).unwrap().1.node;
+
+        if comments {
+            print_module_with_comments(&module, src)
+        } else {
+            print_module(&module)
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let printed = print(SRC, false);
+        assert_eq!(ast(&printed), ast(SRC));
+        assert_eq!(print(&printed, false), printed);
+
+        assert!(printed.contains("import foo.bar as baz, qux\nfrom ..utils import a as b, c\n"));
+        assert!(printed
+            .contains("    pub def div(a: u256, b: u256) -> (quotient: u256, remainder: u256):\n"));
+        assert!(printed.contains("        x: u256 = -(xs[0] + 1) * 2\n"));
+        assert!(printed.contains("            elif not i is not y:\n"));
+        assert!(printed.contains("value=(x,))\n"));
+    }
+
+    #[test]
+    fn comments() {
+        let src = "\
+# The counter.
+contract Counter:
+    count: u256  # the count
+
+    pub def increment():
+        # add one
+        self.count   =   self.count+1
+
+        return
+# the end
+";
+        assert_eq!(
+            print(src, true),
+            "\
+# The counter.
+contract Counter:
+    count: u256  # the count
+
+    pub def increment():
+        # add one
+        self.count = self.count + 1
+
+        return
+# the end
+"
+        );
+        assert_eq!(print(src, false).matches('#').count(), 0);
+    }
+}
@@ -42,6 +42,7 @@ pub mod wasm;
 
 pub use self::tokenize::{
     tokenize,
+    tokenize_partial,
     TokenizeError,
 };
 pub use self::types::{
@@ -43,9 +43,21 @@ pub struct TokenizeError {
 /// Returns:
 ///
 /// A vector of tokens.
+pub fn tokenize(input: &str) -> Result<Vec<Token>, TokenizeError> {
+    match tokenize_partial(input) {
+        (tokens, None) => Ok(tokens),
+        (_, Some(error)) => Err(error),
+    }
+}
+
+/// Parse a source string into a vector of tokens, stopping at the first
+/// error.
+///
+/// Returns the tokens preceding the error along with the error, so tools
+/// like syntax highlighters can still use them.
 #[allow(clippy::cognitive_complexity)]
 #[allow(clippy::trivial_regex)]
-pub fn tokenize<'a>(input: &'a str) -> Result<Vec<Token<'a>>, TokenizeError> {
+pub fn tokenize_partial<'a>(input: &'a str) -> (Vec<Token<'a>>, Option<TokenizeError>) {
     // Static values/helpers
     let pseudo_token_re = compile_anchored(&get_pseudotoken_pattern());
 
@@ -92,6 +104,11 @@ pub fn tokenize<'a>(input: &'a str) -> Result<Vec<Token<'a>>, TokenizeError> {
     let mut contstr_end_re: Option<&Regex> = None;
     let mut needcont: bool = false;
 
+    // Doc comments are emitted after the indentation tokens of the line that
+    // follows them, so that they directly precede the item they document.
+    // Doc comments at the end of the input document nothing and are dropped.
+    let mut pending_docs: Vec<Token<'a>> = Vec::new();
+
     for (line, line_start, line_end) in lines_with_endings(input) {
         // Set parsing position relative to this line
         let mut line_pos: usize = 0;
@@ -160,6 +177,29 @@ pub fn tokenize<'a>(input: &'a str) -> Result<Vec<Token<'a>>, TokenizeError> {
                 break;
             }
 
+            if line[line_pos..].starts_with("///") {
+                let doc_token = rstrip_slice(&line[line_pos..], "\r\n");
+                let doc_token_len = doc_token.len();
+
+                pending_docs.push(Token {
+                    typ: DOCCOMMENT,
+                    string: doc_token,
+                    span: Span::new(line_start + line_pos, line_start + line_pos + doc_token_len),
+                    line,
+                });
+
+                line_pos += doc_token_len;
+
+                result.push(Token {
+                    typ: NL,
+                    string: &line[line_pos..],
+                    span: Span::new(line_start + line_pos, line_end),
+                    line,
+                });
+
+                continue;
+            }
+
             {
                 let c = line[line_pos..].chars().next().unwrap();
                 if c == '#' || c == '\r' || c == '\n' {
@@ -204,10 +244,13 @@ pub fn tokenize<'a>(input: &'a str) -> Result<Vec<Token<'a>>, TokenizeError> {
             }
 
             if !indents.contains(&column) {
-                return Err(TokenizeError {
-                    msg: "unindent does not match any outer indentation level",
-                    offset: rest_off,
-                });
+                return (
+                    result,
+                    Some(TokenizeError {
+                        msg: "unindent does not match any outer indentation level",
+                        offset: rest_off,
+                    }),
+                );
             }
 
             while column < *indents.last().unwrap() {
@@ -219,6 +262,8 @@ pub fn tokenize<'a>(input: &'a str) -> Result<Vec<Token<'a>>, TokenizeError> {
                     line,
                 });
             }
+
+            result.append(&mut pending_docs);
         } else {
             continued = false;
         }
@@ -312,10 +357,13 @@ pub fn tokenize<'a>(input: &'a str) -> Result<Vec<Token<'a>>, TokenizeError> {
                         parenlev += 1;
                     } else if initial == ')' || initial == ']' || initial == '}' {
                         if parenlev == 0 {
-                            return Err(TokenizeError {
-                                msg: "Unbalanced brackets",
-                                offset: line_pos,
-                            });
+                            return (
+                                result,
+                                Some(TokenizeError {
+                                    msg: "Unbalanced brackets",
+                                    offset: line_pos,
+                                }),
+                            );
                         }
                         parenlev -= 1;
                     }
@@ -344,10 +392,13 @@ pub fn tokenize<'a>(input: &'a str) -> Result<Vec<Token<'a>>, TokenizeError> {
 
     // Ensure brackets are balanced
     if parenlev != 0 {
-        return Err(TokenizeError {
-            msg: "Unbalanced brackets",
-            offset: input.len(),
-        });
+        return (
+            result,
+            Some(TokenizeError {
+                msg: "Unbalanced brackets",
+                offset: input.len(),
+            }),
+        );
     }
 
     // We use this zero-length slice as the ending content for remaining tokens.
@@ -357,17 +408,23 @@ pub fn tokenize<'a>(input: &'a str) -> Result<Vec<Token<'a>>, TokenizeError> {
     let empty_end_slice = &input[input_len..];
 
     if contstr_start.is_some() {
-        return Err(TokenizeError {
-            msg: "EOF in multi-line string",
-            offset: input_len,
-        });
+        return (
+            result,
+            Some(TokenizeError {
+                msg: "EOF in multi-line string",
+                offset: input_len,
+            }),
+        );
     }
 
     if continued {
-        return Err(TokenizeError {
-            msg: "EOF in multi-line statement",
-            offset: input_len,
-        });
+        return (
+            result,
+            Some(TokenizeError {
+                msg: "EOF in multi-line statement",
+                offset: input_len,
+            }),
+        );
     }
 
     // Ensure content tokens end with newline (this allows parsers to be defined
@@ -397,7 +454,7 @@ pub fn tokenize<'a>(input: &'a str) -> Result<Vec<Token<'a>>, TokenizeError> {
         line: empty_end_slice,
     });
 
-    Ok(result)
+    (result, None)
 }
 
 #[cfg(test)]
@@ -435,4 +492,22 @@ mod tests {
         assert_eq!(token.typ, ERRORTOKEN);
         assert_eq!(token.string, uni);
     }
+
+    #[test]
+    fn test_doc_comment_follows_indent() {
+        let input = "contract Foo:\n    /// Does bar.\n    def bar():\n        x // y\n";
+        let tokens = tokenize(input).unwrap();
+        let types: Vec<_> = tokens
+            .iter()
+            .filter(|token| token.typ != NL)
+            .map(|token| token.typ)
+            .collect();
+
+        assert_eq!(&types[..6], &[NAME, NAME, OP, NEWLINE, INDENT, DOCCOMMENT]);
+        let doc = tokens.iter().find(|token| token.typ == DOCCOMMENT);
+        assert_eq!(doc.unwrap().string, "/// Does bar.");
+        assert!(tokens
+            .iter()
+            .any(|token| token.typ == OP && token.string == "//"));
+    }
 }
@@ -16,6 +16,8 @@ pub enum TokenType {
     STRING,
     OP,
     COMMENT,
+    /// A `///` comment on its own line, which documents the following item.
+    DOCCOMMENT,
 
     INDENT,
     DEDENT,
@@ -0,0 +1,478 @@
+//! Traversal of the AST.
+//!
+//! A `Visitor` has a method for each kind of node, and the default
+//! implementation of each method visits the children of the node by calling
+//! the matching `walk_*` function. Implementors override the methods for the
+//! nodes they're interested in, and call the `walk_*` function from the
+//! override to keep visiting the children.
+//!
+//! Some expressions, e.g. expression statements and positional call arguments,
+//! aren't spanned in the AST. These are visited with the span of the node that
+//! contains them, which covers the same code.
+
+use crate::ast::*;
+use crate::span::{
+    Span,
+    Spanned,
+};
+
+pub trait Visitor<'ast>: Sized {
+    fn visit_module(&mut self, module: &'ast Module<'ast>) {
+        walk_module(self, module)
+    }
+
+    fn visit_module_stmt(&mut self, stmt: &'ast Spanned<ModuleStmt<'ast>>) {
+        walk_module_stmt(self, stmt)
+    }
+
+    fn visit_contract_stmt(&mut self, stmt: &'ast Spanned<ContractStmt<'ast>>) {
+        walk_contract_stmt(self, stmt)
+    }
+
+    fn visit_struct_stmt(&mut self, stmt: &'ast Spanned<StructStmt<'ast>>) {
+        walk_struct_stmt(self, stmt)
+    }
+
+    fn visit_event_field(&mut self, field: &'ast Spanned<EventField<'ast>>) {
+        walk_event_field(self, field)
+    }
+
+    fn visit_func_sig(&mut self, sig: &'ast Spanned<FuncSig<'ast>>) {
+        walk_func_sig(self, sig)
+    }
+
+    fn visit_func_def_arg(&mut self, arg: &'ast Spanned<FuncDefArg<'ast>>) {
+        walk_func_def_arg(self, arg)
+    }
+
+    fn visit_func_stmt(&mut self, stmt: &'ast Spanned<FuncStmt<'ast>>) {
+        walk_func_stmt(self, stmt)
+    }
+
+    fn visit_match_arm(&mut self, arm: &'ast Spanned<MatchArm<'ast>>) {
+        walk_match_arm(self, arm)
+    }
+
+    fn visit_expr(&mut self, expr: &'ast Expr<'ast>, span: Span) {
+        walk_expr(self, expr, span)
+    }
+
+    fn visit_slice(&mut self, slice: &'ast Spanned<Slice<'ast>>) {
+        walk_slice(self, slice)
+    }
+
+    fn visit_call_arg(&mut self, arg: &'ast Spanned<CallArg<'ast>>) {
+        walk_call_arg(self, arg)
+    }
+
+    fn visit_comprehension(&mut self, comp: &'ast Spanned<Comprehension<'ast>>) {
+        walk_comprehension(self, comp)
+    }
+
+    fn visit_type_desc(&mut self, typ: &'ast Spanned<TypeDesc<'ast>>) {
+        walk_type_desc(self, typ)
+    }
+
+    /// Visits a node for code that couldn't be parsed. Error nodes have no
+    /// children.
+    fn visit_syntax_error(&mut self, _error: &'ast SyntaxError) {}
+}
+
+fn visit_spanned_expr<'ast, V: Visitor<'ast>>(visitor: &mut V, expr: &'ast Spanned<Expr<'ast>>) {
+    visitor.visit_expr(&expr.node, expr.span)
+}
+
+fn visit_body<'ast, V: Visitor<'ast>>(visitor: &mut V, body: &'ast [Spanned<FuncStmt<'ast>>]) {
+    for stmt in body.iter() {
+        visitor.visit_func_stmt(stmt)
+    }
+}
+
+pub fn walk_module<'ast, V: Visitor<'ast>>(visitor: &mut V, module: &'ast Module<'ast>) {
+    for stmt in module.body.iter() {
+        visitor.visit_module_stmt(stmt)
+    }
+}
+
+pub fn walk_module_stmt<'ast, V: Visitor<'ast>>(
+    visitor: &mut V,
+    stmt: &'ast Spanned<ModuleStmt<'ast>>,
+) {
+    match &stmt.node {
+        ModuleStmt::TypeDef { typ, .. } => visitor.visit_type_desc(typ),
+        ModuleStmt::SimpleImport { .. } | ModuleStmt::FromImport { .. } => {}
+        ModuleStmt::ConstDef {
+            decorators,
+            typ,
+            value,
+            ..
+        } => {
+            for decorator in decorators.iter() {
+                visit_spanned_expr(visitor, decorator)
+            }
+            visitor.visit_type_desc(typ);
+            visit_spanned_expr(visitor, value);
+        }
+        ModuleStmt::ContractDef {
+            decorators, body, ..
+        } => {
+            for decorator in decorators.iter() {
+                visit_spanned_expr(visitor, decorator)
+            }
+            for stmt in body.iter() {
+                visitor.visit_contract_stmt(stmt)
+            }
+        }
+        ModuleStmt::MixinDef { body, .. } | ModuleStmt::LibraryDef { body, .. } => {
+            for stmt in body.iter() {
+                visitor.visit_contract_stmt(stmt)
+            }
+        }
+        ModuleStmt::TraitDef { body, .. } | ModuleStmt::InterfaceDef { body, .. } => {
+            for sig in body.iter() {
+                visitor.visit_func_sig(sig)
+            }
+        }
+        ModuleStmt::StructDef {
+            decorators, body, ..
+        } => {
+            for decorator in decorators.iter() {
+                visit_spanned_expr(visitor, decorator)
+            }
+            for stmt in body.iter() {
+                visitor.visit_struct_stmt(stmt)
+            }
+        }
+        ModuleStmt::EnumDef { .. } => {}
+        ModuleStmt::FuncDef { def } => visitor.visit_contract_stmt(def),
+        ModuleStmt::Error(error) => visitor.visit_syntax_error(error),
+    }
+}
+
+pub fn walk_contract_stmt<'ast, V: Visitor<'ast>>(
+    visitor: &mut V,
+    stmt: &'ast Spanned<ContractStmt<'ast>>,
+) {
+    match &stmt.node {
+        ContractStmt::ContractField { typ, .. } => visitor.visit_type_desc(typ),
+        ContractStmt::ConstDef {
+            decorators,
+            typ,
+            value,
+            ..
+        } => {
+            for decorator in decorators.iter() {
+                visit_spanned_expr(visitor, decorator)
+            }
+            visitor.visit_type_desc(typ);
+            visit_spanned_expr(visitor, value);
+        }
+        ContractStmt::EventDef { fields, .. } => {
+            for field in fields.iter() {
+                visitor.visit_event_field(field)
+            }
+        }
+        ContractStmt::ErrorDef { fields, .. } => {
+            for field in fields.iter() {
+                visitor.visit_func_def_arg(field)
+            }
+        }
+        ContractStmt::FuncDef {
+            decorators,
+            args,
+            return_type,
+            body,
+            ..
+        } => {
+            for decorator in decorators.iter() {
+                visit_spanned_expr(visitor, decorator)
+            }
+            for arg in args.iter() {
+                visitor.visit_func_def_arg(arg)
+            }
+            if let Some(return_type) = return_type {
+                visitor.visit_type_desc(return_type)
+            }
+            visit_body(visitor, body);
+        }
+        ContractStmt::Error(error) => visitor.visit_syntax_error(error),
+    }
+}
+
+pub fn walk_struct_stmt<'ast, V: Visitor<'ast>>(
+    visitor: &mut V,
+    stmt: &'ast Spanned<StructStmt<'ast>>,
+) {
+    match &stmt.node {
+        StructStmt::StructField { typ, .. } => visitor.visit_type_desc(typ),
+    }
+}
+
+pub fn walk_event_field<'ast, V: Visitor<'ast>>(
+    visitor: &mut V,
+    field: &'ast Spanned<EventField<'ast>>,
+) {
+    visitor.visit_type_desc(&field.node.typ)
+}
+
+pub fn walk_func_sig<'ast, V: Visitor<'ast>>(visitor: &mut V, sig: &'ast Spanned<FuncSig<'ast>>) {
+    for arg in sig.node.args.iter() {
+        visitor.visit_func_def_arg(arg)
+    }
+    if let Some(return_type) = &sig.node.return_type {
+        visitor.visit_type_desc(return_type)
+    }
+}
+
+pub fn walk_func_def_arg<'ast, V: Visitor<'ast>>(
+    visitor: &mut V,
+    arg: &'ast Spanned<FuncDefArg<'ast>>,
+) {
+    visitor.visit_type_desc(&arg.node.typ)
+}
+
+pub fn walk_func_stmt<'ast, V: Visitor<'ast>>(
+    visitor: &mut V,
+    stmt: &'ast Spanned<FuncStmt<'ast>>,
+) {
+    match &stmt.node {
+        FuncStmt::Return { value } => {
+            if let Some(value) = value {
+                visit_spanned_expr(visitor, value)
+            }
+        }
+        FuncStmt::VarDecl { target, typ, value } => {
+            visit_spanned_expr(visitor, target);
+            visitor.visit_type_desc(typ);
+            if let Some(value) = value {
+                visit_spanned_expr(visitor, value)
+            }
+        }
+        FuncStmt::Assign { targets, value } => {
+            for target in targets.iter() {
+                visit_spanned_expr(visitor, target)
+            }
+            visit_spanned_expr(visitor, value);
+        }
+        FuncStmt::AugAssign { target, value, .. } => {
+            visit_spanned_expr(visitor, target);
+            visit_spanned_expr(visitor, value);
+        }
+        FuncStmt::For {
+            target,
+            iter,
+            body,
+            or_else,
+        } => {
+            visit_spanned_expr(visitor, target);
+            visit_spanned_expr(visitor, iter);
+            visit_body(visitor, body);
+            visit_body(visitor, or_else);
+        }
+        FuncStmt::While {
+            test,
+            body,
+            or_else,
+        }
+        | FuncStmt::If {
+            test,
+            body,
+            or_else,
+        } => {
+            visit_spanned_expr(visitor, test);
+            visit_body(visitor, body);
+            visit_body(visitor, or_else);
+        }
+        FuncStmt::Match { value, arms } => {
+            visit_spanned_expr(visitor, value);
+            for arm in arms.iter() {
+                visitor.visit_match_arm(arm)
+            }
+        }
+        FuncStmt::Assert { test, msg } => {
+            visit_spanned_expr(visitor, test);
+            if let Some(msg) = msg {
+                visit_spanned_expr(visitor, msg)
+            }
+        }
+        FuncStmt::Emit { value } => visit_spanned_expr(visitor, value),
+        FuncStmt::Expr { value } => visitor.visit_expr(value, stmt.span),
+        FuncStmt::Pass | FuncStmt::Break | FuncStmt::Continue => {}
+        FuncStmt::Revert { error } => {
+            if let Some(error) = error {
+                visit_spanned_expr(visitor, error)
+            }
+        }
+        FuncStmt::Error(error) => visitor.visit_syntax_error(error),
+    }
+}
+
+pub fn walk_match_arm<'ast, V: Visitor<'ast>>(visitor: &mut V, arm: &'ast Spanned<MatchArm<'ast>>) {
+    visit_spanned_expr(visitor, &arm.node.pattern);
+    visit_body(visitor, &arm.node.body);
+}
+
+pub fn walk_expr<'ast, V: Visitor<'ast>>(visitor: &mut V, expr: &'ast Expr<'ast>, _span: Span) {
+    match expr {
+        Expr::Ternary {
+            if_expr,
+            test,
+            else_expr,
+        } => {
+            visit_spanned_expr(visitor, if_expr);
+            visit_spanned_expr(visitor, test);
+            visit_spanned_expr(visitor, else_expr);
+        }
+        Expr::BoolOperation { left, right, .. }
+        | Expr::BinOperation { left, right, .. }
+        | Expr::CompOperation { left, right, .. } => {
+            visit_spanned_expr(visitor, left);
+            visit_spanned_expr(visitor, right);
+        }
+        Expr::UnaryOperation { operand, .. } => visit_spanned_expr(visitor, operand),
+        Expr::Attribute { value, .. } => visit_spanned_expr(visitor, value),
+        Expr::Subscript { value, slices } => {
+            visit_spanned_expr(visitor, value);
+            for slice in slices.node.iter() {
+                visitor.visit_slice(slice)
+            }
+        }
+        Expr::Call { func, args } => {
+            visit_spanned_expr(visitor, func);
+            for arg in args.node.iter() {
+                visitor.visit_call_arg(arg)
+            }
+        }
+        Expr::List { elts } | Expr::Tuple { elts } => {
+            for elt in elts.iter() {
+                visit_spanned_expr(visitor, elt)
+            }
+        }
+        Expr::ListComp { elt, comps } => {
+            visit_spanned_expr(visitor, elt);
+            for comp in comps.iter() {
+                visitor.visit_comprehension(comp)
+            }
+        }
+        Expr::Bool(_) | Expr::Name(_) | Expr::Num(_) | Expr::Str(_) | Expr::Ellipsis => {}
+    }
+}
+
+pub fn walk_slice<'ast, V: Visitor<'ast>>(visitor: &mut V, slice: &'ast Spanned<Slice<'ast>>) {
+    match &slice.node {
+        Slice::Slice { lower, upper, step } => {
+            for bound in lower.iter().chain(upper).chain(step) {
+                visit_spanned_expr(visitor, bound)
+            }
+        }
+        Slice::Index(index) => visitor.visit_expr(index, slice.span),
+    }
+}
+
+pub fn walk_call_arg<'ast, V: Visitor<'ast>>(visitor: &mut V, arg: &'ast Spanned<CallArg<'ast>>) {
+    match &arg.node {
+        CallArg::Arg(value) => visitor.visit_expr(value, arg.span),
+        CallArg::Kwarg(kwarg) => visit_spanned_expr(visitor, &kwarg.value),
+    }
+}
+
+pub fn walk_comprehension<'ast, V: Visitor<'ast>>(
+    visitor: &mut V,
+    comp: &'ast Spanned<Comprehension<'ast>>,
+) {
+    visit_spanned_expr(visitor, &comp.node.target);
+    visit_spanned_expr(visitor, &comp.node.iter);
+    for test in comp.node.ifs.iter() {
+        visit_spanned_expr(visitor, test)
+    }
+}
+
+pub fn walk_type_desc<'ast, V: Visitor<'ast>>(visitor: &mut V, typ: &'ast Spanned<TypeDesc<'ast>>) {
+    match &typ.node {
+        TypeDesc::Base { .. } => {}
+        TypeDesc::Array { typ, .. } => visitor.visit_type_desc(typ),
+        TypeDesc::Map { from, to } => {
+            visitor.visit_type_desc(from);
+            visitor.visit_type_desc(to);
+        }
+        TypeDesc::Tuple { items: args } | TypeDesc::Generic { args, .. } => {
+            for arg in args.iter() {
+                visitor.visit_type_desc(arg)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{
+        Expr,
+        Module,
+        TypeDesc,
+    };
+    use crate::get_parse_tokens;
+    use crate::parsers::file_input;
+    use crate::span::{
+        Span,
+        Spanned,
+    };
+    use crate::visit::{
+        walk_expr,
+        walk_type_desc,
+        Visitor,
+    };
+
+    #[derive(Default)]
+    struct Collector<'a> {
+        names: Vec<&'a str>,
+        types: Vec<&'a str>,
+    }
+
+    impl<'a> Visitor<'a> for Collector<'a> {
+        fn visit_expr(&mut self, expr: &'a Expr<'a>, span: Span) {
+            if let Expr::Name(name) = expr {
+                self.names.push(name)
+            }
+            walk_expr(self, expr, span)
+        }
+
+        fn visit_type_desc(&mut self, typ: &'a Spanned<TypeDesc<'a>>) {
+            if let TypeDesc::Base { base } = &typ.node {
+                self.types.push(base)
+            }
+            walk_type_desc(self, typ)
+        }
+    }
+
+    fn collect<'a>(module: &'a Module<'a>) -> Collector<'a> {
+        let mut collector = Collector::default();
+        collector.visit_module(module);
+        collector
+    }
+
+    #[test]
+    fn collect_names() {
+        let src = "\
+contract Foo:
+    bar: map<address, u256>
+
+    pub def baz(x: u256, y: bool) -> u256:
+        if y:
+            emit Baz(x)
+        return self.qux(a[x], b=min(x, 1))
+";
+        let tokens = get_parse_tokens(src).expect("unable to tokenize");
+        let (_, module) = file_input(&tokens[..]).expect("unable to parse");
+
+        let collector = collect(&module.node);
+
+        assert_eq!(
+            collector.names,
+            vec!["y", "Baz", "x", "self", "a", "x", "min", "x"]
+        );
+        assert_eq!(
+            collector.types,
+            vec!["address", "u256", "u256", "bool", "u256"]
+        );
+    }
+}
@@ -5,7 +5,13 @@ mod utils;
 
 use wasm_bindgen_test::wasm_bindgen_test;
 
-use fe_parser::ast::Module;
+use fe_parser::ast::{
+    doc_string,
+    ContractStmt,
+    FuncStmt,
+    Module,
+    ModuleStmt,
+};
 use fe_parser::builders::{
     many0,
     many1,
@@ -275,6 +281,114 @@ fn test_arr_dim_err() {
     );
 }
 
+#[test]
+#[wasm_bindgen_test]
+fn test_file_input_with_errors() {
+    let src = "contract Foo:
+    pub def bar() -> :
+        pass
+
+contract Bar:
+    x: u256
+
+contract Baz
+";
+    let toks = get_parse_tokens(src).unwrap();
+    let (module, errors) = file_input_with_errors(&toks);
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(module.node.body.len(), 3);
+    assert!(matches!(
+        &module.node.body[0].node,
+        ModuleStmt::ContractDef { name, body, .. }
+            if name.node == "Foo" && matches!(body[0].node, ContractStmt::Error(_))
+    ));
+    assert!(matches!(
+        &module.node.body[1].node,
+        ModuleStmt::ContractDef { name, .. } if name.node == "Bar"
+    ));
+    assert!(matches!(&module.node.body[2].node, ModuleStmt::Error(_)));
+    assert_eq!(
+        &src[module.node.body[2].span.start..module.node.body[2].span.end],
+        "contract Baz"
+    );
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn test_recovering_function_body() {
+    let src = "contract Foo:
+    pub def bar() -> u256:
+        x: u256 =
+        return 1
+
+    pub def baz():
+        pass
+";
+    let toks = get_parse_tokens(src).unwrap();
+    let (module, errors) = file_input_with_errors(&toks);
+
+    assert_eq!(errors.len(), 1);
+
+    let body = match &module.node.body[0].node {
+        ModuleStmt::ContractDef { body, .. } => body,
+        _ => panic!("expected a contract"),
+    };
+    assert_eq!(body.len(), 2);
+    match &body[0].node {
+        ContractStmt::FuncDef { body, .. } => {
+            assert!(matches!(body[0].node, FuncStmt::Error(_)));
+            assert_eq!(&src[body[0].span.start..body[0].span.end], "x: u256 =");
+            assert!(matches!(body[1].node, FuncStmt::Return { .. }));
+        }
+        _ => panic!("expected a function"),
+    }
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn test_doc_comments() {
+    let src = "/// A counter.
+///
+/// Counts things.
+contract Foo:
+    /// The current count.
+    count: u256
+
+    /// Adds one.
+    @only_owner
+    pub def inc():
+        /// Doc comments on statements are ignored.
+        self.count += 1
+";
+    let toks = get_parse_tokens(src).unwrap();
+    let (_, module) = file_input(&toks).unwrap();
+
+    let body = match &module.node.body[0].node {
+        ModuleStmt::ContractDef { docs, body, .. } => {
+            assert_eq!(doc_string(docs), "A counter.\n\nCounts things.");
+            assert_eq!(docs[0].span, Span::new(0, 14));
+            body
+        }
+        _ => panic!("expected a contract definition"),
+    };
+    match &body[0].node {
+        ContractStmt::ContractField { docs, .. } => {
+            assert_eq!(doc_string(docs), "The current count.")
+        }
+        _ => panic!("expected a contract field"),
+    }
+    match &body[1].node {
+        ContractStmt::FuncDef {
+            docs, decorators, ..
+        } => {
+            assert_eq!(doc_string(docs), "Adds one.");
+            assert_eq!(decorators.len(), 1);
+        }
+        _ => panic!("expected a function definition"),
+    }
+}
+
 // Uncomment this to update all fixtures.
 //#[test]
 //fn write_fixtures() {
@@ -372,6 +486,36 @@ parser_fixture_tests! {
         write_struct_def,
         "fixtures/parsers/struct_def.ron",
     ),
+    (
+        repeat(enum_def),
+        test_enum_def,
+        write_enum_def,
+        "fixtures/parsers/enum_def.ron",
+    ),
+    (
+        repeat(trait_def),
+        test_trait_def,
+        write_trait_def,
+        "fixtures/parsers/trait_def.ron",
+    ),
+    (
+        repeat(interface_def),
+        test_interface_def,
+        write_interface_def,
+        "fixtures/parsers/interface_def.ron",
+    ),
+    (
+        repeat(mixin_def),
+        test_mixin_def,
+        write_mixin_def,
+        "fixtures/parsers/mixin_def.ron",
+    ),
+    (
+        repeat(library_def),
+        test_library_def,
+        write_library_def,
+        "fixtures/parsers/library_def.ron",
+    ),
     (
         repeat(contract_stmt),
         test_contract_stmt,
@@ -396,6 +540,12 @@ parser_fixture_tests! {
         write_event_field,
         "fixtures/parsers/event_field.ron",
     ),
+    (
+        repeat(error_def),
+        test_error_def,
+        write_error_def,
+        "fixtures/parsers/error_def.ron",
+    ),
     (
         repeat(func_def),
         test_func_def,
@@ -420,6 +570,12 @@ parser_fixture_tests! {
         write_type_def,
         "fixtures/parsers/type_def.ron",
     ),
+    (
+        repeat(const_def),
+        test_const_def,
+        write_const_def,
+        "fixtures/parsers/const_def.ron",
+    ),
     (
         repeat_newline(type_desc),
         test_type_desc,
@@ -570,6 +726,12 @@ parser_fixture_tests! {
         write_while_stmt,
         "fixtures/parsers/while_stmt.ron",
     ),
+    (
+        repeat(match_stmt),
+        test_match_stmt,
+        write_match_stmt,
+        "fixtures/parsers/match_stmt.ron",
+    ),
     (
         repeat_newline(exprs),
         test_exprs,
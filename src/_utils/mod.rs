@@ -1,39 +1,142 @@
-/// Formats any kind of structured text that uses curly braces blocks
-pub fn pretty_curly_print(text: &str, indent: usize) -> String {
-    let mut formatted = String::new();
-    let mut level = 0;
-    let mut previous_char: Option<char> = None;
-
-    const CURLY_OPEN: char = '{';
-    const CURLY_CLOSE: char = '}';
-    const WHITESPACE: char = ' ';
-    const NEWLINE: &str = "\n";
-
-    for character in text.chars() {
-        match character {
-            CURLY_OPEN => {
-                level += 1;
-                formatted.push(character);
-                formatted.push_str(NEWLINE);
-                formatted.push_str(&WHITESPACE.to_string().repeat(indent * level));
+use fe_compiler::files::{
+    read_source,
+    SourceTree,
+};
+use std::fs;
+use std::path::{
+    Path,
+    PathBuf,
+};
+use std::str::FromStr;
+
+/// Formats the lines that differ between two texts like a unified diff
+/// without context lines.
+pub fn line_diff(old: &str, new: &str) -> String {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+
+    // lengths of the longest common subsequences of the remaining lines
+    let mut common = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+            continue;
+        }
+
+        diff.push_str(&format!("@@ -{} +{} @@\n", i + 1, j + 1));
+        while (i < old.len() || j < new.len())
+            && !(i < old.len() && j < new.len() && old[i] == new[j])
+        {
+            if j == new.len() || (i < old.len() && common[i + 1][j] >= common[i][j + 1]) {
+                diff.push_str(&format!("-{}\n", old[i]));
+                i += 1;
+            } else {
+                diff.push_str(&format!("+{}\n", new[j]));
+                j += 1;
             }
-            CURLY_CLOSE => {
-                level -= 1;
-                formatted.push_str(NEWLINE);
-                formatted.push_str(&WHITESPACE.to_string().repeat(indent * level));
-                formatted.push(character);
-                formatted.push_str(NEWLINE);
-                formatted.push_str(&WHITESPACE.to_string().repeat(indent * level));
+        }
+    }
+
+    diff
+}
+
+/// Replaces a prefix of paths with another one, given as `FROM=TO`.
+#[derive(Debug, PartialEq)]
+pub struct PathPrefixMap {
+    from: PathBuf,
+    to: PathBuf,
+}
+
+impl FromStr for PathPrefixMap {
+    type Err = String;
+
+    /// Splits at the last `=`, so only the original prefix may contain one.
+    fn from_str(map: &str) -> Result<Self, Self::Err> {
+        match map.rfind('=') {
+            Some(index) => Ok(PathPrefixMap {
+                from: PathBuf::from(&map[..index]),
+                to: PathBuf::from(&map[index + 1..]),
+            }),
+            None => Err(format!(
+                "invalid path prefix map `{}`, expected `FROM=TO`",
+                map
+            )),
+        }
+    }
+}
+
+/// Replaces the prefix of the path using the last map whose prefix matches
+/// and returns it with `/` as the separator on all platforms.
+pub fn remap_path(path: &str, maps: &[PathPrefixMap]) -> String {
+    let path = Path::new(path);
+    let remapped = maps
+        .iter()
+        .rev()
+        .find_map(|map| {
+            path.strip_prefix(&map.from)
+                .ok()
+                .map(|rest| map.to.join(rest))
+        })
+        .unwrap_or_else(|| path.to_path_buf());
+
+    let remapped = remapped.to_string_lossy();
+    if cfg!(windows) {
+        remapped.replace('\\', "/")
+    } else {
+        remapped.into_owned()
+    }
+}
+
+/// Loads a source file, or all `.fe` files under a directory with their paths
+/// relative to it. Hidden files and directories are skipped.
+pub fn load_sources(path: &str) -> Result<SourceTree, String> {
+    let path = Path::new(path);
+    if !path.is_dir() {
+        let content = read_source(path).map_err(|error| error.to_string())?;
+        return Ok(SourceTree::new(vec![(path.display().to_string(), content)]));
+    }
+
+    let mut files = vec![];
+    let mut dirs = vec![path.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let entries = fs::read_dir(&dir)
+            .map_err(|error| format!("unable to read {}: {}", dir.display(), error))?;
+        for entry in entries {
+            let entry_path = entry.map_err(|error| error.to_string())?.path();
+            let hidden = entry_path
+                .file_name()
+                .map_or(false, |name| name.to_string_lossy().starts_with('.'));
+            if hidden {
+                continue;
             }
-            WHITESPACE => {
-                if !matches!(previous_char, Some(CURLY_CLOSE)) {
-                    formatted.push(character)
-                }
+
+            if entry_path.is_dir() {
+                dirs.push(entry_path);
+            } else if entry_path.extension().map_or(false, |ext| ext == "fe") {
+                let content = read_source(&entry_path).map_err(|error| {
+                    format!("unable to read {}: {}", entry_path.display(), error)
+                })?;
+                let relative = entry_path.strip_prefix(path).unwrap_or(&entry_path);
+                files.push((remap_path(&relative.to_string_lossy(), &[]), content));
             }
-            _ => formatted.push(character),
         }
-        previous_char = Some(character);
     }
 
-    formatted
+    if files.is_empty() {
+        return Err(format!("no `.fe` files found in {}", path.display()));
+    }
+    Ok(SourceTree::new(files))
 }
@@ -0,0 +1,290 @@
+//! Differential testing against Solidity (`fe difftest`).
+//!
+//! A case is a Fe contract and a Solidity contract of the same name that are
+//! meant to behave the same, along with a `fe simulate` script of the calls
+//! to make to them. Both contracts are deployed to an in-memory EVM and given
+//! the same calls. A call diverges if only one of the contracts reverts, or if
+//! they return different data or emit different logs. The gas they use and
+//! the layout of their storage are expected to differ and aren't compared.
+
+use crate::simulate::{
+    Call,
+    Script,
+    Transaction,
+};
+use fe_compiler::files::read_source;
+use primitive_types::U256;
+use std::fs;
+use std::path::Path;
+
+/// A Fe source file `<name>.fe` with the Solidity source file `<name>.sol`
+/// and the script `<name>.json` next to it.
+pub struct Case {
+    pub name: String,
+    pub fe_src: String,
+    pub solidity_src: String,
+    pub script: Script,
+}
+
+/// A deployment or call whose outcome differs between the contracts.
+#[derive(Debug, PartialEq)]
+pub struct Divergence {
+    /// The deployment, or the call with its arguments.
+    pub transaction: String,
+    pub fe: String,
+    pub solidity: String,
+}
+
+/// Loads the case of a Fe source file, or the cases of the Fe source files in
+/// a directory in the order of their names.
+pub fn load_cases(path: &Path) -> Result<Vec<Case>, String> {
+    if !path.is_dir() {
+        return Ok(vec![load_case(path)?]);
+    }
+
+    let mut paths = fs::read_dir(path)
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|error| format!("{}: {}", path.display(), error))?;
+    paths.sort();
+
+    paths
+        .iter()
+        .filter(|path| {
+            path.extension()
+                .map_or(false, |extension| extension == "fe")
+        })
+        .map(|path| load_case(path))
+        .collect()
+}
+
+fn load_case(path: &Path) -> Result<Case, String> {
+    let read = |extension: &str| {
+        let path = path.with_extension(extension);
+        read_source(&path).map_err(|error| format!("{}: {}", path.display(), error))
+    };
+
+    Ok(Case {
+        name: path
+            .file_stem()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        fe_src: read("fe")?,
+        solidity_src: read("sol")?,
+        script: Script::parse(&read("json")?)?,
+    })
+}
+
+/// Compares the transactions of a script that was run against the Fe contract
+/// with those of the run against the Solidity contract.
+pub fn compare(script: &Script, fe: &[Transaction], solidity: &[Transaction]) -> Vec<Divergence> {
+    let names = std::iter::once("deployment".to_string()).chain(
+        script
+            .calls
+            .iter()
+            .map(|call| format!("{}({})", call.function, call.args.join(", "))),
+    );
+
+    // no calls are made if a deployment fails, so the runs stop together
+    names
+        .zip(fe.iter().zip(solidity.iter()))
+        .filter_map(|(transaction, (fe, solidity))| {
+            let (fe, solidity) = (behavior(fe), behavior(solidity));
+            if fe == solidity {
+                None
+            } else {
+                Some(Divergence {
+                    transaction,
+                    fe,
+                    solidity,
+                })
+            }
+        })
+        .collect()
+}
+
+/// The observable behavior of a transaction.
+fn behavior(transaction: &Transaction) -> String {
+    if !transaction.succeeded {
+        return "reverted".to_string();
+    }
+
+    let mut behavior = if transaction.output.is_empty() {
+        "succeeded".to_string()
+    } else {
+        format!("returned 0x{}", hex::encode(&transaction.output))
+    };
+    for log in transaction.logs.iter() {
+        behavior.push_str(&format!(", {}", log));
+    }
+
+    behavior
+}
+
+/// Generates `count` calls of each public function of the contract with the
+/// given JSON ABI. The arguments are the bounds of the ranges of their types
+/// or pseudorandom values from the seed. Functions with parameters of types
+/// other than integers, booleans and addresses are left out.
+pub fn generate_calls(json_abi: &str, count: usize, seed: u64) -> Result<Vec<Call>, String> {
+    let entries: Vec<serde_json::Value> =
+        serde_json::from_str(json_abi).map_err(|error| format!("invalid ABI: {}", error))?;
+    // xorshift never leaves a state of zero
+    let mut rng = Rng(seed.max(1));
+
+    let mut calls = vec![];
+    for entry in entries.iter().filter(|entry| entry["type"] == "function") {
+        let types = entry["inputs"]
+            .as_array()
+            .map(|inputs| {
+                inputs
+                    .iter()
+                    .map(|input| input["type"].as_str().unwrap_or_default())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        for _ in 0..count {
+            match types.iter().map(|typ| rng.value(typ)).collect() {
+                Some(args) => calls.push(Call {
+                    function: entry["name"].as_str().unwrap_or_default().to_string(),
+                    args,
+                    caller: None,
+                    value: None,
+                }),
+                None => break,
+            }
+        }
+    }
+
+    Ok(calls)
+}
+
+/// A xorshift generator, which is enough to vary the arguments of calls.
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A value of the ABI type as an argument of a script, or `None` for
+    /// types that aren't generated.
+    fn value(&mut self, typ: &str) -> Option<String> {
+        match typ {
+            "bool" => return Some((self.next_u64() % 2 == 0).to_string()),
+            // a few addresses, one of which is the caller, so that calls of a
+            // contract that keeps balances affect each other
+            "address" => return Some(format!("0x{:040x}", self.next_u64() % 4 + 1)),
+            _ => {}
+        }
+
+        let (signed, bits) = match typ.strip_prefix("uint") {
+            Some(bits) => (false, bits),
+            None => (true, typ.strip_prefix("int")?),
+        };
+        let bits = if bits.is_empty() {
+            256
+        } else {
+            bits.parse::<usize>()
+                .ok()
+                .filter(|bits| (1..=256).contains(bits))?
+        };
+        // signed values are kept positive
+        let bits = if signed { bits - 1 } else { bits };
+        let max = if bits == 256 {
+            U256::MAX
+        } else {
+            (U256::one() << bits) - 1
+        };
+
+        let value = match self.next_u64() % 4 {
+            0 => U256::zero(),
+            1 => U256::one(),
+            2 => max,
+            _ => {
+                U256([
+                    self.next_u64(),
+                    self.next_u64(),
+                    self.next_u64(),
+                    self.next_u64(),
+                ]) & max
+            }
+        };
+        Some(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::difftest::{
+        compare,
+        generate_calls,
+        Divergence,
+    };
+    use crate::simulate::{
+        Script,
+        Transaction,
+    };
+
+    const ABI: &str = r#"[
+        {"type": "function", "name": "transfer", "inputs": [{"name": "to", "type": "address"}, {"name": "value", "type": "uint256"}], "outputs": []},
+        {"type": "function", "name": "set_name", "inputs": [{"name": "name", "type": "string"}], "outputs": []},
+        {"type": "event", "name": "Transfer", "inputs": [], "anonymous": false}
+    ]"#;
+
+    fn transaction(succeeded: bool, output: Vec<u8>) -> Transaction {
+        Transaction {
+            function: None,
+            summary: String::new(),
+            succeeded,
+            gas: 0,
+            output,
+            logs: vec![],
+            details: vec![],
+        }
+    }
+
+    #[test]
+    fn generated_calls() {
+        let calls = generate_calls(ABI, 5, 7).unwrap();
+        assert_eq!(calls.len(), 5);
+        assert!(calls.iter().all(|call| call.function == "transfer"));
+        assert!(calls.iter().all(|call| call.args[0].len() == 42));
+        assert_eq!(calls, generate_calls(ABI, 5, 7).unwrap());
+        assert_ne!(calls, generate_calls(ABI, 5, 8).unwrap());
+    }
+
+    #[test]
+    fn divergences() {
+        let script = Script::parse(
+            r#"{"contract": "Foo", "calls": [{"function": "a"}, {"function": "b", "args": ["1"]}]}"#,
+        )
+        .unwrap();
+        let fe = vec![
+            transaction(true, vec![]),
+            transaction(true, vec![1]),
+            transaction(false, vec![]),
+        ];
+        let solidity = vec![
+            transaction(true, vec![]),
+            transaction(true, vec![1]),
+            transaction(true, vec![]),
+        ];
+
+        assert_eq!(compare(&script, &fe, &fe), vec![]);
+        assert_eq!(
+            compare(&script, &fe, &solidity),
+            vec![Divergence {
+                transaction: "b(1)".to_string(),
+                fe: "reverted".to_string(),
+                solidity: "succeeded".to_string(),
+            }]
+        );
+    }
+}
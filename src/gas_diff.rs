@@ -0,0 +1,193 @@
+//! Compares the gas used by each function with a baseline from an earlier
+//! build (`fe gas-diff`), e.g. to comment the changes on a pull request.
+//!
+//! A baseline is a JSON object that maps functions like `Token.transfer` to
+//! the average gas of their calls, as measured by the same samples that
+//! `fe profile` reports.
+
+use crate::profile::Sample;
+use std::collections::BTreeMap;
+
+/// The average gas used by each function.
+pub type Snapshot = BTreeMap<String, u64>;
+
+/// A function whose gas differs from the baseline, or that was added or
+/// removed since.
+#[derive(Debug, PartialEq)]
+pub struct Change {
+    pub function: String,
+    pub baseline: Option<u64>,
+    pub current: Option<u64>,
+}
+
+impl Change {
+    /// The change of the gas as a percentage of the baseline, if the function
+    /// is in both.
+    pub fn percent(&self) -> Option<f64> {
+        match (self.baseline, self.current) {
+            (Some(baseline), Some(current)) => {
+                Some((current as f64 - baseline as f64) * 100.0 / baseline.max(1) as f64)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the function uses more gas than the tolerance allows.
+    pub fn is_regression(&self, tolerance: f64) -> bool {
+        self.percent().map_or(false, |percent| percent > tolerance)
+    }
+}
+
+/// The average gas of the samples of each function.
+pub fn snapshot(samples: &[Sample]) -> Snapshot {
+    let mut totals = BTreeMap::<String, (u64, u64)>::new();
+    for sample in samples {
+        let total = totals.entry(sample.stack.join(".")).or_default();
+        total.0 += 1;
+        total.1 += sample.gas;
+    }
+
+    totals
+        .into_iter()
+        .map(|(function, (calls, gas))| (function, gas / calls))
+        .collect()
+}
+
+pub fn parse_snapshot(content: &str) -> Result<Snapshot, String> {
+    serde_json::from_str(content).map_err(|error| format!("invalid baseline: {}", error))
+}
+
+pub fn snapshot_to_json(snapshot: &Snapshot) -> String {
+    serde_json::to_string_pretty(snapshot).expect("unable to serialize the gas snapshot")
+}
+
+/// The functions whose gas changed, in the order of their names.
+pub fn diff(baseline: &Snapshot, current: &Snapshot) -> Vec<Change> {
+    let mut functions = baseline.keys().chain(current.keys()).collect::<Vec<_>>();
+    functions.sort();
+    functions.dedup();
+
+    functions
+        .into_iter()
+        .map(|function| Change {
+            function: function.clone(),
+            baseline: baseline.get(function).copied(),
+            current: current.get(function).copied(),
+        })
+        .filter(|change| change.baseline != change.current)
+        .collect()
+}
+
+/// Formats the changes as a Markdown table with a summary line, which reads
+/// well in a terminal and in a pull request comment.
+pub fn report(changes: &[Change], tolerance: f64) -> String {
+    let regressions = changes
+        .iter()
+        .filter(|change| change.is_regression(tolerance))
+        .count();
+    let improvements = changes
+        .iter()
+        .filter(|change| change.percent().map_or(false, |percent| percent < 0.0))
+        .count();
+    let summary = format!(
+        "{} regression(s), {} improvement(s), {} function(s) changed",
+        regressions,
+        improvements,
+        changes.len()
+    );
+    if changes.is_empty() {
+        return format!("No changes in gas usage.\n\n{}\n", summary);
+    }
+
+    let gas = |gas: Option<u64>| gas.map_or("-".to_string(), |gas| gas.to_string());
+    let mut report =
+        "| Function | Baseline | Current | Change |\n|---|---:|---:|---:|\n".to_string();
+    for change in changes {
+        let percent = match change.percent() {
+            Some(percent) => {
+                let marker = if change.is_regression(tolerance) {
+                    " :warning:"
+                } else {
+                    ""
+                };
+                format!("{:+.2}%{}", percent, marker)
+            }
+            None if change.current.is_some() => "added".to_string(),
+            None => "removed".to_string(),
+        };
+        report.push_str(&format!(
+            "| `{}` | {} | {} | {} |\n",
+            change.function,
+            gas(change.baseline),
+            gas(change.current),
+            percent
+        ));
+    }
+    report.push_str(&format!("\n{}\n", summary));
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gas_diff::{
+        diff,
+        report,
+        snapshot,
+        Snapshot,
+    };
+    use crate::profile::Sample;
+
+    fn snapshot_of(functions: &[(&str, u64)]) -> Snapshot {
+        functions
+            .iter()
+            .map(|(function, gas)| (function.to_string(), *gas))
+            .collect()
+    }
+
+    #[test]
+    fn averages() {
+        let samples = vec![
+            Sample {
+                stack: vec!["Token".to_string(), "transfer".to_string()],
+                gas: 100,
+            },
+            Sample {
+                stack: vec!["Token".to_string(), "transfer".to_string()],
+                gas: 300,
+            },
+        ];
+        assert_eq!(snapshot(&samples), snapshot_of(&[("Token.transfer", 200)]));
+    }
+
+    #[test]
+    fn changes() {
+        let baseline = snapshot_of(&[
+            ("Token.approve", 100),
+            ("Token.mint", 50),
+            ("Token.transfer", 200),
+        ]);
+        let current = snapshot_of(&[
+            ("Token.approve", 100),
+            ("Token.burn", 70),
+            ("Token.transfer", 210),
+        ]);
+
+        let changes = diff(&baseline, &current);
+        let functions = changes
+            .iter()
+            .map(|change| change.function.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            functions,
+            vec!["Token.burn", "Token.mint", "Token.transfer"]
+        );
+
+        let report = report(&changes, 0.0);
+        assert!(report.contains("| `Token.burn` | - | 70 | added |"));
+        assert!(report.contains("| `Token.mint` | 50 | - | removed |"));
+        assert!(report.contains("| `Token.transfer` | 200 | 210 | +5.00% :warning: |"));
+        assert!(report.ends_with("1 regression(s), 0 improvement(s), 3 function(s) changed\n"));
+        assert!(!changes[2].is_regression(10.0));
+    }
+}
@@ -0,0 +1,97 @@
+//! The commands of the `[hooks]` table of the manifest, which run before and
+//! after a build.
+
+use std::path::{
+    Path,
+    PathBuf,
+};
+use std::process::Command;
+
+/// Runs the command of a hook with the shell, in the directory of the
+/// manifest. The output of the command is forwarded to stderr with `json`, so
+/// that stdout only contains the summary of the build.
+pub fn run(
+    hook: &str,
+    command: &str,
+    dir: Option<&Path>,
+    env: &[(&str, String)],
+    json: bool,
+) -> Result<(), String> {
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    let mut process = Command::new(shell);
+    process.arg(flag).arg(command).envs(env.iter().cloned());
+    if let Some(dir) = dir {
+        process.current_dir(dir);
+    }
+
+    let output = process
+        .output()
+        .map_err(|error| format!("unable to run the {} hook: {}", hook, error))?;
+    if json {
+        eprint!("{}", String::from_utf8_lossy(&output.stdout));
+    } else {
+        print!("{}", String::from_utf8_lossy(&output.stdout));
+    }
+    eprint!("{}", String::from_utf8_lossy(&output.stderr));
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "the {} hook `{}` failed with {}",
+            hook, command, output.status
+        ))
+    }
+}
+
+/// The environment variables passed to hooks: the source and the output
+/// directory of the build and, once it has been written, the outputs, which
+/// are separated by line breaks. The paths are absolute, since hooks run in
+/// the directory of the manifest.
+pub fn env(source: &str, output_dir: &str, outputs: &[PathBuf]) -> Vec<(&'static str, String)> {
+    let outputs = outputs
+        .iter()
+        .map(|path| absolute(path).display().to_string())
+        .collect::<Vec<_>>();
+
+    vec![
+        (
+            "FE_BUILD_SOURCE",
+            absolute(Path::new(source)).display().to_string(),
+        ),
+        (
+            "FE_BUILD_OUTPUT_DIR",
+            absolute(Path::new(output_dir)).display().to_string(),
+        ),
+        ("FE_BUILD_ARTIFACTS", outputs.join("\n")),
+    ]
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    std::env::current_dir()
+        .map(|dir| dir.join(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use crate::hooks::run;
+
+    #[test]
+    fn hook_env_and_status() {
+        let env = [("FE_BUILD_SOURCE", "main.fe".to_string())];
+        assert!(run(
+            "prebuild",
+            "test \"$FE_BUILD_SOURCE\" = main.fe",
+            None,
+            &env,
+            true
+        )
+        .is_ok());
+        assert!(run("postbuild", "exit 3", None, &env, true).is_err());
+    }
+}
@@ -0,0 +1,110 @@
+//! Reporting of internal compiler errors, i.e. panics of the compiler, in a
+//! form that can be attached to a bug report.
+
+use std::cell::RefCell;
+use std::fs;
+use std::panic::PanicInfo;
+use std::path::PathBuf;
+use std::time::{
+    SystemTime,
+    UNIX_EPOCH,
+};
+
+const ISSUES_URL: &str = "https://github.com/ethereum/fe/issues/new";
+
+thread_local! {
+    /// The fingerprint of the source being compiled.
+    static SOURCE: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// Replaces the default panic message with a banner that asks for a bug
+/// report and points to a file with the details of the panic.
+pub fn install(version: &'static str) {
+    std::panic::set_hook(Box::new(move |info| report(version, info)));
+}
+
+/// Records the source being compiled, so that a report can say which source
+/// the compiler panicked on without including it.
+pub fn set_source(src: &str) {
+    SOURCE.with(|source| *source.borrow_mut() = Some(fingerprint(src)));
+}
+
+/// Identifies a source by its size and hash.
+fn fingerprint(src: &str) -> String {
+    format!(
+        "{} lines, {} bytes, keccak {}",
+        src.lines().count(),
+        src.len(),
+        fe_common::utils::keccak::partial(src.as_bytes(), 8)
+    )
+}
+
+fn report(version: &str, info: &PanicInfo) {
+    let message = match (
+        info.payload().downcast_ref::<&str>(),
+        info.payload().downcast_ref::<String>(),
+    ) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => "unknown panic".to_string(),
+    };
+    let location = info
+        .location()
+        .map_or("an unknown location".to_string(), |location| {
+            location.to_string()
+        });
+    let flags = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let source = SOURCE
+        .with(|source| source.borrow().clone())
+        .unwrap_or_else(|| "none".to_string());
+
+    let details = format!(
+        "version: {}\nflags: {}\nsource: {}\npanicked at {}: {}\n\n{:?}\n",
+        version,
+        flags,
+        source,
+        location,
+        message,
+        backtrace::Backtrace::new()
+    );
+    let file = write_report(&details);
+
+    eprintln!("error: internal compiler error: {}", message);
+    eprintln!("  --> {}", location);
+    eprintln!();
+    eprintln!(
+        "This is a bug in the Fe compiler. Please report it at {}",
+        ISSUES_URL
+    );
+    match file {
+        Some(file) => eprintln!("and attach {}", file.display()),
+        None => eprintln!("and include the following details:\n\n{}", details),
+    }
+    eprintln!();
+    eprintln!("version: {}", version);
+    eprintln!("flags: {}", flags);
+    eprintln!("source: {}", source);
+}
+
+/// Writes the details of the panic to a file in the temporary directory.
+fn write_report(details: &str) -> Option<PathBuf> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let path = std::env::temp_dir().join(format!("fe-ice-{}.txt", timestamp));
+
+    fs::write(&path, details).ok().map(|_| path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ice::fingerprint;
+
+    #[test]
+    fn source_fingerprint() {
+        let src = "contract Foo:\n    pub def bar():\n        pass\n";
+        let fingerprint = fingerprint(src);
+        assert!(fingerprint.starts_with("3 lines, 46 bytes, keccak 0x"));
+        assert_eq!(fingerprint.len(), "3 lines, 46 bytes, keccak 0x".len() + 16);
+    }
+}
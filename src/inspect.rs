@@ -0,0 +1,169 @@
+//! Printing of the summaries of contracts (`fe inspect`) as tables.
+
+use fe_compiler::files::read_source;
+use fe_compiler::inspect::{
+    self,
+    ContractSummary,
+    Entry,
+    Inspection,
+};
+use std::path::Path;
+
+/// Summarizes the contracts of a source file, a build info file or a JSON ABI
+/// file.
+pub fn load(path: &str) -> Result<Inspection, String> {
+    let content = read_source(path).map_err(|error| error.to_string())?;
+    let path = Path::new(path);
+
+    let inspection = if path
+        .extension()
+        .map_or(false, |extension| extension == "fe")
+    {
+        inspect::from_src(&content, cfg!(feature = "solc-backend"))
+            .map_err(|error| error.format_user(&content))?
+    } else {
+        // ABI files are named after their contract, e.g. `Foo_abi.json`
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        inspect::from_artifact(stem.trim_end_matches("_abi"), &content)
+            .map_err(|error| error.to_string())?
+    };
+
+    Ok(inspection)
+}
+
+/// Formats an inspection as one section of tables per contract.
+pub fn format(inspection: &Inspection) -> String {
+    let mut sections = vec![];
+
+    if !inspection.metadata.is_empty() {
+        let rows = inspection
+            .metadata
+            .iter()
+            .map(|(key, value)| vec![key.clone(), value.clone()])
+            .collect();
+        sections.push(format!("Metadata\n{}", table(&["Key", "Value"], rows)));
+    }
+
+    for (name, contract) in inspection.contracts.iter() {
+        sections.push(format!("Contract {}\n{}", name, format_contract(contract)));
+    }
+
+    sections.join("\n")
+}
+
+fn format_contract(contract: &ContractSummary) -> String {
+    let entries = |entries: &[Entry]| {
+        entries
+            .iter()
+            .map(|entry| {
+                vec![
+                    entry.hash.clone(),
+                    entry.signature.clone(),
+                    entry.details.clone(),
+                ]
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut tables = vec![];
+    if !contract.functions.is_empty() {
+        tables.push(format!(
+            "Functions\n{}",
+            table(
+                &["Selector", "Signature", "Mutability"],
+                entries(&contract.functions)
+            )
+        ));
+    }
+    if !contract.events.is_empty() {
+        tables.push(format!(
+            "Events\n{}",
+            table(
+                &["Topic", "Signature", "Indexed"],
+                entries(&contract.events)
+            )
+        ));
+    }
+    if !contract.errors.is_empty() {
+        tables.push(format!(
+            "Errors\n{}",
+            table(&["Selector", "Signature", ""], entries(&contract.errors))
+        ));
+    }
+    if !contract.storage_layout.is_empty() {
+        let rows = contract
+            .storage_layout
+            .iter()
+            .map(|slot| {
+                vec![
+                    slot.nonce.to_string(),
+                    slot.name.clone(),
+                    slot.typ.clone(),
+                    slot.slot.clone(),
+                ]
+            })
+            .collect();
+        tables.push(format!(
+            "Storage\n{}",
+            table(&["Nonce", "Name", "Type", "Slot"], rows)
+        ));
+    }
+    if let Some((creation, runtime)) = contract.bytecode_sizes {
+        tables.push(format!(
+            "Bytecode\n{}",
+            table(
+                &["Creation (B)", "Runtime (B)"],
+                vec![vec![creation.to_string(), runtime.to_string()]]
+            )
+        ));
+    }
+
+    tables.join("\n")
+}
+
+/// Formats rows as a table whose columns are as wide as their widest cell.
+fn table(headers: &[&str], rows: Vec<Vec<String>>) -> String {
+    let mut widths = headers
+        .iter()
+        .map(|header| header.len())
+        .collect::<Vec<_>>();
+    for row in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let header = headers.iter().map(|header| header.to_string()).collect();
+    let mut lines = vec![];
+    for row in std::iter::once(header).chain(rows) {
+        let cells = row
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>();
+        lines.push(format!("  {}", cells.join("  ").trim_end()));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::inspect::table;
+
+    #[test]
+    fn table_columns() {
+        let rows = vec![
+            vec!["0".to_string(), "owner".to_string()],
+            vec!["1".to_string(), "balances".to_string()],
+        ];
+
+        assert_eq!(
+            table(&["Nonce", "Name"], rows),
+            "  Nonce  Name\n  0      owner\n  1      balances\n"
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! Pinning of files on an IPFS node through its HTTP API (`--pin-metadata`),
+//! which makes the IPFS hashes in the metadata of contracts resolvable.
+
+use fe_compiler::metadata::ipfs_hash;
+use std::io::Write;
+use std::process::{
+    Command,
+    Stdio,
+};
+
+/// Adds a file to the IPFS node with the API at `api_url`, e.g.
+/// `http://127.0.0.1:5001`, and pins it. Returns the IPFS hash of the file,
+/// which has to be the hash that the compiler computed for it.
+pub fn pin(api_url: &str, name: &str, content: &[u8]) -> Result<String, String> {
+    let expected = ipfs_hash(content)
+        .ok_or_else(|| format!("`{}` is too large to be pinned as a single block", name))?;
+    let url = format!(
+        "{}/api/v0/add?pin=true&cid-version=0",
+        api_url.trim_end_matches('/')
+    );
+
+    let mut child = Command::new("curl")
+        .args(&["--silent", "--show-error", "--fail", "-X", "POST", "-F"])
+        .arg(format!("file=@-;filename=\"{}\"", name))
+        .arg(&url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|_| "unable to run curl, make sure it is installed".to_string())?;
+
+    child
+        .stdin
+        .as_mut()
+        .expect("curl stdin not captured")
+        .write_all(content)
+        .map_err(|error| format!("unable to upload `{}`: {}", name, error))?;
+
+    let output = child
+        .wait_with_output()
+        .map_err(|error| format!("unable to upload `{}`: {}", name, error))?;
+    if !output.status.success() {
+        return Err(format!(
+            "unable to upload `{}` to {}: {}",
+            name,
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let hash = parse_hash(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| format!("unexpected response of {} to `{}`", url, name))?;
+    if hash != expected {
+        return Err(format!(
+            "IPFS added `{}` as {} instead of {}",
+            name, hash, expected
+        ));
+    }
+    Ok(hash)
+}
+
+/// The hash in the response of `/api/v0/add`.
+fn parse_hash(response: &str) -> Option<String> {
+    let response: serde_json::Value = serde_json::from_str(response.trim()).ok()?;
+    response["Hash"].as_str().map(str::to_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ipfs::parse_hash;
+
+    #[test]
+    fn add_response() {
+        assert_eq!(
+            parse_hash(
+                "{\"Name\":\"main.fe\",\"Hash\":\"QmbFMke1KXqnYyBBWxB74N4c5SBnJMVAiMNRcGu6x1AwQH\",\"Size\":\"6\"}\n"
+            ),
+            Some("QmbFMke1KXqnYyBBWxB74N4c5SBnJMVAiMNRcGu6x1AwQH".to_string())
+        );
+        assert_eq!(parse_hash("Not Found"), None);
+    }
+}
@@ -0,0 +1,209 @@
+//! Writes an index of a source tree as an LSIF dump (`fe index --format
+//! lsif`), which code hosts like GitHub and Sourcegraph use to navigate code
+//! without running a language server.
+//!
+//! A dump is a sequence of JSON vertices and edges, one per line. Each symbol
+//! becomes a result set that the ranges of its definition and references
+//! point to, with the definition, reference and hover results of the symbol
+//! attached to it.
+
+use fe_compiler::index::{
+    Index,
+    Location,
+};
+use serde_json::{
+    json,
+    Value,
+};
+use std::collections::BTreeMap;
+
+/// The version of LSIF that dumps conform to.
+const LSIF_VERSION: &str = "0.4.3";
+
+struct Dump {
+    lines: Vec<String>,
+    next_id: usize,
+}
+
+impl Dump {
+    /// Adds an element with the given properties and returns its id.
+    fn add(&mut self, element: &str, label: &str, properties: Value) -> usize {
+        self.next_id += 1;
+        let mut object = json!({ "id": self.next_id, "type": element, "label": label });
+        if let (Some(object), Value::Object(properties)) = (object.as_object_mut(), properties) {
+            object.extend(properties);
+        }
+        self.lines.push(object.to_string());
+
+        self.next_id
+    }
+
+    fn vertex(&mut self, label: &str, properties: Value) -> usize {
+        self.add("vertex", label, properties)
+    }
+
+    fn edge(&mut self, label: &str, out_v: usize, in_v: usize) {
+        self.add("edge", label, json!({ "outV": out_v, "inV": in_v }));
+    }
+
+    fn range(&mut self, location: &Location) -> usize {
+        self.vertex(
+            "range",
+            json!({
+                "start": { "line": location.start.line, "character": location.start.character },
+                "end": { "line": location.end.line, "character": location.end.character },
+            }),
+        )
+    }
+}
+
+/// Formats the index as an LSIF dump of the files under the root directory,
+/// which is given as an absolute path.
+pub fn dump(index: &Index, root: &str) -> String {
+    let root = format!("file://{}", root.trim_end_matches('/'));
+    let mut dump = Dump {
+        lines: vec![],
+        next_id: 0,
+    };
+
+    dump.vertex(
+        "metaData",
+        json!({
+            "version": LSIF_VERSION,
+            "projectRoot": root,
+            "positionEncoding": "utf-16",
+            "toolInfo": { "name": "fe", "version": env!("CARGO_PKG_VERSION") },
+        }),
+    );
+    let project = dump.vertex("project", json!({ "kind": "fe" }));
+    let documents = index
+        .files
+        .iter()
+        .map(|path| {
+            let uri = format!("{}/{}", root, path.trim_start_matches("./"));
+            let document = dump.vertex("document", json!({ "uri": uri, "languageId": "fe" }));
+            (path.as_str(), document)
+        })
+        .collect::<BTreeMap<_, _>>();
+    dump.add(
+        "edge",
+        "contains",
+        json!({ "outV": project, "inVs": documents.values().collect::<Vec<_>>() }),
+    );
+
+    let mut ranges = BTreeMap::<usize, Vec<usize>>::new();
+    for symbol in index.symbols.iter() {
+        let document = |location: &Location| documents[location.path.as_str()];
+        let result_set = dump.vertex("resultSet", json!({}));
+
+        let definition = dump.range(&symbol.definition);
+        dump.edge("next", definition, result_set);
+        ranges
+            .entry(document(&symbol.definition))
+            .or_default()
+            .push(definition);
+
+        let definition_result = dump.vertex("definitionResult", json!({}));
+        dump.edge("textDocument/definition", result_set, definition_result);
+        dump.add(
+            "edge",
+            "item",
+            json!({
+                "outV": definition_result,
+                "inVs": [definition],
+                "document": document(&symbol.definition),
+            }),
+        );
+
+        let reference_result = dump.vertex("referenceResult", json!({}));
+        dump.edge("textDocument/references", result_set, reference_result);
+        dump.add(
+            "edge",
+            "item",
+            json!({
+                "outV": reference_result,
+                "inVs": [definition],
+                "document": document(&symbol.definition),
+                "property": "definitions",
+            }),
+        );
+        let mut references = BTreeMap::<usize, Vec<usize>>::new();
+        for location in symbol.references.iter() {
+            let reference = dump.range(location);
+            dump.edge("next", reference, result_set);
+            ranges
+                .entry(document(location))
+                .or_default()
+                .push(reference);
+            references
+                .entry(document(location))
+                .or_default()
+                .push(reference);
+        }
+        for (document, references) in references {
+            dump.add(
+                "edge",
+                "item",
+                json!({
+                    "outV": reference_result,
+                    "inVs": references,
+                    "document": document,
+                    "property": "references",
+                }),
+            );
+        }
+
+        let mut contents = vec![json!({ "language": "fe", "value": symbol.signature })];
+        if !symbol.docs.is_empty() {
+            contents.push(json!(symbol.docs));
+        }
+        let hover_result =
+            dump.vertex("hoverResult", json!({ "result": { "contents": contents } }));
+        dump.edge("textDocument/hover", result_set, hover_result);
+    }
+
+    for (document, ranges) in ranges {
+        dump.add(
+            "edge",
+            "contains",
+            json!({ "outV": document, "inVs": ranges }),
+        );
+    }
+
+    let mut lsif = dump.lines.join("\n");
+    lsif.push('\n');
+    lsif
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lsif::dump;
+    use fe_compiler::files::SourceTree;
+    use serde_json::Value;
+
+    #[test]
+    fn lsif_dump() {
+        let tree = SourceTree::new(vec![(
+            "foo.fe".to_string(),
+            "contract Foo:\n    x: u256\n\n    pub def get() -> u256:\n        return self.x\n"
+                .to_string(),
+        )]);
+        let index = fe_compiler::index::build(&tree).unwrap();
+        let lines = dump(&index, "/project")
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines[0]["label"], "metaData");
+        assert!(lines
+            .iter()
+            .any(|line| line["label"] == "document" && line["uri"] == "file:///project/foo.fe"));
+        assert!(lines
+            .iter()
+            .any(|line| line["label"] == "item" && line["property"] == "references"));
+        assert!(lines.iter().any(|line| {
+            line["label"] == "hoverResult"
+                && line["result"]["contents"][0]["value"] == "pub def get() -> u256"
+        }));
+    }
+}
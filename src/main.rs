@@ -1,179 +1,1983 @@
 #![feature(external_doc)]
 #![doc(include = "../README.md")]
 
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+};
 use std::fs;
 use std::io::{
     Error,
     Write,
 };
 use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Instant;
 
 use clap::{
-    arg_enum,
+    value_t,
     values_t,
     App,
+    AppSettings,
     Arg,
+    ArgMatches,
+    SubCommand,
 };
 
 mod _utils;
-use crate::_utils::pretty_curly_print;
+mod hooks;
+#[cfg(feature = "solc-backend")]
+mod difftest;
+#[cfg(feature = "solc-backend")]
+mod gas_diff;
+mod ice;
+mod inspect;
+mod ipfs;
+mod lsif;
+mod manifest;
+#[cfg(feature = "solc-backend")]
+mod mutate;
+mod new;
+mod plugins;
+#[cfg(feature = "solc-backend")]
+mod profile;
+#[cfg(feature = "solc-backend")]
+mod simulate;
+mod summary;
+#[cfg(feature = "solc-backend")]
+mod test_runner;
+use crate::_utils::{
+    line_diff,
+    load_sources,
+    remap_path,
+    PathPrefixMap,
+};
+use crate::manifest::{
+    Features,
+    LintLevels,
+    Manifest,
+};
+use crate::plugins::{
+    LintPackLibrary,
+    Plugin,
+};
+use crate::summary::BuildSummary;
+use fe_compiler::driver::{
+    Build,
+    BytecodeFormat,
+    Target,
+};
+use fe_compiler::errors::CompileError;
+use fe_compiler::files::{
+    decode_source,
+    encode_source,
+    read_source,
+    SourceTree,
+};
+use fe_compiler::lints::{
+    Level,
+    LintInfo,
+    LintPack,
+};
+use fe_compiler::proxy::Implementation;
+#[cfg(feature = "solc-backend")]
 use fe_compiler::types::CompiledModule;
+use fe_compiler::types::Progress;
+use fe_compiler::Compiler;
 
 const DEFAULT_OUTPUT_DIR_NAME: &str = "output";
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-arg_enum! {
-    #[derive(PartialEq, Debug)]
-    pub enum CompilationTarget {
-        Abi,
-        Ast,
-        Bytecode,
-        Tokens,
-        Yul,
+pub fn main() {
+    ice::install(VERSION);
+
+    let matches = app().get_matches();
+    configure_color(matches.subcommand().1.unwrap_or(&matches));
+
+    let result = match matches.subcommand() {
+        ("fix", Some(matches)) => fix_command(matches),
+        ("disasm", Some(matches)) => disasm_command(matches),
+        ("flatten", Some(matches)) => flatten_command(matches),
+        ("graph", Some(matches)) => graph_command(matches),
+        ("index", Some(matches)) => index_command(matches),
+        ("semantic-tokens", Some(matches)) => semantic_tokens_command(matches),
+        ("inspect", Some(matches)) => inspect_command(matches),
+        ("test", Some(matches)) => test_command(matches),
+        ("mutate", Some(matches)) => mutate_command(matches),
+        ("profile", Some(matches)) => profile_command(matches),
+        ("gas-diff", Some(matches)) => gas_diff_command(matches),
+        ("simulate", Some(matches)) => simulate_command(matches),
+        ("difftest", Some(matches)) => difftest_command(matches),
+        ("abi-diff", Some(matches)) => abi_diff_command(matches),
+        ("abi-encode", Some(matches)) => abi_encode_command(matches),
+        ("layout-diff", Some(matches)) => layout_diff_command(matches),
+        ("new", Some(matches)) => new_command(matches),
+        _ => build_command(&matches),
+    };
+    if let Err(err) = result {
+        println!("{}", err);
+        std::process::exit(1)
     }
 }
 
-pub fn main() {
-    let matches = App::new("Fe")
-        .version(VERSION)
-        .about("Compiler for the Fe language")
-        .arg(
-            Arg::with_name("input")
-                .help("The input source file to use e.g erc20.fe")
-                .index(1)
-                .required(true),
-        )
-        .arg(
-            Arg::with_name("output-dir")
-                .short("o")
-                .long("output-dir")
-                .help("The directory to store the compiler output e.g /tmp/output")
-                .takes_value(true)
-                .default_value(DEFAULT_OUTPUT_DIR_NAME),
-        )
-        .arg(
-            Arg::with_name("emit")
-                .short("e")
-                .long("emit")
-                .help("Comma separated compile targets e.g. -e=bytecode,yul")
-                .possible_values(&["abi", "bytecode", "ast", "tokens", "yul"])
-                .default_value("abi,bytecode")
-                .use_delimiter(true)
-                .takes_value(true),
-        )
-        .arg(
-            Arg::with_name("overwrite")
-                .long("overwrite")
-                .help("Overwrite contents of output directory`"),
-        )
-        .arg(
-            Arg::with_name("optimize")
-                .long("optimize")
-                .help("Enables the Yul optimizer`"),
+/// The command line interface. Without a subcommand, `fe` compiles its input.
+fn app() -> App<'static, 'static> {
+    let emit_values = Target::ALL
+        .iter()
+        .map(|target| target.name())
+        .chain(std::iter::once("all"))
+        .collect::<Vec<_>>();
+    App::new("Fe")
+    .version(VERSION)
+    .about("Compiler for the Fe language")
+    .setting(AppSettings::SubcommandsNegateReqs)
+    .subcommand(
+        SubCommand::with_name("fix")
+            .about("Applies the suggested fixes of errors to the source file")
+            .arg(
+                Arg::with_name("input")
+                    .help("The source file to fix")
+                    .index(1)
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("dry-run")
+                    .long("dry-run")
+                    .help("Prints the changes instead of writing them"),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("disasm")
+            .about("Prints the opcodes of EVM bytecode")
+            .arg(
+                Arg::with_name("input")
+                    .help("A file containing hex encoded or raw bytecode, or hex encoded bytecode starting with 0x")
+                    .index(1)
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("source-map")
+                    .long("source-map")
+                    .help("A file containing a solc style source map of the bytecode")
+                    .takes_value(true)
+                    .requires("source"),
+            )
+            .arg(
+                Arg::with_name("source")
+                    .long("source")
+                    .help("The source file that the source map refers to")
+                    .takes_value(true)
+                    .requires("source-map"),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("flatten")
+            .about("Joins a directory of source files into a single source file")
+            .arg(
+                Arg::with_name("input")
+                    .help("The source file or directory of source files")
+                    .index(1)
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("output")
+                    .long("output")
+                    .short("o")
+                    .help("Writes the flattened source to the given file instead of printing it")
+                    .takes_value(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("graph")
+            .about("Prints the graph of the modules, their imports and the contracts that deploy or use each other")
+            .arg(
+                Arg::with_name("input")
+                    .help("The source file or directory of source files")
+                    .index(1)
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("format")
+                    .long("format")
+                    .help("The format of the graph")
+                    .possible_values(&["dot", "json"])
+                    .default_value("dot")
+                    .takes_value(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("index")
+            .about("Writes an index of the definitions and references of a project for code navigation tools")
+            .arg(
+                Arg::with_name("input")
+                    .help("The source file or directory of source files")
+                    .index(1)
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("format")
+                    .long("format")
+                    .help("The format of the index")
+                    .possible_values(&["json", "lsif"])
+                    .default_value("json")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("output")
+                    .long("output")
+                    .short("o")
+                    .help("Writes the index to the given file instead of printing it")
+                    .takes_value(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("semantic-tokens")
+            .about("Prints the semantic tokens of the names in a project for editor highlighting")
+            .arg(
+                Arg::with_name("input")
+                    .help("The source file or directory of source files")
+                    .index(1)
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("format")
+                    .long("format")
+                    .help("The format of the tokens")
+                    .possible_values(&["text", "json"])
+                    .default_value("text")
+                    .takes_value(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("inspect")
+            .about("Prints the selectors, events, errors, storage layout and bytecode size of each contract")
+            .arg(
+                Arg::with_name("input")
+                    .help("The source file, build info file or ABI file to inspect")
+                    .index(1)
+                    .required(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("test")
+            .about("Runs the functions marked with `@test` and exits with an error if any of them fails")
+            .arg(
+                Arg::with_name("input")
+                    .help("The source file to test")
+                    .index(1)
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("optimize")
+                    .long("optimize")
+                    .help("Enables the Yul optimizer [env: FE_OPTIMIZE]"),
+            )
+            .arg(
+                Arg::with_name("profile")
+                    .long("profile")
+                    .help("The build profile of the manifest whose settings apply, e.g. `release`")
+                    .takes_value(true)
+                    .default_value("debug")
+                    .env("FE_PROFILE"),
+            )
+            .arg(
+                Arg::with_name("features")
+                    .long("features")
+                    .help("Comma separated features of the manifest to enable")
+                    .takes_value(true)
+                    .use_delimiter(true)
+                    .env("FE_FEATURES"),
+            )
+            .arg(
+                Arg::with_name("all-features")
+                    .long("all-features")
+                    .help("Enables all features of the manifest [env: FE_ALL_FEATURES]"),
+            )
+            .arg(
+                Arg::with_name("no-default-features")
+                    .long("no-default-features")
+                    .help("Doesn't enable the `default` feature of the manifest [env: FE_NO_DEFAULT_FEATURES]"),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("mutate")
+            .about("Runs the tests against mutants of the code and reports the mutants that pass them")
+            .arg(
+                Arg::with_name("input")
+                    .help("The source file to mutate")
+                    .index(1)
+                    .required(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("profile")
+            .about("Prints the gas used by the tests or by the calls of a simulation script")
+            .arg(
+                Arg::with_name("input")
+                    .help("The source file to profile")
+                    .index(1)
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("script")
+                    .long("script")
+                    .help("Profiles the calls of a simulation script instead of the tests")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("folded")
+                    .long("folded")
+                    .help("Writes the profile as folded stacks for flamegraph tools to the given file")
+                    .takes_value(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("gas-diff")
+            .about("Compares the gas used by each function with a baseline and reports regressions and improvements")
+            .arg(
+                Arg::with_name("input")
+                    .help("The source file to measure")
+                    .index(1)
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("baseline")
+                    .help("A JSON file of the average gas used by each function in an earlier build")
+                    .index(2)
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("script")
+                    .long("script")
+                    .help("Measures the calls of a simulation script instead of the tests")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("tolerance")
+                    .long("tolerance")
+                    .help("The percentage by which the gas of a function may grow before it is reported as a regression")
+                    .takes_value(true)
+                    .default_value("0"),
+            )
+            .arg(
+                Arg::with_name("update")
+                    .long("update")
+                    .help("Writes the current gas usage to the baseline instead of comparing it"),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("simulate")
+            .about("Deploys a contract to an in-memory EVM and runs the calls of a script")
+            .arg(
+                Arg::with_name("input")
+                    .help("The source file of the contract")
+                    .index(1)
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("script")
+                    .help("A JSON file naming the contract and the calls to make")
+                    .index(2)
+                    .required(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("difftest")
+            .about("Runs the same calls against equivalent Fe and Solidity contracts and reports where they behave differently")
+            .arg(
+                Arg::with_name("input")
+                    .help("A Fe source file with a Solidity source file and a script of the same name next to it, or a directory of them")
+                    .index(1)
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("generate")
+                    .long("generate")
+                    .help("Also makes this many calls with generated arguments of each public function")
+                    .takes_value(true)
+                    .default_value("0"),
+            )
+            .arg(
+                Arg::with_name("seed")
+                    .long("seed")
+                    .help("The seed of the generated arguments")
+                    .takes_value(true)
+                    .default_value("1"),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("abi-diff")
+            .about("Compares the ABIs of two builds and reports breaking and additive changes")
+            .arg(
+                Arg::with_name("old")
+                    .help("The ABI file, output directory or source file of the old build")
+                    .index(1)
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("new")
+                    .help("The ABI file, output directory or source file of the new build")
+                    .index(2)
+                    .required(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("abi-encode")
+            .about("Prints the ABI-encoded calldata of a function call")
+            .arg(
+                Arg::with_name("function")
+                    .help("The signature of the function, e.g. `transfer(address,uint256)`, or its name with `--abi`")
+                    .index(1)
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("args")
+                    .help("The arguments of the call")
+                    .index(2)
+                    .multiple(true)
+                    .allow_hyphen_values(true),
+            )
+            .arg(
+                Arg::with_name("abi")
+                    .long("abi")
+                    .help("The ABI file, output directory or source file that defines the function")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("contract")
+                    .long("contract")
+                    .help("The contract of the function, if the ABIs define several")
+                    .requires("abi")
+                    .takes_value(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("layout-diff")
+            .about("Checks that upgrading proxies to a new build keeps their storage intact")
+            .arg(
+                Arg::with_name("old")
+                    .help("The proxy output of the deployed build, e.g. output/module_proxy.json")
+                    .index(1)
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("new")
+                    .help("The proxy output or output directory of the new build, or its source file")
+                    .index(2)
+                    .required(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("new")
+            .about("Creates a new project from a template")
+            .arg(
+                Arg::with_name("name")
+                    .help("The directory of the project")
+                    .index(1)
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("template")
+                    .long("template")
+                    .help("The contract that the project starts with")
+                    .possible_values(new::TEMPLATES)
+                    .default_value(new::TEMPLATES[0])
+                    .takes_value(true),
+            ),
+    )
+    .arg(
+        Arg::with_name("input")
+            .help("The input source file or directory of source files to use e.g erc20.fe")
+            .index(1)
+            .required(true),
+    )
+    .arg(
+        Arg::with_name("output-dir")
+            .short("o")
+            .long("output-dir")
+            .help("The directory to store the compiler output e.g /tmp/output")
+            .takes_value(true)
+            .env("FE_OUTPUT_DIR")
+            .default_value(DEFAULT_OUTPUT_DIR_NAME),
+    )
+    .arg(
+        Arg::with_name("emit")
+            .short("e")
+            .long("emit")
+            .help("Comma separated compile targets e.g. -e=bytecode,yul, or `all` for every available target")
+            .possible_values(&emit_values)
+            .default_value("abi,bytecode")
+            .env("FE_EMIT")
+            .use_delimiter(true)
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("path-prefix-map")
+            .long("path-prefix-map")
+            .help(
+                "Replaces a prefix of source paths in the outputs, e.g. /home/me/project=/src",
+            )
+            .value_name("FROM=TO")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1),
+    )
+    .arg(
+        Arg::with_name("build-info")
+            .long("build-info")
+            .help("Writes the input and output of the compilation to `build-info/` [env: FE_BUILD_INFO]"),
+    )
+    .arg(
+        Arg::with_name("pin-metadata")
+            .long("pin-metadata")
+            .help("Pins the source and the metadata of each contract on the IPFS node with this API URL, e.g. http://127.0.0.1:5001. Implies `--emit metadata`")
+            .value_name("IPFS_API_URL")
+            .env("FE_PIN_METADATA")
+            .takes_value(true),
+    )
+    .arg(Arg::with_name("flat-output").long("flat-output").help(
+        "Writes all outputs into the output directory instead of a directory per contract [env: FE_FLAT_OUTPUT]",
+    ))
+    .arg(
+        Arg::with_name("bytecode-format")
+            .long("bytecode-format")
+            .help("The encoding of bytecode outputs")
+            .possible_values(&["hex", "hex0x", "raw"])
+            .default_value("hex")
+            .env("FE_BYTECODE_FORMAT")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("overwrite")
+            .long("overwrite")
+            .help("Overwrite contents of output directory [env: FE_OVERWRITE]"),
+    )
+    .arg(
+        Arg::with_name("optimize")
+            .long("optimize")
+            .help("Enables the Yul optimizer [env: FE_OPTIMIZE]"),
+    )
+    .arg(
+        Arg::with_name("json")
+            .long("json")
+            .help("Prints a JSON summary of the build instead of messages for humans [env: FE_JSON]"),
+    )
+    .arg(
+        Arg::with_name("profile")
+            .long("profile")
+            .help("The build profile of the manifest whose settings apply, e.g. `release`")
+            .takes_value(true)
+            .default_value("debug")
+            .env("FE_PROFILE"),
+    )
+    .arg(
+        Arg::with_name("features")
+            .long("features")
+            .help("Comma separated features of the manifest to enable")
+            .takes_value(true)
+            .use_delimiter(true)
+            .env("FE_FEATURES"),
+    )
+    .arg(
+        Arg::with_name("all-features")
+            .long("all-features")
+            .help("Enables all features of the manifest [env: FE_ALL_FEATURES]"),
+    )
+    .arg(
+        Arg::with_name("no-default-features")
+            .long("no-default-features")
+            .help("Doesn't enable the `default` feature of the manifest [env: FE_NO_DEFAULT_FEATURES]"),
+    )
+    .arg(
+        Arg::with_name("quiet")
+            .short("q")
+            .long("quiet")
+            .help("Doesn't report the progress of the build or print a message when it succeeds [env: FE_QUIET]"),
+    )
+    .arg(
+        Arg::with_name("color")
+            .long("color")
+            .help("When to color diagnostics. `auto` colors them if they are written to a terminal and `NO_COLOR` isn't set")
+            .takes_value(true)
+            .possible_values(&["auto", "always", "never"])
+            .default_value("auto")
+            .env("FE_COLOR")
+            .global(true),
+    )
+    .arg(
+        Arg::with_name("size-report")
+            .long("size-report")
+            .help("Prints the creation and runtime bytecode size of each contract [env: FE_SIZE_REPORT]"),
+    )
+    .arg(
+        Arg::with_name("warn")
+            .short("W")
+            .long("warn")
+            .help("Reports warnings of the given lint [env: FE_WARN]")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1),
+    )
+    .arg(
+        Arg::with_name("allow")
+            .short("A")
+            .long("allow")
+            .help("Ignores warnings of the given lint [env: FE_ALLOW]")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1),
+    )
+    .arg(
+        Arg::with_name("deny")
+            .short("D")
+            .long("deny")
+            .help("Reports warnings of the given lint as errors [env: FE_DENY]")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1),
+    )
+    .arg(
+        Arg::with_name("max-errors")
+            .long("max-errors")
+            .help("The maximum number of errors to report, or 0 to report all of them")
+            .takes_value(true)
+            .default_value("20")
+            .env("FE_MAX_ERRORS"),
+    )
+    .arg(
+        Arg::with_name("smt")
+            .long("smt")
+            .help("Checks assertions with the z3 SMT solver instead of compiling"),
+    )
+    .arg(
+        Arg::with_name("parse-only")
+            .long("parse-only")
+            .help("Only reports syntax errors, without analyzing or compiling the source"),
+    )
+}
+
+fn fix_command(matches: &ArgMatches) -> Result<(), String> {
+    let input_file = matches.value_of("input").unwrap();
+    fix(input_file, matches.is_present("dry-run"))
+        .map_err(|err| format!("Unable to fix {}. \nError: {}", input_file, err))
+}
+
+fn disasm_command(matches: &ArgMatches) -> Result<(), String> {
+    let input = matches.value_of("input").unwrap();
+    let source = matches
+        .value_of("source-map")
+        .zip(matches.value_of("source"));
+    disasm(input, source)
+        .map_err(|err| format!("Unable to disassemble {}. \nError: {}", input, err))
+}
+
+fn flatten_command(matches: &ArgMatches) -> Result<(), String> {
+    let input = matches.value_of("input").unwrap();
+    flatten(input, matches.value_of("output"))
+        .map_err(|err| format!("Unable to flatten {}. \nError: {}", input, err))
+}
+
+fn graph_command(matches: &ArgMatches) -> Result<(), String> {
+    let input = matches.value_of("input").unwrap();
+    graph(input, matches.value_of("format").unwrap())
+        .map_err(|err| format!("Unable to graph {}. \nError: {}", input, err))
+}
+
+fn index_command(matches: &ArgMatches) -> Result<(), String> {
+    let input = matches.value_of("input").unwrap();
+    index(
+        input,
+        matches.value_of("format").unwrap(),
+        matches.value_of("output"),
+    )
+    .map_err(|err| format!("Unable to index {}. \nError: {}", input, err))
+}
+
+fn semantic_tokens_command(matches: &ArgMatches) -> Result<(), String> {
+    let input = matches.value_of("input").unwrap();
+    semantic_tokens(input, matches.value_of("format").unwrap()).map_err(|err| {
+        format!(
+            "Unable to classify the tokens of {}. \nError: {}",
+            input, err
         )
-        .get_matches();
+    })
+}
 
+fn inspect_command(matches: &ArgMatches) -> Result<(), String> {
+    let input_file = matches.value_of("input").unwrap();
+    let inspection = inspect::load(input_file)
+        .map_err(|err| format!("Unable to inspect {}. \nError: {}", input_file, err))?;
+    print!("{}", inspect::format(&inspection));
+    Ok(())
+}
+
+/// Runs the tests of the input file with the optimizer setting of the chosen
+/// profile and the features enabled in the manifest.
+fn test_command(matches: &ArgMatches) -> Result<(), String> {
+    let input_file = matches.value_of("input").unwrap();
+    let result = Manifest::load(input_file).and_then(|manifest| {
+        let profile = manifest.profile(matches.value_of("profile").unwrap())?;
+        let features = manifest.resolve_features(
+            &matches
+                .values_of("features")
+                .map(|features| features.collect::<Vec<_>>())
+                .unwrap_or_default(),
+            !flag(matches, "no-default-features"),
+            flag(matches, "all-features"),
+        )?;
+        let optimize = flag_setting(matches, "optimize")
+            .or(profile.optimize)
+            .unwrap_or(false);
+        test(input_file, optimize, &features.enabled)
+    });
+    match result {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(format!("Some tests of {} failed.", input_file)),
+        Err(err) => Err(format!("Unable to test {}. \nError: {}", input_file, err)),
+    }
+}
+
+fn mutate_command(matches: &ArgMatches) -> Result<(), String> {
+    let input_file = matches.value_of("input").unwrap();
+    match mutate(input_file) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(format!("Some mutants of {} pass the tests.", input_file)),
+        Err(err) => Err(format!("Unable to mutate {}. \nError: {}", input_file, err)),
+    }
+}
+
+fn profile_command(matches: &ArgMatches) -> Result<(), String> {
+    let input_file = matches.value_of("input").unwrap();
+    profile(
+        input_file,
+        matches.value_of("script"),
+        matches.value_of("folded"),
+    )
+    .map_err(|err| format!("Unable to profile {}. \nError: {}", input_file, err))
+}
+
+fn gas_diff_command(matches: &ArgMatches) -> Result<(), String> {
+    let input_file = matches.value_of("input").unwrap();
+    let tolerance = value_t!(matches, "tolerance", f64).unwrap_or_else(|e| e.exit());
+    match gas_diff(
+        input_file,
+        matches.value_of("baseline").unwrap(),
+        matches.value_of("script"),
+        tolerance,
+        matches.is_present("update"),
+    ) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(format!(
+            "The gas used by {} grew by more than {}%.",
+            input_file, tolerance
+        )),
+        Err(err) => Err(format!(
+            "Unable to compare the gas of {}. \nError: {}",
+            input_file, err
+        )),
+    }
+}
+
+fn simulate_command(matches: &ArgMatches) -> Result<(), String> {
+    let input_file = matches.value_of("input").unwrap();
+    let script_file = matches.value_of("script").unwrap();
+    match simulate(input_file, script_file) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(format!("Some calls of {} failed.", script_file)),
+        Err(err) => Err(format!(
+            "Unable to simulate {}. \nError: {}",
+            script_file, err
+        )),
+    }
+}
+
+fn difftest_command(matches: &ArgMatches) -> Result<(), String> {
+    let input = matches.value_of("input").unwrap();
+    let generate = value_t!(matches, "generate", usize).unwrap_or_else(|e| e.exit());
+    let seed = value_t!(matches, "seed", u64).unwrap_or_else(|e| e.exit());
+    match difftest(input, generate, seed) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(format!("Some cases of {} diverged.", input)),
+        Err(err) => Err(format!(
+            "Unable to run difftest {}. \nError: {}",
+            input, err
+        )),
+    }
+}
+
+fn abi_diff_command(matches: &ArgMatches) -> Result<(), String> {
+    let old = matches.value_of("old").unwrap();
+    let new = matches.value_of("new").unwrap();
+    match abi_diff(old, new) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(format!("The ABIs of {} break callers of {}.", new, old)),
+        Err(err) => Err(format!(
+            "Unable to compare {} and {}. \nError: {}",
+            old, new, err
+        )),
+    }
+}
+
+fn abi_encode_command(matches: &ArgMatches) -> Result<(), String> {
+    let function = matches.value_of("function").unwrap();
+    let args = matches
+        .values_of("args")
+        .map(|args| args.map(|arg| arg.to_string()).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let calldata = abi_encode(
+        function,
+        &args,
+        matches.value_of("abi"),
+        matches.value_of("contract"),
+    )
+    .map_err(|err| format!("Unable to encode a call of {}. \nError: {}", function, err))?;
+    println!("{}", calldata);
+    Ok(())
+}
+
+fn layout_diff_command(matches: &ArgMatches) -> Result<(), String> {
+    let old = matches.value_of("old").unwrap();
+    let new = matches.value_of("new").unwrap();
+    match layout_diff(old, new) {
+        Ok(true) => {
+            println!("The storage layouts of {} and {} are compatible.", old, new);
+            Ok(())
+        }
+        Ok(false) => Err(format!(
+            "The storage layouts of {} and {} are incompatible.",
+            old, new
+        )),
+        Err(err) => Err(format!(
+            "Unable to compare {} and {}. \nError: {}",
+            old, new, err
+        )),
+    }
+}
+
+fn new_command(matches: &ArgMatches) -> Result<(), String> {
+    let name = matches.value_of("name").unwrap();
+    let template = matches.value_of("template").unwrap();
+    let files = new::create(Path::new(name), template)
+        .map_err(|err| format!("Unable to create {}. \nError: {}", name, err))?;
+    for file in files {
+        println!("Created `{}`", file.display());
+    }
+    println!(
+        "Created project `{}` from the {} template. Run its tests with `fe test {}/src/main.fe`",
+        name, template, name
+    );
+    Ok(())
+}
+
+/// Compiles the input source file or directory with the settings of its
+/// manifest, or only checks it with `--smt` or `--parse-only`.
+fn build_command(matches: &ArgMatches) -> Result<(), String> {
     let input_file = matches.value_of("input").unwrap();
     let output_dir = matches.value_of("output-dir").unwrap();
-    let overwrite = matches.is_present("overwrite");
-    let optimize = matches.is_present("overwrite");
-    let targets =
-        values_t!(matches.values_of("emit"), CompilationTarget).unwrap_or_else(|e| e.exit());
+    let json = flag(matches, "json");
+    let started = Instant::now();
+    let failed = |err| build_error(input_file, output_dir, json, started, err);
+
+    let manifest = Manifest::load(input_file).map_err(failed)?;
+    let profile = manifest
+        .profile(matches.value_of("profile").unwrap())
+        .map_err(failed)?;
+    let features = manifest
+        .resolve_features(
+            &matches
+                .values_of("features")
+                .map(|features| features.collect::<Vec<_>>())
+                .unwrap_or_default(),
+            !flag(matches, "no-default-features"),
+            flag(matches, "all-features"),
+        )
+        .map_err(failed)?;
+    let overwrite = flag(matches, "overwrite");
+    let flat_output = flag(matches, "flat-output");
+    let with_build_info = flag_setting(matches, "build-info")
+        .or(profile.build_info)
+        .unwrap_or(false);
+    let path_prefix_maps = if matches.is_present("path-prefix-map") {
+        values_t!(matches.values_of("path-prefix-map"), PathPrefixMap).unwrap_or_else(|e| e.exit())
+    } else {
+        vec![]
+    };
+    let bytecode_format = match &profile.bytecode_format {
+        Some(format) if !explicit(matches, "bytecode-format") => format.as_str(),
+        _ => matches.value_of("bytecode-format").unwrap(),
+    };
+    let bytecode_format = BytecodeFormat::from_str(bytecode_format)
+        .map_err(|err| format!("Invalid profile. \nError: {}", err))?;
+    let optimize = flag_setting(matches, "optimize")
+        .or(profile.optimize)
+        .unwrap_or(false);
+    let size_report = flag(matches, "size-report");
+    let emit = match &profile.emit {
+        Some(emit) if !explicit(matches, "emit") => emit.iter().map(String::as_str).collect(),
+        _ => matches.values_of("emit").unwrap().collect::<Vec<_>>(),
+    };
+    let mut targets =
+        parse_targets(&emit).map_err(|err| format!("Invalid profile. \nError: {}", err))?;
+    let pinned_by = matches.value_of("pin-metadata");
+    if pinned_by.is_some() && !targets.contains(&Target::Metadata) {
+        targets.push(Target::Metadata);
+    }
+    let max_errors = match value_t!(matches, "max-errors", usize).unwrap_or_else(|e| e.exit()) {
+        0 => None,
+        max_errors => Some(max_errors),
+    };
+
+    if matches.is_present("smt") {
+        return match prove(input_file) {
+            Ok(true) => {
+                println!("All assertions in {} hold.", input_file);
+                Ok(())
+            }
+            Ok(false) => Err(format!("Some assertions in {} don't hold.", input_file)),
+            Err(err) => Err(format!("Unable to check {}. \nError: {}", input_file, err)),
+        };
+    }
 
-    match compile_and_write(input_file, &targets, &output_dir, overwrite, optimize) {
-        Ok(_) => println!("Compiled {}. Outputs in `{}`", input_file, output_dir),
-        Err(err) => {
-            println!("Unable to compile {}. \nError: {}", input_file, err);
-            std::process::exit(1)
+    if matches.is_present("parse-only") {
+        check_syntax(input_file, max_errors)
+            .map_err(|err| format!("Unable to parse {}. \nError: {}", input_file, err))?;
+        if !flag(matches, "quiet") {
+            println!("No syntax errors in {}", input_file);
         }
+        return Ok(());
     }
+
+    let quiet = flag(matches, "quiet");
+    let (plugins, lint_packs) = load_plugins(&manifest, &features).map_err(failed)?;
+    let external_lints = lint_packs
+        .iter()
+        .flat_map(|pack| pack.lints())
+        .collect::<Vec<_>>();
+    let lint_levels = lint_levels(&manifest, matches, &external_lints).map_err(failed)?;
+    let hooks = manifest.hooks;
+    let hooks_dir = manifest.root.as_deref();
+
+    if let Some(command) = &hooks.prebuild {
+        let env = hooks::env(input_file, output_dir, &[]);
+        hooks::run("prebuild", command, hooks_dir, &env, json).map_err(failed)?;
+    }
+
+    let mut compiler = Compiler::new()
+        .optimize(optimize)
+        .emit(&targets)
+        .require_bytecode(size_report)
+        .flat_output(flat_output)
+        .bytecode_format(bytecode_format)
+        .src_path(&remap_path(input_file, &path_prefix_maps))
+        .lint_params(lint_levels.params().clone());
+    if with_build_info {
+        compiler = compiler.build_info(&remap_path(input_file, &path_prefix_maps));
+    }
+    if !quiet {
+        compiler = compiler.progress(report_progress);
+    }
+    for feature in features.enabled.iter() {
+        compiler = compiler.feature(feature);
+    }
+    for plugin in plugins {
+        compiler = compiler.pass(Arc::new(plugin));
+    }
+    for lint_pack in lint_packs {
+        compiler = compiler.lint_pack(Arc::new(lint_pack));
+    }
+
+    let result = compile_and_write(
+        input_file,
+        &compiler,
+        &output_dir,
+        overwrite,
+        size_report && !json,
+        &lint_levels,
+        max_errors,
+    );
+    let result = match (result, &hooks.postbuild) {
+        (Ok((build, warnings)), Some(command)) => {
+            let outputs = build
+                .outputs
+                .iter()
+                .map(|output| Path::new(output_dir).join(&output.path))
+                .collect::<Vec<_>>();
+            let env = hooks::env(input_file, output_dir, &outputs);
+            hooks::run("postbuild", command, hooks_dir, &env, json).map(|_| (build, warnings))
+        }
+        (result, _) => result,
+    };
+
+    let (build, warnings) = result.map_err(failed)?;
+    if !json && !quiet {
+        println!("Compiled {}. Outputs in `{}`", input_file, output_dir);
+    }
+    if let Some(api_url) = pinned_by {
+        pin_metadata(api_url, input_file, &build)
+            .map_err(|err| failed(format!("unable to pin the metadata: {}", err)))?;
+    }
+    if json {
+        let summary =
+            BuildSummary::new(input_file, output_dir, &build, warnings, started.elapsed());
+        println!("{}", summary.to_json());
+    }
+
+    Ok(())
+}
+
+/// The message of a failed build, which is a JSON summary with `json`.
+fn build_error(
+    src_file: &str,
+    output_dir: &str,
+    json: bool,
+    started: Instant,
+    err: String,
+) -> String {
+    if json {
+        BuildSummary::failed(src_file, output_dir, err, started.elapsed()).to_json()
+    } else {
+        format!("Unable to compile {}. \nError: {}", src_file, err)
+    }
+}
+
+/// The name of the environment variable that sets an option, e.g.
+/// `FE_FLAT_OUTPUT` for `--flat-output`.
+fn env_var_name(option: &str) -> String {
+    format!("FE_{}", option.to_uppercase().replace('-', "_"))
+}
+
+/// Enables or disables colored diagnostics according to `--color`, the
+/// `NO_COLOR` environment variable and whether the output is a terminal.
+/// JSON summaries are never colored.
+fn configure_color(matches: &ArgMatches) {
+    let choice = fe_common::color::ColorChoice::parse(matches.value_of("color").unwrap())
+        .expect("clap validates the possible values");
+    let terminal = atty::is(atty::Stream::Stdout) && atty::is(atty::Stream::Stderr);
+    let no_color = std::env::var_os("NO_COLOR").map_or(false, |value| !value.is_empty());
+
+    fe_common::color::set_enabled(choice.resolve(terminal, no_color) && !flag(matches, "json"));
+}
+
+/// Whether an option with a default value is passed on the command line or
+/// set in its environment variable.
+fn explicit(matches: &ArgMatches, name: &str) -> bool {
+    matches.occurrences_of(name) > 0 || std::env::var_os(env_var_name(name)).is_some()
+}
+
+/// Parses the names of emit targets, of which `all` stands for every
+/// available target.
+fn parse_targets(names: &[&str]) -> Result<Vec<Target>, String> {
+    if names.contains(&"all") {
+        return Ok(Target::available());
+    }
+
+    names.iter().map(|name| Target::from_str(name)).collect()
+}
+
+/// Whether a flag is passed on the command line or set in its environment
+/// variable to `1`, `true`, `yes` or `on`.
+fn flag(matches: &ArgMatches, name: &str) -> bool {
+    flag_setting(matches, name).unwrap_or(false)
+}
+
+/// Whether a flag is enabled on the command line, or else enabled or
+/// disabled in its environment variable: `1`, `true`, `yes` and `on` enable
+/// it, `0`, `false`, `no` and `off` disable it. `None` if it is set in
+/// neither, so that the setting of a profile applies.
+fn flag_setting(matches: &ArgMatches, name: &str) -> Option<bool> {
+    if matches.is_present(name) {
+        return Some(true);
+    }
+
+    let value = std::env::var(env_var_name(name)).ok()?.to_lowercase();
+    if ["1", "true", "yes", "on"].contains(&value.as_str()) {
+        Some(true)
+    } else if ["0", "false", "no", "off"].contains(&value.as_str()) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Loads the plugins and lint packs listed in the project manifest.
+fn load_plugins(
+    manifest: &Manifest,
+    features: &Features,
+) -> Result<(Vec<Plugin>, Vec<LintPackLibrary>), String> {
+    let plugins = manifest
+        .plugin_paths(features)
+        .into_iter()
+        .map(|(name, path)| Plugin::load(name, &path))
+        .collect::<Result<_, _>>()?;
+    let lint_packs = manifest
+        .lint_pack_paths(features)
+        .into_iter()
+        .map(|(name, path)| LintPackLibrary::load(name, &path))
+        .collect::<Result<_, _>>()?;
+
+    Ok((plugins, lint_packs))
+}
+
+/// Combines the lint levels of the project manifest with those set in the
+/// environment and those passed on the command line. The environment
+/// variables `FE_WARN`, `FE_ALLOW` and `FE_DENY` take comma separated lint
+/// names and precedence over the manifest. Command line flags take
+/// precedence over both, and later flags take precedence over earlier ones.
+fn lint_levels(
+    manifest: &Manifest,
+    matches: &ArgMatches,
+    external: &[LintInfo],
+) -> Result<LintLevels, String> {
+    let mut levels = manifest.lint_levels_with(external)?;
+
+    for level in &["warn", "allow", "deny"] {
+        if let Ok(names) = std::env::var(env_var_name(level)) {
+            let names = names
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty());
+            for name in names {
+                levels.set(name, level)?;
+            }
+        }
+    }
+
+    let mut flags = vec![];
+    for level in &["warn", "allow", "deny"] {
+        if let (Some(indices), Some(names)) = (matches.indices_of(level), matches.values_of(level))
+        {
+            flags.extend(
+                indices
+                    .zip(names)
+                    .map(|(index, name)| (index, name, *level)),
+            );
+        }
+    }
+    flags.sort_by_key(|(index, _, _)| *index);
+
+    for (_, name, level) in flags {
+        levels.set(name, level)?;
+    }
+
+    Ok(levels)
 }
 
+#[cfg_attr(not(feature = "solc-backend"), allow(unused_variables))]
 fn compile_and_write(
     src_file: &str,
-    targets: &[CompilationTarget],
+    compiler: &Compiler,
     output_dir: &str,
     overwrite: bool,
-    optimize: bool,
-) -> Result<(), String> {
-    let src = fs::read_to_string(src_file).map_err(ioerr_to_string)?;
-    let with_bytecode = targets.contains(&CompilationTarget::Bytecode);
+    size_report: bool,
+    lint_levels: &LintLevels,
+    max_errors: Option<usize>,
+) -> Result<(Build, usize), String> {
+    let sources = load_sources(src_file)?;
+    let src = sources.src();
+    ice::set_source(src);
 
     #[cfg(not(feature = "solc-backend"))]
-    if with_bytecode {
+    if compiler.with_bytecode() {
         eprintln!("Warning: bytecode output requires 'solc-backend' feature. Try `cargo build --release --features solc-backend`. Skipping.");
     }
 
-    let compiled_module =
-        fe_compiler::compile(&src, with_bytecode, optimize).map_err(|error| error.to_string())?;
+    let build = compiler.compile(src);
+    clear_progress();
+    let build = build.map_err(|error| format_compile_error(&error, &sources, max_errors))?;
+
+    let mut denied = 0;
+    let mut warnings = 0;
+    for warning in build.module.warnings.iter() {
+        let level = lint_levels.get(warning.lint);
+        if level == Level::Deny {
+            denied += 1;
+        }
+        if level != Level::Allow {
+            warnings += 1;
+            eprintln!("{}\n", sources.format_warning(warning, level));
+        }
+    }
+    if denied > 0 {
+        return Err(format!("aborting due to {} denied lint warning(s)", denied));
+    }
+
+    #[cfg(feature = "solc-backend")]
+    if compiler.with_bytecode() {
+        check_contract_sizes(&build.module, size_report);
+    }
+
+    let removed = build
+        .write(Path::new(output_dir), overwrite)
+        .map_err(|error| error.to_string())?;
+    for path in removed {
+        eprintln!("Removed stale output `{}`", path.display());
+    }
 
-    write_compiled_module(compiled_module, targets, output_dir, overwrite)
+    Ok((build, warnings))
 }
 
-fn write_compiled_module(
-    mut module: CompiledModule,
-    targets: &[CompilationTarget],
-    output_dir: &str,
-    overwrite: bool,
-) -> Result<(), String> {
-    let output_dir = Path::new(output_dir);
-    if output_dir.is_file() {
-        return Err(format!(
-            "A file exists at path `{}`, the location of the output directory. Refusing to overwrite.",
-            output_dir.display()
+/// Pins the source and the metadata files of a build on an IPFS node, so
+/// that the IPFS hashes in the metadata resolve.
+fn pin_metadata(api_url: &str, src_file: &str, build: &Build) -> Result<(), String> {
+    let hash = ipfs::pin(api_url, src_file, build.src.as_bytes())?;
+    eprintln!("Pinned `{}` as {}", src_file, hash);
+
+    for output in build
+        .outputs
+        .iter()
+        .filter(|output| output.target == Target::Metadata.name())
+    {
+        let path = output.path.display().to_string();
+        let hash = ipfs::pin(api_url, &path, &output.content)?;
+        eprintln!("Pinned `{}` as {}", path, hash);
+    }
+
+    Ok(())
+}
+
+/// Reports the phase of a build on stderr. In a terminal, each phase replaces
+/// the previous one on the same line.
+fn report_progress(progress: Progress) {
+    if atty::is(atty::Stream::Stderr) {
+        eprint!("\r\x1b[K{}...", progress);
+    } else {
+        eprintln!("{}...", progress);
+    }
+}
+
+/// Removes the progress line from the terminal before other messages are
+/// printed.
+fn clear_progress() {
+    if atty::is(atty::Stream::Stderr) {
+        eprint!("\r\x1b[K");
+    }
+}
+
+/// Formats the errors of a failed compilation, of which at most `max_errors`
+/// are shown.
+fn format_compile_error(
+    error: &CompileError,
+    sources: &SourceTree,
+    max_errors: Option<usize>,
+) -> String {
+    let diagnostics = error.diagnostics();
+    let shown = max_errors.map_or(diagnostics.len(), |max| max.min(diagnostics.len()));
+
+    let mut formatted = diagnostics[..shown]
+        .iter()
+        .map(|diagnostic| sources.format_diagnostic(diagnostic))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if shown < diagnostics.len() {
+        formatted.push_str(&format!(
+            "\n\n... and {} more error(s)",
+            diagnostics.len() - shown
         ));
     }
 
-    if !overwrite {
-        verify_nonexistent_or_empty(output_dir)?;
+    formatted
+}
+
+/// Reports the syntax errors of a source file or of the source files of a
+/// directory.
+fn check_syntax(src_file: &str, max_errors: Option<usize>) -> Result<(), String> {
+    let sources = load_sources(src_file)?;
+    fe_compiler::check_syntax(sources.src())
+        .map_err(|error| format_compile_error(&error, &sources, max_errors))
+}
+
+/// Warns about contracts whose runtime bytecode exceeds the EIP-170 limit and
+/// optionally prints a table of all contract sizes.
+#[cfg(feature = "solc-backend")]
+fn check_contract_sizes(module: &CompiledModule, size_report: bool) {
+    use fe_compiler::evm::{
+        bytecode_size,
+        MAX_RUNTIME_SIZE,
+    };
+
+    let mut names = module.contracts.keys().collect::<Vec<_>>();
+    names.sort();
+
+    if size_report {
+        println!(
+            "{:<32} {:>16} {:>16}",
+            "Contract", "Creation (B)", "Runtime (B)"
+        );
     }
 
-    fs::create_dir_all(output_dir).map_err(ioerr_to_string)?;
+    for name in names {
+        let contract = &module.contracts[name];
+        let creation_size = bytecode_size(&contract.bytecode);
+        let runtime_size = bytecode_size(&contract.runtime_bytecode);
 
-    if targets.contains(&CompilationTarget::Ast) {
-        write_output(&output_dir.join("module.ast"), &module.fe_ast)?;
+        if size_report {
+            println!("{:<32} {:>16} {:>16}", name, creation_size, runtime_size);
+        }
+
+        if runtime_size > MAX_RUNTIME_SIZE {
+            eprintln!(
+                "Warning: contract `{}` has a runtime size of {} bytes, which exceeds the EIP-170 limit of {} bytes. It can not be deployed on mainnet.",
+                name, runtime_size, MAX_RUNTIME_SIZE
+            );
+        }
     }
+}
 
-    if targets.contains(&CompilationTarget::Tokens) {
-        write_output(&output_dir.join("module.tokens"), &module.fe_tokens)?;
+/// Applies the machine-applicable suggestions of the errors in the source
+/// file. With `dry_run`, a diff of the changes is printed instead.
+fn fix(src_file: &str, dry_run: bool) -> Result<(), String> {
+    let original = fs::read(src_file).map_err(ioerr_to_string)?;
+    let src = decode_source(&original)?;
+    let fixed = fe_compiler::fix::fix(&src);
+
+    if fixed.fixes == 0 {
+        println!("Nothing to fix in {}", src_file);
+    } else if dry_run {
+        print!(
+            "--- {}\n+++ {}\n{}",
+            src_file,
+            src_file,
+            line_diff(&src, &fixed.src)
+        );
+        println!("Would apply {} fix(es) to {}", fixed.fixes, src_file);
+    } else {
+        // the file keeps its byte order mark and line endings
+        write_output(Path::new(src_file), &encode_source(&fixed.src, &original))?;
+        println!("Applied {} fix(es) to {}", fixed.fixes, src_file);
+    }
+
+    Ok(())
+}
+
+/// Prints the module and contract graph of the sources in the format, which
+/// is either `dot` or `json`.
+fn graph(input: &str, format: &str) -> Result<(), String> {
+    let sources = load_sources(input)?;
+    let graph = fe_compiler::graph::build(&sources)
+        .map_err(|error| format_compile_error(&error, &sources, None))?;
+
+    match format {
+        "json" => println!("{}", graph.to_json()),
+        _ => print!("{}", graph.to_dot()),
+    }
+    Ok(())
+}
+
+/// Prints the flattened source of a source tree, or writes it to `output`.
+fn flatten(input: &str, output: Option<&str>) -> Result<(), String> {
+    let sources = load_sources(input)?;
+    let flattened = fe_compiler::flatten::flatten(&sources)
+        .map_err(|error| format_compile_error(&error, &sources, None))?;
+
+    match output {
+        Some(output) => {
+            write_output(Path::new(output), flattened.as_bytes())?;
+            println!("Wrote the flattened source to `{}`", output);
+        }
+        None => print!("{}", flattened),
     }
+    Ok(())
+}
 
-    for (name, contract) in module.contracts.drain() {
-        let contract_output_dir = output_dir.join(&name);
-        fs::create_dir_all(&contract_output_dir).map_err(ioerr_to_string)?;
+/// Prints the index of a source tree in the given format, or writes it to
+/// `output`.
+fn index(input: &str, format: &str, output: Option<&str>) -> Result<(), String> {
+    let sources = load_sources(input)?;
+    let index = fe_compiler::index::build(&sources)
+        .map_err(|error| format_compile_error(&error, &sources, None))?;
 
-        if targets.contains(&CompilationTarget::Abi) {
-            let file_name = format!("{}_abi.json", &name);
-            write_output(&contract_output_dir.join(file_name), &contract.json_abi)?;
+    let content = match format {
+        "lsif" => {
+            // the paths of the files are relative to the directory, or to
+            // the working directory for a single file
+            let root = if Path::new(input).is_dir() {
+                Path::new(input)
+            } else {
+                Path::new(".")
+            };
+            let root = fs::canonicalize(root).map_err(ioerr_to_string)?;
+            lsif::dump(&index, &root.to_string_lossy())
         }
+        _ => format!("{}\n", index.to_json()),
+    };
 
-        if targets.contains(&CompilationTarget::Yul) {
-            let file_name = format!("{}_ir.yul", &name);
-            write_output(
-                &contract_output_dir.join(file_name),
-                &pretty_curly_print(&contract.yul, 4),
-            )?;
+    match output {
+        Some(output) => {
+            write_output(Path::new(output), content.as_bytes())?;
+            println!("Wrote the index to `{}`", output);
+        }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
+
+/// Prints the classified names of each file, or the legend and the encoded
+/// tokens of the files like a language server would send them.
+fn semantic_tokens(input: &str, format: &str) -> Result<(), String> {
+    use fe_compiler::semantic_tokens;
+
+    let sources = load_sources(input)?;
+    let index = fe_compiler::index::build(&sources)
+        .map_err(|error| format_compile_error(&error, &sources, None))?;
+    let files = semantic_tokens::tokens(&index);
+
+    if format == "json" {
+        let files = files
+            .iter()
+            .map(|file| serde_json::json!({ "path": file.path, "data": file.encode() }))
+            .collect::<Vec<_>>();
+        let json = serde_json::json!({ "legend": semantic_tokens::legend(), "files": files });
+        println!("{}", json);
+        return Ok(());
+    }
+
+    for file in files {
+        let lines = sources
+            .files()
+            .iter()
+            .find(|source| source.path == file.path)
+            .map(|source| source.content.lines().collect::<Vec<_>>())
+            .unwrap_or_default();
+        for token in file.tokens {
+            // the name is read back from the line, whose characters are
+            // counted in UTF-16 code units
+            let name = lines
+                .get(token.line)
+                .map(|line| {
+                    let units = line.encode_utf16().collect::<Vec<_>>();
+                    let end = (token.character + token.length).min(units.len());
+                    String::from_utf16_lossy(&units[token.character.min(end)..end])
+                })
+                .unwrap_or_default();
+            println!(
+                "{}:{}:{} {} {} [{}] {}",
+                file.path,
+                token.line + 1,
+                token.character + 1,
+                token.length,
+                token.token_type,
+                token.modifiers.join(" "),
+                name
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Prints the instructions of bytecode given as a file or as a hex string.
+/// `source` is the path of a source map and of the source file it refers to.
+fn disasm(input: &str, source: Option<(&str, &str)>) -> Result<(), String> {
+    use fe_compiler::disasm;
+
+    let content = if input.starts_with("0x") {
+        input.as_bytes().to_vec()
+    } else {
+        fs::read(input).map_err(ioerr_to_string)?
+    };
+    let bytecode = disasm::decode(&content).map_err(|error| error.to_string())?;
+    let instructions = disasm::disassemble(&bytecode);
+
+    let formatted = match source {
+        Some((map_file, src_file)) => {
+            let map = fs::read_to_string(map_file).map_err(ioerr_to_string)?;
+            let locations = disasm::parse_source_map(&map).map_err(|error| error.to_string())?;
+            let src = read_source(src_file).map_err(ioerr_to_string)?;
+            disasm::format(&instructions, Some((locations.as_slice(), src.as_str())))
+        }
+        None => disasm::format(&instructions, None),
+    };
+    print!("{}", formatted);
+
+    Ok(())
+}
+
+/// Prints the changes between the ABIs of two builds, marking those that
+/// break callers of the old ABI.
+///
+/// Returns false if there are any breaking changes.
+fn abi_diff(old: &str, new: &str) -> Result<bool, String> {
+    let changes = fe_compiler::abi::diff::changes(&load_abis(old)?, &load_abis(new)?)
+        .map_err(|error| error.to_string())?;
+    for change in changes.iter() {
+        let kind = if change.is_breaking() {
+            "breaking"
+        } else {
+            "additive"
+        };
+        println!("{}: {}", kind, change);
+    }
+
+    let breaking = changes.iter().filter(|change| change.is_breaking()).count();
+    let bump = if breaking > 0 {
+        "a major"
+    } else if !changes.is_empty() {
+        "a minor"
+    } else {
+        "no"
+    };
+    println!(
+        "{} breaking change(s), {} additive change(s): {} version bump is needed",
+        breaking,
+        changes.len() - breaking,
+        bump
+    );
+
+    Ok(breaking == 0)
+}
+
+/// Encodes a call of the function as hex calldata. The function is looked up
+/// in the ABIs of a build if they are given, and is a signature otherwise.
+fn abi_encode(
+    function: &str,
+    args: &[String],
+    abi: Option<&str>,
+    contract: Option<&str>,
+) -> Result<String, String> {
+    use fe_compiler::abi::encoding;
+
+    let calldata = match abi {
+        Some(abi) => {
+            let abis = load_abis(abi)?;
+            let json_abi = match contract {
+                Some(contract) => abis
+                    .get(contract)
+                    .ok_or_else(|| format!("no contract named `{}`", contract))?,
+                None if abis.len() == 1 => abis.values().next().unwrap(),
+                None if abis.is_empty() => return Err(format!("no ABIs were found in {}", abi)),
+                None => {
+                    return Err(format!(
+                        "the ABIs define the contracts {}; choose one with `--contract`",
+                        abis.keys().cloned().collect::<Vec<_>>().join(", ")
+                    ))
+                }
+            };
+            let abi = encoding::load(json_abi).map_err(|error| error.to_string())?;
+            encoding::encode_call(&abi, function, args)
+        }
+        None => encoding::encode_signature_call(function, args),
+    }
+    .map_err(|error| error.to_string())?;
+
+    Ok(format!(
+        "0x{}",
+        calldata
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>()
+    ))
+}
+
+/// Loads the JSON ABIs of the contracts of a build from an ABI output, from
+/// the output directory containing them, or by compiling a source file.
+fn load_abis(path: &str) -> Result<BTreeMap<String, String>, String> {
+    let path = Path::new(path);
+    if path.is_dir() {
+        let mut abis = BTreeMap::new();
+        for entry in fs::read_dir(path).map_err(ioerr_to_string)? {
+            let contract = entry.map_err(ioerr_to_string)?.file_name();
+            let contract = contract.to_string_lossy();
+            let abi_file = path
+                .join(contract.as_ref())
+                .join(format!("{}_abi.json", contract));
+            if abi_file.is_file() {
+                abis.insert(
+                    contract.to_string(),
+                    fs::read_to_string(abi_file).map_err(ioerr_to_string)?,
+                );
+            }
+        }
+        Ok(abis)
+    } else if path
+        .extension()
+        .map_or(false, |extension| extension == "fe")
+    {
+        let src = read_source(path).map_err(ioerr_to_string)?;
+        let module =
+            fe_compiler::compile(&src, false, false).map_err(|error| error.format_user(&src))?;
+        Ok(module
+            .contracts
+            .into_iter()
+            .map(|(name, contract)| (name, contract.json_abi))
+            .collect())
+    } else {
+        let contract = path
+            .file_stem()
+            .map(|name| name.to_string_lossy().trim_end_matches("_abi").to_string())
+            .unwrap_or_default();
+        let abi = fs::read_to_string(path).map_err(ioerr_to_string)?;
+        Ok(vec![(contract, abi)].into_iter().collect())
+    }
+}
+
+/// Prints the changes between the storage layouts of the upgradeable
+/// contracts of two builds that make it unsafe to upgrade their proxies.
+///
+/// Returns false if there are any such changes.
+fn layout_diff(old: &str, new: &str) -> Result<bool, String> {
+    let changes =
+        fe_compiler::proxy::layout_changes(&load_proxy_info(old)?, &load_proxy_info(new)?);
+    for change in changes.iter() {
+        println!("error: {}", change);
+    }
+
+    Ok(changes.is_empty())
+}
+
+/// Loads the proxy information of a build from its proxy output, from the
+/// output directory containing it, or by compiling a source file.
+fn load_proxy_info(path: &str) -> Result<BTreeMap<String, Implementation>, String> {
+    let path = Path::new(path);
+    let json = if path.is_dir() {
+        fs::read_to_string(path.join("module_proxy.json")).map_err(ioerr_to_string)?
+    } else if path
+        .extension()
+        .map_or(false, |extension| extension == "fe")
+    {
+        let src = read_source(path).map_err(ioerr_to_string)?;
+        fe_compiler::compile(&src, false, false)
+            .map_err(|error| error.format_user(&src))?
+            .proxy
+    } else {
+        fs::read_to_string(path).map_err(ioerr_to_string)?
+    };
+
+    fe_compiler::proxy::parse(&json).map_err(|error| format!("{}: {}", path.display(), error))
+}
+
+/// Checks all assertions in the source file and prints the result of each.
+///
+/// Returns false if a counterexample was found for any of the assertions.
+fn prove(src_file: &str) -> Result<bool, String> {
+    use fe_compiler::smt::Outcome;
+
+    let src = read_source(src_file).map_err(ioerr_to_string)?;
+    let results = fe_compiler::smt::check_module(&src).map_err(|error| error.format_user(&src))?;
+
+    let mut holds = true;
+    for (query, outcome) in results {
+        let line = src[..query.span.start].matches('\n').count() + 1;
+        let location = format!(
+            "{}:{} {}.{}",
+            src_file, line, query.contract, query.function
+        );
+
+        match outcome {
+            Outcome::Proved => println!("{}: assertion proved", location),
+            Outcome::Counterexample(assignments) => {
+                holds = false;
+                let assignments = assignments
+                    .iter()
+                    .map(|(name, value)| format!("{} = {}", name, value))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                println!(
+                    "{}: assertion violated, counterexample: {}",
+                    location, assignments
+                );
+            }
+            Outcome::Unknown(reason) => {
+                println!("{}: assertion not checked ({})", location, reason)
+            }
         }
+    }
+
+    Ok(holds)
+}
 
-        #[cfg(feature = "solc-backend")]
-        if targets.contains(&CompilationTarget::Bytecode) {
-            let file_name = format!("{}.bin", &name);
-            write_output(&contract_output_dir.join(file_name), &contract.bytecode)?;
+/// Runs all test functions in the source file and prints the result of each.
+///
+/// Returns false if any of the tests failed.
+#[cfg(feature = "solc-backend")]
+fn test(src_file: &str, optimize: bool, features: &BTreeSet<String>) -> Result<bool, String> {
+    let src = read_source(src_file).map_err(ioerr_to_string)?;
+    let config = fe_compiler::cfg::Config {
+        test: true,
+        features: features.clone(),
+    };
+    let compiled = fe_compiler::compile_tests_with_config(&src, optimize, &config)
+        .map_err(|error| error.format_user(&src))?;
+    let results = test_runner::run_tests(&compiled);
+
+    println!("running {} test(s)", results.len());
+    let mut failed = 0;
+    for result in results.iter() {
+        let name = format!("{}.{}", result.test.contract, result.test.name);
+        match &result.failure {
+            None => println!("test {} ... ok", name),
+            Some(reason) => {
+                failed += 1;
+                println!("test {} ... FAILED ({})", name, reason);
+            }
         }
     }
+    println!(
+        "\ntest result: {}. {} passed; {} failed",
+        if failed == 0 { "ok" } else { "FAILED" },
+        results.len() - failed,
+        failed
+    );
+
+    Ok(failed == 0)
+}
+
+#[cfg(not(feature = "solc-backend"))]
+fn test(_src_file: &str, _optimize: bool, _features: &BTreeSet<String>) -> Result<bool, String> {
+    Err("running tests requires the 'solc-backend' feature. Try `cargo build --release --features solc-backend`.".to_string())
+}
+
+/// Runs the tests of a source file against each of its mutants and prints
+/// the mutants that pass all of them.
+///
+/// Returns false if any of the mutants survived.
+#[cfg(feature = "solc-backend")]
+fn mutate(src_file: &str) -> Result<bool, String> {
+    let src = read_source(src_file).map_err(ioerr_to_string)?;
+    let compiled =
+        fe_compiler::compile_tests(&src, false).map_err(|error| error.format_user(&src))?;
+    let results = test_runner::run_tests(&compiled);
+    if results.is_empty() {
+        return Err("there are no tests to run against the mutants".to_string());
+    }
+    if results.iter().any(|result| result.failure.is_some()) {
+        return Err("the tests have to pass before mutants can be tested".to_string());
+    }
+
+    let mutants = mutate::mutants(&src)?;
+    println!("testing {} mutant(s)", mutants.len());
+    let (mut killed, mut survived, mut rejected) = (0, 0, 0);
+    for mutant in mutants.iter() {
+        let location = format!(
+            "{}:{}:{}",
+            src_file,
+            mutant.position.line,
+            mutant.position.col + 1
+        );
+        match fe_compiler::compile_tests(&mutant.src, false) {
+            Ok(compiled) => {
+                let results = test_runner::run_tests(&compiled);
+                if results.iter().all(|result| result.failure.is_none()) {
+                    survived += 1;
+                    println!("mutant {} ... SURVIVED ({})", location, mutant.description);
+                } else {
+                    killed += 1;
+                    println!("mutant {} ... killed", location);
+                }
+            }
+            Err(_) => {
+                rejected += 1;
+                println!("mutant {} ... rejected by the compiler", location);
+            }
+        }
+    }
+    println!(
+        "\nmutation result: {}. {} killed; {} survived; {} rejected",
+        if survived == 0 { "ok" } else { "FAILED" },
+        killed,
+        survived,
+        rejected
+    );
+
+    Ok(survived == 0)
+}
+
+#[cfg(not(feature = "solc-backend"))]
+fn mutate(_src_file: &str) -> Result<bool, String> {
+    Err("mutation testing requires the 'solc-backend' feature. Try `cargo build --release --features solc-backend`.".to_string())
+}
+
+/// Prints a table of the gas used by the tests of a source file, or by the
+/// calls of a simulation script if one is given, and optionally writes it as
+/// folded stacks.
+#[cfg(feature = "solc-backend")]
+fn profile(src_file: &str, script_file: Option<&str>, folded: Option<&str>) -> Result<(), String> {
+    let samples = gas_samples(src_file, script_file)?;
+
+    print!("{}", profile::table(&samples));
+    if let Some(folded) = folded {
+        write_output(Path::new(folded), profile::folded(&samples).as_bytes())?;
+        println!("Wrote folded stacks to `{}`", folded);
+    }
 
     Ok(())
 }
 
-fn write_output(path: &Path, content: &str) -> Result<(), String> {
+/// Measures the gas used by the tests of a source file, or by the calls of a
+/// simulation script if one is given.
+#[cfg(feature = "solc-backend")]
+fn gas_samples(src_file: &str, script_file: Option<&str>) -> Result<Vec<profile::Sample>, String> {
+    let src = read_source(src_file).map_err(ioerr_to_string)?;
+    match script_file {
+        Some(script_file) => {
+            let script = simulate::Script::parse(
+                &fs::read_to_string(script_file).map_err(ioerr_to_string)?,
+            )?;
+            let module =
+                fe_compiler::compile(&src, true, false).map_err(|error| error.format_user(&src))?;
+            let transactions = simulate::run(&module, &script)?;
+            Ok(profile::from_transactions(&script.contract, &transactions))
+        }
+        None => {
+            let compiled =
+                fe_compiler::compile_tests(&src, false).map_err(|error| error.format_user(&src))?;
+            Ok(profile::from_tests(&test_runner::run_tests(&compiled)))
+        }
+    }
+}
+
+#[cfg(not(feature = "solc-backend"))]
+fn profile(
+    _src_file: &str,
+    _script_file: Option<&str>,
+    _folded: Option<&str>,
+) -> Result<(), String> {
+    Err("profiling requires the 'solc-backend' feature. Try `cargo build --release --features solc-backend`.".to_string())
+}
+
+/// Compares the gas used by each function with a baseline file and prints the
+/// changes as a Markdown table, or writes the current gas to the baseline if
+/// `update` is set.
+///
+/// Returns false if the gas of any function grew by more than `tolerance`
+/// percent.
+#[cfg(feature = "solc-backend")]
+fn gas_diff(
+    src_file: &str,
+    baseline_file: &str,
+    script_file: Option<&str>,
+    tolerance: f64,
+    update: bool,
+) -> Result<bool, String> {
+    let current = gas_diff::snapshot(&gas_samples(src_file, script_file)?);
+    if update {
+        write_output(
+            Path::new(baseline_file),
+            gas_diff::snapshot_to_json(&current).as_bytes(),
+        )?;
+        println!(
+            "Wrote the gas of {} function(s) to `{}`",
+            current.len(),
+            baseline_file
+        );
+        return Ok(true);
+    }
+
+    let baseline =
+        gas_diff::parse_snapshot(&fs::read_to_string(baseline_file).map_err(ioerr_to_string)?)?;
+    let changes = gas_diff::diff(&baseline, &current);
+    print!("{}", gas_diff::report(&changes, tolerance));
+
+    Ok(!changes.iter().any(|change| change.is_regression(tolerance)))
+}
+
+#[cfg(not(feature = "solc-backend"))]
+fn gas_diff(
+    _src_file: &str,
+    _baseline_file: &str,
+    _script_file: Option<&str>,
+    _tolerance: f64,
+    _update: bool,
+) -> Result<bool, String> {
+    Err("comparing gas requires the 'solc-backend' feature. Try `cargo build --release --features solc-backend`.".to_string())
+}
+
+/// Runs the calls of a script against the contract of a source file and
+/// prints what each of them did.
+///
+/// Returns false if any of the calls failed.
+#[cfg(feature = "solc-backend")]
+fn simulate(src_file: &str, script_file: &str) -> Result<bool, String> {
+    let src = read_source(src_file).map_err(ioerr_to_string)?;
+    let script =
+        simulate::Script::parse(&fs::read_to_string(script_file).map_err(ioerr_to_string)?)?;
+    let module =
+        fe_compiler::compile(&src, true, false).map_err(|error| error.format_user(&src))?;
+
+    let transactions = simulate::run(&module, &script)?;
+    print!("{}", simulate::report(&transactions));
+
+    Ok(transactions.iter().all(|transaction| transaction.succeeded))
+}
+
+#[cfg(not(feature = "solc-backend"))]
+fn simulate(_src_file: &str, _script_file: &str) -> Result<bool, String> {
+    Err("simulating requires the 'solc-backend' feature. Try `cargo build --release --features solc-backend`.".to_string())
+}
+
+/// Runs the scripts of the cases against their Fe and Solidity contracts,
+/// along with generated calls, and prints the calls whose outcomes differ.
+///
+/// Returns false if any of the cases diverged.
+#[cfg(feature = "solc-backend")]
+fn difftest(path: &str, generate: usize, seed: u64) -> Result<bool, String> {
+    let cases = difftest::load_cases(Path::new(path))?;
+    if cases.is_empty() {
+        return Err(format!("no Fe source files found in {}", path));
+    }
+
+    let mut diverged = 0;
+    for case in cases.iter() {
+        let module = fe_compiler::compile(&case.fe_src, true, false)
+            .map_err(|error| format!("{}: {}", case.name, error.format_user(&case.fe_src)))?;
+        let contract = module.contracts.get(&case.script.contract).ok_or_else(|| {
+            format!(
+                "{}: no contract named `{}`",
+                case.name, case.script.contract
+            )
+        })?;
+        let solidity =
+            fe_compiler::evm::compile_solidity(&case.solidity_src, &case.script.contract, false)
+                .map_err(|error| {
+                    format!("{}: {}", case.name, error.format_user(&case.solidity_src))
+                })?;
+
+        let mut script = case.script.clone();
+        script.calls.extend(difftest::generate_calls(
+            &contract.json_abi,
+            generate,
+            seed,
+        )?);
+        let fe = simulate::run(&module, &script)?;
+        let solidity = simulate::run_contract(&solidity.bytecode, &solidity.json_abi, &script)?;
+
+        let divergences = difftest::compare(&script, &fe, &solidity);
+        if divergences.is_empty() {
+            println!("case {} ... ok ({} call(s))", case.name, script.calls.len());
+        } else {
+            diverged += 1;
+            println!("case {} ... DIVERGED", case.name);
+            for divergence in divergences {
+                println!(
+                    "  {}: Fe {}, Solidity {}",
+                    divergence.transaction, divergence.fe, divergence.solidity
+                );
+            }
+        }
+    }
+
+    println!(
+        "\ndifftest result: {}. {} passed; {} diverged",
+        if diverged == 0 { "ok" } else { "FAILED" },
+        cases.len() - diverged,
+        diverged
+    );
+
+    Ok(diverged == 0)
+}
+
+#[cfg(not(feature = "solc-backend"))]
+fn difftest(_path: &str, _generate: usize, _seed: u64) -> Result<bool, String> {
+    Err("differential testing requires the 'solc-backend' feature. Try `cargo build --release --features solc-backend`.".to_string())
+}
+
+fn write_output(path: &Path, content: &[u8]) -> Result<(), String> {
     let mut file = fs::OpenOptions::new()
         .write(true)
         .create(true)
         .truncate(true)
         .open(path)
         .map_err(ioerr_to_string)?;
-    file.write_all(content.as_bytes())
-        .map_err(ioerr_to_string)?;
+    file.write_all(content).map_err(ioerr_to_string)?;
     Ok(())
 }
 
@@ -181,13 +1985,36 @@ fn ioerr_to_string(error: Error) -> String {
     format!("{}", error)
 }
 
-fn verify_nonexistent_or_empty(dir: &Path) -> Result<(), String> {
-    if !dir.exists() || dir.read_dir().map_err(ioerr_to_string)?.next().is_none() {
-        Ok(())
-    } else {
-        Err(format!(
-            "Directory '{}' is not empty. Use --overwrite to overwrite.",
-            dir.display()
-        ))
+#[cfg(test)]
+mod tests {
+    use crate::flag_setting;
+    use crate::manifest::Manifest;
+    use clap::{
+        App,
+        Arg,
+    };
+
+    #[test]
+    fn flags_override_profiles() {
+        let app = App::new("fe").arg(Arg::with_name("optimize").long("optimize"));
+        let profile = Manifest::parse("[profiles.debug]\noptimize = true")
+            .unwrap()
+            .profile("debug")
+            .unwrap();
+        let optimize = |args: &[&str]| {
+            flag_setting(&app.clone().get_matches_from(args.to_vec()), "optimize")
+                .or(profile.optimize)
+                .unwrap_or(false)
+        };
+
+        assert!(optimize(&["fe"]));
+
+        std::env::set_var("FE_OPTIMIZE", "false");
+        assert!(!optimize(&["fe"]));
+        assert!(optimize(&["fe", "--optimize"]));
+
+        std::env::set_var("FE_OPTIMIZE", "maybe");
+        assert!(optimize(&["fe"]));
+        std::env::remove_var("FE_OPTIMIZE");
     }
 }
@@ -0,0 +1,607 @@
+//! The project manifest (`fe.toml`).
+
+use fe_compiler::lints::{
+    self,
+    Level,
+    LintInfo,
+    LintParams,
+};
+use serde::de::{
+    self,
+    value::MapAccessDeserializer,
+};
+use serde::{
+    Deserialize,
+    Deserializer,
+};
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+    HashMap,
+};
+use std::fmt;
+use std::fs;
+use std::path::{
+    Path,
+    PathBuf,
+};
+use std::str::FromStr;
+
+pub const MANIFEST_FILE_NAME: &str = "fe.toml";
+
+/// The contents of a manifest file.
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// Lint levels by lint name.
+    #[serde(default)]
+    pub lints: HashMap<String, LintConfig>,
+    /// The plugins by name.
+    #[serde(default)]
+    pub plugins: BTreeMap<String, Dependency>,
+    /// The lint packs by name.
+    #[serde(default)]
+    pub lint_packs: BTreeMap<String, Dependency>,
+    /// The features by name, with the features and optional dependencies
+    /// that each of them enables.
+    #[serde(default)]
+    pub features: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    pub hooks: Hooks,
+    /// Build profiles by name.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+    /// The directory of the manifest, if it was loaded from a file.
+    #[serde(skip)]
+    pub root: Option<PathBuf>,
+}
+
+/// A plugin or lint pack, given by its path relative to the manifest or by a
+/// table like `{ path = "...", optional = true }`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Dependency {
+    Path(PathBuf),
+    Table {
+        path: PathBuf,
+        /// Optional dependencies are only loaded if a feature enables them.
+        #[serde(default)]
+        optional: bool,
+    },
+}
+
+impl Dependency {
+    pub fn path(&self) -> &Path {
+        match self {
+            Dependency::Path(path) | Dependency::Table { path, .. } => path,
+        }
+    }
+
+    pub fn optional(&self) -> bool {
+        matches!(self, Dependency::Table { optional: true, .. })
+    }
+}
+
+/// The features enabled for a build and the optional dependencies that they
+/// enable.
+#[derive(Debug, Default, PartialEq)]
+pub struct Features {
+    pub enabled: BTreeSet<String>,
+    pub dependencies: BTreeSet<String>,
+}
+
+/// Shell commands that run in the directory of the manifest before and after
+/// a build.
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Hooks {
+    pub prebuild: Option<String>,
+    /// Runs once the outputs have been written, but not if the build failed.
+    pub postbuild: Option<String>,
+}
+
+/// The settings of a build profile. Settings that are left out keep their
+/// defaults, and options passed on the command line or set in environment
+/// variables take precedence over the profile.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub optimize: Option<bool>,
+    /// Whether to write build info, which makes the build reproducible.
+    pub build_info: Option<bool>,
+    pub emit: Option<Vec<String>>,
+    pub bytecode_format: Option<String>,
+}
+
+impl Profile {
+    /// Uses the settings of `base` that aren't set in this profile.
+    fn or(self, base: Profile) -> Profile {
+        Profile {
+            optimize: self.optimize.or(base.optimize),
+            build_info: self.build_info.or(base.build_info),
+            emit: self.emit.or(base.emit),
+            bytecode_format: self.bytecode_format.or(base.bytecode_format),
+        }
+    }
+}
+
+/// The configuration of a single lint.
+///
+/// Either just a level (`tx_origin = "deny"`) or a table containing the level
+/// and the parameters of the lint (`[lints.unbounded_loop]` followed by
+/// `level = "deny"` and `max_iterations = 100`).
+#[derive(Debug, PartialEq)]
+pub enum LintConfig {
+    Level(String),
+    Table(LintTable),
+}
+
+/// The table of a lint. Every key other than `level` sets a parameter of the
+/// lint.
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct LintTable {
+    /// The level, which is left at its default if it isn't set.
+    pub level: Option<String>,
+    #[serde(flatten)]
+    pub params: BTreeMap<String, u64>,
+}
+
+impl LintConfig {
+    fn level(&self) -> Option<&str> {
+        match self {
+            LintConfig::Level(level) => Some(level),
+            LintConfig::Table(table) => table.level.as_deref(),
+        }
+    }
+
+    fn params(&self) -> Option<&BTreeMap<String, u64>> {
+        match self {
+            LintConfig::Level(_) => None,
+            LintConfig::Table(table) => Some(&table.params),
+        }
+    }
+}
+
+// not derived as an untagged enum, whose errors wouldn't name the unknown key
+impl<'de> Deserialize<'de> for LintConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = LintConfig;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a lint level or a table of a level and parameters")
+            }
+
+            fn visit_str<E: de::Error>(self, level: &str) -> Result<LintConfig, E> {
+                Ok(LintConfig::Level(level.to_string()))
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<LintConfig, A::Error> {
+                LintTable::deserialize(MapAccessDeserializer::new(map)).map(LintConfig::Table)
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+impl Manifest {
+    /// Parses the contents of a manifest file.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        toml::from_str(content)
+            .map_err(|error| format!("invalid {}: {}", MANIFEST_FILE_NAME, error))
+    }
+
+    /// Finds and loads the manifest of the project containing `src_file`.
+    ///
+    /// The manifest is searched for in the directory of the source file, or
+    /// in the source directory itself, and all of its parent directories.
+    /// Returns the default manifest if none exists.
+    pub fn load(src_file: &str) -> Result<Self, String> {
+        match find(Path::new(src_file)) {
+            Some(path) => {
+                let content = fs::read_to_string(&path)
+                    .map_err(|error| format!("unable to read {}: {}", path.display(), error))?;
+                let mut manifest = Self::parse(&content)?;
+                manifest.root = path.parent().map(Path::to_path_buf);
+                Ok(manifest)
+            }
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// The plugins by name with their paths resolved against the directory of
+    /// the manifest. Optional plugins are left out unless one of the features
+    /// enables them.
+    pub fn plugin_paths(&self, features: &Features) -> Vec<(&str, PathBuf)> {
+        self.resolve(&self.plugins, features)
+    }
+
+    /// The lint packs by name with their paths resolved like those of
+    /// plugins.
+    pub fn lint_pack_paths(&self, features: &Features) -> Vec<(&str, PathBuf)> {
+        self.resolve(&self.lint_packs, features)
+    }
+
+    fn resolve<'a>(
+        &self,
+        dependencies: &'a BTreeMap<String, Dependency>,
+        features: &Features,
+    ) -> Vec<(&'a str, PathBuf)> {
+        dependencies
+            .iter()
+            .filter(|(name, dependency)| {
+                !dependency.optional() || features.dependencies.contains(name.as_str())
+            })
+            .map(|(name, dependency)| {
+                let path = match &self.root {
+                    Some(root) => root.join(dependency.path()),
+                    None => dependency.path().to_path_buf(),
+                };
+                (name.as_str(), path)
+            })
+            .collect()
+    }
+
+    /// Resolves the features to enable like cargo does. The requested
+    /// features are enabled along with the `default` feature, unless
+    /// `default_features` is false, or all features and optional
+    /// dependencies with `all`. Each enabled feature enables the features it
+    /// lists, and the optional dependencies it lists as `dep:name`.
+    pub fn resolve_features(
+        &self,
+        requested: &[&str],
+        default_features: bool,
+        all: bool,
+    ) -> Result<Features, String> {
+        let mut pending = requested
+            .iter()
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>();
+        if default_features && self.features.contains_key("default") {
+            pending.push("default".to_string());
+        }
+        if all {
+            pending.extend(self.features.keys().cloned());
+            pending.extend(
+                self.plugins
+                    .iter()
+                    .chain(self.lint_packs.iter())
+                    .filter(|(_, dependency)| dependency.optional())
+                    .map(|(name, _)| format!("dep:{}", name)),
+            );
+        }
+
+        let mut features = Features::default();
+        while let Some(name) = pending.pop() {
+            if let Some(dependency) = name.strip_prefix("dep:") {
+                let optional = self
+                    .plugins
+                    .get(dependency)
+                    .or_else(|| self.lint_packs.get(dependency))
+                    .map_or(false, Dependency::optional);
+                if !optional {
+                    return Err(format!(
+                        "`{}` is not an optional plugin or lint pack",
+                        dependency
+                    ));
+                }
+                features.dependencies.insert(dependency.to_string());
+            } else {
+                let enables = self
+                    .features
+                    .get(&name)
+                    .ok_or_else(|| format!("unknown feature `{}`", name))?;
+                if features.enabled.insert(name) {
+                    pending.extend(enables.iter().cloned());
+                }
+            }
+        }
+
+        Ok(features)
+    }
+
+    /// The build profile named `name`. The `debug` profile builds without
+    /// optimizing and the `release` profile builds optimized with build info,
+    /// unless the manifest changes them.
+    pub fn profile(&self, name: &str) -> Result<Profile, String> {
+        let builtin = match name {
+            "debug" => Some(Profile {
+                optimize: Some(false),
+                ..Profile::default()
+            }),
+            "release" => Some(Profile {
+                optimize: Some(true),
+                build_info: Some(true),
+                ..Profile::default()
+            }),
+            _ => None,
+        };
+
+        match (self.profiles.get(name), builtin) {
+            (Some(profile), Some(builtin)) => Ok(profile.clone().or(builtin)),
+            (Some(profile), None) => Ok(profile.clone()),
+            (None, Some(builtin)) => Ok(builtin),
+            (None, None) => Err(format!("unknown profile `{}`", name)),
+        }
+    }
+
+    /// The lint levels and parameters configured in the manifest.
+    pub fn lint_levels(&self) -> Result<LintLevels, String> {
+        self.lint_levels_with(&[])
+    }
+
+    /// The lint levels and parameters configured in the manifest, which may
+    /// also configure the levels of the lints of lint packs.
+    pub fn lint_levels_with(&self, external: &[LintInfo]) -> Result<LintLevels, String> {
+        let mut levels = LintLevels::with_external(external);
+
+        for (name, config) in self.lints.iter() {
+            if let Some(level) = config.level() {
+                levels.set(name, level)?;
+            }
+            for (param, value) in config.params().into_iter().flatten() {
+                levels.set_param(name, param, *value)?;
+            }
+        }
+
+        Ok(levels)
+    }
+}
+
+fn find(src_file: &Path) -> Option<PathBuf> {
+    let src_file = src_file.canonicalize().ok()?;
+
+    // a source directory may contain the manifest itself
+    src_file
+        .ancestors()
+        .skip(if src_file.is_dir() { 0 } else { 1 })
+        .map(|dir| dir.join(MANIFEST_FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+/// The level of each lint, and the parameters of the built-in lints. Lints
+/// that have not been configured default to `Level::Warn`.
+#[derive(Debug, Default, PartialEq)]
+pub struct LintLevels {
+    levels: HashMap<&'static str, Level>,
+    params: LintParams,
+    /// The names of the lints of lint packs.
+    external: Vec<&'static str>,
+}
+
+impl LintLevels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Levels that may also be set for the lints of lint packs.
+    pub fn with_external(external: &[LintInfo]) -> Self {
+        LintLevels {
+            levels: HashMap::new(),
+            params: LintParams::new(),
+            external: external.iter().map(|lint| lint.name).collect(),
+        }
+    }
+
+    /// Sets the level of the lint named `name`.
+    pub fn set(&mut self, name: &str, level: &str) -> Result<(), String> {
+        let lint = lints::find(name)
+            .map(|lint| lint.name)
+            .or_else(|| self.external.iter().copied().find(|lint| *lint == name))
+            .ok_or_else(|| format!("unknown lint `{}`", name))?;
+        let level = Level::from_str(level).map_err(|_| {
+            format!(
+                "invalid level `{}` for lint `{}`, expected one of: allow, warn, deny",
+                level, name
+            )
+        })?;
+
+        self.levels.insert(lint, level);
+        Ok(())
+    }
+
+    /// Sets the parameter `param` of the lint named `name`.
+    pub fn set_param(&mut self, name: &str, param: &str, value: u64) -> Result<(), String> {
+        if self.external.contains(&name) {
+            return Err(format!(
+                "unknown parameter `{}` of lint `{}`, the lints of lint packs take no parameters",
+                param, name
+            ));
+        }
+
+        self.params.set(name, param, value)
+    }
+
+    /// The level of the lint named `name`.
+    pub fn get(&self, name: &str) -> Level {
+        self.levels.get(name).copied().unwrap_or_default()
+    }
+
+    /// The parameters of the built-in lints.
+    pub fn params(&self) -> &LintParams {
+        &self.params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::manifest::{
+        Features,
+        Manifest,
+    };
+    use fe_compiler::lints::{
+        Level,
+        LintInfo,
+    };
+    use std::path::PathBuf;
+
+    #[test]
+    fn lint_levels() {
+        let manifest = Manifest::parse(
+            r#"
+            [lints]
+            tx_origin = "deny"
+            unbounded_loop = { level = "allow" }
+            "#,
+        )
+        .expect("unable to parse manifest");
+        let levels = manifest.lint_levels().expect("invalid lint levels");
+
+        assert_eq!(levels.get("tx_origin"), Level::Deny);
+        assert_eq!(levels.get("unbounded_loop"), Level::Allow);
+    }
+
+    #[test]
+    fn lint_params() {
+        let manifest = Manifest::parse(
+            r#"
+            [lints.unbounded_loop]
+            level = "deny"
+            max_iterations = 100
+            "#,
+        )
+        .expect("unable to parse manifest");
+        let levels = manifest.lint_levels().expect("invalid lint levels");
+
+        assert_eq!(levels.get("unbounded_loop"), Level::Deny);
+        assert_eq!(
+            levels.params().get("unbounded_loop", "max_iterations"),
+            Some(100)
+        );
+
+        // parameters may be set without changing the level
+        let manifest = Manifest::parse("[lints.unbounded_loop]\nmax_iterations = 5").unwrap();
+        let levels = manifest.lint_levels().unwrap();
+        assert_eq!(levels.get("unbounded_loop"), Level::Warn);
+        assert_eq!(
+            levels.params().get("unbounded_loop", "max_iterations"),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn invalid_lints() {
+        let unknown = Manifest::parse("[lints]\nfoo = \"deny\"").unwrap();
+        assert_eq!(unknown.lint_levels(), Err("unknown lint `foo`".to_string()));
+
+        let invalid_level = Manifest::parse("[lints]\ntx_origin = \"forbid\"").unwrap();
+        assert!(invalid_level.lint_levels().is_err());
+
+        let unknown_param = Manifest::parse("[lints.tx_origin]\nlevel = \"deny\"\nlimit = 10")
+            .unwrap()
+            .lint_levels();
+        assert_eq!(
+            unknown_param,
+            Err("unknown parameter `limit` of lint `tx_origin`".to_string())
+        );
+
+        let invalid_param = Manifest::parse("[lints.unbounded_loop]\nmax_iterations = \"ten\"");
+        assert!(invalid_param.is_err());
+    }
+
+    #[test]
+    fn external_lint_levels() {
+        let manifest = Manifest::parse("[lints]\n\"vendor::reentrancy\" = \"deny\"").unwrap();
+        assert!(manifest.lint_levels().is_err());
+
+        let external = [LintInfo {
+            name: "vendor::reentrancy",
+            description: "state changes after external calls",
+        }];
+        let levels = manifest.lint_levels_with(&external).unwrap();
+        assert_eq!(levels.get("vendor::reentrancy"), Level::Deny);
+    }
+
+    #[test]
+    fn profiles() {
+        let manifest = Manifest::parse(
+            r#"
+            [profiles.release]
+            emit = ["abi", "bytecode", "yul"]
+
+            [profiles.audit]
+            emit = ["all"]
+            "#,
+        )
+        .unwrap();
+
+        let release = manifest.profile("release").unwrap();
+        assert_eq!(release.optimize, Some(true));
+        assert_eq!(release.build_info, Some(true));
+        assert_eq!(release.emit.unwrap().len(), 3);
+        assert_eq!(manifest.profile("debug").unwrap().optimize, Some(false));
+        assert_eq!(manifest.profile("audit").unwrap().optimize, None);
+        assert!(manifest.profile("fast").is_err());
+    }
+
+    #[test]
+    fn plugin_paths() {
+        let mut manifest = Manifest::parse(
+            r#"
+            [plugins]
+            coverage = "plugins/libcoverage.so"
+            tracing = { path = "plugins/libtracing.so", optional = true }
+            "#,
+        )
+        .unwrap();
+        manifest.root = Some(PathBuf::from("project"));
+
+        assert_eq!(
+            manifest.plugin_paths(&Features::default()),
+            vec![("coverage", PathBuf::from("project/plugins/libcoverage.so"))]
+        );
+
+        let features = Features {
+            dependencies: vec!["tracing".to_string()].into_iter().collect(),
+            ..Features::default()
+        };
+        assert_eq!(manifest.plugin_paths(&features).len(), 2);
+    }
+
+    #[test]
+    fn features() {
+        let manifest = Manifest::parse(
+            r#"
+            [plugins]
+            tracing = { path = "plugins/libtracing.so", optional = true }
+
+            [features]
+            default = ["mocks"]
+            mocks = []
+            mainnet = ["dep:tracing"]
+            audit = ["mainnet", "mocks"]
+            "#,
+        )
+        .unwrap();
+        let enabled = |requested: &[&str], default_features, all| {
+            let features = manifest
+                .resolve_features(requested, default_features, all)
+                .unwrap();
+            (
+                features.enabled.into_iter().collect::<Vec<_>>(),
+                features.dependencies.into_iter().collect::<Vec<_>>(),
+            )
+        };
+        let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+
+        assert_eq!(
+            enabled(&[], true, false),
+            (names(&["default", "mocks"]), vec![])
+        );
+        assert_eq!(enabled(&[], false, false), (vec![], vec![]));
+        assert_eq!(
+            enabled(&["audit"], false, false),
+            (names(&["audit", "mainnet", "mocks"]), names(&["tracing"]))
+        );
+        assert_eq!(enabled(&[], false, true).0.len(), 4);
+
+        assert!(manifest
+            .resolve_features(&["testnet"], true, false)
+            .is_err());
+        let invalid = Manifest::parse("[features]\nmainnet = [\"dep:tracing\"]").unwrap();
+        assert!(invalid.resolve_features(&["mainnet"], true, false).is_err());
+    }
+}
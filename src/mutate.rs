@@ -0,0 +1,354 @@
+//! Mutation testing (`fe mutate`), which measures how thoroughly the tests of
+//! a module check its code.
+//!
+//! Each mutant of a module differs from it by a single small change to the
+//! code under test: an operator is swapped, the boundary of a comparison is
+//! moved, or an `assert` is deleted. A mutant that passes all tests survives,
+//! which points at behavior that none of the tests check.
+//!
+//! Mutations are made to the AST of the module, which is printed back to
+//! source code and compiled like any other module, so that mutants that the
+//! analyzer rejects are never run. Tests and definitions that are only
+//! compiled for tests aren't mutated.
+
+use fe_compiler::errors::CompileError;
+use fe_parser::ast as fe;
+use fe_parser::span::{
+    Span,
+    Spanned,
+};
+use fe_parser::string_utils::{
+    Position,
+    StringPositions,
+};
+
+/// A copy of a module with a single mutation.
+#[derive(Debug, PartialEq)]
+pub struct Mutant {
+    /// The position of the mutated code in the source of the module.
+    pub position: Position,
+    /// The mutation, e.g. "replaced `<` with `<=`".
+    pub description: String,
+    /// The source code of the mutant.
+    pub src: String,
+}
+
+/// The mutants of the module in the source code, in the order of the code
+/// they mutate.
+pub fn mutants(src: &str) -> Result<Vec<Mutant>, String> {
+    fe_compiler::check_syntax(src).map_err(|error| error.format_user(src))?;
+    let tokens = fe_parser::get_parse_tokens(src)
+        .map_err(|error| CompileError::from(error).format_user(src))?;
+    let module = fe_parser::parsers::file_input_with_errors(&tokens).0.node;
+
+    let mut counter = Mutator::new(None);
+    counter.module(&mut module.clone());
+
+    let mut positions = StringPositions::new(src);
+    Ok((0..counter.count)
+        .map(|index| {
+            let mut mutant = module.clone();
+            let mut mutator = Mutator::new(Some(index));
+            mutator.module(&mut mutant);
+            let (span, description) = mutator.made.expect("the mutation wasn't made");
+
+            Mutant {
+                position: positions
+                    .get_pos(span.start)
+                    .expect("the mutation is outside of the source code"),
+                description,
+                src: fe_parser::printer::print_module(&mutant),
+            }
+        })
+        .collect())
+}
+
+/// Counts the possible mutations of a module in order, and makes the one with
+/// the given index.
+struct Mutator {
+    target: Option<usize>,
+    count: usize,
+    /// The span and the description of the mutation that was made.
+    made: Option<(Span, String)>,
+}
+
+impl Mutator {
+    fn new(target: Option<usize>) -> Self {
+        Mutator {
+            target,
+            count: 0,
+            made: None,
+        }
+    }
+
+    /// Replaces the node with its mutation, if the mutation is the target.
+    fn offer<T>(&mut self, node: &mut T, span: Span, mutation: Option<(T, String)>) {
+        if let Some((mutated, description)) = mutation {
+            if self.target == Some(self.count) {
+                *node = mutated;
+                self.made = Some((span, description));
+            }
+            self.count += 1;
+        }
+    }
+
+    fn module(&mut self, module: &mut fe::Module) {
+        for stmt in module.body.iter_mut() {
+            match &mut stmt.node {
+                fe::ModuleStmt::ContractDef {
+                    decorators, body, ..
+                } => {
+                    if !decorators.iter().any(is_test_decorator) {
+                        self.contract_body(body)
+                    }
+                }
+                fe::ModuleStmt::MixinDef { body, .. } | fe::ModuleStmt::LibraryDef { body, .. } => {
+                    self.contract_body(body)
+                }
+                fe::ModuleStmt::FuncDef { def } => self.contract_stmt(def),
+                _ => {}
+            }
+        }
+    }
+
+    fn contract_body(&mut self, body: &mut [Spanned<fe::ContractStmt>]) {
+        for stmt in body.iter_mut() {
+            self.contract_stmt(stmt)
+        }
+    }
+
+    fn contract_stmt(&mut self, stmt: &mut Spanned<fe::ContractStmt>) {
+        if let fe::ContractStmt::FuncDef {
+            decorators, body, ..
+        } = &mut stmt.node
+        {
+            if !decorators.iter().any(is_test_decorator) {
+                self.block(body)
+            }
+        }
+    }
+
+    fn block(&mut self, body: &mut [Spanned<fe::FuncStmt>]) {
+        for stmt in body.iter_mut() {
+            self.func_stmt(stmt)
+        }
+    }
+
+    fn func_stmt(&mut self, stmt: &mut Spanned<fe::FuncStmt>) {
+        if let fe::FuncStmt::Assert { .. } = stmt.node {
+            self.offer(
+                &mut stmt.node,
+                stmt.span,
+                Some((fe::FuncStmt::Pass, "deleted the `assert`".to_string())),
+            );
+        }
+
+        match &mut stmt.node {
+            fe::FuncStmt::Return { value: Some(value) }
+            | fe::FuncStmt::VarDecl {
+                value: Some(value), ..
+            }
+            | fe::FuncStmt::Assign { value, .. }
+            | fe::FuncStmt::Emit { value }
+            | fe::FuncStmt::Revert { error: Some(value) }
+            | fe::FuncStmt::Assert { test: value, .. } => self.expr(&mut value.node),
+            fe::FuncStmt::AugAssign { op, value, .. } => {
+                let mutation = bin_op_mutation(&op.node);
+                self.offer(&mut op.node, op.span, Some(mutation));
+                self.expr(&mut value.node);
+            }
+            fe::FuncStmt::For {
+                iter: test,
+                body,
+                or_else,
+                ..
+            }
+            | fe::FuncStmt::While {
+                test,
+                body,
+                or_else,
+            }
+            | fe::FuncStmt::If {
+                test,
+                body,
+                or_else,
+            } => {
+                self.expr(&mut test.node);
+                self.block(body);
+                self.block(or_else);
+            }
+            fe::FuncStmt::Match { value, arms } => {
+                self.expr(&mut value.node);
+                for arm in arms.iter_mut() {
+                    self.block(&mut arm.node.body);
+                }
+            }
+            fe::FuncStmt::Expr { value } => self.expr(value),
+            _ => {}
+        }
+    }
+
+    fn expr(&mut self, expr: &mut fe::Expr) {
+        match expr {
+            fe::Expr::BinOperation { left, op, right } => {
+                self.expr(&mut left.node);
+                let mutation = bin_op_mutation(&op.node);
+                self.offer(&mut op.node, op.span, Some(mutation));
+                self.expr(&mut right.node);
+            }
+            fe::Expr::BoolOperation { left, op, right } => {
+                self.expr(&mut left.node);
+                let mutation = match op.node {
+                    fe::BoolOperator::And => (fe::BoolOperator::Or, replaced("and", "or")),
+                    fe::BoolOperator::Or => (fe::BoolOperator::And, replaced("or", "and")),
+                };
+                self.offer(&mut op.node, op.span, Some(mutation));
+                self.expr(&mut right.node);
+            }
+            fe::Expr::CompOperation { left, op, right } => {
+                self.expr(&mut left.node);
+                let mutation = comp_op_mutation(&op.node);
+                self.offer(&mut op.node, op.span, mutation);
+                self.expr(&mut right.node);
+            }
+            fe::Expr::Ternary {
+                if_expr,
+                test,
+                else_expr,
+            } => {
+                self.expr(&mut if_expr.node);
+                self.expr(&mut test.node);
+                self.expr(&mut else_expr.node);
+            }
+            fe::Expr::UnaryOperation { operand: value, .. } | fe::Expr::Attribute { value, .. } => {
+                self.expr(&mut value.node)
+            }
+            fe::Expr::Subscript { value, slices } => {
+                self.expr(&mut value.node);
+                for slice in slices.node.iter_mut() {
+                    match &mut slice.node {
+                        fe::Slice::Index(index) => self.expr(index),
+                        fe::Slice::Slice { lower, upper, step } => {
+                            for bound in lower
+                                .iter_mut()
+                                .chain(upper.iter_mut())
+                                .chain(step.iter_mut())
+                            {
+                                self.expr(&mut bound.node)
+                            }
+                        }
+                    }
+                }
+            }
+            fe::Expr::Call { func, args } => {
+                self.expr(&mut func.node);
+                for arg in args.node.iter_mut() {
+                    match &mut arg.node {
+                        fe::CallArg::Arg(value) => self.expr(value),
+                        fe::CallArg::Kwarg(kwarg) => self.expr(&mut kwarg.value.node),
+                    }
+                }
+            }
+            fe::Expr::List { elts } | fe::Expr::Tuple { elts } => {
+                for elt in elts.iter_mut() {
+                    self.expr(&mut elt.node)
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether the decorator marks a test, or a definition that is only compiled
+/// for tests.
+fn is_test_decorator(decorator: &Spanned<fe::Expr>) -> bool {
+    match &decorator.node {
+        fe::Expr::Name("test") => true,
+        fe::Expr::Call { func, args } => {
+            func.node == fe::Expr::Name("cfg")
+                && matches!(
+                    args.node.as_slice(),
+                    [condition] if condition.node == fe::CallArg::Arg(fe::Expr::Name("test"))
+                )
+        }
+        _ => false,
+    }
+}
+
+/// Swaps an arithmetic or bitwise operator for a similar one.
+fn bin_op_mutation(op: &fe::BinOperator) -> (fe::BinOperator, String) {
+    let (mutated, from, to) = match op {
+        fe::BinOperator::Add => (fe::BinOperator::Sub, "+", "-"),
+        fe::BinOperator::Sub => (fe::BinOperator::Add, "-", "+"),
+        fe::BinOperator::Mult => (fe::BinOperator::Div, "*", "/"),
+        fe::BinOperator::Div => (fe::BinOperator::Mult, "/", "*"),
+        fe::BinOperator::FloorDiv => (fe::BinOperator::Mult, "//", "*"),
+        fe::BinOperator::Mod => (fe::BinOperator::Div, "%", "/"),
+        fe::BinOperator::Pow => (fe::BinOperator::Mult, "**", "*"),
+        fe::BinOperator::LShift => (fe::BinOperator::RShift, "<<", ">>"),
+        fe::BinOperator::RShift => (fe::BinOperator::LShift, ">>", "<<"),
+        fe::BinOperator::BitAnd => (fe::BinOperator::BitOr, "&", "|"),
+        fe::BinOperator::BitOr => (fe::BinOperator::BitAnd, "|", "&"),
+        fe::BinOperator::BitXor => (fe::BinOperator::BitAnd, "^", "&"),
+    };
+
+    (mutated, replaced(from, to))
+}
+
+/// Moves the boundary of an ordering, or negates an equality.
+fn comp_op_mutation(op: &fe::CompOperator) -> Option<(fe::CompOperator, String)> {
+    let (mutated, from, to) = match op {
+        fe::CompOperator::Lt => (fe::CompOperator::LtE, "<", "<="),
+        fe::CompOperator::LtE => (fe::CompOperator::Lt, "<=", "<"),
+        fe::CompOperator::Gt => (fe::CompOperator::GtE, ">", ">="),
+        fe::CompOperator::GtE => (fe::CompOperator::Gt, ">=", ">"),
+        fe::CompOperator::Eq => (fe::CompOperator::NotEq, "==", "!="),
+        fe::CompOperator::NotEq => (fe::CompOperator::Eq, "!=", "=="),
+        _ => return None,
+    };
+
+    Some((mutated, replaced(from, to)))
+}
+
+fn replaced(from: &str, to: &str) -> String {
+    format!("replaced `{}` with `{}`", from, to)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mutate::mutants;
+
+    #[test]
+    fn mutations() {
+        let src = "\
+contract Foo:
+    pub def max(a: u256, b: u256) -> u256:
+        assert a != b
+        if a > b:
+            return a
+        return b
+
+    @test
+    def test_max():
+        assert self.max(1, 2) == 2
+";
+        let mutants = mutants(src).unwrap();
+        let mutations = mutants
+            .iter()
+            .map(|mutant| (mutant.position.line, mutant.description.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            mutations,
+            vec![
+                (3, "deleted the `assert`"),
+                (3, "replaced `!=` with `==`"),
+                (4, "replaced `>` with `>=`"),
+            ]
+        );
+
+        assert!(!mutants[0].src.contains("assert a != b"));
+        assert!(mutants[1].src.contains("assert a == b"));
+        assert!(mutants[2].src.contains("if a >= b:"));
+        assert!(mutants[2].src.contains("assert self.max(1, 2) == 2"));
+    }
+}
@@ -0,0 +1,107 @@
+//! Scaffolding of new projects (`fe new`) from templates.
+
+use crate::manifest::MANIFEST_FILE_NAME;
+use std::fs;
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+/// The names of the templates, of which the first is the default.
+pub const TEMPLATES: &[&str] = &["counter", "erc20", "erc721", "erc1155", "multisig"];
+
+/// The path of the source file within a new project.
+const SRC_FILE: &str = "src/main.fe";
+
+/// The source of a template, containing a contract and its tests.
+pub fn template_src(template: &str) -> Option<&'static str> {
+    match template {
+        "counter" => Some(include_str!("templates/counter.fe")),
+        "erc20" => Some(include_str!("templates/erc20.fe")),
+        "erc721" => Some(include_str!("templates/erc721.fe")),
+        "erc1155" => Some(include_str!("templates/erc1155.fe")),
+        "multisig" => Some(include_str!("templates/multisig.fe")),
+        _ => None,
+    }
+}
+
+/// The manifest of a new project, with an empty lint table to be filled in.
+fn manifest(name: &str) -> String {
+    format!(
+        "# The manifest of the `{}` project.\n\n# Lint levels by lint name, e.g. `tx_origin = \"deny\"`.\n[lints]\n",
+        name
+    )
+}
+
+/// Creates a project in the directory `dir` from the given template and
+/// returns the paths of the files that were written.
+///
+/// Fails if the directory already exists, so that no files are overwritten.
+pub fn create(dir: &Path, template: &str) -> Result<Vec<PathBuf>, String> {
+    let src = template_src(template).ok_or_else(|| format!("unknown template `{}`", template))?;
+    if dir.exists() {
+        return Err(format!("`{}` already exists", dir.display()));
+    }
+    let name = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| format!("invalid project directory `{}`", dir.display()))?;
+
+    let files = vec![
+        (dir.join(MANIFEST_FILE_NAME), manifest(&name)),
+        (dir.join(SRC_FILE), src.to_string()),
+        (dir.join(".gitignore"), "/output\n".to_string()),
+    ];
+    for (path, content) in files.iter() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+        }
+        fs::write(path, content).map_err(|error| error.to_string())?;
+    }
+
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::manifest::Manifest;
+    use crate::new::{
+        manifest,
+        template_src,
+        TEMPLATES,
+    };
+
+    #[test]
+    fn templates_compile() {
+        for template in TEMPLATES {
+            let src = template_src(template).unwrap();
+            if let Err(error) = fe_compiler::compile_tests(src, false) {
+                panic!("template `{}`: {}", template, error.format_user(src))
+            }
+        }
+    }
+
+    #[cfg(feature = "solc-backend")]
+    #[test]
+    fn template_tests_pass() {
+        for template in TEMPLATES {
+            let compiled = fe_compiler::compile_tests(template_src(template).unwrap(), false)
+                .expect("unable to compile template");
+            let results = crate::test_runner::run_tests(&compiled);
+            assert!(!results.is_empty(), "template `{}` has no tests", template);
+
+            for result in results {
+                assert_eq!(
+                    result.failure, None,
+                    "template `{}`: test `{}` failed",
+                    template, result.test.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn manifest_parses() {
+        assert_eq!(Manifest::parse(&manifest("foo")), Ok(Manifest::default()));
+    }
+}
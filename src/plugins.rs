@@ -0,0 +1,311 @@
+//! Compiler passes and lint packs loaded from dynamic libraries that are
+//! listed in the `[plugins]` and `[lint_packs]` tables of the manifest.
+//!
+//! Strings passed between the compiler and a library are UTF-8 and
+//! nul-terminated, and strings returned by the library are passed back to
+//! `fe_plugin_free` once they have been read. Spans are byte offsets into
+//! the source like `{"start": 0, "end": 4}`.
+//!
+//! A plugin exports these C functions, of which only `fe_plugin_free` is
+//! required.
+//!
+//! ```c
+//! // Checks the module, given its AST as JSON. Returns a JSON array of
+//! // errors like `{"message": "...", "span": ...}`, in which the span is
+//! // optional, or null if there are none.
+//! char *fe_plugin_check(const char *ast);
+//! // Returns the transformed Yul object of the contract, or null to leave
+//! // it unchanged.
+//! char *fe_plugin_transform_yul(const char *contract, const char *yul);
+//! void fe_plugin_free(char *string);
+//! ```
+//!
+//! A lint pack exports these, all of which are required.
+//!
+//! ```c
+//! // Returns a JSON array of the lints of the pack, like
+//! // `{"name": "vendor::reentrancy", "description": "..."}`.
+//! char *fe_lints(void);
+//! // Checks the module, given as JSON like `{"ast": ..., "types": [...]}`
+//! // where each type is `{"span": ..., "type": "..."}` for an expression.
+//! // Returns a JSON array of warnings like `{"lint": "vendor::reentrancy",
+//! // "message": "...", "span": ..., "help": "..."}`, in which the help is
+//! // optional, or null if there are none.
+//! char *fe_lint_check(const char *module);
+//! void fe_plugin_free(char *string);
+//! ```
+
+use fe_analyzer::Context;
+use fe_compiler::errors::Diagnostic;
+use fe_compiler::lints::{
+    LintInfo,
+    LintPack,
+    Warning,
+};
+use fe_compiler::passes::Pass;
+use fe_parser::ast as fe;
+use fe_parser::span::Span;
+use serde::Deserialize;
+use std::ffi::{
+    CStr,
+    CString,
+};
+use std::fmt;
+use std::os::raw::c_char;
+use std::path::Path;
+
+type CheckFn = unsafe extern "C" fn(*const c_char) -> *mut c_char;
+type TransformYulFn = unsafe extern "C" fn(*const c_char, *const c_char) -> *mut c_char;
+type LintsFn = unsafe extern "C" fn() -> *mut c_char;
+type FreeFn = unsafe extern "C" fn(*mut c_char);
+
+/// A loaded dynamic library.
+struct Library {
+    name: String,
+    library: libloading::Library,
+}
+
+impl Library {
+    /// Loads the library at `path`, which has to export `fe_plugin_free`.
+    ///
+    /// Loading a library runs its initialization code, so plugins and lint
+    /// packs have to be trusted like the compiler itself.
+    fn load(kind: &str, name: &str, path: &Path) -> Result<Self, String> {
+        let library = unsafe { libloading::Library::new(path) }
+            .map_err(|error| format!("unable to load {} `{}`: {}", kind, name, error))?;
+        let library = Library {
+            name: name.to_string(),
+            library,
+        };
+        library.get::<FreeFn>("fe_plugin_free").ok_or_else(|| {
+            format!(
+                "the {} `{}` at {} doesn't export `fe_plugin_free`",
+                kind,
+                name,
+                path.display()
+            )
+        })?;
+
+        Ok(library)
+    }
+
+    /// The function named `symbol`, if the library exports it.
+    fn get<T: Copy>(&self, symbol: &str) -> Option<T> {
+        let symbol = format!("{}\0", symbol);
+        unsafe { self.library.get::<T>(symbol.as_bytes()) }
+            .ok()
+            .map(|function| *function)
+    }
+
+    /// Takes a string returned by the library, or `None` for null.
+    fn take(&self, string: *mut c_char) -> Result<Option<String>, String> {
+        if string.is_null() {
+            return Ok(None);
+        }
+
+        let taken = unsafe { CStr::from_ptr(string) }
+            .to_str()
+            .map(str::to_string)
+            .map_err(|_| "the library returned a string that isn't UTF-8".to_string());
+        if let Some(free) = self.get::<FreeFn>("fe_plugin_free") {
+            unsafe { free(string) };
+        }
+
+        taken.map(Some)
+    }
+}
+
+/// A compiler pass loaded from a plugin.
+pub struct Plugin(Library);
+
+/// An error reported by the check of a plugin.
+#[derive(Deserialize)]
+struct PluginError {
+    message: String,
+    span: Option<Span>,
+}
+
+impl Plugin {
+    pub fn load(name: &str, path: &Path) -> Result<Self, String> {
+        Library::load("plugin", name, path).map(Plugin)
+    }
+}
+
+impl fmt::Debug for Plugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Plugin").field(&self.0.name).finish()
+    }
+}
+
+impl Pass for Plugin {
+    fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    fn check(&self, module: &fe::Module) -> Result<Vec<Diagnostic>, String> {
+        let check = match self.0.get::<CheckFn>("fe_plugin_check") {
+            Some(check) => check,
+            None => return Ok(vec![]),
+        };
+
+        let ast = serde_json::to_string(module).map_err(|error| error.to_string())?;
+        let ast = CString::new(ast).map_err(|error| error.to_string())?;
+        let errors = match self.0.take(unsafe { check(ast.as_ptr()) })? {
+            Some(errors) => serde_json::from_str::<Vec<PluginError>>(&errors)
+                .map_err(|error| format!("invalid errors: {}", error))?,
+            None => vec![],
+        };
+
+        Ok(errors
+            .into_iter()
+            .map(|error| {
+                let diagnostic = Diagnostic::error(&self.0.name, &error.message);
+                match error.span {
+                    Some(span) => diagnostic.with_label(span, ""),
+                    None => diagnostic,
+                }
+            })
+            .collect())
+    }
+
+    fn transform_yul(&self, contract: &str, yul: String) -> Result<String, String> {
+        let transform = match self.0.get::<TransformYulFn>("fe_plugin_transform_yul") {
+            Some(transform) => transform,
+            None => return Ok(yul),
+        };
+
+        let contract_name = CString::new(contract).map_err(|error| error.to_string())?;
+        let input = CString::new(yul.as_str()).map_err(|error| error.to_string())?;
+        let transformed = self
+            .0
+            .take(unsafe { transform(contract_name.as_ptr(), input.as_ptr()) })?;
+
+        Ok(transformed.unwrap_or(yul))
+    }
+}
+
+/// A lint pack loaded from a library.
+pub struct LintPackLibrary {
+    library: Library,
+    lints: Vec<LintInfo>,
+}
+
+#[derive(Deserialize)]
+struct LintDescription {
+    name: String,
+    description: String,
+}
+
+#[derive(Deserialize)]
+struct LintWarning {
+    lint: String,
+    message: String,
+    span: Span,
+    help: Option<String>,
+}
+
+impl LintPackLibrary {
+    /// Loads the lint pack and asks it for its lints.
+    pub fn load(name: &str, path: &Path) -> Result<Self, String> {
+        let library = Library::load("lint pack", name, path)?;
+        let (lints_fn, _) = match (
+            library.get::<LintsFn>("fe_lints"),
+            library.get::<CheckFn>("fe_lint_check"),
+        ) {
+            (Some(lints_fn), Some(check)) => (lints_fn, check),
+            _ => {
+                return Err(format!(
+                    "the lint pack `{}` doesn't export `fe_lints` and `fe_lint_check`",
+                    name
+                ))
+            }
+        };
+
+        let descriptions = library.take(unsafe { lints_fn() })?.unwrap_or_default();
+        let descriptions = serde_json::from_str::<Vec<LintDescription>>(&descriptions)
+            .map_err(|error| format!("invalid lints of the lint pack `{}`: {}", name, error))?;
+        // the compiler refers to lints by static names, and lint packs stay
+        // loaded until it exits
+        let lints = descriptions
+            .into_iter()
+            .map(|lint| LintInfo {
+                name: Box::leak(lint.name.into_boxed_str()),
+                description: Box::leak(lint.description.into_boxed_str()),
+            })
+            .collect();
+
+        Ok(LintPackLibrary { library, lints })
+    }
+
+    /// Checks the module, failing with a message if the lint pack misbehaves.
+    fn try_check(&self, module: &fe::Module, context: &Context) -> Result<Vec<Warning>, String> {
+        let check = self
+            .library
+            .get::<CheckFn>("fe_lint_check")
+            .ok_or("`fe_lint_check` is missing")?;
+
+        let mut types = context
+            .expressions()
+            .map(|(span, attributes)| {
+                serde_json::json!({
+                    "span": span,
+                    "type": format!("{:?}", attributes.typ),
+                })
+            })
+            .collect::<Vec<_>>();
+        types.sort_by_key(|typ| typ["span"]["start"].as_u64());
+        let input = serde_json::json!({ "ast": module, "types": types }).to_string();
+        let input = CString::new(input).map_err(|error| error.to_string())?;
+
+        let warnings = match self.library.take(unsafe { check(input.as_ptr()) })? {
+            Some(warnings) => serde_json::from_str::<Vec<LintWarning>>(&warnings)
+                .map_err(|error| format!("invalid warnings: {}", error))?,
+            None => vec![],
+        };
+
+        warnings
+            .into_iter()
+            .map(|warning| {
+                let lint = self
+                    .lints
+                    .iter()
+                    .find(|lint| lint.name == warning.lint)
+                    .ok_or_else(|| format!("warning of the unknown lint `{}`", warning.lint))?;
+                Ok(Warning {
+                    lint: lint.name,
+                    message: warning.message,
+                    help: warning.help,
+                    span: warning.span,
+                    notes: vec![],
+                })
+            })
+            .collect()
+    }
+}
+
+impl fmt::Debug for LintPackLibrary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LintPackLibrary")
+            .field("name", &self.library.name)
+            .field("lints", &self.lints)
+            .finish()
+    }
+}
+
+impl LintPack for LintPackLibrary {
+    fn lints(&self) -> Vec<LintInfo> {
+        self.lints.clone()
+    }
+
+    fn check(&self, module: &fe::Module, context: &Context) -> Vec<Warning> {
+        // lints can't fail the compilation, so a misbehaving pack is only
+        // reported
+        self.try_check(module, context).unwrap_or_else(|error| {
+            eprintln!(
+                "Warning: the lint pack `{}` failed: {}",
+                self.library.name, error
+            );
+            vec![]
+        })
+    }
+}
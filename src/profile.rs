@@ -0,0 +1,131 @@
+//! Attributes the gas used by tests or by the calls of a simulation script to
+//! the functions that were called (`fe profile`).
+//!
+//! The compiler doesn't emit source maps yet, so gas is attributed to the
+//! function that a test or a transaction calls, rather than to the internal
+//! functions and lines that it runs.
+
+use crate::simulate::Transaction;
+use crate::test_runner::TestResult;
+use std::collections::BTreeMap;
+
+/// The gas used by one execution of a function.
+#[derive(Debug, PartialEq)]
+pub struct Sample {
+    /// The contract and the function, e.g. `["Token", "transfer"]`.
+    pub stack: Vec<String>,
+    pub gas: u64,
+}
+
+/// The name under which the deployment of a contract is profiled.
+const DEPLOYMENT: &str = "<deploy>";
+
+/// A sample of each test function.
+pub fn from_tests(results: &[TestResult]) -> Vec<Sample> {
+    results
+        .iter()
+        .map(|result| Sample {
+            stack: vec![result.test.contract.clone(), result.test.name.clone()],
+            gas: result.gas,
+        })
+        .collect()
+}
+
+/// A sample of the deployment and of each call of a simulation of the
+/// contract.
+pub fn from_transactions(contract: &str, transactions: &[Transaction]) -> Vec<Sample> {
+    transactions
+        .iter()
+        .map(|transaction| Sample {
+            stack: vec![
+                contract.to_string(),
+                transaction
+                    .function
+                    .clone()
+                    .unwrap_or_else(|| DEPLOYMENT.to_string()),
+            ],
+            gas: transaction.gas,
+        })
+        .collect()
+}
+
+/// The number of samples and the total gas of each stack.
+fn totals(samples: &[Sample]) -> BTreeMap<&[String], (u64, u64)> {
+    let mut totals = BTreeMap::<&[String], (u64, u64)>::new();
+    for sample in samples {
+        let total = totals.entry(&sample.stack).or_default();
+        total.0 += 1;
+        total.1 += sample.gas;
+    }
+
+    totals
+}
+
+/// Formats a table of the functions sorted by the total gas they used.
+pub fn table(samples: &[Sample]) -> String {
+    let mut totals = totals(samples).into_iter().collect::<Vec<_>>();
+    totals.sort_by(|(a_stack, (_, a_gas)), (b_stack, (_, b_gas))| {
+        b_gas.cmp(a_gas).then(a_stack.cmp(b_stack))
+    });
+    let all_gas = samples.iter().map(|sample| sample.gas).sum::<u64>().max(1);
+
+    let mut table = format!(
+        "{:<40} {:>8} {:>12} {:>12} {:>8}\n",
+        "Function", "Calls", "Total gas", "Average gas", "Share"
+    );
+    for (stack, (calls, gas)) in totals {
+        table.push_str(&format!(
+            "{:<40} {:>8} {:>12} {:>12} {:>7.1}%\n",
+            stack.join("."),
+            calls,
+            gas,
+            gas / calls,
+            gas as f64 * 100.0 / all_gas as f64
+        ));
+    }
+
+    table
+}
+
+/// Formats the samples as folded stacks, which tools like `inferno` and
+/// `flamegraph.pl` turn into flamegraphs.
+pub fn folded(samples: &[Sample]) -> String {
+    totals(samples)
+        .into_iter()
+        .map(|(stack, (_, gas))| format!("{} {}\n", stack.join(";"), gas))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::profile::{
+        folded,
+        table,
+        Sample,
+    };
+
+    fn sample(function: &str, gas: u64) -> Sample {
+        Sample {
+            stack: vec!["Token".to_string(), function.to_string()],
+            gas,
+        }
+    }
+
+    #[test]
+    fn profile() {
+        let samples = vec![
+            sample("approve", 100),
+            sample("transfer", 200),
+            sample("transfer", 400),
+        ];
+
+        assert_eq!(folded(&samples), "Token;approve 100\nToken;transfer 600\n");
+
+        let table = table(&samples);
+        let rows = table.lines().skip(1).collect::<Vec<_>>();
+        assert!(rows[0].starts_with("Token.transfer "));
+        assert!(rows[0].contains(" 300 "));
+        assert!(rows[0].ends_with("85.7%"));
+        assert!(rows[1].starts_with("Token.approve "));
+    }
+}
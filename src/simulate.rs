@@ -0,0 +1,360 @@
+//! Runs a sequence of calls against a contract deployed to an in-memory EVM
+//! (`fe simulate`) and reports what each of them did.
+
+use evm::backend::ApplyBackend;
+use fe_compiler::abi::encoding;
+use fe_compiler::types::CompiledModule;
+use primitive_types::{
+    H160,
+    H256,
+    U256,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// The caller of calls that don't name one, which is also the deployer.
+const DEFAULT_CALLER: &str = "0x0000000000000000000000000000000000000001";
+/// The gas limit of each transaction.
+const GAS_LIMIT: u64 = 30_000_000;
+
+/// The contract to deploy and the calls to make, as read from a JSON file.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Script {
+    pub contract: String,
+    #[serde(default)]
+    pub constructor_args: Vec<String>,
+    pub calls: Vec<Call>,
+}
+
+/// A single call of a public function.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Call {
+    pub function: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// The address of the caller, which defaults to the deployer.
+    pub caller: Option<String>,
+    /// The ether sent with the call in wei.
+    pub value: Option<String>,
+}
+
+impl Script {
+    pub fn parse(content: &str) -> Result<Self, String> {
+        serde_json::from_str(content).map_err(|error| format!("invalid script: {}", error))
+    }
+}
+
+/// The deployment or a call of the script.
+#[derive(Debug, PartialEq)]
+pub struct Transaction {
+    /// The called function, or `None` for the deployment.
+    pub function: Option<String>,
+    /// A line describing the transaction and its outcome.
+    pub summary: String,
+    pub succeeded: bool,
+    pub gas: u64,
+    /// The data returned by a call, or its revert data.
+    pub output: Vec<u8>,
+    /// The logs of the transaction, formatted as in the details.
+    pub logs: Vec<String>,
+    /// The returned values, logs and state changes, one per line.
+    pub details: Vec<String>,
+}
+
+/// Formats the transactions with their details below them.
+pub fn report(transactions: &[Transaction]) -> String {
+    let mut report = String::new();
+    for transaction in transactions {
+        report.push_str(&format!("{}\n", transaction.summary));
+        for detail in transaction.details.iter() {
+            report.push_str(&format!("  {}\n", detail));
+        }
+    }
+
+    report
+}
+
+/// Deploys the contract of the script and makes its calls in order, each in
+/// the state left by the previous ones. Calls that fail don't change the
+/// state, and none are made if the deployment fails.
+pub fn run(module: &CompiledModule, script: &Script) -> Result<Vec<Transaction>, String> {
+    let contract = module
+        .contracts
+        .get(&script.contract)
+        .ok_or_else(|| format!("no contract named `{}`", script.contract))?;
+
+    run_contract(&contract.bytecode, &contract.json_abi, script)
+}
+
+/// Runs the script like `run`, against a contract given by its hex encoded
+/// creation bytecode and its JSON ABI.
+pub fn run_contract(
+    bytecode: &str,
+    json_abi: &str,
+    script: &Script,
+) -> Result<Vec<Transaction>, String> {
+    let abi = encoding::load(json_abi).map_err(|error| error.to_string())?;
+
+    let callers = std::iter::once(DEFAULT_CALLER)
+        .chain(
+            script
+                .calls
+                .iter()
+                .filter_map(|call| call.caller.as_deref()),
+        )
+        .map(parse_address)
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut backend_state = BTreeMap::new();
+    for caller in callers {
+        // callers are funded so that calls can send ether
+        backend_state.insert(
+            caller,
+            evm::backend::MemoryAccount {
+                nonce: U256::zero(),
+                balance: U256::from(10).pow(U256::from(30)),
+                storage: BTreeMap::new(),
+                code: vec![],
+            },
+        );
+    }
+
+    let vicinity = evm::backend::MemoryVicinity {
+        gas_price: U256::zero(),
+        origin: H160::zero(),
+        chain_id: U256::one(),
+        block_hashes: Vec::new(),
+        block_number: U256::zero(),
+        block_coinbase: H160::zero(),
+        block_timestamp: U256::zero(),
+        block_difficulty: U256::zero(),
+        block_gas_limit: U256::from(GAS_LIMIT),
+    };
+    let mut backend = evm::backend::MemoryBackend::new(&vicinity, backend_state);
+    let config = evm::Config::istanbul();
+    let deployer = parse_address(DEFAULT_CALLER)?;
+
+    let mut transactions = vec![];
+    let bytecode = hex::decode(bytecode).map_err(|error| error.to_string())?;
+    let init_code = encoding::encode_constructor(&abi, bytecode, &script.constructor_args)
+        .map_err(|error| error.to_string())?;
+
+    let before = backend.state().clone();
+    let mut executor = evm::executor::StackExecutor::new(&backend, GAS_LIMIT as usize, &config);
+    let address = executor.create_address(evm::CreateScheme::Legacy { caller: deployer });
+    let reason = executor.transact_create(deployer, U256::zero(), init_code, GAS_LIMIT);
+    let gas = executor.used_gas();
+    let (values, logs) = executor.deconstruct();
+    let (values, logs) = (
+        values.into_iter().collect::<Vec<_>>(),
+        logs.into_iter().collect::<Vec<_>>(),
+    );
+
+    let mut deployment = Transaction {
+        function: None,
+        summary: format!(
+            "deploy {} at {:?}: {}, gas {}",
+            script.contract,
+            address,
+            outcome(&reason, &[]),
+            gas
+        ),
+        succeeded: reason.is_succeed(),
+        gas,
+        output: vec![],
+        logs: vec![],
+        details: vec![],
+    };
+    if !deployment.succeeded {
+        transactions.push(deployment);
+        return Ok(transactions);
+    }
+    backend.apply(values, logs.clone(), false);
+    deployment.logs = format_logs(&logs);
+    deployment.details = deployment.logs.clone();
+    deployment
+        .details
+        .extend(state_changes(&before, backend.state()));
+    transactions.push(deployment);
+
+    for call in script.calls.iter() {
+        let caller = parse_address(call.caller.as_deref().unwrap_or(DEFAULT_CALLER))?;
+        let value = match &call.value {
+            Some(value) => U256::from_dec_str(value)
+                .map_err(|_| format!("invalid value `{}` of `{}`", value, call.function))?,
+            None => U256::zero(),
+        };
+        let input = encoding::encode_call(&abi, &call.function, &call.args)
+            .map_err(|error| format!("calling `{}`: {}", call.function, error))?;
+
+        let before = backend.state().clone();
+        let mut executor = evm::executor::StackExecutor::new(&backend, GAS_LIMIT as usize, &config);
+        let (reason, output) = executor.transact_call(caller, address, value, input, GAS_LIMIT);
+        let gas = executor.used_gas();
+        let (values, logs) = executor.deconstruct();
+        let (values, logs) = (
+            values.into_iter().collect::<Vec<_>>(),
+            logs.into_iter().collect::<Vec<_>>(),
+        );
+
+        let mut transaction = Transaction {
+            function: Some(call.function.clone()),
+            summary: format!(
+                "call {}({}) from {:?}: {}, gas {}",
+                call.function,
+                call.args.join(", "),
+                caller,
+                outcome(&reason, &output),
+                gas
+            ),
+            succeeded: reason.is_succeed(),
+            gas,
+            output,
+            logs: vec![],
+            details: vec![],
+        };
+        if transaction.succeeded {
+            if let Ok(returned) = encoding::decode_output(&abi, &call.function, &transaction.output)
+            {
+                if !returned.is_empty() {
+                    transaction
+                        .details
+                        .push(format!("returned {}", returned.join(", ")));
+                }
+            }
+            backend.apply(values, logs.clone(), false);
+            transaction.logs = format_logs(&logs);
+            transaction.details.extend(transaction.logs.clone());
+            transaction
+                .details
+                .extend(state_changes(&before, backend.state()));
+        }
+        transactions.push(transaction);
+    }
+
+    Ok(transactions)
+}
+
+fn parse_address(address: &str) -> Result<H160, String> {
+    match hex::decode(address.trim_start_matches("0x")) {
+        Ok(bytes) if bytes.len() == 20 => Ok(H160::from_slice(&bytes)),
+        _ => Err(format!("invalid address `{}`", address)),
+    }
+}
+
+fn outcome(reason: &evm::ExitReason, output: &[u8]) -> String {
+    match reason {
+        evm::ExitReason::Succeed(_) => "ok".to_string(),
+        evm::ExitReason::Revert(_) if output.is_empty() => "reverted".to_string(),
+        evm::ExitReason::Revert(_) => format!("reverted with 0x{}", hex::encode(output)),
+        reason => format!("failed ({:?})", reason),
+    }
+}
+
+/// Formats the logs of a transaction.
+fn format_logs(logs: &[evm::backend::Log]) -> Vec<String> {
+    logs.iter()
+        .map(|log| {
+            let topics = log
+                .topics
+                .iter()
+                .map(|topic| format!("{:?}", topic))
+                .collect::<Vec<_>>();
+            format!(
+                "log {:?} topics [{}] data 0x{}",
+                log.address,
+                topics.join(", "),
+                hex::encode(&log.data)
+            )
+        })
+        .collect()
+}
+
+/// Formats the changes that a transaction made to balances and storage.
+fn state_changes(
+    before: &BTreeMap<H160, evm::backend::MemoryAccount>,
+    after: &BTreeMap<H160, evm::backend::MemoryAccount>,
+) -> Vec<String> {
+    let mut lines = vec![];
+
+    for (address, account) in after {
+        let old = before.get(address);
+        let old_balance = old.map_or(U256::zero(), |old| old.balance);
+        if old_balance != account.balance {
+            lines.push(format!(
+                "balance {:?}: {} -> {}",
+                address, old_balance, account.balance
+            ));
+        }
+
+        let empty = BTreeMap::new();
+        let old_storage = old.map_or(&empty, |old| &old.storage);
+        let mut keys = old_storage
+            .keys()
+            .chain(account.storage.keys())
+            .collect::<Vec<_>>();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            let old_value = old_storage.get(key).copied().unwrap_or_default();
+            let new_value = account.storage.get(key).copied().unwrap_or_default();
+            if old_value != new_value {
+                lines.push(format!(
+                    "storage {:?} {:?}: {} -> {}",
+                    address,
+                    key,
+                    word(old_value),
+                    word(new_value)
+                ));
+            }
+        }
+    }
+
+    lines
+}
+
+/// A storage word as a number, which is how most stored values are read.
+fn word(value: H256) -> U256 {
+    U256::from_big_endian(value.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulate::{
+        report,
+        run,
+        Script,
+    };
+
+    #[test]
+    fn counter() {
+        let src = "\
+contract Counter:
+    count: u256
+
+    pub def increment(by: u256) -> u256:
+        self.count = self.count + by
+        return self.count
+";
+        let module = fe_compiler::compile(src, true, false).unwrap();
+        let script = Script::parse(
+            r#"{
+                "contract": "Counter",
+                "calls": [
+                    {"function": "increment", "args": ["2"]},
+                    {"function": "increment", "args": ["3"], "caller": "0x0000000000000000000000000000000000000002"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let transactions = run(&module, &script).unwrap();
+        assert_eq!(transactions.len(), 3);
+        assert!(transactions.iter().all(|transaction| transaction.succeeded));
+
+        let report = report(&transactions);
+        assert!(report.contains("returned 5"));
+        assert!(report.contains(": 2 -> 5"));
+    }
+}
@@ -0,0 +1,115 @@
+//! The machine-readable summary of a build that `--json` prints to stdout.
+
+use fe_compiler::driver::Build;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+/// The summary of a build, which is also printed if it fails.
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub struct BuildSummary {
+    pub success: bool,
+    pub source: String,
+    pub output_dir: String,
+    pub duration_ms: u128,
+    /// The number of lint warnings that were reported, including denied ones.
+    pub warnings: usize,
+    /// The outputs of the whole module, e.g. the AST.
+    pub artifacts: Vec<Artifact>,
+    pub contracts: BTreeMap<String, ContractSummary>,
+    /// The errors of a failed build, formatted for humans.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The outputs of a single contract.
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub struct ContractSummary {
+    pub artifacts: Vec<Artifact>,
+    /// The size of the creation bytecode in bytes, if it was compiled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creation_size: Option<usize>,
+    /// The size of the runtime bytecode in bytes, if it was compiled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runtime_size: Option<usize>,
+}
+
+/// A file written to the output directory.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Artifact {
+    /// The emit target that produced the file, e.g. `abi`.
+    pub target: String,
+    /// The path of the file, including the output directory.
+    pub path: String,
+    /// The size of the file in bytes.
+    pub bytes: usize,
+}
+
+impl BuildSummary {
+    /// The summary of a build whose outputs were written to `output_dir`.
+    pub fn new(
+        source: &str,
+        output_dir: &str,
+        build: &Build,
+        warnings: usize,
+        duration: Duration,
+    ) -> Self {
+        let mut summary = BuildSummary {
+            success: true,
+            source: source.to_string(),
+            output_dir: output_dir.to_string(),
+            duration_ms: duration.as_millis(),
+            warnings,
+            ..BuildSummary::default()
+        };
+
+        for output in build.outputs.iter() {
+            let artifact = Artifact {
+                target: output.target.clone(),
+                path: Path::new(output_dir)
+                    .join(&output.path)
+                    .display()
+                    .to_string(),
+                bytes: output.content.len(),
+            };
+            match &output.contract {
+                Some(contract) => summary
+                    .contracts
+                    .entry(contract.clone())
+                    .or_default()
+                    .artifacts
+                    .push(artifact),
+                None => summary.artifacts.push(artifact),
+            }
+        }
+
+        #[cfg(feature = "solc-backend")]
+        for (name, contract) in build.module.contracts.iter() {
+            if !contract.bytecode.is_empty() {
+                let entry = summary.contracts.entry(name.clone()).or_default();
+                entry.creation_size = Some(fe_compiler::evm::bytecode_size(&contract.bytecode));
+                entry.runtime_size =
+                    Some(fe_compiler::evm::bytecode_size(&contract.runtime_bytecode));
+            }
+        }
+
+        summary
+    }
+
+    /// The summary of a build that failed with the given error.
+    pub fn failed(source: &str, output_dir: &str, error: String, duration: Duration) -> Self {
+        BuildSummary {
+            success: false,
+            source: source.to_string(),
+            output_dir: output_dir.to_string(),
+            duration_ms: duration.as_millis(),
+            error: Some(error),
+            ..BuildSummary::default()
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("unable to serialize the build summary")
+    }
+}
@@ -0,0 +1,111 @@
+//! Runs the test functions of a module on an in-memory EVM.
+
+use fe_compiler::abi::utils::func_selector;
+use fe_compiler::types::{
+    CompiledTests,
+    TestFunction,
+};
+use primitive_types::{
+    H160,
+    U256,
+};
+use std::collections::BTreeMap;
+
+const CALLER: u64 = 1;
+
+/// The outcome of a single test function.
+pub struct TestResult {
+    pub test: TestFunction,
+    /// The reason the test failed, if it did.
+    pub failure: Option<String>,
+    /// The gas used by the call of the test function, without the deployment
+    /// of its contract and the intrinsic gas of a transaction.
+    pub gas: u64,
+}
+
+/// Runs each test in a fresh state, so that tests do not affect each other.
+///
+/// Each test deploys its contract and then calls the test function. A test
+/// passes if neither reverts.
+pub fn run_tests(compiled: &CompiledTests) -> Vec<TestResult> {
+    compiled
+        .tests
+        .iter()
+        .map(|test| {
+            let (outcome, gas) = run_test(compiled, test);
+            TestResult {
+                test: test.clone(),
+                failure: outcome.err(),
+                gas,
+            }
+        })
+        .collect()
+}
+
+/// Runs a test and returns its outcome and the gas used by the call of the
+/// test function.
+fn run_test(compiled: &CompiledTests, test: &TestFunction) -> (Result<(), String>, u64) {
+    let bytecode = match hex::decode(&compiled.module.contracts[&test.contract].bytecode) {
+        Ok(bytecode) => bytecode,
+        Err(error) => return (Err(error.to_string()), 0),
+    };
+
+    let vicinity = evm::backend::MemoryVicinity {
+        gas_price: U256::zero(),
+        origin: H160::zero(),
+        chain_id: U256::zero(),
+        block_hashes: Vec::new(),
+        block_number: U256::zero(),
+        block_coinbase: H160::zero(),
+        block_timestamp: U256::zero(),
+        block_difficulty: U256::zero(),
+        block_gas_limit: U256::MAX,
+    };
+    let backend = evm::backend::MemoryBackend::new(&vicinity, BTreeMap::new());
+    let config = evm::Config::istanbul();
+    let mut executor = evm::executor::StackExecutor::new(&backend, usize::max_value(), &config);
+    let caller = H160::from_low_u64_be(CALLER);
+
+    let address = match executor.create(
+        caller,
+        evm::CreateScheme::Legacy { caller },
+        U256::zero(),
+        bytecode,
+        None,
+    ) {
+        evm::Capture::Exit((evm::ExitReason::Succeed(_), Some(address), _)) => address,
+        evm::Capture::Exit((reason, _, _)) => {
+            return (
+                Err(format!(
+                    "deploying `{}` failed: {:?}",
+                    test.contract, reason
+                )),
+                0,
+            )
+        }
+        _ => return (Err(format!("deploying `{}` failed", test.contract)), 0),
+    };
+    let deploy_gas = executor.used_gas();
+
+    let selector = func_selector(&test.name, vec![]);
+    let input = hex::decode(&selector[2..]).expect("invalid selector");
+    let context = evm::Context {
+        address,
+        caller,
+        apparent_value: U256::zero(),
+    };
+
+    let outcome = match executor.call(address, None, input, None, false, context) {
+        evm::Capture::Exit((evm::ExitReason::Succeed(_), _)) => Ok(()),
+        evm::Capture::Exit((evm::ExitReason::Revert(_), output)) if output.is_empty() => {
+            Err("reverted".to_string())
+        }
+        evm::Capture::Exit((evm::ExitReason::Revert(_), output)) => {
+            Err(format!("reverted with 0x{}", hex::encode(output)))
+        }
+        evm::Capture::Exit((reason, _)) => Err(format!("{:?}", reason)),
+        _ => Err("the call was trapped".to_string()),
+    };
+
+    (outcome, executor.used_gas() - deploy_gas)
+}